@@ -0,0 +1,102 @@
+use std::{collections::HashMap, time::Duration};
+
+use mongodb::bson::DateTime;
+use tracing::warn;
+
+use crate::{
+    model::{CommandUsage, SnowflakeId},
+    Context,
+};
+
+/// How many commands and guilds `/analytics` lists
+pub const TOP_LEN: usize = 10;
+
+/// Stores the invocation of the command for `/analytics`, without making the command wait for it.
+/// Nothing is stored during maintenance or while the database is unreachable.
+pub fn record(ctx: Context<'_>, outcome: &str, latency: Duration) {
+    let data = ctx.data();
+    if data.maintenance.is_enabled() || data.outage.is_offline() {
+        return;
+    }
+
+    let usage = CommandUsage {
+        guild_id: ctx.guild_id().map(Into::into),
+        command: ctx.command().qualified_name.clone(),
+        latency_ms: latency.as_millis() as u64,
+        outcome: outcome.to_owned(),
+        used_at: DateTime::now(),
+    };
+    let storage = data.storage.clone();
+    data.tasks.spawn(async move {
+        if let Err(err) = storage.record_command_usage(&usage).await {
+            warn!(?err, "Could not record command usage");
+        }
+    });
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct CommandStats {
+    pub command: String,
+    pub uses: usize,
+    pub errors: usize,
+    total_latency_ms: u64,
+}
+
+impl CommandStats {
+    pub fn average_latency(&self) -> Duration {
+        Duration::from_millis(self.total_latency_ms / self.uses.max(1) as u64)
+    }
+}
+
+/// The invocations of a time window, counted by command and by guild.
+#[derive(Debug, Clone, Default)]
+pub struct Summary {
+    pub uses: usize,
+    pub errors: usize,
+    /// The most used first
+    pub commands: Vec<CommandStats>,
+    /// The guild with the most invocations first
+    pub guilds: Vec<(SnowflakeId, usize)>,
+}
+
+impl Summary {
+    pub fn new(usages: &[CommandUsage]) -> Self {
+        let mut commands = HashMap::<&str, CommandStats>::new();
+        let mut guilds = HashMap::<SnowflakeId, usize>::new();
+        for usage in usages {
+            let stats = commands
+                .entry(&usage.command)
+                .or_insert_with(|| CommandStats {
+                    command: usage.command.clone(),
+                    ..CommandStats::default()
+                });
+            stats.uses += 1;
+            stats.errors += usize::from(usage.outcome == "error");
+            stats.total_latency_ms += usage.latency_ms;
+
+            if let Some(guild_id) = usage.guild_id {
+                *guilds.entry(guild_id).or_default() += 1;
+            }
+        }
+
+        let mut commands = commands.into_values().collect::<Vec<_>>();
+        commands.sort_by(|a, b| b.uses.cmp(&a.uses).then_with(|| a.command.cmp(&b.command)));
+        let mut guilds = guilds.into_iter().collect::<Vec<_>>();
+        guilds.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+
+        Self {
+            uses: usages.len(),
+            errors: commands.iter().map(|stats| stats.errors).sum(),
+            commands,
+            guilds,
+        }
+    }
+}
+
+/// The share of the errors in percent, with one decimal.
+pub fn error_rate(errors: usize, uses: usize) -> String {
+    if uses == 0 {
+        return "-".to_owned();
+    }
+    format!("{:.1}%", errors as f64 * 100.0 / uses as f64)
+}
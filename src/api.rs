@@ -0,0 +1,162 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, Query},
+    http::{header::AUTHORIZATION, HeaderMap, StatusCode},
+    routing::get,
+    Extension, Json, Router,
+};
+use color_eyre::Report;
+use mongodb::bson::Uuid;
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use tracing::error;
+
+use crate::{
+    model::{PrisonEntry, SnowflakeId},
+    storage::Storage,
+    webhook,
+};
+
+const DEFAULT_LIMIT: usize = 25;
+const MAX_LIMIT: usize = 100;
+
+/// Read only endpoints for websites that show the docket of a guild, served under `/api/v1` next
+/// to the health check. Every request needs the token of the guild from `/config api_token` as
+/// `Authorization: Bearer <token>`.
+///
+/// The lists are paginated, `?after=` takes the `next` of the previous page and `?limit=` the
+/// number of items up to 100. The items look like the payloads of the webhooks, see
+/// [`crate::webhook`].
+pub fn router() -> Router {
+    Router::new()
+        .route("/guilds/:guild_id/lawsuits", get(lawsuits))
+        .route("/guilds/:guild_id/prisoners", get(prisoners))
+}
+
+/// Creates a new random token. Only its hash is stored, the token is shown once to the admin.
+pub fn generate_token() -> (String, String) {
+    let mut bytes = [0; 32];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    let token = hex::encode(bytes);
+    let hash = hash_token(&token);
+    (token, hash)
+}
+
+pub(crate) fn hash_token(token: &str) -> String {
+    hex::encode(Sha256::digest(token.as_bytes()))
+}
+
+#[derive(Debug, Deserialize)]
+struct PageQuery {
+    after: Option<String>,
+    limit: Option<usize>,
+}
+
+impl PageQuery {
+    fn limit(&self) -> usize {
+        self.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT)
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct PageResponse {
+    items: Vec<Value>,
+    next: Option<String>,
+}
+
+async fn lawsuits(
+    Path(guild_id): Path<SnowflakeId>,
+    Query(query): Query<PageQuery>,
+    headers: HeaderMap,
+    Extension(storage): Extension<Arc<dyn Storage>>,
+) -> Result<Json<PageResponse>, StatusCode> {
+    authorize(&*storage, guild_id, &headers).await?;
+
+    let after = query
+        .after
+        .as_deref()
+        .map(Uuid::parse_str)
+        .transpose()
+        .map_err(|_| StatusCode::BAD_REQUEST)?;
+
+    let page = storage
+        .find_lawsuits_page(guild_id, after, query.limit())
+        .await
+        .map_err(internal_error)?;
+
+    Ok(Json(PageResponse {
+        // sealed lawsuits are only for the court, so a page can have fewer items than the limit
+        items: page
+            .items
+            .iter()
+            .filter(|lawsuit| !lawsuit.sealed)
+            .map(webhook::lawsuit_json)
+            .collect(),
+        next: page.next.map(|id| id.to_string()),
+    }))
+}
+
+async fn prisoners(
+    Path(guild_id): Path<SnowflakeId>,
+    Query(query): Query<PageQuery>,
+    headers: HeaderMap,
+    Extension(storage): Extension<Arc<dyn Storage>>,
+) -> Result<Json<PageResponse>, StatusCode> {
+    authorize(&*storage, guild_id, &headers).await?;
+
+    let after = query
+        .after
+        .as_deref()
+        .map(str::parse)
+        .transpose()
+        .map_err(|_| StatusCode::BAD_REQUEST)?;
+
+    let page = storage
+        .find_prisoners_page(guild_id, after, query.limit())
+        .await
+        .map_err(internal_error)?;
+
+    Ok(Json(PageResponse {
+        items: page.items.iter().map(prisoner_json).collect(),
+        next: page.next.map(|id| id.to_string()),
+    }))
+}
+
+/// Unknown guilds and guilds without a token get the same answer as a wrong token, so the API
+/// doesn't tell which guilds use the bot.
+async fn authorize(
+    storage: &dyn Storage,
+    guild_id: SnowflakeId,
+    headers: &HeaderMap,
+) -> Result<(), StatusCode> {
+    let token = headers
+        .get(AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .ok_or(StatusCode::UNAUTHORIZED)?;
+
+    let state = storage.find_state(guild_id).await.map_err(internal_error)?;
+
+    match state.and_then(|state| state.api_token_hash) {
+        Some(hash) if hash == hash_token(token) => Ok(()),
+        _ => Err(StatusCode::UNAUTHORIZED),
+    }
+}
+
+fn internal_error(err: Report) -> StatusCode {
+    error!(?err, "API request failed");
+    StatusCode::INTERNAL_SERVER_ERROR
+}
+
+fn prisoner_json(entry: &PrisonEntry) -> Value {
+    json!({
+        "user_id": entry.user_id,
+        "tier": entry.tier,
+        "release_at": entry.release_at.map(|time| time.timestamp_millis()),
+        "parole_until": entry.parole_until.map(|time| time.timestamp_millis()),
+        "arrested_by": entry.arrested_by,
+    })
+}
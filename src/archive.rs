@@ -0,0 +1,198 @@
+use std::collections::HashMap;
+
+use mongodb::bson::DateTime;
+use serde::Deserialize;
+
+use crate::{duration, handler::Response, i18n::Strings, lawsuit::Lawsuit, model::SnowflakeId};
+
+/// More cases can be imported in multiple files
+pub const MAX_CASES: usize = 1000;
+
+/// A closed case from before the bot, imported into the records with `/records import`. The
+/// files are JSON arrays of these, or CSV files with the field names as header.
+#[derive(Debug, Clone, Deserialize)]
+pub struct HistoricalCase {
+    pub plaintiff: String,
+    pub accused: String,
+    /// The importing admin if there is none
+    #[serde(default)]
+    pub judge: Option<String>,
+    pub reason: String,
+    pub verdict: String,
+    /// Like `2023-04-01`
+    pub date: String,
+}
+
+/// A case that was checked and can be stored.
+#[derive(Debug, Clone)]
+pub struct ValidCase {
+    plaintiff: SnowflakeId,
+    accused: SnowflakeId,
+    judge: SnowflakeId,
+    reason: String,
+    verdict: String,
+    date: DateTime,
+}
+
+impl ValidCase {
+    pub fn date(&self) -> DateTime {
+        self.date
+    }
+
+    /// The lawsuit is closed when it's opened and never had a court room.
+    pub fn into_lawsuit(self, guild_id: SnowflakeId, case_number: String) -> Lawsuit {
+        Lawsuit {
+            verdict: Some(self.verdict),
+            opened_at: Some(self.date),
+            closed_at: Some(self.date),
+            case_number: Some(case_number),
+            ..Lawsuit::new(
+                guild_id,
+                self.plaintiff,
+                self.accused,
+                self.judge,
+                self.reason,
+            )
+        }
+    }
+}
+
+/// Reads the cases from a JSON or CSV file and checks all of them, nothing is imported if a
+/// single case is invalid.
+pub fn parse(
+    strings: &Strings,
+    filename: &str,
+    bytes: &[u8],
+    imported_by: SnowflakeId,
+) -> Result<Vec<ValidCase>, Response> {
+    let text = String::from_utf8_lossy(bytes);
+    // spreadsheet programs start their CSV files with a byte order mark
+    let text = text.trim_start_matches('\u{feff}');
+    let is_json = filename.to_lowercase().ends_with(".json") || text.trim_start().starts_with('[');
+    let cases = if is_json {
+        serde_json::from_str::<Vec<HistoricalCase>>(text)
+            .map_err(|err| Response((strings.cases_import_invalid_file)(&err.to_string())))?
+    } else {
+        parse_csv(strings, text)?
+    };
+
+    if cases.is_empty() {
+        return Err(Response(strings.cases_import_empty.to_string()));
+    }
+    if cases.len() > MAX_CASES {
+        return Err(Response((strings.cases_import_too_many)(MAX_CASES)));
+    }
+
+    cases
+        .into_iter()
+        .enumerate()
+        .map(|(index, case)| validate(strings, index + 1, case, imported_by))
+        .collect()
+}
+
+fn validate(
+    strings: &Strings,
+    number: usize,
+    case: HistoricalCase,
+    imported_by: SnowflakeId,
+) -> Result<ValidCase, Response> {
+    let invalid = |value: &str| Response((strings.cases_import_invalid_case)(number, value));
+    let user = |value: &str| {
+        let value = value.trim();
+        poise::serenity::utils::parse_username(value)
+            .or_else(|| value.parse().ok())
+            .map(SnowflakeId)
+            .ok_or_else(|| invalid(value))
+    };
+
+    let judge = match case.judge.as_deref().map(str::trim) {
+        Some(judge) if !judge.is_empty() => user(judge)?,
+        _ => imported_by,
+    };
+    if case.reason.trim().is_empty() {
+        return Err(invalid(&case.reason));
+    }
+    if case.verdict.trim().is_empty() {
+        return Err(invalid(&case.verdict));
+    }
+
+    Ok(ValidCase {
+        plaintiff: user(&case.plaintiff)?,
+        accused: user(&case.accused)?,
+        judge,
+        date: duration::parse_date(&case.date).ok_or_else(|| invalid(&case.date))?,
+        reason: case.reason.trim().to_owned(),
+        verdict: case.verdict.trim().to_owned(),
+    })
+}
+
+fn parse_csv(strings: &Strings, text: &str) -> Result<Vec<HistoricalCase>, Response> {
+    let mut rows = csv_rows(text).into_iter();
+    let header = rows.next().unwrap_or_default();
+    let columns = header
+        .iter()
+        .enumerate()
+        .map(|(index, name)| (name.trim().to_lowercase(), index))
+        .collect::<HashMap<_, _>>();
+
+    let column = |name: &str| {
+        columns
+            .get(name)
+            .copied()
+            .ok_or_else(|| Response((strings.cases_import_missing_column)(name)))
+    };
+    let plaintiff = column("plaintiff")?;
+    let accused = column("accused")?;
+    let reason = column("reason")?;
+    let verdict = column("verdict")?;
+    let date = column("date")?;
+    let judge = columns.get("judge").copied();
+
+    Ok(rows
+        .filter(|row| row.iter().any(|field| !field.trim().is_empty()))
+        .map(|row| {
+            let field = |index: usize| row.get(index).cloned().unwrap_or_default();
+            HistoricalCase {
+                plaintiff: field(plaintiff),
+                accused: field(accused),
+                judge: judge.map(field),
+                reason: field(reason),
+                verdict: field(verdict),
+                date: field(date),
+            }
+        })
+        .collect())
+}
+
+/// Splits the CSV into rows and fields. Fields in double quotes can contain commas, line breaks
+/// and quotes written as `""`.
+fn csv_rows(text: &str) -> Vec<Vec<String>> {
+    let mut rows = Vec::new();
+    let mut row = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = text.chars().peekable();
+
+    while let Some(char) = chars.next() {
+        match char {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                chars.next();
+                field.push('"');
+            }
+            '"' => quoted = !quoted,
+            ',' if !quoted => row.push(std::mem::take(&mut field)),
+            '\n' if !quoted => {
+                row.push(std::mem::take(&mut field));
+                rows.push(std::mem::take(&mut row));
+            }
+            '\r' if !quoted => {}
+            char => field.push(char),
+        }
+    }
+    if !field.is_empty() || !row.is_empty() {
+        row.push(field);
+        rows.push(row);
+    }
+
+    rows
+}
@@ -0,0 +1,391 @@
+use mongodb::bson::DateTime;
+use poise::serenity::{http::Http, model::prelude::*};
+use tracing::error;
+
+use crate::{
+    backup::ImportMode,
+    duration,
+    election::Election,
+    i18n::Strings,
+    lawsuit::{Billing, Contempt, Lawsuit},
+    model::State,
+    webhook,
+};
+
+/// Something the bot did that is posted in the audit channel of the guild.
+#[derive(Debug)]
+pub enum AuditEvent<'a> {
+    LawsuitCreated(&'a Lawsuit),
+    LawsuitClosed(&'a Lawsuit),
+    CourtRoomCreated {
+        channel_id: ChannelId,
+    },
+    Arrested {
+        user_id: UserId,
+        ordered_by: Option<UserId>,
+        reason: Option<&'a str>,
+        release_at: Option<DateTime>,
+    },
+    ArrestedMany {
+        count: usize,
+        ordered_by: Option<UserId>,
+    },
+    /// `released_by` is `None` if the sentence or parole is over
+    Released {
+        user_id: UserId,
+        released_by: Option<UserId>,
+    },
+    ReleasedAll {
+        count: usize,
+        released_by: UserId,
+    },
+    SentenceChanged {
+        user_id: UserId,
+        release_at: DateTime,
+        changed_by: UserId,
+    },
+    Paroled {
+        user_id: UserId,
+        until: DateTime,
+        ordered_by: UserId,
+    },
+    ParoleRevoked {
+        user_id: UserId,
+        revoked_by: UserId,
+    },
+    Escaped {
+        user_id: UserId,
+    },
+    ConfigChanged {
+        setting: &'a str,
+        value: String,
+        changed_by: UserId,
+    },
+    GuildImported {
+        mode: ImportMode,
+        imported_by: UserId,
+    },
+    /// Closed cases from before the bot, see [`crate::archive`]
+    LawsuitsImported {
+        count: usize,
+        imported_by: UserId,
+    },
+    /// The erased user is not mentioned, that would defeat the point
+    UserErased {
+        erased_by: UserId,
+    },
+    FineImposed {
+        lawsuit: &'a Lawsuit,
+        amount: i64,
+    },
+    FinesPaid {
+        user_id: UserId,
+        count: usize,
+        total: i64,
+    },
+    /// `amount` is negative if money was taken
+    BalanceChanged {
+        user_id: UserId,
+        amount: i64,
+        balance: i64,
+        changed_by: UserId,
+    },
+    LawyerPaid {
+        lawsuit: &'a Lawsuit,
+        billing: &'a Billing,
+    },
+    ContemptOfCourt {
+        lawsuit: &'a Lawsuit,
+        contempt: &'a Contempt,
+    },
+    Ejected {
+        lawsuit: &'a Lawsuit,
+        user_id: UserId,
+        ejected_by: UserId,
+    },
+    GagOrdered {
+        lawsuit: &'a Lawsuit,
+        user_id: UserId,
+        ordered_by: UserId,
+    },
+    LawyerAdmitted {
+        user_id: UserId,
+    },
+    LitigantBanned {
+        user_id: UserId,
+        banned: bool,
+        changed_by: UserId,
+    },
+    ElectionFinished(&'a Election),
+}
+
+impl AuditEvent<'_> {
+    fn title(&self, strings: &Strings) -> &'static str {
+        match self {
+            Self::LawsuitCreated(_) => strings.audit_lawsuit_created,
+            Self::LawsuitClosed(_) => strings.audit_lawsuit_closed,
+            Self::CourtRoomCreated { .. } => strings.audit_court_room_created,
+            Self::Arrested { .. } => strings.audit_arrested,
+            Self::ArrestedMany { .. } => strings.audit_arrested_many,
+            Self::Released { .. } => strings.audit_released,
+            Self::ReleasedAll { .. } => strings.audit_released_all,
+            Self::SentenceChanged { .. } => strings.audit_sentence_changed,
+            Self::Paroled { .. } => strings.audit_paroled,
+            Self::ParoleRevoked { .. } => strings.audit_parole_revoked,
+            Self::Escaped { .. } => strings.audit_escaped,
+            Self::ConfigChanged { .. } => strings.audit_config_changed,
+            Self::GuildImported { .. } => strings.audit_guild_imported,
+            Self::LawsuitsImported { .. } => strings.audit_lawsuits_imported,
+            Self::UserErased { .. } => strings.audit_user_erased,
+            Self::FineImposed { .. } => strings.audit_fine_imposed,
+            Self::FinesPaid { .. } => strings.audit_fines_paid,
+            Self::BalanceChanged { .. } => strings.audit_balance_changed,
+            Self::LawyerPaid { .. } => strings.audit_lawyer_paid,
+            Self::ContemptOfCourt { .. } => strings.audit_contempt,
+            Self::Ejected { .. } => strings.audit_ejected,
+            Self::GagOrdered { .. } => strings.audit_gag_ordered,
+            Self::LawyerAdmitted { .. } => strings.audit_lawyer_admitted,
+            Self::LitigantBanned { banned: true, .. } => strings.audit_litigant_banned,
+            Self::LitigantBanned { banned: false, .. } => strings.audit_litigant_unbanned,
+            Self::ElectionFinished(_) => strings.audit_election_finished,
+        }
+    }
+
+    fn fields(&self, strings: &Strings) -> Vec<(&'static str, String)> {
+        match self {
+            Self::LawsuitCreated(lawsuit) | Self::LawsuitClosed(lawsuit) => {
+                let mut fields = vec![
+                    (strings.field_reason, lawsuit.reason.clone()),
+                    (strings.field_plaintiff, mention(lawsuit.plaintiff)),
+                    (strings.field_accused, mention(lawsuit.accused)),
+                    (strings.field_judge, mention(lawsuit.judge)),
+                    (
+                        strings.field_court_room,
+                        format!("<#{}>", lawsuit.court_room),
+                    ),
+                ];
+                if let Some(verdict) = &lawsuit.verdict {
+                    fields.push((strings.field_verdict, verdict.clone()));
+                }
+                if let Some(fine) = &lawsuit.fine {
+                    fields.push((strings.field_fine, (strings.currency)(fine.amount)));
+                }
+                fields
+            }
+            Self::CourtRoomCreated { channel_id } => {
+                vec![(strings.field_channel, format!("<#{channel_id}>"))]
+            }
+            Self::Arrested {
+                user_id,
+                ordered_by,
+                reason,
+                release_at,
+            } => vec![
+                (strings.field_prisoner, mention(user_id)),
+                (
+                    strings.field_ordered_by,
+                    mention_or(*ordered_by, strings.none),
+                ),
+                (
+                    strings.field_reason,
+                    reason.unwrap_or(strings.none).to_string(),
+                ),
+                (
+                    strings.field_duration,
+                    release_at.map_or_else(
+                        || strings.indefinite.to_string(),
+                        |release_at| (strings.until)(&duration::relative_timestamp(release_at)),
+                    ),
+                ),
+            ],
+            Self::ArrestedMany { count, ordered_by } => vec![
+                (strings.field_count, count.to_string()),
+                (
+                    strings.field_ordered_by,
+                    mention_or(*ordered_by, strings.none),
+                ),
+            ],
+            Self::Released {
+                user_id,
+                released_by,
+            } => vec![
+                (strings.field_prisoner, mention(user_id)),
+                (
+                    strings.field_released_by,
+                    mention_or(*released_by, strings.audit_sentence_served),
+                ),
+            ],
+            Self::ReleasedAll { count, released_by } => vec![
+                (strings.field_count, count.to_string()),
+                (strings.field_released_by, mention(released_by)),
+            ],
+            Self::SentenceChanged {
+                user_id,
+                release_at,
+                changed_by,
+            } => vec![
+                (strings.field_prisoner, mention(user_id)),
+                (
+                    strings.field_release,
+                    duration::relative_timestamp(*release_at),
+                ),
+                (strings.field_changed_by, mention(changed_by)),
+            ],
+            Self::Paroled {
+                user_id,
+                until,
+                ordered_by,
+            } => vec![
+                (strings.field_prisoner, mention(user_id)),
+                (strings.field_until, duration::relative_timestamp(*until)),
+                (strings.field_ordered_by, mention(ordered_by)),
+            ],
+            Self::ParoleRevoked {
+                user_id,
+                revoked_by,
+            } => vec![
+                (strings.field_prisoner, mention(user_id)),
+                (strings.field_revoked_by, mention(revoked_by)),
+            ],
+            Self::Escaped { user_id } => vec![(strings.field_prisoner, mention(user_id))],
+            Self::ConfigChanged {
+                setting,
+                value,
+                changed_by,
+            } => vec![
+                (strings.field_setting, setting.to_string()),
+                (strings.field_value, value.clone()),
+                (strings.field_changed_by, mention(changed_by)),
+            ],
+            Self::GuildImported { mode, imported_by } => vec![
+                (strings.field_mode, mode.label(strings).to_string()),
+                (strings.field_imported_by, mention(imported_by)),
+            ],
+            Self::LawsuitsImported { count, imported_by } => vec![
+                (strings.field_count, count.to_string()),
+                (strings.field_imported_by, mention(imported_by)),
+            ],
+            Self::UserErased { erased_by } => vec![(strings.field_erased_by, mention(erased_by))],
+            Self::FineImposed { lawsuit, amount } => vec![
+                (strings.field_reason, lawsuit.reason.clone()),
+                (strings.field_accused, mention(lawsuit.accused)),
+                (strings.field_judge, mention(lawsuit.judge)),
+                (strings.field_amount, (strings.currency)(*amount)),
+            ],
+            Self::FinesPaid {
+                user_id,
+                count,
+                total,
+            } => vec![
+                (strings.field_user, mention(user_id)),
+                (strings.field_count, count.to_string()),
+                (strings.field_amount, (strings.currency)(*total)),
+            ],
+            Self::BalanceChanged {
+                user_id,
+                amount,
+                balance,
+                changed_by,
+            } => vec![
+                (strings.field_user, mention(user_id)),
+                (strings.field_amount, (strings.currency)(*amount)),
+                (strings.field_balance, (strings.currency)(*balance)),
+                (strings.field_changed_by, mention(changed_by)),
+            ],
+            Self::LawyerPaid { lawsuit, billing } => vec![
+                (strings.field_reason, lawsuit.reason.clone()),
+                (strings.field_lawyer, mention(billing.lawyer)),
+                (strings.field_client, mention(billing.client)),
+                (strings.field_amount, (strings.currency)(billing.amount)),
+            ],
+            Self::ContemptOfCourt { lawsuit, contempt } => vec![
+                (strings.field_reason, lawsuit.reason.clone()),
+                (strings.field_user, mention(contempt.user_id)),
+                (strings.field_judge, mention(lawsuit.judge)),
+                (
+                    strings.field_until,
+                    duration::relative_timestamp(contempt.until),
+                ),
+            ],
+            Self::Ejected {
+                lawsuit,
+                user_id,
+                ejected_by,
+            } => vec![
+                (strings.field_reason, lawsuit.reason.clone()),
+                (strings.field_user, mention(user_id)),
+                (strings.field_ordered_by, mention(ejected_by)),
+            ],
+            Self::GagOrdered {
+                lawsuit,
+                user_id,
+                ordered_by,
+            } => vec![
+                (strings.field_reason, lawsuit.reason.clone()),
+                (strings.field_user, mention(user_id)),
+                (strings.field_ordered_by, mention(ordered_by)),
+            ],
+            Self::LawyerAdmitted { user_id } => vec![(strings.field_lawyer, mention(user_id))],
+            Self::LitigantBanned {
+                user_id,
+                changed_by,
+                ..
+            } => vec![
+                (strings.field_user, mention(user_id)),
+                (strings.field_changed_by, mention(changed_by)),
+            ],
+            Self::ElectionFinished(election) => {
+                let winners = election.winners.iter().map(mention).collect::<Vec<_>>();
+                vec![
+                    (strings.field_role, format!("<@&{}>", election.role_id)),
+                    (strings.field_voters, election.voters.to_string()),
+                    (
+                        strings.field_winners,
+                        if winners.is_empty() {
+                            strings.election_no_winners.to_string()
+                        } else {
+                            winners.join(", ")
+                        },
+                    ),
+                ]
+            }
+        }
+    }
+}
+
+fn mention(user_id: impl std::fmt::Display) -> String {
+    format!("<@{user_id}>")
+}
+
+fn mention_or(user_id: Option<UserId>, fallback: &str) -> String {
+    user_id.map_or_else(|| fallback.to_string(), mention)
+}
+
+/// Posts the event in the audit channel of the guild if one is set and sends it to the webhook.
+/// Failing to post it is only logged, the action itself has already happened at that point.
+pub async fn log(http: &Http, state: &State, event: AuditEvent<'_>) {
+    webhook::dispatch(state, &event);
+
+    let channel_id = match state.audit_channel {
+        Some(channel_id) => ChannelId::from(channel_id),
+        None => return,
+    };
+
+    let strings = state.locale.strings();
+
+    let result = channel_id
+        .send_message(http, |message| {
+            message.embed(|embed| {
+                embed.title(event.title(strings));
+                for (name, value) in event.fields(strings) {
+                    embed.field(name, value, true);
+                }
+                embed
+            })
+        })
+        .await;
+
+    if let Err(err) = result {
+        error!(?err, guild_id = %state.guild_id, ?event, "Failed to post audit event");
+    }
+}
@@ -0,0 +1,217 @@
+use crate::{
+    handler::Response,
+    i18n::Strings,
+    model::{GuildData, SnowflakeId, State},
+    webhook,
+};
+
+/// How imported data is combined with the data that is already there.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, poise::ChoiceParameter)]
+pub enum ImportMode {
+    /// Everything is replaced with the imported data
+    #[name = "Ersetzen"]
+    Replace,
+    /// The existing data wins, only things that don't exist yet are added
+    #[name = "Zusammenführen"]
+    Merge,
+}
+
+impl ImportMode {
+    pub fn label(self, strings: &Strings) -> &'static str {
+        match self {
+            Self::Replace => strings.import_mode_replace,
+            Self::Merge => strings.import_mode_merge,
+        }
+    }
+}
+
+/// How many of everything there are before and after the import.
+#[derive(Debug, Clone)]
+pub struct ImportSummary {
+    pub lawsuits: (usize, usize),
+    pub court_rooms: (usize, usize),
+    pub prison_tiers: (usize, usize),
+    pub prison_entries: (usize, usize),
+    pub prison_history: (usize, usize),
+    pub jobs: (usize, usize),
+    pub members: (usize, usize),
+    pub settings_changed: bool,
+}
+
+/// Checks that the data belongs to the guild and is consistent. The webhook is checked like one
+/// that is set with `/config set webhook`.
+pub async fn validate(
+    strings: &Strings,
+    guild_id: SnowflakeId,
+    data: &GuildData,
+) -> Result<(), Response> {
+    let guild_ids = std::iter::once(data.state.guild_id)
+        .chain(data.lawsuits.iter().map(|lawsuit| lawsuit.guild_id))
+        .chain(data.prison_entries.iter().map(|entry| entry.guild_id))
+        .chain(data.prison_history.iter().map(|record| record.guild_id))
+        .chain(data.jobs.iter().map(|job| job.guild_id))
+        .chain(data.members.iter().map(|member| member.guild_id));
+    for id in guild_ids {
+        if id != guild_id {
+            return Err(Response(strings.import_wrong_guild.to_string()));
+        }
+    }
+
+    for (i, entry) in data.prison_entries.iter().enumerate() {
+        if data.prison_entries[..i]
+            .iter()
+            .any(|other| other.user_id == entry.user_id)
+        {
+            return Err(Response((strings.import_duplicate_prisoner)(&format!(
+                "<@{}>",
+                entry.user_id
+            ))));
+        }
+    }
+
+    for (i, member) in data.members.iter().enumerate() {
+        if data.members[..i]
+            .iter()
+            .any(|other| other.user_id == member.user_id)
+        {
+            return Err(Response((strings.import_duplicate_member)(&format!(
+                "<@{}>",
+                member.user_id
+            ))));
+        }
+    }
+
+    if let Some(url) = &data.state.webhook_url {
+        if !webhook::is_valid_url(url).await {
+            return Err(Response(strings.import_invalid_webhook.to_string()));
+        }
+    }
+
+    Ok(())
+}
+
+/// Combines the current data with the imported data. The result is what is stored after the
+/// import, so it can be shown as a preview without touching the database.
+pub fn combine(current: &GuildData, import: GuildData, mode: ImportMode) -> GuildData {
+    match mode {
+        ImportMode::Replace => import,
+        ImportMode::Merge => {
+            let mut data = current.clone();
+            merge_settings(&mut data.state, &import.state);
+
+            add_missing(&mut data.lawsuits, import.lawsuits, |a, b| a.id == b.id);
+            add_missing(
+                &mut data.state.court_rooms,
+                import.state.court_rooms,
+                |a, b| a.channel_id == b.channel_id,
+            );
+            add_missing(
+                &mut data.state.prison_tiers,
+                import.state.prison_tiers,
+                |a, b| a.name == b.name,
+            );
+            add_missing(&mut data.prison_entries, import.prison_entries, |a, b| {
+                a.user_id == b.user_id
+            });
+            add_missing(&mut data.prison_history, import.prison_history, |a, b| {
+                a.user_id == b.user_id && a.arrested_at == b.arrested_at
+            });
+            add_missing(&mut data.jobs, import.jobs, |a, b| a.kind == b.kind);
+            add_missing(&mut data.members, import.members, |a, b| {
+                a.user_id == b.user_id
+            });
+
+            data
+        }
+    }
+}
+
+pub fn summarize(current: &GuildData, new: &GuildData) -> ImportSummary {
+    ImportSummary {
+        lawsuits: (current.lawsuits.len(), new.lawsuits.len()),
+        court_rooms: (current.state.court_rooms.len(), new.state.court_rooms.len()),
+        prison_tiers: (
+            current.state.prison_tiers.len(),
+            new.state.prison_tiers.len(),
+        ),
+        prison_entries: (current.prison_entries.len(), new.prison_entries.len()),
+        prison_history: (current.prison_history.len(), new.prison_history.len()),
+        jobs: (current.jobs.len(), new.jobs.len()),
+        members: (current.members.len(), new.members.len()),
+        settings_changed: settings(&current.state) != settings(&new.state),
+    }
+}
+
+/// Settings that are set in the import but not yet in the guild are taken over.
+fn merge_settings(state: &mut State, import: &State) {
+    if state.prison_channel.is_none() {
+        state.prison_channel = import.prison_channel;
+        state.prison_lockdown = import.prison_lockdown;
+    }
+    if state.webhook_url.is_none() {
+        state.webhook_url = import.webhook_url.clone();
+    }
+    for (key, text) in &import.message_templates {
+        state
+            .message_templates
+            .entry(key.clone())
+            .or_insert_with(|| text.clone());
+    }
+    if state.bar_questions.is_empty() {
+        state.bar_questions = import.bar_questions.clone();
+    }
+    add_missing(
+        &mut state.admitted_lawyers,
+        import.admitted_lawyers.clone(),
+        |a, b| a == b,
+    );
+    add_missing(&mut state.jury_pool, import.jury_pool.clone(), |a, b| {
+        a == b
+    });
+
+    if state.prison_role.is_none() {
+        state.prison_role_template = import.prison_role_template.clone();
+    }
+    if state.appeal_category.is_none() {
+        state.appeal_room_name = import.appeal_room_name.clone();
+    }
+    for (setting, imported) in [
+        (&mut state.court_category, import.court_category),
+        (&mut state.appeal_category, import.appeal_category),
+        (&mut state.prison_role, import.prison_role),
+        (&mut state.probation_role, import.probation_role),
+        (&mut state.moderator_channel, import.moderator_channel),
+        (&mut state.appeal_judge, import.appeal_judge),
+        (&mut state.audit_channel, import.audit_channel),
+        (&mut state.error_channel, import.error_channel),
+        (&mut state.admin_role, import.admin_role),
+        (&mut state.judge_role, import.judge_role),
+        (&mut state.bailiff_role, import.bailiff_role),
+    ] {
+        if setting.is_none() {
+            *setting = imported;
+        }
+    }
+}
+
+fn add_missing<T>(items: &mut Vec<T>, imported: Vec<T>, same: impl Fn(&T, &T) -> bool) {
+    for item in imported {
+        if !items.iter().any(|existing| same(existing, &item)) {
+            items.push(item);
+        }
+    }
+}
+
+/// The state without the lists, lawyers, jurors and the version, to compare only the settings.
+fn settings(state: &State) -> serde_json::Value {
+    let state = State {
+        court_rooms: vec![],
+        prison_tiers: vec![],
+        admitted_lawyers: vec![],
+        jury_pool: vec![],
+        gag_orders: vec![],
+        version: 0,
+        ..state.clone()
+    };
+    serde_json::to_value(state).unwrap_or_default()
+}
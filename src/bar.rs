@@ -0,0 +1,153 @@
+use std::time::Duration;
+
+use color_eyre::Result;
+use mongodb::bson::Uuid;
+use poise::serenity::{
+    builder::CreateComponents,
+    collector::CollectComponentInteraction,
+    model::interactions::{message_component::ButtonStyle, InteractionResponseType},
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{i18n::Strings, Context, WrapErr};
+
+/// The answers are buttons and discord only allows 5 buttons in a row
+pub const MAX_ANSWERS: usize = 5;
+/// The longest label of a button
+pub const MAX_ANSWER_LEN: usize = 80;
+/// How many of the answers have to be right to pass
+const PASS_PERCENT: usize = 75;
+const TIMEOUT: Duration = Duration::from_secs(60 * 2);
+
+/// A multiple choice question of the bar exam, the guilds write their own with
+/// `/lawyer questions add`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BarQuestion {
+    pub question: String,
+    pub answers: Vec<String>,
+    /// The index of the right answer
+    pub correct: usize,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct ExamResult {
+    pub correct: usize,
+    pub total: usize,
+}
+
+impl ExamResult {
+    pub fn passed(&self) -> bool {
+        self.correct * 100 >= self.total * PASS_PERCENT
+    }
+}
+
+/// Asks the questions one after another in an ephemeral message, every click on an answer shows
+/// the next question. The result replaces the last question.
+///
+/// Returns `None` if a question wasn't answered in time.
+pub async fn take_exam(
+    ctx: Context<'_>,
+    strings: &Strings,
+    questions: &[BarQuestion],
+) -> Result<Option<ExamResult>> {
+    let total = questions.len();
+    let ids = ExamIds::new();
+
+    let first = match questions.first() {
+        Some(first) => first,
+        None => return Ok(Some(ExamResult { correct: 0, total })),
+    };
+    ctx.send(|reply| {
+        reply
+            .ephemeral(true)
+            .content((strings.bar_question)(1, total, &first.question))
+            .components(|c| answer_components(c, &ids, 0, first))
+    })
+    .await
+    .wrap_err("send exam")?;
+
+    let mut correct = 0;
+    for (index, question) in questions.iter().enumerate() {
+        // the ids contain the question, so that a late click on an old question doesn't count
+        let prefix = ids.question(index);
+        let filter_prefix = prefix.clone();
+        let interaction = CollectComponentInteraction::new(ctx.discord())
+            .channel_id(ctx.channel_id())
+            .author_id(ctx.author().id)
+            .filter(move |interaction| interaction.data.custom_id.starts_with(&filter_prefix))
+            .timeout(TIMEOUT)
+            .await;
+
+        let interaction = match interaction {
+            Some(interaction) => interaction,
+            None => return Ok(None),
+        };
+
+        let answer = interaction.data.custom_id[prefix.len()..]
+            .parse::<usize>()
+            .ok();
+        if answer == Some(question.correct) {
+            correct += 1;
+        }
+
+        let next = questions.get(index + 1);
+        let result = ExamResult { correct, total };
+        interaction
+            .create_interaction_response(ctx.discord(), |response| {
+                response
+                    .kind(InteractionResponseType::UpdateMessage)
+                    .interaction_response_data(|data| match next {
+                        Some(next) => data
+                            .content((strings.bar_question)(index + 2, total, &next.question))
+                            .components(|c| answer_components(c, &ids, index + 1, next)),
+                        None if result.passed() => data
+                            .content((strings.bar_exam_passed)(correct, total))
+                            .components(|c| c),
+                        None => data
+                            .content((strings.bar_exam_failed)(correct, total))
+                            .components(|c| c),
+                    })
+            })
+            .await
+            .wrap_err("show next question")?;
+    }
+
+    Ok(Some(ExamResult { correct, total }))
+}
+
+struct ExamIds {
+    prefix: String,
+}
+
+impl ExamIds {
+    fn new() -> Self {
+        // the ids must be unique so that multiple exams in the same channel don't interfere
+        Self {
+            prefix: format!("exam-{}", Uuid::new()),
+        }
+    }
+
+    /// The prefix of the ids of the answers to the question, followed by the index of the answer.
+    fn question(&self, index: usize) -> String {
+        format!("{}-{index}-", self.prefix)
+    }
+}
+
+fn answer_components<'a>(
+    components: &'a mut CreateComponents,
+    ids: &ExamIds,
+    index: usize,
+    question: &BarQuestion,
+) -> &'a mut CreateComponents {
+    components.create_action_row(|row| {
+        for (answer_index, answer) in question.answers.iter().enumerate() {
+            row.create_button(|button| {
+                button
+                    .custom_id(format!("{}{answer_index}", ids.question(index)))
+                    .label(answer)
+                    .style(ButtonStyle::Secondary)
+            });
+        }
+        row
+    })
+}
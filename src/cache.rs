@@ -0,0 +1,1115 @@
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use async_trait::async_trait;
+use color_eyre::Result;
+use dashmap::DashMap;
+use futures::StreamExt;
+use mongodb::bson::{DateTime, Uuid};
+use tracing::{error, info};
+
+use crate::{
+    bar::BarQuestion,
+    election::Election,
+    i18n::Locale,
+    lawsuit::{
+        Billing, ConflictPolicy, Contempt, Evidence, Fine, JuryTally, Lawsuit, LawyerFee,
+        SubpoenaResponse, Witness,
+    },
+    model::{
+        BotStats, CommandUsage, CourtReport, CourtRoom, CourtStats, ErasureReport, GagOrder,
+        GuildData, GuildListing, Job, JobKind, JudgeRegistry, MemberData, Page, PrisonEntry,
+        PrisonRecord, PrisonStats, PrisonTier, RoleTemplate, SnowflakeId, State,
+    },
+    permissions::CourtRole,
+    shutdown::BackgroundTasks,
+    storage::Storage,
+    templates::Template,
+};
+
+/// Changes that don't go through the bot, like fixing something directly in the database, are
+/// picked up after this.
+const TTL: Duration = Duration::from_secs(60 * 5);
+/// How long to wait before watching the changes again after the database stopped sending them
+const REWATCH_DELAY: Duration = Duration::from_secs(30);
+
+/// Keeps the states of the guilds in memory, so that not every interaction has to ask the
+/// database for it. Everything else is passed through. Every change of the state drops the cached
+/// state of the guild.
+pub struct CachedStorage<S> {
+    inner: S,
+    states: DashMap<SnowflakeId, CachedState>,
+}
+
+#[derive(Default)]
+struct CachedState {
+    /// Bumped by every change, a state that was read before the change isn't cached anymore
+    generation: u64,
+    state: Option<(Instant, State)>,
+}
+
+impl<S: Storage> CachedStorage<S> {
+    pub fn new(inner: S) -> Self {
+        Self {
+            inner,
+            states: DashMap::new(),
+        }
+    }
+
+    fn invalidate(&self, guild_id: SnowflakeId) {
+        let mut cached = self.states.entry(guild_id).or_default();
+        cached.generation += 1;
+        cached.state = None;
+    }
+
+    fn invalidate_all(&self) {
+        for mut cached in self.states.iter_mut() {
+            cached.generation += 1;
+            cached.state = None;
+        }
+    }
+
+    /// The cached state if it's still fresh, and the generation to cache a newly read one with.
+    fn lookup(&self, guild_id: SnowflakeId) -> (u64, Option<State>) {
+        let cached = self.states.entry(guild_id).or_default();
+        let state = match &cached.state {
+            Some((cached_at, state)) if cached_at.elapsed() < TTL => Some(state.clone()),
+            _ => None,
+        };
+        (cached.generation, state)
+    }
+
+    /// Caches the state unless it was changed since the `generation` of the lookup, the read
+    /// could have started before the change and the old state would stay until the TTL.
+    fn fill(&self, guild_id: SnowflakeId, generation: u64, state: &State) {
+        let mut cached = self.states.entry(guild_id).or_default();
+        if cached.generation == generation {
+            cached.state = Some((Instant::now(), state.clone()));
+        }
+    }
+
+    /// Drops the states that are changed by other processes, so that more than one bot can run
+    /// on the same database. Runs until the bot shuts down.
+    pub async fn watch_changes(self: Arc<Self>, tasks: BackgroundTasks) {
+        loop {
+            let result = tokio::select! {
+                result = self.apply_changes() => result,
+                _ = tasks.shutting_down() => return,
+            };
+            match result {
+                Ok(false) => {
+                    info!("The database can't report changes, cached states expire after the TTL");
+                    return;
+                }
+                Ok(true) => error!("Stopped receiving state changes"),
+                Err(err) => error!(?err, "Error watching state changes"),
+            }
+
+            // changes could have been missed in the meantime
+            self.invalidate_all();
+            tokio::select! {
+                _ = tokio::time::sleep(REWATCH_DELAY) => {}
+                _ = tasks.shutting_down() => return,
+            }
+        }
+    }
+
+    /// Returns `false` if the database doesn't support watching changes.
+    async fn apply_changes(&self) -> Result<bool> {
+        let mut changes = match self.inner.state_changes().await? {
+            Some(changes) => changes,
+            None => return Ok(false),
+        };
+        info!("Watching state changes");
+
+        while let Some(change) = changes.next().await {
+            match change? {
+                Some(guild_id) => self.invalidate(guild_id),
+                None => self.invalidate_all(),
+            }
+        }
+        Ok(true)
+    }
+}
+
+#[async_trait]
+impl<S: Storage> Storage for CachedStorage<S> {
+    async fn ping(&self) -> Result<()> {
+        self.inner.ping().await
+    }
+
+    async fn close(&self) {
+        self.inner.close().await
+    }
+
+    fn cached_state(&self, guild_id: SnowflakeId) -> Option<(Duration, State)> {
+        let cached = self.states.get(&guild_id)?;
+        let (cached_at, state) = cached.state.as_ref()?;
+        Some((cached_at.elapsed(), state.clone()))
+    }
+
+    async fn find_or_insert_state(&self, guild_id: SnowflakeId) -> Result<State> {
+        let (generation, cached) = self.lookup(guild_id);
+        if let Some(state) = cached {
+            return Ok(state);
+        }
+
+        let state = self.inner.find_or_insert_state(guild_id).await?;
+        self.fill(guild_id, generation, &state);
+        Ok(state)
+    }
+
+    async fn find_state(&self, guild_id: SnowflakeId) -> Result<Option<State>> {
+        let (generation, cached) = self.lookup(guild_id);
+        if let Some(state) = cached {
+            return Ok(Some(state));
+        }
+
+        let state = self.inner.find_state(guild_id).await?;
+        if let Some(state) = &state {
+            self.fill(guild_id, generation, state);
+        }
+        Ok(state)
+    }
+
+    async fn set_court_category(&self, guild_id: SnowflakeId, category: SnowflakeId) -> Result<()> {
+        let result = self.inner.set_court_category(guild_id, category).await;
+        self.invalidate(guild_id);
+        result
+    }
+
+    async fn set_appeal_category(
+        &self,
+        guild_id: SnowflakeId,
+        category: SnowflakeId,
+        room_name: Option<&str>,
+    ) -> Result<()> {
+        let result = self
+            .inner
+            .set_appeal_category(guild_id, category, room_name)
+            .await;
+        self.invalidate(guild_id);
+        result
+    }
+
+    async fn set_prison_role(
+        &self,
+        guild_id: SnowflakeId,
+        prison_role: SnowflakeId,
+        template: &RoleTemplate,
+    ) -> Result<()> {
+        let result = self
+            .inner
+            .set_prison_role(guild_id, prison_role, template)
+            .await;
+        self.invalidate(guild_id);
+        result
+    }
+
+    async fn set_probation_role(
+        &self,
+        guild_id: SnowflakeId,
+        probation_role: SnowflakeId,
+    ) -> Result<()> {
+        let result = self
+            .inner
+            .set_probation_role(guild_id, probation_role)
+            .await;
+        self.invalidate(guild_id);
+        result
+    }
+
+    async fn set_prison_channel(
+        &self,
+        guild_id: SnowflakeId,
+        prison_channel: SnowflakeId,
+        lockdown: bool,
+    ) -> Result<()> {
+        let result = self
+            .inner
+            .set_prison_channel(guild_id, prison_channel, lockdown)
+            .await;
+        self.invalidate(guild_id);
+        result
+    }
+
+    async fn set_strip_roles_on_arrest(
+        &self,
+        guild_id: SnowflakeId,
+        strip_roles: bool,
+    ) -> Result<()> {
+        let result = self
+            .inner
+            .set_strip_roles_on_arrest(guild_id, strip_roles)
+            .await;
+        self.invalidate(guild_id);
+        result
+    }
+
+    async fn set_moderator_channel(
+        &self,
+        guild_id: SnowflakeId,
+        moderator_channel: SnowflakeId,
+    ) -> Result<()> {
+        let result = self
+            .inner
+            .set_moderator_channel(guild_id, moderator_channel)
+            .await;
+        self.invalidate(guild_id);
+        result
+    }
+
+    async fn set_notify_prisoners(
+        &self,
+        guild_id: SnowflakeId,
+        notify_prisoners: bool,
+    ) -> Result<()> {
+        let result = self
+            .inner
+            .set_notify_prisoners(guild_id, notify_prisoners)
+            .await;
+        self.invalidate(guild_id);
+        result
+    }
+
+    async fn set_strict_lawyers(&self, guild_id: SnowflakeId, strict_lawyers: bool) -> Result<()> {
+        let result = self
+            .inner
+            .set_strict_lawyers(guild_id, strict_lawyers)
+            .await;
+        self.invalidate(guild_id);
+        result
+    }
+
+    async fn set_conflict_policy(
+        &self,
+        guild_id: SnowflakeId,
+        conflict_policy: ConflictPolicy,
+    ) -> Result<()> {
+        let result = self
+            .inner
+            .set_conflict_policy(guild_id, conflict_policy)
+            .await;
+        self.invalidate(guild_id);
+        result
+    }
+
+    async fn set_appeal_judge(
+        &self,
+        guild_id: SnowflakeId,
+        appeal_judge: SnowflakeId,
+    ) -> Result<()> {
+        let result = self.inner.set_appeal_judge(guild_id, appeal_judge).await;
+        self.invalidate(guild_id);
+        result
+    }
+
+    async fn set_audit_channel(
+        &self,
+        guild_id: SnowflakeId,
+        audit_channel: SnowflakeId,
+    ) -> Result<()> {
+        let result = self.inner.set_audit_channel(guild_id, audit_channel).await;
+        self.invalidate(guild_id);
+        result
+    }
+
+    async fn set_error_channel(
+        &self,
+        guild_id: SnowflakeId,
+        error_channel: SnowflakeId,
+    ) -> Result<()> {
+        let result = self.inner.set_error_channel(guild_id, error_channel).await;
+        self.invalidate(guild_id);
+        result
+    }
+
+    async fn set_records_channel(
+        &self,
+        guild_id: SnowflakeId,
+        records_channel: SnowflakeId,
+    ) -> Result<()> {
+        let result = self
+            .inner
+            .set_records_channel(guild_id, records_channel)
+            .await;
+        self.invalidate(guild_id);
+        result
+    }
+
+    async fn set_news_role(
+        &self,
+        guild_id: SnowflakeId,
+        role_id: Option<SnowflakeId>,
+    ) -> Result<()> {
+        let result = self.inner.set_news_role(guild_id, role_id).await;
+        self.invalidate(guild_id);
+        result
+    }
+
+    async fn set_report_channel(
+        &self,
+        guild_id: SnowflakeId,
+        report_channel: Option<SnowflakeId>,
+    ) -> Result<()> {
+        let result = self
+            .inner
+            .set_report_channel(guild_id, report_channel)
+            .await;
+        self.invalidate(guild_id);
+        result
+    }
+
+    async fn set_statute_of_limitations(
+        &self,
+        guild_id: SnowflakeId,
+        max_age: Option<Duration>,
+    ) -> Result<()> {
+        let result = self
+            .inner
+            .set_statute_of_limitations(guild_id, max_age)
+            .await;
+        self.invalidate(guild_id);
+        result
+    }
+
+    async fn set_inactivity_nudge(
+        &self,
+        guild_id: SnowflakeId,
+        after: Option<Duration>,
+    ) -> Result<()> {
+        let result = self.inner.set_inactivity_nudge(guild_id, after).await;
+        self.invalidate(guild_id);
+        result
+    }
+
+    async fn set_max_open_lawsuits(&self, guild_id: SnowflakeId, max: Option<u32>) -> Result<()> {
+        let result = self.inner.set_max_open_lawsuits(guild_id, max).await;
+        self.invalidate(guild_id);
+        result
+    }
+
+    async fn set_filing_cooldown(
+        &self,
+        guild_id: SnowflakeId,
+        cooldown: Option<Duration>,
+    ) -> Result<()> {
+        let result = self.inner.set_filing_cooldown(guild_id, cooldown).await;
+        self.invalidate(guild_id);
+        result
+    }
+
+    async fn set_court_slowmode(
+        &self,
+        guild_id: SnowflakeId,
+        slowmode: Option<Duration>,
+    ) -> Result<()> {
+        let result = self.inner.set_court_slowmode(guild_id, slowmode).await;
+        self.invalidate(guild_id);
+        result
+    }
+
+    async fn set_last_filed(
+        &self,
+        guild_id: SnowflakeId,
+        user_id: SnowflakeId,
+        filed_at: DateTime,
+    ) -> Result<()> {
+        self.inner.set_last_filed(guild_id, user_id, filed_at).await
+    }
+
+    async fn next_case_number(&self, guild_id: SnowflakeId, year: i64) -> Result<u32> {
+        let result = self.inner.next_case_number(guild_id, year).await;
+        self.invalidate(guild_id);
+        result
+    }
+
+    async fn set_locale(&self, guild_id: SnowflakeId, locale: Locale) -> Result<()> {
+        let result = self.inner.set_locale(guild_id, locale).await;
+        self.invalidate(guild_id);
+        result
+    }
+
+    async fn set_webhook_url(
+        &self,
+        guild_id: SnowflakeId,
+        webhook_url: Option<&str>,
+    ) -> Result<()> {
+        let result = self.inner.set_webhook_url(guild_id, webhook_url).await;
+        self.invalidate(guild_id);
+        result
+    }
+
+    async fn find_member(&self, guild_id: SnowflakeId, user_id: SnowflakeId) -> Result<MemberData> {
+        self.inner.find_member(guild_id, user_id).await
+    }
+
+    async fn find_members(&self, guild_id: SnowflakeId) -> Result<Vec<MemberData>> {
+        self.inner.find_members(guild_id).await
+    }
+
+    async fn add_to_balance(
+        &self,
+        guild_id: SnowflakeId,
+        user_id: SnowflakeId,
+        amount: i64,
+    ) -> Result<i64> {
+        self.inner.add_to_balance(guild_id, user_id, amount).await
+    }
+
+    async fn transfer(
+        &self,
+        guild_id: SnowflakeId,
+        from: SnowflakeId,
+        to: SnowflakeId,
+        amount: i64,
+        overdraw: bool,
+    ) -> Result<Option<i64>> {
+        self.inner
+            .transfer(guild_id, from, to, amount, overdraw)
+            .await
+    }
+
+    async fn set_lawyer_fee(
+        &self,
+        guild_id: SnowflakeId,
+        lawyer: SnowflakeId,
+        fee: Option<LawyerFee>,
+    ) -> Result<()> {
+        self.inner.set_lawyer_fee(guild_id, lawyer, fee).await
+    }
+
+    async fn set_bar_questions(
+        &self,
+        guild_id: SnowflakeId,
+        questions: &[BarQuestion],
+    ) -> Result<()> {
+        let result = self.inner.set_bar_questions(guild_id, questions).await;
+        self.invalidate(guild_id);
+        result
+    }
+
+    async fn admit_lawyer(&self, guild_id: SnowflakeId, lawyer: SnowflakeId) -> Result<()> {
+        let result = self.inner.admit_lawyer(guild_id, lawyer).await;
+        self.invalidate(guild_id);
+        result
+    }
+
+    async fn set_jury_pool_member(
+        &self,
+        guild_id: SnowflakeId,
+        user_id: SnowflakeId,
+        member: bool,
+    ) -> Result<()> {
+        let result = self
+            .inner
+            .set_jury_pool_member(guild_id, user_id, member)
+            .await;
+        self.invalidate(guild_id);
+        result
+    }
+
+    async fn add_gag_order(&self, guild_id: SnowflakeId, order: &GagOrder) -> Result<()> {
+        let result = self.inner.add_gag_order(guild_id, order).await;
+        self.invalidate(guild_id);
+        result
+    }
+
+    async fn remove_gag_orders(&self, guild_id: SnowflakeId, lawsuit_id: Uuid) -> Result<()> {
+        let result = self.inner.remove_gag_orders(guild_id, lawsuit_id).await;
+        self.invalidate(guild_id);
+        result
+    }
+
+    async fn set_litigant_banned(
+        &self,
+        guild_id: SnowflakeId,
+        user_id: SnowflakeId,
+        banned: bool,
+    ) -> Result<()> {
+        let result = self
+            .inner
+            .set_litigant_banned(guild_id, user_id, banned)
+            .await;
+        self.invalidate(guild_id);
+        result
+    }
+
+    async fn set_message_template(
+        &self,
+        guild_id: SnowflakeId,
+        template: Template,
+        text: Option<&str>,
+    ) -> Result<()> {
+        let result = self
+            .inner
+            .set_message_template(guild_id, template, text)
+            .await;
+        self.invalidate(guild_id);
+        result
+    }
+
+    async fn set_api_token_hash(
+        &self,
+        guild_id: SnowflakeId,
+        api_token_hash: Option<&str>,
+    ) -> Result<()> {
+        let result = self
+            .inner
+            .set_api_token_hash(guild_id, api_token_hash)
+            .await;
+        self.invalidate(guild_id);
+        result
+    }
+
+    async fn set_calendar_token_hash(
+        &self,
+        guild_id: SnowflakeId,
+        calendar_token_hash: Option<&str>,
+    ) -> Result<()> {
+        let result = self
+            .inner
+            .set_calendar_token_hash(guild_id, calendar_token_hash)
+            .await;
+        self.invalidate(guild_id);
+        result
+    }
+
+    async fn set_court_role(
+        &self,
+        guild_id: SnowflakeId,
+        court_role: CourtRole,
+        role_id: SnowflakeId,
+    ) -> Result<()> {
+        let result = self
+            .inner
+            .set_court_role(guild_id, court_role, role_id)
+            .await;
+        self.invalidate(guild_id);
+        result
+    }
+
+    async fn set_prison_tier(&self, guild_id: SnowflakeId, tier: &PrisonTier) -> Result<()> {
+        let result = self.inner.set_prison_tier(guild_id, tier).await;
+        self.invalidate(guild_id);
+        result
+    }
+
+    async fn remove_prison_tier(&self, guild_id: SnowflakeId, name: &str) -> Result<()> {
+        let result = self.inner.remove_prison_tier(guild_id, name).await;
+        self.invalidate(guild_id);
+        result
+    }
+
+    async fn add_court_room(&self, guild_id: SnowflakeId, room: &CourtRoom) -> Result<()> {
+        let result = self.inner.add_court_room(guild_id, room).await;
+        self.invalidate(guild_id);
+        result
+    }
+
+    async fn remove_court_rooms(&self, guild_id: SnowflakeId) -> Result<()> {
+        let result = self.inner.remove_court_rooms(guild_id).await;
+        self.invalidate(guild_id);
+        result
+    }
+
+    async fn add_lawsuit(&self, lawsuit: &Lawsuit) -> Result<()> {
+        self.inner.add_lawsuit(lawsuit).await
+    }
+
+    async fn delete_lawsuits(&self, guild_id: SnowflakeId) -> Result<u64> {
+        let result = self.inner.delete_lawsuits(guild_id).await;
+        self.invalidate(guild_id);
+        result
+    }
+
+    async fn find_lawsuits(&self, guild_id: SnowflakeId) -> Result<Vec<Lawsuit>> {
+        self.inner.find_lawsuits(guild_id).await
+    }
+
+    async fn find_open_lawsuits(&self, guild_id: SnowflakeId) -> Result<Vec<Lawsuit>> {
+        self.inner.find_open_lawsuits(guild_id).await
+    }
+
+    async fn find_open_lawsuits_by_reason(
+        &self,
+        guild_id: SnowflakeId,
+        prefix: &str,
+        limit: usize,
+    ) -> Result<Vec<Lawsuit>> {
+        self.inner
+            .find_open_lawsuits_by_reason(guild_id, prefix, limit)
+            .await
+    }
+
+    async fn search_closed_lawsuits(
+        &self,
+        guild_id: SnowflakeId,
+        query: &str,
+        limit: usize,
+    ) -> Result<Vec<Lawsuit>> {
+        self.inner
+            .search_closed_lawsuits(guild_id, query, limit)
+            .await
+    }
+
+    async fn find_lawsuits_page(
+        &self,
+        guild_id: SnowflakeId,
+        after: Option<Uuid>,
+        limit: usize,
+    ) -> Result<Page<Lawsuit, Uuid>> {
+        self.inner.find_lawsuits_page(guild_id, after, limit).await
+    }
+
+    async fn count_open_lawsuits(&self) -> Result<Vec<(SnowflakeId, u64)>> {
+        self.inner.count_open_lawsuits().await
+    }
+
+    async fn set_court_room_ongoing(
+        &self,
+        guild_id: SnowflakeId,
+        channel_id: SnowflakeId,
+        ongoing_lawsuit: bool,
+    ) -> Result<()> {
+        let result = self
+            .inner
+            .set_court_room_ongoing(guild_id, channel_id, ongoing_lawsuit)
+            .await;
+        self.invalidate(guild_id);
+        result
+    }
+
+    async fn claim_court_room(
+        &self,
+        guild_id: SnowflakeId,
+        channel_id: SnowflakeId,
+        version: u64,
+    ) -> Result<bool> {
+        let result = self
+            .inner
+            .claim_court_room(guild_id, channel_id, version)
+            .await;
+        self.invalidate(guild_id);
+        result
+    }
+
+    async fn set_room_activity(
+        &self,
+        guild_id: SnowflakeId,
+        channel_id: SnowflakeId,
+        last_activity: DateTime,
+    ) -> Result<()> {
+        let result = self
+            .inner
+            .set_room_activity(guild_id, channel_id, last_activity)
+            .await;
+        self.invalidate(guild_id);
+        result
+    }
+
+    async fn set_room_nudges(
+        &self,
+        guild_id: SnowflakeId,
+        channel_id: SnowflakeId,
+        nudges: u32,
+    ) -> Result<()> {
+        let result = self
+            .inner
+            .set_room_nudges(guild_id, channel_id, nudges)
+            .await;
+        self.invalidate(guild_id);
+        result
+    }
+
+    async fn set_room_closed_to_public(
+        &self,
+        guild_id: SnowflakeId,
+        channel_id: SnowflakeId,
+        closed: bool,
+    ) -> Result<()> {
+        let result = self
+            .inner
+            .set_room_closed_to_public(guild_id, channel_id, closed)
+            .await;
+        self.invalidate(guild_id);
+        result
+    }
+
+    async fn set_lawsuit_verdict(
+        &self,
+        guild_id: SnowflakeId,
+        lawsuit_id: Uuid,
+        verdict: Option<&str>,
+    ) -> Result<()> {
+        self.inner
+            .set_lawsuit_verdict(guild_id, lawsuit_id, verdict)
+            .await
+    }
+
+    async fn close_lawsuit(&self, guild_id: SnowflakeId, lawsuit: &Lawsuit) -> Result<()> {
+        let result = self.inner.close_lawsuit(guild_id, lawsuit).await;
+        self.invalidate(guild_id);
+        result
+    }
+
+    async fn add_evidence(
+        &self,
+        guild_id: SnowflakeId,
+        lawsuit_id: Uuid,
+        evidence: &Evidence,
+    ) -> Result<()> {
+        self.inner
+            .add_evidence(guild_id, lawsuit_id, evidence)
+            .await
+    }
+
+    async fn add_witness(
+        &self,
+        guild_id: SnowflakeId,
+        lawsuit_id: Uuid,
+        witness: &Witness,
+    ) -> Result<()> {
+        self.inner.add_witness(guild_id, lawsuit_id, witness).await
+    }
+
+    async fn answer_subpoena(
+        &self,
+        guild_id: SnowflakeId,
+        lawsuit_id: Uuid,
+        witness_id: SnowflakeId,
+        response: SubpoenaResponse,
+    ) -> Result<bool> {
+        self.inner
+            .answer_subpoena(guild_id, lawsuit_id, witness_id, response)
+            .await
+    }
+
+    async fn set_jury_tally(
+        &self,
+        guild_id: SnowflakeId,
+        lawsuit_id: Uuid,
+        tally: &JuryTally,
+    ) -> Result<()> {
+        self.inner.set_jury_tally(guild_id, lawsuit_id, tally).await
+    }
+
+    async fn add_billing(
+        &self,
+        guild_id: SnowflakeId,
+        lawsuit_id: Uuid,
+        billing: &Billing,
+    ) -> Result<()> {
+        self.inner.add_billing(guild_id, lawsuit_id, billing).await
+    }
+
+    async fn add_contempt(
+        &self,
+        guild_id: SnowflakeId,
+        lawsuit_id: Uuid,
+        contempt: &Contempt,
+    ) -> Result<()> {
+        self.inner
+            .add_contempt(guild_id, lawsuit_id, contempt)
+            .await
+    }
+
+    async fn add_ejected(
+        &self,
+        guild_id: SnowflakeId,
+        lawsuit_id: Uuid,
+        user_id: SnowflakeId,
+    ) -> Result<()> {
+        self.inner.add_ejected(guild_id, lawsuit_id, user_id).await
+    }
+
+    async fn set_lawsuit_hearing(
+        &self,
+        guild_id: SnowflakeId,
+        lawsuit_id: Uuid,
+        hearing_at: Option<DateTime>,
+    ) -> Result<()> {
+        self.inner
+            .set_lawsuit_hearing(guild_id, lawsuit_id, hearing_at)
+            .await
+    }
+
+    async fn add_citation(
+        &self,
+        guild_id: SnowflakeId,
+        lawsuit_id: Uuid,
+        precedent_id: Uuid,
+    ) -> Result<()> {
+        self.inner
+            .add_citation(guild_id, lawsuit_id, precedent_id)
+            .await
+    }
+
+    async fn set_lawsuit_jurors(
+        &self,
+        guild_id: SnowflakeId,
+        lawsuit_id: Uuid,
+        jurors: &[SnowflakeId],
+    ) -> Result<()> {
+        self.inner
+            .set_lawsuit_jurors(guild_id, lawsuit_id, jurors)
+            .await
+    }
+
+    async fn set_lawsuit_fine(
+        &self,
+        guild_id: SnowflakeId,
+        lawsuit_id: Uuid,
+        fine: &Fine,
+    ) -> Result<()> {
+        self.inner
+            .set_lawsuit_fine(guild_id, lawsuit_id, fine)
+            .await
+    }
+
+    async fn trash_guild(&self, guild_id: SnowflakeId) -> Result<()> {
+        let result = self.inner.trash_guild(guild_id).await;
+        self.invalidate(guild_id);
+        result
+    }
+
+    async fn restore_guild(&self, guild_id: SnowflakeId) -> Result<Option<State>> {
+        let result = self.inner.restore_guild(guild_id).await;
+        self.invalidate(guild_id);
+        result
+    }
+
+    async fn purge_trash(&self, guild_id: SnowflakeId) -> Result<()> {
+        self.inner.purge_trash(guild_id).await
+    }
+
+    async fn export_guild(&self, guild_id: SnowflakeId) -> Result<GuildData> {
+        self.inner.export_guild(guild_id).await
+    }
+
+    async fn erase_user(
+        &self,
+        guild_id: SnowflakeId,
+        user_id: SnowflakeId,
+    ) -> Result<ErasureReport> {
+        let result = self.inner.erase_user(guild_id, user_id).await;
+        self.invalidate(guild_id);
+        result
+    }
+
+    async fn replace_guild(&self, data: &GuildData) -> Result<()> {
+        let result = self.inner.replace_guild(data).await;
+        self.invalidate(data.state.guild_id);
+        result
+    }
+
+    async fn add_to_prison(
+        &self,
+        guild_id: SnowflakeId,
+        user_id: SnowflakeId,
+        tier: Option<&str>,
+        release_at: Option<DateTime>,
+        arrested_by: Option<SnowflakeId>,
+    ) -> Result<()> {
+        self.inner
+            .add_to_prison(guild_id, user_id, tier, release_at, arrested_by)
+            .await
+    }
+
+    async fn remove_from_prison(&self, guild_id: SnowflakeId, user_id: SnowflakeId) -> Result<()> {
+        self.inner.remove_from_prison(guild_id, user_id).await
+    }
+
+    async fn find_prison_entry(
+        &self,
+        guild_id: SnowflakeId,
+        user_id: SnowflakeId,
+    ) -> Result<Option<PrisonEntry>> {
+        self.inner.find_prison_entry(guild_id, user_id).await
+    }
+
+    async fn set_parole(
+        &self,
+        guild_id: SnowflakeId,
+        user_id: SnowflakeId,
+        parole_until: Option<DateTime>,
+    ) -> Result<()> {
+        self.inner.set_parole(guild_id, user_id, parole_until).await
+    }
+
+    async fn insert_prison_entries(&self, entries: &[PrisonEntry]) -> Result<()> {
+        self.inner.insert_prison_entries(entries).await
+    }
+
+    async fn set_prison_entries_tier(
+        &self,
+        guild_id: SnowflakeId,
+        user_ids: &[SnowflakeId],
+        tier: Option<&str>,
+    ) -> Result<()> {
+        self.inner
+            .set_prison_entries_tier(guild_id, user_ids, tier)
+            .await
+    }
+
+    async fn remove_all_from_prison(&self, guild_id: SnowflakeId) -> Result<()> {
+        self.inner.remove_all_from_prison(guild_id).await
+    }
+
+    async fn set_saved_roles(
+        &self,
+        guild_id: SnowflakeId,
+        user_id: SnowflakeId,
+        saved_roles: &[SnowflakeId],
+    ) -> Result<()> {
+        self.inner
+            .set_saved_roles(guild_id, user_id, saved_roles)
+            .await
+    }
+
+    async fn set_release_at(
+        &self,
+        guild_id: SnowflakeId,
+        user_id: SnowflakeId,
+        release_at: Option<DateTime>,
+    ) -> Result<()> {
+        self.inner
+            .set_release_at(guild_id, user_id, release_at)
+            .await
+    }
+
+    async fn find_prison_entries(&self, guild_id: SnowflakeId) -> Result<Vec<PrisonEntry>> {
+        self.inner.find_prison_entries(guild_id).await
+    }
+
+    async fn find_prisoners_page(
+        &self,
+        guild_id: SnowflakeId,
+        after: Option<SnowflakeId>,
+        limit: usize,
+    ) -> Result<Page<PrisonEntry, SnowflakeId>> {
+        self.inner.find_prisoners_page(guild_id, after, limit).await
+    }
+
+    async fn count_prisoners(&self) -> Result<Vec<(SnowflakeId, u64)>> {
+        self.inner.count_prisoners().await
+    }
+
+    async fn bot_stats(&self) -> Result<BotStats> {
+        self.inner.bot_stats().await
+    }
+
+    async fn add_prison_records(&self, records: &[PrisonRecord]) -> Result<()> {
+        self.inner.add_prison_records(records).await
+    }
+
+    async fn find_prison_records(&self, guild_id: SnowflakeId) -> Result<Vec<PrisonRecord>> {
+        self.inner.find_prison_records(guild_id).await
+    }
+
+    async fn close_prison_records(
+        &self,
+        guild_id: SnowflakeId,
+        user_ids: Option<&[SnowflakeId]>,
+        released_at: DateTime,
+    ) -> Result<()> {
+        self.inner
+            .close_prison_records(guild_id, user_ids, released_at)
+            .await
+    }
+
+    async fn prison_stats(&self, guild_id: SnowflakeId) -> Result<PrisonStats> {
+        self.inner.prison_stats(guild_id).await
+    }
+
+    async fn court_stats(&self, guild_id: SnowflakeId) -> Result<CourtStats> {
+        self.inner.court_stats(guild_id).await
+    }
+
+    async fn court_report(&self, guild_id: SnowflakeId, since: DateTime) -> Result<CourtReport> {
+        self.inner.court_report(guild_id, since).await
+    }
+
+    async fn schedule_job(
+        &self,
+        guild_id: SnowflakeId,
+        kind: &JobKind,
+        run_at: DateTime,
+    ) -> Result<()> {
+        self.inner.schedule_job(guild_id, kind, run_at).await
+    }
+
+    async fn cancel_job(&self, guild_id: SnowflakeId, kind: &JobKind) -> Result<()> {
+        self.inner.cancel_job(guild_id, kind).await
+    }
+
+    async fn cancel_prison_jobs(&self, guild_id: SnowflakeId) -> Result<()> {
+        self.inner.cancel_prison_jobs(guild_id).await
+    }
+
+    async fn find_jobs(&self, guild_id: SnowflakeId) -> Result<Vec<Job>> {
+        self.inner.find_jobs(guild_id).await
+    }
+
+    async fn find_due_jobs(&self, now: DateTime) -> Result<Vec<Job>> {
+        self.inner.find_due_jobs(now).await
+    }
+
+    async fn claim_job(&self, job: &Job, until: DateTime) -> Result<bool> {
+        self.inner.claim_job(job, until).await
+    }
+
+    async fn finish_job(&self, job: &Job) -> Result<()> {
+        self.inner.finish_job(job).await
+    }
+
+    async fn retry_job(&self, job: &Job, run_at: DateTime) -> Result<()> {
+        self.inner.retry_job(job, run_at).await
+    }
+
+    async fn find_judge_registry(&self, guild_id: SnowflakeId) -> Result<JudgeRegistry> {
+        self.inner.find_judge_registry(guild_id).await
+    }
+
+    async fn register_judge(&self, guild_id: SnowflakeId, judge: SnowflakeId) -> Result<bool> {
+        self.inner.register_judge(guild_id, judge).await
+    }
+
+    async fn unregister_judge(&self, guild_id: SnowflakeId, judge: SnowflakeId) -> Result<bool> {
+        self.inner.unregister_judge(guild_id, judge).await
+    }
+
+    async fn assign_judge(
+        &self,
+        guild_id: SnowflakeId,
+        parties: &[SnowflakeId],
+    ) -> Result<Option<SnowflakeId>> {
+        self.inner.assign_judge(guild_id, parties).await
+    }
+
+    async fn add_election(&self, election: &Election) -> Result<()> {
+        self.inner.add_election(election).await
+    }
+
+    async fn find_elections(&self, guild_id: SnowflakeId) -> Result<Vec<Election>> {
+        self.inner.find_elections(guild_id).await
+    }
+
+    async fn record_command_usage(&self, usage: &CommandUsage) -> Result<()> {
+        self.inner.record_command_usage(usage).await
+    }
+
+    async fn find_command_usage(&self, since: DateTime) -> Result<Vec<CommandUsage>> {
+        self.inner.find_command_usage(since).await
+    }
+
+    async fn find_guild_listings(&self) -> Result<Vec<GuildListing>> {
+        self.inner.find_guild_listings().await
+    }
+
+    async fn set_guild_listing(&self, guild_id: SnowflakeId, allowed: Option<bool>) -> Result<()> {
+        self.inner.set_guild_listing(guild_id, allowed).await
+    }
+}
@@ -0,0 +1,140 @@
+use std::{env, sync::Arc};
+
+use axum::{
+    extract::Path,
+    http::{header::CONTENT_TYPE, StatusCode},
+    response::IntoResponse,
+    routing::get,
+    Extension, Router,
+};
+use color_eyre::Report;
+use mongodb::bson::DateTime;
+use once_cell::sync::Lazy;
+use tracing::error;
+
+use crate::{api, duration, lawsuit::Lawsuit, model::SnowflakeId, storage::Storage};
+
+/// Calendar apps don't know how long a hearing takes, an hour looks right in most of them
+const HEARING_DURATION: &str = "PT1H";
+/// The lines of calendar files should be folded after 75 bytes
+const MAX_LINE_LEN: usize = 75;
+
+/// Where the calendar router is reachable from the outside, like `https://example.com/calendar`.
+/// Without it, the admins only get the path of the feed.
+static BASE_URL: Lazy<Option<String>> = Lazy::new(|| env::var("CALENDAR_URL").ok());
+
+/// The hearings of the open lawsuits of a guild as an iCalendar feed, served under `/calendar`
+/// next to the API. Calendar apps can't send headers, so the token from `/config calendar_token`
+/// is part of the URL: `/calendar/<guild_id>/<token>.ics`.
+pub fn router() -> Router {
+    Router::new().route("/:guild_id/:file", get(feed))
+}
+
+/// The URL of the feed that the judges subscribe to.
+pub fn url(guild_id: SnowflakeId, token: &str) -> String {
+    let base_url = BASE_URL.as_deref().unwrap_or("/calendar");
+    format!("{}/{guild_id}/{token}.ics", base_url.trim_end_matches('/'))
+}
+
+async fn feed(
+    Path((guild_id, file)): Path<(SnowflakeId, String)>,
+    Extension(storage): Extension<Arc<dyn Storage>>,
+) -> Result<impl IntoResponse, StatusCode> {
+    let token = file.strip_suffix(".ics").ok_or(StatusCode::NOT_FOUND)?;
+
+    let hash = api::hash_token(token);
+    let state = storage.find_state(guild_id).await.map_err(internal_error)?;
+    let state = match state {
+        Some(state) if state.calendar_token_hash.as_ref() == Some(&hash) => state,
+        // a wrong token looks like a feed that doesn't exist
+        _ => return Err(StatusCode::NOT_FOUND),
+    };
+
+    let lawsuits = storage
+        .find_open_lawsuits(guild_id)
+        .await
+        .map_err(internal_error)?;
+    let strings = state.locale.strings();
+
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_owned(),
+        "VERSION:2.0".to_owned(),
+        "PRODID:-//discord-court-bot//hearings//EN".to_owned(),
+        format!("X-WR-CALNAME:{}", escape(strings.calendar_name)),
+    ];
+    let now = duration::ical(DateTime::now());
+    for lawsuit in &lawsuits {
+        let hearing_at = match lawsuit.hearing_at {
+            Some(hearing_at) => hearing_at,
+            None => continue,
+        };
+        let summary = (strings.calendar_hearing)(&case_number(lawsuit), &lawsuit.reason);
+        lines.extend([
+            "BEGIN:VEVENT".to_owned(),
+            format!("UID:{}@discord-court-bot", lawsuit.id),
+            format!("DTSTAMP:{now}"),
+            format!("DTSTART:{}", duration::ical(hearing_at)),
+            format!("DURATION:{HEARING_DURATION}"),
+            format!("SUMMARY:{}", escape(&summary)),
+            format!(
+                "URL:https://discord.com/channels/{guild_id}/{}",
+                lawsuit.court_room
+            ),
+            "END:VEVENT".to_owned(),
+        ]);
+    }
+    lines.push("END:VCALENDAR".to_owned());
+
+    let body = lines
+        .iter()
+        .map(|line| fold(line))
+        .collect::<Vec<_>>()
+        .join("\r\n");
+    Ok((
+        [(CONTENT_TYPE, "text/calendar; charset=utf-8")],
+        body + "\r\n",
+    ))
+}
+
+fn case_number(lawsuit: &Lawsuit) -> String {
+    lawsuit
+        .case_number
+        .clone()
+        .unwrap_or_else(|| lawsuit.id.to_string())
+}
+
+/// Escapes the characters that have a meaning in the values of calendar files.
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for char in text.chars() {
+        match char {
+            '\\' => escaped.push_str("\\\\"),
+            ';' => escaped.push_str("\\;"),
+            ',' => escaped.push_str("\\,"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => {}
+            char => escaped.push(char),
+        }
+    }
+    escaped
+}
+
+/// Splits long lines, the continuation lines start with a space.
+fn fold(line: &str) -> String {
+    let mut folded = String::with_capacity(line.len());
+    let mut len = 0;
+    for char in line.chars() {
+        if len + char.len_utf8() > MAX_LINE_LEN {
+            folded.push_str("\r\n ");
+            len = 1;
+        }
+        folded.push(char);
+        len += char.len_utf8();
+    }
+    folded
+}
+
+fn internal_error(err: Report) -> StatusCode {
+    error!(?err, "Calendar request failed");
+    StatusCode::INTERNAL_SERVER_ERROR
+}
@@ -0,0 +1,72 @@
+use std::time::Duration;
+
+use color_eyre::Result;
+use mongodb::bson::Uuid;
+use poise::serenity::{
+    collector::CollectComponentInteraction,
+    model::interactions::{message_component::ButtonStyle, InteractionResponseType},
+};
+
+use crate::{i18n::Strings, Context, WrapErr};
+
+const TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Asks the author with buttons whether they really want to do it, for commands that delete
+/// something. Without an answer within a minute, it counts as cancelled.
+pub async fn confirm(ctx: Context<'_>, strings: &Strings, question: &str) -> Result<bool> {
+    // unique, so that multiple confirmations in the same channel don't interfere
+    let prefix = format!("confirm-{}", Uuid::new());
+    let confirm_id = format!("{prefix}-yes");
+    let cancel_id = format!("{prefix}-no");
+
+    ctx.send(|reply| {
+        reply.content(question).components(|c| {
+            c.create_action_row(|row| {
+                row.create_button(|button| {
+                    button
+                        .custom_id(&confirm_id)
+                        .label(strings.confirm_button)
+                        .style(ButtonStyle::Danger)
+                })
+                .create_button(|button| {
+                    button
+                        .custom_id(&cancel_id)
+                        .label(strings.cancel_button)
+                        .style(ButtonStyle::Secondary)
+                })
+            })
+        })
+    })
+    .await
+    .wrap_err("send confirmation")?;
+
+    let interaction = CollectComponentInteraction::new(ctx.discord())
+        .channel_id(ctx.channel_id())
+        .author_id(ctx.author().id)
+        .filter(move |interaction| interaction.data.custom_id.starts_with(&prefix))
+        .timeout(TIMEOUT)
+        .await;
+
+    let interaction = match interaction {
+        Some(interaction) => interaction,
+        None => return Ok(false),
+    };
+    let confirmed = interaction.data.custom_id == confirm_id;
+
+    // the buttons are removed so that it can't be confirmed twice
+    interaction
+        .create_interaction_response(ctx.discord(), |response| {
+            response
+                .kind(InteractionResponseType::UpdateMessage)
+                .interaction_response_data(|data| {
+                    if !confirmed {
+                        data.content(strings.cancelled);
+                    }
+                    data.components(|c| c)
+                })
+        })
+        .await
+        .wrap_err("answer confirmation")?;
+
+    Ok(confirmed)
+}
@@ -0,0 +1,178 @@
+use std::{
+    collections::HashMap,
+    env,
+    fmt::{Display, Formatter},
+    time::{Duration, Instant},
+};
+
+use color_eyre::{
+    eyre::{bail, ContextCompat},
+    Result,
+};
+use dashmap::DashMap;
+use poise::serenity::model::id::{GuildId, UserId};
+
+use crate::{duration, Context, WrapErr};
+
+/// The commands that are expensive or easy to spam, with how long a user and the whole guild have
+/// to wait before using them again.
+const DEFAULTS: &[(&str, Cooldown)] = &[
+    ("lawsuit create", Cooldown::new(30, 5)),
+    ("lawsuit file", Cooldown::new(30, 5)),
+    ("lawsuit jury", Cooldown::new(60, 10)),
+    // so that nobody finds the right answers by trying
+    ("lawyer exam", Cooldown::new(60 * 60, 0)),
+    ("prison arrest_many", Cooldown::new(60, 30)),
+    ("prison release_all", Cooldown::new(60, 60)),
+    ("config export", Cooldown::new(60, 60)),
+    ("config import", Cooldown::new(60, 60)),
+    ("stats", Cooldown::new(10, 0)),
+];
+
+/// Once there are this many entries, the expired ones are removed.
+const CLEANUP_THRESHOLD: usize = 10_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cooldown {
+    pub user: Duration,
+    pub guild: Duration,
+}
+
+impl Cooldown {
+    const fn new(user_secs: u64, guild_secs: u64) -> Self {
+        Self {
+            user: Duration::from_secs(user_secs),
+            guild: Duration::from_secs(guild_secs),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum Scope {
+    User(UserId),
+    Guild(GuildId),
+}
+
+/// Returned from the command check when the command is still on cooldown, the error handler
+/// answers with the remaining time.
+#[derive(Debug)]
+pub struct OnCooldown(pub Duration);
+
+impl Display for OnCooldown {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "on cooldown for {}s", self.0.as_secs())
+    }
+}
+
+impl std::error::Error for OnCooldown {}
+
+/// The cooldowns of the commands and when they end. `COOLDOWNS` overrides the defaults, like
+/// `lawsuit create=1m/10s,stats=0s/0s` for one minute per user and ten seconds per guild.
+#[derive(Debug)]
+pub struct Cooldowns {
+    limits: HashMap<String, Cooldown>,
+    running: DashMap<(String, Scope), Instant>,
+}
+
+impl Cooldowns {
+    pub fn from_env() -> Result<Self> {
+        let mut limits = DEFAULTS
+            .iter()
+            .map(|(command, cooldown)| (command.to_string(), *cooldown))
+            .collect::<HashMap<_, _>>();
+
+        if let Ok(overrides) = env::var("COOLDOWNS") {
+            for entry in overrides
+                .split(',')
+                .filter(|entry| !entry.trim().is_empty())
+            {
+                let (command, cooldown) = parse_entry(entry)
+                    .wrap_err_with(|| format!("invalid entry in COOLDOWNS: {entry}"))?;
+                limits.insert(command, cooldown);
+            }
+        }
+
+        Ok(Self {
+            limits,
+            running: DashMap::new(),
+        })
+    }
+
+    /// Starts the cooldowns of the command for the user and the guild, or returns how long the
+    /// longer of the two still runs.
+    fn hit(
+        &self,
+        command: &str,
+        user_id: UserId,
+        guild_id: Option<GuildId>,
+    ) -> std::result::Result<(), Duration> {
+        let cooldown = match self.limits.get(command) {
+            Some(cooldown) => *cooldown,
+            None => return Ok(()),
+        };
+
+        let mut scopes = vec![(Scope::User(user_id), cooldown.user)];
+        if let Some(guild_id) = guild_id {
+            scopes.push((Scope::Guild(guild_id), cooldown.guild));
+        }
+
+        let now = Instant::now();
+        let remaining = scopes
+            .iter()
+            .filter_map(|(scope, _)| self.running.get(&(command.to_owned(), scope.clone())))
+            .map(|until| until.saturating_duration_since(now))
+            .max()
+            .unwrap_or_default();
+        if !remaining.is_zero() {
+            return Err(remaining);
+        }
+
+        if self.running.len() > CLEANUP_THRESHOLD {
+            self.running.retain(|_, until| *until > now);
+        }
+        for (scope, duration) in scopes {
+            if !duration.is_zero() {
+                self.running
+                    .insert((command.to_owned(), scope), now + duration);
+            }
+        }
+        Ok(())
+    }
+}
+
+fn parse_entry(entry: &str) -> Result<(String, Cooldown)> {
+    let (command, durations) = entry.split_once('=').wrap_err("missing `=`")?;
+    let (user, guild) = durations.split_once('/').wrap_err("missing `/`")?;
+
+    let parse = |value: &str| -> Result<Duration> {
+        match value.trim() {
+            "0" | "0s" => Ok(Duration::ZERO),
+            value => duration::parse(value).wrap_err("invalid duration"),
+        }
+    };
+    let command = command.split_whitespace().collect::<Vec<_>>().join(" ");
+    if command.is_empty() {
+        bail!("missing command");
+    }
+
+    Ok((
+        command,
+        Cooldown {
+            user: parse(user)?,
+            guild: parse(guild)?,
+        },
+    ))
+}
+
+/// The command check of the framework, runs before every command.
+pub async fn check(ctx: Context<'_>) -> Result<bool> {
+    let command = &ctx.command().qualified_name;
+    match ctx
+        .data()
+        .cooldowns
+        .hit(command, ctx.author().id, ctx.guild_id())
+    {
+        Ok(()) => Ok(true),
+        Err(remaining) => Err(OnCooldown(remaining).into()),
+    }
+}
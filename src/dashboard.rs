@@ -0,0 +1,760 @@
+use std::{
+    env,
+    fmt::Write,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use axum::{
+    extract::{Form, Path, Query},
+    http::{
+        header::{COOKIE, SET_COOKIE},
+        HeaderMap, StatusCode,
+    },
+    response::{Html, IntoResponse, Redirect, Response},
+    routing::{get, post},
+    Extension, Router,
+};
+use color_eyre::{Report, Result};
+use dashmap::DashMap;
+use mongodb::bson::{DateTime, Uuid};
+use once_cell::sync::Lazy;
+use poise::serenity::{http::Http, model::permissions::Permissions};
+use rand::RngCore;
+use reqwest::Url;
+use serde::Deserialize;
+use tracing::{error, info, warn};
+
+use crate::{
+    audit::{self, AuditEvent},
+    i18n::{Locale, Strings},
+    maintenance::Maintenance,
+    model::{SnowflakeId, State},
+    storage::Storage,
+    transcript::escape_html,
+    webhook, WrapErr,
+};
+
+const DISCORD_API: &str = "https://discord.com/api/v10";
+const SESSION_COOKIE: &str = "court_session";
+const OAUTH_STATE_COOKIE: &str = "court_oauth_state";
+const SESSION_DURATION: Duration = Duration::from_secs(60 * 60 * 12);
+const LAWSUITS_PER_PAGE: usize = 25;
+/// Only the most recent arrests are shown, the full history is in the export
+const MAX_PRISON_RECORDS: usize = 50;
+
+/// The values of the locales in the form, the same as in the database.
+const LOCALES: &[(Locale, &str)] = &[
+    (Locale::DeCh, "de-CH"),
+    (Locale::DeDe, "de-DE"),
+    (Locale::En, "en"),
+];
+
+static CLIENT: Lazy<reqwest::Client> = Lazy::new(|| {
+    reqwest::Client::builder()
+        .timeout(Duration::from_secs(10))
+        .build()
+        .expect("create dashboard client")
+});
+
+/// A small web UI under `/dashboard` where admins log in with discord and can look at the
+/// lawsuits, prisoners and past arrests of their servers and change some of the settings.
+///
+/// Everyone who can manage the server or has the court administration role can use it, like with
+/// `/config`. Settings that point to channels, roles or users are still changed with the commands,
+/// discord checks those for us there.
+pub struct Dashboard {
+    client_id: String,
+    client_secret: String,
+    /// The public url of the dashboard, discord redirects back to `{base_url}/callback`
+    base_url: String,
+    http: Arc<Http>,
+    sessions: DashMap<String, Session>,
+}
+
+#[derive(Debug, Clone)]
+struct Session {
+    user_id: SnowflakeId,
+    user_name: String,
+    /// The guilds of the user that use the bot
+    guilds: Vec<DashboardGuild>,
+    /// Sent with every form, so other sites can't change settings in the name of the user
+    csrf_token: String,
+    expires_at: Instant,
+}
+
+#[derive(Debug, Clone)]
+struct DashboardGuild {
+    id: SnowflakeId,
+    name: String,
+}
+
+impl Dashboard {
+    /// The dashboard is only served when `DASHBOARD_CLIENT_ID` is set, it also needs
+    /// `DASHBOARD_CLIENT_SECRET` and `DASHBOARD_URL`.
+    pub fn from_env(http: Arc<Http>) -> Result<Option<Self>> {
+        let client_id = match env::var("DASHBOARD_CLIENT_ID") {
+            Ok(client_id) => client_id,
+            Err(_) => return Ok(None),
+        };
+        let client_secret = env::var("DASHBOARD_CLIENT_SECRET")
+            .wrap_err("DASHBOARD_CLIENT_SECRET must be set when DASHBOARD_CLIENT_ID is set")?;
+        let base_url = env::var("DASHBOARD_URL")
+            .wrap_err("DASHBOARD_URL must be set when DASHBOARD_CLIENT_ID is set")?;
+
+        Ok(Some(Self {
+            client_id,
+            client_secret,
+            base_url: base_url.trim_end_matches('/').to_owned(),
+            http,
+            sessions: DashMap::new(),
+        }))
+    }
+
+    fn redirect_uri(&self) -> String {
+        format!("{}/callback", self.base_url)
+    }
+
+    fn cookie(&self, name: &str, value: &str, max_age: Duration) -> String {
+        let secure = if self.base_url.starts_with("https://") {
+            "; Secure"
+        } else {
+            ""
+        };
+        format!(
+            "{name}={value}; Max-Age={}; Path=/dashboard; HttpOnly; SameSite=Lax{secure}",
+            max_age.as_secs()
+        )
+    }
+
+    fn session(&self, headers: &HeaderMap) -> Option<Session> {
+        let id = cookie(headers, SESSION_COOKIE)?;
+        let session = self.sessions.get(id)?.clone();
+        if session.expires_at < Instant::now() {
+            self.sessions.remove(id);
+            return None;
+        }
+        Some(session)
+    }
+
+    /// Checks again on every request whether the user is still an admin, roles change more often
+    /// than people log in. The permissions from the login would stay valid for the whole session,
+    /// so they are looked up through the bot.
+    async fn can_manage(&self, session: &Session, state: &State) -> bool {
+        if !session
+            .guilds
+            .iter()
+            .any(|guild| guild.id == state.guild_id)
+        {
+            return false;
+        }
+
+        match self.is_admin(session.user_id, state).await {
+            Ok(is_admin) => is_admin,
+            Err(err) => {
+                warn!(?err, guild_id = %state.guild_id, "Failed to check the dashboard permissions");
+                false
+            }
+        }
+    }
+
+    /// Whether the user owns or can manage the server or has the court administration role.
+    async fn is_admin(&self, user_id: SnowflakeId, state: &State) -> Result<bool> {
+        let guild_id = state.guild_id;
+        let member = self
+            .http
+            .get_member(guild_id.0, user_id.0)
+            .await
+            .wrap_err("get member")?;
+        if state
+            .admin_role
+            .map_or(false, |role| member.roles.contains(&role.into()))
+        {
+            return Ok(true);
+        }
+
+        let guild = self
+            .http
+            .get_guild(guild_id.0)
+            .await
+            .wrap_err("get guild")?;
+        if guild.owner_id == member.user.id {
+            return Ok(true);
+        }
+        // the @everyone role has the same id as the guild
+        let permissions = guild
+            .roles
+            .values()
+            .filter(|role| role.id.0 == guild_id.0 || member.roles.contains(&role.id))
+            .fold(Permissions::empty(), |permissions, role| {
+                permissions | role.permissions
+            });
+        Ok(permissions.intersects(Permissions::MANAGE_GUILD | Permissions::ADMINISTRATOR))
+    }
+}
+
+pub fn router(dashboard: Arc<Dashboard>, maintenance: Arc<Maintenance>) -> Router {
+    Router::new()
+        .route("/", get(index))
+        .route("/login", get(login))
+        .route("/callback", get(callback))
+        .route("/logout", get(logout))
+        .route("/guilds/:guild_id", get(guild))
+        .route("/guilds/:guild_id/config", post(update_config))
+        .layer(Extension(dashboard))
+        .layer(Extension(maintenance))
+}
+
+async fn index(headers: HeaderMap, Extension(dashboard): Extension<Arc<Dashboard>>) -> Response {
+    let session = match dashboard.session(&headers) {
+        Some(session) => session,
+        None => return Redirect::to("/dashboard/login").into_response(),
+    };
+    let strings = Locale::default().strings();
+
+    let mut body = format!(
+        "<p>{} · <a href=\"/dashboard/logout\">{}</a></p>",
+        escape_html(&session.user_name),
+        strings.dashboard_logout
+    );
+    if session.guilds.is_empty() {
+        let _ = write!(body, "<p>{}</p>", strings.dashboard_no_guilds);
+    } else {
+        body.push_str("<ul>");
+        for guild in &session.guilds {
+            let _ = write!(
+                body,
+                "<li><a href=\"/dashboard/guilds/{}\">{}</a></li>",
+                guild.id,
+                escape_html(&guild.name)
+            );
+        }
+        body.push_str("</ul>");
+    }
+
+    page(strings.dashboard_title, &body).into_response()
+}
+
+async fn login(Extension(dashboard): Extension<Arc<Dashboard>>) -> impl IntoResponse {
+    let oauth_state = random_token();
+    let url = Url::parse_with_params(
+        "https://discord.com/oauth2/authorize",
+        &[
+            ("response_type", "code"),
+            ("scope", "identify guilds"),
+            ("client_id", dashboard.client_id.as_str()),
+            ("redirect_uri", dashboard.redirect_uri().as_str()),
+            ("state", oauth_state.as_str()),
+        ],
+    )
+    .expect("authorize url is valid");
+    let cookie = dashboard.cookie(OAUTH_STATE_COOKIE, &oauth_state, Duration::from_secs(600));
+
+    ([(SET_COOKIE, cookie)], Redirect::to(url.as_str()))
+}
+
+#[derive(Debug, Deserialize)]
+struct CallbackQuery {
+    code: String,
+    state: String,
+}
+
+async fn callback(
+    Query(query): Query<CallbackQuery>,
+    headers: HeaderMap,
+    Extension(dashboard): Extension<Arc<Dashboard>>,
+    Extension(storage): Extension<Arc<dyn Storage>>,
+) -> Result<Response, StatusCode> {
+    if cookie(&headers, OAUTH_STATE_COOKIE) != Some(query.state.as_str()) {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let session = login_session(&dashboard, &*storage, &query.code)
+        .await
+        .map_err(internal_error)?;
+    info!(user_id = %session.user_id, "Logged in to the dashboard");
+
+    let now = Instant::now();
+    dashboard
+        .sessions
+        .retain(|_, session| session.expires_at > now);
+
+    let id = random_token();
+    let cookie = dashboard.cookie(SESSION_COOKIE, &id, SESSION_DURATION);
+    dashboard.sessions.insert(id, session);
+
+    Ok(([(SET_COOKIE, cookie)], Redirect::to("/dashboard")).into_response())
+}
+
+#[derive(Debug, Deserialize)]
+struct TokenResponse {
+    access_token: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct DiscordUser {
+    id: SnowflakeId,
+    username: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct PartialGuild {
+    id: SnowflakeId,
+    name: String,
+}
+
+/// Exchanges the code from discord for a token, which is only used to get the user and their
+/// guilds right away.
+async fn login_session(
+    dashboard: &Dashboard,
+    storage: &dyn Storage,
+    code: &str,
+) -> Result<Session> {
+    let redirect_uri = dashboard.redirect_uri();
+    let token = CLIENT
+        .post(format!("{DISCORD_API}/oauth2/token"))
+        .form(&[
+            ("client_id", dashboard.client_id.as_str()),
+            ("client_secret", dashboard.client_secret.as_str()),
+            ("grant_type", "authorization_code"),
+            ("code", code),
+            ("redirect_uri", redirect_uri.as_str()),
+        ])
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .wrap_err("exchange oauth code")?
+        .json::<TokenResponse>()
+        .await
+        .wrap_err("parse oauth token")?;
+
+    let user = discord_get::<DiscordUser>(&token.access_token, "/users/@me")
+        .await
+        .wrap_err("get user")?;
+    let partial_guilds = discord_get::<Vec<PartialGuild>>(&token.access_token, "/users/@me/guilds")
+        .await
+        .wrap_err("get guilds")?;
+
+    let mut guilds = Vec::new();
+    for guild in partial_guilds {
+        // guilds that never used the bot have nothing to show
+        if storage.find_state(guild.id).await?.is_none() {
+            continue;
+        }
+        guilds.push(DashboardGuild {
+            id: guild.id,
+            name: guild.name,
+        });
+    }
+
+    Ok(Session {
+        user_id: user.id,
+        user_name: user.username,
+        guilds,
+        csrf_token: random_token(),
+        expires_at: Instant::now() + SESSION_DURATION,
+    })
+}
+
+async fn discord_get<T: serde::de::DeserializeOwned>(access_token: &str, path: &str) -> Result<T> {
+    let value = CLIENT
+        .get(format!("{DISCORD_API}{path}"))
+        .bearer_auth(access_token)
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())?
+        .json()
+        .await?;
+    Ok(value)
+}
+
+async fn logout(
+    headers: HeaderMap,
+    Extension(dashboard): Extension<Arc<Dashboard>>,
+) -> impl IntoResponse {
+    if let Some(id) = cookie(&headers, SESSION_COOKIE) {
+        dashboard.sessions.remove(id);
+    }
+    let cookie = dashboard.cookie(SESSION_COOKIE, "", Duration::ZERO);
+
+    ([(SET_COOKIE, cookie)], Redirect::to("/dashboard/login"))
+}
+
+#[derive(Debug, Deserialize)]
+struct GuildQuery {
+    after: Option<String>,
+}
+
+async fn guild(
+    Path(guild_id): Path<SnowflakeId>,
+    Query(query): Query<GuildQuery>,
+    headers: HeaderMap,
+    Extension(dashboard): Extension<Arc<Dashboard>>,
+    Extension(storage): Extension<Arc<dyn Storage>>,
+) -> Result<Response, StatusCode> {
+    let session = match dashboard.session(&headers) {
+        Some(session) => session,
+        None => return Ok(Redirect::to("/dashboard/login").into_response()),
+    };
+    let state = authorize(&dashboard, &*storage, &session, guild_id).await?;
+
+    let after = query
+        .after
+        .as_deref()
+        .map(Uuid::parse_str)
+        .transpose()
+        .map_err(|_| StatusCode::BAD_REQUEST)?;
+    let body = guild_page(&*storage, &session, &state, after)
+        .await
+        .map_err(internal_error)?;
+    let guild_name = session
+        .guilds
+        .iter()
+        .find(|guild| guild.id == guild_id)
+        .map_or_else(|| guild_id.to_string(), |guild| guild.name.clone());
+
+    Ok(page(&guild_name, &body).into_response())
+}
+
+async fn guild_page(
+    storage: &dyn Storage,
+    session: &Session,
+    state: &State,
+    after: Option<Uuid>,
+) -> Result<String> {
+    let strings = state.locale.strings();
+    let guild_id = state.guild_id;
+    let mut body = String::new();
+
+    let _ = write!(
+        body,
+        "<p><a href=\"/dashboard\">{}</a></p><h2>{}</h2>",
+        strings.dashboard_title, strings.config_title
+    );
+    config_form(&mut body, strings, session, state);
+
+    let lawsuits = storage
+        .find_lawsuits_page(guild_id, after, LAWSUITS_PER_PAGE)
+        .await?;
+    let _ = write!(body, "<h2>{}</h2>", strings.lawsuits_title);
+    table(
+        &mut body,
+        &[
+            strings.field_reason,
+            strings.field_plaintiff,
+            strings.field_accused,
+            strings.field_judge,
+            strings.field_verdict,
+        ],
+        lawsuits.items.iter().map(|lawsuit| {
+            vec![
+                lawsuit.reason.clone(),
+                lawsuit.plaintiff.to_string(),
+                lawsuit.accused.to_string(),
+                lawsuit.judge.to_string(),
+                lawsuit
+                    .verdict
+                    .clone()
+                    .unwrap_or_else(|| strings.none.to_owned()),
+            ]
+        }),
+    );
+    if let Some(next) = lawsuits.next {
+        let _ = write!(
+            body,
+            "<p><a href=\"/dashboard/guilds/{guild_id}?after={next}\">{}</a></p>",
+            strings.next_page
+        );
+    }
+
+    let prisoners = storage.find_prison_entries(guild_id).await?;
+    let _ = write!(body, "<h2>{}</h2>", strings.prisoners_title);
+    table(
+        &mut body,
+        &[
+            strings.field_prisoner,
+            strings.setting_prison_tier,
+            strings.field_release,
+            strings.field_until,
+            strings.field_ordered_by,
+        ],
+        prisoners.iter().map(|entry| {
+            vec![
+                entry.user_id.to_string(),
+                entry
+                    .tier
+                    .clone()
+                    .unwrap_or_else(|| strings.none.to_owned()),
+                format_time(entry.release_at, strings.indefinite),
+                format_time(entry.parole_until, strings.none),
+                entry
+                    .arrested_by
+                    .map_or_else(|| strings.none.to_owned(), |id| id.to_string()),
+            ]
+        }),
+    );
+
+    let mut records = storage.find_prison_records(guild_id).await?;
+    records.sort_by_key(|record| std::cmp::Reverse(record.arrested_at));
+    let _ = write!(body, "<h2>{}</h2>", strings.import_prison_history);
+    table(
+        &mut body,
+        &[
+            strings.field_prisoner,
+            strings.field_reason,
+            strings.audit_arrested,
+            strings.audit_released,
+        ],
+        records.iter().take(MAX_PRISON_RECORDS).map(|record| {
+            vec![
+                record.user_id.to_string(),
+                record
+                    .reason
+                    .clone()
+                    .unwrap_or_else(|| strings.no_reason.to_owned()),
+                format_time(Some(record.arrested_at), strings.none),
+                format_time(record.released_at, strings.none),
+            ]
+        }),
+    );
+
+    Ok(body)
+}
+
+fn config_form(body: &mut String, strings: &Strings, session: &Session, state: &State) {
+    let _ = write!(
+        body,
+        "<form method=\"post\" action=\"/dashboard/guilds/{}/config\">\
+         <input type=\"hidden\" name=\"csrf_token\" value=\"{}\">",
+        state.guild_id, session.csrf_token
+    );
+
+    let _ = write!(
+        body,
+        "<p><label>{} <select name=\"locale\">",
+        strings.setting_locale
+    );
+    for (locale, value) in LOCALES {
+        let selected = if *locale == state.locale {
+            " selected"
+        } else {
+            ""
+        };
+        let _ = write!(
+            body,
+            "<option value=\"{value}\"{selected}>{locale}</option>"
+        );
+    }
+    body.push_str("</select></label></p>");
+
+    for (name, label, checked) in [
+        (
+            "strip_roles",
+            strings.setting_strip_roles,
+            state.strip_roles_on_arrest,
+        ),
+        (
+            "notifications",
+            strings.setting_notifications,
+            state.notify_prisoners,
+        ),
+    ] {
+        let checked = if checked { " checked" } else { "" };
+        let _ = write!(
+            body,
+            "<p><label><input type=\"checkbox\" name=\"{name}\" value=\"true\"{checked}> \
+             {label}</label></p>"
+        );
+    }
+
+    let _ = write!(
+        body,
+        "<p><label>{} <input type=\"url\" name=\"webhook_url\" value=\"{}\"></label></p>\
+         <p><button type=\"submit\">{}</button></p></form>",
+        strings.setting_webhook,
+        escape_html(state.webhook_url.as_deref().unwrap_or_default()),
+        strings.dashboard_save
+    );
+}
+
+#[derive(Debug, Deserialize)]
+struct ConfigForm {
+    csrf_token: String,
+    locale: Locale,
+    /// Checkboxes are only sent when they are checked
+    strip_roles: Option<bool>,
+    notifications: Option<bool>,
+    webhook_url: String,
+}
+
+async fn update_config(
+    Path(guild_id): Path<SnowflakeId>,
+    headers: HeaderMap,
+    Extension(dashboard): Extension<Arc<Dashboard>>,
+    Extension(storage): Extension<Arc<dyn Storage>>,
+    Extension(maintenance): Extension<Arc<Maintenance>>,
+    Form(form): Form<ConfigForm>,
+) -> Result<Response, StatusCode> {
+    // read-only like the commands, the database may be in the middle of a migration
+    if maintenance.is_enabled() {
+        return Err(StatusCode::SERVICE_UNAVAILABLE);
+    }
+    let session = dashboard
+        .session(&headers)
+        .ok_or(StatusCode::UNAUTHORIZED)?;
+    if form.csrf_token != session.csrf_token {
+        return Err(StatusCode::FORBIDDEN);
+    }
+    let state = authorize(&dashboard, &*storage, &session, guild_id).await?;
+
+    let webhook_url = Some(form.webhook_url.trim()).filter(|url| !url.is_empty());
+    if let Some(url) = webhook_url {
+        if !webhook::is_valid_url(url).await {
+            return Err(StatusCode::BAD_REQUEST);
+        }
+    }
+
+    apply_config(&dashboard, &*storage, &session, &state, &form, webhook_url)
+        .await
+        .map_err(internal_error)?;
+
+    Ok(Redirect::to(&format!("/dashboard/guilds/{guild_id}")).into_response())
+}
+
+/// Only changes the settings that are different, every change is logged in the audit channel like
+/// the ones made with the commands.
+async fn apply_config(
+    dashboard: &Dashboard,
+    storage: &dyn Storage,
+    session: &Session,
+    state: &State,
+    form: &ConfigForm,
+    webhook_url: Option<&str>,
+) -> Result<()> {
+    let guild_id = state.guild_id;
+    let strip_roles = form.strip_roles.unwrap_or(false);
+    let notifications = form.notifications.unwrap_or(false);
+    let strings = form.locale.strings();
+    let mut changes = Vec::new();
+
+    if form.locale != state.locale {
+        storage.set_locale(guild_id, form.locale).await?;
+        changes.push((strings.setting_locale, form.locale.to_string()));
+    }
+    if strip_roles != state.strip_roles_on_arrest {
+        storage
+            .set_strip_roles_on_arrest(guild_id, strip_roles)
+            .await?;
+        changes.push((strings.setting_strip_roles, strip_roles.to_string()));
+    }
+    if notifications != state.notify_prisoners {
+        storage
+            .set_notify_prisoners(guild_id, notifications)
+            .await?;
+        changes.push((strings.setting_notifications, notifications.to_string()));
+    }
+    if webhook_url != state.webhook_url.as_deref() {
+        storage.set_webhook_url(guild_id, webhook_url).await?;
+        changes.push((
+            strings.setting_webhook,
+            webhook_url
+                .map_or(strings.none, webhook::display_url)
+                .to_owned(),
+        ));
+    }
+
+    if changes.is_empty() {
+        return Ok(());
+    }
+
+    info!(%guild_id, user_id = %session.user_id, "Changed settings in the dashboard");
+    let state = storage.find_or_insert_state(guild_id).await?;
+    for (setting, value) in changes {
+        audit::log(
+            &dashboard.http,
+            &state,
+            AuditEvent::ConfigChanged {
+                setting,
+                value,
+                changed_by: session.user_id.into(),
+            },
+        )
+        .await;
+    }
+
+    Ok(())
+}
+
+/// Unknown guilds get the same answer as guilds the user can't manage.
+async fn authorize(
+    dashboard: &Dashboard,
+    storage: &dyn Storage,
+    session: &Session,
+    guild_id: SnowflakeId,
+) -> Result<State, StatusCode> {
+    let state = storage
+        .find_state(guild_id)
+        .await
+        .map_err(internal_error)?
+        .ok_or(StatusCode::FORBIDDEN)?;
+
+    if dashboard.can_manage(session, &state).await {
+        Ok(state)
+    } else {
+        Err(StatusCode::FORBIDDEN)
+    }
+}
+
+fn internal_error(err: Report) -> StatusCode {
+    error!(?err, "Dashboard request failed");
+    StatusCode::INTERNAL_SERVER_ERROR
+}
+
+fn cookie<'a>(headers: &'a HeaderMap, name: &str) -> Option<&'a str> {
+    headers
+        .get_all(COOKIE)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(';'))
+        .filter_map(|pair| pair.trim().split_once('='))
+        .find(|(key, _)| *key == name)
+        .map(|(_, value)| value)
+}
+
+fn random_token() -> String {
+    let mut bytes = [0; 32];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    hex::encode(bytes)
+}
+
+fn format_time(time: Option<DateTime>, fallback: &str) -> String {
+    time.and_then(|time| time.try_to_rfc3339_string().ok())
+        .unwrap_or_else(|| fallback.to_owned())
+}
+
+fn table(body: &mut String, headers: &[&str], rows: impl Iterator<Item = Vec<String>>) {
+    body.push_str("<table><tr>");
+    for header in headers {
+        let _ = write!(body, "<th>{header}</th>");
+    }
+    body.push_str("</tr>");
+    for row in rows {
+        body.push_str("<tr>");
+        for cell in row {
+            let _ = write!(body, "<td>{}</td>", escape_html(&cell));
+        }
+        body.push_str("</tr>");
+    }
+    body.push_str("</table>");
+}
+
+fn page(title: &str, body: &str) -> Html<String> {
+    Html(format!(
+        "<!DOCTYPE html><html><head><meta charset=\"utf-8\"><title>{title}</title>\
+         <style>body {{ font-family: sans-serif; max-width: 60em; margin: auto; }} \
+         table {{ border-collapse: collapse; }} \
+         td, th {{ border: 1px solid #ccc; padding: 0.3em; }}</style></head>\
+         <body><h1>{title}</h1>{body}</body></html>",
+        title = escape_html(title),
+    ))
+}
@@ -0,0 +1,179 @@
+use std::time::Duration;
+
+use mongodb::bson::DateTime;
+
+/// Parses durations like `30m`, `12h`, `7d` or `1d12h`.
+pub fn parse(input: &str) -> Option<Duration> {
+    let mut total = 0u64;
+    let mut number = String::new();
+
+    for char in input.trim().chars() {
+        if char.is_ascii_digit() {
+            number.push(char);
+            continue;
+        }
+
+        let factor = match char {
+            's' => 1,
+            'm' => 60,
+            'h' => 60 * 60,
+            'd' => 60 * 60 * 24,
+            'w' => 60 * 60 * 24 * 7,
+            _ => return None,
+        };
+
+        let value = number.parse::<u64>().ok()?;
+        total = total.checked_add(value.checked_mul(factor)?)?;
+        number.clear();
+    }
+
+    if !number.is_empty() || total == 0 {
+        return None;
+    }
+
+    Some(Duration::from_secs(total))
+}
+
+/// The point in time that is `duration` from now.
+pub fn from_now(duration: Duration) -> DateTime {
+    DateTime::from_millis(DateTime::now().timestamp_millis() + duration.as_millis() as i64)
+}
+
+/// Formats the duration like `1d 12h`, leaving out the seconds.
+pub fn format(duration: Duration) -> String {
+    let minutes = duration.as_secs() / 60;
+    let (days, hours, minutes) = (minutes / (60 * 24), minutes / 60 % 24, minutes % 60);
+
+    let parts = [(days, "d"), (hours, "h"), (minutes, "m")]
+        .into_iter()
+        .filter(|(value, _)| *value > 0)
+        .map(|(value, unit)| format!("{value}{unit}"))
+        .collect::<Vec<_>>();
+
+    if parts.is_empty() {
+        "0m".to_string()
+    } else {
+        parts.join(" ")
+    }
+}
+
+/// Formats the time as a relative discord timestamp that is rendered in the user's timezone.
+pub fn relative_timestamp(time: DateTime) -> String {
+    format!("<t:{}:R>", time.timestamp_millis() / 1000)
+}
+
+/// Formats the time as a discord timestamp with the date, for things that stay around.
+pub fn date_timestamp(time: DateTime) -> String {
+    format!("<t:{}:D>", time.timestamp_millis() / 1000)
+}
+
+/// Formats the time as a discord timestamp with the date and the time of day.
+pub fn full_timestamp(time: DateTime) -> String {
+    format!("<t:{}:F>", time.timestamp_millis() / 1000)
+}
+
+/// The year of the time in UTC.
+pub fn year(time: DateTime) -> i64 {
+    civil(time).0
+}
+
+/// The month of the time in UTC like `2023-04`.
+pub fn month(time: DateTime) -> String {
+    let (year, month, _) = civil(time);
+    format!("{year}-{month:02}")
+}
+
+/// Formats the time like `2023-04-01 13:37 UTC`, for places where discord timestamps aren't
+/// rendered.
+pub fn utc(time: DateTime) -> String {
+    let (year, month, day) = civil(time);
+    let minutes = time
+        .timestamp_millis()
+        .div_euclid(1000 * 60)
+        .rem_euclid(60 * 24);
+    format!(
+        "{year}-{month:02}-{day:02} {:02}:{:02} UTC",
+        minutes / 60,
+        minutes % 60
+    )
+}
+
+/// Formats the time like `20230401T133700Z`, the UTC format of calendar files.
+pub fn ical(time: DateTime) -> String {
+    let (year, month, day) = civil(time);
+    let seconds = time
+        .timestamp_millis()
+        .div_euclid(1000)
+        .rem_euclid(60 * 60 * 24);
+    format!(
+        "{year:04}{month:02}{day:02}T{:02}{:02}{:02}Z",
+        seconds / (60 * 60),
+        seconds / 60 % 60,
+        seconds % 60
+    )
+}
+
+/// Parses a date like `2023-04-01` to midnight in UTC.
+pub fn parse_date(input: &str) -> Option<DateTime> {
+    let mut parts = input.trim().splitn(3, '-').map(str::parse::<i64>);
+    let (year, month, day) = (
+        parts.next()?.ok()?,
+        parts.next()?.ok()?,
+        parts.next()?.ok()?,
+    );
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return None;
+    }
+
+    // the days from civil algorithm from the same place as `civil`
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year.rem_euclid(400);
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    let days = era * 146_097 + day_of_era - 719_468;
+
+    let date = DateTime::from_millis(days * 1000 * 60 * 60 * 24);
+    // days like February 31st would move to the next month
+    if civil(date).2 == day {
+        Some(date)
+    } else {
+        None
+    }
+}
+
+/// Parses a date with a time like `2023-04-01 13:37` in UTC.
+pub fn parse_date_time(input: &str) -> Option<DateTime> {
+    let (date, time) = input.trim().split_once(|char| char == ' ' || char == 'T')?;
+    let (hours, minutes) = time.trim().split_once(':')?;
+    let (hours, minutes) = (hours.parse::<i64>().ok()?, minutes.parse::<i64>().ok()?);
+    if !(0..24).contains(&hours) || !(0..60).contains(&minutes) {
+        return None;
+    }
+
+    let date = parse_date(date)?;
+    Some(DateTime::from_millis(
+        date.timestamp_millis() + (hours * 60 + minutes) * 1000 * 60,
+    ))
+}
+
+/// The year, month and day of the time in UTC.
+fn civil(time: DateTime) -> (i64, i64, i64) {
+    // the civil from days algorithm from http://howardhinnant.github.io/date_algorithms.html,
+    // the years of the algorithm start in March
+    let days = time.timestamp_millis().div_euclid(1000 * 60 * 60 * 24) + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month + 2) / 5 + 1;
+    let year = year_of_era + era * 400;
+    // January and February belong to the next year
+    if month >= 10 {
+        (year + 1, month - 9, day)
+    } else {
+        (year, month + 3, day)
+    }
+}
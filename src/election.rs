@@ -0,0 +1,432 @@
+use std::{collections::HashMap, sync::Arc, time::Duration};
+
+use color_eyre::{eyre::ContextCompat, Result};
+use futures::StreamExt;
+use mongodb::bson::{DateTime, Uuid};
+use poise::serenity::{
+    builder::{CreateComponents, CreateEmbed},
+    collector::ComponentInteractionCollectorBuilder,
+    model::{
+        channel::Message,
+        id::{GuildId, RoleId, UserId},
+        interactions::{message_component::ButtonStyle, InteractionResponseType},
+    },
+    prelude::Context,
+};
+use serde::{Deserialize, Serialize};
+use tracing::{error, info};
+
+use crate::{
+    audit::{self, AuditEvent},
+    duration,
+    i18n::Strings,
+    model::SnowflakeId,
+    retry::retry,
+    shutdown::BackgroundTasks,
+    storage::Storage,
+    WrapErr,
+};
+
+/// The candidates are the options of a select menu and discord only allows 25 of them
+pub const MAX_CANDIDATES: usize = 25;
+
+/// A finished election of the members who get a role, usually the judge role. Like with the jury,
+/// only the sums of the votes are stored.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Election {
+    pub id: Uuid,
+    pub guild_id: SnowflakeId,
+    pub role_id: SnowflakeId,
+    pub seats: u32,
+    pub started_by: SnowflakeId,
+    pub started_at: DateTime,
+    pub ended_at: DateTime,
+    /// The candidates with the most votes first
+    pub results: Vec<CandidateResult>,
+    pub winners: Vec<SnowflakeId>,
+    pub voters: u32,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct CandidateResult {
+    pub candidate: SnowflakeId,
+    pub votes: u32,
+}
+
+impl Election {
+    /// Replaces the user with the id 0. Returns whether they took part in the election.
+    pub fn anonymize_user(&mut self, user_id: SnowflakeId) -> bool {
+        const ANONYMOUS: SnowflakeId = SnowflakeId(0);
+
+        let mut touched = false;
+        let participants = std::iter::once(&mut self.started_by)
+            .chain(self.results.iter_mut().map(|result| &mut result.candidate))
+            .chain(self.winners.iter_mut());
+        for participant in participants {
+            if *participant == user_id {
+                *participant = ANONYMOUS;
+                touched = true;
+            }
+        }
+        touched
+    }
+}
+
+/// An election that is still running. First the members can run as candidates for a while, then
+/// everyone can vote for one of them for the same time. The votes are only known while the election
+/// is running, only the sums end up in [`Election`].
+pub struct RunningElection {
+    ids: ElectionIds,
+    message: Message,
+    guild_id: GuildId,
+    role_id: RoleId,
+    seats: u32,
+    started_by: UserId,
+    started_at: DateTime,
+    phase: Duration,
+}
+
+impl RunningElection {
+    /// Sends the message where the members can run as candidates to the channel of the command.
+    pub async fn start(
+        ctx: crate::Context<'_>,
+        strings: &Strings,
+        role_id: RoleId,
+        seats: u32,
+        phase: Duration,
+    ) -> Result<Self> {
+        let guild_id = ctx.guild_id().wrap_err("guild_id not found")?;
+        let ids = ElectionIds::new();
+        let deadline = duration::relative_timestamp(duration::from_now(phase));
+
+        let message = ctx
+            .channel_id()
+            .send_message(ctx.discord(), |message| {
+                message
+                    .embed(|embed| {
+                        embed
+                            .title(strings.election_title)
+                            .description((strings.election_candidacy_description)(&deadline))
+                            .field(strings.field_role, format!("<@&{role_id}>"), true)
+                            .field(strings.field_seats, seats, true)
+                    })
+                    .components(|c| candidacy_components(c, strings, &ids))
+            })
+            .await
+            .wrap_err("send election")?;
+
+        Ok(Self {
+            ids,
+            message,
+            guild_id,
+            role_id,
+            seats,
+            started_by: ctx.author().id,
+            started_at: DateTime::now(),
+            phase,
+        })
+    }
+
+    /// Collects the candidates and then the votes, gives the winners the role and stores the
+    /// result. If the bot shuts down while the candidates run, the election is cancelled, once
+    /// the voting started the votes so far are counted.
+    pub async fn run(
+        mut self,
+        ctx: Context,
+        storage: Arc<dyn Storage>,
+        tasks: BackgroundTasks,
+        strings: &'static Strings,
+    ) -> Result<Option<Election>> {
+        let candidates = match self.collect_candidates(&ctx, &tasks, strings).await? {
+            Some(candidates) if !candidates.is_empty() => candidates,
+            candidates => {
+                let text = match candidates {
+                    Some(_) => strings.election_no_candidates,
+                    None => strings.election_cancelled,
+                };
+                self.message
+                    .edit(&ctx.http, |message| {
+                        message
+                            .embed(|embed| embed.title(strings.election_title).description(text))
+                            .components(|c| c)
+                    })
+                    .await
+                    .wrap_err("end election without candidates")?;
+                return Ok(None);
+            }
+        };
+
+        let mut names = Vec::new();
+        for &candidate in &candidates {
+            let name = match self.guild_id.member(&ctx, candidate).await {
+                Ok(member) => member.display_name().into_owned(),
+                Err(_) => candidate.to_string(),
+            };
+            names.push((candidate, name));
+        }
+
+        let votes = self.collect_votes(&ctx, &tasks, strings, &names).await?;
+
+        let mut results = candidates
+            .iter()
+            .map(|&candidate| CandidateResult {
+                candidate: candidate.into(),
+                votes: votes.values().filter(|vote| **vote == candidate).count() as u32,
+            })
+            .collect::<Vec<_>>();
+        // the sort is stable, so on a tie the one who ran first wins
+        results.sort_by(|a, b| b.votes.cmp(&a.votes));
+        let winners = results
+            .iter()
+            .filter(|result| result.votes > 0)
+            .take(self.seats as usize)
+            .map(|result| result.candidate)
+            .collect::<Vec<_>>();
+
+        for &winner in &winners {
+            let result = retry(|| {
+                ctx.http
+                    .add_member_role(self.guild_id.0, winner.0, self.role_id.0, None)
+            })
+            .await;
+            // a winner who left the server in the meantime must not keep the others from their role
+            if let Err(err) = result {
+                error!(?err, %winner, "Failed to give the elected member the role");
+            }
+        }
+
+        let election = Election {
+            id: Uuid::new(),
+            guild_id: self.guild_id.into(),
+            role_id: self.role_id.into(),
+            seats: self.seats,
+            started_by: self.started_by.into(),
+            started_at: self.started_at,
+            ended_at: DateTime::now(),
+            results,
+            winners,
+            voters: votes.len() as u32,
+        };
+        storage.add_election(&election).await?;
+        info!(election_id = %election.id, ?election, "Election is over");
+
+        self.message
+            .edit(&ctx.http, |message| {
+                message
+                    .embed(|embed| result_embed(embed, strings, &election))
+                    .components(|c| c)
+            })
+            .await
+            .wrap_err("show election result")?;
+
+        let state = storage.find_or_insert_state(election.guild_id).await?;
+        audit::log(&ctx.http, &state, AuditEvent::ElectionFinished(&election)).await;
+
+        Ok(Some(election))
+    }
+
+    /// `None` if the bot shut down before the time was up.
+    async fn collect_candidates(
+        &self,
+        ctx: &Context,
+        tasks: &BackgroundTasks,
+        strings: &Strings,
+    ) -> Result<Option<Vec<UserId>>> {
+        let candidacy = self.ids.candidacy.clone();
+        let mut collector = ComponentInteractionCollectorBuilder::new(ctx)
+            .filter(move |interaction| interaction.data.custom_id == candidacy)
+            .timeout(self.phase)
+            .build();
+
+        let mut candidates = Vec::new();
+        loop {
+            let interaction = tokio::select! {
+                interaction = collector.next() => interaction,
+                _ = tasks.shutting_down() => return Ok(None),
+            };
+            let interaction = match interaction {
+                Some(interaction) => interaction,
+                None => break,
+            };
+
+            let user_id = interaction.user.id;
+            let answer = if candidates.contains(&user_id) {
+                strings.election_already_candidate.to_owned()
+            } else if candidates.len() >= MAX_CANDIDATES {
+                (strings.election_full)(MAX_CANDIDATES)
+            } else {
+                candidates.push(user_id);
+                strings.election_candidate_registered.to_owned()
+            };
+            interaction
+                .create_interaction_response(&ctx.http, |response| {
+                    response
+                        .kind(InteractionResponseType::ChannelMessageWithSource)
+                        .interaction_response_data(|data| data.content(answer).ephemeral(true))
+                })
+                .await
+                .wrap_err("answer candidacy")?;
+        }
+
+        Ok(Some(candidates))
+    }
+
+    /// The candidate that every member voted for. A member can change their vote until the time
+    /// is up, only the last one counts.
+    async fn collect_votes(
+        &mut self,
+        ctx: &Context,
+        tasks: &BackgroundTasks,
+        strings: &Strings,
+        names: &[(UserId, String)],
+    ) -> Result<HashMap<UserId, UserId>> {
+        let deadline = duration::relative_timestamp(duration::from_now(self.phase));
+        let ids = &self.ids;
+        let candidates = names
+            .iter()
+            .map(|(candidate, _)| format!("<@{candidate}>"))
+            .collect::<Vec<_>>();
+        self.message
+            .edit(&ctx.http, |message| {
+                message
+                    .embed(|embed| {
+                        embed
+                            .title(strings.election_title)
+                            .description((strings.election_voting_description)(&deadline))
+                            .field(strings.field_candidates, candidates.join("\n"), false)
+                    })
+                    .components(|c| ballot_components(c, strings, ids, names))
+            })
+            .await
+            .wrap_err("start voting")?;
+
+        let ballot = self.ids.ballot.clone();
+        let mut collector = ComponentInteractionCollectorBuilder::new(ctx)
+            .filter(move |interaction| interaction.data.custom_id == ballot)
+            .timeout(self.phase)
+            .build();
+
+        let mut votes = HashMap::new();
+        loop {
+            let interaction = tokio::select! {
+                interaction = collector.next() => interaction,
+                _ = tasks.shutting_down() => None,
+            };
+            let interaction = match interaction {
+                Some(interaction) => interaction,
+                None => break,
+            };
+
+            let choice = interaction
+                .data
+                .values
+                .first()
+                .and_then(|value| value.parse().ok())
+                .and_then(|value| names.iter().find(|(candidate, _)| candidate.0 == value));
+            let (candidate, name) = match choice {
+                Some(choice) => choice,
+                None => continue,
+            };
+            votes.insert(interaction.user.id, *candidate);
+
+            interaction
+                .create_interaction_response(&ctx.http, |response| {
+                    response
+                        .kind(InteractionResponseType::ChannelMessageWithSource)
+                        .interaction_response_data(|data| {
+                            data.content((strings.jury_vote_counted)(name))
+                                .ephemeral(true)
+                        })
+                })
+                .await
+                .wrap_err("acknowledge vote")?;
+        }
+
+        Ok(votes)
+    }
+}
+
+pub fn result_embed<'a>(
+    embed: &'a mut CreateEmbed,
+    strings: &Strings,
+    election: &Election,
+) -> &'a mut CreateEmbed {
+    let results = election
+        .results
+        .iter()
+        .map(|result| format!("<@{}>: {}", result.candidate, result.votes))
+        .collect::<Vec<_>>();
+    let winners = election
+        .winners
+        .iter()
+        .map(|winner| format!("<@{winner}>"))
+        .collect::<Vec<_>>();
+    let winners = if winners.is_empty() {
+        strings.election_no_winners.to_owned()
+    } else {
+        winners.join("\n")
+    };
+
+    embed
+        .title(strings.election_result_title)
+        .field(
+            strings.field_role,
+            format!("<@&{}>", election.role_id),
+            true,
+        )
+        .field(strings.field_seats, election.seats, true)
+        .field(strings.field_voters, election.voters, true)
+        .field(strings.field_winners, winners, false)
+        .field(strings.field_candidates, results.join("\n"), false)
+}
+
+struct ElectionIds {
+    candidacy: String,
+    ballot: String,
+}
+
+impl ElectionIds {
+    fn new() -> Self {
+        // the ids must be unique so that two elections running at the same time don't interfere
+        let prefix = format!("election-{}", Uuid::new());
+        Self {
+            candidacy: format!("{prefix}-candidacy"),
+            ballot: format!("{prefix}-ballot"),
+        }
+    }
+}
+
+fn candidacy_components<'a>(
+    components: &'a mut CreateComponents,
+    strings: &Strings,
+    ids: &ElectionIds,
+) -> &'a mut CreateComponents {
+    components.create_action_row(|row| {
+        row.create_button(|button| {
+            button
+                .custom_id(&ids.candidacy)
+                .label(strings.election_candidacy_button)
+                .style(ButtonStyle::Primary)
+        })
+    })
+}
+
+fn ballot_components<'a>(
+    components: &'a mut CreateComponents,
+    strings: &Strings,
+    ids: &ElectionIds,
+    names: &[(UserId, String)],
+) -> &'a mut CreateComponents {
+    components.create_action_row(|row| {
+        row.create_select_menu(|menu| {
+            menu.custom_id(&ids.ballot)
+                .placeholder(strings.election_vote_placeholder)
+                .options(|o| {
+                    for (candidate, name) in names {
+                        o.create_option(|option| option.label(name).value(candidate));
+                    }
+                    o
+                })
+        })
+    })
+}
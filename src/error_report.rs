@@ -0,0 +1,135 @@
+use std::env;
+
+use color_eyre::Result;
+use poise::serenity::{http::Http, model::id::ChannelId};
+use tracing::error;
+
+use crate::{
+    i18n::{Locale, Strings},
+    Context, Report, WrapErr,
+};
+
+/// Embeds can't show more, the end of the report is cut off.
+const MAX_REPORT_LEN: usize = 4000;
+
+/// The channel of the owner of the bot where the errors of all guilds are reported, read from
+/// `ERROR_CHANNEL_ID`.
+pub fn owner_channel_from_env() -> Result<Option<ChannelId>> {
+    match env::var("ERROR_CHANNEL_ID") {
+        Ok(id) => Ok(Some(ChannelId(
+            id.parse().wrap_err("ERROR_CHANNEL_ID must be an integer")?,
+        ))),
+        Err(_) => Ok(None),
+    }
+}
+
+/// Posts an error of a command with its id in the error channel of the guild and the channel of
+/// the owner, so that it doesn't only end up in the logs. Failing to post it is only logged.
+pub async fn report(ctx: Context<'_>, error_id: &str, error: &Report) {
+    let command = ctx.command().qualified_name.clone();
+    let user = format!("<@{}>", ctx.author().id);
+    let details = format_report(error);
+
+    let state = match ctx.guild_id() {
+        Some(guild_id) => ctx
+            .data()
+            .storage
+            .find_or_insert_state(guild_id.into())
+            .await
+            .ok(),
+        None => None,
+    };
+
+    let http = &ctx.discord().http;
+    let report = ErrorReport {
+        error_id,
+        command: &command,
+        user: &user,
+        guild: ctx.guild_id().map(|guild_id| guild_id.to_string()),
+        details: &details,
+    };
+
+    if let Some(state) = &state {
+        if let Some(channel_id) = state.error_channel {
+            send(
+                http,
+                channel_id.into(),
+                state.locale.strings(),
+                &report,
+                false,
+            )
+            .await;
+        }
+    }
+    if let Some(channel_id) = ctx.data().error_channel {
+        send(http, channel_id, Locale::default().strings(), &report, true).await;
+    }
+}
+
+struct ErrorReport<'a> {
+    error_id: &'a str,
+    command: &'a str,
+    user: &'a str,
+    guild: Option<String>,
+    details: &'a str,
+}
+
+async fn send(
+    http: &Http,
+    channel_id: ChannelId,
+    strings: &Strings,
+    report: &ErrorReport<'_>,
+    with_guild: bool,
+) {
+    let result = channel_id
+        .send_message(http, |message| {
+            message.embed(|embed| {
+                embed
+                    .title(strings.error_report_title)
+                    .description(format!("```\n{}\n```", report.details))
+                    .field(strings.field_error_id, report.error_id, true)
+                    .field(strings.field_command, format!("/{}", report.command), true)
+                    .field(strings.field_user, report.user, true);
+                // the guild only sees its own errors, the owner sees the errors of all guilds
+                if let (true, Some(guild)) = (with_guild, &report.guild) {
+                    embed.field(strings.field_guild, guild, true);
+                }
+                embed
+            })
+        })
+        .await;
+
+    if let Err(err) = result {
+        error!(?err, %channel_id, error_id = report.error_id, "Failed to report error");
+    }
+}
+
+/// The error with its causes and the backtrace, without the colors and cut off to fit.
+fn format_report(error: &Report) -> String {
+    let report = strip_ansi(&format!("{error:?}")).replace("```", "'''");
+    if report.chars().count() <= MAX_REPORT_LEN {
+        return report;
+    }
+    let mut truncated = report.chars().take(MAX_REPORT_LEN - 1).collect::<String>();
+    truncated.push('…');
+    truncated
+}
+
+/// Removes the escape sequences that color_eyre uses for colors.
+fn strip_ansi(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(char) = chars.next() {
+        if char == '\x1b' {
+            // the sequences look like `ESC [ 1 ; 31 m`
+            for char in chars.by_ref() {
+                if char.is_ascii_alphabetic() {
+                    break;
+                }
+            }
+        } else {
+            result.push(char);
+        }
+    }
+    result
+}
@@ -0,0 +1,325 @@
+use std::sync::Arc;
+
+use color_eyre::Result;
+use mongodb::bson::DateTime;
+use poise::serenity::{
+    builder::{CreateComponents, CreateEmbed},
+    http::Http,
+    model::{
+        id::{GuildId, UserId},
+        interactions::{
+            message_component::{
+                ActionRowComponent, ButtonStyle, InputTextStyle, MessageComponentInteraction,
+            },
+            modal::ModalSubmitInteraction,
+            Interaction, InteractionResponseType,
+        },
+    },
+};
+use tracing::info;
+
+use crate::{
+    i18n::Strings,
+    lawsuit::{Evidence, Lawsuit},
+    maintenance::Maintenance,
+    storage::Storage,
+    WrapErr,
+};
+
+/// The id of the button under the message that opens the lawsuit, followed by the id of the
+/// lawsuit. The buttons are handled in the event listener instead of a collector, so they keep
+/// working until the verdict, even after a restart.
+const SUBMIT_PREFIX: &str = "evidence-submit-";
+/// The id of the modal that the button opens, followed by the id of the lawsuit
+const MODAL_PREFIX: &str = "evidence-modal-";
+const TITLE_INPUT: &str = "title";
+const DESCRIPTION_INPUT: &str = "description";
+const LINK_INPUT: &str = "link";
+
+// embeds can't show more
+const MAX_DESCRIPTION_LEN: usize = 4096;
+const MAX_FIELD_LEN: usize = 1024;
+
+/// The "Beweis einreichen" button for the message that opens the lawsuit.
+pub fn submit_button<'a>(
+    components: &'a mut CreateComponents,
+    strings: &Strings,
+    lawsuit: &Lawsuit,
+) -> &'a mut CreateComponents {
+    components.create_action_row(|row| {
+        row.create_button(|button| {
+            button
+                .custom_id(format!("{SUBMIT_PREFIX}{}", lawsuit.id))
+                .label(strings.evidence_submit)
+                .style(ButtonStyle::Secondary)
+        })
+    })
+}
+
+/// Shows the evidence with its number in the lawsuit.
+pub fn embed<'a>(
+    embed: &'a mut CreateEmbed,
+    strings: &Strings,
+    number: usize,
+    evidence: &Evidence,
+) -> &'a mut CreateEmbed {
+    embed
+        .title((strings.evidence_title)(number))
+        .url(&evidence.link)
+        .field(
+            strings.field_author,
+            format!("<@{}>", evidence.author),
+            true,
+        )
+        .field(
+            strings.field_added_by,
+            format!("<@{}>", evidence.added_by),
+            true,
+        );
+    if !evidence.content.is_empty() {
+        embed.description(
+            evidence
+                .content
+                .chars()
+                .take(MAX_DESCRIPTION_LEN)
+                .collect::<String>(),
+        );
+    }
+    if !evidence.attachments.is_empty() {
+        embed.field(
+            strings.field_attachments,
+            evidence
+                .attachments
+                .join("\n")
+                .chars()
+                .take(MAX_FIELD_LEN)
+                .collect::<String>(),
+            false,
+        );
+    }
+    embed
+}
+
+/// Called for every interaction, only the evidence buttons and modals are handled here.
+pub async fn handle_interaction(
+    http: &Http,
+    storage: &Arc<dyn Storage>,
+    maintenance: &Maintenance,
+    interaction: &Interaction,
+) -> Result<()> {
+    let read_only = maintenance.is_enabled();
+    match interaction {
+        Interaction::MessageComponent(interaction) => {
+            if let Some(lawsuit_id) = interaction.data.custom_id.strip_prefix(SUBMIT_PREFIX) {
+                open_modal(http, storage, interaction, lawsuit_id, read_only).await?;
+            }
+        }
+        Interaction::ModalSubmit(interaction) => {
+            if let Some(lawsuit_id) = interaction.data.custom_id.strip_prefix(MODAL_PREFIX) {
+                submit(http, storage, interaction, lawsuit_id, read_only).await?;
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+/// The lawsuit if it's still open and the user takes part in it, otherwise why they can't submit
+/// evidence.
+async fn find_lawsuit(
+    storage: &Arc<dyn Storage>,
+    guild_id: Option<GuildId>,
+    user_id: UserId,
+    lawsuit_id: &str,
+    read_only: bool,
+) -> Result<(&'static Strings, Result<Lawsuit, &'static str>)> {
+    let guild_id = guild_id.wrap_err("guild_id not found")?;
+    let state = storage.find_or_insert_state(guild_id.into()).await?;
+    let strings = state.locale.strings();
+    if read_only {
+        return Ok((strings, Err(strings.under_maintenance)));
+    }
+
+    let lawsuit = storage
+        .find_open_lawsuits(guild_id.into())
+        .await?
+        .into_iter()
+        .find(|lawsuit| lawsuit.id.to_string() == lawsuit_id);
+    let result = match lawsuit {
+        None => Err(strings.no_active_lawsuit),
+        Some(lawsuit) if !lawsuit.takes_part(user_id.into()) => Err(strings.no_permission),
+        Some(lawsuit) => Ok(lawsuit),
+    };
+    Ok((strings, result))
+}
+
+async fn open_modal(
+    http: &Http,
+    storage: &Arc<dyn Storage>,
+    interaction: &MessageComponentInteraction,
+    lawsuit_id: &str,
+    read_only: bool,
+) -> Result<()> {
+    let (strings, lawsuit) = find_lawsuit(
+        storage,
+        interaction.guild_id,
+        interaction.user.id,
+        lawsuit_id,
+        read_only,
+    )
+    .await?;
+
+    match lawsuit {
+        Ok(lawsuit) => interaction
+            .create_interaction_response(http, |response| {
+                response
+                    .kind(InteractionResponseType::Modal)
+                    .interaction_response_data(|data| {
+                        data.custom_id(format!("{MODAL_PREFIX}{}", lawsuit.id))
+                            .title(strings.evidence_submit)
+                            .components(|c| modal_components(c, strings))
+                    })
+            })
+            .await
+            .wrap_err("open evidence modal"),
+        Err(content) => interaction
+            .create_interaction_response(http, |response| {
+                response
+                    .kind(InteractionResponseType::ChannelMessageWithSource)
+                    .interaction_response_data(|data| data.content(content).ephemeral(true))
+            })
+            .await
+            .wrap_err("reject evidence button"),
+    }
+}
+
+async fn submit(
+    http: &Http,
+    storage: &Arc<dyn Storage>,
+    interaction: &ModalSubmitInteraction,
+    lawsuit_id: &str,
+    read_only: bool,
+) -> Result<()> {
+    let (strings, lawsuit) = find_lawsuit(
+        storage,
+        interaction.guild_id,
+        interaction.user.id,
+        lawsuit_id,
+        read_only,
+    )
+    .await?;
+
+    let value = |custom_id: &str| {
+        interaction
+            .data
+            .components
+            .iter()
+            .flat_map(|row| &row.components)
+            .find_map(|component| match component {
+                ActionRowComponent::InputText(input) if input.custom_id == custom_id => {
+                    Some(input.value.trim().to_owned())
+                }
+                _ => None,
+            })
+            .filter(|value| !value.is_empty())
+    };
+    let link = value(LINK_INPUT);
+    let lawsuit = match lawsuit {
+        Ok(_) if link.as_deref().map_or(false, |link| !is_url(link)) => {
+            Err(strings.invalid_evidence_link)
+        }
+        lawsuit => lawsuit,
+    };
+    let lawsuit = match lawsuit {
+        Ok(lawsuit) => lawsuit,
+        Err(content) => {
+            return interaction
+                .create_interaction_response(http, |response| {
+                    response
+                        .kind(InteractionResponseType::ChannelMessageWithSource)
+                        .interaction_response_data(|data| data.content(content).ephemeral(true))
+                })
+                .await
+                .wrap_err("reject evidence");
+        }
+    };
+
+    let title = value(TITLE_INPUT).unwrap_or_default();
+    let content = match value(DESCRIPTION_INPUT) {
+        Some(description) => format!("**{title}**\n{description}"),
+        None => format!("**{title}**"),
+    };
+    // without a link, the evidence links to the court room
+    let link = link.unwrap_or_else(|| {
+        format!(
+            "https://discord.com/channels/{}/{}",
+            lawsuit.guild_id, lawsuit.court_room
+        )
+    });
+    let user_id = interaction.user.id.into();
+    let evidence = Evidence {
+        author: user_id,
+        content,
+        link,
+        attachments: Vec::new(),
+        added_by: user_id,
+        added_at: DateTime::now(),
+    };
+    storage
+        .add_evidence(lawsuit.guild_id, lawsuit.id, &evidence)
+        .await?;
+    info!(lawsuit_id = %lawsuit.id, "Submitted evidence");
+
+    let number = lawsuit.evidence.len() + 1;
+    interaction
+        .create_interaction_response(http, |response| {
+            response
+                .kind(InteractionResponseType::ChannelMessageWithSource)
+                .interaction_response_data(|data| {
+                    data.embed(|e| embed(e, strings, number, &evidence))
+                })
+        })
+        .await
+        .wrap_err("answer evidence")
+}
+
+fn modal_components<'a>(
+    components: &'a mut CreateComponents,
+    strings: &Strings,
+) -> &'a mut CreateComponents {
+    components
+        .create_action_row(|row| {
+            row.create_input_text(|input| {
+                input
+                    .custom_id(TITLE_INPUT)
+                    .label(strings.evidence_modal_title)
+                    .style(InputTextStyle::Short)
+                    .max_length(200)
+                    .required(true)
+            })
+        })
+        .create_action_row(|row| {
+            row.create_input_text(|input| {
+                input
+                    .custom_id(DESCRIPTION_INPUT)
+                    .label(strings.evidence_modal_description)
+                    .style(InputTextStyle::Paragraph)
+                    .max_length(3000)
+                    .required(true)
+            })
+        })
+        .create_action_row(|row| {
+            row.create_input_text(|input| {
+                input
+                    .custom_id(LINK_INPUT)
+                    .label(strings.evidence_modal_link)
+                    .placeholder("https://")
+                    .style(InputTextStyle::Short)
+                    .required(false)
+            })
+        })
+}
+
+fn is_url(link: &str) -> bool {
+    link.starts_with("https://") || link.starts_with("http://")
+}
@@ -0,0 +1,102 @@
+use std::sync::Arc;
+
+use color_eyre::Result;
+use poise::serenity::{http::Http, model::channel::Message};
+use tracing::info;
+
+use crate::{lawsuit::Lawsuit, model::SnowflakeId, storage::Storage, WrapErr};
+
+/// Called for every message the bot sees. Deletes the messages of members under a gag order that
+/// talk about their lawsuit outside the court room, and warns them where they wrote it. Without
+/// `message_content`, the content is always empty and only the mentions are checked.
+pub async fn enforce(
+    http: &Http,
+    storage: &Arc<dyn Storage>,
+    message: &Message,
+    message_content: bool,
+) -> Result<()> {
+    let guild_id = match message.guild_id {
+        Some(guild_id) => guild_id,
+        None => return Ok(()),
+    };
+    if message.author.bot {
+        return Ok(());
+    }
+
+    // don't create a state for every guild the bot sees a message in
+    let state = match storage.find_state(guild_id.into()).await? {
+        Some(state) => state,
+        None => return Ok(()),
+    };
+    let author = SnowflakeId::from(message.author.id);
+    let gagged_in = state
+        .gag_orders
+        .iter()
+        .filter(|order| order.user_id == author)
+        .map(|order| order.lawsuit_id)
+        .collect::<Vec<_>>();
+    if gagged_in.is_empty() {
+        return Ok(());
+    }
+
+    // the gag orders are lifted at the verdict, closed lawsuits don't count if that failed
+    let lawsuits = storage.find_open_lawsuits(guild_id.into()).await?;
+    let lawsuit = lawsuits
+        .iter()
+        .filter(|lawsuit| gagged_in.contains(&lawsuit.id))
+        .find(|lawsuit| is_about(lawsuit, message, message_content));
+    let lawsuit = match lawsuit {
+        Some(lawsuit) => lawsuit,
+        None => return Ok(()),
+    };
+
+    message
+        .delete(http)
+        .await
+        .wrap_err("delete message against gag order")?;
+    info!(lawsuit_id = %lawsuit.id, user_id = %author, "Deleted message against gag order");
+
+    let strings = state.locale.strings();
+    let case = lawsuit.case_number.as_deref().unwrap_or(&lawsuit.reason);
+    message
+        .channel_id
+        .say(
+            http,
+            (strings.gag_order_violated)(&format!("<@{author}>"), case),
+        )
+        .await
+        .wrap_err("warn about gag order")?;
+
+    Ok(())
+}
+
+/// Whether the message outside the court room mentions someone who takes part in the lawsuit or
+/// contains its case number.
+fn is_about(lawsuit: &Lawsuit, message: &Message, message_content: bool) -> bool {
+    if SnowflakeId::from(message.channel_id) == lawsuit.court_room {
+        return false;
+    }
+
+    let author = SnowflakeId::from(message.author.id);
+    let parties = lawsuit.parties();
+    let mentions_party = message
+        .mentions
+        .iter()
+        .map(|user| SnowflakeId::from(user.id))
+        .any(|user| user != author && parties.contains(&user));
+    let mentions_case = message_content
+        && lawsuit.case_number.as_deref().map_or(false, |case_number| {
+            mentions_case_number(&message.content, case_number)
+        });
+    mentions_party || mentions_case
+}
+
+/// Whether the content contains the case number on its own, so `2022-1` isn't found in `2022-12`.
+fn mentions_case_number(content: &str, case_number: &str) -> bool {
+    let is_part = |c: char| c.is_alphanumeric() || c == '-';
+    content.match_indices(case_number).any(|(start, _)| {
+        let before = content[..start].chars().next_back();
+        let after = content[start + case_number.len()..].chars().next();
+        !before.map_or(false, is_part) && !after.map_or(false, is_part)
+    })
+}
@@ -0,0 +1,107 @@
+use std::{collections::HashSet, env, sync::Arc};
+
+use color_eyre::Result;
+use poise::serenity::{
+    http::Http,
+    model::id::{ChannelId, GuildId},
+};
+use tracing::{info, warn};
+
+use crate::{model::SnowflakeId, storage::Storage, WrapErr};
+
+/// Which guilds may use the bot. `GUILD_ALLOWLIST` and `GUILD_BLOCKLIST` take comma separated
+/// guild ids, the owner adds more with `/guilds`, those are stored and win over the environment.
+/// With an allowlist, the bot only stays in the listed guilds, blocked guilds are always left.
+/// The dev guild is always approved.
+#[derive(Debug)]
+pub struct GuildAccess {
+    dev_guild_id: Option<SnowflakeId>,
+    allowlist_mode: bool,
+    allowed: HashSet<SnowflakeId>,
+    blocked: HashSet<SnowflakeId>,
+}
+
+impl GuildAccess {
+    pub fn from_env(dev_guild_id: Option<GuildId>) -> Result<Self> {
+        let allowlist = env::var("GUILD_ALLOWLIST").ok();
+        let allowed = parse_ids("GUILD_ALLOWLIST", allowlist.as_deref())?;
+        let blocked = parse_ids(
+            "GUILD_BLOCKLIST",
+            env::var("GUILD_BLOCKLIST").ok().as_deref(),
+        )?;
+
+        Ok(Self {
+            dev_guild_id: dev_guild_id.map(Into::into),
+            allowlist_mode: allowlist.is_some(),
+            allowed,
+            blocked,
+        })
+    }
+
+    pub fn allowlist_mode(&self) -> bool {
+        self.allowlist_mode
+    }
+
+    pub async fn is_approved(
+        &self,
+        storage: &Arc<dyn Storage>,
+        guild_id: SnowflakeId,
+    ) -> Result<bool> {
+        if self.dev_guild_id == Some(guild_id) {
+            return Ok(true);
+        }
+        let listing = storage
+            .find_guild_listings()
+            .await?
+            .into_iter()
+            .find(|listing| listing.guild_id == guild_id);
+        Ok(match listing {
+            Some(listing) => listing.allowed,
+            None if self.blocked.contains(&guild_id) => false,
+            None => !self.allowlist_mode || self.allowed.contains(&guild_id),
+        })
+    }
+}
+
+/// Called when the bot joins a guild and for every guild on startup. Leaves the guild if it's not
+/// approved, after explaining why in its system channel.
+pub async fn leave_if_unapproved(
+    http: &Http,
+    storage: &Arc<dyn Storage>,
+    access: &GuildAccess,
+    guild_id: GuildId,
+    system_channel: Option<ChannelId>,
+) -> Result<bool> {
+    if access.is_approved(storage, guild_id.into()).await? {
+        return Ok(false);
+    }
+
+    let locale = storage
+        .find_state(guild_id.into())
+        .await?
+        .map(|state| state.locale)
+        .unwrap_or_default();
+    if let Some(channel) = system_channel {
+        // the bot leaves either way
+        if let Err(err) = channel.say(http, locale.strings().guild_not_approved).await {
+            warn!(?err, %guild_id, "Could not explain leaving the guild");
+        }
+    }
+
+    guild_id.leave(http).await.wrap_err("leave guild")?;
+    info!(%guild_id, "Left unapproved guild");
+    Ok(true)
+}
+
+fn parse_ids(var: &str, value: Option<&str>) -> Result<HashSet<SnowflakeId>> {
+    value
+        .unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|id| !id.is_empty())
+        .map(|id| {
+            id.parse()
+                .wrap_err_with(|| format!("invalid guild id in {var}: {id}"))
+        })
+        .collect()
+}
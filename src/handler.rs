@@ -1,20 +1,69 @@
-use std::fmt::{Debug, Display, Formatter};
+use std::{
+    fmt::{Debug, Display, Formatter},
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
 use color_eyre::{eyre::ContextCompat, Result};
-use mongodb::bson::Uuid;
+use mongodb::bson::{DateTime, Uuid};
 use poise::{serenity::model::prelude::*, serenity_prelude as serenity, Event};
-use tracing::{debug, error, info};
+use tracing::{debug, error, info, warn};
 
 use crate::{
-    lawsuit::{Lawsuit, LawsuitCtx},
-    model::SnowflakeId,
-    Context, Mongo, Report, WrapErr,
+    api, archive,
+    audit::{self, AuditEvent},
+    backup::{self, ImportMode},
+    bar::{self, BarQuestion},
+    calendar, confirmation,
+    cooldown::{Cooldowns, OnCooldown},
+    duration, error_report, evidence, gag,
+    guild_access::{self, GuildAccess},
+    i18n::{self, Locale, Strings},
+    inactivity::{self, ActivityTracker},
+    jury,
+    lawsuit::{
+        impose_fine, Billing, ConflictPolicy, Contempt, Evidence, FeeKind, Fine, InvalidParties,
+        JudgeStats, Lawsuit, LawsuitCtx, LawyerFee, LawyerRecord, Leaderboard, Side,
+        SubpoenaResponse, VerdictDetails, Witness,
+    },
+    maintenance::{Maintenance, UnderMaintenance},
+    metrics,
+    model::{
+        GagOrder, GuildData, JobKind, PrisonEntry, PrisonTier, RoleTemplate, SnowflakeId, State,
+        DEFAULT_APPEAL_ROOM_NAME, TRASH_GRACE_PERIOD,
+    },
+    outage::{self, Offline, Outage},
+    pagination::paginate,
+    permissions::{is_admin, is_bailiff, is_judge, CourtRole},
+    presence::Presence,
+    report,
+    shutdown::BackgroundTasks,
+    storage::Storage,
+    subpoena,
+    templates::{self, Template},
+    transcript, webhook, wizard, Context, Report, WrapErr,
 };
 
 pub struct Handler {
     pub dev_guild_id: Option<GuildId>,
     pub set_global_commands: bool,
-    pub mongo: Mongo,
+    /// Destructive commands only report what they would change, for trying out the bot
+    pub dry_run: bool,
+    /// Whether the bot gets the content of messages, needed for the case numbers in gag orders
+    pub message_content: bool,
+    pub storage: Arc<dyn Storage>,
+    pub tasks: BackgroundTasks,
+    pub cooldowns: Cooldowns,
+    /// The channel of the owner where errors of all guilds are reported
+    pub error_channel: Option<ChannelId>,
+    pub presence: Arc<Presence>,
+    /// Whether the database is reachable
+    pub outage: Arc<Outage>,
+    pub maintenance: Arc<Maintenance>,
+    /// Which guilds may use the bot
+    pub guild_access: GuildAccess,
+    pub activity: ActivityTracker,
+    pub started_at: Instant,
 }
 
 impl Debug for Handler {
@@ -39,18 +88,21 @@ impl Handler {
     ) -> Result<()> {
         let guild_id = member.guild_id;
         let user_id = member.user.id;
-        let state = self.mongo.find_or_insert_state(guild_id.into()).await?;
+        let state = self.storage.find_or_insert_state(guild_id.into()).await?;
 
         debug!(member = ?member.user.id, "New member joined");
 
-        if let Some(role_id) = state.prison_role {
-            if self
-                .mongo
-                .find_prison_entry(guild_id.into(), user_id.into())
-                .await?
-                .is_some()
-            {
-                info!("New member was in prison, giving them the prison role");
+        let entry = self
+            .storage
+            .find_prison_entry(guild_id.into(), user_id.into())
+            .await?;
+
+        if let Some(entry) = entry {
+            if let Some(role_id) = crate::prison::entry_role(&state, &entry) {
+                info!(
+                    on_parole = %entry.parole_until.is_some(),
+                    "New member was in prison, giving them their role back"
+                );
 
                 member
                     .clone()
@@ -64,30 +116,155 @@ impl Handler {
     }
 }
 
+/// Posts the changed setting in the audit channel.
+async fn log_config_change(ctx: Context<'_>, setting: &str, value: String) -> Result<()> {
+    let guild_id = ctx.guild_id().wrap_err("guild_id not found")?;
+    let state = ctx
+        .data()
+        .storage
+        .find_or_insert_state(guild_id.into())
+        .await?;
+
+    audit::log(
+        &ctx.discord().http,
+        &state,
+        AuditEvent::ConfigChanged {
+            setting,
+            value,
+            changed_by: ctx.author().id,
+        },
+    )
+    .await;
+
+    Ok(())
+}
+
+/// Answers that the setting was changed, with the text of the guild if it set its own.
+async fn say_is_set(ctx: Context<'_>) -> Result<()> {
+    let guild_id = ctx.guild_id().wrap_err("guild_id not found")?;
+    let state = ctx
+        .data()
+        .storage
+        .find_or_insert_state(guild_id.into())
+        .await?;
+    ctx.say(templates::render(&state, Template::IsSet, &[]))
+        .await
+        .wrap_err("reply")?;
+    Ok(())
+}
+
+/// The count of a mass arrest or release, with the members whose roles couldn't be changed.
+fn batch_message(
+    strings: &Strings,
+    count_message: fn(usize) -> String,
+    batch: &crate::prison::Batch,
+) -> String {
+    let mut message = count_message(batch.count);
+    if !batch.failed.is_empty() {
+        let users = batch
+            .failed
+            .iter()
+            .map(|user_id| format!("<@{user_id}>"))
+            .collect::<Vec<_>>()
+            .join(" ");
+        message.push('\n');
+        message.push_str(&(strings.batch_failed)(&users));
+    }
+    message
+}
+
 pub mod lawsuit {
     use color_eyre::eyre::eyre;
+    use poise::Modal;
 
     use super::*;
 
+    #[derive(Debug, Modal)]
+    #[name = "Verklage"]
+    struct SueModal {
+        #[name = "Wieso?"]
+        #[paragraph]
+        reason: String,
+        #[name = "Gegen welles Gsetz isch verstosse worde?"]
+        #[placeholder = "z.B. Art. 3 Abs. 1"]
+        law: Option<String>,
+    }
+
+    #[derive(Debug, Modal)]
+    #[name = "Urteil"]
+    struct VerdictModal {
+        #[name = "Wie isch entschiede worde?"]
+        #[placeholder = "z.B. schuldig"]
+        outcome: String,
+        #[name = "Wieso?"]
+        #[paragraph]
+        reasoning: Option<String>,
+        #[name = "Strafe"]
+        #[placeholder = "z.B. 3 Täg Gfängnis"]
+        sentence: Option<String>,
+        #[name = "Buess"]
+        #[placeholder = "De Betrag, leer für kei Buess"]
+        fine: Option<String>,
+    }
+
+    /// Gerichtsprozesse verwalten
     #[poise::command(
         slash_command,
         guild_only,
-        subcommands("create", "set_category", "close", "clear")
+        subcommands(
+            "create",
+            "file",
+            "close",
+            "appeal",
+            "jury",
+            "witness",
+            "hearing",
+            "public",
+            "contempt",
+            "eject",
+            "gag",
+            "fine",
+            "transcript",
+            "summary",
+            "list",
+            "clear",
+            "restore",
+            "ban",
+            "unban",
+            "sync_permissions"
+        )
     )]
     pub async fn lawsuit(_: Context<'_>) -> Result<()> {
         unreachable!()
     }
 
+    /// Die Person verklagen, der Richter ist der für Berufungen
+    #[poise::command(context_menu_command = "Verklagen", guild_only)]
+    pub async fn sue(ctx: Context<'_>, user: User) -> Result<()> {
+        lawsuit_sue_impl(ctx, user).await.wrap_err("lawsuit_sue")
+    }
+
+    /// Die Nachricht als Beweis zum laufenden Prozess in diesem Kanal hinzufügen
+    #[poise::command(context_menu_command = "Als Beweis markieren", guild_only)]
+    pub async fn mark_evidence(ctx: Context<'_>, message: Message) -> Result<()> {
+        lawsuit_mark_evidence_impl(ctx, message)
+            .await
+            .wrap_err("lawsuit_mark_evidence")
+    }
+
     /// Einen neuen Gerichtsprozess erstellen
-    #[poise::command(slash_command, guild_only, required_permissions = "MANAGE_GUILD")]
+    #[poise::command(slash_command, guild_only, check = "is_judge")]
+    #[allow(clippy::too_many_arguments)]
     async fn create(
         ctx: Context<'_>,
         #[description = "Der Kläger"] plaintiff: User,
         #[description = "Der Angeklagte"] accused: User,
-        #[description = "Der Richter"] judge: User,
         #[description = "Der Grund für die Klage"] reason: String,
+        #[description = "Der Richter, sonst der nächste registrierte Richter"] judge: Option<User>,
         #[description = "Der Anwalt des Klägers"] plaintiff_lawyer: Option<User>,
         #[description = "Der Anwalt des Angeklagten"] accused_lawyer: Option<User>,
+        #[description = "Auch erstellen, wenn schon ein Prozess zwischen den beiden läuft (nur Admins)"]
+        allow_duplicate: Option<bool>,
     ) -> Result<()> {
         lawsuit_create_impl(
             ctx,
@@ -97,341 +274,6915 @@ pub mod lawsuit {
             reason,
             plaintiff_lawyer,
             accused_lawyer,
+            allow_duplicate.unwrap_or(false),
         )
         .await
         .wrap_err("lawsuit_create")
     }
 
-    /// Die Rolle für Gefangene setzen
-    #[poise::command(slash_command, guild_only, required_permissions = "MANAGE_GUILD")]
-    async fn set_category(
-        ctx: Context<'_>,
-        #[description = "Die Kategorie"] category: Channel,
-    ) -> Result<()> {
-        lawsuit_set_category_impl(ctx, category)
-            .await
-            .wrap_err("lawsuit_set_category")
+    /// Eine Klage Schritt für Schritt einreichen
+    #[poise::command(slash_command, guild_only)]
+    async fn file(ctx: Context<'_>) -> Result<()> {
+        lawsuit_file_impl(ctx).await.wrap_err("lawsuit_file")
     }
 
     /// Den Gerichtsprozess abschliessen und ein Urteil fällen
-    #[poise::command(slash_command, guild_only, required_permissions = "MANAGE_GUILD")]
-    async fn close(ctx: Context<'_>, #[description = "Das Urteil"] verdict: String) -> Result<()> {
-        lawsuit_close_impl(ctx, verdict)
+    #[poise::command(slash_command, guild_only, check = "is_judge")]
+    async fn close(
+        ctx: Context<'_>,
+        #[description = "Der Prozess, sonst der in diesem Kanal"]
+        #[autocomplete = "autocomplete_open_lawsuit"]
+        case: Option<String>,
+        #[description = "Bei einer Berufung: ob das ursprüngliche Urteil aufgehoben wird"]
+        overturn: Option<bool>,
+        #[description = "Das Urteil nicht in den öffentlichen Akten veröffentlichen"]
+        sealed: Option<bool>,
+        #[description = "Frühere Fälle, auf die sich das Urteil stützt, z.B. 2023-04"]
+        precedent: Option<String>,
+    ) -> Result<()> {
+        lawsuit_close_impl(ctx, case, overturn, sealed, precedent)
             .await
             .wrap_err("lawsuit_close")
     }
 
-    /// Alle Rechtsprozessdaten löschen
-    #[poise::command(slash_command, guild_only, required_permissions = "MANAGE_GUILD")]
-    async fn clear(ctx: Context<'_>) -> Result<()> {
-        lawsuit_clear_impl(ctx).await.wrap_err("lawsuit_clear")
+    /// Gegen das Urteil eines abgeschlossenen Prozesses Berufung einlegen
+    #[poise::command(slash_command, guild_only)]
+    async fn appeal(
+        ctx: Context<'_>,
+        #[description = "Der abgeschlossene Prozess"]
+        #[autocomplete = "autocomplete_closed_lawsuit"]
+        case: String,
+        #[description = "Wieso das Urteil falsch ist"] reason: String,
+    ) -> Result<()> {
+        lawsuit_appeal_impl(ctx, case, reason)
+            .await
+            .wrap_err("lawsuit_appeal")
     }
 
-    #[tracing::instrument(skip(ctx))]
-    async fn lawsuit_create_impl(
+    /// Die Geschworenen geheim über den Prozess in diesem Kanal abstimmen lassen
+    #[poise::command(slash_command, guild_only, check = "is_judge")]
+    async fn jury(
         ctx: Context<'_>,
-        plaintiff: User,
-        accused: User,
-        judge: User,
-        reason: String,
-        plaintiff_lawyer: Option<User>,
-        accused_lawyer: Option<User>,
+        #[description = "Die Geschworenen, z.B. @person1 @person2, sonst aus dem Pool ausgelost"]
+        jurors: Option<String>,
+        #[description = "Wie viele Geschworene aus dem Pool ausgelost werden (Standard: 5)"]
+        count: Option<u32>,
+        #[description = "Wie lange abgestimmt werden kann, z.B. 30m oder 1d (Standard: 1h)"]
+        deadline: Option<String>,
     ) -> Result<()> {
-        let guild_id = ctx.guild_id().wrap_err("guild_id not found")?;
-
-        let lawsuit = Lawsuit {
-            id: Uuid::new(),
-            plaintiff: plaintiff.id.into(),
-            accused: accused.id.into(),
-            judge: judge.id.into(),
-            plaintiff_lawyer: plaintiff_lawyer.map(|user| user.id.into()),
-            accused_lawyer: accused_lawyer.map(|user| user.id.into()),
-            reason: reason.to_owned(),
-            verdict: None,
-            court_room: SnowflakeId(0),
-        };
-
-        let lawsuit_ctx = LawsuitCtx {
-            lawsuit,
-            mongo_client: ctx.data().mongo.clone(),
-            http: ctx.discord().http.clone(),
-            guild_id,
-        };
-
-        let response = lawsuit_ctx
-            .initialize()
+        lawsuit_jury_impl(ctx, jurors, count, deadline)
             .await
-            .wrap_err("initialize lawsuit")?;
-
-        ctx.say(response.to_string()).await?;
-
-        Ok(())
+            .wrap_err("lawsuit_jury")
     }
 
-    #[tracing::instrument(skip(ctx))]
-    async fn lawsuit_set_category_impl(ctx: Context<'_>, category: Channel) -> Result<()> {
-        let guild_id = ctx.guild_id().wrap_err("guild_id not found")?;
+    /// Eine Person als Zeuge zum Prozess in diesem Kanal vorladen
+    #[poise::command(slash_command, guild_only)]
+    async fn witness(
+        ctx: Context<'_>,
+        #[description = "Der Zeuge"] user: User,
+        #[description = "Bis wann der Zeuge antworten muss, z.B. 12h (Standard: 1d)"]
+        deadline: Option<String>,
+    ) -> Result<()> {
+        lawsuit_witness_impl(ctx, user, deadline)
+            .await
+            .wrap_err("lawsuit_witness")
+    }
 
-        match category.category() {
-            Some(category) => {
-                let id = category.id;
-                ctx.data()
-                    .mongo
-                    .set_court_category(guild_id.into(), id.into())
-                    .await?;
-                ctx.say("isch gsetzt").await?;
-            }
-            None => {
-                ctx.say("Das ist keine Kategorie!").await?;
-            }
-        }
+    /// Den Termin der Verhandlung des Prozesses in diesem Kanal setzen
+    #[poise::command(slash_command, guild_only, check = "is_judge")]
+    async fn hearing(
+        ctx: Context<'_>,
+        #[description = "Wann, z.B. 2d oder 2023-04-01 18:00 in UTC, sonst wird der Termin entfernt"]
+        when: Option<String>,
+    ) -> Result<()> {
+        lawsuit_hearing_impl(ctx, when)
+            .await
+            .wrap_err("lawsuit_hearing")
+    }
 
-        Ok(())
+    /// Den Prozess in diesem Kanal für die Öffentlichkeit öffnen oder schliessen
+    #[poise::command(slash_command, guild_only, check = "is_judge")]
+    async fn public(
+        ctx: Context<'_>,
+        #[description = "Ob alle den Gerichtssaal sehen können oder nur die Beteiligten"]
+        public: bool,
+    ) -> Result<()> {
+        lawsuit_public_impl(ctx, public)
+            .await
+            .wrap_err("lawsuit_public")
     }
 
-    #[tracing::instrument(skip(ctx))]
-    async fn lawsuit_close_impl(ctx: Context<'_>, verdict: String) -> Result<()> {
-        let guild_id = ctx.guild_id().wrap_err("guild_id not found")?;
+    /// Eine Person wegen Missachtung des Gerichts stummschalten
+    #[poise::command(slash_command, guild_only, check = "is_judge")]
+    async fn contempt(
+        ctx: Context<'_>,
+        #[description = "Die Person"] user: User,
+        #[description = "Wie lange, z.B. 10m oder 1h"] duration: String,
+    ) -> Result<()> {
+        lawsuit_contempt_impl(ctx, user, duration)
+            .await
+            .wrap_err("lawsuit_contempt")
+    }
 
-        let application_context = match ctx {
-            Context::Application(ctx) => ctx,
-            Context::Prefix(_) => return Err(eyre!("wrong context, cannot happen!")),
-        };
+    /// Eine Person, die nicht am Prozess beteiligt ist, aus dem Gerichtssaal werfen
+    #[poise::command(slash_command, guild_only, check = "is_bailiff")]
+    async fn eject(ctx: Context<'_>, #[description = "Die Person"] user: User) -> Result<()> {
+        lawsuit_eject_impl(ctx, user)
+            .await
+            .wrap_err("lawsuit_eject")
+    }
 
-        let member = application_context
-            .interaction
-            .member()
-            .wrap_err("member not found")?;
+    /// Einer Person verbieten, bis zum Urteil ausserhalb des Gerichtssaals über den Prozess zu schreiben
+    #[poise::command(slash_command, guild_only, check = "is_judge")]
+    async fn gag(ctx: Context<'_>, #[description = "Die Person"] user: User) -> Result<()> {
+        lawsuit_gag_impl(ctx, user).await.wrap_err("lawsuit_gag")
+    }
 
-        let permission_override = member
-            .permissions
-            .map(|p| p.contains(Permissions::MANAGE_GUILD))
-            .unwrap_or(false);
+    /// Die Protokolle der Prozesse
+    #[poise::command(slash_command, guild_only, subcommands("transcript_export"))]
+    async fn transcript(_: Context<'_>) -> Result<()> {
+        unreachable!()
+    }
 
-        let room_id = ctx.channel_id();
-        let mongo_client = &ctx.data().mongo;
+    /// Den Verlauf eines Prozesses als HTML-Datei herunterladen
+    #[poise::command(slash_command, guild_only, rename = "export")]
+    async fn transcript_export(
+        ctx: Context<'_>,
+        #[description = "Die Fallnummer, sonst der laufende Prozess in diesem Kanal"]
+        #[autocomplete = "records::autocomplete_case_number"]
+        case: Option<String>,
+    ) -> Result<()> {
+        lawsuit_transcript_export_impl(ctx, case)
+            .await
+            .wrap_err("lawsuit_transcript_export")
+    }
 
-        let state = mongo_client
-            .find_or_insert_state(guild_id.into())
+    /// Eine Zusammenfassung eines Prozesses mit Grund, Beweismitteln und Urteil erstellen
+    #[poise::command(slash_command, guild_only)]
+    async fn summary(
+        ctx: Context<'_>,
+        #[description = "Die Fallnummer, sonst der laufende Prozess in diesem Kanal"]
+        #[autocomplete = "records::autocomplete_case_number"]
+        case: Option<String>,
+    ) -> Result<()> {
+        lawsuit_summary_impl(ctx, case)
             .await
-            .wrap_err("find guild for verdict")?;
+            .wrap_err("lawsuit_summary")
+    }
 
-        let lawsuit = state
-            .lawsuits
-            .iter()
-            .find(|l| l.court_room == room_id.into() && l.verdict.is_none());
+    /// Dem Angeklagten des Prozesses in diesem Kanal eine Busse auferlegen
+    #[poise::command(slash_command, guild_only, check = "is_judge")]
+    async fn fine(
+        ctx: Context<'_>,
+        #[description = "Der Betrag, wird sofort vom Konto abgezogen"] amount: i64,
+    ) -> Result<()> {
+        lawsuit_fine_impl(ctx, amount)
+            .await
+            .wrap_err("lawsuit_fine")
+    }
 
-        let lawsuit = match lawsuit {
-            Some(lawsuit) => lawsuit.clone(),
-            None => {
-                ctx.say("i dem channel lauft kein aktive prozess!").await?;
-                return Ok(());
-            }
-        };
+    /// Suggests the open lawsuits by their reason, the value is the id of the lawsuit.
+    async fn autocomplete_open_lawsuit(
+        ctx: Context<'_>,
+        partial: String,
+    ) -> impl Iterator<Item = poise::AutocompleteChoice<String>> {
+        // discord doesn't show more than 25 suggestions
+        const MAX_SUGGESTIONS: usize = 25;
+        // and the names can't be longer than 100 characters
+        const MAX_NAME_LEN: usize = 100;
 
-        let room = state
-            .court_rooms
-            .iter()
-            .find(|r| r.channel_id == room_id.into());
-        let room = match room {
-            Some(room) => room.clone(),
-            None => {
-                ctx.say("i dem channel lauft kein aktive prozess!").await?;
-                return Ok(());
-            }
+        let lawsuits = match ctx.guild_id() {
+            Some(guild_id) => match ctx
+                .data()
+                .storage
+                .find_open_lawsuits_by_reason(guild_id.into(), &partial, MAX_SUGGESTIONS)
+                .await
+            {
+                Ok(lawsuits) => lawsuits,
+                Err(err) => {
+                    error!(?err, "Error fetching open lawsuits for autocomplete");
+                    vec![]
+                }
+            },
+            None => vec![],
         };
 
-        let mut lawsuit_ctx = LawsuitCtx {
-            lawsuit,
-            mongo_client: mongo_client.clone(),
-            http: ctx.discord().http.clone(),
-            guild_id,
+        let cache = ctx.discord().cache.clone();
+        let name_of = move |user_id: SnowflakeId| {
+            cache
+                .user(UserId::from(user_id))
+                .map_or_else(|| user_id.to_string(), |user| user.name)
         };
 
-        let response = lawsuit_ctx
-            .rule_verdict(
-                permission_override,
-                member.user.id,
-                verdict.to_string(),
-                room,
-            )
-            .await?;
+        lawsuits.into_iter().map(move |lawsuit| {
+            let name = format!(
+                "{}: {} vs {}",
+                lawsuit.reason,
+                name_of(lawsuit.plaintiff),
+                name_of(lawsuit.accused)
+            );
+            poise::AutocompleteChoice {
+                name: name.chars().take(MAX_NAME_LEN).collect(),
+                value: lawsuit.id.to_string(),
+            }
+        })
+    }
 
-        if let Err(response) = response {
-            ctx.say(response.to_string()).await?;
-            return Ok(());
-        }
+    /// Suggests the closed lawsuits of the author by their reason, the value is the id of the
+    /// lawsuit.
+    async fn autocomplete_closed_lawsuit(
+        ctx: Context<'_>,
+        partial: String,
+    ) -> impl Iterator<Item = poise::AutocompleteChoice<String>> {
+        // discord doesn't show more than 25 suggestions
+        const MAX_SUGGESTIONS: usize = 25;
+        // and the names can't be longer than 100 characters
+        const MAX_NAME_LEN: usize = 100;
 
-        ctx.say("ich han en dir abschlosse").await?;
+        let lawsuits = match ctx.guild_id() {
+            Some(guild_id) => match ctx.data().storage.find_lawsuits(guild_id.into()).await {
+                Ok(lawsuits) => lawsuits,
+                Err(err) => {
+                    error!(?err, "Error fetching lawsuits for autocomplete");
+                    vec![]
+                }
+            },
+            None => vec![],
+        };
 
-        Ok(())
+        let author = SnowflakeId::from(ctx.author().id);
+        let partial = partial.to_lowercase();
+        lawsuits
+            .into_iter()
+            .filter(move |lawsuit| {
+                lawsuit.verdict.is_some()
+                    && (lawsuit.plaintiff == author || lawsuit.accused == author)
+                    && lawsuit.reason.to_lowercase().contains(&partial)
+            })
+            .take(MAX_SUGGESTIONS)
+            .map(|lawsuit| poise::AutocompleteChoice {
+                name: lawsuit.reason.chars().take(MAX_NAME_LEN).collect(),
+                value: lawsuit.id.to_string(),
+            })
     }
 
-    #[tracing::instrument(skip(ctx))]
-    async fn lawsuit_clear_impl(ctx: Context<'_>) -> Result<()> {
-        let guild_id = ctx.guild_id().wrap_err("guild_id not found")?;
-
-        ctx.data().mongo.delete_guild(guild_id.into()).await?;
-        ctx.say("alles weg").await?;
-        Ok(())
+    /// Alle Gerichtsprozesse auflisten
+    #[poise::command(slash_command, guild_only)]
+    async fn list(ctx: Context<'_>) -> Result<()> {
+        lawsuit_list_impl(ctx).await.wrap_err("lawsuit_list")
     }
-}
 
-pub mod prison {
-    use super::*;
+    /// Daten des Servers löschen, jeweils nach einer Bestätigung
     #[poise::command(
         slash_command,
         guild_only,
-        subcommands("set_role", "arrest", "release")
+        subcommands("clear_lawsuits", "clear_rooms", "clear_prison", "clear_all")
     )]
-    pub async fn prison(_: Context<'_>) -> Result<()> {
+    async fn clear(_: Context<'_>) -> Result<()> {
         unreachable!()
     }
 
-    /// Die Rolle für Gefangene setzen
-    #[poise::command(slash_command, guild_only, required_permissions = "MANAGE_GUILD")]
-    async fn set_role(ctx: Context<'_>, #[description = "Die Rolle"] role: Role) -> Result<()> {
-        prison_set_role_impl(ctx, role)
+    /// Alle Prozesse löschen, die Gerichtsräume werden dadurch frei
+    #[poise::command(slash_command, guild_only, check = "is_admin", rename = "lawsuits")]
+    async fn clear_lawsuits(
+        ctx: Context<'_>,
+        #[description = "Nur anzeigen, was gelöscht würde, ohne etwas zu ändern (Standard: nein)"]
+        dry_run: Option<bool>,
+    ) -> Result<()> {
+        lawsuit_clear_lawsuits_impl(ctx, dry_run.unwrap_or(false) || ctx.data().dry_run)
             .await
-            .wrap_err("prison_set_role")
+            .wrap_err("lawsuit_clear_lawsuits")
     }
 
-    /// Jemanden einsperren
-    #[poise::command(slash_command, guild_only, required_permissions = "MANAGE_GUILD")]
-    async fn arrest(
+    /// Alle Gerichtsräume entfernen, die Kanäle bleiben bestehen
+    #[poise::command(slash_command, guild_only, check = "is_admin", rename = "rooms")]
+    async fn clear_rooms(
         ctx: Context<'_>,
-        #[description = "Die Person zum einsperren"] user: User,
+        #[description = "Nur anzeigen, was gelöscht würde, ohne etwas zu ändern (Standard: nein)"]
+        dry_run: Option<bool>,
     ) -> Result<()> {
-        prison_arrest_impl(ctx, user)
+        lawsuit_clear_rooms_impl(ctx, dry_run.unwrap_or(false) || ctx.data().dry_run)
             .await
-            .wrap_err("prison_arrest")
+            .wrap_err("lawsuit_clear_rooms")
     }
 
-    /// Einen Gefangenen freilassen
-    #[poise::command(slash_command, guild_only, required_permissions = "MANAGE_GUILD")]
-    async fn release(
+    /// Alle Gefangenen freilassen
+    #[poise::command(slash_command, guild_only, check = "is_admin", rename = "prison")]
+    async fn clear_prison(
         ctx: Context<'_>,
-        #[description = "Die Person zum freilassen"] user: User,
+        #[description = "Nur anzeigen, was gelöscht würde, ohne etwas zu ändern (Standard: nein)"]
+        dry_run: Option<bool>,
     ) -> Result<()> {
-        prison_release_impl(ctx, user)
+        lawsuit_clear_prison_impl(ctx, dry_run.unwrap_or(false) || ctx.data().dry_run)
             .await
-            .wrap_err("prison_release")
+            .wrap_err("lawsuit_clear_prison")
     }
 
-    #[tracing::instrument(skip(ctx))]
-    async fn prison_set_role_impl(ctx: Context<'_>, role: Role) -> Result<()> {
-        ctx.data()
-            .mongo
-            .set_prison_role(
-                ctx.guild_id().wrap_err("guild_id not found")?.into(),
-                role.id.into(),
-            )
-            .await?;
+    /// Alles löschen, auch die Einstellungen, kann eine Woche lang rückgängig gemacht werden
+    #[poise::command(slash_command, guild_only, check = "is_admin", rename = "all")]
+    async fn clear_all(
+        ctx: Context<'_>,
+        #[description = "Nur anzeigen, was gelöscht würde, ohne etwas zu ändern (Standard: nein)"]
+        dry_run: Option<bool>,
+    ) -> Result<()> {
+        lawsuit_clear_all_impl(ctx, dry_run.unwrap_or(false) || ctx.data().dry_run)
+            .await
+            .wrap_err("lawsuit_clear_all")
+    }
 
-        ctx.say("isch gsetzt").await.wrap_err("reply")?;
+    /// Die mit /lawsuit clear all gelöschten Daten wiederherstellen
+    #[poise::command(slash_command, guild_only, check = "is_admin")]
+    async fn restore(ctx: Context<'_>) -> Result<()> {
+        lawsuit_restore_impl(ctx).await.wrap_err("lawsuit_restore")
+    }
 
-        Ok(())
+    /// Einer Person verbieten, Klagen einzureichen
+    #[poise::command(slash_command, guild_only, check = "is_admin")]
+    async fn ban(ctx: Context<'_>, #[description = "Die Person"] user: User) -> Result<()> {
+        lawsuit_ban_impl(ctx, user, true)
+            .await
+            .wrap_err("lawsuit_ban")
     }
 
-    #[tracing::instrument(skip(ctx))]
-    async fn prison_arrest_impl(ctx: Context<'_>, user: User) -> Result<()> {
-        let mongo_client = &ctx.data().mongo;
-        let guild_id = ctx.guild_id().wrap_err("guild_id not found")?;
-        let http = &ctx.discord().http;
+    /// Einer Person wieder erlauben, Klagen einzureichen
+    #[poise::command(slash_command, guild_only, check = "is_admin")]
+    async fn unban(ctx: Context<'_>, #[description = "Die Person"] user: User) -> Result<()> {
+        lawsuit_ban_impl(ctx, user, false)
+            .await
+            .wrap_err("lawsuit_unban")
+    }
+
+    /// Die Berechtigungen der Gerichtssäle und des Gefängnisses wiederherstellen
+    #[poise::command(slash_command, guild_only, check = "is_admin")]
+    async fn sync_permissions(ctx: Context<'_>) -> Result<()> {
+        lawsuit_sync_permissions_impl(ctx)
+            .await
+            .wrap_err("lawsuit_sync_permissions")
+    }
 
-        let state = mongo_client.find_or_insert_state(guild_id.into()).await?;
-        let role = state.prison_role;
+    /// Answers with the open lawsuit of the plaintiff against the accused if there is one, a
+    /// second lawsuit would only take another court room. Returns whether there was one.
+    async fn reject_duplicate(
+        ctx: Context<'_>,
+        strings: &Strings,
+        plaintiff: SnowflakeId,
+        accused: SnowflakeId,
+    ) -> Result<bool> {
+        let guild_id = ctx.guild_id().wrap_err("guild_id not found")?;
+        let duplicate = ctx
+            .data()
+            .storage
+            .find_open_lawsuits(guild_id.into())
+            .await?
+            .into_iter()
+            .find(|lawsuit| lawsuit.plaintiff == plaintiff && lawsuit.accused == accused);
 
-        let role = match role {
-            Some(role) => role,
-            None => {
-                ctx.say("du mosch zerst e rolle setze mit /prison set_role")
-                    .await?;
-                return Ok(());
+        match duplicate {
+            Some(duplicate) => {
+                let channel = format!("<#{}>", duplicate.court_room);
+                ctx.send(|reply| {
+                    reply
+                        .ephemeral(true)
+                        .content((strings.lawsuit_duplicate)(&channel))
+                })
+                .await?;
+                Ok(true)
             }
-        };
+            None => Ok(false),
+        }
+    }
 
-        mongo_client
-            .add_to_prison(guild_id.into(), user.id.into())
+    /// Answers with a refusal if the plaintiff was banned with `/lawsuit ban`. Returns whether they
+    /// were.
+    async fn reject_banned(
+        ctx: Context<'_>,
+        strings: &Strings,
+        plaintiff: SnowflakeId,
+    ) -> Result<bool> {
+        let guild_id = ctx.guild_id().wrap_err("guild_id not found")?;
+        let state = ctx
+            .data()
+            .storage
+            .find_or_insert_state(guild_id.into())
             .await?;
+        if !state.banned_litigants.contains(&plaintiff) {
+            return Ok(false);
+        }
 
-        guild_id
-            .member(http, user.id)
-            .await
-            .wrap_err("fetching guild member")?
-            .add_role(http, role)
-            .await
-            .wrap_err("add guild member role")?;
-
-        ctx.say("isch igsperrt").await?;
-
-        Ok(())
+        ctx.send(|reply| {
+            reply
+                .ephemeral(true)
+                .content((strings.litigant_banned_refusal)(&format!(
+                    "<@{plaintiff}>"
+                )))
+        })
+        .await?;
+        Ok(true)
     }
 
-    #[tracing::instrument(skip(ctx))]
-    async fn prison_release_impl(ctx: Context<'_>, user: User) -> Result<()> {
-        let mongo_client = &ctx.data().mongo;
+    /// Answers with a refusal if the plaintiff already has as many open lawsuits as the guild
+    /// allows, so that a single member can't occupy every court room. Returns whether they do.
+    async fn reject_over_limit(
+        ctx: Context<'_>,
+        strings: &Strings,
+        plaintiff: SnowflakeId,
+    ) -> Result<bool> {
         let guild_id = ctx.guild_id().wrap_err("guild_id not found")?;
-        let http = &ctx.discord().http;
+        let storage = &ctx.data().storage;
 
-        let state = mongo_client.find_or_insert_state(guild_id.into()).await?;
-        let role = state.prison_role;
-
-        let role = match role {
-            Some(role) => role,
-            None => {
-                ctx.say("du mosch zerst e rolle setze mit /prison set_role")
-                    .await?;
-                return Ok(());
-            }
+        let max = match storage
+            .find_or_insert_state(guild_id.into())
+            .await?
+            .max_open_lawsuits
+        {
+            Some(max) => max as usize,
+            None => return Ok(false),
         };
+        let open = storage
+            .find_open_lawsuits(guild_id.into())
+            .await?
+            .iter()
+            .filter(|lawsuit| lawsuit.plaintiff == plaintiff)
+            .count();
+        if open < max {
+            return Ok(false);
+        }
 
-        mongo_client
-            .remove_from_prison(guild_id.into(), user.id.into())
-            .await?;
-
-        guild_id
-            .member(http, user.id)
-            .await
-            .wrap_err("fetching guild member")?
-            .remove_role(http, role)
-            .await
-            .wrap_err("remove guild member role")?;
+        ctx.send(|reply| {
+            reply
+                .ephemeral(true)
+                .content((strings.lawsuit_limit_reached)(
+                    &format!("<@{plaintiff}>"),
+                    max,
+                ))
+        })
+        .await?;
+        Ok(true)
+    }
 
-        ctx.say("d'freiheit wartet").await?;
+    /// Answers with a refusal if the author filed a lawsuit too recently. It's stored, unlike the
+    /// command cooldowns, so that restarts don't reset it. Admins can always file. Returns whether
+    /// the author has to wait.
+    async fn reject_filing_cooldown(ctx: Context<'_>, strings: &Strings) -> Result<bool> {
+        let guild_id = ctx.guild_id().wrap_err("guild_id not found")?;
+        let storage = &ctx.data().storage;
+        let author_id = SnowflakeId::from(ctx.author().id);
 
-        Ok(())
-    }
-}
+        let state = storage.find_or_insert_state(guild_id.into()).await?;
+        let cooldown = match state.filing_cooldown {
+            Some(cooldown) => cooldown as i64 * 1000,
+            None => return Ok(false),
+        };
+        if is_admin(ctx).await? {
+            return Ok(false);
+        }
 
-pub async fn listener(
-    ctx: &serenity::Context,
-    event: &Event<'_>,
-    _: poise::FrameworkContext<'_, Handler, Report>,
-    data: &Handler,
-) -> Result<()> {
-    #[allow(clippy::single_match)]
-    match event {
-        Event::GuildMemberAddition { new_member } => {
-            if let Err(err) = data.handle_guild_member_join(ctx, new_member).await {
-                error!(?err, "An error occurred in guild_member_addition handler");
+        let now = DateTime::now();
+        let member = storage.find_member(guild_id.into(), author_id).await?;
+        if let Some(last_filed) = member.last_filed {
+            let until = DateTime::from_millis(last_filed.timestamp_millis() + cooldown);
+            if until > now {
+                ctx.send(|reply| {
+                    reply.ephemeral(true).content((strings.filing_cooldown)(
+                        &duration::relative_timestamp(until),
+                    ))
+                })
+                .await?;
+                return Ok(true);
             }
         }
-        _ => {}
+
+        Ok(false)
     }
-    Ok(())
-}
 
-pub async fn error_handler(error: poise::FrameworkError<'_, Handler, Report>) {
-    match error {
-        poise::FrameworkError::MissingUserPermissions { ctx, .. } => {
-            let _ = ctx.say("du häsch kei recht für da!").await;
+    /// Starts the filing cooldown of the author once their lawsuit is open, lawsuits that were
+    /// refused on the way don't count.
+    async fn start_filing_cooldown(ctx: Context<'_>) -> Result<()> {
+        let guild_id = ctx.guild_id().wrap_err("guild_id not found")?;
+        let storage = &ctx.data().storage;
+
+        let state = storage.find_or_insert_state(guild_id.into()).await?;
+        if state.filing_cooldown.is_some() {
+            storage
+                .set_last_filed(guild_id.into(), ctx.author().id.into(), DateTime::now())
+                .await?;
         }
-        poise::FrameworkError::GuildOnly { ctx } => {
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(ctx))]
+    #[allow(clippy::too_many_arguments)]
+    async fn lawsuit_create_impl(
+        ctx: Context<'_>,
+        plaintiff: User,
+        accused: User,
+        judge: Option<User>,
+        reason: String,
+        plaintiff_lawyer: Option<User>,
+        accused_lawyer: Option<User>,
+        allow_duplicate: bool,
+    ) -> Result<()> {
+        let guild_id = ctx.guild_id().wrap_err("guild_id not found")?;
+
+        let state = ctx
+            .data()
+            .storage
+            .find_or_insert_state(guild_id.into())
+            .await?;
+        let strings = state.locale.strings();
+
+        let bots = [Some(&plaintiff), Some(&accused), judge.as_ref()]
+            .into_iter()
+            .chain([plaintiff_lawyer.as_ref(), accused_lawyer.as_ref()])
+            .flatten()
+            .filter(|user| user.bot)
+            .map(|user| SnowflakeId::from(user.id))
+            .collect::<Vec<_>>();
+        let invalid = InvalidParties::check(
+            plaintiff.id.into(),
+            accused.id.into(),
+            judge.as_ref().map(|judge| judge.id.into()),
+            &bots,
+        )
+        .or_else(|| {
+            InvalidParties::check_lawyers(
+                plaintiff.id.into(),
+                accused.id.into(),
+                plaintiff_lawyer.as_ref().map(|lawyer| lawyer.id.into()),
+                accused_lawyer.as_ref().map(|lawyer| lawyer.id.into()),
+            )
+        });
+        if let Some(invalid) = invalid {
+            ctx.send(|reply| reply.ephemeral(true).content(invalid.describe(strings)))
+                .await?;
+            return Ok(());
+        }
+
+        if reject_banned(ctx, strings, plaintiff.id.into()).await? {
+            return Ok(());
+        }
+        if allow_duplicate && !is_admin(ctx).await? {
+            ctx.say(strings.no_permission).await?;
+            return Ok(());
+        }
+        if !allow_duplicate
+            && reject_duplicate(ctx, strings, plaintiff.id.into(), accused.id.into()).await?
+        {
+            return Ok(());
+        }
+        if reject_over_limit(ctx, strings, plaintiff.id.into()).await?
+            || reject_filing_cooldown(ctx, strings).await?
+        {
+            return Ok(());
+        }
+
+        if state.strict_lawyers {
+            let not_admitted = [&plaintiff_lawyer, &accused_lawyer]
+                .into_iter()
+                .flatten()
+                .find(|lawyer| !state.admitted_lawyers.contains(&lawyer.id.into()));
+            if let Some(lawyer) = not_admitted {
+                ctx.say((strings.lawyer_not_admitted)(&format!("<@{}>", lawyer.id)))
+                    .await?;
+                return Ok(());
+            }
+        }
+
+        let judge = match judge {
+            Some(judge) => judge.id.into(),
+            None => {
+                let parties = [plaintiff.id, accused.id]
+                    .into_iter()
+                    .chain(plaintiff_lawyer.iter().map(|user| user.id))
+                    .chain(accused_lawyer.iter().map(|user| user.id))
+                    .map(SnowflakeId::from)
+                    .collect::<Vec<_>>();
+                match ctx
+                    .data()
+                    .storage
+                    .assign_judge(guild_id.into(), &parties)
+                    .await?
+                {
+                    Some(judge) => judge,
+                    None => {
+                        ctx.say(strings.no_registered_judge).await?;
+                        return Ok(());
+                    }
+                }
+            }
+        };
+
+        let lawsuit = Lawsuit {
+            plaintiff_lawyer: plaintiff_lawyer.map(|user| user.id.into()),
+            accused_lawyer: accused_lawyer.map(|user| user.id.into()),
+            ..Lawsuit::new(
+                guild_id.into(),
+                plaintiff.id.into(),
+                accused.id.into(),
+                judge,
+                reason.to_owned(),
+            )
+        };
+
+        let conflicts = lawsuit
+            .conflicts()
+            .iter()
+            .map(|conflict| conflict.describe(strings))
+            .collect::<Vec<_>>()
+            .join("\n");
+        if !conflicts.is_empty() && state.conflict_policy == ConflictPolicy::Reject {
+            ctx.say(conflicts).await?;
+            return Ok(());
+        }
+
+        let lawsuit_ctx = LawsuitCtx {
+            lawsuit,
+            storage: ctx.data().storage.clone(),
+            http: ctx.discord().http.clone(),
+            tasks: ctx.data().tasks.clone(),
+            guild_id,
+        };
+
+        let room = choose_court_room(ctx).await?;
+        let response = match lawsuit_ctx
+            .initialize_in(room)
+            .await
+            .wrap_err("initialize lawsuit")?
+        {
+            Ok(response) => response,
+            Err(response) => {
+                ctx.say(response.to_string()).await?;
+                return Ok(());
+            }
+        };
+        start_filing_cooldown(ctx).await?;
+
+        if !conflicts.is_empty() && state.conflict_policy == ConflictPolicy::Warn {
+            ctx.say(format!(
+                "{response}\n{}",
+                (strings.conflict_warning)(&conflicts)
+            ))
+            .await?;
+        } else {
+            ctx.say(response.to_string()).await?;
+        }
+
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(ctx))]
+    async fn lawsuit_file_impl(ctx: Context<'_>) -> Result<()> {
+        let guild_id = ctx.guild_id().wrap_err("guild_id not found")?;
+        let strings = i18n::strings(ctx).await?;
+
+        let draft = match wizard::file_lawsuit(ctx, strings).await? {
+            Some(draft) => draft,
+            None => return Ok(()),
+        };
+
+        let invalid = InvalidParties::check(
+            ctx.author().id.into(),
+            draft.accused.into(),
+            Some(draft.judge.into()),
+            &[],
+        );
+        if let Some(invalid) = invalid {
+            ctx.send(|reply| reply.ephemeral(true).content(invalid.describe(strings)))
+                .await?;
+            return Ok(());
+        }
+        if reject_banned(ctx, strings, ctx.author().id.into()).await?
+            || reject_duplicate(ctx, strings, ctx.author().id.into(), draft.accused.into()).await?
+            || reject_over_limit(ctx, strings, ctx.author().id.into()).await?
+            || reject_filing_cooldown(ctx, strings).await?
+        {
+            return Ok(());
+        }
+
+        let reason = match &draft.remedy {
+            Some(remedy) => (strings.reason_with_remedy)(&draft.reason, remedy),
+            None => draft.reason,
+        };
+
+        let lawsuit = Lawsuit::new(
+            guild_id.into(),
+            ctx.author().id.into(),
+            draft.accused.into(),
+            draft.judge.into(),
+            reason,
+        );
+
+        let lawsuit_ctx = LawsuitCtx {
+            lawsuit,
+            storage: ctx.data().storage.clone(),
+            http: ctx.discord().http.clone(),
+            tasks: ctx.data().tasks.clone(),
+            guild_id,
+        };
+
+        let room = choose_court_room(ctx).await?;
+        let response = lawsuit_ctx
+            .initialize_in(room)
+            .await
+            .wrap_err("initialize lawsuit")?;
+
+        if response.is_ok() {
+            start_filing_cooldown(ctx).await?;
+        }
+        let (Ok(response) | Err(response)) = response;
+        ctx.say(response.to_string()).await?;
+
+        Ok(())
+    }
+
+    /// Lets the author choose the court room with an ephemeral select menu if more than one is free.
+    /// `None` if there's nothing to choose or nobody chose in time, then the first free room is
+    /// used.
+    async fn choose_court_room(ctx: Context<'_>) -> Result<Option<SnowflakeId>> {
+        const TIMEOUT: Duration = Duration::from_secs(60);
+        // discord only allows 25 options in a select menu
+        const MAX_SELECT_OPTIONS: usize = 25;
+
+        let guild_id = ctx.guild_id().wrap_err("guild_id not found")?;
+        let state = ctx
+            .data()
+            .storage
+            .find_or_insert_state(guild_id.into())
+            .await?;
+        let strings = state.locale.strings();
+
+        let mut free_rooms = state
+            .court_rooms
+            .into_iter()
+            .filter(|room| !room.ongoing_lawsuit)
+            .collect::<Vec<_>>();
+        if free_rooms.len() <= 1 {
+            return Ok(None);
+        }
+        // the rooms that weren't used for the longest time first, they are the most likely to be
+        // tidy
+        free_rooms.sort_by_key(|room| room.last_used);
+        free_rooms.truncate(MAX_SELECT_OPTIONS);
+
+        let mut options = Vec::new();
+        for room in &free_rooms {
+            let name = ChannelId::from(room.channel_id)
+                .name(ctx.discord())
+                .await
+                .unwrap_or_else(|| room.channel_id.to_string());
+            let last_used = match room.last_used {
+                Some(last_used) => {
+                    let ago = DateTime::now().timestamp_millis() - last_used.timestamp_millis();
+                    (strings.room_last_used)(&duration::format(Duration::from_millis(
+                        ago.max(0) as u64
+                    )))
+                }
+                None => strings.room_never_used.to_owned(),
+            };
+            options.push((room.channel_id, name, last_used));
+        }
+
+        let custom_id = format!("room-{}", Uuid::new());
+        ctx.send(|reply| {
+            reply
+                .content(strings.room_select_prompt)
+                .ephemeral(true)
+                .components(|c| {
+                    c.create_action_row(|row| {
+                        row.create_select_menu(|menu| {
+                            menu.custom_id(&custom_id)
+                                .placeholder(strings.field_court_room)
+                                .options(|o| {
+                                    for (channel_id, name, last_used) in &options {
+                                        o.create_option(|option| {
+                                            option
+                                                .label(format!("#{name}"))
+                                                .description(last_used)
+                                                .value(channel_id)
+                                        });
+                                    }
+                                    o
+                                })
+                        })
+                    })
+                })
+        })
+        .await
+        .wrap_err("send court room selection")?;
+
+        let filter_id = custom_id.clone();
+        let interaction =
+            poise::serenity::collector::CollectComponentInteraction::new(ctx.discord())
+                .channel_id(ctx.channel_id())
+                .author_id(ctx.author().id)
+                .filter(move |interaction| interaction.data.custom_id == filter_id)
+                .timeout(TIMEOUT)
+                .await;
+
+        let interaction = match interaction {
+            Some(interaction) => interaction,
+            None => return Ok(None),
+        };
+
+        interaction
+            .create_interaction_response(ctx.discord(), |response| {
+                response
+                    .kind(InteractionResponseType::UpdateMessage)
+                    .interaction_response_data(|data| data.components(|c| c))
+            })
+            .await
+            .wrap_err("close court room selection")?;
+
+        Ok(interaction
+            .data
+            .values
+            .first()
+            .and_then(|value| value.parse().ok()))
+    }
+
+    #[tracing::instrument(skip(ctx, message))]
+    async fn lawsuit_mark_evidence_impl(ctx: Context<'_>, message: Message) -> Result<()> {
+        let guild_id = ctx.guild_id().wrap_err("guild_id not found")?;
+        let strings = i18n::strings(ctx).await?;
+        let storage = &ctx.data().storage;
+
+        let lawsuit = storage
+            .find_open_lawsuits(guild_id.into())
+            .await?
+            .into_iter()
+            .find(|lawsuit| lawsuit.court_room == ctx.channel_id().into());
+        let lawsuit = match lawsuit {
+            Some(lawsuit) => lawsuit,
+            None => {
+                ctx.say(strings.no_active_lawsuit).await?;
+                return Ok(());
+            }
+        };
+
+        // everyone who takes part can bring evidence, not only the judges
+        let author_id = SnowflakeId::from(ctx.author().id);
+        if !lawsuit.takes_part(author_id) && !is_judge(ctx).await? {
+            ctx.say(strings.no_permission).await?;
+            return Ok(());
+        }
+
+        let evidence = Evidence {
+            author: message.author.id.into(),
+            content: message.content.clone(),
+            link: format!(
+                "https://discord.com/channels/{guild_id}/{}/{}",
+                message.channel_id, message.id
+            ),
+            attachments: message
+                .attachments
+                .iter()
+                .map(|attachment| attachment.url.clone())
+                .collect(),
+            added_by: author_id,
+            added_at: DateTime::now(),
+        };
+        storage
+            .add_evidence(guild_id.into(), lawsuit.id, &evidence)
+            .await?;
+        info!(lawsuit_id = %lawsuit.id, "Added evidence");
+
+        let number = lawsuit.evidence.len() + 1;
+        ctx.send(|reply| reply.embed(|embed| evidence::embed(embed, strings, number, &evidence)))
+            .await?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(ctx))]
+    async fn lawsuit_sue_impl(ctx: Context<'_>, accused: User) -> Result<()> {
+        let guild_id = ctx.guild_id().wrap_err("guild_id not found")?;
+        let storage = &ctx.data().storage;
+
+        let state = storage.find_or_insert_state(guild_id.into()).await?;
+        let strings = state.locale.strings();
+
+        let bots = if accused.bot {
+            vec![accused.id.into()]
+        } else {
+            Vec::new()
+        };
+        let invalid = InvalidParties::check(ctx.author().id.into(), accused.id.into(), None, &bots);
+        if let Some(invalid) = invalid {
+            ctx.send(|reply| reply.ephemeral(true).content(invalid.describe(strings)))
+                .await?;
+            return Ok(());
+        }
+        if reject_banned(ctx, strings, ctx.author().id.into()).await?
+            || reject_duplicate(ctx, strings, ctx.author().id.into(), accused.id.into()).await?
+            || reject_over_limit(ctx, strings, ctx.author().id.into()).await?
+            || reject_filing_cooldown(ctx, strings).await?
+        {
+            return Ok(());
+        }
+
+        // assigned before the modal, so nobody writes their reason for nothing
+        let parties = [ctx.author().id.into(), accused.id.into()];
+        let judge = match storage.assign_judge(guild_id.into(), &parties).await? {
+            Some(judge) => judge,
+            None => {
+                ctx.say(strings.no_registered_judge).await?;
+                return Ok(());
+            }
+        };
+
+        let application_context = match ctx {
+            Context::Application(ctx) => ctx,
+            Context::Prefix(_) => return Err(eyre!("wrong context, cannot happen!")),
+        };
+        let modal = SueModal::execute(application_context)
+            .await
+            .wrap_err("sue modal")?;
+
+        let reason = match modal.law.as_deref().map(str::trim) {
+            Some(law) if !law.is_empty() => (strings.reason_with_law)(&modal.reason, law),
+            _ => modal.reason,
+        };
+
+        let lawsuit = Lawsuit::new(
+            guild_id.into(),
+            ctx.author().id.into(),
+            accused.id.into(),
+            judge,
+            reason,
+        );
+
+        let lawsuit_ctx = LawsuitCtx {
+            lawsuit,
+            storage: storage.clone(),
+            http: ctx.discord().http.clone(),
+            tasks: ctx.data().tasks.clone(),
+            guild_id,
+        };
+
+        let response = lawsuit_ctx
+            .initialize()
+            .await
+            .wrap_err("initialize lawsuit")?;
+
+        if response.is_ok() {
+            start_filing_cooldown(ctx).await?;
+        }
+        let (Ok(response) | Err(response)) = response;
+        ctx.say(response.to_string()).await?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(ctx))]
+    async fn lawsuit_close_impl(
+        ctx: Context<'_>,
+        case: Option<String>,
+        overturn: Option<bool>,
+        sealed: Option<bool>,
+        precedent: Option<String>,
+    ) -> Result<()> {
+        let guild_id = ctx.guild_id().wrap_err("guild_id not found")?;
+
+        let permission_override = is_admin(ctx).await?;
+
+        let room_id = ctx.channel_id();
+        let storage = &ctx.data().storage;
+
+        let state = storage
+            .find_or_insert_state(guild_id.into())
+            .await
+            .wrap_err("find guild for verdict")?;
+        let strings = state.locale.strings();
+
+        let mut open_lawsuits = storage
+            .find_open_lawsuits(guild_id.into())
+            .await
+            .wrap_err("find lawsuits for verdict")?
+            .into_iter();
+
+        let lawsuit = match &case {
+            Some(case) => open_lawsuits.find(|l| l.id.to_string() == *case),
+            None => open_lawsuits.find(|l| l.court_room == room_id.into()),
+        };
+
+        let lawsuit = match lawsuit {
+            Some(lawsuit) => lawsuit,
+            None if case.is_some() => {
+                ctx.say(strings.lawsuit_not_found).await?;
+                return Ok(());
+            }
+            None => {
+                ctx.say(strings.no_active_lawsuit).await?;
+                return Ok(());
+            }
+        };
+        if overturn.is_some() && lawsuit.appeal_of.is_none() {
+            ctx.say(strings.not_an_appeal).await?;
+            return Ok(());
+        }
+
+        let mut precedents = Vec::new();
+        if let Some(precedent) = &precedent {
+            let lawsuits = storage.find_lawsuits(guild_id.into()).await?;
+            let case_numbers = precedent
+                .split(|char: char| char == ',' || char.is_whitespace())
+                .filter(|case_number| !case_number.is_empty());
+            for case_number in case_numbers {
+                let found = lawsuits.iter().find(|lawsuit| {
+                    lawsuit.verdict.is_some() && lawsuit.case_number.as_deref() == Some(case_number)
+                });
+                match found {
+                    Some(found) => precedents.push(found.id),
+                    None => {
+                        ctx.say((strings.precedent_not_found)(case_number)).await?;
+                        return Ok(());
+                    }
+                }
+            }
+        }
+
+        let room = state
+            .court_rooms
+            .iter()
+            .find(|r| r.channel_id == lawsuit.court_room);
+        let room = match room {
+            Some(room) => room.clone(),
+            None => {
+                ctx.say(strings.no_active_lawsuit).await?;
+                return Ok(());
+            }
+        };
+
+        if lawsuit.judge != ctx.author().id.into() && !permission_override {
+            ctx.say(strings.no_permission).await?;
+            return Ok(());
+        }
+
+        let application_context = match ctx {
+            Context::Application(ctx) => ctx,
+            Context::Prefix(_) => return Err(eyre!("wrong context, cannot happen!")),
+        };
+        let modal = VerdictModal::execute(application_context)
+            .await
+            .wrap_err("verdict modal")?;
+
+        let non_empty = |text: Option<String>| {
+            text.map(|text| text.trim().to_owned())
+                .filter(|text| !text.is_empty())
+        };
+        let fine = match non_empty(modal.fine) {
+            Some(fine) => match fine.parse::<i64>() {
+                Ok(amount) if amount > 0 => Some(amount),
+                _ => {
+                    ctx.say(strings.invalid_amount).await?;
+                    return Ok(());
+                }
+            },
+            None => None,
+        };
+        if fine.is_some() && lawsuit.fine.is_some() {
+            ctx.say(strings.fine_already_imposed).await?;
+            return Ok(());
+        }
+        let details = VerdictDetails {
+            reasoning: non_empty(modal.reasoning),
+            sentence: non_empty(modal.sentence),
+        };
+        let verdict = modal.outcome.trim().to_owned();
+
+        // stored together with the verdict
+        let lawsuit = Lawsuit {
+            overturned: overturn.unwrap_or(lawsuit.overturned),
+            sealed: sealed.unwrap_or(lawsuit.sealed),
+            verdict_details: (details.reasoning.is_some() || details.sentence.is_some())
+                .then_some(details),
+            ..lawsuit
+        };
+
+        let mut lawsuit_ctx = LawsuitCtx {
+            lawsuit,
+            storage: storage.clone(),
+            http: ctx.discord().http.clone(),
+            tasks: ctx.data().tasks.clone(),
+            guild_id,
+        };
+
+        let response = lawsuit_ctx
+            .rule_verdict(permission_override, ctx.author().id, verdict, fine, room)
+            .await?;
+
+        if let Err(response) = response {
+            ctx.say(response.to_string()).await?;
+            return Ok(());
+        }
+        for precedent_id in precedents {
+            storage
+                .add_citation(guild_id.into(), lawsuit_ctx.lawsuit.id, precedent_id)
+                .await?;
+        }
+
+        ctx.say(templates::render(
+            &state,
+            Template::LawsuitClosed,
+            &templates::lawsuit_values(&lawsuit_ctx.lawsuit),
+        ))
+        .await?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(ctx))]
+    async fn lawsuit_appeal_impl(ctx: Context<'_>, case: String, reason: String) -> Result<()> {
+        let guild_id = ctx.guild_id().wrap_err("guild_id not found")?;
+        let storage = &ctx.data().storage;
+        let author = SnowflakeId::from(ctx.author().id);
+
+        let state = storage.find_or_insert_state(guild_id.into()).await?;
+        let strings = state.locale.strings();
+
+        let lawsuits = storage.find_lawsuits(guild_id.into()).await?;
+        let appealed = lawsuits
+            .iter()
+            .find(|lawsuit| lawsuit.id.to_string() == case && lawsuit.verdict.is_some());
+        let appealed = match appealed {
+            Some(appealed) => appealed,
+            None => {
+                ctx.say(strings.appeal_lawsuit_not_found).await?;
+                return Ok(());
+            }
+        };
+
+        // the other party of the lawsuit is the accused of the appeal
+        let accused = if appealed.plaintiff == author {
+            appealed.accused
+        } else if appealed.accused == author {
+            appealed.plaintiff
+        } else {
+            ctx.say(strings.appeal_not_a_party).await?;
+            return Ok(());
+        };
+
+        let judge = match state.appeal_judge {
+            Some(judge) if judge == appealed.judge => {
+                ctx.say(strings.appeal_same_judge).await?;
+                return Ok(());
+            }
+            Some(judge) => judge,
+            None => {
+                ctx.say(strings.appeal_no_judge).await?;
+                return Ok(());
+            }
+        };
+        let invalid = InvalidParties::check(author, accused, Some(judge), &[]);
+        if let Some(invalid) = invalid {
+            ctx.send(|reply| reply.ephemeral(true).content(invalid.describe(strings)))
+                .await?;
+            return Ok(());
+        }
+
+        let already_appealed = lawsuits
+            .iter()
+            .any(|lawsuit| lawsuit.appeal_of == Some(appealed.id) && lawsuit.verdict.is_none());
+        if already_appealed {
+            ctx.say(strings.appeal_already_running).await?;
+            return Ok(());
+        }
+
+        let lawsuit = Lawsuit {
+            appeal_of: Some(appealed.id),
+            ..Lawsuit::new(
+                guild_id.into(),
+                author,
+                accused,
+                judge,
+                (strings.verdict_appeal_reason)(&appealed.reason, &reason),
+            )
+        };
+
+        let lawsuit_ctx = LawsuitCtx {
+            lawsuit,
+            storage: storage.clone(),
+            http: ctx.discord().http.clone(),
+            tasks: ctx.data().tasks.clone(),
+            guild_id,
+        };
+
+        let (Ok(response) | Err(response)) = lawsuit_ctx
+            .initialize()
+            .await
+            .wrap_err("initialize appeal lawsuit")?;
+
+        ctx.say(response.to_string()).await?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(ctx))]
+    async fn lawsuit_jury_impl(
+        ctx: Context<'_>,
+        jurors: Option<String>,
+        count: Option<u32>,
+        deadline: Option<String>,
+    ) -> Result<()> {
+        const DEFAULT_DEADLINE: Duration = Duration::from_secs(60 * 60);
+        const DEFAULT_JURORS: u32 = 5;
+
+        let guild_id = ctx.guild_id().wrap_err("guild_id not found")?;
+        let storage = &ctx.data().storage;
+
+        let state = storage.find_or_insert_state(guild_id.into()).await?;
+        let strings = state.locale.strings();
+
+        let timeout = match deadline.as_deref().map(duration::parse) {
+            Some(Some(timeout)) => timeout,
+            Some(None) => {
+                ctx.say(strings.invalid_duration).await?;
+                return Ok(());
+            }
+            None => DEFAULT_DEADLINE,
+        };
+
+        let open_lawsuits = storage.find_open_lawsuits(guild_id.into()).await?;
+        let lawsuit = open_lawsuits
+            .iter()
+            .find(|lawsuit| lawsuit.court_room == ctx.channel_id().into())
+            .cloned();
+        let lawsuit = match lawsuit {
+            Some(lawsuit) => lawsuit,
+            None => {
+                ctx.say(strings.no_active_lawsuit).await?;
+                return Ok(());
+            }
+        };
+        if lawsuit.jury_tally.is_some() {
+            ctx.say(strings.jury_already_voted).await?;
+            return Ok(());
+        }
+
+        let juror_ids = match jurors {
+            Some(jurors) => {
+                let mut juror_ids = jurors
+                    .split_whitespace()
+                    .filter_map(|user| {
+                        poise::serenity::utils::parse_username(user).or_else(|| user.parse().ok())
+                    })
+                    .map(UserId)
+                    .collect::<Vec<_>>();
+                juror_ids.sort();
+                juror_ids.dedup();
+                juror_ids
+            }
+            None => {
+                let count = count.unwrap_or(DEFAULT_JURORS) as usize;
+                // nobody judges their own case or sits on two juries at once
+                let unavailable = open_lawsuits
+                    .iter()
+                    .flat_map(|open| open.jurors.iter().copied())
+                    .chain(lawsuit.parties())
+                    .collect::<Vec<_>>();
+                let drawn = jury::draw(&state.jury_pool, &unavailable, count);
+                if drawn.len() < count {
+                    ctx.say((strings.jury_pool_too_small)(drawn.len(), count))
+                        .await?;
+                    return Ok(());
+                }
+                drawn.into_iter().map(UserId::from).collect()
+            }
+        };
+        if juror_ids.is_empty() {
+            ctx.say(strings.no_jurors).await?;
+            return Ok(());
+        }
+
+        // sending all the direct messages can take longer than discord waits for an answer
+        ctx.defer().await?;
+
+        if let Some(room) = state
+            .court_rooms
+            .iter()
+            .find(|room| room.channel_id == lawsuit.court_room)
+        {
+            jury::summon(
+                &ctx.discord().http,
+                guild_id,
+                strings,
+                &lawsuit,
+                room.role_id,
+                &juror_ids,
+            )
+            .await?;
+            // the jurors of an earlier vote in the same trial keep the role until it's closed
+            let mut jurors = lawsuit.jurors.clone();
+            jurors.extend(juror_ids.iter().map(|&juror| SnowflakeId::from(juror)));
+            jurors.sort();
+            jurors.dedup();
+            storage
+                .set_lawsuit_jurors(guild_id.into(), lawsuit.id, &jurors)
+                .await?;
+        }
+
+        let ballot = jury::Ballot::send(
+            &ctx.discord().http,
+            guild_id,
+            strings,
+            lawsuit,
+            &juror_ids,
+            timeout,
+        )
+        .await?;
+
+        ctx.say((strings.jury_ballots_sent)(
+            ballot.reached(),
+            &duration::relative_timestamp(duration::from_now(timeout)),
+        ))
+        .await?;
+
+        let discord = ctx.discord().clone();
+        let storage = storage.clone();
+        let tasks = ctx.data().tasks.clone();
+        ctx.data().tasks.spawn(async move {
+            if let Err(err) = ballot.collect(discord, storage, tasks, strings).await {
+                error!(?err, "Failed to collect the votes of the jury");
+            }
+        });
+
+        Ok(())
+    }
+
+    /// The lawsuit with the case number, or the one running in the channel. The participants can
+    /// read their own lawsuits unless they're sealed, judges can read all of them. Answers and
+    /// returns `None` otherwise.
+    async fn find_readable_lawsuit(
+        ctx: Context<'_>,
+        strings: &Strings,
+        case: Option<String>,
+    ) -> Result<Option<Lawsuit>> {
+        let guild_id = ctx.guild_id().wrap_err("guild_id not found")?;
+        let storage = &ctx.data().storage;
+
+        let lawsuit = match &case {
+            Some(case) => storage
+                .find_lawsuits(guild_id.into())
+                .await?
+                .into_iter()
+                .find(|lawsuit| lawsuit.case_number.as_deref() == Some(case.trim())),
+            None => storage
+                .find_open_lawsuits(guild_id.into())
+                .await?
+                .into_iter()
+                .find(|lawsuit| lawsuit.court_room == ctx.channel_id().into()),
+        };
+        let lawsuit = match (lawsuit, case) {
+            (Some(lawsuit), _) => lawsuit,
+            (None, Some(_)) => {
+                ctx.say(strings.lawsuit_not_found).await?;
+                return Ok(None);
+            }
+            (None, None) => {
+                ctx.say(strings.no_active_lawsuit).await?;
+                return Ok(None);
+            }
+        };
+
+        let author_id = SnowflakeId::from(ctx.author().id);
+        let participants = [
+            Some(lawsuit.plaintiff),
+            Some(lawsuit.accused),
+            Some(lawsuit.judge),
+            lawsuit.plaintiff_lawyer,
+            lawsuit.accused_lawyer,
+        ];
+        let is_participant = participants.contains(&Some(author_id)) && !lawsuit.sealed;
+        if !is_participant && !is_judge(ctx).await? {
+            ctx.say(strings.no_permission).await?;
+            return Ok(None);
+        }
+
+        Ok(Some(lawsuit))
+    }
+
+    #[tracing::instrument(skip(ctx))]
+    async fn lawsuit_transcript_export_impl(ctx: Context<'_>, case: Option<String>) -> Result<()> {
+        let strings = i18n::strings(ctx).await?;
+        let lawsuit = match find_readable_lawsuit(ctx, strings, case).await? {
+            Some(lawsuit) => lawsuit,
+            None => return Ok(()),
+        };
+
+        ctx.defer_ephemeral().await?;
+
+        let messages = match transcript::fetch_messages(&ctx.discord().http, &lawsuit).await? {
+            Some(messages) => messages,
+            None => {
+                ctx.say(strings.transcript_unavailable).await?;
+                return Ok(());
+            }
+        };
+        let case_number = lawsuit
+            .case_number
+            .clone()
+            .unwrap_or_else(|| lawsuit.id.to_string());
+        let title = (strings.transcript_title)(&case_number);
+        let html = transcript::render(strings, &title, &lawsuit, &messages);
+
+        ctx.send(|reply| {
+            reply
+                .ephemeral(true)
+                .content((strings.transcript_exported)(messages.len()))
+                .attachment(AttachmentType::Bytes {
+                    data: html.into_bytes().into(),
+                    filename: format!("transcript-{case_number}.html"),
+                })
+        })
+        .await
+        .wrap_err("reply")?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(ctx))]
+    async fn lawsuit_summary_impl(ctx: Context<'_>, case: Option<String>) -> Result<()> {
+        // longer summaries are sent as a file
+        const MAX_MESSAGE_LEN: usize = 2000;
+
+        let guild_id = ctx.guild_id().wrap_err("guild_id not found")?;
+        let state = ctx
+            .data()
+            .storage
+            .find_or_insert_state(guild_id.into())
+            .await?;
+        let strings = state.locale.strings();
+
+        let lawsuit = match find_readable_lawsuit(ctx, strings, case).await? {
+            Some(lawsuit) => lawsuit,
+            None => return Ok(()),
+        };
+
+        let values = templates::summary_values(strings, &lawsuit);
+        let summary = templates::render(&state, Template::CaseSummary, &values);
+        // sealed lawsuits stay between the people who can see them
+        let ephemeral = lawsuit.sealed;
+
+        if summary.chars().count() <= MAX_MESSAGE_LEN {
+            ctx.send(|reply| reply.ephemeral(ephemeral).content(summary))
+                .await
+                .wrap_err("reply")?;
+        } else {
+            let case_number = lawsuit
+                .case_number
+                .clone()
+                .unwrap_or_else(|| lawsuit.id.to_string());
+            ctx.send(|reply| {
+                reply
+                    .ephemeral(ephemeral)
+                    .content(strings.case_summary_too_long)
+                    .attachment(AttachmentType::Bytes {
+                        data: summary.into_bytes().into(),
+                        filename: format!("summary-{case_number}.md"),
+                    })
+            })
+            .await
+            .wrap_err("reply")?;
+        }
+
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(ctx))]
+    async fn lawsuit_witness_impl(
+        ctx: Context<'_>,
+        user: User,
+        deadline: Option<String>,
+    ) -> Result<()> {
+        let guild_id = ctx.guild_id().wrap_err("guild_id not found")?;
+        let storage = &ctx.data().storage;
+
+        let state = storage.find_or_insert_state(guild_id.into()).await?;
+        let strings = state.locale.strings();
+
+        let deadline = match deadline.as_deref().map(duration::parse) {
+            Some(Some(deadline)) => deadline,
+            Some(None) => {
+                ctx.say(strings.invalid_duration).await?;
+                return Ok(());
+            }
+            None => subpoena::DEFAULT_DEADLINE,
+        };
+
+        let lawsuit = storage
+            .find_open_lawsuits(guild_id.into())
+            .await?
+            .into_iter()
+            .find(|lawsuit| lawsuit.court_room == ctx.channel_id().into());
+        let lawsuit = match lawsuit {
+            Some(lawsuit) => lawsuit,
+            None => {
+                ctx.say(strings.no_active_lawsuit).await?;
+                return Ok(());
+            }
+        };
+
+        // like evidence, everyone who takes part can summon witnesses
+        let author_id = SnowflakeId::from(ctx.author().id);
+        if !lawsuit.takes_part(author_id) && !is_judge(ctx).await? {
+            ctx.say(strings.no_permission).await?;
+            return Ok(());
+        }
+
+        let witness_id = SnowflakeId::from(user.id);
+        if lawsuit.takes_part(witness_id) {
+            ctx.say(strings.witness_is_party).await?;
+            return Ok(());
+        }
+        if lawsuit
+            .witnesses
+            .iter()
+            .any(|witness| witness.user_id == witness_id)
+        {
+            ctx.say(strings.witness_already_added).await?;
+            return Ok(());
+        }
+
+        let lawsuit_id = lawsuit.id;
+        let subpoena = subpoena::Subpoena::send(
+            &ctx.discord().http,
+            guild_id,
+            strings,
+            lawsuit,
+            user.id,
+            deadline,
+        )
+        .await?;
+
+        let response = match subpoena {
+            Some(_) => SubpoenaResponse::Pending,
+            None => SubpoenaResponse::Undelivered,
+        };
+        let witness = Witness {
+            user_id: witness_id,
+            added_by: author_id,
+            added_at: DateTime::now(),
+            response,
+        };
+        storage
+            .add_witness(guild_id.into(), lawsuit_id, &witness)
+            .await?;
+        info!(%lawsuit_id, %witness_id, ?response, "Added witness");
+
+        let subpoena = match subpoena {
+            Some(subpoena) => subpoena,
+            None => {
+                ctx.say((strings.subpoena_undelivered)(&format!("<@{witness_id}>")))
+                    .await?;
+                return Ok(());
+            }
+        };
+        ctx.say((strings.subpoena_sent)(
+            &format!("<@{witness_id}>"),
+            &duration::relative_timestamp(duration::from_now(deadline)),
+        ))
+        .await?;
+
+        let discord = ctx.discord().clone();
+        let storage = storage.clone();
+        let tasks = ctx.data().tasks.clone();
+        ctx.data().tasks.spawn(async move {
+            if let Err(err) = subpoena.collect(discord, storage, tasks, strings).await {
+                error!(?err, "Failed to collect the answer to the subpoena");
+            }
+        });
+
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(ctx))]
+    async fn lawsuit_hearing_impl(ctx: Context<'_>, when: Option<String>) -> Result<()> {
+        let guild_id = ctx.guild_id().wrap_err("guild_id not found")?;
+        let storage = &ctx.data().storage;
+
+        let state = storage.find_or_insert_state(guild_id.into()).await?;
+        let strings = state.locale.strings();
+
+        let hearing_at = match when.as_deref() {
+            Some(when) => {
+                let hearing_at = duration::parse(when)
+                    .map(duration::from_now)
+                    .or_else(|| duration::parse_date_time(when));
+                match hearing_at {
+                    Some(hearing_at) if hearing_at > DateTime::now() => Some(hearing_at),
+                    _ => {
+                        ctx.say(strings.invalid_hearing_time).await?;
+                        return Ok(());
+                    }
+                }
+            }
+            None => None,
+        };
+
+        let lawsuit = storage
+            .find_open_lawsuits(guild_id.into())
+            .await?
+            .into_iter()
+            .find(|lawsuit| lawsuit.court_room == ctx.channel_id().into());
+        let lawsuit = match lawsuit {
+            Some(lawsuit) => lawsuit,
+            None => {
+                ctx.say(strings.no_active_lawsuit).await?;
+                return Ok(());
+            }
+        };
+
+        storage
+            .set_lawsuit_hearing(guild_id.into(), lawsuit.id, hearing_at)
+            .await?;
+        info!(lawsuit_id = %lawsuit.id, ?hearing_at, "Set hearing");
+
+        let content = match hearing_at {
+            Some(hearing_at) => (strings.hearing_set)(&duration::full_timestamp(hearing_at)),
+            None => strings.hearing_removed.to_string(),
+        };
+        ctx.say(content).await?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(ctx))]
+    async fn lawsuit_public_impl(ctx: Context<'_>, public: bool) -> Result<()> {
+        let guild_id = ctx.guild_id().wrap_err("guild_id not found")?;
+        let storage = &ctx.data().storage;
+
+        let state = storage.find_or_insert_state(guild_id.into()).await?;
+        let strings = state.locale.strings();
+
+        let lawsuit = storage
+            .find_open_lawsuits(guild_id.into())
+            .await?
+            .into_iter()
+            .find(|lawsuit| lawsuit.court_room == ctx.channel_id().into());
+        let room = state
+            .court_rooms
+            .iter()
+            .find(|room| room.channel_id == ctx.channel_id().into());
+        let (lawsuit, room) = match (lawsuit, room) {
+            (Some(lawsuit), Some(room)) => (lawsuit, room),
+            _ => {
+                ctx.say(strings.no_active_lawsuit).await?;
+                return Ok(());
+            }
+        };
+        if lawsuit.judge != ctx.author().id.into() {
+            ctx.say(strings.no_permission).await?;
+            return Ok(());
+        }
+        if room.closed_to_public != public {
+            let content = if public {
+                strings.room_already_public
+            } else {
+                strings.room_already_closed_to_public
+            };
+            ctx.say(content).await?;
+            return Ok(());
+        }
+
+        crate::lawsuit::set_room_closed_to_public(&ctx.discord().http, guild_id, room, !public)
+            .await?;
+        storage
+            .set_room_closed_to_public(guild_id.into(), room.channel_id, !public)
+            .await?;
+        info!(lawsuit_id = %lawsuit.id, public, "Changed court room visibility");
+
+        // the message stays in the court room, so it's part of the transcript
+        let content = if public {
+            strings.room_opened_to_public
+        } else {
+            strings.room_closed_to_public
+        };
+        ctx.say(content).await?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(ctx))]
+    async fn lawsuit_contempt_impl(ctx: Context<'_>, user: User, duration: String) -> Result<()> {
+        let guild_id = ctx.guild_id().wrap_err("guild_id not found")?;
+        let storage = &ctx.data().storage;
+
+        let state = storage.find_or_insert_state(guild_id.into()).await?;
+        let strings = state.locale.strings();
+
+        let duration = match duration::parse(&duration) {
+            Some(duration) if duration > crate::lawsuit::MAX_CONTEMPT => {
+                let max = duration::format(crate::lawsuit::MAX_CONTEMPT);
+                ctx.say((strings.contempt_too_long)(&max)).await?;
+                return Ok(());
+            }
+            Some(duration) => duration,
+            None => {
+                ctx.say(strings.invalid_duration).await?;
+                return Ok(());
+            }
+        };
+
+        let lawsuit = storage
+            .find_open_lawsuits(guild_id.into())
+            .await?
+            .into_iter()
+            .find(|lawsuit| lawsuit.court_room == ctx.channel_id().into());
+        let lawsuit = match lawsuit {
+            Some(lawsuit) => lawsuit,
+            None => {
+                ctx.say(strings.no_active_lawsuit).await?;
+                return Ok(());
+            }
+        };
+        if lawsuit.judge != ctx.author().id.into() {
+            ctx.say(strings.no_permission).await?;
+            return Ok(());
+        }
+
+        let until = duration::from_now(duration);
+        let timed_out = crate::lawsuit::hold_in_contempt(
+            &ctx.discord().http,
+            storage,
+            guild_id,
+            lawsuit.court_room,
+            user.id,
+            until,
+        )
+        .await?;
+        let contempt = Contempt {
+            user_id: user.id.into(),
+            ordered_at: DateTime::now(),
+            until,
+            timed_out,
+        };
+        storage
+            .add_contempt(guild_id.into(), lawsuit.id, &contempt)
+            .await?;
+        info!(lawsuit_id = %lawsuit.id, user_id = %user.id, ?duration, timed_out, "Held in contempt of court");
+
+        audit::log(
+            &ctx.discord().http,
+            &state,
+            AuditEvent::ContemptOfCourt {
+                lawsuit: &lawsuit,
+                contempt: &contempt,
+            },
+        )
+        .await;
+
+        // the message stays in the court room, so it's part of the transcript
+        let mention = format!("<@{}>", user.id);
+        let until = duration::relative_timestamp(until);
+        let content = if timed_out {
+            (strings.held_in_contempt)(&mention, &until)
+        } else {
+            (strings.held_in_contempt_in_room)(&mention, &until)
+        };
+        ctx.say(content).await?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(ctx))]
+    async fn lawsuit_eject_impl(ctx: Context<'_>, user: User) -> Result<()> {
+        let guild_id = ctx.guild_id().wrap_err("guild_id not found")?;
+        let storage = &ctx.data().storage;
+        let user_id = SnowflakeId::from(user.id);
+
+        let state = storage.find_or_insert_state(guild_id.into()).await?;
+        let strings = state.locale.strings();
+
+        let lawsuit = storage
+            .find_open_lawsuits(guild_id.into())
+            .await?
+            .into_iter()
+            .find(|lawsuit| lawsuit.court_room == ctx.channel_id().into());
+        let room = state
+            .court_rooms
+            .iter()
+            .find(|room| room.channel_id == ctx.channel_id().into());
+        let (lawsuit, room) = match (lawsuit, room) {
+            (Some(lawsuit), Some(room)) => (lawsuit, room),
+            _ => {
+                ctx.say(strings.no_active_lawsuit).await?;
+                return Ok(());
+            }
+        };
+        if lawsuit.takes_part(user_id) {
+            ctx.say(strings.cannot_eject_participant).await?;
+            return Ok(());
+        }
+        if lawsuit.ejected.contains(&user_id) {
+            ctx.say(strings.already_ejected).await?;
+            return Ok(());
+        }
+
+        crate::lawsuit::eject(&ctx.discord().http, guild_id, room, user_id).await?;
+        storage
+            .add_ejected(guild_id.into(), lawsuit.id, user_id)
+            .await?;
+        info!(lawsuit_id = %lawsuit.id, %user_id, "Ejected from court room");
+
+        audit::log(
+            &ctx.discord().http,
+            &state,
+            AuditEvent::Ejected {
+                lawsuit: &lawsuit,
+                user_id: user.id,
+                ejected_by: ctx.author().id,
+            },
+        )
+        .await;
+
+        ctx.say((strings.ejected)(&format!("<@{user_id}>"))).await?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(ctx))]
+    async fn lawsuit_gag_impl(ctx: Context<'_>, user: User) -> Result<()> {
+        let guild_id = ctx.guild_id().wrap_err("guild_id not found")?;
+        let storage = &ctx.data().storage;
+        let user_id = SnowflakeId::from(user.id);
+
+        let state = storage.find_or_insert_state(guild_id.into()).await?;
+        let strings = state.locale.strings();
+
+        let lawsuit = storage
+            .find_open_lawsuits(guild_id.into())
+            .await?
+            .into_iter()
+            .find(|lawsuit| lawsuit.court_room == ctx.channel_id().into());
+        let lawsuit = match lawsuit {
+            Some(lawsuit) => lawsuit,
+            None => {
+                ctx.say(strings.no_active_lawsuit).await?;
+                return Ok(());
+            }
+        };
+        if lawsuit.judge != ctx.author().id.into() {
+            ctx.say(strings.no_permission).await?;
+            return Ok(());
+        }
+
+        let order = GagOrder {
+            lawsuit_id: lawsuit.id,
+            user_id,
+        };
+        if state.gag_orders.contains(&order) {
+            ctx.say(strings.gag_already_ordered).await?;
+            return Ok(());
+        }
+
+        storage.add_gag_order(guild_id.into(), &order).await?;
+        info!(lawsuit_id = %lawsuit.id, %user_id, "Ordered gag");
+
+        audit::log(
+            &ctx.discord().http,
+            &state,
+            AuditEvent::GagOrdered {
+                lawsuit: &lawsuit,
+                user_id: user.id,
+                ordered_by: ctx.author().id,
+            },
+        )
+        .await;
+
+        ctx.say((strings.gag_ordered)(&format!("<@{user_id}>")))
+            .await?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(ctx))]
+    async fn lawsuit_fine_impl(ctx: Context<'_>, amount: i64) -> Result<()> {
+        let guild_id = ctx.guild_id().wrap_err("guild_id not found")?;
+        let storage = &ctx.data().storage;
+
+        let state = storage.find_or_insert_state(guild_id.into()).await?;
+        let strings = state.locale.strings();
+
+        if amount <= 0 {
+            ctx.say(strings.invalid_amount).await?;
+            return Ok(());
+        }
+
+        let lawsuit = storage
+            .find_open_lawsuits(guild_id.into())
+            .await?
+            .into_iter()
+            .find(|lawsuit| lawsuit.court_room == ctx.channel_id().into());
+        let mut lawsuit = match lawsuit {
+            Some(lawsuit) => lawsuit,
+            None => {
+                ctx.say(strings.no_active_lawsuit).await?;
+                return Ok(());
+            }
+        };
+        if lawsuit.judge != ctx.author().id.into() && !is_admin(ctx).await? {
+            ctx.say(strings.no_permission).await?;
+            return Ok(());
+        }
+        if lawsuit.fine.is_some() {
+            ctx.say(strings.fine_already_imposed).await?;
+            return Ok(());
+        }
+
+        let fine = impose_fine(&ctx.discord().http, storage, &state, &mut lawsuit, amount).await?;
+
+        ctx.say((strings.fine_imposed)(
+            &format!("<@{}>", lawsuit.accused),
+            &fine.describe(strings),
+        ))
+        .await?;
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(ctx))]
+    async fn lawsuit_list_impl(ctx: Context<'_>) -> Result<()> {
+        let guild_id = ctx.guild_id().wrap_err("guild_id not found")?;
+
+        let storage = &ctx.data().storage;
+
+        let state = storage.find_or_insert_state(guild_id.into()).await?;
+        let strings = state.locale.strings();
+
+        let lawsuits = storage.find_lawsuits(guild_id.into()).await?;
+
+        if lawsuits.is_empty() {
+            ctx.say(strings.no_lawsuits).await?;
+            return Ok(());
+        }
+
+        // open lawsuits are more interesting, so they go first
+        let (open, closed): (Vec<_>, Vec<_>) = lawsuits.iter().partition(|l| l.verdict.is_none());
+
+        let entries = open
+            .iter()
+            .chain(closed.iter())
+            .map(|lawsuit| {
+                let status = match &lawsuit.verdict {
+                    Some(_) if lawsuit.sealed => strings.lawsuit_verdict_sealed.to_owned(),
+                    Some(verdict) => (strings.lawsuit_verdict)(verdict),
+                    None => (strings.lawsuit_running_in)(&format!("<#{}>", lawsuit.court_room)),
+                };
+                (strings.lawsuit_entry)(
+                    &lawsuit.reason,
+                    &format!("<@{}>", lawsuit.plaintiff),
+                    &format!("<@{}>", lawsuit.accused),
+                    &status,
+                )
+            })
+            .collect::<Vec<_>>();
+
+        paginate(ctx, strings.lawsuits_title, &entries).await
+    }
+
+    /// Only says what would be removed for dry runs, otherwise asks whether to remove it. Returns
+    /// whether it should be removed.
+    async fn confirm_clear(
+        ctx: Context<'_>,
+        strings: &Strings,
+        dry_run: bool,
+        what: &str,
+        question: &str,
+    ) -> Result<bool> {
+        if dry_run {
+            ctx.say((strings.clear_dry_run)(what)).await?;
+            return Ok(false);
+        }
+        confirmation::confirm(ctx, strings, question).await
+    }
+
+    #[tracing::instrument(skip(ctx))]
+    async fn lawsuit_clear_lawsuits_impl(ctx: Context<'_>, dry_run: bool) -> Result<()> {
+        let guild_id = ctx.guild_id().wrap_err("guild_id not found")?;
+        let strings = i18n::strings(ctx).await?;
+        let storage = &ctx.data().storage;
+
+        let lawsuits = storage.find_lawsuits(guild_id.into()).await?;
+        let what = (strings.clear_lawsuits)(lawsuits.len());
+        if !confirm_clear(
+            ctx,
+            strings,
+            dry_run,
+            &what,
+            &(strings.clear_confirm)(&what),
+        )
+        .await?
+        {
+            return Ok(());
+        }
+
+        let deleted = storage.delete_lawsuits(guild_id.into()).await?;
+        info!(%guild_id, %deleted, "Deleted all lawsuits");
+
+        ctx.say((strings.lawsuits_deleted)(deleted as usize))
+            .await?;
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(ctx))]
+    async fn lawsuit_clear_rooms_impl(ctx: Context<'_>, dry_run: bool) -> Result<()> {
+        let guild_id = ctx.guild_id().wrap_err("guild_id not found")?;
+        let strings = i18n::strings(ctx).await?;
+        let storage = &ctx.data().storage;
+
+        let state = storage.find_or_insert_state(guild_id.into()).await?;
+        // the lawsuits would never free their rooms again
+        if state.court_rooms.iter().any(|room| room.ongoing_lawsuit) {
+            ctx.say(strings.court_rooms_in_use).await?;
+            return Ok(());
+        }
+        let rooms = state.court_rooms.len();
+        let what = (strings.clear_rooms)(rooms);
+        if !confirm_clear(
+            ctx,
+            strings,
+            dry_run,
+            &what,
+            &(strings.clear_confirm)(&what),
+        )
+        .await?
+        {
+            return Ok(());
+        }
+
+        storage.remove_court_rooms(guild_id.into()).await?;
+        info!(%guild_id, %rooms, "Removed all court rooms");
+
+        ctx.say((strings.court_rooms_removed)(rooms)).await?;
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(ctx))]
+    async fn lawsuit_clear_prison_impl(ctx: Context<'_>, dry_run: bool) -> Result<()> {
+        let guild_id = ctx.guild_id().wrap_err("guild_id not found")?;
+        let strings = i18n::strings(ctx).await?;
+        let storage = &ctx.data().storage;
+
+        let prisoners = storage.find_prison_entries(guild_id.into()).await?;
+        let what = (strings.clear_prisoners)(prisoners.len());
+        if !confirm_clear(
+            ctx,
+            strings,
+            dry_run,
+            &what,
+            &(strings.clear_confirm)(&what),
+        )
+        .await?
+        {
+            return Ok(());
+        }
+
+        let state = storage.find_or_insert_state(guild_id.into()).await?;
+        let batch = crate::prison::release_all(
+            &ctx.discord().http,
+            storage,
+            &state,
+            guild_id,
+            ctx.author().id,
+        )
+        .await?;
+
+        ctx.say(batch_message(strings, strings.released_many, &batch))
+            .await?;
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(ctx))]
+    async fn lawsuit_clear_all_impl(ctx: Context<'_>, dry_run: bool) -> Result<()> {
+        let guild_id = ctx.guild_id().wrap_err("guild_id not found")?;
+        let strings = i18n::strings(ctx).await?;
+        let storage = &ctx.data().storage;
+
+        let state = storage.find_or_insert_state(guild_id.into()).await?;
+        let lawsuits = storage.find_lawsuits(guild_id.into()).await?;
+        let prisoners = storage.find_prison_entries(guild_id.into()).await?;
+        let what = [
+            (strings.clear_lawsuits)(lawsuits.len()),
+            (strings.clear_rooms)(state.court_rooms.len()),
+            (strings.clear_prisoners)(prisoners.len()),
+            strings.clear_settings.to_owned(),
+        ]
+        .join(", ");
+        let question = (strings.clear_all_confirm)(&what, &duration::format(TRASH_GRACE_PERIOD));
+        if !confirm_clear(ctx, strings, dry_run, &what, &question).await? {
+            return Ok(());
+        }
+
+        // the prison roles are gone with the settings, so the prisoners are released first
+        crate::prison::release_all(
+            &ctx.discord().http,
+            storage,
+            &state,
+            guild_id,
+            ctx.author().id,
+        )
+        .await?;
+
+        storage.trash_guild(guild_id.into()).await?;
+        storage
+            .schedule_job(
+                guild_id.into(),
+                &JobKind::PurgeTrash,
+                duration::from_now(TRASH_GRACE_PERIOD),
+            )
+            .await?;
+
+        ctx.say((strings.lawsuits_cleared)(&duration::format(
+            TRASH_GRACE_PERIOD,
+        )))
+        .await?;
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(ctx))]
+    async fn lawsuit_restore_impl(ctx: Context<'_>) -> Result<()> {
+        let guild_id = ctx.guild_id().wrap_err("guild_id not found")?;
+        let storage = &ctx.data().storage;
+
+        let state = match storage.restore_guild(guild_id.into()).await? {
+            Some(state) => state,
+            None => {
+                let strings = i18n::strings(ctx).await?;
+                ctx.say(strings.nothing_to_restore).await?;
+                return Ok(());
+            }
+        };
+        storage
+            .cancel_job(guild_id.into(), &JobKind::PurgeTrash)
+            .await?;
+
+        info!(%guild_id, "Restored cleared state");
+
+        ctx.say(state.locale.strings().lawsuits_restored).await?;
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(ctx))]
+    async fn lawsuit_ban_impl(ctx: Context<'_>, user: User, banned: bool) -> Result<()> {
+        let guild_id = ctx.guild_id().wrap_err("guild_id not found")?;
+        let storage = &ctx.data().storage;
+
+        let state = storage.find_or_insert_state(guild_id.into()).await?;
+        let strings = state.locale.strings();
+        let user_id = SnowflakeId::from(user.id);
+
+        if state.banned_litigants.contains(&user_id) == banned {
+            let content = if banned {
+                strings.litigant_already_banned
+            } else {
+                strings.litigant_not_banned
+            };
+            ctx.say(content).await?;
+            return Ok(());
+        }
+
+        storage
+            .set_litigant_banned(guild_id.into(), user_id, banned)
+            .await?;
+        info!(%guild_id, %user_id, banned, "Changed litigant ban");
+
+        audit::log(
+            &ctx.discord().http,
+            &state,
+            AuditEvent::LitigantBanned {
+                user_id: user.id,
+                banned,
+                changed_by: ctx.author().id,
+            },
+        )
+        .await;
+
+        let mention = format!("<@{user_id}>");
+        let content = if banned {
+            (strings.litigant_banned)(&mention)
+        } else {
+            (strings.litigant_unbanned)(&mention)
+        };
+        ctx.say(content).await?;
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(ctx))]
+    async fn lawsuit_sync_permissions_impl(ctx: Context<'_>) -> Result<()> {
+        let guild_id = ctx.guild_id().wrap_err("guild_id not found")?;
+        let http = &ctx.discord().http;
+
+        // every room is a few requests
+        ctx.defer().await?;
+
+        let state = ctx
+            .data()
+            .storage
+            .find_or_insert_state(guild_id.into())
+            .await?;
+        let strings = state.locale.strings();
+
+        let mut failed = Vec::new();
+        for room in &state.court_rooms {
+            if let Err(err) = crate::lawsuit::sync_room_permissions(http, guild_id, room).await {
+                warn!(?err, channel_id = %room.channel_id, "Failed to sync court room permissions");
+                failed.push(format!("<#{}>", room.channel_id));
+            }
+        }
+        crate::prison::apply_channel_permissions(http, guild_id, &state).await?;
+
+        info!(%guild_id, failed = failed.len(), "Synced permissions");
+
+        let content = if failed.is_empty() {
+            (strings.permissions_synced)(state.court_rooms.len())
+        } else {
+            (strings.permissions_sync_failed)(&failed.join(", "))
+        };
+        ctx.say(content).await?;
+        Ok(())
+    }
+}
+
+pub mod prison {
+    use color_eyre::eyre::eyre;
+    use poise::Modal;
+
+    use super::*;
+
+    #[derive(Debug, Modal)]
+    #[name = "Mehreri Lüt iisperre"]
+    struct ArrestManyModal {
+        #[name = "Wer söll igsperrt werde?"]
+        #[placeholder = "@person1 @person2 oder IDs, mit Leerzeichen getrennt"]
+        #[paragraph]
+        users: String,
+    }
+
+    #[derive(Debug, Modal)]
+    #[name = "Iisperre"]
+    struct ArrestModal {
+        #[name = "Wieso?"]
+        reason: Option<String>,
+        #[name = "Wie lang?"]
+        #[placeholder = "z.B. 7d oder 12h, leer für unbeschränkt"]
+        duration: Option<String>,
+    }
+
+    /// Das Gefängnis verwalten
+    #[poise::command(
+        slash_command,
+        guild_only,
+        subcommands(
+            "set_tier",
+            "remove_tier",
+            "arrest",
+            "arrest_many",
+            "release",
+            "release_all",
+            "extend",
+            "reduce",
+            "parole",
+            "revoke_parole",
+            "appeal",
+            "list",
+            "check",
+            "stats"
+        )
+    )]
+    pub async fn prison(_: Context<'_>) -> Result<()> {
+        unreachable!()
+    }
+
+    /// Die Person einsperren
+    #[poise::command(context_menu_command = "Einsperren", guild_only, check = "is_bailiff")]
+    pub async fn arrest_user(ctx: Context<'_>, user: User) -> Result<()> {
+        let application_context = match ctx {
+            Context::Application(ctx) => ctx,
+            Context::Prefix(_) => return Err(eyre!("wrong context, cannot happen!")),
+        };
+        let modal = ArrestModal::execute(application_context)
+            .await
+            .wrap_err("arrest modal")?;
+
+        let non_empty = |text: Option<String>| {
+            text.map(|text| text.trim().to_owned())
+                .filter(|text| !text.is_empty())
+        };
+        prison_arrest_impl(
+            ctx,
+            user,
+            None,
+            non_empty(modal.duration),
+            non_empty(modal.reason),
+        )
+        .await
+        .wrap_err("prison_arrest_user")
+    }
+
+    /// Eine Haftstufe mit eigener Rolle erstellen oder ändern
+    #[poise::command(slash_command, guild_only, check = "is_admin")]
+    async fn set_tier(
+        ctx: Context<'_>,
+        #[description = "Der Name der Haftstufe, z.B. Einzelhaft"] name: String,
+        #[description = "Die Rolle für Gefangene in dieser Haftstufe"] role: Role,
+    ) -> Result<()> {
+        prison_set_tier_impl(ctx, name, role)
+            .await
+            .wrap_err("prison_set_tier")
+    }
+
+    /// Eine Haftstufe entfernen
+    #[poise::command(slash_command, guild_only, check = "is_admin")]
+    async fn remove_tier(
+        ctx: Context<'_>,
+        #[description = "Der Name der Haftstufe"]
+        #[autocomplete = "autocomplete_tier"]
+        name: String,
+    ) -> Result<()> {
+        prison_remove_tier_impl(ctx, name)
+            .await
+            .wrap_err("prison_remove_tier")
+    }
+
+    /// Jemanden einsperren
+    #[poise::command(slash_command, guild_only, check = "is_bailiff")]
+    async fn arrest(
+        ctx: Context<'_>,
+        #[description = "Die Person zum einsperren"] user: User,
+        #[description = "Die Haftstufe"]
+        #[autocomplete = "autocomplete_tier"]
+        tier: Option<String>,
+        #[description = "Wie lange, z.B. 7d oder 12h"] duration: Option<String>,
+        #[description = "Der Grund für die Verhaftung"] reason: Option<String>,
+    ) -> Result<()> {
+        prison_arrest_impl(ctx, user, tier, duration, reason)
+            .await
+            .wrap_err("prison_arrest")
+    }
+
+    async fn autocomplete_tier(ctx: Context<'_>, partial: String) -> impl Iterator<Item = String> {
+        let tiers = match ctx.guild_id() {
+            Some(guild_id) => match ctx
+                .data()
+                .storage
+                .find_or_insert_state(guild_id.into())
+                .await
+            {
+                Ok(state) => state.prison_tiers,
+                Err(err) => {
+                    error!(?err, "Error fetching prison tiers for autocomplete");
+                    vec![]
+                }
+            },
+            None => vec![],
+        };
+
+        let partial = partial.to_lowercase();
+        tiers
+            .into_iter()
+            .map(|tier| tier.name)
+            .filter(move |name| name.to_lowercase().starts_with(&partial))
+    }
+
+    /// Mehrere Personen auf einmal einsperren, z.B. nach einem Raid
+    #[poise::command(slash_command, guild_only, check = "is_bailiff")]
+    async fn arrest_many(
+        ctx: Context<'_>,
+        #[description = "Alle mit dieser Rolle einsperren, sonst können Personen angegeben werden"]
+        role: Option<Role>,
+        #[description = "Die Haftstufe"]
+        #[autocomplete = "autocomplete_tier"]
+        tier: Option<String>,
+        #[description = "Wie lange, z.B. 7d oder 12h"] duration: Option<String>,
+        #[description = "Der Grund für die Verhaftung"] reason: Option<String>,
+    ) -> Result<()> {
+        prison_arrest_many_impl(ctx, role, tier, duration, reason)
+            .await
+            .wrap_err("prison_arrest_many")
+    }
+
+    /// Einen Gefangenen freilassen
+    #[poise::command(slash_command, guild_only, check = "is_bailiff")]
+    async fn release(
+        ctx: Context<'_>,
+        #[description = "Die Person zum freilassen"] user: User,
+    ) -> Result<()> {
+        prison_release_impl(ctx, user)
+            .await
+            .wrap_err("prison_release")
+    }
+
+    /// Alle Gefangenen freilassen
+    #[poise::command(slash_command, guild_only, check = "is_bailiff")]
+    async fn release_all(
+        ctx: Context<'_>,
+        #[description = "Nur anzeigen, wer freigelassen würde, ohne etwas zu ändern (Standard: nein)"]
+        dry_run: Option<bool>,
+    ) -> Result<()> {
+        prison_release_all_impl(ctx, dry_run.unwrap_or(false) || ctx.data().dry_run)
+            .await
+            .wrap_err("prison_release_all")
+    }
+
+    /// Die Haftstrafe eines Gefangenen verlängern
+    #[poise::command(slash_command, guild_only, check = "is_bailiff")]
+    async fn extend(
+        ctx: Context<'_>,
+        #[description = "Der Gefangene"] user: User,
+        #[description = "Um wie viel, z.B. 7d oder 12h"] duration: String,
+    ) -> Result<()> {
+        prison_adjust_sentence_impl(ctx, user, duration, SentenceChange::Extend)
+            .await
+            .wrap_err("prison_extend")
+    }
+
+    /// Die Haftstrafe eines Gefangenen verkürzen
+    #[poise::command(slash_command, guild_only, check = "is_bailiff")]
+    async fn reduce(
+        ctx: Context<'_>,
+        #[description = "Der Gefangene"] user: User,
+        #[description = "Um wie viel, z.B. 7d oder 12h"] duration: String,
+    ) -> Result<()> {
+        prison_adjust_sentence_impl(ctx, user, duration, SentenceChange::Reduce)
+            .await
+            .wrap_err("prison_reduce")
+    }
+
+    /// Einen Gefangenen auf Bewährung freilassen
+    #[poise::command(slash_command, guild_only, check = "is_bailiff")]
+    async fn parole(
+        ctx: Context<'_>,
+        #[description = "Der Gefangene"] user: User,
+        #[description = "Wie lange die Bewährung dauert, z.B. 7d oder 12h"] duration: String,
+    ) -> Result<()> {
+        prison_parole_impl(ctx, user, duration)
+            .await
+            .wrap_err("prison_parole")
+    }
+
+    /// Die Bewährung widerrufen und die Person wieder einsperren
+    #[poise::command(slash_command, guild_only, check = "is_bailiff")]
+    async fn revoke_parole(
+        ctx: Context<'_>,
+        #[description = "Die Person auf Bewährung"] user: User,
+    ) -> Result<()> {
+        prison_revoke_parole_impl(ctx, user)
+            .await
+            .wrap_err("prison_revoke_parole")
+    }
+
+    /// Gegen die eigene Verhaftung Berufung einlegen
+    #[poise::command(slash_command, guild_only)]
+    async fn appeal(
+        ctx: Context<'_>,
+        #[description = "Wieso die Verhaftung ungerecht ist"] reason: String,
+    ) -> Result<()> {
+        prison_appeal_impl(ctx, reason)
+            .await
+            .wrap_err("prison_appeal")
+    }
+
+    /// Alle Gefangenen auflisten
+    #[poise::command(slash_command, guild_only)]
+    async fn list(ctx: Context<'_>) -> Result<()> {
+        prison_list_impl(ctx).await.wrap_err("prison_list")
+    }
+
+    /// Nachschauen, ob eine Person eingesperrt ist und wann sie freikommt
+    #[poise::command(slash_command, guild_only)]
+    async fn check(
+        ctx: Context<'_>,
+        #[description = "Die Person zum nachschauen"] user: User,
+    ) -> Result<()> {
+        prison_check_impl(ctx, user).await.wrap_err("prison_check")
+    }
+
+    /// Statistiken über das Gefängnis anzeigen
+    #[poise::command(slash_command, guild_only)]
+    async fn stats(ctx: Context<'_>) -> Result<()> {
+        prison_stats_impl(ctx).await.wrap_err("prison_stats")
+    }
+
+    #[tracing::instrument(skip(ctx))]
+    async fn prison_set_tier_impl(ctx: Context<'_>, name: String, role: Role) -> Result<()> {
+        let strings = i18n::strings(ctx).await?;
+        let storage = &ctx.data().storage;
+        let guild_id = ctx.guild_id().wrap_err("guild_id not found")?;
+
+        let tier = PrisonTier {
+            name,
+            role_id: role.id.into(),
+        };
+        storage.set_prison_tier(guild_id.into(), &tier).await?;
+
+        let state = storage.find_or_insert_state(guild_id.into()).await?;
+        crate::prison::apply_channel_permissions(&ctx.discord().http, guild_id, &state).await?;
+
+        log_config_change(
+            ctx,
+            strings.setting_prison_tier,
+            format!("{} (<@&{}>)", tier.name, role.id),
+        )
+        .await?;
+        say_is_set(ctx).await?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(ctx))]
+    async fn prison_remove_tier_impl(ctx: Context<'_>, name: String) -> Result<()> {
+        let strings = i18n::strings(ctx).await?;
+        let storage = &ctx.data().storage;
+        let guild_id = ctx.guild_id().wrap_err("guild_id not found")?;
+
+        let state = storage.find_or_insert_state(guild_id.into()).await?;
+        if !state.prison_tiers.iter().any(|tier| tier.name == name) {
+            ctx.say(strings.tier_not_found).await?;
+            return Ok(());
+        }
+
+        storage.remove_prison_tier(guild_id.into(), &name).await?;
+
+        log_config_change(
+            ctx,
+            strings.setting_prison_tier,
+            (strings.setting_tier_removed)(&name),
+        )
+        .await?;
+        ctx.say(strings.removed).await.wrap_err("reply")?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(ctx))]
+    async fn prison_arrest_impl(
+        ctx: Context<'_>,
+        user: User,
+        tier: Option<String>,
+        duration: Option<String>,
+        reason: Option<String>,
+    ) -> Result<()> {
+        let strings = i18n::strings(ctx).await?;
+        let storage = &ctx.data().storage;
+        let guild_id = ctx.guild_id().wrap_err("guild_id not found")?;
+        let http = &ctx.discord().http;
+
+        let release_at = match duration.as_deref().map(duration::parse) {
+            Some(Some(duration)) => Some(duration::from_now(duration)),
+            Some(None) => {
+                ctx.say(strings.invalid_duration).await?;
+                return Ok(());
+            }
+            None => None,
+        };
+
+        let state = storage.find_or_insert_state(guild_id.into()).await?;
+
+        let response = crate::prison::arrest(
+            http,
+            storage,
+            &state,
+            guild_id,
+            user.id,
+            crate::prison::Sentence {
+                tier: tier.as_deref(),
+                release_at,
+                reason: reason.as_deref(),
+                ordered_by: Some(ctx.author().id),
+            },
+        )
+        .await?;
+
+        if let Err(response) = response {
+            ctx.say(response.to_string()).await?;
+            return Ok(());
+        }
+
+        let mention = format!("<@{}>", user.id);
+        let response = match release_at {
+            Some(release_at) => templates::render(
+                &state,
+                Template::ArrestedUntil,
+                &[
+                    ("user", mention),
+                    ("time", duration::relative_timestamp(release_at)),
+                ],
+            ),
+            None => templates::render(&state, Template::Arrested, &[("user", mention)]),
+        };
+        ctx.say(response).await?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(ctx))]
+    async fn prison_arrest_many_impl(
+        ctx: Context<'_>,
+        role: Option<Role>,
+        tier: Option<String>,
+        duration: Option<String>,
+        reason: Option<String>,
+    ) -> Result<()> {
+        let strings = i18n::strings(ctx).await?;
+        let storage = &ctx.data().storage;
+        let guild_id = ctx.guild_id().wrap_err("guild_id not found")?;
+        let http = &ctx.discord().http;
+
+        let release_at = match duration.as_deref().map(duration::parse) {
+            Some(Some(duration)) => Some(duration::from_now(duration)),
+            Some(None) => {
+                ctx.say(strings.invalid_duration).await?;
+                return Ok(());
+            }
+            None => None,
+        };
+
+        let user_ids = match role {
+            Some(role) => {
+                ctx.defer().await?;
+                crate::prison::members_with_role(http, guild_id, role.id).await?
+            }
+            None => {
+                let application_context = match ctx {
+                    Context::Application(ctx) => ctx,
+                    Context::Prefix(_) => return Err(eyre!("wrong context, cannot happen!")),
+                };
+
+                let modal = ArrestManyModal::execute(application_context)
+                    .await
+                    .wrap_err("arrest many modal")?;
+
+                modal
+                    .users
+                    .split_whitespace()
+                    .filter_map(|user| {
+                        poise::serenity::utils::parse_username(user).or_else(|| user.parse().ok())
+                    })
+                    .map(UserId)
+                    .collect::<Vec<_>>()
+            }
+        };
+
+        if user_ids.is_empty() {
+            ctx.say(strings.nobody_to_arrest).await?;
+            return Ok(());
+        }
+
+        let state = storage.find_or_insert_state(guild_id.into()).await?;
+
+        let response = crate::prison::arrest_many(
+            http,
+            storage,
+            &state,
+            guild_id,
+            &user_ids,
+            crate::prison::Sentence {
+                tier: tier.as_deref(),
+                release_at,
+                reason: reason.as_deref(),
+                ordered_by: Some(ctx.author().id),
+            },
+        )
+        .await?;
+
+        match response {
+            Ok(batch) => {
+                ctx.say(batch_message(strings, strings.arrested_many, &batch))
+                    .await?
+            }
+            Err(response) => ctx.say(response.to_string()).await?,
+        };
+
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(ctx))]
+    async fn prison_release_impl(ctx: Context<'_>, user: User) -> Result<()> {
+        let storage = &ctx.data().storage;
+        let guild_id = ctx.guild_id().wrap_err("guild_id not found")?;
+        let http = &ctx.discord().http;
+
+        let state = storage.find_or_insert_state(guild_id.into()).await?;
+
+        let response = crate::prison::release(
+            http,
+            storage,
+            &state,
+            guild_id,
+            user.id,
+            Some(ctx.author().id),
+        )
+        .await?;
+
+        if let Err(response) = response {
+            ctx.say(response.to_string()).await?;
+            return Ok(());
+        }
+
+        ctx.say(templates::render(
+            &state,
+            Template::Released,
+            &[("user", format!("<@{}>", user.id))],
+        ))
+        .await?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(ctx))]
+    async fn prison_release_all_impl(ctx: Context<'_>, dry_run: bool) -> Result<()> {
+        // the preview would get too long for a message otherwise
+        const MAX_PREVIEW_USERS: usize = 50;
+
+        let strings = i18n::strings(ctx).await?;
+        let storage = &ctx.data().storage;
+        let guild_id = ctx.guild_id().wrap_err("guild_id not found")?;
+        let http = &ctx.discord().http;
+
+        if dry_run {
+            let entries = storage.find_prison_entries(guild_id.into()).await?;
+            let mut users = entries
+                .iter()
+                .take(MAX_PREVIEW_USERS)
+                .map(|entry| format!("<@{}>", entry.user_id))
+                .collect::<Vec<_>>()
+                .join(" ");
+            if entries.len() > MAX_PREVIEW_USERS {
+                users.push_str(" …");
+            }
+            ctx.say((strings.released_many_dry_run)(entries.len(), &users))
+                .await?;
+            return Ok(());
+        }
+
+        ctx.defer().await?;
+
+        let state = storage.find_or_insert_state(guild_id.into()).await?;
+
+        let batch =
+            crate::prison::release_all(http, storage, &state, guild_id, ctx.author().id).await?;
+
+        ctx.say(batch_message(strings, strings.released_many, &batch))
+            .await?;
+
+        Ok(())
+    }
+
+    #[derive(Debug)]
+    enum SentenceChange {
+        Extend,
+        Reduce,
+    }
+
+    #[tracing::instrument(skip(ctx))]
+    async fn prison_adjust_sentence_impl(
+        ctx: Context<'_>,
+        user: User,
+        duration: String,
+        change: SentenceChange,
+    ) -> Result<()> {
+        let strings = i18n::strings(ctx).await?;
+        let storage = &ctx.data().storage;
+        let guild_id = ctx.guild_id().wrap_err("guild_id not found")?;
+        let http = &ctx.discord().http;
+
+        let duration = match duration::parse(&duration) {
+            Some(duration) => duration.as_millis() as i64,
+            None => {
+                ctx.say(strings.invalid_duration).await?;
+                return Ok(());
+            }
+        };
+
+        let entry = storage
+            .find_prison_entry(guild_id.into(), user.id.into())
+            .await?;
+
+        let release_at = match entry {
+            None => {
+                ctx.say(strings.not_in_prison).await?;
+                return Ok(());
+            }
+            Some(PrisonEntry {
+                release_at: Some(release_at),
+                ..
+            }) => release_at,
+            Some(_) => {
+                ctx.say(strings.indefinite_sentence).await?;
+                return Ok(());
+            }
+        };
+
+        let release_at = DateTime::from_millis(match change {
+            SentenceChange::Extend => release_at.timestamp_millis() + duration,
+            SentenceChange::Reduce => release_at.timestamp_millis() - duration,
+        });
+
+        if release_at <= DateTime::now() {
+            let state = storage.find_or_insert_state(guild_id.into()).await?;
+            let response = crate::prison::release(
+                http,
+                storage,
+                &state,
+                guild_id,
+                user.id,
+                Some(ctx.author().id),
+            )
+            .await?;
+
+            if let Err(response) = response {
+                ctx.say(response.to_string()).await?;
+                return Ok(());
+            }
+
+            ctx.say(strings.sentence_over).await?;
+            return Ok(());
+        }
+
+        storage
+            .set_release_at(guild_id.into(), user.id.into(), Some(release_at))
+            .await?;
+        storage
+            .schedule_job(
+                guild_id.into(),
+                &JobKind::Release {
+                    user_id: user.id.into(),
+                },
+                release_at,
+            )
+            .await?;
+
+        let state = storage.find_or_insert_state(guild_id.into()).await?;
+        audit::log(
+            http,
+            &state,
+            AuditEvent::SentenceChanged {
+                user_id: user.id,
+                release_at,
+                changed_by: ctx.author().id,
+            },
+        )
+        .await;
+
+        ctx.say((strings.release_moved)(&duration::relative_timestamp(
+            release_at,
+        )))
+        .await?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(ctx))]
+    async fn prison_parole_impl(ctx: Context<'_>, user: User, duration: String) -> Result<()> {
+        let strings = i18n::strings(ctx).await?;
+        let storage = &ctx.data().storage;
+        let guild_id = ctx.guild_id().wrap_err("guild_id not found")?;
+        let http = &ctx.discord().http;
+
+        let duration = match duration::parse(&duration) {
+            Some(duration) => duration,
+            None => {
+                ctx.say(strings.invalid_duration).await?;
+                return Ok(());
+            }
+        };
+
+        let state = storage.find_or_insert_state(guild_id.into()).await?;
+
+        let entry = storage
+            .find_prison_entry(guild_id.into(), user.id.into())
+            .await?;
+
+        let entry = match entry {
+            None => {
+                ctx.say(strings.not_in_prison).await?;
+                return Ok(());
+            }
+            Some(entry) if entry.parole_until.is_some() => {
+                ctx.say(strings.already_on_parole).await?;
+                return Ok(());
+            }
+            Some(entry) => entry,
+        };
+
+        let prison_role = state.prison_role_for(entry.tier.as_deref());
+        let (prison_role, probation_role) = match (prison_role, state.probation_role) {
+            (Some(prison_role), Some(probation_role)) => (prison_role, probation_role),
+            _ => {
+                ctx.say(strings.set_probation_role_first).await?;
+                return Ok(());
+            }
+        };
+
+        let parole_until = duration::from_now(duration);
+
+        storage
+            .set_parole(guild_id.into(), user.id.into(), Some(parole_until))
+            .await?;
+        storage
+            .schedule_job(
+                guild_id.into(),
+                &JobKind::EndParole {
+                    user_id: user.id.into(),
+                },
+                parole_until,
+            )
+            .await?;
+
+        let mut member = guild_id
+            .member(http, user.id)
+            .await
+            .wrap_err("fetching guild member")?;
+        member
+            .remove_role(http, prison_role)
+            .await
+            .wrap_err("remove guild member role")?;
+        member
+            .add_role(http, probation_role)
+            .await
+            .wrap_err("add guild member role")?;
+
+        audit::log(
+            http,
+            &state,
+            AuditEvent::Paroled {
+                user_id: user.id,
+                until: parole_until,
+                ordered_by: ctx.author().id,
+            },
+        )
+        .await;
+
+        ctx.say((strings.on_parole_until)(&duration::relative_timestamp(
+            parole_until,
+        )))
+        .await?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(ctx))]
+    async fn prison_revoke_parole_impl(ctx: Context<'_>, user: User) -> Result<()> {
+        let strings = i18n::strings(ctx).await?;
+        let storage = &ctx.data().storage;
+        let guild_id = ctx.guild_id().wrap_err("guild_id not found")?;
+        let http = &ctx.discord().http;
+
+        let state = storage.find_or_insert_state(guild_id.into()).await?;
+
+        let entry = storage
+            .find_prison_entry(guild_id.into(), user.id.into())
+            .await?;
+
+        let entry = match entry {
+            Some(entry) if entry.parole_until.is_some() => entry,
+            _ => {
+                ctx.say(strings.not_on_parole).await?;
+                return Ok(());
+            }
+        };
+
+        let prison_role = state.prison_role_for(entry.tier.as_deref());
+        let (prison_role, probation_role) = match (prison_role, state.probation_role) {
+            (Some(prison_role), Some(probation_role)) => (prison_role, probation_role),
+            _ => {
+                ctx.say(strings.set_probation_role_first).await?;
+                return Ok(());
+            }
+        };
+
+        storage
+            .set_parole(guild_id.into(), user.id.into(), None)
+            .await?;
+        storage
+            .cancel_job(
+                guild_id.into(),
+                &JobKind::EndParole {
+                    user_id: user.id.into(),
+                },
+            )
+            .await?;
+        // the release job was skipped while they were on parole
+        if let Some(release_at) = entry.release_at {
+            storage
+                .schedule_job(
+                    guild_id.into(),
+                    &JobKind::Release {
+                        user_id: user.id.into(),
+                    },
+                    release_at,
+                )
+                .await?;
+        }
+
+        let mut member = guild_id
+            .member(http, user.id)
+            .await
+            .wrap_err("fetching guild member")?;
+        member
+            .remove_role(http, probation_role)
+            .await
+            .wrap_err("remove guild member role")?;
+        member
+            .add_role(http, prison_role)
+            .await
+            .wrap_err("add guild member role")?;
+
+        audit::log(
+            http,
+            &state,
+            AuditEvent::ParoleRevoked {
+                user_id: user.id,
+                revoked_by: ctx.author().id,
+            },
+        )
+        .await;
+
+        ctx.say(strings.back_to_prison).await?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(ctx))]
+    async fn prison_appeal_impl(ctx: Context<'_>, reason: String) -> Result<()> {
+        let strings = i18n::strings(ctx).await?;
+        let storage = &ctx.data().storage;
+        let guild_id = ctx.guild_id().wrap_err("guild_id not found")?;
+        let prisoner = ctx.author().id;
+
+        let entry = storage
+            .find_prison_entry(guild_id.into(), prisoner.into())
+            .await?;
+
+        let arrested_by = match entry {
+            None => {
+                ctx.say(strings.appeal_not_in_prison).await?;
+                return Ok(());
+            }
+            Some(PrisonEntry {
+                arrested_by: Some(arrested_by),
+                ..
+            }) => arrested_by,
+            Some(_) => {
+                ctx.say(strings.appeal_unknown_arrester).await?;
+                return Ok(());
+            }
+        };
+
+        let state = storage.find_or_insert_state(guild_id.into()).await?;
+
+        let judge = match state.appeal_judge {
+            Some(judge) => judge,
+            None => {
+                ctx.say(strings.appeal_no_judge).await?;
+                return Ok(());
+            }
+        };
+        let invalid = InvalidParties::check(prisoner.into(), arrested_by, Some(judge), &[]);
+        if let Some(invalid) = invalid {
+            ctx.send(|reply| reply.ephemeral(true).content(invalid.describe(strings)))
+                .await?;
+            return Ok(());
+        }
+
+        let already_appealed = storage
+            .find_open_lawsuits(guild_id.into())
+            .await?
+            .iter()
+            .any(|lawsuit| lawsuit.plaintiff == prisoner.into() && lawsuit.accused == arrested_by);
+        if already_appealed {
+            ctx.say(strings.appeal_already_running).await?;
+            return Ok(());
+        }
+
+        let lawsuit = Lawsuit::new(
+            guild_id.into(),
+            prisoner.into(),
+            arrested_by,
+            judge,
+            (strings.appeal_reason)(&reason),
+        );
+
+        let lawsuit_ctx = LawsuitCtx {
+            lawsuit,
+            storage: storage.clone(),
+            http: ctx.discord().http.clone(),
+            tasks: ctx.data().tasks.clone(),
+            guild_id,
+        };
+
+        let (Ok(response) | Err(response)) = lawsuit_ctx
+            .initialize()
+            .await
+            .wrap_err("initialize appeal lawsuit")?;
+
+        ctx.say(response.to_string()).await?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(ctx))]
+    async fn prison_list_impl(ctx: Context<'_>) -> Result<()> {
+        let strings = i18n::strings(ctx).await?;
+        let guild_id = ctx.guild_id().wrap_err("guild_id not found")?;
+
+        let prisoners = ctx
+            .data()
+            .storage
+            .find_prison_entries(guild_id.into())
+            .await?;
+
+        if prisoners.is_empty() {
+            ctx.say(strings.prison_empty).await?;
+            return Ok(());
+        }
+
+        let entries = prisoners
+            .iter()
+            .map(|entry| {
+                let mut line = format!("<@{}>", entry.user_id);
+                if let Some(tier) = &entry.tier {
+                    line.push_str(&format!(" ({tier})"));
+                }
+                match (entry.parole_until, entry.release_at) {
+                    (Some(until), _) => line.push_str(&format!(
+                        " {}",
+                        (strings.list_on_parole_until)(&duration::relative_timestamp(until))
+                    )),
+                    (None, Some(release_at)) => line.push_str(&format!(
+                        " {}",
+                        (strings.list_released_at)(&duration::relative_timestamp(release_at))
+                    )),
+                    (None, None) => {}
+                }
+                line
+            })
+            .collect::<Vec<_>>();
+
+        paginate(ctx, strings.prisoners_title, &entries).await
+    }
+
+    #[tracing::instrument(skip(ctx))]
+    async fn prison_check_impl(ctx: Context<'_>, user: User) -> Result<()> {
+        let strings = i18n::strings(ctx).await?;
+        let guild_id = ctx.guild_id().wrap_err("guild_id not found")?;
+        let storage = &ctx.data().storage;
+
+        let entry = match storage
+            .find_prison_entry(guild_id.into(), user.id.into())
+            .await?
+        {
+            Some(entry) => entry,
+            None => {
+                ctx.say(strings.not_in_prison).await?;
+                return Ok(());
+            }
+        };
+
+        // the record of the current arrest is the one that isn't closed yet
+        let record = storage
+            .find_prison_records(guild_id.into())
+            .await?
+            .into_iter()
+            .filter(|record| record.user_id == entry.user_id && record.released_at.is_none())
+            .max_by_key(|record| record.arrested_at);
+
+        // the scheduler knows when it really happens, the entry could be out of date
+        let job = match entry.parole_until {
+            Some(_) => JobKind::EndParole {
+                user_id: entry.user_id,
+            },
+            None => JobKind::Release {
+                user_id: entry.user_id,
+            },
+        };
+        let run_at = storage
+            .find_jobs(guild_id.into())
+            .await?
+            .into_iter()
+            .find(|scheduled| scheduled.kind == job)
+            .map(|scheduled| scheduled.run_at);
+        let sentence = match entry.parole_until {
+            Some(until) => (strings.list_on_parole_until)(&duration::relative_timestamp(
+                run_at.unwrap_or(until),
+            )),
+            None => match run_at.or(entry.release_at) {
+                Some(release_at) => {
+                    (strings.list_released_at)(&duration::relative_timestamp(release_at))
+                }
+                None => strings.indefinite_sentence.to_owned(),
+            },
+        };
+
+        let arrested_at = record.as_ref().map_or_else(
+            || strings.none.to_owned(),
+            |record| duration::relative_timestamp(record.arrested_at),
+        );
+        let reason = record
+            .and_then(|record| record.reason)
+            .unwrap_or_else(|| strings.none.to_owned());
+        let ordered_by = entry.arrested_by.map_or_else(
+            || strings.none.to_owned(),
+            |user_id| format!("<@{user_id}>"),
+        );
+
+        ctx.send(|reply| {
+            reply.embed(|embed| {
+                embed
+                    .title((strings.prison_check_title)(&user.name))
+                    .field(strings.field_arrested_at, arrested_at, true)
+                    .field(strings.field_ordered_by, ordered_by, true)
+                    .field(strings.field_sentence, sentence, true)
+                    .field(strings.field_reason, reason, false);
+                if let Some(tier) = &entry.tier {
+                    embed.field(strings.field_tier, tier, true);
+                }
+                embed
+            })
+        })
+        .await
+        .wrap_err("reply")?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(ctx))]
+    async fn prison_stats_impl(ctx: Context<'_>) -> Result<()> {
+        let strings = i18n::strings(ctx).await?;
+        let guild_id = ctx.guild_id().wrap_err("guild_id not found")?;
+
+        let stats = ctx.data().storage.prison_stats(guild_id.into()).await?;
+
+        let average_sentence = stats
+            .average_sentence
+            .map(duration::format)
+            .unwrap_or_else(|| strings.none.to_string());
+
+        let top_reasons = if stats.top_reasons.is_empty() {
+            strings.none.to_string()
+        } else {
+            stats
+                .top_reasons
+                .iter()
+                .map(|(reason, count)| format!("{reason} ({count}x)"))
+                .collect::<Vec<_>>()
+                .join("\n")
+        };
+
+        ctx.send(|reply| {
+            reply.embed(|embed| {
+                embed
+                    .title(strings.stats_title)
+                    .field(strings.stats_current, stats.current_prisoners, true)
+                    .field(strings.stats_total, stats.total_arrests, true)
+                    .field(strings.stats_average_sentence, average_sentence, true)
+                    .field(strings.stats_top_reasons, top_reasons, false)
+            })
+        })
+        .await
+        .wrap_err("reply")?;
+
+        Ok(())
+    }
+}
+
+pub mod config {
+    use super::*;
+
+    /// Die Einstellungen des Servers
+    #[poise::command(
+        slash_command,
+        guild_only,
+        subcommands(
+            "show",
+            "set",
+            "messages",
+            "export",
+            "import",
+            "api_token",
+            "calendar_token"
+        )
+    )]
+    pub async fn config(_: Context<'_>) -> Result<()> {
+        unreachable!()
+    }
+
+    /// Eine Einstellung ändern
+    #[poise::command(
+        slash_command,
+        guild_only,
+        subcommands(
+            "court_category",
+            "appeal_category",
+            "prison_role",
+            "probation_role",
+            "prison_channel",
+            "strip_roles",
+            "moderator_channel",
+            "notifications",
+            "strict_lawyers",
+            "conflicts",
+            "appeal_judge",
+            "audit_channel",
+            "error_channel",
+            "records_channel",
+            "news_role",
+            "weekly_report",
+            "statute_of_limitations",
+            "inactivity_nudge",
+            "lawsuit_limit",
+            "filing_cooldown",
+            "slowmode",
+            "role",
+            "locale",
+            "webhook"
+        )
+    )]
+    async fn set(_: Context<'_>) -> Result<()> {
+        unreachable!()
+    }
+
+    /// Alle Einstellungen anzeigen
+    #[poise::command(slash_command, guild_only, check = "is_admin")]
+    async fn show(ctx: Context<'_>) -> Result<()> {
+        config_show_impl(ctx).await.wrap_err("config_show")
+    }
+
+    /// Eigene Antworten des Bots festlegen
+    #[poise::command(
+        slash_command,
+        guild_only,
+        subcommands("messages_set", "messages_reset", "messages_list")
+    )]
+    async fn messages(_: Context<'_>) -> Result<()> {
+        unreachable!()
+    }
+
+    /// Eine Antwort durch einen eigenen Text ersetzen
+    #[poise::command(slash_command, guild_only, check = "is_admin", rename = "set")]
+    async fn messages_set(
+        ctx: Context<'_>,
+        #[description = "Die Antwort"] key: Template,
+        #[description = "Der Text, Platzhalter wie {user} werden ersetzt"] text: String,
+    ) -> Result<()> {
+        config_messages_set_impl(ctx, key, Some(text))
+            .await
+            .wrap_err("config_messages_set")
+    }
+
+    /// Wieder die normale Antwort verwenden
+    #[poise::command(slash_command, guild_only, check = "is_admin", rename = "reset")]
+    async fn messages_reset(
+        ctx: Context<'_>,
+        #[description = "Die Antwort"] key: Template,
+    ) -> Result<()> {
+        config_messages_set_impl(ctx, key, None)
+            .await
+            .wrap_err("config_messages_reset")
+    }
+
+    /// Alle Antworten mit ihren Platzhaltern anzeigen
+    #[poise::command(slash_command, guild_only, check = "is_admin", rename = "list")]
+    async fn messages_list(ctx: Context<'_>) -> Result<()> {
+        config_messages_list_impl(ctx)
+            .await
+            .wrap_err("config_messages_list")
+    }
+
+    /// Alle Daten des Servers als JSON-Datei exportieren
+    #[poise::command(slash_command, guild_only, check = "is_admin")]
+    async fn export(ctx: Context<'_>) -> Result<()> {
+        config_export_impl(ctx).await.wrap_err("config_export")
+    }
+
+    /// Exportierte Daten wieder importieren
+    #[poise::command(slash_command, guild_only, check = "is_admin")]
+    async fn import(
+        ctx: Context<'_>,
+        #[description = "Die exportierte JSON-Datei"] file: Attachment,
+        #[description = "Ob die Daten ersetzt oder zusammengeführt werden"] mode: ImportMode,
+        #[description = "Nur eine Vorschau anzeigen, ohne etwas zu ändern (Standard: ja)"]
+        dry_run: Option<bool>,
+    ) -> Result<()> {
+        config_import_impl(ctx, file, mode, dry_run.unwrap_or(true))
+            .await
+            .wrap_err("config_import")
+    }
+
+    /// Einen neuen Token für die API erstellen, der alte Token wird dabei ungültig
+    #[poise::command(slash_command, guild_only, check = "is_admin")]
+    async fn api_token(
+        ctx: Context<'_>,
+        #[description = "Nur den alten Token entfernen"] revoke: Option<bool>,
+    ) -> Result<()> {
+        config_api_token_impl(ctx, revoke.unwrap_or(false))
+            .await
+            .wrap_err("config_api_token")
+    }
+
+    /// Einen neuen Link für den Kalender der Verhandlungen erstellen, der alte Link wird ungültig
+    #[poise::command(slash_command, guild_only, check = "is_admin")]
+    async fn calendar_token(
+        ctx: Context<'_>,
+        #[description = "Nur den alten Link entfernen"] revoke: Option<bool>,
+    ) -> Result<()> {
+        config_calendar_token_impl(ctx, revoke.unwrap_or(false))
+            .await
+            .wrap_err("config_calendar_token")
+    }
+
+    /// Die Kategorie für die Gerichtsräume setzen
+    #[poise::command(slash_command, guild_only, check = "is_admin")]
+    async fn court_category(
+        ctx: Context<'_>,
+        #[description = "Die Kategorie"] category: Channel,
+    ) -> Result<()> {
+        config_set_court_category_impl(ctx, category)
+            .await
+            .wrap_err("config_set_court_category")
+    }
+
+    /// Eine eigene Kategorie für die Gerichtsräume der Berufungen setzen
+    #[poise::command(slash_command, guild_only, check = "is_admin")]
+    async fn appeal_category(
+        ctx: Context<'_>,
+        #[description = "Die Kategorie"] category: Channel,
+        #[description = "Der Name der Räume vor ihrer Nummer (Standard: berufungsraum)"]
+        room_name: Option<String>,
+    ) -> Result<()> {
+        config_set_appeal_category_impl(ctx, category, room_name)
+            .await
+            .wrap_err("config_set_appeal_category")
+    }
+
+    /// Die Rolle für Gefangene setzen
+    #[poise::command(slash_command, guild_only, check = "is_admin")]
+    async fn prison_role(ctx: Context<'_>, #[description = "Die Rolle"] role: Role) -> Result<()> {
+        config_set_prison_role_impl(ctx, role)
+            .await
+            .wrap_err("config_set_prison_role")
+    }
+
+    /// Die Rolle für Gefangene auf Bewährung setzen
+    #[poise::command(slash_command, guild_only, check = "is_admin")]
+    async fn probation_role(
+        ctx: Context<'_>,
+        #[description = "Die Rolle"] role: Role,
+    ) -> Result<()> {
+        config_set_probation_role_impl(ctx, role)
+            .await
+            .wrap_err("config_set_probation_role")
+    }
+
+    /// Den Kanal für Gefangene setzen
+    #[poise::command(slash_command, guild_only, check = "is_admin")]
+    async fn prison_channel(
+        ctx: Context<'_>,
+        #[description = "Der Kanal"] channel: GuildChannel,
+        #[description = "Gefangenen das Schreiben in allen anderen Kanälen verbieten"]
+        lockdown: Option<bool>,
+    ) -> Result<()> {
+        config_set_prison_channel_impl(ctx, channel, lockdown.unwrap_or(false))
+            .await
+            .wrap_err("config_set_prison_channel")
+    }
+
+    /// Einstellen, ob Gefangenen alle anderen Rollen weggenommen werden
+    #[poise::command(slash_command, guild_only, check = "is_admin")]
+    async fn strip_roles(
+        ctx: Context<'_>,
+        #[description = "Rollen beim Einsperren entfernen und beim Freilassen zurückgeben"]
+        enabled: bool,
+    ) -> Result<()> {
+        config_set_strip_roles_impl(ctx, enabled)
+            .await
+            .wrap_err("config_set_strip_roles")
+    }
+
+    /// Den Kanal setzen, in dem die Moderatoren über Ausbrüche informiert werden
+    #[poise::command(slash_command, guild_only, check = "is_admin")]
+    async fn moderator_channel(
+        ctx: Context<'_>,
+        #[description = "Der Kanal"] channel: GuildChannel,
+    ) -> Result<()> {
+        config_set_moderator_channel_impl(ctx, channel)
+            .await
+            .wrap_err("config_set_moderator_channel")
+    }
+
+    /// Einstellen, ob Gefangene beim Einsperren und Freilassen eine Direktnachricht bekommen
+    #[poise::command(slash_command, guild_only, check = "is_admin")]
+    async fn notifications(
+        ctx: Context<'_>,
+        #[description = "Gefangenen eine Direktnachricht schicken"] enabled: bool,
+    ) -> Result<()> {
+        config_set_notifications_impl(ctx, enabled)
+            .await
+            .wrap_err("config_set_notifications")
+    }
+
+    /// Festlegen, ob nur zugelassene Anwälte die Parteien vertreten können
+    #[poise::command(slash_command, guild_only, check = "is_admin")]
+    async fn strict_lawyers(
+        ctx: Context<'_>,
+        #[description = "Nur Anwälte zulassen, die die Anwaltsprüfung bestanden haben"]
+        enabled: bool,
+    ) -> Result<()> {
+        config_set_strict_lawyers_impl(ctx, enabled)
+            .await
+            .wrap_err("config_set_strict_lawyers")
+    }
+
+    /// Festlegen, was bei Interessenkonflikten in neuen Prozessen passiert
+    #[poise::command(slash_command, guild_only, check = "is_admin")]
+    async fn conflicts(
+        ctx: Context<'_>,
+        #[description = "Den Prozess ablehnen, nur warnen oder erlauben"] policy: ConflictPolicy,
+    ) -> Result<()> {
+        config_set_conflicts_impl(ctx, policy)
+            .await
+            .wrap_err("config_set_conflicts")
+    }
+
+    /// Den Richter für Berufungen und Klagen über das Kontextmenü setzen
+    #[poise::command(slash_command, guild_only, check = "is_admin")]
+    async fn appeal_judge(
+        ctx: Context<'_>,
+        #[description = "Der Richter"] judge: User,
+    ) -> Result<()> {
+        config_set_appeal_judge_impl(ctx, judge)
+            .await
+            .wrap_err("config_set_appeal_judge")
+    }
+
+    /// Den Kanal setzen, in dem alle Aktionen des Bots protokolliert werden
+    #[poise::command(slash_command, guild_only, check = "is_admin")]
+    async fn audit_channel(
+        ctx: Context<'_>,
+        #[description = "Der Kanal"] channel: GuildChannel,
+    ) -> Result<()> {
+        config_set_audit_channel_impl(ctx, channel)
+            .await
+            .wrap_err("config_set_audit_channel")
+    }
+
+    /// Den Kanal setzen, in dem Fehler bei Befehlen gemeldet werden
+    #[poise::command(slash_command, guild_only, check = "is_admin")]
+    async fn error_channel(
+        ctx: Context<'_>,
+        #[description = "Der Kanal"] channel: GuildChannel,
+    ) -> Result<()> {
+        config_set_error_channel_impl(ctx, channel)
+            .await
+            .wrap_err("config_set_error_channel")
+    }
+
+    /// Den Kanal setzen, in dem die Urteile als öffentliche Akten veröffentlicht werden
+    #[poise::command(slash_command, guild_only, check = "is_admin")]
+    async fn records_channel(
+        ctx: Context<'_>,
+        #[description = "Der Kanal"] channel: GuildChannel,
+    ) -> Result<()> {
+        config_set_records_channel_impl(ctx, channel)
+            .await
+            .wrap_err("config_set_records_channel")
+    }
+
+    /// Die Rolle setzen, die bei neuen Prozessen und Urteilen im Akten-Kanal erwähnt wird
+    #[poise::command(slash_command, guild_only, check = "is_admin")]
+    async fn news_role(
+        ctx: Context<'_>,
+        #[description = "Die Rolle, leer lassen um niemanden zu erwähnen"] role: Option<Role>,
+    ) -> Result<()> {
+        config_set_news_role_impl(ctx, role)
+            .await
+            .wrap_err("config_set_news_role")
+    }
+
+    /// Den Kanal setzen, in dem jede Woche eine Zusammenfassung gepostet wird
+    #[poise::command(slash_command, guild_only, check = "is_admin")]
+    async fn weekly_report(
+        ctx: Context<'_>,
+        #[description = "Der Kanal, leer lassen um die Zusammenfassung abzuschalten"]
+        channel: Option<GuildChannel>,
+    ) -> Result<()> {
+        config_set_weekly_report_impl(ctx, channel)
+            .await
+            .wrap_err("config_set_weekly_report")
+    }
+
+    /// Festlegen, nach welcher Zeit offene Prozesse eingestellt werden
+    #[poise::command(slash_command, guild_only, check = "is_admin")]
+    async fn statute_of_limitations(
+        ctx: Context<'_>,
+        #[description = "Die Zeit wie 30d, leer lassen um Prozesse nie einzustellen"]
+        max_age: Option<String>,
+    ) -> Result<()> {
+        config_set_statute_of_limitations_impl(ctx, max_age)
+            .await
+            .wrap_err("config_set_statute_of_limitations")
+    }
+
+    /// Festlegen, nach welcher Zeit ohne Nachricht im Gerichtssaal der Richter erinnert wird
+    #[poise::command(slash_command, guild_only, check = "is_admin")]
+    async fn inactivity_nudge(
+        ctx: Context<'_>,
+        #[description = "Die Zeit wie 1h, die Admins werden nach der doppelten Zeit erinnert"]
+        after: Option<String>,
+    ) -> Result<()> {
+        config_set_inactivity_nudge_impl(ctx, after)
+            .await
+            .wrap_err("config_set_inactivity_nudge")
+    }
+
+    /// Festlegen, bei wie vielen laufenden Prozessen eine Person gleichzeitig Kläger sein kann
+    #[poise::command(slash_command, guild_only, check = "is_admin")]
+    async fn lawsuit_limit(
+        ctx: Context<'_>,
+        #[description = "Die Anzahl, leer oder 0 für unbeschränkt"] count: Option<u32>,
+    ) -> Result<()> {
+        config_set_lawsuit_limit_impl(ctx, count)
+            .await
+            .wrap_err("config_set_lawsuit_limit")
+    }
+
+    /// Festlegen, wie lange eine Person zwischen zwei Klagen warten muss, Admins sind ausgenommen
+    #[poise::command(slash_command, guild_only, check = "is_admin")]
+    async fn filing_cooldown(
+        ctx: Context<'_>,
+        #[description = "Die Zeit wie 1h, leer lassen damit niemand zwischen Klagen warten muss"]
+        cooldown: Option<String>,
+    ) -> Result<()> {
+        config_set_filing_cooldown_impl(ctx, cooldown)
+            .await
+            .wrap_err("config_set_filing_cooldown")
+    }
+
+    /// Festlegen, welchen Slowmode die Gerichtssäle haben, solange ein Prozess läuft
+    #[poise::command(slash_command, guild_only, check = "is_admin")]
+    async fn slowmode(
+        ctx: Context<'_>,
+        #[description = "Die Zeit wie 30s, höchstens 6h, leer lassen für keinen Slowmode"]
+        interval: Option<String>,
+    ) -> Result<()> {
+        config_set_slowmode_impl(ctx, interval)
+            .await
+            .wrap_err("config_set_slowmode")
+    }
+
+    /// Die Rolle setzen, die Zugriff auf einen Teil der Befehle gibt
+    #[poise::command(slash_command, guild_only, check = "is_admin")]
+    async fn role(
+        ctx: Context<'_>,
+        #[description = "Wofür die Rolle berechtigt"] kind: CourtRole,
+        #[description = "Die Rolle"] role: Role,
+    ) -> Result<()> {
+        config_set_role_impl(ctx, kind, role)
+            .await
+            .wrap_err("config_set_role")
+    }
+
+    /// Die Sprache der Antworten des Bots setzen
+    #[poise::command(slash_command, guild_only, check = "is_admin")]
+    async fn locale(ctx: Context<'_>, #[description = "Die Sprache"] locale: Locale) -> Result<()> {
+        config_set_locale_impl(ctx, locale)
+            .await
+            .wrap_err("config_set_locale")
+    }
+
+    /// Eine URL setzen, an die Ereignisse wie Verhaftungen als JSON geschickt werden
+    #[poise::command(slash_command, guild_only, check = "is_admin")]
+    async fn webhook(
+        ctx: Context<'_>,
+        #[description = "Die URL, leer lassen um den Webhook zu entfernen"] url: Option<String>,
+    ) -> Result<()> {
+        config_set_webhook_impl(ctx, url)
+            .await
+            .wrap_err("config_set_webhook")
+    }
+
+    #[tracing::instrument(skip(ctx))]
+    async fn config_show_impl(ctx: Context<'_>) -> Result<()> {
+        let guild_id = ctx.guild_id().wrap_err("guild_id not found")?;
+
+        let state = ctx
+            .data()
+            .storage
+            .find_or_insert_state(guild_id.into())
+            .await?;
+        let strings = state.locale.strings();
+
+        let channel =
+            |id: Option<SnowflakeId>| id.map_or(strings.none.to_string(), |id| format!("<#{id}>"));
+        let role =
+            |id: Option<SnowflakeId>| id.map_or(strings.none.to_string(), |id| format!("<@&{id}>"));
+        let yes_no = |value: bool| if value { strings.yes } else { strings.no };
+
+        let court_category = match state.appeal_category {
+            Some(id) => format!(
+                "{}\n{}: <#{id}>",
+                channel(state.court_category),
+                strings.setting_appeal_category
+            ),
+            None => channel(state.court_category),
+        };
+        let prison_channel = match state.prison_channel {
+            Some(id) if state.prison_lockdown => format!("<#{id}> (Lockdown)"),
+            id => channel(id),
+        };
+        let tiers = if state.prison_tiers.is_empty() {
+            strings.none.to_string()
+        } else {
+            state
+                .prison_tiers
+                .iter()
+                .map(|tier| format!("{} (<@&{}>)", tier.name, tier.role_id))
+                .collect::<Vec<_>>()
+                .join("\n")
+        };
+
+        ctx.send(|reply| {
+            reply.embed(|embed| {
+                embed
+                    .title(strings.config_title)
+                    .field(strings.setting_court_category, court_category, true)
+                    .field(strings.setting_prison_role, role(state.prison_role), true)
+                    .field(
+                        strings.setting_probation_role,
+                        role(state.probation_role),
+                        true,
+                    )
+                    .field(strings.setting_prison_channel, prison_channel, true)
+                    .field(
+                        strings.setting_strip_roles,
+                        yes_no(state.strip_roles_on_arrest),
+                        true,
+                    )
+                    .field(
+                        strings.setting_moderator_channel,
+                        channel(state.moderator_channel),
+                        true,
+                    )
+                    .field(
+                        strings.setting_notifications,
+                        yes_no(state.notify_prisoners),
+                        true,
+                    )
+                    .field(
+                        strings.setting_strict_lawyers,
+                        yes_no(state.strict_lawyers),
+                        true,
+                    )
+                    .field(
+                        strings.setting_conflict_policy,
+                        state.conflict_policy.to_string(),
+                        true,
+                    )
+                    .field(
+                        strings.setting_appeal_judge,
+                        state
+                            .appeal_judge
+                            .map_or(strings.none.to_string(), |id| format!("<@{id}>")),
+                        true,
+                    )
+                    .field(
+                        strings.setting_audit_channel,
+                        channel(state.audit_channel),
+                        true,
+                    )
+                    .field(
+                        strings.setting_error_channel,
+                        channel(state.error_channel),
+                        true,
+                    )
+                    .field(
+                        strings.setting_records_channel,
+                        channel(state.records_channel),
+                        true,
+                    )
+                    .field(strings.setting_news_role, role(state.news_role), true)
+                    .field(
+                        strings.setting_weekly_report,
+                        channel(state.report_channel),
+                        true,
+                    )
+                    .field(
+                        strings.setting_statute_of_limitations,
+                        state
+                            .statute_of_limitations
+                            .map_or(strings.none.to_string(), |max_age| {
+                                duration::format(Duration::from_secs(max_age))
+                            }),
+                        true,
+                    )
+                    .field(
+                        strings.setting_inactivity_nudge,
+                        state
+                            .inactivity_nudge
+                            .map_or(strings.none.to_string(), |after| {
+                                duration::format(Duration::from_secs(after))
+                            }),
+                        true,
+                    )
+                    .field(
+                        strings.setting_lawsuit_limit,
+                        state
+                            .max_open_lawsuits
+                            .map_or(strings.none.to_string(), |max| max.to_string()),
+                        true,
+                    )
+                    .field(
+                        strings.setting_filing_cooldown,
+                        state
+                            .filing_cooldown
+                            .map_or(strings.none.to_string(), |cooldown| {
+                                duration::format(Duration::from_secs(cooldown))
+                            }),
+                        true,
+                    )
+                    .field(
+                        strings.setting_court_slowmode,
+                        state
+                            .court_slowmode
+                            .map_or(strings.none.to_string(), |slowmode| {
+                                duration::format(Duration::from_secs(slowmode))
+                            }),
+                        true,
+                    )
+                    .field(strings.setting_admin_role, role(state.admin_role), true)
+                    .field(strings.setting_judge_role, role(state.judge_role), true)
+                    .field(strings.setting_bailiff_role, role(state.bailiff_role), true)
+                    .field(strings.setting_locale, state.locale, true)
+                    .field(
+                        strings.setting_webhook,
+                        state
+                            .webhook_url
+                            .as_deref()
+                            .map_or(strings.none, webhook::display_url),
+                        true,
+                    )
+                    .field(
+                        strings.setting_api_token,
+                        yes_no(state.api_token_hash.is_some()),
+                        true,
+                    )
+                    .field(
+                        strings.setting_calendar_token,
+                        yes_no(state.calendar_token_hash.is_some()),
+                        true,
+                    )
+                    .field(strings.setting_prison_tier, tiers, false)
+            })
+        })
+        .await
+        .wrap_err("reply")?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(ctx))]
+    async fn config_export_impl(ctx: Context<'_>) -> Result<()> {
+        let guild_id = ctx.guild_id().wrap_err("guild_id not found")?;
+        let strings = i18n::strings(ctx).await?;
+
+        let data = ctx.data().storage.export_guild(guild_id.into()).await?;
+        let json = serde_json::to_vec_pretty(&data).wrap_err("serialize guild data")?;
+
+        ctx.send(|reply| {
+            reply
+                .content(strings.export_done)
+                .attachment(AttachmentType::Bytes {
+                    data: json.into(),
+                    filename: format!("court-bot-{guild_id}.json"),
+                })
+        })
+        .await
+        .wrap_err("reply")?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(ctx))]
+    async fn config_import_impl(
+        ctx: Context<'_>,
+        file: Attachment,
+        mode: ImportMode,
+        dry_run: bool,
+    ) -> Result<()> {
+        let guild_id = ctx.guild_id().wrap_err("guild_id not found")?;
+        let storage = &ctx.data().storage;
+        let strings = i18n::strings(ctx).await?;
+
+        ctx.defer().await?;
+
+        let bytes = file.download().await.wrap_err("download import file")?;
+        let import = match serde_json::from_slice::<GuildData>(&bytes) {
+            Ok(import) => import,
+            Err(err) => {
+                ctx.say((strings.import_invalid_file)(&err.to_string()))
+                    .await?;
+                return Ok(());
+            }
+        };
+
+        if let Err(response) = backup::validate(strings, guild_id.into(), &import).await {
+            ctx.say(response.to_string()).await?;
+            return Ok(());
+        }
+
+        let current = storage.export_guild(guild_id.into()).await?;
+        let new = backup::combine(&current, import, mode);
+        let summary = backup::summarize(&current, &new);
+
+        if !dry_run {
+            storage.replace_guild(&new).await?;
+            info!(%guild_id, ?mode, "Imported guild data");
+
+            audit::log(
+                &ctx.discord().http,
+                &new.state,
+                AuditEvent::GuildImported {
+                    mode,
+                    imported_by: ctx.author().id,
+                },
+            )
+            .await;
+        }
+
+        let change = |(before, after): (usize, usize)| format!("{before} → {after}");
+        let settings = if summary.settings_changed {
+            strings.import_changed
+        } else {
+            strings.import_unchanged
+        };
+
+        ctx.send(|reply| {
+            reply.embed(|embed| {
+                embed
+                    .title(if dry_run {
+                        strings.import_preview_title
+                    } else {
+                        strings.import_done_title
+                    })
+                    .field(strings.import_lawsuits, change(summary.lawsuits), true)
+                    .field(
+                        strings.import_court_rooms,
+                        change(summary.court_rooms),
+                        true,
+                    )
+                    .field(
+                        strings.import_prison_tiers,
+                        change(summary.prison_tiers),
+                        true,
+                    )
+                    .field(
+                        strings.prisoners_title,
+                        change(summary.prison_entries),
+                        true,
+                    )
+                    .field(
+                        strings.import_prison_history,
+                        change(summary.prison_history),
+                        true,
+                    )
+                    .field(strings.import_jobs, change(summary.jobs), true)
+                    .field(strings.import_members, change(summary.members), true)
+                    .field(strings.config_title, settings, true);
+                if dry_run {
+                    embed.footer(|footer| footer.text(strings.import_dry_run_hint));
+                }
+                embed
+            })
+        })
+        .await
+        .wrap_err("reply")?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(ctx))]
+    async fn config_set_court_category_impl(ctx: Context<'_>, category: Channel) -> Result<()> {
+        let guild_id = ctx.guild_id().wrap_err("guild_id not found")?;
+        let strings = i18n::strings(ctx).await?;
+
+        match category.category() {
+            Some(category) => {
+                let id = category.id;
+                ctx.data()
+                    .storage
+                    .set_court_category(guild_id.into(), id.into())
+                    .await?;
+                log_config_change(ctx, strings.setting_court_category, format!("<#{id}>")).await?;
+                say_is_set(ctx).await?;
+            }
+            None => {
+                ctx.say(strings.not_a_category).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(ctx))]
+    async fn config_set_appeal_category_impl(
+        ctx: Context<'_>,
+        category: Channel,
+        room_name: Option<String>,
+    ) -> Result<()> {
+        let guild_id = ctx.guild_id().wrap_err("guild_id not found")?;
+        let strings = i18n::strings(ctx).await?;
+
+        let category = match category.category() {
+            Some(category) => category,
+            None => {
+                ctx.say(strings.not_a_category).await?;
+                return Ok(());
+            }
+        };
+        // discord only allows lowercase channel names without spaces
+        let room_name = room_name
+            .map(|name| name.trim().to_lowercase().replace(' ', "-"))
+            .filter(|name| !name.is_empty());
+
+        let id = category.id;
+        ctx.data()
+            .storage
+            .set_appeal_category(guild_id.into(), id.into(), room_name.as_deref())
+            .await?;
+        let name = room_name.as_deref().unwrap_or(DEFAULT_APPEAL_ROOM_NAME);
+        log_config_change(
+            ctx,
+            strings.setting_appeal_category,
+            format!("<#{id}> (`{name}-1`)"),
+        )
+        .await?;
+        say_is_set(ctx).await?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(ctx))]
+    async fn config_set_prison_role_impl(ctx: Context<'_>, role: Role) -> Result<()> {
+        let strings = i18n::strings(ctx).await?;
+        let storage = &ctx.data().storage;
+        let guild_id = ctx.guild_id().wrap_err("guild_id not found")?;
+
+        storage
+            .set_prison_role(guild_id.into(), role.id.into(), &RoleTemplate::from(&role))
+            .await?;
+
+        // the prison channel overwrites are for the role, so they have to move to the new role
+        let state = storage.find_or_insert_state(guild_id.into()).await?;
+        crate::prison::apply_channel_permissions(&ctx.discord().http, guild_id, &state).await?;
+
+        log_config_change(ctx, strings.setting_prison_role, format!("<@&{}>", role.id)).await?;
+        say_is_set(ctx).await?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(ctx))]
+    async fn config_set_probation_role_impl(ctx: Context<'_>, role: Role) -> Result<()> {
+        let strings = i18n::strings(ctx).await?;
+        ctx.data()
+            .storage
+            .set_probation_role(
+                ctx.guild_id().wrap_err("guild_id not found")?.into(),
+                role.id.into(),
+            )
+            .await?;
+
+        log_config_change(
+            ctx,
+            strings.setting_probation_role,
+            format!("<@&{}>", role.id),
+        )
+        .await?;
+        say_is_set(ctx).await?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(ctx))]
+    async fn config_set_prison_channel_impl(
+        ctx: Context<'_>,
+        channel: GuildChannel,
+        lockdown: bool,
+    ) -> Result<()> {
+        let strings = i18n::strings(ctx).await?;
+        let storage = &ctx.data().storage;
+        let guild_id = ctx.guild_id().wrap_err("guild_id not found")?;
+
+        let state = storage.find_or_insert_state(guild_id.into()).await?;
+        if state.prison_role.is_none() {
+            ctx.say(strings.set_prison_role_first).await?;
+            return Ok(());
+        }
+
+        storage
+            .set_prison_channel(guild_id.into(), channel.id.into(), lockdown)
+            .await?;
+
+        let state = storage.find_or_insert_state(guild_id.into()).await?;
+        crate::prison::apply_channel_permissions(&ctx.discord().http, guild_id, &state).await?;
+
+        log_config_change(
+            ctx,
+            strings.setting_prison_channel,
+            format!("<#{}> (Lockdown: {lockdown})", channel.id),
+        )
+        .await?;
+        say_is_set(ctx).await?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(ctx))]
+    async fn config_set_strip_roles_impl(ctx: Context<'_>, enabled: bool) -> Result<()> {
+        let strings = i18n::strings(ctx).await?;
+        ctx.data()
+            .storage
+            .set_strip_roles_on_arrest(
+                ctx.guild_id().wrap_err("guild_id not found")?.into(),
+                enabled,
+            )
+            .await?;
+
+        log_config_change(ctx, strings.setting_strip_roles, enabled.to_string()).await?;
+        say_is_set(ctx).await?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(ctx))]
+    async fn config_set_moderator_channel_impl(
+        ctx: Context<'_>,
+        channel: GuildChannel,
+    ) -> Result<()> {
+        let strings = i18n::strings(ctx).await?;
+        ctx.data()
+            .storage
+            .set_moderator_channel(
+                ctx.guild_id().wrap_err("guild_id not found")?.into(),
+                channel.id.into(),
+            )
+            .await?;
+
+        log_config_change(
+            ctx,
+            strings.setting_moderator_channel,
+            format!("<#{}>", channel.id),
+        )
+        .await?;
+        say_is_set(ctx).await?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(ctx))]
+    async fn config_set_notifications_impl(ctx: Context<'_>, enabled: bool) -> Result<()> {
+        let strings = i18n::strings(ctx).await?;
+        ctx.data()
+            .storage
+            .set_notify_prisoners(
+                ctx.guild_id().wrap_err("guild_id not found")?.into(),
+                enabled,
+            )
+            .await?;
+
+        log_config_change(ctx, strings.setting_notifications, enabled.to_string()).await?;
+        say_is_set(ctx).await?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(ctx))]
+    async fn config_set_strict_lawyers_impl(ctx: Context<'_>, enabled: bool) -> Result<()> {
+        let strings = i18n::strings(ctx).await?;
+        ctx.data()
+            .storage
+            .set_strict_lawyers(
+                ctx.guild_id().wrap_err("guild_id not found")?.into(),
+                enabled,
+            )
+            .await?;
+
+        log_config_change(ctx, strings.setting_strict_lawyers, enabled.to_string()).await?;
+        say_is_set(ctx).await?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(ctx))]
+    async fn config_set_conflicts_impl(ctx: Context<'_>, policy: ConflictPolicy) -> Result<()> {
+        let strings = i18n::strings(ctx).await?;
+        ctx.data()
+            .storage
+            .set_conflict_policy(
+                ctx.guild_id().wrap_err("guild_id not found")?.into(),
+                policy,
+            )
+            .await?;
+
+        log_config_change(ctx, strings.setting_conflict_policy, policy.to_string()).await?;
+        say_is_set(ctx).await?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(ctx))]
+    async fn config_set_appeal_judge_impl(ctx: Context<'_>, judge: User) -> Result<()> {
+        let strings = i18n::strings(ctx).await?;
+        ctx.data()
+            .storage
+            .set_appeal_judge(
+                ctx.guild_id().wrap_err("guild_id not found")?.into(),
+                judge.id.into(),
+            )
+            .await?;
+
+        log_config_change(
+            ctx,
+            strings.setting_appeal_judge,
+            format!("<@{}>", judge.id),
+        )
+        .await?;
+        say_is_set(ctx).await?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(ctx))]
+    async fn config_set_audit_channel_impl(ctx: Context<'_>, channel: GuildChannel) -> Result<()> {
+        let strings = i18n::strings(ctx).await?;
+        ctx.data()
+            .storage
+            .set_audit_channel(
+                ctx.guild_id().wrap_err("guild_id not found")?.into(),
+                channel.id.into(),
+            )
+            .await?;
+
+        log_config_change(
+            ctx,
+            strings.setting_audit_channel,
+            format!("<#{}>", channel.id),
+        )
+        .await?;
+        say_is_set(ctx).await?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(ctx))]
+    async fn config_set_error_channel_impl(ctx: Context<'_>, channel: GuildChannel) -> Result<()> {
+        let strings = i18n::strings(ctx).await?;
+        ctx.data()
+            .storage
+            .set_error_channel(
+                ctx.guild_id().wrap_err("guild_id not found")?.into(),
+                channel.id.into(),
+            )
+            .await?;
+
+        log_config_change(
+            ctx,
+            strings.setting_error_channel,
+            format!("<#{}>", channel.id),
+        )
+        .await?;
+        say_is_set(ctx).await?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(ctx))]
+    async fn config_set_records_channel_impl(
+        ctx: Context<'_>,
+        channel: GuildChannel,
+    ) -> Result<()> {
+        let strings = i18n::strings(ctx).await?;
+        ctx.data()
+            .storage
+            .set_records_channel(
+                ctx.guild_id().wrap_err("guild_id not found")?.into(),
+                channel.id.into(),
+            )
+            .await?;
+
+        log_config_change(
+            ctx,
+            strings.setting_records_channel,
+            format!("<#{}>", channel.id),
+        )
+        .await?;
+        say_is_set(ctx).await?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(ctx))]
+    async fn config_set_news_role_impl(ctx: Context<'_>, role: Option<Role>) -> Result<()> {
+        let strings = i18n::strings(ctx).await?;
+        ctx.data()
+            .storage
+            .set_news_role(
+                ctx.guild_id().wrap_err("guild_id not found")?.into(),
+                role.as_ref().map(|role| role.id.into()),
+            )
+            .await?;
+
+        let value = role.map_or(strings.none.to_string(), |role| format!("<@&{}>", role.id));
+        log_config_change(ctx, strings.setting_news_role, value).await?;
+        say_is_set(ctx).await?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(ctx))]
+    async fn config_set_weekly_report_impl(
+        ctx: Context<'_>,
+        channel: Option<GuildChannel>,
+    ) -> Result<()> {
+        let guild_id = ctx.guild_id().wrap_err("guild_id not found")?;
+        let strings = i18n::strings(ctx).await?;
+        let storage = &ctx.data().storage;
+
+        let channel_id = channel.as_ref().map(|channel| channel.id.into());
+        storage
+            .set_report_channel(guild_id.into(), channel_id)
+            .await?;
+        match channel_id {
+            // the first report comes a week after turning it on
+            Some(_) => {
+                storage
+                    .schedule_job(
+                        guild_id.into(),
+                        &JobKind::WeeklyReport,
+                        duration::from_now(report::INTERVAL),
+                    )
+                    .await?
+            }
+            None => {
+                storage
+                    .cancel_job(guild_id.into(), &JobKind::WeeklyReport)
+                    .await?
+            }
+        }
+
+        let value = channel.map_or(strings.none.to_string(), |channel| {
+            format!("<#{}>", channel.id)
+        });
+        log_config_change(ctx, strings.setting_weekly_report, value).await?;
+        say_is_set(ctx).await?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(ctx))]
+    async fn config_set_statute_of_limitations_impl(
+        ctx: Context<'_>,
+        max_age: Option<String>,
+    ) -> Result<()> {
+        let guild_id = ctx.guild_id().wrap_err("guild_id not found")?;
+        let strings = i18n::strings(ctx).await?;
+        let storage = &ctx.data().storage;
+
+        let max_age = match max_age.as_deref().map(duration::parse) {
+            Some(Some(max_age)) => Some(max_age),
+            Some(None) => {
+                ctx.say(strings.invalid_duration).await?;
+                return Ok(());
+            }
+            None => None,
+        };
+
+        storage
+            .set_statute_of_limitations(guild_id.into(), max_age)
+            .await?;
+        match max_age {
+            Some(_) => {
+                storage
+                    .schedule_job(
+                        guild_id.into(),
+                        &JobKind::DismissExpiredLawsuits,
+                        duration::from_now(crate::lawsuit::EXPIRY_CHECK_INTERVAL),
+                    )
+                    .await?
+            }
+            None => {
+                storage
+                    .cancel_job(guild_id.into(), &JobKind::DismissExpiredLawsuits)
+                    .await?
+            }
+        }
+
+        let value = max_age.map_or(strings.none.to_string(), duration::format);
+        log_config_change(ctx, strings.setting_statute_of_limitations, value).await?;
+        say_is_set(ctx).await?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(ctx))]
+    async fn config_set_inactivity_nudge_impl(
+        ctx: Context<'_>,
+        after: Option<String>,
+    ) -> Result<()> {
+        let guild_id = ctx.guild_id().wrap_err("guild_id not found")?;
+        let strings = i18n::strings(ctx).await?;
+        let storage = &ctx.data().storage;
+
+        let after = match after.as_deref().map(duration::parse) {
+            Some(Some(after)) => Some(after),
+            Some(None) => {
+                ctx.say(strings.invalid_duration).await?;
+                return Ok(());
+            }
+            None => None,
+        };
+
+        storage.set_inactivity_nudge(guild_id.into(), after).await?;
+        match after {
+            Some(_) => {
+                storage
+                    .schedule_job(
+                        guild_id.into(),
+                        &JobKind::NudgeInactiveRooms,
+                        duration::from_now(inactivity::CHECK_INTERVAL),
+                    )
+                    .await?
+            }
+            None => {
+                storage
+                    .cancel_job(guild_id.into(), &JobKind::NudgeInactiveRooms)
+                    .await?
+            }
+        }
+
+        let value = after.map_or(strings.none.to_string(), duration::format);
+        log_config_change(ctx, strings.setting_inactivity_nudge, value).await?;
+        say_is_set(ctx).await?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(ctx))]
+    async fn config_set_lawsuit_limit_impl(ctx: Context<'_>, count: Option<u32>) -> Result<()> {
+        let guild_id = ctx.guild_id().wrap_err("guild_id not found")?;
+        let strings = i18n::strings(ctx).await?;
+
+        let max = count.filter(|count| *count > 0);
+        ctx.data()
+            .storage
+            .set_max_open_lawsuits(guild_id.into(), max)
+            .await?;
+
+        let value = max.map_or(strings.none.to_string(), |max| max.to_string());
+        log_config_change(ctx, strings.setting_lawsuit_limit, value).await?;
+        say_is_set(ctx).await?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(ctx))]
+    async fn config_set_filing_cooldown_impl(
+        ctx: Context<'_>,
+        cooldown: Option<String>,
+    ) -> Result<()> {
+        let guild_id = ctx.guild_id().wrap_err("guild_id not found")?;
+        let strings = i18n::strings(ctx).await?;
+
+        let cooldown = match cooldown.as_deref().map(duration::parse) {
+            Some(Some(cooldown)) => Some(cooldown),
+            Some(None) => {
+                ctx.say(strings.invalid_duration).await?;
+                return Ok(());
+            }
+            None => None,
+        };
+
+        ctx.data()
+            .storage
+            .set_filing_cooldown(guild_id.into(), cooldown)
+            .await?;
+
+        let value = cooldown.map_or(strings.none.to_string(), duration::format);
+        log_config_change(ctx, strings.setting_filing_cooldown, value).await?;
+        say_is_set(ctx).await?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(ctx))]
+    async fn config_set_slowmode_impl(ctx: Context<'_>, interval: Option<String>) -> Result<()> {
+        let guild_id = ctx.guild_id().wrap_err("guild_id not found")?;
+        let strings = i18n::strings(ctx).await?;
+
+        let slowmode = match interval.as_deref().map(duration::parse) {
+            Some(Some(slowmode)) if slowmode > crate::lawsuit::MAX_SLOWMODE => {
+                ctx.say((strings.slowmode_too_long)(&duration::format(
+                    crate::lawsuit::MAX_SLOWMODE,
+                )))
+                .await?;
+                return Ok(());
+            }
+            // a slowmode of zero is no slowmode
+            Some(Some(slowmode)) => Some(slowmode).filter(|slowmode| slowmode.as_secs() > 0),
+            Some(None) => {
+                ctx.say(strings.invalid_duration).await?;
+                return Ok(());
+            }
+            None => None,
+        };
+
+        ctx.data()
+            .storage
+            .set_court_slowmode(guild_id.into(), slowmode)
+            .await?;
+
+        let value = slowmode.map_or(strings.none.to_string(), duration::format);
+        log_config_change(ctx, strings.setting_court_slowmode, value).await?;
+        say_is_set(ctx).await?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(ctx))]
+    async fn config_set_role_impl(ctx: Context<'_>, kind: CourtRole, role: Role) -> Result<()> {
+        let strings = i18n::strings(ctx).await?;
+        ctx.data()
+            .storage
+            .set_court_role(
+                ctx.guild_id().wrap_err("guild_id not found")?.into(),
+                kind,
+                role.id.into(),
+            )
+            .await?;
+
+        log_config_change(ctx, kind.label(strings), format!("<@&{}>", role.id)).await?;
+        say_is_set(ctx).await?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(ctx))]
+    async fn config_set_locale_impl(ctx: Context<'_>, locale: Locale) -> Result<()> {
+        ctx.data()
+            .storage
+            .set_locale(
+                ctx.guild_id().wrap_err("guild_id not found")?.into(),
+                locale,
+            )
+            .await?;
+
+        // the new locale is already used for the response
+        let strings = locale.strings();
+        log_config_change(ctx, strings.setting_locale, locale.to_string()).await?;
+        say_is_set(ctx).await?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(ctx))]
+    async fn config_api_token_impl(ctx: Context<'_>, revoke: bool) -> Result<()> {
+        let guild_id = ctx.guild_id().wrap_err("guild_id not found")?;
+        let strings = i18n::strings(ctx).await?;
+        let storage = &ctx.data().storage;
+
+        if revoke {
+            storage.set_api_token_hash(guild_id.into(), None).await?;
+            log_config_change(ctx, strings.setting_api_token, strings.no.to_string()).await?;
+            ctx.say(strings.api_token_revoked).await.wrap_err("reply")?;
+            return Ok(());
+        }
+
+        let (token, hash) = api::generate_token();
+        storage
+            .set_api_token_hash(guild_id.into(), Some(&hash))
+            .await?;
+
+        log_config_change(ctx, strings.setting_api_token, strings.yes.to_string()).await?;
+        ctx.send(|reply| {
+            reply
+                .content((strings.api_token_created)(&token))
+                .ephemeral(true)
+        })
+        .await
+        .wrap_err("reply")?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(ctx))]
+    async fn config_calendar_token_impl(ctx: Context<'_>, revoke: bool) -> Result<()> {
+        let guild_id = ctx.guild_id().wrap_err("guild_id not found")?;
+        let strings = i18n::strings(ctx).await?;
+        let storage = &ctx.data().storage;
+
+        if revoke {
+            storage
+                .set_calendar_token_hash(guild_id.into(), None)
+                .await?;
+            log_config_change(ctx, strings.setting_calendar_token, strings.no.to_string()).await?;
+            ctx.say(strings.calendar_token_revoked)
+                .await
+                .wrap_err("reply")?;
+            return Ok(());
+        }
+
+        // the same kind of token as for the API, but it's part of the URL
+        let (token, hash) = api::generate_token();
+        storage
+            .set_calendar_token_hash(guild_id.into(), Some(&hash))
+            .await?;
+
+        log_config_change(ctx, strings.setting_calendar_token, strings.yes.to_string()).await?;
+        ctx.send(|reply| {
+            reply
+                .content((strings.calendar_token_created)(&calendar::url(
+                    guild_id.into(),
+                    &token,
+                )))
+                .ephemeral(true)
+        })
+        .await
+        .wrap_err("reply")?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(ctx, text))]
+    async fn config_messages_set_impl(
+        ctx: Context<'_>,
+        template: Template,
+        text: Option<String>,
+    ) -> Result<()> {
+        let strings = i18n::strings(ctx).await?;
+
+        if let Some(text) = &text {
+            if let Some(name) = templates::unknown_placeholder(template, text) {
+                let available = template
+                    .placeholders()
+                    .iter()
+                    .map(|name| format!("{{{name}}}"))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                let available = if available.is_empty() {
+                    strings.none.to_string()
+                } else {
+                    available
+                };
+                ctx.say((strings.unknown_placeholder)(name, &available))
+                    .await
+                    .wrap_err("reply")?;
+                return Ok(());
+            }
+        }
+
+        ctx.data()
+            .storage
+            .set_message_template(
+                ctx.guild_id().wrap_err("guild_id not found")?.into(),
+                template,
+                text.as_deref(),
+            )
+            .await?;
+
+        let value = format!(
+            "{}: {}",
+            template.key(),
+            text.as_deref().unwrap_or(strings.message_template_default)
+        );
+        log_config_change(ctx, strings.setting_message_template, value).await?;
+        say_is_set(ctx).await?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(ctx))]
+    async fn config_messages_list_impl(ctx: Context<'_>) -> Result<()> {
+        let guild_id = ctx.guild_id().wrap_err("guild_id not found")?;
+        let state = ctx
+            .data()
+            .storage
+            .find_or_insert_state(guild_id.into())
+            .await?;
+        let strings = state.locale.strings();
+
+        ctx.send(|reply| {
+            reply.ephemeral(true).embed(|embed| {
+                embed.title(strings.message_templates_title);
+                for template in Template::ALL {
+                    let placeholders = template
+                        .placeholders()
+                        .iter()
+                        .map(|name| format!("{{{name}}}"))
+                        .collect::<Vec<_>>()
+                        .join(" ");
+                    let text = match state.message_templates.get(template.key()) {
+                        Some(text) => text.clone(),
+                        None => format!(
+                            "{} {}",
+                            templates::render(&state, template, &[]),
+                            strings.message_template_default
+                        ),
+                    };
+                    embed.field(format!("`{}` {placeholders}", template.key()), text, false);
+                }
+                embed
+            })
+        })
+        .await
+        .wrap_err("reply")?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(ctx, url))]
+    async fn config_set_webhook_impl(ctx: Context<'_>, url: Option<String>) -> Result<()> {
+        let strings = i18n::strings(ctx).await?;
+
+        if let Some(url) = &url {
+            if !webhook::is_valid_url(url).await {
+                ctx.say(strings.invalid_webhook_url)
+                    .await
+                    .wrap_err("reply")?;
+                return Ok(());
+            }
+        }
+
+        ctx.data()
+            .storage
+            .set_webhook_url(
+                ctx.guild_id().wrap_err("guild_id not found")?.into(),
+                url.as_deref(),
+            )
+            .await?;
+
+        let value = url
+            .as_deref()
+            .map_or(strings.none, webhook::display_url)
+            .to_string();
+        log_config_change(ctx, strings.setting_webhook, value).await?;
+        say_is_set(ctx).await?;
+
+        Ok(())
+    }
+}
+
+pub mod privacy {
+    use super::*;
+
+    /// Datenschutz
+    #[poise::command(slash_command, guild_only, subcommands("erase"))]
+    pub async fn privacy(_: Context<'_>) -> Result<()> {
+        unreachable!()
+    }
+
+    /// Alle Daten über eine Person löschen oder anonymisieren
+    #[poise::command(slash_command, guild_only, check = "is_admin")]
+    async fn erase(ctx: Context<'_>, #[description = "Die Person"] user: User) -> Result<()> {
+        privacy_erase_impl(ctx, user)
+            .await
+            .wrap_err("privacy_erase")
+    }
+
+    #[tracing::instrument(skip(ctx))]
+    async fn privacy_erase_impl(ctx: Context<'_>, user: User) -> Result<()> {
+        let guild_id = ctx.guild_id().wrap_err("guild_id not found")?;
+        let storage = &ctx.data().storage;
+
+        let report = storage.erase_user(guild_id.into(), user.id.into()).await?;
+
+        info!(%guild_id, ?report, "Erased user data");
+
+        let state = storage.find_or_insert_state(guild_id.into()).await?;
+        let strings = state.locale.strings();
+
+        audit::log(
+            &ctx.discord().http,
+            &state,
+            AuditEvent::UserErased {
+                erased_by: ctx.author().id,
+            },
+        )
+        .await;
+
+        ctx.send(|reply| {
+            reply.ephemeral(true).embed(|embed| {
+                embed
+                    .title(strings.erase_title)
+                    .field(strings.erase_lawsuits, report.lawsuits, true)
+                    .field(strings.erase_prison_entries, report.prison_entries, true)
+                    .field(strings.import_prison_history, report.prison_records, true)
+                    .field(strings.import_jobs, report.jobs, true)
+                    .field(strings.erase_arrests, report.arrests, true)
+                    .field(
+                        strings.setting_appeal_judge,
+                        if report.appeal_judge {
+                            strings.yes
+                        } else {
+                            strings.no
+                        },
+                        true,
+                    )
+                    .field(
+                        strings.field_balance,
+                        if report.balance {
+                            strings.yes
+                        } else {
+                            strings.no
+                        },
+                        true,
+                    )
+                    .field(
+                        strings.field_lawyer_fee,
+                        if report.lawyer_fee {
+                            strings.yes
+                        } else {
+                            strings.no
+                        },
+                        true,
+                    )
+                    .field(
+                        strings.field_registered_judge,
+                        if report.registered_judge {
+                            strings.yes
+                        } else {
+                            strings.no
+                        },
+                        true,
+                    )
+                    .field(
+                        strings.field_admitted_lawyer,
+                        if report.admitted_lawyer {
+                            strings.yes
+                        } else {
+                            strings.no
+                        },
+                        true,
+                    )
+                    .field(
+                        strings.field_jury_pool,
+                        if report.jury_pool {
+                            strings.yes
+                        } else {
+                            strings.no
+                        },
+                        true,
+                    )
+                    .field(strings.field_elections, report.elections, true)
+            })
+        })
+        .await
+        .wrap_err("reply")?;
+
+        Ok(())
+    }
+}
+
+pub mod fine {
+    use super::*;
+
+    /// Bussen bezahlen
+    #[poise::command(slash_command, guild_only, subcommands("pay"))]
+    pub async fn fine(_: Context<'_>) -> Result<()> {
+        unreachable!()
+    }
+
+    /// Die offenen Bussen begleichen, sobald keine Schulden mehr auf dem Konto sind
+    #[poise::command(slash_command, guild_only)]
+    async fn pay(ctx: Context<'_>) -> Result<()> {
+        fine_pay_impl(ctx).await.wrap_err("fine_pay")
+    }
+
+    #[tracing::instrument(skip(ctx))]
+    async fn fine_pay_impl(ctx: Context<'_>) -> Result<()> {
+        let guild_id = ctx.guild_id().wrap_err("guild_id not found")?;
+        let storage = &ctx.data().storage;
+        let user_id = SnowflakeId::from(ctx.author().id);
+
+        let state = storage.find_or_insert_state(guild_id.into()).await?;
+        let strings = state.locale.strings();
+
+        let open_fines = storage
+            .find_lawsuits(guild_id.into())
+            .await?
+            .into_iter()
+            .filter_map(|lawsuit| match lawsuit.fine {
+                Some(fine) if lawsuit.accused == user_id && fine.paid_at.is_none() => {
+                    Some((lawsuit.id, fine))
+                }
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        if open_fines.is_empty() {
+            ctx.say(strings.no_open_fines).await?;
+            return Ok(());
+        }
+
+        // the fines were already taken from the balance when they were imposed, they are paid
+        // once the debt is gone
+        let balance = storage.find_member(guild_id.into(), user_id).await?.balance;
+        if balance < 0 {
+            ctx.say((strings.fines_in_debt)(&(strings.currency)(-balance)))
+                .await?;
+            return Ok(());
+        }
+
+        let now = DateTime::now();
+        for (lawsuit_id, fine) in &open_fines {
+            let fine = Fine {
+                paid_at: Some(now),
+                ..*fine
+            };
+            storage
+                .set_lawsuit_fine(guild_id.into(), *lawsuit_id, &fine)
+                .await?;
+        }
+        let total = open_fines.iter().map(|(_, fine)| fine.amount).sum();
+
+        info!(%guild_id, %user_id, count = open_fines.len(), total, "Paid fines");
+
+        audit::log(
+            &ctx.discord().http,
+            &state,
+            AuditEvent::FinesPaid {
+                user_id: ctx.author().id,
+                count: open_fines.len(),
+                total,
+            },
+        )
+        .await;
+
+        ctx.say((strings.fines_paid)(
+            open_fines.len(),
+            &(strings.currency)(total),
+        ))
+        .await?;
+        Ok(())
+    }
+}
+
+pub mod economy {
+    use super::*;
+
+    /// Das Geld der Mitglieder verwalten
+    #[poise::command(slash_command, guild_only, subcommands("balance", "give"))]
+    pub async fn economy(_: Context<'_>) -> Result<()> {
+        unreachable!()
+    }
+
+    /// Den Kontostand anzeigen
+    #[poise::command(slash_command, guild_only)]
+    async fn balance(
+        ctx: Context<'_>,
+        #[description = "Die Person, sonst du selbst"] user: Option<User>,
+    ) -> Result<()> {
+        economy_balance_impl(ctx, user)
+            .await
+            .wrap_err("economy_balance")
+    }
+
+    /// Einer Person Geld geben oder mit einem negativen Betrag wegnehmen
+    #[poise::command(slash_command, guild_only, check = "is_admin")]
+    async fn give(
+        ctx: Context<'_>,
+        #[description = "Die Person"] user: User,
+        #[description = "Der Betrag, negativ um Geld wegzunehmen"] amount: i64,
+    ) -> Result<()> {
+        economy_give_impl(ctx, user, amount)
+            .await
+            .wrap_err("economy_give")
+    }
+
+    #[tracing::instrument(skip(ctx))]
+    async fn economy_balance_impl(ctx: Context<'_>, user: Option<User>) -> Result<()> {
+        let guild_id = ctx.guild_id().wrap_err("guild_id not found")?;
+        let user_id = user.map_or(ctx.author().id, |user| user.id);
+
+        let strings = i18n::strings(ctx).await?;
+        let balance = ctx
+            .data()
+            .storage
+            .find_member(guild_id.into(), user_id.into())
+            .await?
+            .balance;
+
+        ctx.say((strings.balance_of)(
+            &format!("<@{user_id}>"),
+            &(strings.currency)(balance),
+        ))
+        .await?;
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(ctx))]
+    async fn economy_give_impl(ctx: Context<'_>, user: User, amount: i64) -> Result<()> {
+        let guild_id = ctx.guild_id().wrap_err("guild_id not found")?;
+        let storage = &ctx.data().storage;
+
+        let balance = storage
+            .add_to_balance(guild_id.into(), user.id.into(), amount)
+            .await?;
+
+        info!(%guild_id, user_id = %user.id, amount, balance, "Changed balance");
+
+        let state = storage.find_or_insert_state(guild_id.into()).await?;
+        let strings = state.locale.strings();
+
+        audit::log(
+            &ctx.discord().http,
+            &state,
+            AuditEvent::BalanceChanged {
+                user_id: user.id,
+                amount,
+                balance,
+                changed_by: ctx.author().id,
+            },
+        )
+        .await;
+
+        ctx.say((strings.balance_of)(
+            &format!("<@{}>", user.id),
+            &(strings.currency)(balance),
+        ))
+        .await?;
+        Ok(())
+    }
+}
+
+pub mod lawyer {
+    use super::*;
+
+    /// Die Anwaltsprüfung und die Honorare der Anwälte
+    #[poise::command(
+        slash_command,
+        guild_only,
+        subcommands("exam", "questions", "fee", "fees", "retainer", "bill", "record")
+    )]
+    pub async fn lawyer(_: Context<'_>) -> Result<()> {
+        unreachable!()
+    }
+
+    /// Die Anwaltsprüfung ablegen, um als Anwalt zugelassen zu werden
+    #[poise::command(slash_command, guild_only)]
+    async fn exam(ctx: Context<'_>) -> Result<()> {
+        lawyer_exam_impl(ctx).await.wrap_err("lawyer_exam")
+    }
+
+    /// Die Fragen der Anwaltsprüfung verwalten
+    #[poise::command(
+        slash_command,
+        guild_only,
+        subcommands("questions_add", "questions_remove", "questions_list")
+    )]
+    async fn questions(_: Context<'_>) -> Result<()> {
+        unreachable!()
+    }
+
+    /// Eine Frage zur Anwaltsprüfung hinzufügen
+    #[poise::command(slash_command, guild_only, check = "is_admin", rename = "add")]
+    async fn questions_add(
+        ctx: Context<'_>,
+        #[description = "Die Frage"] question: String,
+        #[description = "Die Antworten, mit ; getrennt"] answers: String,
+        #[description = "Die Nummer der richtigen Antwort"] correct: usize,
+    ) -> Result<()> {
+        lawyer_questions_add_impl(ctx, question, answers, correct)
+            .await
+            .wrap_err("lawyer_questions_add")
+    }
+
+    /// Eine Frage der Anwaltsprüfung löschen
+    #[poise::command(slash_command, guild_only, check = "is_admin", rename = "remove")]
+    async fn questions_remove(
+        ctx: Context<'_>,
+        #[description = "Die Nummer der Frage"] number: usize,
+    ) -> Result<()> {
+        lawyer_questions_remove_impl(ctx, number)
+            .await
+            .wrap_err("lawyer_questions_remove")
+    }
+
+    /// Alle Fragen der Anwaltsprüfung mit den richtigen Antworten anzeigen
+    #[poise::command(slash_command, guild_only, check = "is_admin", rename = "list")]
+    async fn questions_list(ctx: Context<'_>) -> Result<()> {
+        lawyer_questions_list_impl(ctx)
+            .await
+            .wrap_err("lawyer_questions_list")
+    }
+
+    /// Das eigene Honorar als Anwalt setzen
+    #[poise::command(slash_command, guild_only)]
+    async fn fee(
+        ctx: Context<'_>,
+        #[description = "Pro Stunde oder pauschal pro Prozess"] kind: FeeKind,
+        #[description = "Der Betrag, 0 um kein Honorar mehr zu verlangen"] amount: i64,
+    ) -> Result<()> {
+        lawyer_fee_impl(ctx, kind, amount)
+            .await
+            .wrap_err("lawyer_fee")
+    }
+
+    /// Die Honorare aller Anwälte auflisten
+    #[poise::command(slash_command, guild_only)]
+    async fn fees(ctx: Context<'_>) -> Result<()> {
+        lawyer_fees_impl(ctx).await.wrap_err("lawyer_fees")
+    }
+
+    /// Dem eigenen Anwalt im Prozess in diesem Kanal einen Vorschuss bezahlen
+    #[poise::command(slash_command, guild_only)]
+    async fn retainer(ctx: Context<'_>, #[description = "Der Betrag"] amount: i64) -> Result<()> {
+        lawyer_retainer_impl(ctx, amount)
+            .await
+            .wrap_err("lawyer_retainer")
+    }
+
+    /// Dem Klienten im Prozess in diesem Kanal das Honorar verrechnen
+    #[poise::command(slash_command, guild_only)]
+    async fn bill(
+        ctx: Context<'_>,
+        #[description = "Die Stunden, nur für Honorare pro Stunde (Standard: 1)"] hours: Option<
+            u32,
+        >,
+    ) -> Result<()> {
+        lawyer_bill_impl(ctx, hours.unwrap_or(1))
+            .await
+            .wrap_err("lawyer_bill")
+    }
+
+    /// Die Fälle eines Anwalts und wie oft er sie gewonnen hat anzeigen
+    #[poise::command(slash_command, guild_only)]
+    async fn record(ctx: Context<'_>, #[description = "Der Anwalt"] user: User) -> Result<()> {
+        lawyer_record_impl(ctx, user)
+            .await
+            .wrap_err("lawyer_record")
+    }
+
+    #[tracing::instrument(skip(ctx))]
+    async fn lawyer_exam_impl(ctx: Context<'_>) -> Result<()> {
+        let guild_id = ctx.guild_id().wrap_err("guild_id not found")?;
+        let storage = &ctx.data().storage;
+        let user_id = SnowflakeId::from(ctx.author().id);
+
+        let state = storage.find_or_insert_state(guild_id.into()).await?;
+        let strings = state.locale.strings();
+
+        if state.bar_questions.is_empty() {
+            ctx.say(strings.bar_no_questions).await?;
+            return Ok(());
+        }
+        if state.admitted_lawyers.contains(&user_id) {
+            ctx.say(strings.bar_already_admitted).await?;
+            return Ok(());
+        }
+
+        let result = match bar::take_exam(ctx, strings, &state.bar_questions).await? {
+            Some(result) => result,
+            None => return Ok(()),
+        };
+        info!(%guild_id, %user_id, ?result, "Took bar exam");
+        if !result.passed() {
+            return Ok(());
+        }
+
+        storage.admit_lawyer(guild_id.into(), user_id).await?;
+
+        audit::log(
+            &ctx.discord().http,
+            &state,
+            AuditEvent::LawyerAdmitted {
+                user_id: ctx.author().id,
+            },
+        )
+        .await;
+
+        ctx.say((strings.bar_admitted)(&format!("<@{user_id}>")))
+            .await?;
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(ctx))]
+    async fn lawyer_questions_add_impl(
+        ctx: Context<'_>,
+        question: String,
+        answers: String,
+        correct: usize,
+    ) -> Result<()> {
+        let guild_id = ctx.guild_id().wrap_err("guild_id not found")?;
+        let storage = &ctx.data().storage;
+
+        let state = storage.find_or_insert_state(guild_id.into()).await?;
+        let strings = state.locale.strings();
+
+        let answers = answers
+            .split(';')
+            .map(str::trim)
+            .filter(|answer| !answer.is_empty())
+            .map(str::to_owned)
+            .collect::<Vec<_>>();
+        let valid = (2..=bar::MAX_ANSWERS).contains(&answers.len())
+            && (1..=answers.len()).contains(&correct)
+            && answers
+                .iter()
+                .all(|answer| answer.chars().count() <= bar::MAX_ANSWER_LEN);
+        if !valid {
+            ctx.say((strings.invalid_bar_answers)(bar::MAX_ANSWERS))
+                .await?;
+            return Ok(());
+        }
+
+        let mut questions = state.bar_questions.clone();
+        questions.push(BarQuestion {
+            question,
+            answers,
+            correct: correct - 1,
+        });
+        storage
+            .set_bar_questions(guild_id.into(), &questions)
+            .await?;
+
+        ctx.say((strings.bar_question_added)(questions.len()))
+            .await?;
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(ctx))]
+    async fn lawyer_questions_remove_impl(ctx: Context<'_>, number: usize) -> Result<()> {
+        let guild_id = ctx.guild_id().wrap_err("guild_id not found")?;
+        let storage = &ctx.data().storage;
+
+        let state = storage.find_or_insert_state(guild_id.into()).await?;
+        let strings = state.locale.strings();
+
+        if number == 0 || number > state.bar_questions.len() {
+            ctx.say(strings.bar_question_not_found).await?;
+            return Ok(());
+        }
+
+        let mut questions = state.bar_questions.clone();
+        questions.remove(number - 1);
+        storage
+            .set_bar_questions(guild_id.into(), &questions)
+            .await?;
+
+        ctx.say(strings.bar_question_removed).await?;
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(ctx))]
+    async fn lawyer_questions_list_impl(ctx: Context<'_>) -> Result<()> {
+        let guild_id = ctx.guild_id().wrap_err("guild_id not found")?;
+
+        let state = ctx
+            .data()
+            .storage
+            .find_or_insert_state(guild_id.into())
+            .await?;
+        let strings = state.locale.strings();
+
+        if state.bar_questions.is_empty() {
+            ctx.say(strings.bar_no_questions).await?;
+            return Ok(());
+        }
+
+        let entries = state
+            .bar_questions
+            .iter()
+            .enumerate()
+            .map(|(i, question)| {
+                let answers = question
+                    .answers
+                    .iter()
+                    .enumerate()
+                    .map(|(j, answer)| {
+                        let mark = if j == question.correct {
+                            "✅"
+                        } else {
+                            "▫️"
+                        };
+                        format!("{mark} {answer}")
+                    })
+                    .collect::<Vec<_>>()
+                    .join("\n");
+                format!("**{}. {}**\n{answers}", i + 1, question.question)
+            })
+            .collect::<Vec<_>>();
+
+        paginate(ctx, strings.bar_questions_title, &entries).await
+    }
+
+    #[tracing::instrument(skip(ctx))]
+    async fn lawyer_fee_impl(ctx: Context<'_>, kind: FeeKind, amount: i64) -> Result<()> {
+        let guild_id = ctx.guild_id().wrap_err("guild_id not found")?;
+        let strings = i18n::strings(ctx).await?;
+
+        if amount < 0 {
+            ctx.say(strings.invalid_amount).await?;
+            return Ok(());
+        }
+        let fee = (amount > 0).then_some(LawyerFee { kind, amount });
+
+        ctx.data()
+            .storage
+            .set_lawyer_fee(guild_id.into(), ctx.author().id.into(), fee)
+            .await?;
+
+        match fee {
+            Some(fee) => ctx.say((strings.fee_set)(&fee.describe(strings))).await?,
+            None => ctx.say(strings.fee_removed).await?,
+        };
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(ctx))]
+    async fn lawyer_fees_impl(ctx: Context<'_>) -> Result<()> {
+        let guild_id = ctx.guild_id().wrap_err("guild_id not found")?;
+
+        let strings = i18n::strings(ctx).await?;
+
+        let mut members = ctx.data().storage.find_members(guild_id.into()).await?;
+        members.sort_by_key(|member| member.user_id);
+        let entries = members
+            .iter()
+            .filter_map(|member| {
+                let fee = member.lawyer_fee?;
+                Some(format!("<@{}>: {}", member.user_id, fee.describe(strings)))
+            })
+            .collect::<Vec<_>>();
+        if entries.is_empty() {
+            ctx.say(strings.no_lawyer_fees).await?;
+            return Ok(());
+        }
+
+        paginate(ctx, strings.lawyer_fees_title, &entries).await
+    }
+
+    #[tracing::instrument(skip(ctx))]
+    async fn lawyer_retainer_impl(ctx: Context<'_>, amount: i64) -> Result<()> {
+        let guild_id = ctx.guild_id().wrap_err("guild_id not found")?;
+        let storage = &ctx.data().storage;
+        let client = SnowflakeId::from(ctx.author().id);
+
+        let state = storage.find_or_insert_state(guild_id.into()).await?;
+        let strings = state.locale.strings();
+
+        if amount <= 0 {
+            ctx.say(strings.invalid_amount).await?;
+            return Ok(());
+        }
+
+        let lawsuit = storage
+            .find_open_lawsuits(guild_id.into())
+            .await?
+            .into_iter()
+            .find(|lawsuit| lawsuit.court_room == ctx.channel_id().into());
+        let lawsuit = match lawsuit {
+            Some(lawsuit) => lawsuit,
+            None => {
+                ctx.say(strings.no_active_lawsuit).await?;
+                return Ok(());
+            }
+        };
+
+        let lawyer = if lawsuit.plaintiff == client {
+            lawsuit.plaintiff_lawyer
+        } else if lawsuit.accused == client {
+            lawsuit.accused_lawyer
+        } else {
+            None
+        };
+        // lawsuits from before self-representation was rejected can still have it
+        let lawyer = match lawyer {
+            Some(lawyer) if lawyer != client => lawyer,
+            _ => {
+                ctx.say(strings.not_a_client).await?;
+                return Ok(());
+            }
+        };
+
+        let billing = Billing {
+            lawyer,
+            client,
+            amount,
+            retainer: true,
+            billed_at: DateTime::now(),
+        };
+        // unlike fines and bills, a retainer can't be paid with debts
+        if !pay_lawyer(ctx, &state, &lawsuit, &billing, false).await? {
+            let balance = storage.find_member(guild_id.into(), client).await?.balance;
+            ctx.say((strings.not_enough_money)(&(strings.currency)(balance)))
+                .await?;
+            return Ok(());
+        }
+
+        ctx.say((strings.retainer_paid)(
+            &(strings.currency)(amount),
+            &format!("<@{lawyer}>"),
+        ))
+        .await?;
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(ctx))]
+    async fn lawyer_bill_impl(ctx: Context<'_>, hours: u32) -> Result<()> {
+        let guild_id = ctx.guild_id().wrap_err("guild_id not found")?;
+        let storage = &ctx.data().storage;
+        let lawyer = SnowflakeId::from(ctx.author().id);
+
+        let state = storage.find_or_insert_state(guild_id.into()).await?;
+        let strings = state.locale.strings();
+
+        let lawsuit = storage
+            .find_open_lawsuits(guild_id.into())
+            .await?
+            .into_iter()
+            .find(|lawsuit| lawsuit.court_room == ctx.channel_id().into());
+        let lawsuit = match lawsuit {
+            Some(lawsuit) => lawsuit,
+            None => {
+                ctx.say(strings.no_active_lawsuit).await?;
+                return Ok(());
+            }
+        };
+
+        let client = if lawsuit.plaintiff_lawyer == Some(lawyer) && lawsuit.plaintiff != lawyer {
+            lawsuit.plaintiff
+        } else if lawsuit.accused_lawyer == Some(lawyer) && lawsuit.accused != lawyer {
+            lawsuit.accused
+        } else {
+            ctx.say(strings.not_a_lawyer).await?;
+            return Ok(());
+        };
+        let fee = match storage
+            .find_member(guild_id.into(), lawyer)
+            .await?
+            .lawyer_fee
+        {
+            Some(fee) => fee,
+            None => {
+                ctx.say(strings.no_fee_set).await?;
+                return Ok(());
+            }
+        };
+
+        let billing = Billing {
+            lawyer,
+            client,
+            amount: fee.charge(hours),
+            retainer: false,
+            billed_at: DateTime::now(),
+        };
+        pay_lawyer(ctx, &state, &lawsuit, &billing, true).await?;
+
+        ctx.say((strings.billed)(
+            &format!("<@{client}>"),
+            &(strings.currency)(billing.amount),
+        ))
+        .await?;
+        Ok(())
+    }
+
+    /// Moves the money from the client to the lawyer and records it on the lawsuit. Returns
+    /// `false` without paying if the client doesn't have enough money and can't `overdraw`.
+    async fn pay_lawyer(
+        ctx: Context<'_>,
+        state: &State,
+        lawsuit: &Lawsuit,
+        billing: &Billing,
+        overdraw: bool,
+    ) -> Result<bool> {
+        let storage = &ctx.data().storage;
+        let balance = storage
+            .transfer(
+                lawsuit.guild_id,
+                billing.client,
+                billing.lawyer,
+                billing.amount,
+                overdraw,
+            )
+            .await?;
+        let balance = match balance {
+            Some(balance) => balance,
+            None => return Ok(false),
+        };
+        storage
+            .add_billing(lawsuit.guild_id, lawsuit.id, billing)
+            .await?;
+
+        info!(lawsuit_id = %lawsuit.id, ?billing, balance, "Paid lawyer");
+
+        audit::log(
+            &ctx.discord().http,
+            state,
+            AuditEvent::LawyerPaid { lawsuit, billing },
+        )
+        .await;
+        Ok(true)
+    }
+
+    #[tracing::instrument(skip(ctx))]
+    async fn lawyer_record_impl(ctx: Context<'_>, user: User) -> Result<()> {
+        // embeds can't show more
+        const MAX_CASES: usize = 10;
+        const MAX_FIELD_LEN: usize = 1024;
+
+        let guild_id = ctx.guild_id().wrap_err("guild_id not found")?;
+        let strings = i18n::strings(ctx).await?;
+
+        let lawsuits = ctx.data().storage.find_lawsuits(guild_id.into()).await?;
+        let record = LawyerRecord::of(user.id.into(), &lawsuits);
+
+        let success_rate = record.success_rate().map_or_else(
+            || strings.none.to_owned(),
+            |rate| format!("{rate}% ({}/{})", record.won, record.won + record.lost),
+        );
+        let cases = record
+            .cases
+            .iter()
+            .take(MAX_CASES)
+            .map(|(lawsuit, side)| {
+                let client = match side {
+                    Side::Plaintiff => lawsuit.plaintiff,
+                    Side::Accused => lawsuit.accused,
+                };
+                let outcome = match (&lawsuit.verdict, lawsuit.won_by(*side)) {
+                    (None, _) => strings.lawyer_record_ongoing,
+                    (_, Some(true)) => strings.lawyer_record_won,
+                    (_, Some(false)) => strings.lawyer_record_lost,
+                    (_, None) => strings.lawyer_record_undecided,
+                };
+                (strings.lawyer_record_case)(&lawsuit.reason, &format!("<@{client}>"), outcome)
+            })
+            .collect::<Vec<_>>();
+        let cases = if cases.is_empty() {
+            strings.none.to_owned()
+        } else {
+            cases.join("\n").chars().take(MAX_FIELD_LEN).collect()
+        };
+
+        ctx.send(|reply| {
+            reply.embed(|embed| {
+                embed
+                    .title((strings.lawyer_record_title)(&user.name))
+                    .field(strings.lawyer_record_won, record.won, true)
+                    .field(strings.lawyer_record_lost, record.lost, true)
+                    .field(strings.lawyer_record_success_rate, success_rate, true)
+                    .field(strings.lawyer_record_cases, cases, false)
+            })
+        })
+        .await
+        .wrap_err("reply")?;
+
+        Ok(())
+    }
+}
+
+pub mod judge {
+    use super::*;
+
+    /// Richter, denen Prozesse automatisch zugeteilt werden
+    #[poise::command(
+        slash_command,
+        guild_only,
+        subcommands("register", "unregister", "list", "stats")
+    )]
+    pub async fn judge(_: Context<'_>) -> Result<()> {
+        unreachable!()
+    }
+
+    /// Prozesse ohne Richter der Reihe nach zugeteilt bekommen
+    #[poise::command(slash_command, guild_only, check = "is_judge")]
+    async fn register(ctx: Context<'_>) -> Result<()> {
+        judge_register_impl(ctx).await.wrap_err("judge_register")
+    }
+
+    /// Keine Prozesse mehr zugeteilt bekommen
+    #[poise::command(slash_command, guild_only)]
+    async fn unregister(ctx: Context<'_>) -> Result<()> {
+        judge_unregister_impl(ctx)
+            .await
+            .wrap_err("judge_unregister")
+    }
+
+    /// Die registrierten Richter in der Reihenfolge der Zuteilung auflisten
+    #[poise::command(slash_command, guild_only)]
+    async fn list(ctx: Context<'_>) -> Result<()> {
+        judge_list_impl(ctx).await.wrap_err("judge_list")
+    }
+
+    /// Statistiken über die Prozesse eines Richters anzeigen
+    #[poise::command(slash_command, guild_only)]
+    async fn stats(ctx: Context<'_>, #[description = "Der Richter"] user: User) -> Result<()> {
+        judge_stats_impl(ctx, user).await.wrap_err("judge_stats")
+    }
+
+    #[tracing::instrument(skip(ctx))]
+    async fn judge_register_impl(ctx: Context<'_>) -> Result<()> {
+        let guild_id = ctx.guild_id().wrap_err("guild_id not found")?;
+        let strings = i18n::strings(ctx).await?;
+
+        let registered = ctx
+            .data()
+            .storage
+            .register_judge(guild_id.into(), ctx.author().id.into())
+            .await?;
+
+        if registered {
+            info!(%guild_id, judge = %ctx.author().id, "Registered judge");
+            ctx.say(strings.judge_registered).await?;
+        } else {
+            ctx.say(strings.judge_already_registered).await?;
+        }
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(ctx))]
+    async fn judge_unregister_impl(ctx: Context<'_>) -> Result<()> {
+        let guild_id = ctx.guild_id().wrap_err("guild_id not found")?;
+        let strings = i18n::strings(ctx).await?;
+
+        let unregistered = ctx
+            .data()
+            .storage
+            .unregister_judge(guild_id.into(), ctx.author().id.into())
+            .await?;
+
+        if unregistered {
+            info!(%guild_id, judge = %ctx.author().id, "Unregistered judge");
+            ctx.say(strings.judge_unregistered).await?;
+        } else {
+            ctx.say(strings.judge_not_registered).await?;
+        }
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(ctx))]
+    async fn judge_list_impl(ctx: Context<'_>) -> Result<()> {
+        let guild_id = ctx.guild_id().wrap_err("guild_id not found")?;
+        let strings = i18n::strings(ctx).await?;
+
+        let registry = ctx
+            .data()
+            .storage
+            .find_judge_registry(guild_id.into())
+            .await?;
+
+        if registry.judges.is_empty() {
+            ctx.say(strings.no_registered_judges).await?;
+            return Ok(());
+        }
+
+        // the list starts with the judge who gets the next lawsuit
+        let next = registry.next_judge(&[]);
+        let start = registry
+            .judges
+            .iter()
+            .position(|judge| Some(*judge) == next)
+            .unwrap_or_default();
+        let entries = registry
+            .judges
+            .iter()
+            .cycle()
+            .skip(start)
+            .take(registry.judges.len())
+            .enumerate()
+            .map(|(i, judge)| format!("{}. <@{judge}>", i + 1))
+            .collect::<Vec<_>>();
+
+        paginate(ctx, strings.registered_judges_title, &entries).await
+    }
+
+    #[tracing::instrument(skip(ctx))]
+    async fn judge_stats_impl(ctx: Context<'_>, user: User) -> Result<()> {
+        let guild_id = ctx.guild_id().wrap_err("guild_id not found")?;
+        let strings = i18n::strings(ctx).await?;
+
+        let lawsuits = ctx.data().storage.find_lawsuits(guild_id.into()).await?;
+        let stats = JudgeStats::of(user.id.into(), &lawsuits);
+
+        let average_duration = stats
+            .average_duration
+            .map_or_else(|| strings.none.to_owned(), duration::format);
+        let overturn_rate = stats.overturn_rate().map_or_else(
+            || strings.none.to_owned(),
+            |rate| format!("{rate}% ({}/{})", stats.overturned, stats.appeals),
+        );
+
+        ctx.send(|reply| {
+            reply.embed(|embed| {
+                embed
+                    .title((strings.judge_stats_title)(&user.name))
+                    .field(strings.judge_stats_closed, stats.closed, true)
+                    .field(strings.judge_stats_open, stats.open, true)
+                    .field(strings.judge_stats_average_duration, average_duration, true)
+                    .field(strings.judge_stats_overturn_rate, overturn_rate, true)
+            })
+        })
+        .await
+        .wrap_err("reply")?;
+
+        Ok(())
+    }
+}
+
+pub mod court {
+    use super::*;
+
+    /// Das Gericht des Servers
+    #[poise::command(slash_command, guild_only, subcommands("stats", "leaderboard"))]
+    pub async fn court(_: Context<'_>) -> Result<()> {
+        unreachable!()
+    }
+
+    /// Statistiken über die Prozesse des Servers anzeigen
+    #[poise::command(slash_command, guild_only)]
+    async fn stats(ctx: Context<'_>) -> Result<()> {
+        court_stats_impl(ctx).await.wrap_err("court_stats")
+    }
+
+    #[tracing::instrument(skip(ctx))]
+    async fn court_stats_impl(ctx: Context<'_>) -> Result<()> {
+        let guild_id = ctx.guild_id().wrap_err("guild_id not found")?;
+        let strings = i18n::strings(ctx).await?;
+
+        let stats = ctx.data().storage.court_stats(guild_id.into()).await?;
+
+        let list = |lines: Vec<String>| {
+            if lines.is_empty() {
+                strings.none.to_owned()
+            } else {
+                lines.join("\n")
+            }
+        };
+        let per_month = list(
+            stats
+                .per_month
+                .iter()
+                .map(|(month, count)| format!("{month}: {count}"))
+                .collect(),
+        );
+        let people = |counts: &[(SnowflakeId, u64)]| {
+            list(
+                counts
+                    .iter()
+                    .map(|(user_id, count)| format!("<@{user_id}> ({count}x)"))
+                    .collect(),
+            )
+        };
+        let time_to_verdict = stats
+            .average_time_to_verdict
+            .map_or_else(|| strings.none.to_owned(), duration::format);
+
+        ctx.send(|reply| {
+            reply.embed(|embed| {
+                embed
+                    .title(strings.court_stats_title)
+                    .field(strings.court_stats_per_month, per_month, true)
+                    .field(
+                        strings.court_stats_verdicts,
+                        (strings.court_stats_verdict_counts)(
+                            stats.guilty,
+                            stats.not_guilty,
+                            stats.other_verdicts,
+                        ),
+                        true,
+                    )
+                    .field(strings.court_stats_time_to_verdict, time_to_verdict, true)
+                    .field(
+                        strings.court_stats_busiest_judges,
+                        people(&stats.busiest_judges),
+                        true,
+                    )
+                    .field(
+                        strings.court_stats_most_sued,
+                        people(&stats.most_sued),
+                        true,
+                    )
+            })
+        })
+        .await
+        .wrap_err("reply")?;
+
+        Ok(())
+    }
+
+    /// Die Mitglieder mit den meisten Klagen, Siegen und Verurteilungen anzeigen
+    #[poise::command(slash_command, guild_only)]
+    async fn leaderboard(ctx: Context<'_>) -> Result<()> {
+        court_leaderboard_impl(ctx)
+            .await
+            .wrap_err("court_leaderboard")
+    }
+
+    #[tracing::instrument(skip(ctx))]
+    async fn court_leaderboard_impl(ctx: Context<'_>) -> Result<()> {
+        let guild_id = ctx.guild_id().wrap_err("guild_id not found")?;
+        let strings = i18n::strings(ctx).await?;
+
+        let lawsuits = ctx.data().storage.find_lawsuits(guild_id.into()).await?;
+        let leaderboard = Leaderboard::of(&lawsuits);
+
+        let ranking = |counts: &[(SnowflakeId, u64)]| {
+            if counts.is_empty() {
+                return strings.none.to_owned();
+            }
+            counts
+                .iter()
+                .enumerate()
+                .map(|(i, (user_id, count))| format!("{}. <@{user_id}> ({count}x)", i + 1))
+                .collect::<Vec<_>>()
+                .join("\n")
+        };
+
+        ctx.send(|reply| {
+            reply.embed(|embed| {
+                embed
+                    .title(strings.leaderboard_title)
+                    .field(strings.leaderboard_filed, ranking(&leaderboard.filed), true)
+                    .field(strings.leaderboard_won, ranking(&leaderboard.won), true)
+                    .field(
+                        strings.leaderboard_convicted,
+                        ranking(&leaderboard.convicted),
+                        true,
+                    )
+            })
+        })
+        .await
+        .wrap_err("reply")?;
+
+        Ok(())
+    }
+}
+
+pub mod jury_pool {
+    use super::*;
+
+    /// Der Pool der Mitglieder, die als Geschworene ausgelost werden können
+    #[poise::command(slash_command, guild_only, subcommands("join", "leave"))]
+    pub async fn jury(_: Context<'_>) -> Result<()> {
+        unreachable!()
+    }
+
+    /// Dem Geschworenen-Pool beitreten und für Prozesse ausgelost werden
+    #[poise::command(slash_command, guild_only)]
+    async fn join(ctx: Context<'_>) -> Result<()> {
+        jury_join_impl(ctx).await.wrap_err("jury_join")
+    }
+
+    /// Den Geschworenen-Pool verlassen
+    #[poise::command(slash_command, guild_only)]
+    async fn leave(ctx: Context<'_>) -> Result<()> {
+        jury_leave_impl(ctx).await.wrap_err("jury_leave")
+    }
+
+    #[tracing::instrument(skip(ctx))]
+    async fn jury_join_impl(ctx: Context<'_>) -> Result<()> {
+        let guild_id = ctx.guild_id().wrap_err("guild_id not found")?;
+        let storage = &ctx.data().storage;
+        let user_id = SnowflakeId::from(ctx.author().id);
+
+        let state = storage.find_or_insert_state(guild_id.into()).await?;
+        let strings = state.locale.strings();
+
+        if state.jury_pool.contains(&user_id) {
+            ctx.say(strings.jury_already_joined).await?;
+            return Ok(());
+        }
+
+        storage
+            .set_jury_pool_member(guild_id.into(), user_id, true)
+            .await?;
+        info!(%guild_id, %user_id, "Joined jury pool");
+
+        ctx.say(strings.jury_joined).await?;
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(ctx))]
+    async fn jury_leave_impl(ctx: Context<'_>) -> Result<()> {
+        let guild_id = ctx.guild_id().wrap_err("guild_id not found")?;
+        let storage = &ctx.data().storage;
+        let user_id = SnowflakeId::from(ctx.author().id);
+
+        let state = storage.find_or_insert_state(guild_id.into()).await?;
+        let strings = state.locale.strings();
+
+        if !state.jury_pool.contains(&user_id) {
+            ctx.say(strings.jury_not_joined).await?;
+            return Ok(());
+        }
+
+        storage
+            .set_jury_pool_member(guild_id.into(), user_id, false)
+            .await?;
+        info!(%guild_id, %user_id, "Left jury pool");
+
+        ctx.say(strings.jury_left).await?;
+        Ok(())
+    }
+}
+
+pub mod election {
+    use super::*;
+    use crate::election::RunningElection;
+
+    /// Wahlen von Mitgliedern für eine Rolle
+    #[poise::command(slash_command, guild_only, subcommands("start", "list"))]
+    pub async fn election(_: Context<'_>) -> Result<()> {
+        unreachable!()
+    }
+
+    /// Eine Wahl für eine Rolle starten, z.B. die Richterrolle
+    #[poise::command(slash_command, guild_only, check = "is_admin")]
+    async fn start(
+        ctx: Context<'_>,
+        #[description = "Die Rolle, welche die Gewählten bekommen"] role: Role,
+        #[description = "Wie lange die Kandidatur und danach die Wahl je dauern, z.B. 1d"]
+        duration: String,
+        #[description = "Wie viele gewählt werden (Standard: 1)"] seats: Option<u32>,
+    ) -> Result<()> {
+        election_start_impl(ctx, role, duration, seats)
+            .await
+            .wrap_err("election_start")
+    }
+
+    /// Die vergangenen Wahlen auflisten
+    #[poise::command(slash_command, guild_only)]
+    async fn list(ctx: Context<'_>) -> Result<()> {
+        election_list_impl(ctx).await.wrap_err("election_list")
+    }
+
+    #[tracing::instrument(skip(ctx))]
+    async fn election_start_impl(
+        ctx: Context<'_>,
+        role: Role,
+        duration: String,
+        seats: Option<u32>,
+    ) -> Result<()> {
+        let strings = i18n::strings(ctx).await?;
+
+        let phase = match duration::parse(&duration) {
+            Some(phase) => phase,
+            None => {
+                ctx.say(strings.invalid_duration).await?;
+                return Ok(());
+            }
+        };
+        let seats = seats.unwrap_or(1).max(1);
+
+        let election = RunningElection::start(ctx, strings, role.id, seats, phase).await?;
+        info!(guild_id = ?ctx.guild_id(), role_id = %role.id, seats, "Started election");
+
+        ctx.send(|reply| reply.content(strings.election_started).ephemeral(true))
+            .await
+            .wrap_err("reply")?;
+
+        let discord = ctx.discord().clone();
+        let storage = ctx.data().storage.clone();
+        let tasks = ctx.data().tasks.clone();
+        ctx.data().tasks.spawn(async move {
+            if let Err(err) = election.run(discord, storage, tasks, strings).await {
+                error!(?err, "Failed to run the election");
+            }
+        });
+
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(ctx))]
+    async fn election_list_impl(ctx: Context<'_>) -> Result<()> {
+        let guild_id = ctx.guild_id().wrap_err("guild_id not found")?;
+        let strings = i18n::strings(ctx).await?;
+
+        let elections = ctx.data().storage.find_elections(guild_id.into()).await?;
+        if elections.is_empty() {
+            ctx.say(strings.no_elections).await?;
+            return Ok(());
+        }
+
+        let entries = elections
+            .iter()
+            .map(|election| {
+                let winners = election
+                    .winners
+                    .iter()
+                    .map(|winner| format!("<@{winner}>"))
+                    .collect::<Vec<_>>();
+                let winners = if winners.is_empty() {
+                    strings.election_no_winners.to_owned()
+                } else {
+                    winners.join(", ")
+                };
+                format!(
+                    "{} <@&{}>: {winners}",
+                    duration::relative_timestamp(election.ended_at),
+                    election.role_id
+                )
+            })
+            .collect::<Vec<_>>();
+
+        paginate(ctx, strings.elections_title, &entries).await
+    }
+}
+
+pub mod records {
+    use super::*;
+
+    /// Search results beyond this are not shown
+    const MAX_RESULTS: usize = 100;
+    /// The longest reason and verdict in the results, so that a page fits into an embed
+    const MAX_TEXT_LEN: usize = 80;
+
+    /// Die Akten der abgeschlossenen Prozesse
+    #[poise::command(slash_command, guild_only, subcommands("search", "show", "import"))]
+    pub async fn records(_: Context<'_>) -> Result<()> {
+        unreachable!()
+    }
+
+    pub(super) async fn autocomplete_case_number(
+        ctx: Context<'_>,
+        partial: String,
+    ) -> impl Iterator<Item = String> {
+        // discord doesn't show more than 25 suggestions
+        const MAX_SUGGESTIONS: usize = 25;
+
+        let lawsuits = match ctx.guild_id() {
+            Some(guild_id) => match ctx.data().storage.find_lawsuits(guild_id.into()).await {
+                Ok(lawsuits) => lawsuits,
+                Err(err) => {
+                    error!(?err, "Error fetching lawsuits for autocomplete");
+                    vec![]
+                }
+            },
+            None => vec![],
+        };
+
+        lawsuits
+            .into_iter()
+            .filter(|lawsuit| !lawsuit.sealed)
+            .filter_map(|lawsuit| lawsuit.case_number)
+            .filter(move |case_number| case_number.starts_with(&partial))
+            .take(MAX_SUGGESTIONS)
+    }
+
+    /// Die Begründungen und Urteile der abgeschlossenen Prozesse durchsuchen
+    #[poise::command(slash_command, guild_only)]
+    async fn search(
+        ctx: Context<'_>,
+        #[description = "Die Wörter, nach denen gesucht wird"] query: String,
+    ) -> Result<()> {
+        records_search_impl(ctx, query)
+            .await
+            .wrap_err("records_search")
+    }
+
+    /// Einen Fall mit den Präjudizien anzeigen, die er zitiert und von denen er zitiert wird
+    #[poise::command(slash_command, guild_only)]
+    async fn show(
+        ctx: Context<'_>,
+        #[description = "Die Fallnummer, z.B. 2023-04"]
+        #[autocomplete = "autocomplete_case_number"]
+        case: String,
+    ) -> Result<()> {
+        records_show_impl(ctx, case).await.wrap_err("records_show")
+    }
+
+    /// Alte Fälle aus einer CSV- oder JSON-Datei in die Akten übernehmen, ohne Gerichtsräume
+    #[poise::command(slash_command, guild_only, check = "is_admin")]
+    async fn import(
+        ctx: Context<'_>,
+        #[description = "Die Datei mit plaintiff, accused, judge, reason, verdict und date"]
+        file: Attachment,
+    ) -> Result<()> {
+        records_import_impl(ctx, file)
+            .await
+            .wrap_err("records_import")
+    }
+
+    #[tracing::instrument(skip(ctx))]
+    async fn records_import_impl(ctx: Context<'_>, file: Attachment) -> Result<()> {
+        let guild_id = ctx.guild_id().wrap_err("guild_id not found")?;
+        let storage = &ctx.data().storage;
+        let state = storage.find_or_insert_state(guild_id.into()).await?;
+        let strings = state.locale.strings();
+
+        ctx.defer().await?;
+
+        let bytes = file.download().await.wrap_err("download import file")?;
+        let cases = match archive::parse(strings, &file.filename, &bytes, ctx.author().id.into()) {
+            Ok(cases) => cases,
+            Err(response) => {
+                ctx.say(response.to_string()).await?;
+                return Ok(());
+            }
+        };
+
+        let count = cases.len();
+        for case in cases {
+            // the old cases get the next numbers of their year
+            let year = duration::year(case.date());
+            let number = storage.next_case_number(guild_id.into(), year).await?;
+            let lawsuit = case.into_lawsuit(guild_id.into(), format!("{year}-{number:02}"));
+            storage.add_lawsuit(&lawsuit).await?;
+        }
+        info!(%guild_id, count, "Imported historical cases");
+
+        audit::log(
+            &ctx.discord().http,
+            &state,
+            AuditEvent::LawsuitsImported {
+                count,
+                imported_by: ctx.author().id,
+            },
+        )
+        .await;
+
+        ctx.say((strings.cases_imported)(count)).await?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(ctx))]
+    async fn records_search_impl(ctx: Context<'_>, query: String) -> Result<()> {
+        let guild_id = ctx.guild_id().wrap_err("guild_id not found")?;
+        let strings = i18n::strings(ctx).await?;
+
+        let lawsuits = ctx
+            .data()
+            .storage
+            .search_closed_lawsuits(guild_id.into(), &query, MAX_RESULTS)
+            .await?;
+        if lawsuits.is_empty() {
+            ctx.say(strings.no_records_found).await?;
+            return Ok(());
+        }
+
+        let shorten = |text: &str| -> String {
+            if text.chars().count() <= MAX_TEXT_LEN {
+                return text.to_owned();
+            }
+            let mut shortened = text.chars().take(MAX_TEXT_LEN - 1).collect::<String>();
+            shortened.push('…');
+            shortened
+        };
+        let entries = lawsuits
+            .iter()
+            .map(|lawsuit| {
+                let case_number = lawsuit
+                    .case_number
+                    .clone()
+                    .unwrap_or_else(|| lawsuit.id.to_string());
+                format!(
+                    "**{case_number}** {}: {}",
+                    shorten(&lawsuit.reason),
+                    shorten(lawsuit.verdict.as_deref().unwrap_or_default())
+                )
+            })
+            .collect::<Vec<_>>();
+
+        paginate(ctx, &(strings.records_search_title)(&query), &entries).await
+    }
+
+    #[tracing::instrument(skip(ctx))]
+    async fn records_show_impl(ctx: Context<'_>, case: String) -> Result<()> {
+        let guild_id = ctx.guild_id().wrap_err("guild_id not found")?;
+        let strings = i18n::strings(ctx).await?;
+
+        let lawsuits = ctx.data().storage.find_lawsuits(guild_id.into()).await?;
+        let lawsuit = lawsuits
+            .iter()
+            .find(|lawsuit| !lawsuit.sealed && lawsuit.case_number.as_deref() == Some(case.trim()));
+        let lawsuit = match lawsuit {
+            Some(lawsuit) => lawsuit,
+            None => {
+                ctx.say(strings.lawsuit_not_found).await?;
+                return Ok(());
+            }
+        };
+
+        let case_numbers = |ids: &[Uuid]| -> String {
+            let case_numbers = lawsuits
+                .iter()
+                .filter(|lawsuit| ids.contains(&lawsuit.id))
+                .map(|lawsuit| {
+                    lawsuit
+                        .case_number
+                        .clone()
+                        .unwrap_or_else(|| lawsuit.id.to_string())
+                })
+                .collect::<Vec<_>>();
+            if case_numbers.is_empty() {
+                strings.none.to_owned()
+            } else {
+                case_numbers.join(", ")
+            }
+        };
+
+        ctx.send(|reply| {
+            reply.embed(|embed| {
+                embed
+                    .title((strings.case_title)(case.trim()))
+                    .field(strings.field_reason, &lawsuit.reason, false)
+                    .field(
+                        strings.field_plaintiff,
+                        format!("<@{}>", lawsuit.plaintiff),
+                        true,
+                    )
+                    .field(
+                        strings.field_accused,
+                        format!("<@{}>", lawsuit.accused),
+                        true,
+                    )
+                    .field(strings.field_judge, format!("<@{}>", lawsuit.judge), true);
+                if let Some(verdict) = &lawsuit.verdict {
+                    embed.field(strings.field_verdict, verdict, false);
+                }
+                embed
+                    .field(strings.field_cites, case_numbers(&lawsuit.cites), true)
+                    .field(
+                        strings.field_cited_by,
+                        case_numbers(&lawsuit.cited_by),
+                        true,
+                    )
+            })
+        })
+        .await
+        .wrap_err("send case")?;
+
+        Ok(())
+    }
+}
+
+pub mod docket {
+    use super::*;
+
+    /// Die offenen Prozesse nach Gerichtsraum anzeigen
+    #[poise::command(slash_command, guild_only)]
+    pub async fn docket(ctx: Context<'_>) -> Result<()> {
+        docket_impl(ctx).await.wrap_err("docket")
+    }
+
+    #[tracing::instrument(skip(ctx))]
+    async fn docket_impl(ctx: Context<'_>) -> Result<()> {
+        let guild_id = ctx.guild_id().wrap_err("guild_id not found")?;
+        let storage = &ctx.data().storage;
+
+        let state = storage.find_or_insert_state(guild_id.into()).await?;
+        let strings = state.locale.strings();
+
+        let mut lawsuits = storage.find_open_lawsuits(guild_id.into()).await?;
+        if lawsuits.is_empty() {
+            ctx.say(strings.no_open_lawsuits).await?;
+            return Ok(());
+        }
+        // the lawsuits from before the times were stored are the oldest
+        lawsuits.sort_by_key(|lawsuit| lawsuit.opened_at.map(|time| time.timestamp_millis()));
+
+        let entry = |lawsuit: &Lawsuit| {
+            let case_number = lawsuit
+                .case_number
+                .clone()
+                .unwrap_or_else(|| lawsuit.id.to_string());
+            let opened = lawsuit
+                .opened_at
+                .map_or(strings.unknown.to_owned(), duration::relative_timestamp);
+            (strings.docket_entry)(
+                &case_number,
+                &lawsuit.reason,
+                &format!("<@{}>", lawsuit.judge),
+                &opened,
+            )
+        };
+
+        let mut entries = Vec::new();
+        let mut hearings = lawsuits
+            .iter()
+            .filter_map(|lawsuit| Some((lawsuit, lawsuit.hearing_at?)))
+            .collect::<Vec<_>>();
+        hearings.sort_by_key(|(_, hearing_at)| *hearing_at);
+        if !hearings.is_empty() {
+            entries.push(format!("**{}**", strings.docket_hearings));
+            entries.extend(hearings.into_iter().map(|(lawsuit, hearing_at)| {
+                let case_number = lawsuit
+                    .case_number
+                    .clone()
+                    .unwrap_or_else(|| lawsuit.id.to_string());
+                (strings.docket_hearing)(
+                    &case_number,
+                    &format!("<#{}>", lawsuit.court_room),
+                    &duration::relative_timestamp(hearing_at),
+                )
+            }));
+        }
+        for room in &state.court_rooms {
+            let in_room = lawsuits
+                .iter()
+                .filter(|lawsuit| lawsuit.court_room == room.channel_id)
+                .map(entry)
+                .collect::<Vec<_>>();
+            if !in_room.is_empty() {
+                entries.push(format!("**<#{}>**", room.channel_id));
+                entries.extend(in_room);
+            }
+        }
+        // the rooms can be deleted while their lawsuit is still open
+        let without_room = lawsuits
+            .iter()
+            .filter(|lawsuit| {
+                !state
+                    .court_rooms
+                    .iter()
+                    .any(|room| room.channel_id == lawsuit.court_room)
+            })
+            .map(entry)
+            .collect::<Vec<_>>();
+        if !without_room.is_empty() {
+            entries.push(format!("**{}**", strings.docket_without_room));
+            entries.extend(without_room);
+        }
+
+        paginate(ctx, strings.docket_title, &entries).await
+    }
+}
+
+pub mod stats {
+    use super::*;
+
+    /// Statistiken über den Bot anzeigen
+    #[poise::command(slash_command)]
+    pub async fn stats(ctx: Context<'_>) -> Result<()> {
+        stats_impl(ctx).await.wrap_err("stats")
+    }
+
+    #[tracing::instrument(skip(ctx))]
+    async fn stats_impl(ctx: Context<'_>) -> Result<()> {
+        let data = ctx.data();
+        let strings = i18n::strings(ctx).await?;
+
+        let stats = data.storage.bot_stats().await?;
+
+        ctx.send(|reply| {
+            reply.embed(|embed| {
+                embed
+                    .title(strings.bot_stats_title)
+                    .field(strings.bot_stats_guilds, stats.guilds, true)
+                    .field(strings.bot_stats_lawsuits, stats.lawsuits, true)
+                    .field(strings.bot_stats_open_lawsuits, stats.open_lawsuits, true)
+                    .field(strings.bot_stats_prisoners, stats.prisoners, true)
+                    .field(
+                        strings.bot_stats_uptime,
+                        duration::format(data.started_at.elapsed()),
+                        true,
+                    )
+            })
+        })
+        .await
+        .wrap_err("reply")?;
+
+        Ok(())
+    }
+}
+
+pub mod debug {
+    use super::*;
+
+    /// The state is shown in the embed description, which can't be longer than 4096 characters.
+    const MAX_STATE_LEN: usize = 4000;
+
+    /// Diagnose-Informationen für die Fehlersuche anzeigen, nur für den Besitzer des Bots
+    #[poise::command(slash_command, guild_only, owners_only)]
+    pub async fn debug(ctx: Context<'_>) -> Result<()> {
+        debug_impl(ctx).await.wrap_err("debug")
+    }
+
+    #[tracing::instrument(skip(ctx))]
+    async fn debug_impl(ctx: Context<'_>) -> Result<()> {
+        let guild_id = ctx.guild_id().wrap_err("guild_id not found")?;
+        let data = ctx.data();
+        let storage = &data.storage;
+
+        let ping_started = Instant::now();
+        let database = match storage.ping().await {
+            Ok(()) => format!("{}ms", ping_started.elapsed().as_millis()),
+            Err(err) => format!("unreachable: {err}"),
+        };
+
+        let due_jobs = storage.find_due_jobs(DateTime::now()).await?.len();
+        let guild_jobs = storage.find_jobs(guild_id.into()).await?.len();
+
+        let (cache, state) = match storage.cached_state(guild_id.into()) {
+            Some((age, state)) => {
+                let state = serde_json::to_string_pretty(&state).wrap_err("serialize state")?;
+                let state = state.chars().take(MAX_STATE_LEN).collect::<String>();
+                (
+                    format!("{}s old", age.as_secs()),
+                    Some(format!("```json\n{state}\n```")),
+                )
+            }
+            None => ("not cached".to_owned(), None),
+        };
+
+        ctx.send(|reply| {
+            reply.ephemeral(true).embed(|embed| {
+                if let Some(state) = state {
+                    embed.description(state);
+                }
+                embed
+                    .title("Debug")
+                    .field("Shard", ctx.discord().shard_id, true)
+                    .field("Uptime", duration::format(data.started_at.elapsed()), true)
+                    .field(
+                        "Memory",
+                        memory_usage().unwrap_or_else(|| "unknown".to_owned()),
+                        true,
+                    )
+                    .field("Database latency", database, true)
+                    .field("Due jobs", due_jobs, true)
+                    .field("Jobs in this guild", guild_jobs, true)
+                    .field("Cached state", cache, true)
+            })
+        })
+        .await
+        .wrap_err("reply")?;
+
+        Ok(())
+    }
+
+    /// The resident memory of the process, only known on linux.
+    fn memory_usage() -> Option<String> {
+        let status = std::fs::read_to_string("/proc/self/status").ok()?;
+        status
+            .lines()
+            .find_map(|line| line.strip_prefix("VmRSS:"))
+            .map(|rss| rss.trim().to_owned())
+    }
+}
+
+pub mod analytics {
+    use super::*;
+
+    const DEFAULT_WINDOW: Duration = Duration::from_secs(60 * 60 * 24 * 7);
+
+    /// Anzeigen, wie die Befehle genutzt werden, nur für den Besitzer des Bots
+    #[poise::command(slash_command, owners_only)]
+    pub async fn analytics(
+        ctx: Context<'_>,
+        #[description = "Wie weit zurück, zum Beispiel 24h, sonst die letzten 7 Tage"]
+        window: Option<String>,
+    ) -> Result<()> {
+        analytics_impl(ctx, window).await.wrap_err("analytics")
+    }
+
+    #[tracing::instrument(skip(ctx))]
+    async fn analytics_impl(ctx: Context<'_>, window: Option<String>) -> Result<()> {
+        let window = match window.as_deref().map(duration::parse) {
+            None => DEFAULT_WINDOW,
+            Some(Some(window)) => window,
+            Some(None) => {
+                let strings = i18n::strings(ctx).await?;
+                ctx.say(strings.invalid_duration).await?;
+                return Ok(());
+            }
+        };
+
+        let since =
+            DateTime::from_millis(DateTime::now().timestamp_millis() - window.as_millis() as i64);
+        let usages = ctx.data().storage.find_command_usage(since).await?;
+        let summary = crate::analytics::Summary::new(&usages);
+
+        let commands = summary
+            .commands
+            .iter()
+            .take(crate::analytics::TOP_LEN)
+            .map(|stats| {
+                format!(
+                    "`{}` {}× · {} errors · {}ms",
+                    stats.command,
+                    stats.uses,
+                    crate::analytics::error_rate(stats.errors, stats.uses),
+                    stats.average_latency().as_millis(),
+                )
+            })
+            .collect::<Vec<_>>();
+        let guilds = summary
+            .guilds
+            .iter()
+            .take(crate::analytics::TOP_LEN)
+            .map(|(guild_id, uses)| format!("`{guild_id}` {uses}×"))
+            .collect::<Vec<_>>();
+        let list = |lines: Vec<String>| {
+            if lines.is_empty() {
+                "-".to_owned()
+            } else {
+                lines.join("\n")
+            }
+        };
+
+        ctx.send(|reply| {
+            reply.ephemeral(true).embed(|embed| {
+                embed
+                    .title(format!("Analytics, last {}", duration::format(window)))
+                    .field("Commands", summary.uses, true)
+                    .field(
+                        "Error rate",
+                        crate::analytics::error_rate(summary.errors, summary.uses),
+                        true,
+                    )
+                    .field("Guilds", summary.guilds.len(), true)
+                    .field("Top commands", list(commands), false)
+                    .field("Busiest guilds", list(guilds), false)
+            })
+        })
+        .await
+        .wrap_err("reply")?;
+
+        Ok(())
+    }
+}
+
+pub mod maintenance {
+    use super::*;
+
+    /// Den Bot für Migrationen und Backups schreibgeschützt machen, nur für den Besitzer des Bots
+    #[poise::command(slash_command, owners_only)]
+    pub async fn maintenance(
+        ctx: Context<'_>,
+        #[description = "Ob nur noch Befehle funktionieren, die nichts ändern"] enabled: bool,
+    ) -> Result<()> {
+        maintenance_impl(ctx, enabled).await.wrap_err("maintenance")
+    }
+
+    #[tracing::instrument(skip(ctx))]
+    async fn maintenance_impl(ctx: Context<'_>, enabled: bool) -> Result<()> {
+        let strings = i18n::strings(ctx).await?;
+
+        if ctx.data().maintenance.set(enabled) != enabled {
+            info!(enabled, author = %ctx.author().tag(), "Switched maintenance mode");
+        }
+
+        let content = if enabled {
+            strings.maintenance_enabled
+        } else {
+            strings.maintenance_disabled
+        };
+        ctx.send(|reply| reply.content(content).ephemeral(true))
+            .await
+            .wrap_err("reply")?;
+
+        Ok(())
+    }
+}
+
+pub mod guilds {
+    use super::*;
+
+    /// Festlegen, auf welchen Servern der Bot sein darf, nur für den Besitzer des Bots
+    #[poise::command(
+        slash_command,
+        owners_only,
+        subcommands("allow", "block", "forget", "list")
+    )]
+    pub async fn guilds(_: Context<'_>) -> Result<()> {
+        unreachable!()
+    }
+
+    /// Einen Server freischalten, auch wenn er nicht in der Allowlist ist
+    #[poise::command(slash_command, owners_only)]
+    async fn allow(
+        ctx: Context<'_>,
+        #[description = "Die ID des Servers"] guild_id: String,
+    ) -> Result<()> {
+        guilds_set_impl(ctx, guild_id, Some(true))
+            .await
+            .wrap_err("guilds_allow")
+    }
+
+    /// Einen Server sperren, der Bot verlässt ihn sofort
+    #[poise::command(slash_command, owners_only)]
+    async fn block(
+        ctx: Context<'_>,
+        #[description = "Die ID des Servers"] guild_id: String,
+    ) -> Result<()> {
+        guilds_set_impl(ctx, guild_id, Some(false))
+            .await
+            .wrap_err("guilds_block")
+    }
+
+    /// Einen Server aus den Listen entfernen, danach gelten wieder die Umgebungsvariablen
+    #[poise::command(slash_command, owners_only)]
+    async fn forget(
+        ctx: Context<'_>,
+        #[description = "Die ID des Servers"] guild_id: String,
+    ) -> Result<()> {
+        guilds_set_impl(ctx, guild_id, None)
+            .await
+            .wrap_err("guilds_forget")
+    }
+
+    /// Die freigeschalteten und gesperrten Server auflisten
+    #[poise::command(slash_command, owners_only)]
+    async fn list(ctx: Context<'_>) -> Result<()> {
+        guilds_list_impl(ctx).await.wrap_err("guilds_list")
+    }
+
+    #[tracing::instrument(skip(ctx))]
+    async fn guilds_set_impl(
+        ctx: Context<'_>,
+        guild_id: String,
+        allowed: Option<bool>,
+    ) -> Result<()> {
+        let strings = i18n::strings(ctx).await?;
+        let guild_id = match guild_id.trim().parse::<SnowflakeId>() {
+            Ok(guild_id) => guild_id,
+            Err(_) => {
+                ctx.send(|reply| reply.content(strings.invalid_guild_id).ephemeral(true))
+                    .await
+                    .wrap_err("reply")?;
+                return Ok(());
+            }
+        };
+
+        let data = ctx.data();
+        data.storage.set_guild_listing(guild_id, allowed).await?;
+        info!(%guild_id, ?allowed, author = %ctx.author().tag(), "Changed guild listing");
+
+        let content = match allowed {
+            Some(true) => strings.guild_allowed,
+            Some(false) => strings.guild_blocked,
+            None => strings.guild_forgotten,
+        };
+        ctx.send(|reply| reply.content(content).ephemeral(true))
+            .await
+            .wrap_err("reply")?;
+
+        // only leave after answering, the command could have been used in that guild
+        let guild_id = GuildId(guild_id.0);
+        if allowed != Some(true) {
+            if let Ok(guild) = guild_id.to_partial_guild(&ctx.discord().http).await {
+                guild_access::leave_if_unapproved(
+                    &ctx.discord().http,
+                    &data.storage,
+                    &data.guild_access,
+                    guild_id,
+                    guild.system_channel_id,
+                )
+                .await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(ctx))]
+    async fn guilds_list_impl(ctx: Context<'_>) -> Result<()> {
+        let data = ctx.data();
+        let listings = data.storage.find_guild_listings().await?;
+        let list = |allowed: bool| {
+            let ids = listings
+                .iter()
+                .filter(|listing| listing.allowed == allowed)
+                .map(|listing| format!("`{}`", listing.guild_id))
+                .collect::<Vec<_>>();
+            if ids.is_empty() {
+                "-".to_owned()
+            } else {
+                ids.join("\n")
+            }
+        };
+        let mode = if data.guild_access.allowlist_mode() {
+            "Allowlist, only approved guilds"
+        } else {
+            "Open, all guilds that aren't blocked"
+        };
+
+        ctx.send(|reply| {
+            reply.ephemeral(true).embed(|embed| {
+                embed
+                    .title("Guild access")
+                    .description(mode)
+                    .field("Allowed", list(true), true)
+                    .field("Blocked", list(false), true)
+                    .footer(|footer| {
+                        footer.text("GUILD_ALLOWLIST and GUILD_BLOCKLIST are not listed")
+                    })
+            })
+        })
+        .await
+        .wrap_err("reply")?;
+
+        Ok(())
+    }
+}
+
+pub async fn listener(
+    ctx: &serenity::Context,
+    event: &Event<'_>,
+    _: poise::FrameworkContext<'_, Handler, Report>,
+    data: &Handler,
+) -> Result<()> {
+    match event {
+        Event::Ready { data_about_bot } => {
+            data.presence.start(ctx, &data.storage, &data.tasks).await;
+            info!(shard = ?data_about_bot.shard, "Shard is connected");
+        }
+        // the activity is stored and gag orders are enforced, neither happens during maintenance
+        Event::Message { new_message } if !data.maintenance.is_enabled() => {
+            if let Err(err) = data.activity.record(&data.storage, new_message).await {
+                error!(
+                    ?err,
+                    shard_id = ctx.shard_id,
+                    "An error occurred in message handler"
+                );
+            }
+            if let Err(err) =
+                gag::enforce(&ctx.http, &data.storage, new_message, data.message_content).await
+            {
+                error!(
+                    ?err,
+                    shard_id = ctx.shard_id,
+                    "An error occurred in gag order handler"
+                );
+            }
+        }
+        // also sent for every guild when the shard connects
+        Event::GuildCreate { guild, .. } => {
+            if let Err(err) = guild_access::leave_if_unapproved(
+                &ctx.http,
+                &data.storage,
+                &data.guild_access,
+                guild.id,
+                guild.system_channel_id,
+            )
+            .await
+            {
+                error!(
+                    ?err,
+                    shard_id = ctx.shard_id,
+                    "An error occurred in guild_create handler"
+                );
+            }
+        }
+        Event::GuildMemberAddition { new_member } => {
+            if let Err(err) = data.handle_guild_member_join(ctx, new_member).await {
+                error!(
+                    ?err,
+                    shard_id = ctx.shard_id,
+                    "An error occurred in guild_member_addition handler"
+                );
+            }
+        }
+        Event::InteractionCreate { interaction } => {
+            if let Err(err) = crate::evidence::handle_interaction(
+                &ctx.http,
+                &data.storage,
+                &data.maintenance,
+                interaction,
+            )
+            .await
+            {
+                error!(
+                    ?err,
+                    shard_id = ctx.shard_id,
+                    "An error occurred in interaction_create handler"
+                );
+            }
+        }
+        Event::ChannelCreate { channel } => {
+            if let Err(err) =
+                crate::prison::handle_channel_create(&ctx.http, &data.storage, channel).await
+            {
+                error!(
+                    ?err,
+                    shard_id = ctx.shard_id,
+                    "An error occurred in channel_create handler"
+                );
+            }
+        }
+        // the new role is stored, so it's only recreated after the maintenance on the next arrest
+        Event::GuildRoleDelete {
+            guild_id,
+            removed_role_id,
+            ..
+        } if !data.maintenance.is_enabled() => {
+            if let Err(err) = crate::prison::handle_role_delete(
+                &ctx.http,
+                &data.storage,
+                *guild_id,
+                *removed_role_id,
+            )
+            .await
+            {
+                error!(
+                    ?err,
+                    shard_id = ctx.shard_id,
+                    "An error occurred in guild_role_delete handler"
+                );
+            }
+        }
+        Event::GuildMemberUpdate {
+            old_if_available,
+            new,
+        } => {
+            if let Err(err) = crate::prison::handle_member_update(
+                &ctx.http,
+                &data.storage,
+                old_if_available.as_ref(),
+                new,
+            )
+            .await
+            {
+                error!(
+                    ?err,
+                    shard_id = ctx.shard_id,
+                    "An error occurred in guild_member_update handler"
+                );
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+pub async fn error_handler(error: poise::FrameworkError<'_, Handler, Report>) {
+    match error {
+        poise::FrameworkError::MissingUserPermissions { ctx, .. }
+        | poise::FrameworkError::CommandCheckFailed { ctx, error: None }
+        | poise::FrameworkError::NotAnOwner { ctx } => {
+            metrics::command_finished(ctx, "denied");
+            let _ = ctx.say("du häsch kei recht für da!").await;
+        }
+        poise::FrameworkError::CommandCheckFailed {
+            ctx,
+            error: Some(error),
+        } => match error.downcast_ref::<OnCooldown>() {
+            None if error.downcast_ref::<UnderMaintenance>().is_some() => {
+                metrics::command_finished(ctx, "maintenance");
+
+                let strings = i18n::strings(ctx)
+                    .await
+                    .unwrap_or_else(|_| Locale::default().strings());
+                let _ = ctx
+                    .send(|reply| reply.content(strings.under_maintenance).ephemeral(true))
+                    .await;
+            }
+            None if error.downcast_ref::<Offline>().is_some() => {
+                metrics::command_finished(ctx, "offline");
+
+                let strings = i18n::cached_strings(ctx);
+                let _ = ctx
+                    .send(|reply| reply.content(strings.court_offline).ephemeral(true))
+                    .await;
+            }
+            Some(OnCooldown(remaining)) => {
+                metrics::command_finished(ctx, "cooldown");
+
+                let strings = i18n::strings(ctx)
+                    .await
+                    .unwrap_or_else(|_| Locale::default().strings());
+                // rounded up, so that it never says 0s
+                let secs = remaining.as_secs() + u64::from(remaining.subsec_nanos() > 0);
+                let _ = ctx
+                    .send(|reply| reply.content((strings.on_cooldown)(secs)).ephemeral(true))
+                    .await;
+            }
+            None => error!(?error, "Error during command check"),
+        },
+        poise::FrameworkError::GuildOnly { ctx } => {
             let _ = ctx
                 .say("du chasch de command nur uf emene serve nutze!")
                 .await;
         }
+        poise::FrameworkError::Command { error, ctx } => {
+            metrics::command_finished(ctx, "error");
+
+            // users can't do anything with the report, but with the id it can be found in the logs
+            let error_id = Uuid::new().to_string()[..8].to_owned();
+            error!(%error_id, ?error, "Error during command execution");
+
+            // without the database, the state would only be found after the next timeout
+            if outage::is_unreachable(&error) {
+                ctx.data().outage.record_error(&error);
+                let strings = i18n::cached_strings(ctx);
+                let _ = ctx
+                    .send(|reply| reply.content(strings.court_offline).ephemeral(true))
+                    .await;
+                return;
+            }
+            error_report::report(ctx, &error_id, &error).await;
+
+            let strings = i18n::strings(ctx)
+                .await
+                .unwrap_or_else(|_| Locale::default().strings());
+            let _ = ctx
+                .send(|reply| {
+                    reply
+                        .content((strings.internal_error)(&error_id))
+                        .ephemeral(true)
+                })
+                .await;
+        }
         err => {
             error!(?err, "Error during command execution");
         }
@@ -0,0 +1,121 @@
+use std::{net::SocketAddr, sync::Arc};
+
+use axum::{http::StatusCode, routing::get, Extension, Json, Router};
+use color_eyre::Result;
+use poise::serenity::{client::bridge::gateway::ShardManager, gateway::ConnectionStage};
+use serde::Serialize;
+use tokio::sync::Mutex;
+use tracing::{error, info, warn};
+
+use crate::{
+    api, calendar, dashboard::Dashboard, maintenance::Maintenance, metrics, storage::Storage,
+    WrapErr,
+};
+
+struct HttpState {
+    shard_manager: Arc<Mutex<ShardManager>>,
+    storage: Arc<dyn Storage>,
+}
+
+#[derive(Debug, Serialize)]
+struct Health {
+    gateway: bool,
+    database: bool,
+    shards: Vec<ShardHealth>,
+}
+
+#[derive(Debug, Serialize)]
+struct ShardHealth {
+    id: u64,
+    connected: bool,
+    stage: String,
+    latency_ms: Option<u128>,
+}
+
+/// Serves `/healthz`, which answers with 503 while a shard isn't connected or the database can't
+/// be reached, so the container can be restarted when the bot is stuck. The prometheus metrics are
+/// served on `/metrics`, the API for websites under `/api/v1`, see [`api::router`], the calendar
+/// feeds under `/calendar`, see [`calendar::router`], and the dashboard under `/dashboard` if it's
+/// configured.
+pub async fn serve(
+    addr: SocketAddr,
+    shard_manager: Arc<Mutex<ShardManager>>,
+    storage: Arc<dyn Storage>,
+    dashboard: Option<Arc<Dashboard>>,
+    maintenance: Arc<Maintenance>,
+) -> Result<()> {
+    let state = Arc::new(HttpState {
+        shard_manager,
+        storage: storage.clone(),
+    });
+    let mut app = Router::new()
+        .route("/healthz", get(healthz))
+        .route("/metrics", get(prometheus_metrics))
+        .nest("/api/v1", api::router())
+        .nest("/calendar", calendar::router());
+    if let Some(dashboard) = dashboard {
+        app = app.nest(
+            "/dashboard",
+            crate::dashboard::router(dashboard, maintenance),
+        );
+    }
+    let app = app.layer(Extension(state)).layer(Extension(storage));
+
+    info!(%addr, "Serving health check, metrics and API");
+
+    axum::Server::bind(&addr)
+        .serve(app.into_make_service())
+        .await
+        .wrap_err("http server")
+}
+
+async fn healthz(Extension(state): Extension<Arc<HttpState>>) -> (StatusCode, Json<Health>) {
+    let shards = {
+        let shard_manager = state.shard_manager.lock().await;
+        let runners = shard_manager.runners.lock().await;
+        runners
+            .iter()
+            .map(|(id, runner)| ShardHealth {
+                id: id.0,
+                connected: runner.stage == ConnectionStage::Connected,
+                stage: runner.stage.to_string(),
+                latency_ms: runner.latency.map(|latency| latency.as_millis()),
+            })
+            .collect::<Vec<_>>()
+    };
+    // before the first shard is started there's nothing to look at, which isn't healthy either
+    let gateway = !shards.is_empty() && shards.iter().all(|shard| shard.connected);
+
+    let database = match state.storage.ping().await {
+        Ok(()) => true,
+        Err(err) => {
+            warn!(?err, "Database is not reachable");
+            false
+        }
+    };
+
+    let status = if gateway && database {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+
+    (
+        status,
+        Json(Health {
+            gateway,
+            database,
+            shards,
+        }),
+    )
+}
+
+async fn prometheus_metrics(Extension(state): Extension<Arc<HttpState>>) -> (StatusCode, String) {
+    match metrics::gather(&*state.storage).await {
+        Ok(metrics) => (StatusCode::OK, metrics),
+        Err(err) => {
+            error!(?err, "Failed to gather metrics");
+            (StatusCode::INTERNAL_SERVER_ERROR, String::new())
+        }
+    }
+}
@@ -0,0 +1,1983 @@
+use color_eyre::Result;
+use serde::{Deserialize, Serialize};
+
+use crate::Context;
+
+/// The language of the responses in a guild.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, poise::ChoiceParameter)]
+pub enum Locale {
+    #[name = "Schwiizerdütsch"]
+    #[serde(rename = "de-CH")]
+    DeCh,
+    #[name = "Deutsch"]
+    #[serde(rename = "de-DE")]
+    DeDe,
+    #[name = "English"]
+    #[serde(rename = "en")]
+    En,
+}
+
+impl Default for Locale {
+    fn default() -> Self {
+        Self::DeCh
+    }
+}
+
+impl Locale {
+    pub fn strings(self) -> &'static Strings {
+        match self {
+            Self::DeCh => &DE_CH,
+            Self::DeDe => &DE_DE,
+            Self::En => &EN,
+        }
+    }
+}
+
+/// The strings for the locale of the guild the command was used in.
+pub async fn strings(ctx: Context<'_>) -> Result<&'static Strings> {
+    let guild_id = match ctx.guild_id() {
+        Some(guild_id) => guild_id,
+        None => return Ok(Locale::default().strings()),
+    };
+
+    let state = ctx
+        .data()
+        .storage
+        .find_or_insert_state(guild_id.into())
+        .await?;
+
+    Ok(state.locale.strings())
+}
+
+/// Like [`strings`], but without asking the database, for when it's unreachable. Guilds that
+/// aren't cached get the default locale.
+pub fn cached_strings(ctx: Context<'_>) -> &'static Strings {
+    ctx.guild_id()
+        .and_then(|guild_id| ctx.data().storage.cached_state(guild_id.into()))
+        .map_or_else(
+            || Locale::default().strings(),
+            |(_, state)| state.locale.strings(),
+        )
+}
+
+/// Every user facing string of the bot. Strings that contain values are functions.
+pub struct Strings {
+    // general
+    pub hello: &'static str,
+    pub is_set: &'static str,
+    pub invalid_webhook_url: &'static str,
+    pub unknown_placeholder: fn(&str, &str) -> String,
+    pub message_templates_title: &'static str,
+    pub message_template_default: &'static str,
+    pub api_token_created: fn(&str) -> String,
+    pub api_token_revoked: &'static str,
+    pub calendar_token_created: fn(&str) -> String,
+    pub calendar_token_revoked: &'static str,
+    pub calendar_name: &'static str,
+    pub calendar_hearing: fn(&str, &str) -> String,
+    pub removed: &'static str,
+    pub no_permission: &'static str,
+    pub guild_only: &'static str,
+    pub invalid_duration: &'static str,
+    pub slowmode_too_long: fn(&str) -> String,
+    pub none: &'static str,
+    pub yes: &'static str,
+    pub no: &'static str,
+    pub internal_error: fn(&str) -> String,
+    pub on_cooldown: fn(u64) -> String,
+    pub court_offline: &'static str,
+    pub under_maintenance: &'static str,
+    pub maintenance_enabled: &'static str,
+    pub maintenance_disabled: &'static str,
+    pub guild_not_approved: &'static str,
+    pub invalid_guild_id: &'static str,
+    pub guild_allowed: &'static str,
+    pub guild_blocked: &'static str,
+    pub guild_forgotten: &'static str,
+    pub error_report_title: &'static str,
+    pub presence_open_lawsuits: fn(u64) -> String,
+    pub presence_prisoners: fn(u64) -> String,
+
+    // pagination
+    pub page: fn(usize, usize) -> String,
+    pub page_option: fn(usize) -> String,
+    pub previous_page: &'static str,
+    pub next_page: &'static str,
+
+    // lawsuits
+    pub not_a_category: &'static str,
+    pub no_active_lawsuit: &'static str,
+    pub lawsuit_not_found: &'static str,
+    pub lawsuit_dismissed: fn(&str) -> String,
+    pub inactivity_nudge_judge: fn(&str, &str) -> String,
+    pub inactivity_nudge_admins: fn(&str, &str, &str) -> String,
+    pub record_title: fn(&str) -> String,
+    pub record_opened_title: fn(&str) -> String,
+    pub case_title: fn(&str) -> String,
+    pub transcript_title: fn(&str) -> String,
+    pub transcript_exported: fn(usize) -> String,
+    pub transcript_unavailable: &'static str,
+    pub cases_import_invalid_file: fn(&str) -> String,
+    pub cases_import_missing_column: fn(&str) -> String,
+    pub cases_import_invalid_case: fn(usize, &str) -> String,
+    pub cases_import_empty: &'static str,
+    pub cases_import_too_many: fn(usize) -> String,
+    pub cases_imported: fn(usize) -> String,
+    pub precedent_not_found: fn(&str) -> String,
+    pub lawsuit_closed: &'static str,
+    pub no_lawsuits: &'static str,
+    pub lawsuits_title: &'static str,
+    pub no_open_lawsuits: &'static str,
+    pub docket_title: &'static str,
+    pub docket_without_room: &'static str,
+    pub docket_entry: fn(&str, &str, &str, &str) -> String,
+    pub docket_hearings: &'static str,
+    pub docket_hearing: fn(&str, &str, &str) -> String,
+    pub unknown: &'static str,
+    pub lawsuit_entry: fn(&str, &str, &str, &str) -> String,
+    pub lawsuit_verdict: fn(&str) -> String,
+    pub lawsuit_verdict_sealed: &'static str,
+    pub lawsuit_running_in: fn(&str) -> String,
+    pub lawsuits_cleared: fn(&str) -> String,
+    pub lawsuits_restored: &'static str,
+    pub nothing_to_restore: &'static str,
+    pub confirm_button: &'static str,
+    pub cancel_button: &'static str,
+    pub cancelled: &'static str,
+    pub clear_confirm: fn(&str) -> String,
+    pub clear_all_confirm: fn(&str, &str) -> String,
+    pub clear_dry_run: fn(&str) -> String,
+    pub clear_lawsuits: fn(usize) -> String,
+    pub clear_rooms: fn(usize) -> String,
+    pub clear_prisoners: fn(usize) -> String,
+    pub clear_settings: &'static str,
+    pub lawsuits_deleted: fn(usize) -> String,
+    pub court_rooms_removed: fn(usize) -> String,
+    pub court_rooms_in_use: &'static str,
+    pub lawsuit_title: &'static str,
+    pub lawsuit_closed_title: &'static str,
+    pub case_summary: &'static str,
+    pub case_summary_no_verdict: &'static str,
+    pub case_summary_too_long: &'static str,
+    pub set_category_first: &'static str,
+    pub lawsuit_opened: fn(&str) -> String,
+    pub room_select_prompt: &'static str,
+    pub room_last_used: fn(&str) -> String,
+    pub room_never_used: &'static str,
+    pub evidence_title: fn(usize) -> String,
+    pub evidence_submit: &'static str,
+    pub evidence_modal_title: &'static str,
+    pub evidence_modal_description: &'static str,
+    pub evidence_modal_link: &'static str,
+    pub invalid_evidence_link: &'static str,
+    pub no_jurors: &'static str,
+    pub jury_already_voted: &'static str,
+    pub jury_ballots_sent: fn(usize, &str) -> String,
+    pub jury_ballot_title: &'static str,
+    pub jury_ballot_description: fn(&str) -> String,
+    pub jury_guilty: &'static str,
+    pub jury_not_guilty: &'static str,
+    pub jury_abstained: &'static str,
+    pub jury_vote_counted: fn(&str) -> String,
+    pub jury_result_title: &'static str,
+    pub jury_joined: &'static str,
+    pub jury_already_joined: &'static str,
+    pub jury_left: &'static str,
+    pub jury_not_joined: &'static str,
+    pub jury_pool_too_small: fn(usize, usize) -> String,
+    pub jury_summons_title: &'static str,
+    pub jury_summons_description: fn(&str) -> String,
+    pub subpoena_title: &'static str,
+    pub subpoena_description: fn(&str, &str) -> String,
+    pub subpoena_accept: &'static str,
+    pub subpoena_decline: &'static str,
+    pub subpoena_accepted: &'static str,
+    pub subpoena_declined: &'static str,
+    pub subpoena_sent: fn(&str, &str) -> String,
+    pub subpoena_undelivered: fn(&str) -> String,
+    pub subpoena_declined_flag: fn(&str, &str) -> String,
+    pub subpoena_ignored_flag: fn(&str, &str) -> String,
+    pub witness_already_added: &'static str,
+    pub hearing_set: fn(&str) -> String,
+    pub hearing_removed: &'static str,
+    pub room_closed_to_public: &'static str,
+    pub held_in_contempt: fn(&str, &str) -> String,
+    pub held_in_contempt_in_room: fn(&str, &str) -> String,
+    pub contempt_too_long: fn(&str) -> String,
+    pub ejected: fn(&str) -> String,
+    pub already_ejected: &'static str,
+    pub cannot_eject_participant: &'static str,
+    pub gag_ordered: fn(&str) -> String,
+    pub gag_already_ordered: &'static str,
+    pub gag_order_violated: fn(&str, &str) -> String,
+    pub room_opened_to_public: &'static str,
+    pub room_already_public: &'static str,
+    pub room_already_closed_to_public: &'static str,
+    pub invalid_hearing_time: &'static str,
+    pub witness_is_party: &'static str,
+    pub election_title: &'static str,
+    pub election_candidacy_description: fn(&str) -> String,
+    pub election_candidacy_button: &'static str,
+    pub election_candidate_registered: &'static str,
+    pub election_already_candidate: &'static str,
+    pub election_full: fn(usize) -> String,
+    pub election_voting_description: fn(&str) -> String,
+    pub election_vote_placeholder: &'static str,
+    pub election_no_candidates: &'static str,
+    pub election_cancelled: &'static str,
+    pub election_result_title: &'static str,
+    pub election_no_winners: &'static str,
+    pub election_started: &'static str,
+    pub elections_title: &'static str,
+    pub no_elections: &'static str,
+    pub no_records_found: &'static str,
+    pub records_search_title: fn(&str) -> String,
+    pub invalid_amount: &'static str,
+    pub fine_already_imposed: &'static str,
+    pub fine_imposed: fn(&str, &str) -> String,
+    pub no_open_fines: &'static str,
+    pub fines_paid: fn(usize, &str) -> String,
+    pub fines_in_debt: fn(&str) -> String,
+    pub fine_status_paid: &'static str,
+    pub fine_status_open: &'static str,
+    pub balance_of: fn(&str, &str) -> String,
+    pub currency: fn(i64) -> String,
+    pub fee_hourly: fn(&str) -> String,
+    pub fee_flat: fn(&str) -> String,
+    pub fee_set: fn(&str) -> String,
+    pub fee_removed: &'static str,
+    pub lawyer_fees_title: &'static str,
+    pub no_lawyer_fees: &'static str,
+    pub not_a_client: &'static str,
+    pub not_enough_money: fn(&str) -> String,
+    pub retainer_paid: fn(&str, &str) -> String,
+    pub not_a_lawyer: &'static str,
+    pub no_fee_set: &'static str,
+    pub billed: fn(&str, &str) -> String,
+    pub bar_no_questions: &'static str,
+    pub bar_already_admitted: &'static str,
+    pub bar_question: fn(usize, usize, &str) -> String,
+    pub bar_exam_passed: fn(usize, usize) -> String,
+    pub bar_exam_failed: fn(usize, usize) -> String,
+    pub bar_admitted: fn(&str) -> String,
+    pub lawyer_not_admitted: fn(&str) -> String,
+    pub conflict_judge_is_party: fn(&str) -> String,
+    pub conflict_lawyer_for_both_sides: fn(&str) -> String,
+    pub conflict_lawyer_is_opponent: fn(&str) -> String,
+    pub invalid_parties_same: &'static str,
+    pub invalid_parties_bot: fn(&str) -> String,
+    pub invalid_parties_own_lawyer: fn(&str) -> String,
+    pub lawsuit_duplicate: fn(&str) -> String,
+    pub lawsuit_limit_reached: fn(&str, usize) -> String,
+    pub filing_cooldown: fn(&str) -> String,
+    pub litigant_banned_refusal: fn(&str) -> String,
+    pub litigant_banned: fn(&str) -> String,
+    pub litigant_unbanned: fn(&str) -> String,
+    pub litigant_already_banned: &'static str,
+    pub litigant_not_banned: &'static str,
+    pub permissions_synced: fn(usize) -> String,
+    pub permissions_sync_failed: fn(&str) -> String,
+    pub conflict_warning: fn(&str) -> String,
+    pub invalid_bar_answers: fn(usize) -> String,
+    pub bar_question_added: fn(usize) -> String,
+    pub bar_question_removed: &'static str,
+    pub bar_question_not_found: &'static str,
+    pub bar_questions_title: &'static str,
+    pub court_channel_not_found: &'static str,
+    pub room_in_wrong_category: fn(&str) -> String,
+    pub appeal_reason: fn(&str) -> String,
+    pub no_recent_members: &'static str,
+    pub lawsuit_wizard_prompt: &'static str,
+    pub lawsuit_wizard_accused: &'static str,
+    pub lawsuit_wizard_judge: &'static str,
+    pub lawsuit_wizard_title: &'static str,
+    pub lawsuit_wizard_remedy: &'static str,
+    pub reason_with_law: fn(&str, &str) -> String,
+    pub reason_with_remedy: fn(&str, &str) -> String,
+    pub no_registered_judge: &'static str,
+    pub judge_registered: &'static str,
+    pub judge_already_registered: &'static str,
+    pub judge_unregistered: &'static str,
+    pub judge_not_registered: &'static str,
+    pub registered_judges_title: &'static str,
+    pub no_registered_judges: &'static str,
+    pub judge_stats_title: fn(&str) -> String,
+    pub judge_stats_closed: &'static str,
+    pub judge_stats_open: &'static str,
+    pub judge_stats_average_duration: &'static str,
+    pub judge_stats_overturn_rate: &'static str,
+
+    // prison
+    pub set_prison_role_first: &'static str,
+    pub set_probation_role_first: &'static str,
+    pub tier_not_found: &'static str,
+    pub arrested: &'static str,
+    pub arrested_until: fn(&str) -> String,
+    pub nobody_to_arrest: &'static str,
+    pub arrested_many: fn(usize) -> String,
+    pub released: &'static str,
+    pub released_many: fn(usize) -> String,
+    pub released_many_dry_run: fn(usize, &str) -> String,
+    pub batch_failed: fn(&str) -> String,
+    pub not_in_prison: &'static str,
+    pub indefinite_sentence: &'static str,
+    pub sentence_over: &'static str,
+    pub release_moved: fn(&str) -> String,
+    pub already_on_parole: &'static str,
+    pub on_parole_until: fn(&str) -> String,
+    pub not_on_parole: &'static str,
+    pub back_to_prison: &'static str,
+    pub appeal_not_in_prison: &'static str,
+    pub appeal_unknown_arrester: &'static str,
+    pub appeal_no_judge: &'static str,
+    pub appeal_already_running: &'static str,
+    pub appeal_lawsuit_not_found: &'static str,
+    pub appeal_not_a_party: &'static str,
+    pub appeal_same_judge: &'static str,
+    pub verdict_appeal_reason: fn(&str, &str) -> String,
+    pub not_an_appeal: &'static str,
+    pub prison_empty: &'static str,
+    pub prisoners_title: &'static str,
+    pub list_on_parole_until: fn(&str) -> String,
+    pub list_released_at: fn(&str) -> String,
+    pub escaped: fn(&str) -> String,
+    pub prison_role_recreated: fn(&str) -> String,
+    pub arrested_dm_title: &'static str,
+    pub released_dm_title: &'static str,
+    pub dm_ordered_by: &'static str,
+    pub dm_released_by: &'static str,
+    pub stats_title: &'static str,
+    pub prison_check_title: fn(&str) -> String,
+    pub stats_current: &'static str,
+    pub stats_total: &'static str,
+    pub stats_average_sentence: &'static str,
+    pub stats_top_reasons: &'static str,
+    pub court_stats_title: &'static str,
+    pub court_stats_per_month: &'static str,
+    pub court_stats_verdicts: &'static str,
+    pub court_stats_verdict_counts: fn(u64, u64, u64) -> String,
+    pub court_stats_time_to_verdict: &'static str,
+    pub court_stats_busiest_judges: &'static str,
+    pub court_stats_most_sued: &'static str,
+    pub leaderboard_title: &'static str,
+    pub leaderboard_filed: &'static str,
+    pub leaderboard_won: &'static str,
+    pub leaderboard_convicted: &'static str,
+    pub lawyer_record_title: fn(&str) -> String,
+    pub lawyer_record_won: &'static str,
+    pub lawyer_record_lost: &'static str,
+    pub lawyer_record_success_rate: &'static str,
+    pub lawyer_record_cases: &'static str,
+    pub lawyer_record_case: fn(&str, &str, &str) -> String,
+    pub lawyer_record_ongoing: &'static str,
+    pub lawyer_record_undecided: &'static str,
+    pub bot_stats_title: &'static str,
+    pub bot_stats_guilds: &'static str,
+    pub bot_stats_lawsuits: &'static str,
+    pub bot_stats_open_lawsuits: &'static str,
+    pub bot_stats_prisoners: &'static str,
+    pub bot_stats_uptime: &'static str,
+    pub weekly_report_title: &'static str,
+    pub weekly_report_opened: &'static str,
+    pub weekly_report_closed: &'static str,
+    pub weekly_report_arrests: &'static str,
+    pub weekly_report_releases: &'static str,
+    pub weekly_report_verdicts: &'static str,
+    pub dashboard_title: &'static str,
+    pub dashboard_no_guilds: &'static str,
+    pub dashboard_save: &'static str,
+    pub dashboard_logout: &'static str,
+
+    // embed fields
+    pub field_reason: &'static str,
+    pub field_plaintiff: &'static str,
+    pub field_plaintiff_lawyer: &'static str,
+    pub field_accused: &'static str,
+    pub field_accused_lawyer: &'static str,
+    pub field_judge: &'static str,
+    pub field_case_number: &'static str,
+    pub field_opened_at: &'static str,
+    pub field_closed_at: &'static str,
+    pub field_cites: &'static str,
+    pub field_cited_by: &'static str,
+    pub field_verdict: &'static str,
+    pub field_reasoning: &'static str,
+    pub field_sentence: &'static str,
+    pub field_author: &'static str,
+    pub field_added_by: &'static str,
+    pub field_attachments: &'static str,
+    pub field_error_id: &'static str,
+    pub field_command: &'static str,
+    pub field_user: &'static str,
+    pub field_guild: &'static str,
+    pub field_court_room: &'static str,
+    pub field_channel: &'static str,
+    pub field_prisoner: &'static str,
+    pub field_arrested_at: &'static str,
+    pub field_tier: &'static str,
+    pub field_duration: &'static str,
+    pub field_ordered_by: &'static str,
+    pub field_released_by: &'static str,
+    pub field_changed_by: &'static str,
+    pub field_revoked_by: &'static str,
+    pub field_count: &'static str,
+    pub field_release: &'static str,
+    pub field_until: &'static str,
+    pub field_setting: &'static str,
+    pub field_value: &'static str,
+    pub field_mode: &'static str,
+    pub field_imported_by: &'static str,
+    pub field_erased_by: &'static str,
+    pub field_fine: &'static str,
+    pub field_amount: &'static str,
+    pub field_balance: &'static str,
+    pub field_lawyer_fees: &'static str,
+    pub field_lawyer_fee: &'static str,
+    pub field_lawyer: &'static str,
+    pub field_client: &'static str,
+    pub field_registered_judge: &'static str,
+    pub field_admitted_lawyer: &'static str,
+    pub field_jury_pool: &'static str,
+    pub field_role: &'static str,
+    pub field_seats: &'static str,
+    pub field_voters: &'static str,
+    pub field_winners: &'static str,
+    pub field_candidates: &'static str,
+    pub field_elections: &'static str,
+    pub no_lawyer: &'static str,
+    pub no_reason: &'static str,
+    pub indefinite: &'static str,
+    pub until: fn(&str) -> String,
+    pub sentence_served: &'static str,
+
+    // audit
+    pub audit_lawsuit_created: &'static str,
+    pub audit_lawsuit_closed: &'static str,
+    pub audit_court_room_created: &'static str,
+    pub audit_arrested: &'static str,
+    pub audit_arrested_many: &'static str,
+    pub audit_released: &'static str,
+    pub audit_released_all: &'static str,
+    pub audit_sentence_changed: &'static str,
+    pub audit_paroled: &'static str,
+    pub audit_parole_revoked: &'static str,
+    pub audit_escaped: &'static str,
+    pub audit_config_changed: &'static str,
+    pub audit_sentence_served: &'static str,
+    pub audit_guild_imported: &'static str,
+    pub audit_lawsuits_imported: &'static str,
+    pub audit_user_erased: &'static str,
+    pub audit_fine_imposed: &'static str,
+    pub audit_fines_paid: &'static str,
+    pub audit_balance_changed: &'static str,
+    pub audit_lawyer_paid: &'static str,
+    pub audit_contempt: &'static str,
+    pub audit_ejected: &'static str,
+    pub audit_gag_ordered: &'static str,
+    pub audit_lawyer_admitted: &'static str,
+    pub audit_litigant_banned: &'static str,
+    pub audit_litigant_unbanned: &'static str,
+    pub audit_election_finished: &'static str,
+
+    // privacy
+    pub erase_title: &'static str,
+    pub erase_lawsuits: &'static str,
+    pub erase_prison_entries: &'static str,
+    pub erase_arrests: &'static str,
+
+    // settings
+    pub config_title: &'static str,
+    pub export_done: &'static str,
+    pub import_invalid_file: fn(&str) -> String,
+    pub import_wrong_guild: &'static str,
+    pub import_duplicate_prisoner: fn(&str) -> String,
+    pub import_duplicate_member: fn(&str) -> String,
+    pub import_invalid_webhook: &'static str,
+    pub import_preview_title: &'static str,
+    pub import_done_title: &'static str,
+    pub import_dry_run_hint: &'static str,
+    pub import_lawsuits: &'static str,
+    pub import_court_rooms: &'static str,
+    pub import_prison_tiers: &'static str,
+    pub import_prison_history: &'static str,
+    pub import_jobs: &'static str,
+    pub import_members: &'static str,
+    pub import_changed: &'static str,
+    pub import_unchanged: &'static str,
+    pub import_mode_replace: &'static str,
+    pub import_mode_merge: &'static str,
+    pub setting_court_category: &'static str,
+    pub setting_appeal_category: &'static str,
+    pub setting_prison_role: &'static str,
+    pub setting_probation_role: &'static str,
+    pub setting_prison_channel: &'static str,
+    pub setting_prison_tier: &'static str,
+    pub setting_tier_removed: fn(&str) -> String,
+    pub setting_strip_roles: &'static str,
+    pub setting_moderator_channel: &'static str,
+    pub setting_notifications: &'static str,
+    pub setting_appeal_judge: &'static str,
+    pub setting_audit_channel: &'static str,
+    pub setting_error_channel: &'static str,
+    pub setting_records_channel: &'static str,
+    pub setting_news_role: &'static str,
+    pub setting_weekly_report: &'static str,
+    pub setting_statute_of_limitations: &'static str,
+    pub setting_inactivity_nudge: &'static str,
+    pub setting_lawsuit_limit: &'static str,
+    pub setting_filing_cooldown: &'static str,
+    pub setting_court_slowmode: &'static str,
+    pub setting_locale: &'static str,
+    pub setting_webhook: &'static str,
+    pub setting_api_token: &'static str,
+    pub setting_calendar_token: &'static str,
+    pub setting_message_template: &'static str,
+    pub setting_admin_role: &'static str,
+    pub setting_judge_role: &'static str,
+    pub setting_bailiff_role: &'static str,
+    pub setting_strict_lawyers: &'static str,
+    pub setting_conflict_policy: &'static str,
+}
+
+static DE_CH: Strings = Strings {
+    hello: "hoi!",
+    is_set: "isch gsetzt",
+    invalid_webhook_url: "das isch kei gültigi URL, si mues mit https:// afange und dörf nöd is interne netz zeige",
+    unknown_placeholder: |name, available| format!("de platzhalter {{{name}}} gits nöd, mögli sind: {available}"),
+    message_templates_title: "Eigeni Antworte",
+    message_template_default: "(standard)",
+    api_token_created: |token| format!("das isch de neu token für d'API, er wird nur jetzt azeigt: `{token}`"),
+    api_token_revoked: "de token isch glöscht",
+    calendar_token_created: |url| format!("das isch de neu link für de kalender vo de verhandlige, er wird nur jetzt azeigt: <{url}>"),
+    calendar_token_revoked: "de kalender-link isch glöscht",
+    calendar_name: "Verhandlige",
+    calendar_hearing: |case_number, reason| format!("Verhandlig {case_number}: {reason}"),
+    removed: "isch weg",
+    no_permission: "du häsch kei recht für da!",
+    guild_only: "du chasch de command nur uf emene serve nutze!",
+    invalid_duration: "das isch kei gültigi dauer, probiers mal mit öppis wie 7d oder 12h",
+    slowmode_too_long: |max| format!("de slowmode cha höchstens {max} si"),
+    none: "-",
+    yes: "ja",
+    no: "nei",
+    internal_error: |id| format!("Es isch en interne Fehler passiert. Fehler-ID: `{id}`"),
+    on_cooldown: |secs| format!("nöd so schnell, probiers in {secs}s nomal"),
+    court_offline: "s gricht isch grad offline, probiers spöter nomal",
+    under_maintenance: "de bot wird grad gwartet, im moment gönd nur befehl wo nüt ändered",
+    maintenance_enabled: "wartigsmodus isch a, nur no befehl wo nüt ändered gönd",
+    maintenance_disabled: "wartigsmodus isch us",
+    guild_not_approved: "de bot isch uf däm server nöd freigschalte und gaht wieder. de bsitzer vom bot cha de server freischalte",
+    invalid_guild_id: "das isch kei gültigi server-id",
+    guild_allowed: "de server isch freigschalte",
+    guild_blocked: "de server isch gsperrt",
+    guild_forgotten: "de server isch us de liste entfernt",
+    error_report_title: "Fehler bi emne befehl",
+    presence_open_lawsuits: |count| format!("{count} offeni prozess"),
+    presence_prisoners: |count| format!("{count} gfangeni"),
+
+    page: |current, total| format!("Siite {current}/{total}"),
+    page_option: |page| format!("Siite {page}"),
+    previous_page: "Zrugg",
+    next_page: "Witer",
+
+    not_a_category: "Das ist keine Kategorie!",
+    no_active_lawsuit: "i dem channel lauft kein aktive prozess!",
+    lawsuit_not_found: "dä prozess gits nöd oder er isch scho abgschlosse",
+    lawsuit_dismissed: |max_age| format!("igstellt, will de prozess länger als {max_age} offe gsi isch"),
+    inactivity_nudge_judge: |judge, idle| format!("{judge}, i dem grichtssaal isch sit {idle} nüt meh passiert"),
+    inactivity_nudge_admins: |admins, judge, idle| format!("{admins}, i dem grichtssaal isch sit {idle} nüt meh passiert und de richter {judge} reagiert nöd"),
+    record_title: |case_number| format!("Urteil im Fall {case_number}"),
+    record_opened_title: |case_number| format!("Neue Prozess im Fall {case_number}"),
+    case_title: |case_number| format!("Fall {case_number}"),
+    transcript_title: |case_number| format!("Protokoll vom Fall {case_number}"),
+    transcript_exported: |count| format!("da isch s protokoll mit {count} nachrichte"),
+    transcript_unavailable: "für de prozess isch nöd gspeicheret wänn er eröffnet worde isch, drum gits kei protokoll",
+    cases_import_invalid_file: |err| format!("d datei isch ungültig: {err}"),
+    cases_import_missing_column: |column| format!("i de datei fehlt d spalte `{column}`"),
+    cases_import_invalid_case: |number, value| format!("fall nr. {number} isch ungültig: `{value}`"),
+    cases_import_empty: "i de datei sind kei fäll",
+    cases_import_too_many: |max| format!("es chönd maximal {max} fäll ufs mal importiert werde"),
+    cases_imported: |count| format!("ha {count} fäll i d akte übernoh"),
+    precedent_not_found: |case_number| format!("es git kein abgschlossne fall {case_number}"),
+    lawsuit_closed: "ich han en dir abschlosse",
+    no_lawsuits: "es git no kei prozess",
+    lawsuits_title: "Gerichtsprozess",
+    no_open_lawsuits: "es lauft grad kein prozess",
+    docket_title: "Terminlischte",
+    docket_without_room: "Ohni Gerichtsruum",
+    docket_entry: |case_number, reason, judge, opened| format!("`{case_number}` {reason}, richter {judge}, eröffnet {opened}"),
+    docket_hearings: "Aagsetzti verhandlige",
+    docket_hearing: |case_number, room, hearing| format!("`{case_number}` i {room}, {hearing}"),
+    unknown: "unbekannt",
+    lawsuit_entry: |reason, plaintiff, accused, status| {
+        format!("**{reason}**: {plaintiff} gegen {accused} - {status}")
+    },
+    lawsuit_verdict: |verdict| format!("Urteil: {verdict}"),
+    lawsuit_verdict_sealed: "Urteil: versiglet",
+    lawsuit_running_in: |channel| format!("lauft in {channel}"),
+    lawsuits_cleared: |period| format!("alles weg, mit `/lawsuit restore` chasch es innerhalb vo {period} zrugghole"),
+    lawsuits_restored: "alles wieder da",
+    nothing_to_restore: "es git nüt zum zrugghole",
+    confirm_button: "bestätige",
+    cancel_button: "abbräche",
+    cancelled: "abbroche, es isch nüt gänderet worde",
+    clear_confirm: |what| format!("wotsch würklich {what} entferne? das chasch nöd rückgängig mache"),
+    clear_all_confirm: |what, period| format!("wotsch würklich {what} entferne? d'prozess und iistellige chasch innerhalb vo {period} mit `/lawsuit restore` zrugghole"),
+    clear_dry_run: |what| format!("es würdet {what} entfernt werde, es isch no nüt gänderet"),
+    clear_lawsuits: |count| format!("{count} prozess"),
+    clear_rooms: |count| format!("{count} gerichtsrüüm"),
+    clear_prisoners: |count| format!("{count} gfangeni"),
+    clear_settings: "alli iistellige",
+    lawsuits_deleted: |count| format!("{count} prozess sind glöscht"),
+    court_rooms_removed: |count| format!("{count} gerichtsrüüm sind entfernt, d'kanäl gits aber no"),
+    court_rooms_in_use: "i de gerichtsrüüm laufed no prozess, lösch zersch d'prozess mit `/lawsuit clear lawsuits`",
+    lawsuit_title: "Prozess",
+    lawsuit_closed_title: "Prozess abgeschlossen",
+    case_summary: "**Fall {case_number}**\n**Kläger:** {plaintiff}\n**Angeklagter:** {accused}\n**Richter:** {judge}\n\n**Grund**\n{reason}\n\n**Beweismittel**\n{evidence}\n\n**Züüge**\n{witnesses}\n\n**Urteil**\n{verdict}",
+    case_summary_no_verdict: "no keis urteil",
+    case_summary_too_long: "d'zämmefassig isch z'lang für e nachricht, da isch sie als datei",
+    set_category_first:
+        "Zuerst eine Kategorie für die Gerichtsräume festlegen mit `/config set court_category`",
+    lawsuit_opened: |channel| format!("ha eine ufgmacht im channel {channel}"),
+    room_select_prompt: "es sind mehreri gerichtsrüüm frei, welle söll brucht werde?",
+    room_last_used: |ago| format!("zletscht brucht vor {ago}"),
+    room_never_used: "no nie brucht",
+    evidence_title: |number| format!("bewiis nr. {number}"),
+    evidence_submit: "Bewiis iireiche",
+    evidence_modal_title: "Was isch de bewiis?",
+    evidence_modal_description: "Beschriib de bewiis",
+    evidence_modal_link: "En link zum bewiis, falls es eine git",
+    invalid_evidence_link: "de link muess mit https:// afange",
+    no_jurors: "es sind kei gschworene aagä worde",
+    jury_already_voted: "d gschworene händ in dem prozess scho abgstimmt",
+    jury_ballots_sent: |count, deadline| format!("ha {count} gschworene en stimmzettel gschickt, s resultat chunnt {deadline}"),
+    jury_ballot_title: "Du bisch gschworene",
+    jury_ballot_description: |deadline| format!("stimm bis {deadline} ab, niemert erfahrt was du gwählt häsch, nur s gsamtresultat wird im gerichtssaal zeigt"),
+    jury_guilty: "Schuldig",
+    jury_not_guilty: "Nöd schuldig",
+    jury_abstained: "Enthalte",
+    jury_vote_counted: |choice| format!("dini stimm isch zählt: {choice}"),
+    jury_result_title: "Urteil vo de gschworene",
+    jury_joined: "du bisch jetzt im gschworene-pool und chasch für prozess uusgloost werde",
+    jury_already_joined: "du bisch scho im gschworene-pool",
+    jury_left: "du bisch nüm im gschworene-pool",
+    jury_not_joined: "du bisch gar nöd im gschworene-pool",
+    jury_pool_too_small: |available, count| format!("im gschworene-pool sind nur {available} lüt verfüegbar, es bruucht {count}"),
+    jury_summons_title: "Vorladig als Gschworene",
+    jury_summons_description: |room| format!("du bisch als gschworene uusgloost worde, verfolg de prozess im {room}, din stimmzettel chunnt separat"),
+    subpoena_title: "Vorladig als Züüge",
+    subpoena_description: |room, deadline| format!("du bisch als züüge im prozess im {room} vorglade, säg bis {deadline} zue oder ab"),
+    subpoena_accept: "Zuesäge",
+    subpoena_decline: "Absäge",
+    subpoena_accepted: "merci, de richter weiss jetzt dass du chunnsch",
+    subpoena_declined: "du häsch abgseit, de richter wird informiert",
+    subpoena_sent: |witness, deadline| format!("{witness} isch als züüge vorglade worde und mues bis {deadline} antworte"),
+    subpoena_undelivered: |witness| format!("{witness} isch als züüge hinzuegfüegt worde, nimmt aber kei direktnachrichte aa"),
+    subpoena_declined_flag: |judge, witness| format!("{judge}, {witness} het d vorladig abgseit, du chasch entscheide obs e missachtig vom gricht isch"),
+    subpoena_ignored_flag: |judge, witness| format!("{judge}, {witness} het nöd uf d vorladig antwortet, du chasch entscheide obs e missachtig vom gricht isch"),
+    witness_already_added: "die person isch scho als züüge vorglade",
+    hearing_set: |time| format!("d'verhandlig findet am {time} statt"),
+    hearing_removed: "de termin vo de verhandlig isch entfernt",
+    room_closed_to_public: "de prozess isch jetzt nöd öffentlich, nur no d beteiligte gsehnd de grichtssaal",
+    held_in_contempt: |user, until| format!("{user} isch wäge missachtig vom gricht stummgschaltet, das endet {until}"),
+    held_in_contempt_in_room: |user, until| format!("{user} het nöd chöne stummgschaltet werde und darf wäge missachtig vom gricht nüme i dem grichtssaal schribe, das endet {until}"),
+    contempt_too_long: |max| format!("länger als {max} gaht nöd"),
+    ejected: |user| format!("{user} isch bis zum urteil us em grichtssaal gworfe worde"),
+    already_ejected: "die person isch scho usegworfe worde",
+    cannot_eject_participant: "d parteie, ihri anwält und de richter chönd nöd usegworfe werde",
+    gag_ordered: |user| format!("{user} darf bis zum urteil usserhalb vom grichtssaal nüt über de prozess schribe"),
+    gag_already_ordered: "die person het scho e schwiigepflicht",
+    gag_order_violated: |user, case| format!("{user}, du darfsch bis zum urteil nüt über de prozess {case} schribe, dini nachricht isch glöscht worde"),
+    room_opened_to_public: "de prozess isch wieder öffentlich",
+    room_already_public: "de prozess isch scho öffentlich",
+    room_already_closed_to_public: "de prozess isch scho nöd öffentlich",
+    invalid_hearing_time: "das isch kei gültige termin, probiers mal mit öppis wie 2d oder 2023-04-01 18:00 (UTC)",
+    witness_is_party: "e partei vom prozess cha kei züüge sii",
+    election_title: "Wahl",
+    election_candidacy_description: |deadline| format!("wer sich zur wahl stelle will, drückt bis {deadline} uf kandidiere"),
+    election_candidacy_button: "Kandidiere",
+    election_candidate_registered: "du bisch jetzt kandidat",
+    election_already_candidate: "du bisch scho kandidat",
+    election_full: |max| format!("es chönd nöd meh als {max} lüt kandidiere"),
+    election_voting_description: |deadline| format!("wähl bis {deadline}, niemert erfahrt was du gwählt häsch"),
+    election_vote_placeholder: "Kandidat uswähle",
+    election_no_candidates: "niemert hät kandidiert, d wahl fallt us",
+    election_cancelled: "d wahl isch abbroche worde, will de bot neu gstartet worde isch",
+    election_result_title: "Wahlresultat",
+    election_no_winners: "niemert",
+    election_started: "d wahl isch gstartet",
+    elections_title: "Wahle",
+    no_elections: "es hät no kei wahle geh",
+    no_records_found: "es git kei abgschlossni prozess, wo dezue passed",
+    records_search_title: |query| format!("Akte zu \"{query}\""),
+    invalid_amount: "dä betrag muess grösser als null sii",
+    fine_already_imposed: "i dem prozess isch scho e buess verhängt worde",
+    fine_imposed: |accused, amount| format!("{accused} muess e buess vo {amount} zahle"),
+    no_open_fines: "du hesch kei offeni bussene",
+    fines_paid: |count, total| format!("du hesch {count} bussene über {total} zahlt"),
+    fines_in_debt: |debt| format!("du hesch no {debt} schulde, zersch muesch die abzahle"),
+    fine_status_paid: "zahlt",
+    fine_status_open: "offe",
+    balance_of: |user, balance| format!("{user} het {balance}"),
+    currency: |amount| format!("{amount} Fr."),
+    fee_hourly: |amount| format!("{amount} pro stund"),
+    fee_flat: |amount| format!("{amount} pauschal"),
+    fee_set: |fee| format!("dis honorar isch jetzt {fee}"),
+    fee_removed: "du verlangsch kei honorar meh",
+    lawyer_fees_title: "Honorar vo de Anwält",
+    no_lawyer_fees: "no kein awalt het es honorar gsetzt",
+    not_a_client: "du hesch i dem prozess kein awalt",
+    not_enough_money: |balance| format!("so viel gäld hesch nöd, du hesch nur {balance}"),
+    retainer_paid: |amount, lawyer| format!("du hesch {lawyer} {amount} als vorschuss zahlt"),
+    not_a_lawyer: "du bisch i dem prozess kein awalt",
+    no_fee_set: "setz zersch dis honorar mit /lawyer fee",
+    billed: |client, amount| format!("{client} het dir {amount} zahlt"),
+    bar_no_questions: "es git no kei prüefigsfrage, d gerichtsverwaltig muess zersch welli mit /lawyer questions add erfasse",
+    bar_already_admitted: "du bisch scho zur anwaltschaft zuegla",
+    bar_question: |number, total, question| format!("**frag {number} vo {total}:** {question}"),
+    bar_exam_passed: |correct, total| format!("bestande! {correct} vo {total} antworte sind richtig gsi"),
+    bar_exam_failed: |correct, total| format!("nöd bestande, nur {correct} vo {total} antworte sind richtig gsi"),
+    bar_admitted: |lawyer| format!("{lawyer} het d anwaltsprüefig bestande und isch jetzt zuegla"),
+    lawyer_not_admitted: |lawyer| format!("{lawyer} isch nöd als anwalt zuegla"),
+    conflict_judge_is_party: |judge| format!("{judge} chan nöd richter si, wenn er selber partei isch"),
+    conflict_lawyer_for_both_sides: |lawyer| format!("{lawyer} chan nöd beidi siite vertrete"),
+    conflict_lawyer_is_opponent: |lawyer| format!("{lawyer} chan nöd de gegner vo sim eigete klient vertrete"),
+    invalid_parties_same: "de kläger und de aagklagti chönd nöd die gliich person si",
+    invalid_parties_bot: |bot| format!("{bot} isch en bot und chan nöd amene prozess teilneh"),
+    invalid_parties_own_lawyer: |party| format!("{party} cha nöd sin eigete aawalt si"),
+    lawsuit_duplicate: |channel| format!("zwüsche dene lüt lauft scho en prozess, er isch in {channel}"),
+    lawsuit_limit_reached: |plaintiff, max| format!("{plaintiff} isch scho bi {max} laufende prozess de kläger, meh gaht nöd"),
+    filing_cooldown: |until| format!("du hesch grad erst gklagt, du chasch {until} wieder klage"),
+    litigant_banned_refusal: |user| format!("s'gricht het {user} als querulant iigstuft, vo {user} werded kei klage meh aagnoh"),
+    litigant_banned: |user| format!("{user} chan kei klage meh iireiche"),
+    litigant_unbanned: |user| format!("{user} chan wieder klage iireiche"),
+    litigant_already_banned: "die person isch scho gsperrt",
+    litigant_not_banned: "die person isch nöd gsperrt",
+    permissions_synced: |rooms| format!("d berechtigunge vo {rooms} grichtssääl und vom gfängnis sind wiederhergstellt"),
+    permissions_sync_failed: |rooms| format!("d berechtigunge vo {rooms} händ nöd chöne wiederhergstellt werde, git s die kanäl no?"),
+    conflict_warning: |conflicts| format!("achtung, interessekonflikt:\n{conflicts}"),
+    invalid_bar_answers: |max| format!("gib zwüsche 2 und {max} antworte mit ; trennt a, und d nummere vo de richtige"),
+    bar_question_added: |number| format!("frag {number} isch erfasst"),
+    bar_question_removed: "d frag isch glöscht",
+    bar_question_not_found: "die frag gits nöd",
+    bar_questions_title: "Prüefigsfrage",
+    court_channel_not_found: "i ha de channel für de prozess nöd gfunde",
+    room_in_wrong_category: |room| format!("de channel {room} isch i de falsche kategorie, man eh"),
+    appeal_reason: |reason| format!("Berufig gege d'verhaftig: {reason}"),
+    no_recent_members: "i dem channel het in letschter ziit niemert gschribe",
+    lawsuit_wizard_prompt: "wähl de aagklagti und de richter us",
+    lawsuit_wizard_accused: "Wer wird aagklagt?",
+    lawsuit_wizard_judge: "Wer isch de richter?",
+    lawsuit_wizard_title: "Klag iireiche",
+    lawsuit_wizard_remedy: "Was forderisch?",
+    reason_with_law: |reason, law| format!("{reason} (gmäss {law})"),
+    reason_with_remedy: |reason, remedy| format!("{reason}\nGforderet: {remedy}"),
+    no_registered_judge: "es git kein registrierte richter wo nöd partei isch, gib en richter a",
+    judge_registered: "du bechunsch jetzt au prozess zueteilt",
+    judge_already_registered: "du bisch scho registriert",
+    judge_unregistered: "du bechunsch kei prozess meh zueteilt",
+    judge_not_registered: "du bisch nöd registriert",
+    registered_judges_title: "Registrierti Richter",
+    no_registered_judges: "es het sich no kein richter registriert",
+    judge_stats_title: |judge| format!("Statistik vo {judge}"),
+    judge_stats_closed: "Abgeschlossene Prozesse",
+    judge_stats_open: "Offene Prozesse",
+    judge_stats_average_duration: "Durchschnittliche Dauer",
+    judge_stats_overturn_rate: "Aufgehobene Urteile",
+
+    set_prison_role_first: "du mosch zerst e rolle setze mit /config set prison_role",
+    set_probation_role_first:
+        "du mosch zerst e rolle setze mit /config set prison_role und /config set probation_role",
+    tier_not_found: "die haftstufe gits nöd",
+    arrested: "isch igsperrt",
+    arrested_until: |time| format!("isch igsperrt bis {time}"),
+    nobody_to_arrest: "i ha niemer gfunde zum iisperre",
+    arrested_many: |count| format!("{count} lüt sind igsperrt"),
+    released: "d'freiheit wartet",
+    released_many: |count| format!("{count} lüt sind frei"),
+    released_many_dry_run: |count, users| format!("es würdet {count} lüt freiglah werde, es isch no nüt gänderet: {users}"),
+    batch_failed: |users| format!("bi {users} händ sich d rolle nöd la ändere, für die isch alles blibe wies gsi isch"),
+    not_in_prison: "de isch gar nöd igsperrt",
+    indefinite_sentence: "de isch unbefristet igsperrt",
+    sentence_over: "d'strof isch abgsässe, d'freiheit wartet",
+    release_moved: |time| format!("chunnt jetzt {time} frei"),
+    already_on_parole: "de isch scho uf bewährig",
+    on_parole_until: |time| format!("isch uf bewährig bis {time}"),
+    not_on_parole: "de isch gar nöd uf bewährig",
+    back_to_prison: "zrugg is gfängnis",
+    appeal_not_in_prison: "du bisch gar nöd igsperrt",
+    appeal_unknown_arrester: "i weiss nöd wer di igsperrt het, drum chasch kei berufig ilege",
+    appeal_no_judge:
+        "es git no kei richter für berufige, de mues zerst mit /config set appeal_judge gsetzt werde",
+    appeal_already_running: "du häsch scho e laufendi berufig",
+    appeal_lawsuit_not_found: "dä prozess gits nöd oder er isch no nöd abgschlosse",
+    appeal_not_a_party: "du chasch nur gege urteil vo dine eigene prozess berufig ilege",
+    appeal_same_judge: "de richter für berufige hät dä prozess sälber gfüehrt, drum chasch kei berufig ilege",
+    verdict_appeal_reason: |original, reason| format!("Berufig gege s urteil vo \"{original}\": {reason}"),
+    not_an_appeal: "dä prozess isch kei berufig",
+    prison_empty: "s'gfängnis isch leer",
+    prisoners_title: "Gfangeni",
+    list_on_parole_until: |time| format!("uf bewährig bis {time}"),
+    list_released_at: |time| format!("chunnt {time} frei"),
+    escaped: |user| {
+        format!("{user} isch usbroche, öpper het d'gfängnisrolle wäggno! ich has wieder igsperrt")
+    },
+    prison_role_recreated: |role| format!("d'gfängnisrolle isch glöscht worde, ich ha {role} neu erstellt und allne gfangene wieder geh"),
+    arrested_dm_title: "Du bisch igsperrt worde",
+    released_dm_title: "Du bisch freiglah worde",
+    dm_ordered_by: "Agordnet vo",
+    dm_released_by: "Freiglah vo",
+    stats_title: "Gfängnisstatistik",
+    prison_check_title: |user| format!("{user} im gfängnis"),
+    stats_current: "Aktuelli Gfangeni",
+    stats_total: "Verhaftige insgesamt",
+    stats_average_sentence: "Durchschnittlichi Haftziit",
+    stats_top_reasons: "Häufigsti Gründ",
+    court_stats_title: "Grichtsstatistik",
+    court_stats_per_month: "Prozess pro monet",
+    court_stats_verdicts: "Urteil",
+    court_stats_verdict_counts: |guilty, not_guilty, other| format!("{guilty} schuldig, {not_guilty} nöd schuldig, {other} anders"),
+    court_stats_time_to_verdict: "Durchschnittlichi ziit bis zum urteil",
+    court_stats_busiest_judges: "Flissigsti richter",
+    court_stats_most_sued: "Am meiste verklagt",
+    leaderboard_title: "Bestelischte vom gricht",
+    leaderboard_filed: "Am meiste klagt",
+    leaderboard_won: "Am meiste gwunne",
+    leaderboard_convicted: "Am meiste verurteilt",
+    lawyer_record_title: |lawyer| format!("Bilanz vo {lawyer}"),
+    lawyer_record_won: "Gwunne",
+    lawyer_record_lost: "Verlore",
+    lawyer_record_success_rate: "Erfolgsquote",
+    lawyer_record_cases: "Letschti fäll",
+    lawyer_record_case: |reason, client, outcome| format!("**{reason}**: für {client} - {outcome}"),
+    lawyer_record_ongoing: "lauft no",
+    lawyer_record_undecided: "unklar",
+    bot_stats_title: "Statistik vom Bot",
+    bot_stats_guilds: "Server",
+    bot_stats_lawsuits: "Gerichtsprozess insgesamt",
+    bot_stats_open_lawsuits: "Offeni Prozess",
+    bot_stats_prisoners: "Gfangeni",
+    bot_stats_uptime: "Laufziit",
+    weekly_report_title: "Wochebricht vom Gricht",
+    weekly_report_opened: "Eröffneti Prozess",
+    weekly_report_closed: "Abgschlosseni Prozess",
+    weekly_report_arrests: "Neui Gfangeni",
+    weekly_report_releases: "Freilassige",
+    weekly_report_verdicts: "Urteil",
+    dashboard_title: "Gerichtsverwaltig",
+    dashboard_no_guilds: "Du verwaltisch no kein Server wo Karin druf isch",
+    dashboard_save: "Speichere",
+    dashboard_logout: "Abmelde",
+
+    field_reason: "Grund",
+    field_plaintiff: "Kläger",
+    field_plaintiff_lawyer: "Anwalt des Klägers",
+    field_accused: "Angeklagter",
+    field_accused_lawyer: "Anwalt des Angeklagten",
+    field_judge: "Richter",
+    field_case_number: "Fallnummer",
+    field_opened_at: "Eröffnet",
+    field_closed_at: "Abgschlosse",
+    field_cites: "Zitiert",
+    field_cited_by: "Zitiert vo",
+    field_verdict: "Urteil",
+    field_reasoning: "Begründig",
+    field_sentence: "Strafe",
+    field_author: "Verfasser",
+    field_added_by: "Eingebracht von",
+    field_attachments: "Anhänge",
+    field_error_id: "Fehler-ID",
+    field_command: "Befehl",
+    field_user: "Person",
+    field_guild: "Server",
+    field_court_room: "Gerichtsraum",
+    field_channel: "Kanal",
+    field_prisoner: "Gefangener",
+    field_arrested_at: "Igsperrt sit",
+    field_tier: "Haftstufe",
+    field_duration: "Dauer",
+    field_ordered_by: "Angeordnet von",
+    field_released_by: "Freigelassen von",
+    field_changed_by: "Geändert von",
+    field_revoked_by: "Widerrufen von",
+    field_count: "Anzahl",
+    field_release: "Frei",
+    field_until: "Bis",
+    field_setting: "Einstellung",
+    field_value: "Wert",
+    field_mode: "Modus",
+    field_imported_by: "Importiert von",
+    field_erased_by: "Gelöscht von",
+    field_fine: "Buess",
+    field_amount: "Betrag",
+    field_balance: "Kontostand",
+    field_lawyer_fees: "Anwaltschöste",
+    field_lawyer_fee: "Honorar",
+    field_lawyer: "Anwalt",
+    field_client: "Klient",
+    field_registered_judge: "Registrierter Richter",
+    field_admitted_lawyer: "Zuegla als Anwalt",
+    field_jury_pool: "Im Gschworene-Pool",
+    field_role: "Rolle",
+    field_seats: "Sitz",
+    field_voters: "Wähler",
+    field_winners: "Gwählt",
+    field_candidates: "Kandidate",
+    field_elections: "Wahle",
+    no_lawyer: "Keinen",
+    no_reason: "kein grund agäh",
+    indefinite: "unbefristet",
+    until: |time| format!("bis {time}"),
+    sentence_served: "d'strof isch abgsässe",
+
+    audit_lawsuit_created: "Prozess eröffnet",
+    audit_lawsuit_closed: "Prozess abgeschlossen",
+    audit_court_room_created: "Gerichtsraum erstellt",
+    audit_arrested: "Verhaftung",
+    audit_arrested_many: "Massenverhaftung",
+    audit_released: "Freilassung",
+    audit_released_all: "Alle freigelassen",
+    audit_sentence_changed: "Haftstrafe geändert",
+    audit_paroled: "Bewährung",
+    audit_parole_revoked: "Bewährung widerrufen",
+    audit_escaped: "Ausbruch",
+    audit_config_changed: "Einstellung geändert",
+    audit_sentence_served: "Strafe abgesessen",
+    audit_guild_imported: "Import",
+    audit_lawsuits_imported: "Alti fäll importiert",
+    audit_user_erased: "Benutzerdaten gelöscht",
+    audit_fine_imposed: "Buess verhängt",
+    audit_fines_paid: "Bussene zahlt",
+    audit_balance_changed: "Kontostand gänderet",
+    audit_lawyer_paid: "Anwalt zahlt",
+    audit_contempt: "Missachtig vom gricht",
+    audit_ejected: "Usem grichtssaal gworfe",
+    audit_gag_ordered: "Schwiigepflicht",
+    audit_lawyer_admitted: "Anwalt zuegla",
+    audit_litigant_banned: "Querulant gsperrt",
+    audit_litigant_unbanned: "Querulant entsperrt",
+    audit_election_finished: "Wahl abgschlosse",
+
+    erase_title: "Date glöscht",
+    erase_lawsuits: "Anonymisierti Prozess",
+    erase_prison_entries: "Gfängniseiträg",
+    erase_arrests: "Verhaftige vo dere person",
+
+    config_title: "Iistellige",
+    export_done: "da sind alli date vom server",
+    import_invalid_file: |err| format!("das isch kei gültigi export-datei: {err}"),
+    import_wrong_guild: "die datei isch vo emene andere server",
+    import_duplicate_prisoner: |user| format!("{user} isch i de datei meh als einisch igsperrt"),
+    import_duplicate_member: |user| format!("{user} chunt i de datei meh als einisch als mitglied vor"),
+    import_invalid_webhook: "de webhook i de datei isch kei gültigi https URL zu ere öffentliche adresse",
+    import_preview_title: "Vorschau vom Import",
+    import_done_title: "Import abgschlosse",
+    import_dry_run_hint: "es isch no nüt gänderet, mach de befehl mit dry_run: false zum importiere",
+    import_lawsuits: "Prozess",
+    import_court_rooms: "Gerichtsrüüm",
+    import_prison_tiers: "Haftstufe",
+    import_prison_history: "Verhaftige",
+    import_jobs: "Planti Ufgabe",
+    import_members: "Mitglieder",
+    import_changed: "wärded gänderet",
+    import_unchanged: "bliibed glich",
+    import_mode_replace: "Ersetzen",
+    import_mode_merge: "Zusammenführen",
+    setting_court_category: "Gerichtskategorie",
+    setting_appeal_category: "Berufigskategorie",
+    setting_prison_role: "Gefängnisrolle",
+    setting_probation_role: "Bewährungsrolle",
+    setting_prison_channel: "Gefängniskanal",
+    setting_prison_tier: "Haftstufe",
+    setting_tier_removed: |tier| format!("{tier} entfernt"),
+    setting_strip_roles: "Rollen entfernen",
+    setting_moderator_channel: "Moderatorenkanal",
+    setting_notifications: "Benachrichtigungen",
+    setting_appeal_judge: "Berufungsrichter",
+    setting_audit_channel: "Audit-Kanal",
+    setting_error_channel: "Fehler-Kanal",
+    setting_records_channel: "Akte-Kanal",
+    setting_news_role: "Gerichts-News",
+    setting_weekly_report: "Wochebricht",
+    setting_statute_of_limitations: "Verjährig",
+    setting_inactivity_nudge: "Erinnerig bi inaktivität",
+    setting_lawsuit_limit: "Maximali laufendi klage",
+    setting_filing_cooldown: "Wartezyt zwüsche klage",
+    setting_court_slowmode: "Slowmode im grichtssaal",
+    setting_locale: "Sprache",
+    setting_webhook: "Webhook",
+    setting_api_token: "API-Token",
+    setting_calendar_token: "Kalender",
+    setting_message_template: "Eigene Antwort",
+    setting_admin_role: "Gerichtsverwaltung",
+    setting_judge_role: "Richter",
+    setting_bailiff_role: "Gerichtsdiener",
+    setting_strict_lawyers: "Nur zuegloni Anwält",
+    setting_conflict_policy: "Interessekonflikt",
+};
+
+static DE_DE: Strings = Strings {
+    hello: "Hallo!",
+    is_set: "Wurde gesetzt",
+    invalid_webhook_url: "Das ist keine gültige URL, sie muss mit https:// beginnen und darf nicht ins interne Netz zeigen",
+    unknown_placeholder: |name, available| format!("Den Platzhalter {{{name}}} gibt es nicht, möglich sind: {available}"),
+    message_templates_title: "Eigene Antworten",
+    message_template_default: "(Standard)",
+    api_token_created: |token| format!("Das ist der neue Token für die API, er wird nur jetzt angezeigt: `{token}`"),
+    api_token_revoked: "Der Token wurde entfernt",
+    calendar_token_created: |url| format!("Das ist der neue Link für den Kalender der Verhandlungen, er wird nur jetzt angezeigt: <{url}>"),
+    calendar_token_revoked: "Der Kalender-Link wurde entfernt",
+    calendar_name: "Verhandlungen",
+    calendar_hearing: |case_number, reason| format!("Verhandlung {case_number}: {reason}"),
+    removed: "Wurde entfernt",
+    no_permission: "Dafür hast du keine Berechtigung!",
+    guild_only: "Diesen Befehl kannst du nur auf einem Server nutzen!",
+    invalid_duration: "Das ist keine gültige Dauer, versuche es mit etwas wie 7d oder 12h",
+    slowmode_too_long: |max| format!("Der Slowmode kann höchstens {max} sein"),
+    none: "-",
+    yes: "ja",
+    no: "nein",
+    internal_error: |id| format!("Ein interner Fehler ist aufgetreten. Fehler-ID: `{id}`"),
+    on_cooldown: |secs| format!("Nicht so schnell, versuche es in {secs}s nochmal"),
+    court_offline: "Das Gericht ist gerade offline, versuche es später nochmal",
+    under_maintenance: "Der Bot wird gerade gewartet, im Moment funktionieren nur Befehle, die nichts ändern",
+    maintenance_enabled: "Wartungsmodus ist an, nur noch Befehle, die nichts ändern, funktionieren",
+    maintenance_disabled: "Wartungsmodus ist aus",
+    guild_not_approved: "Der Bot ist auf diesem Server nicht freigeschaltet und verlässt ihn wieder. Der Besitzer des Bots kann den Server freischalten",
+    invalid_guild_id: "Das ist keine gültige Server-ID",
+    guild_allowed: "Der Server ist freigeschaltet",
+    guild_blocked: "Der Server ist gesperrt",
+    guild_forgotten: "Der Server ist aus den Listen entfernt",
+    error_report_title: "Fehler bei einem Befehl",
+    presence_open_lawsuits: |count| format!("{count} offene Prozesse"),
+    presence_prisoners: |count| format!("{count} Gefangene"),
+
+    page: |current, total| format!("Seite {current}/{total}"),
+    page_option: |page| format!("Seite {page}"),
+    previous_page: "Zurück",
+    next_page: "Weiter",
+
+    not_a_category: "Das ist keine Kategorie!",
+    no_active_lawsuit: "In diesem Kanal läuft kein aktiver Prozess!",
+    lawsuit_not_found: "Diesen Prozess gibt es nicht oder er ist schon abgeschlossen",
+    lawsuit_dismissed: |max_age| format!("Eingestellt, weil der Prozess länger als {max_age} offen war"),
+    inactivity_nudge_judge: |judge, idle| format!("{judge}, in diesem Gerichtssaal ist seit {idle} nichts mehr passiert"),
+    inactivity_nudge_admins: |admins, judge, idle| format!("{admins}, in diesem Gerichtssaal ist seit {idle} nichts mehr passiert und der Richter {judge} reagiert nicht"),
+    record_title: |case_number| format!("Urteil im Fall {case_number}"),
+    record_opened_title: |case_number| format!("Neuer Prozess im Fall {case_number}"),
+    case_title: |case_number| format!("Fall {case_number}"),
+    transcript_title: |case_number| format!("Protokoll des Falls {case_number}"),
+    transcript_exported: |count| format!("Hier ist das Protokoll mit {count} Nachrichten"),
+    transcript_unavailable: "Für diesen Prozess ist nicht gespeichert, wann er eröffnet wurde, deshalb gibt es kein Protokoll",
+    cases_import_invalid_file: |err| format!("Die Datei ist ungültig: {err}"),
+    cases_import_missing_column: |column| format!("In der Datei fehlt die Spalte `{column}`"),
+    cases_import_invalid_case: |number, value| format!("Fall Nr. {number} ist ungültig: `{value}`"),
+    cases_import_empty: "In der Datei sind keine Fälle",
+    cases_import_too_many: |max| format!("Es können höchstens {max} Fälle auf einmal importiert werden"),
+    cases_imported: |count| format!("{count} Fälle wurden in die Akten übernommen"),
+    precedent_not_found: |case_number| format!("Es gibt keinen abgeschlossenen Fall {case_number}"),
+    lawsuit_closed: "Der Prozess wurde abgeschlossen",
+    no_lawsuits: "Es gibt noch keine Prozesse",
+    lawsuits_title: "Gerichtsprozesse",
+    no_open_lawsuits: "Es läuft gerade kein Prozess",
+    docket_title: "Terminliste",
+    docket_without_room: "Ohne Gerichtsraum",
+    docket_entry: |case_number, reason, judge, opened| format!("`{case_number}` {reason}, Richter {judge}, eröffnet {opened}"),
+    docket_hearings: "Angesetzte Verhandlungen",
+    docket_hearing: |case_number, room, hearing| format!("`{case_number}` in {room}, {hearing}"),
+    unknown: "unbekannt",
+    lawsuit_entry: |reason, plaintiff, accused, status| {
+        format!("**{reason}**: {plaintiff} gegen {accused} - {status}")
+    },
+    lawsuit_verdict: |verdict| format!("Urteil: {verdict}"),
+    lawsuit_verdict_sealed: "Urteil: versiegelt",
+    lawsuit_running_in: |channel| format!("läuft in {channel}"),
+    lawsuits_cleared: |period| format!("Alles wurde gelöscht, mit `/lawsuit restore` kann es innerhalb von {period} wiederhergestellt werden"),
+    lawsuits_restored: "Alles wurde wiederhergestellt",
+    nothing_to_restore: "Es gibt nichts zum Wiederherstellen",
+    confirm_button: "Bestätigen",
+    cancel_button: "Abbrechen",
+    cancelled: "Abgebrochen, es wurde nichts geändert",
+    clear_confirm: |what| format!("Willst du wirklich {what} entfernen? Das kann nicht rückgängig gemacht werden"),
+    clear_all_confirm: |what, period| format!("Willst du wirklich {what} entfernen? Prozesse und Einstellungen können innerhalb von {period} mit `/lawsuit restore` wiederhergestellt werden"),
+    clear_dry_run: |what| format!("Es würden {what} entfernt, es wurde noch nichts geändert"),
+    clear_lawsuits: |count| format!("{count} Prozesse"),
+    clear_rooms: |count| format!("{count} Gerichtsräume"),
+    clear_prisoners: |count| format!("{count} Gefangene"),
+    clear_settings: "alle Einstellungen",
+    lawsuits_deleted: |count| format!("{count} Prozesse wurden gelöscht"),
+    court_rooms_removed: |count| format!("{count} Gerichtsräume wurden entfernt, die Kanäle gibt es aber noch"),
+    court_rooms_in_use: "In den Gerichtsräumen laufen noch Prozesse, lösche zuerst die Prozesse mit `/lawsuit clear lawsuits`",
+    lawsuit_title: "Prozess",
+    lawsuit_closed_title: "Prozess abgeschlossen",
+    case_summary: "**Fall {case_number}**\n**Kläger:** {plaintiff}\n**Angeklagter:** {accused}\n**Richter:** {judge}\n\n**Grund**\n{reason}\n\n**Beweismittel**\n{evidence}\n\n**Zeugen**\n{witnesses}\n\n**Urteil**\n{verdict}",
+    case_summary_no_verdict: "Noch kein Urteil",
+    case_summary_too_long: "Die Zusammenfassung ist zu lang für eine Nachricht, hier ist sie als Datei",
+    set_category_first:
+        "Zuerst eine Kategorie für die Gerichtsräume festlegen mit `/config set court_category`",
+    lawsuit_opened: |channel| format!("Der Prozess wurde im Kanal {channel} eröffnet"),
+    room_select_prompt: "Es sind mehrere Gerichtsräume frei, welcher soll benutzt werden?",
+    room_last_used: |ago| format!("Zuletzt benutzt vor {ago}"),
+    room_never_used: "Noch nie benutzt",
+    evidence_title: |number| format!("Beweis Nr. {number}"),
+    evidence_submit: "Beweis einreichen",
+    evidence_modal_title: "Was ist der Beweis?",
+    evidence_modal_description: "Beschreibe den Beweis",
+    evidence_modal_link: "Ein Link zum Beweis, falls es einen gibt",
+    invalid_evidence_link: "Der Link muss mit https:// beginnen",
+    no_jurors: "Es wurden keine Geschworenen angegeben",
+    jury_already_voted: "Die Geschworenen haben in diesem Prozess schon abgestimmt",
+    jury_ballots_sent: |count, deadline| format!("{count} Geschworenen wurde ein Stimmzettel geschickt, das Ergebnis kommt {deadline}"),
+    jury_ballot_title: "Du bist Geschworener",
+    jury_ballot_description: |deadline| format!("Stimme bis {deadline} ab. Niemand erfährt, was du gewählt hast, nur das Gesamtergebnis wird im Gerichtssaal gezeigt."),
+    jury_guilty: "Schuldig",
+    jury_not_guilty: "Nicht schuldig",
+    jury_abstained: "Enthalten",
+    jury_vote_counted: |choice| format!("Deine Stimme wurde gezählt: {choice}"),
+    jury_result_title: "Urteil der Geschworenen",
+    jury_joined: "Du bist jetzt im Geschworenen-Pool und kannst für Prozesse ausgelost werden",
+    jury_already_joined: "Du bist schon im Geschworenen-Pool",
+    jury_left: "Du bist nicht mehr im Geschworenen-Pool",
+    jury_not_joined: "Du bist nicht im Geschworenen-Pool",
+    jury_pool_too_small: |available, count| format!("Im Geschworenen-Pool sind nur {available} Mitglieder verfügbar, es braucht {count}"),
+    jury_summons_title: "Vorladung als Geschworener",
+    jury_summons_description: |room| format!("Du wurdest als Geschworener ausgelost. Verfolge den Prozess in {room}, dein Stimmzettel kommt separat."),
+    subpoena_title: "Vorladung als Zeuge",
+    subpoena_description: |room, deadline| format!("Du wurdest als Zeuge im Prozess in {room} vorgeladen. Sage bis {deadline} zu oder ab."),
+    subpoena_accept: "Zusagen",
+    subpoena_decline: "Absagen",
+    subpoena_accepted: "Danke, der Richter weiss jetzt, dass du kommst.",
+    subpoena_declined: "Du hast abgesagt, der Richter wird informiert.",
+    subpoena_sent: |witness, deadline| format!("{witness} wurde als Zeuge vorgeladen und muss bis {deadline} antworten"),
+    subpoena_undelivered: |witness| format!("{witness} wurde als Zeuge hinzugefügt, nimmt aber keine Direktnachrichten an"),
+    subpoena_declined_flag: |judge, witness| format!("{judge}, {witness} hat die Vorladung abgesagt. Du kannst entscheiden, ob das eine Missachtung des Gerichts ist."),
+    subpoena_ignored_flag: |judge, witness| format!("{judge}, {witness} hat nicht auf die Vorladung geantwortet. Du kannst entscheiden, ob das eine Missachtung des Gerichts ist."),
+    witness_already_added: "Diese Person ist bereits als Zeuge vorgeladen",
+    hearing_set: |time| format!("Die Verhandlung findet am {time} statt"),
+    hearing_removed: "Der Termin der Verhandlung wurde entfernt",
+    room_closed_to_public: "Der Prozess ist jetzt nicht öffentlich, nur noch die Beteiligten sehen den Gerichtssaal",
+    held_in_contempt: |user, until| format!("{user} ist wegen Missachtung des Gerichts stummgeschaltet, das endet {until}"),
+    held_in_contempt_in_room: |user, until| format!("{user} konnte nicht stummgeschaltet werden und darf wegen Missachtung des Gerichts nicht mehr in diesem Gerichtssaal schreiben, das endet {until}"),
+    contempt_too_long: |max| format!("Länger als {max} ist nicht möglich"),
+    ejected: |user| format!("{user} wurde bis zum Urteil aus dem Gerichtssaal geworfen"),
+    already_ejected: "Die Person wurde bereits hinausgeworfen",
+    cannot_eject_participant: "Die Parteien, ihre Anwälte und der Richter können nicht hinausgeworfen werden",
+    gag_ordered: |user| format!("{user} darf bis zum Urteil ausserhalb des Gerichtssaals nichts über den Prozess schreiben"),
+    gag_already_ordered: "Die Person hat bereits eine Schweigepflicht",
+    gag_order_violated: |user, case| format!("{user}, du darfst bis zum Urteil nichts über den Prozess {case} schreiben, deine Nachricht wurde gelöscht"),
+    room_opened_to_public: "Der Prozess ist wieder öffentlich",
+    room_already_public: "Der Prozess ist bereits öffentlich",
+    room_already_closed_to_public: "Der Prozess ist bereits nicht öffentlich",
+    invalid_hearing_time: "Das ist kein gültiger Termin, versuche es mit etwas wie 2d oder 2023-04-01 18:00 (UTC)",
+    witness_is_party: "Eine Partei des Prozesses kann kein Zeuge sein",
+    election_title: "Wahl",
+    election_candidacy_description: |deadline| format!("Wer sich zur Wahl stellen will, drückt bis {deadline} auf Kandidieren"),
+    election_candidacy_button: "Kandidieren",
+    election_candidate_registered: "Du bist jetzt Kandidat",
+    election_already_candidate: "Du bist schon Kandidat",
+    election_full: |max| format!("Es können nicht mehr als {max} Personen kandidieren"),
+    election_voting_description: |deadline| format!("Wähle bis {deadline}. Niemand erfährt, was du gewählt hast."),
+    election_vote_placeholder: "Kandidat auswählen",
+    election_no_candidates: "Niemand hat kandidiert, die Wahl fällt aus",
+    election_cancelled: "Die Wahl wurde abgebrochen, weil der Bot neu gestartet wurde",
+    election_result_title: "Wahlergebnis",
+    election_no_winners: "Niemand",
+    election_started: "Die Wahl wurde gestartet",
+    elections_title: "Wahlen",
+    no_elections: "Es gab noch keine Wahlen",
+    no_records_found: "Es gibt keine abgeschlossenen Prozesse, die dazu passen",
+    records_search_title: |query| format!("Akten zu \"{query}\""),
+    invalid_amount: "Der Betrag muss grösser als null sein",
+    fine_already_imposed: "In diesem Prozess wurde bereits eine Busse verhängt",
+    fine_imposed: |accused, amount| format!("{accused} muss eine Busse von {amount} bezahlen"),
+    no_open_fines: "Du hast keine offenen Bussen",
+    fines_paid: |count, total| format!("Du hast {count} Bussen über {total} bezahlt"),
+    fines_in_debt: |debt| format!("Du hast noch {debt} Schulden, die musst du zuerst abbezahlen"),
+    fine_status_paid: "bezahlt",
+    fine_status_open: "offen",
+    balance_of: |user, balance| format!("{user} hat {balance}"),
+    currency: |amount| format!("{amount} Fr."),
+    fee_hourly: |amount| format!("{amount} pro Stunde"),
+    fee_flat: |amount| format!("{amount} pauschal"),
+    fee_set: |fee| format!("Dein Honorar ist jetzt {fee}"),
+    fee_removed: "Du verlangst kein Honorar mehr",
+    lawyer_fees_title: "Honorare der Anwälte",
+    no_lawyer_fees: "Noch kein Anwalt hat ein Honorar gesetzt",
+    not_a_client: "Du hast in diesem Prozess keinen Anwalt",
+    not_enough_money: |balance| format!("So viel Geld hast du nicht, du hast nur {balance}"),
+    retainer_paid: |amount, lawyer| format!("Du hast {lawyer} {amount} als Vorschuss bezahlt"),
+    not_a_lawyer: "Du bist in diesem Prozess kein Anwalt",
+    no_fee_set: "Setze zuerst dein Honorar mit /lawyer fee",
+    billed: |client, amount| format!("{client} hat dir {amount} bezahlt"),
+    bar_no_questions: "Es gibt noch keine Prüfungsfragen, die Gerichtsverwaltung muss zuerst welche mit /lawyer questions add erfassen",
+    bar_already_admitted: "Du bist bereits als Anwalt zugelassen",
+    bar_question: |number, total, question| format!("**Frage {number} von {total}:** {question}"),
+    bar_exam_passed: |correct, total| format!("Bestanden! {correct} von {total} Antworten waren richtig"),
+    bar_exam_failed: |correct, total| format!("Nicht bestanden, nur {correct} von {total} Antworten waren richtig"),
+    bar_admitted: |lawyer| format!("{lawyer} hat die Anwaltsprüfung bestanden und ist jetzt zugelassen"),
+    lawyer_not_admitted: |lawyer| format!("{lawyer} ist nicht als Anwalt zugelassen"),
+    conflict_judge_is_party: |judge| format!("{judge} kann nicht Richter sein, wenn er selbst Partei ist"),
+    conflict_lawyer_for_both_sides: |lawyer| format!("{lawyer} kann nicht beide Seiten vertreten"),
+    conflict_lawyer_is_opponent: |lawyer| format!("{lawyer} kann nicht den Gegner seines eigenen Mandanten vertreten"),
+    invalid_parties_same: "Der Kläger und der Angeklagte können nicht dieselbe Person sein",
+    invalid_parties_bot: |bot| format!("{bot} ist ein Bot und kann nicht an einem Prozess teilnehmen"),
+    invalid_parties_own_lawyer: |party| format!("{party} kann nicht der eigene Anwalt sein"),
+    lawsuit_duplicate: |channel| format!("Es läuft bereits ein Prozess zwischen diesen Personen, er findet in {channel} statt"),
+    lawsuit_limit_reached: |plaintiff, max| format!("{plaintiff} ist bereits bei {max} laufenden Prozessen der Kläger, mehr sind nicht erlaubt"),
+    filing_cooldown: |until| format!("Du hast gerade erst geklagt, du kannst {until} wieder klagen"),
+    litigant_banned_refusal: |user| format!("Das Gericht hat {user} als Querulanten eingestuft, von {user} werden keine Klagen mehr angenommen"),
+    litigant_banned: |user| format!("{user} kann keine Klagen mehr einreichen"),
+    litigant_unbanned: |user| format!("{user} kann wieder Klagen einreichen"),
+    litigant_already_banned: "Diese Person ist bereits gesperrt",
+    litigant_not_banned: "Diese Person ist nicht gesperrt",
+    permissions_synced: |rooms| format!("Die Berechtigungen von {rooms} Gerichtssälen und vom Gefängnis sind wiederhergestellt"),
+    permissions_sync_failed: |rooms| format!("Die Berechtigungen von {rooms} konnten nicht wiederhergestellt werden, gibt es die Kanäle noch?"),
+    conflict_warning: |conflicts| format!("Achtung, Interessenkonflikt:\n{conflicts}"),
+    invalid_bar_answers: |max| format!("Gib zwischen 2 und {max} Antworten mit ; getrennt an, und die Nummer der richtigen"),
+    bar_question_added: |number| format!("Frage {number} wurde erfasst"),
+    bar_question_removed: "Die Frage wurde gelöscht",
+    bar_question_not_found: "Diese Frage gibt es nicht",
+    bar_questions_title: "Prüfungsfragen",
+    court_channel_not_found: "Der Kanal für den Prozess wurde nicht gefunden",
+    room_in_wrong_category: |room| format!("Der Kanal {room} ist in der falschen Kategorie"),
+    appeal_reason: |reason| format!("Berufung gegen die Verhaftung: {reason}"),
+    no_recent_members: "In diesem Kanal hat in letzter Zeit niemand geschrieben",
+    lawsuit_wizard_prompt: "Wähle den Angeklagten und den Richter aus",
+    lawsuit_wizard_accused: "Wer wird angeklagt?",
+    lawsuit_wizard_judge: "Wer ist der Richter?",
+    lawsuit_wizard_title: "Klage einreichen",
+    lawsuit_wizard_remedy: "Was forderst du?",
+    reason_with_law: |reason, law| format!("{reason} (gemäss {law})"),
+    reason_with_remedy: |reason, remedy| format!("{reason}\nGefordert: {remedy}"),
+    no_registered_judge: "Es gibt keinen registrierten Richter, der nicht Partei ist, gib einen Richter an",
+    judge_registered: "Dir werden jetzt auch Prozesse zugeteilt",
+    judge_already_registered: "Du bist bereits registriert",
+    judge_unregistered: "Dir werden keine Prozesse mehr zugeteilt",
+    judge_not_registered: "Du bist nicht registriert",
+    registered_judges_title: "Registrierte Richter",
+    no_registered_judges: "Es hat sich noch kein Richter registriert",
+    judge_stats_title: |judge| format!("Statistik von {judge}"),
+    judge_stats_closed: "Abgeschlossene Prozesse",
+    judge_stats_open: "Offene Prozesse",
+    judge_stats_average_duration: "Durchschnittliche Dauer",
+    judge_stats_overturn_rate: "Aufgehobene Urteile",
+
+    set_prison_role_first: "Zuerst eine Rolle festlegen mit /config set prison_role",
+    set_probation_role_first:
+        "Zuerst die Rollen festlegen mit /config set prison_role und /config set probation_role",
+    tier_not_found: "Diese Haftstufe gibt es nicht",
+    arrested: "Wurde eingesperrt",
+    arrested_until: |time| format!("Wurde eingesperrt bis {time}"),
+    nobody_to_arrest: "Es wurde niemand zum Einsperren gefunden",
+    arrested_many: |count| format!("{count} Personen wurden eingesperrt"),
+    released: "Die Freiheit wartet",
+    released_many: |count| format!("{count} Personen wurden freigelassen"),
+    released_many_dry_run: |count, users| format!("Es würden {count} Personen freigelassen, es wurde noch nichts geändert: {users}"),
+    batch_failed: |users| format!("Bei {users} konnten die Rollen nicht geändert werden, für sie bleibt alles wie es war"),
+    not_in_prison: "Diese Person ist gar nicht eingesperrt",
+    indefinite_sentence: "Diese Person ist unbefristet eingesperrt",
+    sentence_over: "Die Strafe ist abgesessen, die Freiheit wartet",
+    release_moved: |time| format!("Kommt jetzt {time} frei"),
+    already_on_parole: "Diese Person ist schon auf Bewährung",
+    on_parole_until: |time| format!("Ist auf Bewährung bis {time}"),
+    not_on_parole: "Diese Person ist gar nicht auf Bewährung",
+    back_to_prison: "Zurück ins Gefängnis",
+    appeal_not_in_prison: "Du bist gar nicht eingesperrt",
+    appeal_unknown_arrester:
+        "Es ist nicht bekannt, wer dich eingesperrt hat, darum kannst du keine Berufung einlegen",
+    appeal_no_judge:
+        "Es gibt noch keinen Richter für Berufungen, er muss zuerst mit /config set appeal_judge gesetzt werden",
+    appeal_already_running: "Du hast schon eine laufende Berufung",
+    appeal_lawsuit_not_found: "Diesen Prozess gibt es nicht oder er ist noch nicht abgeschlossen",
+    appeal_not_a_party: "Du kannst nur gegen Urteile deiner eigenen Prozesse Berufung einlegen",
+    appeal_same_judge: "Der Richter für Berufungen hat diesen Prozess selbst geführt, deshalb kannst du keine Berufung einlegen",
+    verdict_appeal_reason: |original, reason| format!("Berufung gegen das Urteil von \"{original}\": {reason}"),
+    not_an_appeal: "Dieser Prozess ist keine Berufung",
+    prison_empty: "Das Gefängnis ist leer",
+    prisoners_title: "Gefangene",
+    list_on_parole_until: |time| format!("auf Bewährung bis {time}"),
+    list_released_at: |time| format!("kommt {time} frei"),
+    escaped: |user| {
+        format!(
+            "{user} ist ausgebrochen, jemand hat die Gefängnisrolle entfernt! Die Person wurde wieder eingesperrt"
+        )
+    },
+    prison_role_recreated: |role| format!("Die Gefängnisrolle wurde gelöscht, ich habe {role} neu erstellt und allen Gefangenen wieder gegeben"),
+    arrested_dm_title: "Du wurdest eingesperrt",
+    released_dm_title: "Du wurdest freigelassen",
+    dm_ordered_by: "Angeordnet von",
+    dm_released_by: "Freigelassen von",
+    stats_title: "Gefängnisstatistik",
+    prison_check_title: |user| format!("{user} im Gefängnis"),
+    stats_current: "Aktuelle Gefangene",
+    stats_total: "Verhaftungen insgesamt",
+    stats_average_sentence: "Durchschnittliche Haftzeit",
+    stats_top_reasons: "Häufigste Gründe",
+    court_stats_title: "Gerichtsstatistik",
+    court_stats_per_month: "Prozesse pro Monat",
+    court_stats_verdicts: "Urteile",
+    court_stats_verdict_counts: |guilty, not_guilty, other| format!("{guilty} schuldig, {not_guilty} nicht schuldig, {other} anders"),
+    court_stats_time_to_verdict: "Durchschnittliche Zeit bis zum Urteil",
+    court_stats_busiest_judges: "Fleissigste Richter",
+    court_stats_most_sued: "Am meisten verklagt",
+    leaderboard_title: "Bestenliste des Gerichts",
+    leaderboard_filed: "Am meisten geklagt",
+    leaderboard_won: "Am meisten gewonnen",
+    leaderboard_convicted: "Am meisten verurteilt",
+    lawyer_record_title: |lawyer| format!("Bilanz von {lawyer}"),
+    lawyer_record_won: "Gewonnen",
+    lawyer_record_lost: "Verloren",
+    lawyer_record_success_rate: "Erfolgsquote",
+    lawyer_record_cases: "Letzte Fälle",
+    lawyer_record_case: |reason, client, outcome| format!("**{reason}**: für {client} - {outcome}"),
+    lawyer_record_ongoing: "läuft noch",
+    lawyer_record_undecided: "unklar",
+    bot_stats_title: "Bot-Statistik",
+    bot_stats_guilds: "Server",
+    bot_stats_lawsuits: "Gerichtsprozesse insgesamt",
+    bot_stats_open_lawsuits: "Offene Prozesse",
+    bot_stats_prisoners: "Gefangene",
+    bot_stats_uptime: "Laufzeit",
+    weekly_report_title: "Wochenbericht des Gerichts",
+    weekly_report_opened: "Eröffnete Prozesse",
+    weekly_report_closed: "Abgeschlossene Prozesse",
+    weekly_report_arrests: "Neue Gefangene",
+    weekly_report_releases: "Freilassungen",
+    weekly_report_verdicts: "Urteile",
+    dashboard_title: "Gerichtsverwaltung",
+    dashboard_no_guilds: "Du verwaltest noch keinen Server, auf dem Karin ist",
+    dashboard_save: "Speichern",
+    dashboard_logout: "Abmelden",
+
+    field_reason: "Grund",
+    field_plaintiff: "Kläger",
+    field_plaintiff_lawyer: "Anwalt des Klägers",
+    field_accused: "Angeklagter",
+    field_accused_lawyer: "Anwalt des Angeklagten",
+    field_judge: "Richter",
+    field_case_number: "Fallnummer",
+    field_opened_at: "Eröffnet",
+    field_closed_at: "Abgeschlossen",
+    field_cites: "Zitiert",
+    field_cited_by: "Zitiert von",
+    field_verdict: "Urteil",
+    field_reasoning: "Begründung",
+    field_sentence: "Strafe",
+    field_author: "Verfasser",
+    field_added_by: "Eingebracht von",
+    field_attachments: "Anhänge",
+    field_error_id: "Fehler-ID",
+    field_command: "Befehl",
+    field_user: "Person",
+    field_guild: "Server",
+    field_court_room: "Gerichtsraum",
+    field_channel: "Kanal",
+    field_prisoner: "Gefangener",
+    field_arrested_at: "Eingesperrt seit",
+    field_tier: "Haftstufe",
+    field_duration: "Dauer",
+    field_ordered_by: "Angeordnet von",
+    field_released_by: "Freigelassen von",
+    field_changed_by: "Geändert von",
+    field_revoked_by: "Widerrufen von",
+    field_count: "Anzahl",
+    field_release: "Frei",
+    field_until: "Bis",
+    field_setting: "Einstellung",
+    field_value: "Wert",
+    field_mode: "Modus",
+    field_imported_by: "Importiert von",
+    field_erased_by: "Gelöscht von",
+    field_fine: "Busse",
+    field_amount: "Betrag",
+    field_balance: "Kontostand",
+    field_lawyer_fees: "Anwaltskosten",
+    field_lawyer_fee: "Honorar",
+    field_lawyer: "Anwalt",
+    field_client: "Klient",
+    field_registered_judge: "Registrierter Richter",
+    field_admitted_lawyer: "Als Anwalt zugelassen",
+    field_jury_pool: "Im Geschworenen-Pool",
+    field_role: "Rolle",
+    field_seats: "Sitze",
+    field_voters: "Wähler",
+    field_winners: "Gewählt",
+    field_candidates: "Kandidaten",
+    field_elections: "Wahlen",
+    no_lawyer: "Keinen",
+    no_reason: "Kein Grund angegeben",
+    indefinite: "unbefristet",
+    until: |time| format!("bis {time}"),
+    sentence_served: "Strafe abgesessen",
+
+    audit_lawsuit_created: "Prozess eröffnet",
+    audit_lawsuit_closed: "Prozess abgeschlossen",
+    audit_court_room_created: "Gerichtsraum erstellt",
+    audit_arrested: "Verhaftung",
+    audit_arrested_many: "Massenverhaftung",
+    audit_released: "Freilassung",
+    audit_released_all: "Alle freigelassen",
+    audit_sentence_changed: "Haftstrafe geändert",
+    audit_paroled: "Bewährung",
+    audit_parole_revoked: "Bewährung widerrufen",
+    audit_escaped: "Ausbruch",
+    audit_config_changed: "Einstellung geändert",
+    audit_sentence_served: "Strafe abgesessen",
+    audit_guild_imported: "Import",
+    audit_lawsuits_imported: "Alte Fälle importiert",
+    audit_user_erased: "Benutzerdaten gelöscht",
+    audit_fine_imposed: "Busse verhängt",
+    audit_fines_paid: "Bussen bezahlt",
+    audit_balance_changed: "Kontostand geändert",
+    audit_lawyer_paid: "Anwalt bezahlt",
+    audit_contempt: "Missachtung des Gerichts",
+    audit_ejected: "Aus dem Gerichtssaal geworfen",
+    audit_gag_ordered: "Schweigepflicht",
+    audit_lawyer_admitted: "Anwalt zugelassen",
+    audit_litigant_banned: "Querulant gesperrt",
+    audit_litigant_unbanned: "Querulant entsperrt",
+    audit_election_finished: "Wahl abgeschlossen",
+
+    erase_title: "Daten gelöscht",
+    erase_lawsuits: "Anonymisierte Prozesse",
+    erase_prison_entries: "Gefängniseinträge",
+    erase_arrests: "Verhaftungen durch diese Person",
+
+    config_title: "Einstellungen",
+    export_done: "Hier sind alle Daten des Servers",
+    import_invalid_file: |err| format!("Das ist keine gültige Export-Datei: {err}"),
+    import_wrong_guild: "Die Datei ist von einem anderen Server",
+    import_duplicate_prisoner: |user| format!("{user} ist in der Datei mehrfach eingesperrt"),
+    import_duplicate_member: |user| format!("{user} kommt in der Datei mehrfach als Mitglied vor"),
+    import_invalid_webhook: "Der Webhook in der Datei ist keine gültige https-URL zu einer öffentlichen Adresse",
+    import_preview_title: "Import-Vorschau",
+    import_done_title: "Import abgeschlossen",
+    import_dry_run_hint: "Es wurde noch nichts geändert, zum Importieren den Befehl mit dry_run: false ausführen",
+    import_lawsuits: "Prozesse",
+    import_court_rooms: "Gerichtsräume",
+    import_prison_tiers: "Haftstufen",
+    import_prison_history: "Verhaftungen",
+    import_jobs: "Geplante Aufgaben",
+    import_members: "Mitglieder",
+    import_changed: "werden geändert",
+    import_unchanged: "bleiben gleich",
+    import_mode_replace: "Ersetzen",
+    import_mode_merge: "Zusammenführen",
+    setting_court_category: "Gerichtskategorie",
+    setting_appeal_category: "Berufungskategorie",
+    setting_prison_role: "Gefängnisrolle",
+    setting_probation_role: "Bewährungsrolle",
+    setting_prison_channel: "Gefängniskanal",
+    setting_prison_tier: "Haftstufe",
+    setting_tier_removed: |tier| format!("{tier} entfernt"),
+    setting_strip_roles: "Rollen entfernen",
+    setting_moderator_channel: "Moderatorenkanal",
+    setting_notifications: "Benachrichtigungen",
+    setting_appeal_judge: "Berufungsrichter",
+    setting_audit_channel: "Audit-Kanal",
+    setting_error_channel: "Fehler-Kanal",
+    setting_records_channel: "Akten-Kanal",
+    setting_news_role: "Gerichts-News",
+    setting_weekly_report: "Wochenbericht",
+    setting_statute_of_limitations: "Verjährung",
+    setting_inactivity_nudge: "Erinnerung bei Inaktivität",
+    setting_lawsuit_limit: "Maximale laufende Klagen",
+    setting_filing_cooldown: "Wartezeit zwischen Klagen",
+    setting_court_slowmode: "Slowmode im Gerichtssaal",
+    setting_locale: "Sprache",
+    setting_webhook: "Webhook",
+    setting_api_token: "API-Token",
+    setting_calendar_token: "Kalender",
+    setting_message_template: "Eigene Antwort",
+    setting_admin_role: "Gerichtsverwaltung",
+    setting_judge_role: "Richter",
+    setting_bailiff_role: "Gerichtsdiener",
+    setting_strict_lawyers: "Nur zugelassene Anwälte",
+    setting_conflict_policy: "Interessenkonflikte",
+};
+
+static EN: Strings = Strings {
+    hello: "Hello!",
+    is_set: "Done",
+    invalid_webhook_url: "That's not a valid URL, it has to start with https:// and can't point to an internal network",
+    unknown_placeholder: |name, available| {
+        format!("There is no placeholder {{{name}}}, possible are: {available}")
+    },
+    message_templates_title: "Custom responses",
+    message_template_default: "(default)",
+    api_token_created: |token| format!("This is the new API token, it's only shown now: `{token}`"),
+    api_token_revoked: "The token was removed",
+    calendar_token_created: |url| format!("This is the new link to the calendar of the hearings, it's only shown now: <{url}>"),
+    calendar_token_revoked: "The calendar link was removed",
+    calendar_name: "Hearings",
+    calendar_hearing: |case_number, reason| format!("Hearing {case_number}: {reason}"),
+    removed: "Removed",
+    no_permission: "You don't have permission for that!",
+    guild_only: "You can only use this command on a server!",
+    invalid_duration: "That's not a valid duration, try something like 7d or 12h",
+    slowmode_too_long: |max| format!("The slowmode can be at most {max}"),
+    none: "-",
+    yes: "yes",
+    no: "no",
+    internal_error: |id| format!("An internal error occurred. Error id: `{id}`"),
+    on_cooldown: |secs| format!("Not so fast, try again in {secs}s"),
+    court_offline: "The court is offline right now, try again later",
+    under_maintenance: "The bot is under maintenance, only commands that don't change anything work right now",
+    maintenance_enabled: "Maintenance mode is on, only commands that don't change anything work",
+    maintenance_disabled: "Maintenance mode is off",
+    guild_not_approved: "The bot is not approved for this server and leaves again. The owner of the bot can approve the server",
+    invalid_guild_id: "That is not a valid server id",
+    guild_allowed: "The server is approved",
+    guild_blocked: "The server is blocked",
+    guild_forgotten: "The server is removed from the lists",
+    error_report_title: "Error in a command",
+    presence_open_lawsuits: |count| format!("{count} open lawsuits"),
+    presence_prisoners: |count| format!("{count} prisoners"),
+
+    page: |current, total| format!("Page {current}/{total}"),
+    page_option: |page| format!("Page {page}"),
+    previous_page: "Previous",
+    next_page: "Next",
+
+    not_a_category: "That's not a category!",
+    no_active_lawsuit: "There is no active lawsuit in this channel!",
+    lawsuit_not_found: "That lawsuit doesn't exist or is already closed",
+    lawsuit_dismissed: |max_age| format!("Dismissed because the lawsuit was open for longer than {max_age}"),
+    inactivity_nudge_judge: |judge, idle| format!("{judge}, nothing has happened in this court room for {idle}"),
+    inactivity_nudge_admins: |admins, judge, idle| format!("{admins}, nothing has happened in this court room for {idle} and the judge {judge} is not responding"),
+    record_title: |case_number| format!("Verdict in case {case_number}"),
+    record_opened_title: |case_number| format!("New lawsuit in case {case_number}"),
+    case_title: |case_number| format!("Case {case_number}"),
+    transcript_title: |case_number| format!("Transcript of case {case_number}"),
+    transcript_exported: |count| format!("Here is the transcript with {count} messages"),
+    transcript_unavailable: "It is not stored when this lawsuit was opened, so there is no transcript",
+    cases_import_invalid_file: |err| format!("The file is invalid: {err}"),
+    cases_import_missing_column: |column| format!("The column `{column}` is missing in the file"),
+    cases_import_invalid_case: |number, value| format!("Case no. {number} is invalid: `{value}`"),
+    cases_import_empty: "There are no cases in the file",
+    cases_import_too_many: |max| format!("At most {max} cases can be imported at once"),
+    cases_imported: |count| format!("{count} cases were added to the records"),
+    precedent_not_found: |case_number| format!("There is no closed case {case_number}"),
+    lawsuit_closed: "The lawsuit has been closed",
+    no_lawsuits: "There are no lawsuits yet",
+    lawsuits_title: "Lawsuits",
+    no_open_lawsuits: "There are no open lawsuits",
+    docket_title: "Docket",
+    docket_without_room: "Without a court room",
+    docket_entry: |case_number, reason, judge, opened| format!("`{case_number}` {reason}, judge {judge}, opened {opened}"),
+    docket_hearings: "Scheduled hearings",
+    docket_hearing: |case_number, room, hearing| format!("`{case_number}` in {room}, {hearing}"),
+    unknown: "unknown",
+    lawsuit_entry: |reason, plaintiff, accused, status| {
+        format!("**{reason}**: {plaintiff} against {accused} - {status}")
+    },
+    lawsuit_verdict: |verdict| format!("Verdict: {verdict}"),
+    lawsuit_verdict_sealed: "Verdict: sealed",
+    lawsuit_running_in: |channel| format!("running in {channel}"),
+    lawsuits_cleared: |period| {
+        format!("Everything has been deleted, it can be restored with `/lawsuit restore` within {period}")
+    },
+    lawsuits_restored: "Everything has been restored",
+    nothing_to_restore: "There is nothing to restore",
+    confirm_button: "Confirm",
+    cancel_button: "Cancel",
+    cancelled: "Cancelled, nothing was changed",
+    clear_confirm: |what| format!("Do you really want to remove {what}? This can't be undone"),
+    clear_all_confirm: |what, period| format!("Do you really want to remove {what}? Lawsuits and settings can be restored with `/lawsuit restore` within {period}"),
+    clear_dry_run: |what| format!("This would remove {what}, nothing was changed yet"),
+    clear_lawsuits: |count| format!("{count} lawsuits"),
+    clear_rooms: |count| format!("{count} court rooms"),
+    clear_prisoners: |count| format!("{count} prisoners"),
+    clear_settings: "all settings",
+    lawsuits_deleted: |count| format!("Deleted {count} lawsuits"),
+    court_rooms_removed: |count| format!("Removed {count} court rooms, the channels still exist"),
+    court_rooms_in_use: "There are still lawsuits ongoing in the court rooms, delete the lawsuits with `/lawsuit clear lawsuits` first",
+    lawsuit_title: "Lawsuit",
+    lawsuit_closed_title: "Lawsuit closed",
+    case_summary: "**Case {case_number}**\n**Plaintiff:** {plaintiff}\n**Accused:** {accused}\n**Judge:** {judge}\n\n**Reason**\n{reason}\n\n**Evidence**\n{evidence}\n\n**Witnesses**\n{witnesses}\n\n**Verdict**\n{verdict}",
+    case_summary_no_verdict: "No verdict yet",
+    case_summary_too_long: "The summary is too long for a message, here it is as a file",
+    set_category_first:
+        "Set a category for the court rooms first with `/config set court_category`",
+    lawsuit_opened: |channel| format!("Opened the lawsuit in {channel}"),
+    room_select_prompt: "Several court rooms are free, which one should be used?",
+    room_last_used: |ago| format!("Last used {ago} ago"),
+    room_never_used: "Never used",
+    evidence_title: |number| format!("Evidence no. {number}"),
+    evidence_submit: "Submit evidence",
+    evidence_modal_title: "What is the evidence?",
+    evidence_modal_description: "Describe the evidence",
+    evidence_modal_link: "A link to the evidence if there is one",
+    invalid_evidence_link: "The link has to start with https://",
+    no_jurors: "No jurors were given",
+    jury_already_voted: "The jury has already voted in this lawsuit",
+    jury_ballots_sent: |count, deadline| {
+        format!("Sent a ballot to {count} jurors, the result will be revealed {deadline}")
+    },
+    jury_ballot_title: "You are a juror",
+    jury_ballot_description: |deadline| {
+        format!("Vote until {deadline}. Nobody will know what you chose, only the overall result is shown in the court room.")
+    },
+    jury_guilty: "Guilty",
+    jury_not_guilty: "Not guilty",
+    jury_abstained: "Abstained",
+    jury_vote_counted: |choice| format!("Your vote was counted: {choice}"),
+    jury_result_title: "Verdict of the jury",
+    jury_joined: "You are now in the jury pool and can be drawn for trials",
+    jury_already_joined: "You are already in the jury pool",
+    jury_left: "You left the jury pool",
+    jury_not_joined: "You are not in the jury pool",
+    jury_pool_too_small: |available, count| format!("Only {available} members of the jury pool are available, {count} are needed"),
+    jury_summons_title: "Jury summons",
+    jury_summons_description: |room| format!("You were drawn as a juror. Follow the trial in {room}, your ballot will be sent separately."),
+    subpoena_title: "Subpoena",
+    subpoena_description: |room, deadline| format!("You were summoned as a witness in the trial in {room}. Accept or decline until {deadline}."),
+    subpoena_accept: "Accept",
+    subpoena_decline: "Decline",
+    subpoena_accepted: "Thank you, the judge now knows that you will come.",
+    subpoena_declined: "You declined, the judge will be informed.",
+    subpoena_sent: |witness, deadline| format!("{witness} was summoned as a witness and has to answer until {deadline}"),
+    subpoena_undelivered: |witness| format!("{witness} was added as a witness, but does not accept direct messages"),
+    subpoena_declined_flag: |judge, witness| format!("{judge}, {witness} declined the subpoena. You can decide whether this is contempt of court."),
+    subpoena_ignored_flag: |judge, witness| format!("{judge}, {witness} did not answer the subpoena. You can decide whether this is contempt of court."),
+    witness_already_added: "This person was already summoned as a witness",
+    hearing_set: |time| format!("The hearing takes place on {time}"),
+    hearing_removed: "The date of the hearing was removed",
+    room_closed_to_public: "The lawsuit is now closed to the public, only the participants can see the court room",
+    held_in_contempt: |user, until| format!("{user} is muted for contempt of court, it ends {until}"),
+    held_in_contempt_in_room: |user, until| format!("{user} could not be muted and may not write in this court room for contempt of court, it ends {until}"),
+    contempt_too_long: |max| format!("It cannot be longer than {max}"),
+    ejected: |user| format!("{user} was ejected from the court room until the verdict"),
+    already_ejected: "The person was already ejected",
+    cannot_eject_participant: "The parties, their lawyers and the judge cannot be ejected",
+    gag_ordered: |user| format!("{user} may not write about the lawsuit outside the court room until the verdict"),
+    gag_already_ordered: "The person is already under a gag order",
+    gag_order_violated: |user, case| format!("{user}, you may not write about the lawsuit {case} until the verdict, your message was deleted"),
+    room_opened_to_public: "The lawsuit is public again",
+    room_already_public: "The lawsuit is already public",
+    room_already_closed_to_public: "The lawsuit is already closed to the public",
+    invalid_hearing_time: "That's not a valid date, try something like 2d or 2023-04-01 18:00 (UTC)",
+    witness_is_party: "A party of the lawsuit can not be a witness",
+    election_title: "Election",
+    election_candidacy_description: |deadline| format!("Press run as candidate until {deadline} to stand for election"),
+    election_candidacy_button: "Run as candidate",
+    election_candidate_registered: "You are now a candidate",
+    election_already_candidate: "You are already a candidate",
+    election_full: |max| format!("No more than {max} members can run"),
+    election_voting_description: |deadline| format!("Vote until {deadline}. Nobody will find out who you voted for."),
+    election_vote_placeholder: "Choose a candidate",
+    election_no_candidates: "Nobody ran, the election is cancelled",
+    election_cancelled: "The election was cancelled because the bot was restarted",
+    election_result_title: "Election result",
+    election_no_winners: "Nobody",
+    election_started: "The election has started",
+    elections_title: "Elections",
+    no_elections: "There were no elections yet",
+    no_records_found: "There are no closed lawsuits that match",
+    records_search_title: |query| format!("Records for \"{query}\""),
+    invalid_amount: "The amount must be greater than zero",
+    fine_already_imposed: "A fine was already imposed in this lawsuit",
+    fine_imposed: |accused, amount| format!("{accused} has to pay a fine of {amount}"),
+    no_open_fines: "You have no open fines",
+    fines_paid: |count, total| format!("You paid {count} fines over {total}"),
+    fines_in_debt: |debt| format!("You still have {debt} of debt, pay it off first"),
+    fine_status_paid: "paid",
+    fine_status_open: "open",
+    balance_of: |user, balance| format!("{user} has {balance}"),
+    currency: |amount| format!("CHF {amount}"),
+    fee_hourly: |amount| format!("{amount} per hour"),
+    fee_flat: |amount| format!("{amount} flat"),
+    fee_set: |fee| format!("Your fee is now {fee}"),
+    fee_removed: "You no longer charge a fee",
+    lawyer_fees_title: "Fees of the lawyers",
+    no_lawyer_fees: "No lawyer has set a fee yet",
+    not_a_client: "You have no lawyer in this lawsuit",
+    not_enough_money: |balance| format!("You don't have that much money, you only have {balance}"),
+    retainer_paid: |amount, lawyer| format!("You paid {lawyer} a retainer of {amount}"),
+    not_a_lawyer: "You are not a lawyer in this lawsuit",
+    no_fee_set: "Set your fee with /lawyer fee first",
+    billed: |client, amount| format!("{client} paid you {amount}"),
+    bar_no_questions: "There are no exam questions yet, the court administration has to add some with /lawyer questions add first",
+    bar_already_admitted: "You are already admitted to the bar",
+    bar_question: |number, total, question| format!("**Question {number} of {total}:** {question}"),
+    bar_exam_passed: |correct, total| format!("Passed! {correct} of {total} answers were right"),
+    bar_exam_failed: |correct, total| format!("Failed, only {correct} of {total} answers were right"),
+    bar_admitted: |lawyer| format!("{lawyer} passed the bar exam and is now admitted"),
+    lawyer_not_admitted: |lawyer| format!("{lawyer} is not admitted to the bar"),
+    conflict_judge_is_party: |judge| format!("{judge} cannot be the judge while being a party"),
+    conflict_lawyer_for_both_sides: |lawyer| format!("{lawyer} cannot represent both sides"),
+    conflict_lawyer_is_opponent: |lawyer| format!("{lawyer} cannot represent the opponent of their own client"),
+    invalid_parties_same: "The plaintiff and the accused cannot be the same person",
+    invalid_parties_bot: |bot| format!("{bot} is a bot and cannot take part in a lawsuit"),
+    invalid_parties_own_lawyer: |party| format!("{party} cannot be their own lawyer"),
+    lawsuit_duplicate: |channel| format!("A lawsuit between these people is already running in {channel}"),
+    lawsuit_limit_reached: |plaintiff, max| format!("{plaintiff} is already the plaintiff of {max} running lawsuits, more are not allowed"),
+    filing_cooldown: |until| format!("You just filed a lawsuit, you can file again {until}"),
+    litigant_banned_refusal: |user| format!("The court has declared {user} a vexatious litigant, no more lawsuits are accepted from {user}"),
+    litigant_banned: |user| format!("{user} can no longer file lawsuits"),
+    litigant_unbanned: |user| format!("{user} can file lawsuits again"),
+    litigant_already_banned: "This person is already banned",
+    litigant_not_banned: "This person is not banned",
+    permissions_synced: |rooms| format!("The permissions of {rooms} court rooms and the prison were restored"),
+    permissions_sync_failed: |rooms| format!("The permissions of {rooms} could not be restored, do the channels still exist?"),
+    conflict_warning: |conflicts| format!("Warning, conflict of interest:\n{conflicts}"),
+    invalid_bar_answers: |max| format!("Give between 2 and {max} answers separated by ; and the number of the right one"),
+    bar_question_added: |number| format!("Added question {number}"),
+    bar_question_removed: "The question was removed",
+    bar_question_not_found: "There is no such question",
+    bar_questions_title: "Exam questions",
+    court_channel_not_found: "The channel for the lawsuit could not be found",
+    room_in_wrong_category: |room| format!("The channel {room} is in the wrong category"),
+    appeal_reason: |reason| format!("Appeal against the arrest: {reason}"),
+    no_recent_members: "Nobody wrote in this channel recently",
+    lawsuit_wizard_prompt: "Choose the accused and the judge",
+    lawsuit_wizard_accused: "Who is accused?",
+    lawsuit_wizard_judge: "Who is the judge?",
+    lawsuit_wizard_title: "File a lawsuit",
+    lawsuit_wizard_remedy: "What do you demand?",
+    reason_with_law: |reason, law| format!("{reason} (according to {law})"),
+    reason_with_remedy: |reason, remedy| format!("{reason}\nDemanded: {remedy}"),
+    no_registered_judge: "There is no registered judge who is not a party, choose a judge",
+    judge_registered: "You will now be assigned lawsuits too",
+    judge_already_registered: "You are already registered",
+    judge_unregistered: "You will no longer be assigned lawsuits",
+    judge_not_registered: "You are not registered",
+    registered_judges_title: "Registered judges",
+    no_registered_judges: "No judge has registered yet",
+    judge_stats_title: |judge| format!("Statistics of {judge}"),
+    judge_stats_closed: "Closed lawsuits",
+    judge_stats_open: "Open lawsuits",
+    judge_stats_average_duration: "Average duration",
+    judge_stats_overturn_rate: "Overturned verdicts",
+
+    set_prison_role_first: "Set a role first with /config set prison_role",
+    set_probation_role_first:
+        "Set the roles first with /config set prison_role and /config set probation_role",
+    tier_not_found: "That prison tier doesn't exist",
+    arrested: "Arrested",
+    arrested_until: |time| format!("Arrested until {time}"),
+    nobody_to_arrest: "Nobody was found to arrest",
+    arrested_many: |count| format!("Arrested {count} people"),
+    released: "Freedom awaits",
+    released_many: |count| format!("Released {count} people"),
+    released_many_dry_run: |count, users| format!("This would release {count} people, nothing was changed yet: {users}"),
+    batch_failed: |users| format!("The roles of {users} could not be changed, nothing changed for them"),
+    not_in_prison: "They are not in prison",
+    indefinite_sentence: "They are in prison indefinitely",
+    sentence_over: "The sentence has been served, freedom awaits",
+    release_moved: |time| format!("Will now be released {time}"),
+    already_on_parole: "They are already on parole",
+    on_parole_until: |time| format!("On parole until {time}"),
+    not_on_parole: "They are not on parole",
+    back_to_prison: "Back to prison",
+    appeal_not_in_prison: "You are not in prison",
+    appeal_unknown_arrester: "It's not known who arrested you, so you can't appeal",
+    appeal_no_judge:
+        "There is no judge for appeals yet, one has to be set with /config set appeal_judge first",
+    appeal_already_running: "You already have an appeal running",
+    appeal_lawsuit_not_found: "That lawsuit doesn't exist or isn't closed yet",
+    appeal_not_a_party: "You can only appeal the verdicts of your own lawsuits",
+    appeal_same_judge: "The judge for appeals led this lawsuit themselves, so it can't be appealed",
+    verdict_appeal_reason: |original, reason| format!("Appeal against the verdict of \"{original}\": {reason}"),
+    not_an_appeal: "This lawsuit is not an appeal",
+    prison_empty: "The prison is empty",
+    prisoners_title: "Prisoners",
+    list_on_parole_until: |time| format!("on parole until {time}"),
+    list_released_at: |time| format!("released {time}"),
+    escaped: |user| {
+        format!("{user} escaped, someone removed their prison role! They have been arrested again")
+    },
+    prison_role_recreated: |role| format!("The prison role was deleted, I recreated {role} and gave it to all prisoners again"),
+    arrested_dm_title: "You have been arrested",
+    released_dm_title: "You have been released",
+    dm_ordered_by: "Ordered by",
+    dm_released_by: "Released by",
+    stats_title: "Prison statistics",
+    prison_check_title: |user| format!("{user} in prison"),
+    stats_current: "Current prisoners",
+    stats_total: "Total arrests",
+    stats_average_sentence: "Average sentence",
+    stats_top_reasons: "Most frequent reasons",
+    court_stats_title: "Court statistics",
+    court_stats_per_month: "Lawsuits per month",
+    court_stats_verdicts: "Verdicts",
+    court_stats_verdict_counts: |guilty, not_guilty, other| format!("{guilty} guilty, {not_guilty} not guilty, {other} other"),
+    court_stats_time_to_verdict: "Average time to verdict",
+    court_stats_busiest_judges: "Busiest judges",
+    court_stats_most_sued: "Most sued",
+    leaderboard_title: "Court leaderboard",
+    leaderboard_filed: "Most lawsuits filed",
+    leaderboard_won: "Most lawsuits won",
+    leaderboard_convicted: "Most often convicted",
+    lawyer_record_title: |lawyer| format!("Record of {lawyer}"),
+    lawyer_record_won: "Won",
+    lawyer_record_lost: "Lost",
+    lawyer_record_success_rate: "Success rate",
+    lawyer_record_cases: "Latest cases",
+    lawyer_record_case: |reason, client, outcome| format!("**{reason}**: for {client} - {outcome}"),
+    lawyer_record_ongoing: "ongoing",
+    lawyer_record_undecided: "unclear",
+    bot_stats_title: "Bot statistics",
+    bot_stats_guilds: "Servers",
+    bot_stats_lawsuits: "Total lawsuits",
+    bot_stats_open_lawsuits: "Open lawsuits",
+    bot_stats_prisoners: "Prisoners",
+    bot_stats_uptime: "Uptime",
+    weekly_report_title: "Weekly court report",
+    weekly_report_opened: "Opened lawsuits",
+    weekly_report_closed: "Closed lawsuits",
+    weekly_report_arrests: "New prisoners",
+    weekly_report_releases: "Releases",
+    weekly_report_verdicts: "Verdicts",
+    dashboard_title: "Court dashboard",
+    dashboard_no_guilds: "You don't manage any servers that Karin is on yet",
+    dashboard_save: "Save",
+    dashboard_logout: "Log out",
+
+    field_reason: "Reason",
+    field_plaintiff: "Plaintiff",
+    field_plaintiff_lawyer: "Plaintiff's lawyer",
+    field_accused: "Accused",
+    field_accused_lawyer: "Accused's lawyer",
+    field_judge: "Judge",
+    field_case_number: "Case number",
+    field_opened_at: "Opened",
+    field_closed_at: "Closed",
+    field_cites: "Cites",
+    field_cited_by: "Cited by",
+    field_verdict: "Verdict",
+    field_reasoning: "Reasoning",
+    field_sentence: "Sentence",
+    field_author: "Author",
+    field_added_by: "Submitted by",
+    field_attachments: "Attachments",
+    field_error_id: "Error ID",
+    field_command: "Command",
+    field_user: "User",
+    field_guild: "Server",
+    field_court_room: "Court room",
+    field_channel: "Channel",
+    field_prisoner: "Prisoner",
+    field_arrested_at: "Arrested",
+    field_tier: "Prison tier",
+    field_duration: "Duration",
+    field_ordered_by: "Ordered by",
+    field_released_by: "Released by",
+    field_changed_by: "Changed by",
+    field_revoked_by: "Revoked by",
+    field_count: "Count",
+    field_release: "Release",
+    field_until: "Until",
+    field_setting: "Setting",
+    field_value: "Value",
+    field_mode: "Mode",
+    field_imported_by: "Imported by",
+    field_erased_by: "Erased by",
+    field_fine: "Fine",
+    field_amount: "Amount",
+    field_balance: "Balance",
+    field_lawyer_fees: "Lawyer fees",
+    field_lawyer_fee: "Fee",
+    field_lawyer: "Lawyer",
+    field_client: "Client",
+    field_registered_judge: "Registered judge",
+    field_admitted_lawyer: "Admitted to the bar",
+    field_jury_pool: "In the jury pool",
+    field_role: "Role",
+    field_seats: "Seats",
+    field_voters: "Voters",
+    field_winners: "Elected",
+    field_candidates: "Candidates",
+    field_elections: "Elections",
+    no_lawyer: "None",
+    no_reason: "No reason given",
+    indefinite: "indefinite",
+    until: |time| format!("until {time}"),
+    sentence_served: "Sentence served",
+
+    audit_lawsuit_created: "Lawsuit opened",
+    audit_lawsuit_closed: "Lawsuit closed",
+    audit_court_room_created: "Court room created",
+    audit_arrested: "Arrest",
+    audit_arrested_many: "Mass arrest",
+    audit_released: "Release",
+    audit_released_all: "Everyone released",
+    audit_sentence_changed: "Sentence changed",
+    audit_paroled: "Parole",
+    audit_parole_revoked: "Parole revoked",
+    audit_escaped: "Escape",
+    audit_config_changed: "Setting changed",
+    audit_sentence_served: "Sentence served",
+    audit_guild_imported: "Import",
+    audit_lawsuits_imported: "Old cases imported",
+    audit_user_erased: "User data erased",
+    audit_fine_imposed: "Fine imposed",
+    audit_fines_paid: "Fines paid",
+    audit_balance_changed: "Balance changed",
+    audit_lawyer_paid: "Lawyer paid",
+    audit_contempt: "Contempt of court",
+    audit_ejected: "Ejected from the court room",
+    audit_gag_ordered: "Gag order",
+    audit_lawyer_admitted: "Lawyer admitted",
+    audit_litigant_banned: "Vexatious litigant banned",
+    audit_litigant_unbanned: "Vexatious litigant unbanned",
+    audit_election_finished: "Election finished",
+
+    erase_title: "Data erased",
+    erase_lawsuits: "Anonymized lawsuits",
+    erase_prison_entries: "Prison entries",
+    erase_arrests: "Arrests by this person",
+
+    config_title: "Settings",
+    export_done: "Here is all the data of the server",
+    import_invalid_file: |err| format!("That's not a valid export file: {err}"),
+    import_wrong_guild: "The file is from another server",
+    import_duplicate_prisoner: |user| format!("{user} is in prison more than once in the file"),
+    import_duplicate_member: |user| format!("{user} is in the file as a member more than once"),
+    import_invalid_webhook: "The webhook in the file isn't a valid https URL to a public address",
+    import_preview_title: "Import preview",
+    import_done_title: "Import done",
+    import_dry_run_hint:
+        "Nothing has been changed yet, run the command with dry_run: false to import",
+    import_lawsuits: "Lawsuits",
+    import_court_rooms: "Court rooms",
+    import_prison_tiers: "Prison tiers",
+    import_prison_history: "Arrests",
+    import_jobs: "Scheduled jobs",
+    import_members: "Members",
+    import_changed: "will be changed",
+    import_unchanged: "stay the same",
+    import_mode_replace: "Replace",
+    import_mode_merge: "Merge",
+    setting_court_category: "Court category",
+    setting_appeal_category: "Appeal category",
+    setting_prison_role: "Prison role",
+    setting_probation_role: "Probation role",
+    setting_prison_channel: "Prison channel",
+    setting_prison_tier: "Prison tier",
+    setting_tier_removed: |tier| format!("{tier} removed"),
+    setting_strip_roles: "Strip roles",
+    setting_moderator_channel: "Moderator channel",
+    setting_notifications: "Notifications",
+    setting_appeal_judge: "Appeal judge",
+    setting_audit_channel: "Audit channel",
+    setting_error_channel: "Error channel",
+    setting_records_channel: "Records channel",
+    setting_news_role: "Court news",
+    setting_weekly_report: "Weekly report",
+    setting_statute_of_limitations: "Statute of limitations",
+    setting_inactivity_nudge: "Inactivity nudge",
+    setting_lawsuit_limit: "Maximum running lawsuits",
+    setting_filing_cooldown: "Cooldown between lawsuits",
+    setting_court_slowmode: "Court room slowmode",
+    setting_locale: "Language",
+    setting_webhook: "Webhook",
+    setting_api_token: "API token",
+    setting_calendar_token: "Calendar",
+    setting_message_template: "Custom response",
+    setting_admin_role: "Court administration",
+    setting_judge_role: "Judge",
+    setting_bailiff_role: "Bailiff",
+    setting_strict_lawyers: "Only admitted lawyers",
+    setting_conflict_policy: "Conflicts of interest",
+};
@@ -0,0 +1,149 @@
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use color_eyre::Result;
+use dashmap::DashMap;
+use mongodb::bson::DateTime;
+use poise::serenity::{
+    http::Http,
+    model::{channel::Message, id::ChannelId},
+};
+use tracing::info;
+
+use crate::{
+    duration,
+    model::{JobKind, SnowflakeId},
+    storage::Storage,
+    WrapErr,
+};
+
+/// How often the ongoing court rooms are checked for inactivity.
+pub const CHECK_INTERVAL: Duration = Duration::from_secs(60 * 10);
+/// The activity is written at most this often per room, a busy court room would write on every
+/// message otherwise. Much shorter than any nudge time that makes sense.
+const WRITE_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Remembers when the last message was posted in the court rooms with an ongoing lawsuit.
+#[derive(Debug, Default)]
+pub struct ActivityTracker {
+    /// When the activity of the channel was last written to the storage
+    written: DashMap<ChannelId, Instant>,
+}
+
+impl ActivityTracker {
+    /// Called for every message the bot sees, only messages of users in ongoing court rooms count.
+    pub async fn record(&self, storage: &Arc<dyn Storage>, message: &Message) -> Result<()> {
+        let guild_id = match message.guild_id {
+            Some(guild_id) => guild_id,
+            None => return Ok(()),
+        };
+        if message.author.bot {
+            return Ok(());
+        }
+
+        let now = Instant::now();
+        if let Some(written) = self.written.get(&message.channel_id) {
+            if now.duration_since(*written) < WRITE_INTERVAL {
+                return Ok(());
+            }
+        }
+
+        // don't create a state for every guild the bot sees a message in
+        let state = match storage.find_state(guild_id.into()).await? {
+            Some(state) => state,
+            None => return Ok(()),
+        };
+        let channel_id = SnowflakeId::from(message.channel_id);
+        let is_ongoing_room = state
+            .court_rooms
+            .iter()
+            .any(|room| room.channel_id == channel_id && room.ongoing_lawsuit);
+        if !is_ongoing_room {
+            return Ok(());
+        }
+
+        self.written.insert(message.channel_id, now);
+        storage
+            .set_room_activity(guild_id.into(), channel_id, DateTime::now())
+            .await
+    }
+}
+
+/// Pings the judge in the ongoing court rooms where nothing was posted for the configured time,
+/// and the admins after twice the time. Every room is only nudged once per level until the next
+/// message resets it. Schedules the next check first.
+pub async fn nudge_inactive(
+    http: &Arc<Http>,
+    storage: &Arc<dyn Storage>,
+    guild_id: SnowflakeId,
+) -> Result<()> {
+    let state = storage.find_or_insert_state(guild_id).await?;
+    let after = match state.inactivity_nudge {
+        Some(after) => after as i64 * 1000,
+        // turned off after the job was scheduled
+        None => return Ok(()),
+    };
+
+    storage
+        .schedule_job(
+            guild_id,
+            &JobKind::NudgeInactiveRooms,
+            duration::from_now(CHECK_INTERVAL),
+        )
+        .await?;
+
+    let strings = state.locale.strings();
+    let now = DateTime::now().timestamp_millis();
+    let lawsuits = storage.find_open_lawsuits(guild_id).await?;
+
+    for room in state.court_rooms.iter().filter(|room| room.ongoing_lawsuit) {
+        let lawsuit = match lawsuits
+            .iter()
+            .find(|lawsuit| lawsuit.court_room == room.channel_id)
+        {
+            Some(lawsuit) => lawsuit,
+            None => continue,
+        };
+
+        // the lawsuit itself is the first activity in the room
+        let since = [room.last_activity, lawsuit.opened_at, room.last_used]
+            .into_iter()
+            .flatten()
+            .map(|time| time.timestamp_millis())
+            .max();
+        let idle = match since {
+            Some(since) => now - since,
+            None => continue,
+        };
+
+        let judge = format!("<@{}>", lawsuit.judge);
+        let idle_for = duration::format(Duration::from_millis(idle as u64));
+        // without an admin role there is nobody to escalate to
+        let escalate = state
+            .admin_role
+            .filter(|_| idle >= 2 * after && room.nudges < 2);
+        let (nudges, content) = match escalate {
+            Some(admin_role) => (
+                2,
+                (strings.inactivity_nudge_admins)(&format!("<@&{admin_role}>"), &judge, &idle_for),
+            ),
+            None if idle >= after && room.nudges < 1 => {
+                (1, (strings.inactivity_nudge_judge)(&judge, &idle_for))
+            }
+            None => continue,
+        };
+
+        ChannelId::from(room.channel_id)
+            .say(http, content)
+            .await
+            .wrap_err("send inactivity nudge")?;
+        storage
+            .set_room_nudges(guild_id, room.channel_id, nudges)
+            .await?;
+        info!(%guild_id, channel_id = %room.channel_id, nudges, "Nudged inactive court room");
+    }
+
+    Ok(())
+}
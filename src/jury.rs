@@ -0,0 +1,289 @@
+use std::{collections::HashMap, sync::Arc, time::Duration};
+
+use color_eyre::Result;
+use futures::StreamExt;
+use mongodb::bson::Uuid;
+use poise::serenity::{
+    builder::CreateComponents,
+    collector::ComponentInteractionCollectorBuilder,
+    http::Http,
+    model::{
+        id::{ChannelId, GuildId, UserId},
+        interactions::{message_component::ButtonStyle, InteractionResponseType},
+    },
+    prelude::Context,
+};
+use rand::seq::SliceRandom;
+use tracing::{debug, info};
+
+use crate::{
+    duration,
+    i18n::Strings,
+    lawsuit::{JuryTally, Lawsuit},
+    model::SnowflakeId,
+    retry::retry,
+    shutdown::BackgroundTasks,
+    storage::Storage,
+    WrapErr,
+};
+
+/// The votes are secret: every juror gets a ballot as a direct message and only the sums are
+/// stored on the lawsuit and shown in the court room. Who voted what is only known while the
+/// ballot is running and never written anywhere.
+pub struct Ballot {
+    ids: BallotIds,
+    lawsuit: Lawsuit,
+    /// The jurors who got their ballot, the others can't vote and abstain
+    reached: Vec<UserId>,
+    jurors: usize,
+    timeout: Duration,
+}
+
+impl Ballot {
+    /// Sends the ballots to the jurors. Jurors who don't accept direct messages are skipped.
+    pub async fn send(
+        http: &Http,
+        guild_id: GuildId,
+        strings: &Strings,
+        lawsuit: Lawsuit,
+        jurors: &[UserId],
+        timeout: Duration,
+    ) -> Result<Self> {
+        let guild = guild_id
+            .to_partial_guild(http)
+            .await
+            .wrap_err("fetch guild")?;
+        let ids = BallotIds::new();
+        let deadline = duration::relative_timestamp(duration::from_now(timeout));
+
+        let mut reached = Vec::new();
+        for &juror in jurors {
+            let result = async {
+                let channel = juror
+                    .create_dm_channel(http)
+                    .await
+                    .wrap_err("create dm channel")?;
+                channel
+                    .send_message(http, |message| {
+                        message
+                            .embed(|embed| {
+                                embed
+                                    .title(strings.jury_ballot_title)
+                                    .description((strings.jury_ballot_description)(&deadline))
+                                    .field(strings.field_reason, &lawsuit.reason, false)
+                                    .field(
+                                        strings.field_plaintiff,
+                                        format!("<@{}>", lawsuit.plaintiff),
+                                        true,
+                                    )
+                                    .field(
+                                        strings.field_accused,
+                                        format!("<@{}>", lawsuit.accused),
+                                        true,
+                                    )
+                                    .footer(|footer| footer.text(&guild.name))
+                            })
+                            .components(|c| ballot_components(c, strings, &ids))
+                    })
+                    .await
+                    .wrap_err("send ballot")
+            }
+            .await;
+
+            match result {
+                Ok(_) => reached.push(juror),
+                Err(err) => debug!(?err, %juror, "Could not send ballot"),
+            }
+        }
+
+        Ok(Self {
+            ids,
+            lawsuit,
+            reached,
+            jurors: jurors.len(),
+            timeout,
+        })
+    }
+
+    pub fn reached(&self) -> usize {
+        self.reached.len()
+    }
+
+    /// Waits until every juror voted or the time is up, then stores the tally and reveals it in
+    /// the court room. If the bot shuts down before, the votes so far are counted.
+    pub async fn collect(
+        self,
+        ctx: Context,
+        storage: Arc<dyn Storage>,
+        tasks: BackgroundTasks,
+        strings: &'static Strings,
+    ) -> Result<JuryTally> {
+        let prefix = self.ids.prefix.clone();
+        let reached = self.reached.clone();
+        let mut collector = ComponentInteractionCollectorBuilder::new(&ctx)
+            .filter(move |interaction| {
+                interaction.data.custom_id.starts_with(&prefix)
+                    && reached.contains(&interaction.user.id)
+            })
+            .timeout(self.timeout)
+            .build();
+
+        let mut votes = HashMap::new();
+        while votes.len() < self.reached.len() {
+            let interaction = tokio::select! {
+                interaction = collector.next() => interaction,
+                _ = tasks.shutting_down() => None,
+            };
+            let interaction = match interaction {
+                Some(interaction) => interaction,
+                None => break,
+            };
+
+            let guilty = interaction.data.custom_id == self.ids.guilty;
+            votes.insert(interaction.user.id, guilty);
+
+            let choice = if guilty {
+                strings.jury_guilty
+            } else {
+                strings.jury_not_guilty
+            };
+            // the buttons are removed, every juror has exactly one vote
+            interaction
+                .create_interaction_response(&ctx.http, |response| {
+                    response
+                        .kind(InteractionResponseType::UpdateMessage)
+                        .interaction_response_data(|data| {
+                            data.content((strings.jury_vote_counted)(choice))
+                                .components(|c| c)
+                        })
+                })
+                .await
+                .wrap_err("acknowledge vote")?;
+        }
+
+        let guilty = votes.values().filter(|guilty| **guilty).count();
+        let tally = JuryTally {
+            guilty: guilty as u32,
+            not_guilty: (votes.len() - guilty) as u32,
+            abstained: (self.jurors - votes.len()) as u32,
+        };
+
+        storage
+            .set_jury_tally(self.lawsuit.guild_id, self.lawsuit.id, &tally)
+            .await?;
+        info!(lawsuit_id = %self.lawsuit.id, ?tally, "Jury has voted");
+
+        ChannelId::from(self.lawsuit.court_room)
+            .send_message(&ctx.http, |message| {
+                message.embed(|embed| {
+                    embed
+                        .title(strings.jury_result_title)
+                        .field(strings.field_reason, &self.lawsuit.reason, false)
+                        .field(strings.jury_guilty, tally.guilty, true)
+                        .field(strings.jury_not_guilty, tally.not_guilty, true)
+                        .field(strings.jury_abstained, tally.abstained, true)
+                })
+            })
+            .await
+            .wrap_err("send jury result")?;
+
+        Ok(tally)
+    }
+}
+
+/// Draws up to `count` jurors at random from the members of the pool who are available, the
+/// unavailable ones are the parties of the lawsuit and the jurors of other running trials.
+pub fn draw(pool: &[SnowflakeId], unavailable: &[SnowflakeId], count: usize) -> Vec<SnowflakeId> {
+    let available = pool
+        .iter()
+        .filter(|member| !unavailable.contains(member))
+        .copied()
+        .collect::<Vec<_>>();
+    available
+        .choose_multiple(&mut rand::thread_rng(), count)
+        .copied()
+        .collect()
+}
+
+/// Gives the jurors the role of the court room so that they can follow the trial and sends them a
+/// summons. Jurors who don't accept direct messages still get the role.
+pub async fn summon(
+    http: &Http,
+    guild_id: GuildId,
+    strings: &Strings,
+    lawsuit: &Lawsuit,
+    role_id: SnowflakeId,
+    jurors: &[UserId],
+) -> Result<()> {
+    for &juror in jurors {
+        retry(|| http.add_member_role(guild_id.0, juror.0, role_id.0, None))
+            .await
+            .wrap_err("add role to juror")?;
+
+        let result = async {
+            let channel = juror
+                .create_dm_channel(http)
+                .await
+                .wrap_err("create dm channel")?;
+            channel
+                .send_message(http, |message| {
+                    message.embed(|embed| {
+                        embed
+                            .title(strings.jury_summons_title)
+                            .description((strings.jury_summons_description)(&format!(
+                                "<#{}>",
+                                lawsuit.court_room
+                            )))
+                            .field(strings.field_reason, &lawsuit.reason, false)
+                    })
+                })
+                .await
+                .wrap_err("send summons")
+        }
+        .await;
+
+        if let Err(err) = result {
+            debug!(?err, %juror, "Could not send summons");
+        }
+    }
+    Ok(())
+}
+
+struct BallotIds {
+    prefix: String,
+    guilty: String,
+    not_guilty: String,
+}
+
+impl BallotIds {
+    fn new() -> Self {
+        // the ids must be unique so that two ballots running at the same time don't interfere
+        let prefix = format!("ballot-{}", Uuid::new());
+        Self {
+            guilty: format!("{prefix}-guilty"),
+            not_guilty: format!("{prefix}-not-guilty"),
+            prefix,
+        }
+    }
+}
+
+fn ballot_components<'a>(
+    components: &'a mut CreateComponents,
+    strings: &Strings,
+    ids: &BallotIds,
+) -> &'a mut CreateComponents {
+    components.create_action_row(|row| {
+        row.create_button(|button| {
+            button
+                .custom_id(&ids.guilty)
+                .label(strings.jury_guilty)
+                .style(ButtonStyle::Danger)
+        })
+        .create_button(|button| {
+            button
+                .custom_id(&ids.not_guilty)
+                .label(strings.jury_not_guilty)
+                .style(ButtonStyle::Success)
+        })
+    })
+}
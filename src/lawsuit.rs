@@ -1,23 +1,36 @@
-use std::sync::Arc;
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::Arc,
+    time::Duration,
+};
 
-use color_eyre::Result;
-use mongodb::bson::{doc, Uuid};
+use color_eyre::{eyre::bail, Result};
+use mongodb::bson::{DateTime, Uuid};
 use poise::{
     serenity::model::prelude::*,
-    serenity_prelude::{CreateMessage, Http},
+    serenity_prelude::{CreateEmbed, CreateMessage, Http},
 };
 use serde::{Deserialize, Serialize};
-use tracing::{error, info};
+use tracing::{error, info, warn};
 
 use crate::{
+    audit::{self, AuditEvent},
+    duration, evidence,
     handler::Response,
-    model::{CourtRoom, SnowflakeId},
-    Mongo, WrapErr,
+    i18n::Strings,
+    metrics,
+    model::{CourtLevel, CourtRoom, JobKind, SnowflakeId, State, DEFAULT_APPEAL_ROOM_NAME},
+    retry::retry,
+    shutdown::BackgroundTasks,
+    storage::Storage,
+    templates::{self, Template},
+    WrapErr,
 };
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Lawsuit {
     pub id: Uuid,
+    pub guild_id: SnowflakeId,
     pub plaintiff: SnowflakeId,
     pub accused: SnowflakeId,
     pub plaintiff_lawyer: Option<SnowflakeId>,
@@ -26,89 +39,1146 @@ pub struct Lawsuit {
     pub reason: String,
     pub verdict: Option<String>,
     pub court_room: SnowflakeId,
+    #[serde(default)]
+    pub evidence: Vec<Evidence>,
+    /// The result of the jury, see [`crate::jury`]
+    #[serde(default)]
+    pub jury_tally: Option<JuryTally>,
+    /// The jurors who got the role of the court room for the trial
+    #[serde(default)]
+    pub jurors: Vec<SnowflakeId>,
+    #[serde(default)]
+    pub fine: Option<Fine>,
+    /// What the parties paid their lawyers
+    #[serde(default)]
+    pub billing: Vec<Billing>,
+    /// `None` for lawsuits from before the times were stored
+    #[serde(default)]
+    pub opened_at: Option<DateTime>,
+    #[serde(default)]
+    pub closed_at: Option<DateTime>,
+    /// The lawsuit whose verdict is appealed, see `/lawsuit appeal`
+    #[serde(default)]
+    pub appeal_of: Option<Uuid>,
+    /// Whether the appeal overturned the verdict of the appealed lawsuit
+    #[serde(default)]
+    pub overturned: bool,
+    /// The number of the case in the year like `2023-04`, `None` for lawsuits from before the
+    /// cases were numbered
+    #[serde(default)]
+    pub case_number: Option<String>,
+    /// Sealed lawsuits are not published in the records channel
+    #[serde(default)]
+    pub sealed: bool,
+    /// The earlier lawsuits that the verdict cites as precedents
+    #[serde(default)]
+    pub cites: Vec<Uuid>,
+    /// The later lawsuits that cite this one as precedent
+    #[serde(default)]
+    pub cited_by: Vec<Uuid>,
+    #[serde(default)]
+    pub witnesses: Vec<Witness>,
+    /// When the next hearing takes place, shown in the calendar feed, see [`crate::calendar`]
+    #[serde(default)]
+    pub hearing_at: Option<DateTime>,
+    /// The parts of the verdict from the modal of `/lawsuit close`, `verdict` is the outcome
+    #[serde(default)]
+    pub verdict_details: Option<VerdictDetails>,
+    #[serde(default)]
+    pub contempts: Vec<Contempt>,
+    /// The people who can't see the court room until the verdict, see `/lawsuit eject`
+    #[serde(default)]
+    pub ejected: Vec<SnowflakeId>,
 }
 
-pub struct LawsuitCtx {
-    pub lawsuit: Lawsuit,
-    pub mongo_client: Mongo,
-    pub http: Arc<Http>,
-    pub guild_id: GuildId,
+/// What the judge wrote in the verdict modal next to the outcome.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VerdictDetails {
+    /// Why the judge decided like that
+    pub reasoning: Option<String>,
+    /// The punishment, if there is one
+    pub sentence: Option<String>,
 }
 
-impl LawsuitCtx {
-    pub async fn initialize(mut self) -> Result<Response> {
-        let state = self
-            .mongo_client
-            .find_or_insert_state(self.guild_id.into())
-            .await?;
+/// A message that was marked as evidence in the court room.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Evidence {
+    /// The author of the message
+    pub author: SnowflakeId,
+    pub content: String,
+    pub link: String,
+    /// The urls of the attachments
+    #[serde(default)]
+    pub attachments: Vec<String>,
+    pub added_by: SnowflakeId,
+    pub added_at: DateTime,
+}
+
+/// A witness who was summoned with `/lawsuit witness`, see [`crate::subpoena`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Witness {
+    pub user_id: SnowflakeId,
+    pub added_by: SnowflakeId,
+    pub added_at: DateTime,
+    pub response: SubpoenaResponse,
+}
+
+/// Someone the judge held in contempt of court with `/lawsuit contempt`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Contempt {
+    pub user_id: SnowflakeId,
+    pub ordered_at: DateTime,
+    pub until: DateTime,
+    /// `false` if they couldn't be timed out and only lost the permission to write in the court
+    /// room instead
+    pub timed_out: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SubpoenaResponse {
+    /// The witness didn't answer yet
+    Pending,
+    Accepted,
+    Declined,
+    /// The witness didn't answer before the deadline
+    Ignored,
+    /// The witness doesn't accept direct messages
+    Undelivered,
+}
+
+/// Money that the judge imposed on the accused. It's taken from their balance once they pay it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Fine {
+    pub amount: i64,
+    pub imposed_at: DateTime,
+    /// `None` as long as the fine is open
+    pub paid_at: Option<DateTime>,
+}
+
+impl Fine {
+    /// The amount with whether it's paid yet.
+    pub fn describe(&self, strings: &Strings) -> String {
+        let status = match self.paid_at {
+            Some(_) => strings.fine_status_paid,
+            None => strings.fine_status_open,
+        };
+        format!("{} ({status})", (strings.currency)(self.amount))
+    }
+}
+
+/// What a lawyer charges their clients, set with `/lawyer fee`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LawyerFee {
+    pub kind: FeeKind,
+    pub amount: i64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, poise::ChoiceParameter)]
+pub enum FeeKind {
+    #[name = "Pro Stunde"]
+    Hourly,
+    #[name = "Pauschal"]
+    Flat,
+}
+
+/// What happens when `/lawsuit create` finds a conflict of interest, see [`Lawsuit::conflicts`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, poise::ChoiceParameter)]
+pub enum ConflictPolicy {
+    #[name = "Ablehnen"]
+    Reject,
+    #[name = "Warnen"]
+    Warn,
+    #[name = "Erlauben"]
+    Allow,
+}
+
+impl Default for ConflictPolicy {
+    fn default() -> Self {
+        Self::Reject
+    }
+}
+
+/// Someone who has more than one role in a lawsuit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Conflict {
+    /// The judge is the plaintiff, the accused or one of the lawyers
+    JudgeIsParty(SnowflakeId),
+    /// The same lawyer represents the plaintiff and the accused
+    LawyerForBothSides(SnowflakeId),
+    /// A lawyer represents the opponent of their own client
+    LawyerIsOpponent(SnowflakeId),
+}
+
+impl Conflict {
+    pub fn describe(&self, strings: &Strings) -> String {
+        match *self {
+            Self::JudgeIsParty(id) => (strings.conflict_judge_is_party)(&format!("<@{id}>")),
+            Self::LawyerForBothSides(id) => {
+                (strings.conflict_lawyer_for_both_sides)(&format!("<@{id}>"))
+            }
+            Self::LawyerIsOpponent(id) => {
+                (strings.conflict_lawyer_is_opponent)(&format!("<@{id}>"))
+            }
+        }
+    }
+}
+
+/// Why a lawsuit can't be opened at all, unlike a [`Conflict`] regardless of the conflict policy.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InvalidParties {
+    /// The plaintiff and the accused are the same person
+    SameParties,
+    /// The judge is the plaintiff or the accused
+    JudgeIsParty(SnowflakeId),
+    /// Bots can't answer in court
+    Bot(SnowflakeId),
+    /// A party is their own lawyer, they would pay the fees to themselves
+    OwnLawyer(SnowflakeId),
+}
+
+impl InvalidParties {
+    /// Checks the parties before a lawsuit is created. The judge is `None` if it's assigned later,
+    /// `bots` are the participants that are bots.
+    pub fn check(
+        plaintiff: SnowflakeId,
+        accused: SnowflakeId,
+        judge: Option<SnowflakeId>,
+        bots: &[SnowflakeId],
+    ) -> Option<Self> {
+        if let Some(&bot) = bots.first() {
+            return Some(Self::Bot(bot));
+        }
+        if plaintiff == accused {
+            return Some(Self::SameParties);
+        }
+        judge
+            .filter(|judge| *judge == plaintiff || *judge == accused)
+            .map(Self::JudgeIsParty)
+    }
+
+    /// Checks the lawyers of the parties, only lawsuits that are created with lawyers have them.
+    pub fn check_lawyers(
+        plaintiff: SnowflakeId,
+        accused: SnowflakeId,
+        plaintiff_lawyer: Option<SnowflakeId>,
+        accused_lawyer: Option<SnowflakeId>,
+    ) -> Option<Self> {
+        [(plaintiff, plaintiff_lawyer), (accused, accused_lawyer)]
+            .into_iter()
+            .find(|(party, lawyer)| Some(*party) == *lawyer)
+            .map(|(party, _)| Self::OwnLawyer(party))
+    }
+
+    pub fn describe(&self, strings: &Strings) -> String {
+        match *self {
+            Self::SameParties => strings.invalid_parties_same.to_owned(),
+            Self::JudgeIsParty(id) => (strings.conflict_judge_is_party)(&format!("<@{id}>")),
+            Self::Bot(id) => (strings.invalid_parties_bot)(&format!("<@{id}>")),
+            Self::OwnLawyer(id) => (strings.invalid_parties_own_lawyer)(&format!("<@{id}>")),
+        }
+    }
+}
+
+impl LawyerFee {
+    /// What the lawyer gets for the work, the hours only count for hourly fees.
+    pub fn charge(&self, hours: u32) -> i64 {
+        match self.kind {
+            FeeKind::Hourly => self.amount.saturating_mul(i64::from(hours)),
+            FeeKind::Flat => self.amount,
+        }
+    }
+
+    pub fn describe(&self, strings: &Strings) -> String {
+        let amount = (strings.currency)(self.amount);
+        match self.kind {
+            FeeKind::Hourly => (strings.fee_hourly)(&amount),
+            FeeKind::Flat => (strings.fee_flat)(&amount),
+        }
+    }
+}
+
+/// Money that a client paid their lawyer, either upfront as retainer or billed by the lawyer.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Billing {
+    pub lawyer: SnowflakeId,
+    pub client: SnowflakeId,
+    pub amount: i64,
+    pub retainer: bool,
+    pub billed_at: DateTime,
+}
+
+/// How the jury voted. Only the sums are stored, so that the votes stay secret.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JuryTally {
+    pub guilty: u32,
+    pub not_guilty: u32,
+    /// The jurors who didn't vote in time or couldn't get their ballot
+    pub abstained: u32,
+}
+
+/// The words of verdicts that acquit the accused, in all languages of the bot, separated by `|`
+/// so that the database can use it as a regex. Checked before [`GUILTY_VERDICT`], since
+/// "nicht schuldig" also contains "schuldig".
+pub const NOT_GUILTY_VERDICT: &str =
+    "nicht schuldig|nöd schuldig|not guilty|unschuldig|freispruch|freigesprochen|acquitted";
+/// The words of verdicts that convict the accused, like [`NOT_GUILTY_VERDICT`].
+pub const GUILTY_VERDICT: &str = "schuldig|guilty|verurteilt|convicted";
+
+impl Lawsuit {
+    /// A new open lawsuit without a court room, lawyers or anything else that's added later.
+    pub fn new(
+        guild_id: SnowflakeId,
+        plaintiff: SnowflakeId,
+        accused: SnowflakeId,
+        judge: SnowflakeId,
+        reason: String,
+    ) -> Self {
+        Self {
+            id: Uuid::new(),
+            guild_id,
+            plaintiff,
+            accused,
+            judge,
+            plaintiff_lawyer: None,
+            accused_lawyer: None,
+            reason,
+            verdict: None,
+            court_room: SnowflakeId(0),
+            evidence: Vec::new(),
+            jury_tally: None,
+            jurors: Vec::new(),
+            fine: None,
+            billing: Vec::new(),
+            opened_at: None,
+            closed_at: None,
+            appeal_of: None,
+            overturned: false,
+            case_number: None,
+            sealed: false,
+            cites: Vec::new(),
+            cited_by: Vec::new(),
+            witnesses: Vec::new(),
+            hearing_at: None,
+            verdict_details: None,
+            contempts: Vec::new(),
+            ejected: Vec::new(),
+        }
+    }
+
+    /// Whether the verdict convicts the accused. The verdict is free text, so this is `None` for
+    /// open lawsuits and verdicts that say neither.
+    pub fn is_guilty(&self) -> Option<bool> {
+        let verdict = self.verdict.as_deref()?.to_lowercase();
+        let says = |words: &str| words.split('|').any(|word| verdict.contains(word));
+        if says(NOT_GUILTY_VERDICT) {
+            Some(false)
+        } else if says(GUILTY_VERDICT) {
+            Some(true)
+        } else {
+            None
+        }
+    }
+
+    /// Whether the side won the lawsuit, `None` like [`Lawsuit::is_guilty`].
+    pub fn won_by(&self, side: Side) -> Option<bool> {
+        self.is_guilty()
+            .map(|guilty| guilty == (side == Side::Plaintiff))
+    }
+
+    /// The side that the lawyer represents in the lawsuit.
+    pub fn represented_by(&self, lawyer: SnowflakeId) -> Option<Side> {
+        if self.plaintiff_lawyer == Some(lawyer) {
+            Some(Side::Plaintiff)
+        } else if self.accused_lawyer == Some(lawyer) {
+            Some(Side::Accused)
+        } else {
+            None
+        }
+    }
+
+    /// Whether the user is one of the parties, their lawyers or the judge.
+    pub fn takes_part(&self, user_id: SnowflakeId) -> bool {
+        let participants = [
+            Some(self.plaintiff),
+            Some(self.accused),
+            Some(self.judge),
+            self.plaintiff_lawyer,
+            self.accused_lawyer,
+        ];
+        participants.contains(&Some(user_id))
+    }
 
-        let free_room = state
+    /// Replaces the user with the id 0 and removes them as lawyer or juror. Their messages that
+    /// were marked as evidence are emptied. Returns whether they took part in the lawsuit.
+    pub fn anonymize_user(&mut self, user_id: SnowflakeId) -> bool {
+        const ANONYMOUS: SnowflakeId = SnowflakeId(0);
+
+        let mut touched = false;
+        for participant in [&mut self.plaintiff, &mut self.accused, &mut self.judge] {
+            if *participant == user_id {
+                *participant = ANONYMOUS;
+                touched = true;
+            }
+        }
+        for lawyer in [&mut self.plaintiff_lawyer, &mut self.accused_lawyer] {
+            if *lawyer == Some(user_id) {
+                *lawyer = None;
+                touched = true;
+            }
+        }
+        if self.jurors.contains(&user_id) {
+            self.jurors.retain(|juror| *juror != user_id);
+            touched = true;
+        }
+        for billing in &mut self.billing {
+            for party in [&mut billing.lawyer, &mut billing.client] {
+                if *party == user_id {
+                    *party = ANONYMOUS;
+                    touched = true;
+                }
+            }
+        }
+        for evidence in &mut self.evidence {
+            if evidence.author == user_id {
+                evidence.author = ANONYMOUS;
+                evidence.content.clear();
+                evidence.attachments.clear();
+                touched = true;
+            }
+            if evidence.added_by == user_id {
+                evidence.added_by = ANONYMOUS;
+                touched = true;
+            }
+        }
+        for witness in &mut self.witnesses {
+            for person in [&mut witness.user_id, &mut witness.added_by] {
+                if *person == user_id {
+                    *person = ANONYMOUS;
+                    touched = true;
+                }
+            }
+        }
+        for contempt in &mut self.contempts {
+            if contempt.user_id == user_id {
+                contempt.user_id = ANONYMOUS;
+                touched = true;
+            }
+        }
+        if self.ejected.contains(&user_id) {
+            self.ejected.retain(|ejected| *ejected != user_id);
+            touched = true;
+        }
+        touched
+    }
+
+    /// Everyone who takes part in the lawsuit apart from the jurors.
+    pub fn parties(&self) -> Vec<SnowflakeId> {
+        [self.plaintiff, self.accused, self.judge]
+            .into_iter()
+            .chain(self.plaintiff_lawyer)
+            .chain(self.accused_lawyer)
+            .collect()
+    }
+
+    /// The conflicts of interest between the judge, the parties and their lawyers.
+    pub fn conflicts(&self) -> Vec<Conflict> {
+        let mut conflicts = Vec::new();
+        let judge_is_party = [self.plaintiff, self.accused]
+            .into_iter()
+            .chain(self.plaintiff_lawyer)
+            .chain(self.accused_lawyer)
+            .any(|id| id == self.judge);
+        if judge_is_party {
+            conflicts.push(Conflict::JudgeIsParty(self.judge));
+        }
+        if let Some(lawyer) = self
+            .plaintiff_lawyer
+            .filter(|lawyer| Some(*lawyer) == self.accused_lawyer)
+        {
+            conflicts.push(Conflict::LawyerForBothSides(lawyer));
+        }
+        if let Some(lawyer) = self
+            .plaintiff_lawyer
+            .filter(|lawyer| *lawyer == self.accused)
+        {
+            conflicts.push(Conflict::LawyerIsOpponent(lawyer));
+        }
+        if let Some(lawyer) = self
+            .accused_lawyer
+            .filter(|lawyer| *lawyer == self.plaintiff)
+        {
+            conflicts.push(Conflict::LawyerIsOpponent(lawyer));
+        }
+        conflicts
+    }
+
+    /// The sum of what every lawyer got paid in the lawsuit.
+    pub fn billed_by_lawyer(&self) -> BTreeMap<SnowflakeId, i64> {
+        let mut billed = BTreeMap::new();
+        for billing in &self.billing {
+            *billed.entry(billing.lawyer).or_default() += billing.amount;
+        }
+        billed
+    }
+}
+
+/// How a judge decides, derived from the lawsuits of the guild for `/judge stats`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct JudgeStats {
+    pub closed: u64,
+    pub open: u64,
+    /// Only of the closed lawsuits that know when they were opened
+    pub average_duration: Option<Duration>,
+    /// The closed appeals against the verdicts of the judge
+    pub appeals: u64,
+    pub overturned: u64,
+}
+
+impl JudgeStats {
+    pub fn of(judge: SnowflakeId, lawsuits: &[Lawsuit]) -> Self {
+        let mut stats = Self::default();
+        let mut durations = Vec::new();
+        let mut closed = Vec::new();
+        for lawsuit in lawsuits.iter().filter(|lawsuit| lawsuit.judge == judge) {
+            if lawsuit.verdict.is_none() {
+                stats.open += 1;
+                continue;
+            }
+            stats.closed += 1;
+            closed.push(lawsuit.id);
+            if let (Some(opened_at), Some(closed_at)) = (lawsuit.opened_at, lawsuit.closed_at) {
+                let millis = closed_at.timestamp_millis() - opened_at.timestamp_millis();
+                durations.push(Duration::from_millis(millis.max(0) as u64));
+            }
+        }
+        if !durations.is_empty() {
+            stats.average_duration =
+                Some(durations.iter().sum::<Duration>() / durations.len() as u32);
+        }
+
+        let appeals = lawsuits.iter().filter(|lawsuit| {
+            lawsuit.verdict.is_some()
+                && lawsuit
+                    .appeal_of
+                    .map_or(false, |appealed| closed.contains(&appealed))
+        });
+        for appeal in appeals {
+            stats.appeals += 1;
+            if appeal.overturned {
+                stats.overturned += 1;
+            }
+        }
+        stats
+    }
+
+    /// The share of the appeals that overturned the verdict in percent, `None` without appeals.
+    pub fn overturn_rate(&self) -> Option<u64> {
+        (self.appeals > 0).then(|| self.overturned * 100 / self.appeals)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Side {
+    Plaintiff,
+    Accused,
+}
+
+/// The lawsuits of a lawyer and how they ended for their client, for `/lawyer record`. Sealed
+/// lawsuits are left out.
+#[derive(Debug, Clone, Default)]
+pub struct LawyerRecord<'a> {
+    /// The newest first
+    pub cases: Vec<(&'a Lawsuit, Side)>,
+    pub won: u64,
+    pub lost: u64,
+}
+
+impl<'a> LawyerRecord<'a> {
+    pub fn of(lawyer: SnowflakeId, lawsuits: &'a [Lawsuit]) -> Self {
+        let mut record = Self::default();
+        // the record is public, sealed lawsuits would give away their parties and verdicts
+        for lawsuit in lawsuits.iter().filter(|lawsuit| !lawsuit.sealed) {
+            let side = match lawsuit.represented_by(lawyer) {
+                Some(side) => side,
+                None => continue,
+            };
+            match lawsuit.won_by(side) {
+                Some(true) => record.won += 1,
+                Some(false) => record.lost += 1,
+                None => {}
+            }
+            record.cases.push((lawsuit, side));
+        }
+        // lawsuits from before the times were stored go last
+        record
+            .cases
+            .sort_by(|(a, _), (b, _)| b.opened_at.cmp(&a.opened_at));
+        record
+    }
+
+    /// The share of the decided lawsuits that the lawyer won in percent, `None` without any.
+    pub fn success_rate(&self) -> Option<u64> {
+        let decided = self.won + self.lost;
+        (decided > 0).then(|| self.won * 100 / decided)
+    }
+}
+
+/// The members with the most lawsuits for `/court leaderboard`, each list with the most first.
+#[derive(Debug, Clone, Default)]
+pub struct Leaderboard {
+    pub filed: Vec<(SnowflakeId, u64)>,
+    /// The plaintiffs of guilty verdicts and the accused of not guilty verdicts
+    pub won: Vec<(SnowflakeId, u64)>,
+    pub convicted: Vec<(SnowflakeId, u64)>,
+}
+
+impl Leaderboard {
+    pub const LEN: usize = 10;
+
+    pub fn of(lawsuits: &[Lawsuit]) -> Self {
+        let mut filed = HashMap::new();
+        let mut won = HashMap::new();
+        let mut convicted = HashMap::new();
+        for lawsuit in lawsuits {
+            *filed.entry(lawsuit.plaintiff).or_default() += 1;
+            match lawsuit.is_guilty() {
+                Some(true) => {
+                    *won.entry(lawsuit.plaintiff).or_default() += 1;
+                    *convicted.entry(lawsuit.accused).or_default() += 1;
+                }
+                Some(false) => *won.entry(lawsuit.accused).or_default() += 1,
+                None => {}
+            }
+        }
+
+        Self {
+            filed: ranked(filed),
+            won: ranked(won),
+            convicted: ranked(convicted),
+        }
+    }
+}
+
+fn ranked(counts: HashMap<SnowflakeId, u64>) -> Vec<(SnowflakeId, u64)> {
+    // erased users are replaced with the id 0, they would be at the top for everyone together
+    let mut counts = counts
+        .into_iter()
+        .filter(|(user_id, _)| *user_id != SnowflakeId(0))
+        .collect::<Vec<_>>();
+    counts.sort_by(|(a_id, a), (b_id, b)| b.cmp(a).then(a_id.cmp(b_id)));
+    counts.truncate(Leaderboard::LEN);
+    counts
+}
+
+/// How often the open lawsuits are checked against the statute of limitations.
+pub const EXPIRY_CHECK_INTERVAL: Duration = Duration::from_secs(60 * 60);
+/// Discord doesn't allow a longer slowmode
+pub const MAX_SLOWMODE: Duration = Duration::from_secs(60 * 60 * 6);
+/// How often choosing a court room is tried again when another command changed them meanwhile
+const MAX_CLAIM_ATTEMPTS: u32 = 5;
+
+/// Dismisses the open lawsuits of the guild that were opened longer than the statute of
+/// limitations ago and schedules the next check. Lawsuits from before the times were stored are
+/// never dismissed.
+pub async fn dismiss_expired(
+    http: &Arc<Http>,
+    storage: &Arc<dyn Storage>,
+    tasks: &BackgroundTasks,
+    guild_id: SnowflakeId,
+) -> Result<()> {
+    let state = storage.find_or_insert_state(guild_id).await?;
+    let max_age = match state.statute_of_limitations {
+        Some(max_age) => Duration::from_secs(max_age),
+        // turned off after the job was scheduled
+        None => return Ok(()),
+    };
+
+    storage
+        .schedule_job(
+            guild_id,
+            &JobKind::DismissExpiredLawsuits,
+            duration::from_now(EXPIRY_CHECK_INTERVAL),
+        )
+        .await?;
+
+    let strings = state.locale.strings();
+    let oldest = DateTime::now().timestamp_millis() - max_age.as_millis() as i64;
+    let expired = storage
+        .find_open_lawsuits(guild_id)
+        .await?
+        .into_iter()
+        .filter(|lawsuit| {
+            lawsuit
+                .opened_at
+                .map_or(false, |opened_at| opened_at.timestamp_millis() <= oldest)
+        });
+
+    for lawsuit in expired {
+        let verdict = (strings.lawsuit_dismissed)(&duration::format(max_age));
+        let lawsuit_id = lawsuit.id;
+        let room = state
             .court_rooms
             .iter()
-            .find(|r| !r.ongoing_lawsuit)
+            .find(|room| room.channel_id == lawsuit.court_room)
             .cloned();
 
-        let room = match (free_room, &state.court_category) {
-            (Some(room), _) => room,
-            (None, Some(category)) => {
-                // create room
-
-                let result = self
-                    .create_room(state.court_rooms.len(), *category)
-                    .await
-                    .wrap_err("create new room")?;
-
-                match result {
-                    Err(res) => return Ok(res),
-                    Ok(room) => room,
+        match room {
+            Some(room) => {
+                let judge = lawsuit.judge.into();
+                let mut lawsuit_ctx = LawsuitCtx {
+                    lawsuit,
+                    storage: storage.clone(),
+                    http: http.clone(),
+                    tasks: tasks.clone(),
+                    guild_id: guild_id.into(),
+                };
+                // the bot dismisses it, so it doesn't have to be the judge
+                if let Err(response) = lawsuit_ctx
+                    .rule_verdict(true, judge, verdict, None, room)
+                    .await?
+                {
+                    warn!(%lawsuit_id, %response, "Failed to post the dismissal in the court room");
                 }
             }
-            (None, None) => return Ok(Response(
-                "Zuerst eine Kategorie für die Gerichtsräume festlegen mit `/lawsuit set_category`".to_string(),
-            )),
-        };
+            // there are no roles to remove or room to free without the room
+            None => {
+                storage
+                    .set_lawsuit_verdict(guild_id, lawsuit_id, Some(&verdict))
+                    .await?;
+            }
+        }
+        info!(%guild_id, %lawsuit_id, "Dismissed expired lawsuit");
+    }
+
+    Ok(())
+}
+
+/// Sets the slowmode of the court room in seconds, 0 turns it off.
+async fn set_slowmode(http: &Http, channel_id: SnowflakeId, seconds: u64) -> Result<()> {
+    let seconds = seconds.min(MAX_SLOWMODE.as_secs());
+    retry(|| {
+        ChannelId::from(channel_id).edit(http, |channel| channel.rate_limit_per_user(seconds))
+    })
+    .await
+    .wrap_err("set court room slowmode")?;
+
+    Ok(())
+}
+
+/// The longest timeout that discord allows
+pub const MAX_CONTEMPT: Duration = Duration::from_secs(60 * 60 * 24 * 28);
+
+/// Takes the fine from the balance of the accused and stores it on the lawsuit.
+pub async fn impose_fine(
+    http: &Http,
+    storage: &Arc<dyn Storage>,
+    state: &State,
+    lawsuit: &mut Lawsuit,
+    amount: i64,
+) -> Result<Fine> {
+    // the balance can go negative, then the fine stays open as debt until it's paid off
+    let balance = storage
+        .add_to_balance(lawsuit.guild_id, lawsuit.accused, -amount)
+        .await?;
+    let now = DateTime::now();
+    let fine = Fine {
+        amount,
+        imposed_at: now,
+        paid_at: (balance >= 0).then_some(now),
+    };
+    storage
+        .set_lawsuit_fine(lawsuit.guild_id, lawsuit.id, &fine)
+        .await?;
+    lawsuit.fine = Some(fine);
+
+    info!(lawsuit_id = %lawsuit.id, ?fine, balance, "Imposed fine");
+
+    audit::log(http, state, AuditEvent::FineImposed { lawsuit, amount }).await;
+
+    Ok(fine)
+}
+
+/// Times the member out for contempt of court. If they can't be timed out, for example because
+/// their role is above the role of the bot, they lose the permission to write in the court room
+/// instead and a job gives it back. Returns whether they were timed out.
+pub async fn hold_in_contempt(
+    http: &Http,
+    storage: &Arc<dyn Storage>,
+    guild_id: GuildId,
+    channel_id: SnowflakeId,
+    user_id: UserId,
+    until: DateTime,
+) -> Result<bool> {
+    let timestamp = until
+        .try_to_rfc3339_string()
+        .wrap_err("format end of contempt")?;
+    let timeout = retry(|| {
+        guild_id.edit_member(http, user_id, |member| {
+            member.disable_communication_until(timestamp.clone())
+        })
+    })
+    .await;
+    match timeout {
+        Ok(_) => return Ok(true),
+        Err(err) => warn!(?err, %user_id, "Could not time out member, denying writing instead"),
+    }
+
+    deny_in_room(
+        http,
+        channel_id,
+        PermissionOverwriteType::Member(user_id),
+        Permissions::SEND_MESSAGES,
+        true,
+    )
+    .await?;
+    storage
+        .schedule_job(
+            guild_id.into(),
+            &JobKind::LiftContempt {
+                channel_id,
+                user_id: user_id.into(),
+            },
+            until,
+        )
+        .await?;
+
+    Ok(false)
+}
+
+/// Allows the member to write in the court room again after [`hold_in_contempt`].
+pub async fn lift_contempt(
+    http: &Http,
+    channel_id: SnowflakeId,
+    user_id: SnowflakeId,
+) -> Result<()> {
+    deny_in_room(
+        http,
+        channel_id,
+        PermissionOverwriteType::Member(user_id.into()),
+        Permissions::SEND_MESSAGES,
+        false,
+    )
+    .await?;
+
+    info!(%channel_id, %user_id, "Lifted contempt of court");
+    Ok(())
+}
+
+/// Removes someone who doesn't take part in the lawsuit from the court room until the verdict.
+/// Jurors lose the role of the room, and nobody can see the room with the denied permission,
+/// even when the trial is public.
+pub async fn eject(
+    http: &Http,
+    guild_id: GuildId,
+    room: &CourtRoom,
+    user_id: SnowflakeId,
+) -> Result<()> {
+    retry(|| http.remove_member_role(guild_id.0, user_id.0, room.role_id.0, None))
+        .await
+        .wrap_err("remove room role from ejected member")?;
+    set_ejected(http, room.channel_id, user_id, true).await
+}
+
+async fn set_ejected(
+    http: &Http,
+    channel_id: SnowflakeId,
+    user_id: SnowflakeId,
+    ejected: bool,
+) -> Result<()> {
+    deny_in_room(
+        http,
+        channel_id,
+        PermissionOverwriteType::Member(user_id.into()),
+        Permissions::VIEW_CHANNEL,
+        ejected,
+    )
+    .await
+}
+
+/// Denies `@everyone` sending messages in the court room while there's no lawsuit in it, the
+/// parties can still write during the trial thanks to the role of the room.
+pub async fn set_room_locked(
+    http: &Http,
+    guild_id: GuildId,
+    channel_id: SnowflakeId,
+    locked: bool,
+) -> Result<()> {
+    deny_everyone(
+        http,
+        guild_id,
+        channel_id,
+        Permissions::SEND_MESSAGES,
+        locked,
+    )
+    .await
+}
+
+/// Hides the court room from everyone but the people with the role of the room, for secret
+/// testimony. See `/lawsuit public`.
+pub async fn set_room_closed_to_public(
+    http: &Http,
+    guild_id: GuildId,
+    room: &CourtRoom,
+    closed: bool,
+) -> Result<()> {
+    allow_room_role(http, room).await?;
+    deny_everyone(
+        http,
+        guild_id,
+        room.channel_id,
+        Permissions::VIEW_CHANNEL,
+        closed,
+    )
+    .await
+}
+
+/// Applies the permissions the court room was created with again: the parties can see and write
+/// with the role of the room, and `@everyone` can only write if there's a lawsuit in it.
+pub async fn sync_room_permissions(http: &Http, guild_id: GuildId, room: &CourtRoom) -> Result<()> {
+    allow_room_role(http, room).await?;
+    set_room_locked(http, guild_id, room.channel_id, !room.ongoing_lawsuit).await?;
+    // the judge closed the trial to the public
+    if room.closed_to_public {
+        set_room_closed_to_public(http, guild_id, room, true).await?;
+    }
+    Ok(())
+}
+
+async fn allow_room_role(http: &Http, room: &CourtRoom) -> Result<()> {
+    retry(|| {
+        ChannelId::from(room.channel_id).create_permission(
+            http,
+            &PermissionOverwrite {
+                allow: Permissions::VIEW_CHANNEL | Permissions::SEND_MESSAGES,
+                deny: Permissions::empty(),
+                kind: PermissionOverwriteType::Role(room.role_id.into()),
+            },
+        )
+    })
+    .await
+    .wrap_err("allow room role in court room")?;
+
+    Ok(())
+}
+
+/// Denies or allows again the permission in the `@everyone` overwrite of the court room. The other
+/// permissions of the overwrite are kept in case the admins changed them.
+async fn deny_everyone(
+    http: &Http,
+    guild_id: GuildId,
+    channel_id: SnowflakeId,
+    permission: Permissions,
+    denied: bool,
+) -> Result<()> {
+    // the @everyone role has the same id as the guild
+    let kind = PermissionOverwriteType::Role(RoleId(guild_id.0));
+    deny_in_room(http, channel_id, kind, permission, denied).await
+}
+
+/// Like [`deny_everyone`] for any overwrite. The overwrites of members are removed once they
+/// don't change anything anymore, so contempt and ejection can end independently.
+async fn deny_in_room(
+    http: &Http,
+    channel_id: SnowflakeId,
+    kind: PermissionOverwriteType,
+    permission: Permissions,
+    denied: bool,
+) -> Result<()> {
+    let channel = ChannelId::from(channel_id)
+        .to_channel(http)
+        .await
+        .wrap_err("fetch court room")?;
+    let channel = match channel.guild() {
+        Some(channel) => channel,
+        None => return Ok(()),
+    };
 
-        let result = self
-            .send_process_open_message(&self.http, self.guild_id, &room)
+    let (mut allow, mut deny) = channel
+        .permission_overwrites
+        .iter()
+        .find(|overwrite| overwrite.kind == kind)
+        .map_or((Permissions::empty(), Permissions::empty()), |overwrite| {
+            (overwrite.allow, overwrite.deny)
+        });
+    if deny.contains(permission) == denied {
+        return Ok(());
+    }
+    allow.remove(permission);
+    deny.set(permission, denied);
+
+    if allow.is_empty() && deny.is_empty() && matches!(kind, PermissionOverwriteType::Member(_)) {
+        retry(|| channel.id.delete_permission(http, kind))
             .await
-            .wrap_err("send process open message")?;
+            .wrap_err("remove member permissions")?;
+        return Ok(());
+    }
+    retry(|| {
+        channel
+            .id
+            .create_permission(http, &PermissionOverwrite { allow, deny, kind })
+    })
+    .await
+    .wrap_err("update court room permissions")?;
+
+    Ok(())
+}
+
+/// The reasoning and the sentence of the verdict as separate fields, if the judge wrote them.
+fn verdict_details_fields(embed: &mut CreateEmbed, strings: &Strings, lawsuit: &Lawsuit) {
+    let details = match &lawsuit.verdict_details {
+        Some(details) => details,
+        None => return,
+    };
+    if let Some(reasoning) = &details.reasoning {
+        embed.field(strings.field_reasoning, reasoning, false);
+    }
+    if let Some(sentence) = &details.sentence {
+        embed.field(strings.field_sentence, sentence, false);
+    }
+}
+
+pub struct LawsuitCtx {
+    pub lawsuit: Lawsuit,
+    pub storage: Arc<dyn Storage>,
+    pub http: Arc<Http>,
+    pub tasks: BackgroundTasks,
+    pub guild_id: GuildId,
+}
+
+impl LawsuitCtx {
+    pub async fn initialize(self) -> Result<Result<Response, Response>> {
+        self.initialize_in(None).await
+    }
+
+    /// Opens the lawsuit in the chosen room if it's still free, otherwise in the first free room
+    /// or a new one. The answer is an error if no room could be opened.
+    pub async fn initialize_in(
+        mut self,
+        chosen_room: Option<SnowflakeId>,
+    ) -> Result<Result<Response, Response>> {
+        let mut attempts = 0;
+        let (state, room) = loop {
+            let state = self
+                .storage
+                .find_or_insert_state(self.guild_id.into())
+                .await?;
+            let room = match self.choose_room(&state, chosen_room).await? {
+                Ok(room) => room,
+                Err(response) => return Ok(Err(response)),
+            };
 
-        if let Err(response) = result {
-            return Ok(response);
+            // two lawsuits that are filed at the same time must not get the same room
+            if self
+                .storage
+                .claim_court_room(self.guild_id.into(), room.channel_id, state.version)
+                .await?
+            {
+                break (state, room);
+            }
+            attempts += 1;
+            if attempts >= MAX_CLAIM_ATTEMPTS {
+                bail!("the court rooms changed {attempts} times while choosing one");
+            }
+        };
+
+        let opened_at = DateTime::now();
+        let opened = self.open_room(&state, &room, opened_at).await;
+        if !matches!(opened, Ok(Ok(()))) {
+            self.storage
+                .set_court_room_ongoing(self.guild_id.into(), room.channel_id, false)
+                .await?;
+        }
+        if let Err(response) = opened? {
+            return Ok(Err(response));
         }
 
         let channel_id = room.channel_id;
         self.lawsuit.court_room = channel_id;
+        self.lawsuit.opened_at = Some(opened_at);
+
+        let mut values = templates::lawsuit_values(&self.lawsuit).to_vec();
+        values.push(("channel", format!("<#{channel_id}>")));
+        let response = templates::render(&state, Template::LawsuitOpened, &values);
 
-        tokio::spawn(async move {
+        // the roles are assigned after answering, finishing it is still important on shutdown
+        let tasks = self.tasks.clone();
+        tasks.spawn(async move {
             if let Err(err) = self.setup(room).await {
                 error!(?err, "Error setting up lawsuit");
             }
         });
 
-        Ok(Response(format!(
-            "ha eine ufgmacht im channel <#{}>",
-            channel_id
-        )))
+        Ok(Ok(Response(response)))
+    }
+
+    /// The chosen room if it's still free, otherwise the first free room or a new one.
+    async fn choose_room(
+        &self,
+        state: &State,
+        chosen_room: Option<SnowflakeId>,
+    ) -> Result<Result<CourtRoom, Response>> {
+        let strings = state.locale.strings();
+
+        // without an appeal category, appeals are held in the normal court rooms
+        let (level, category) = match state.appeal_category {
+            Some(category) if self.lawsuit.appeal_of.is_some() => {
+                (CourtLevel::Appeal, Some(category))
+            }
+            _ => (CourtLevel::FirstInstance, state.court_category),
+        };
+
+        let rooms = state.court_rooms.iter().filter(|r| r.level == level);
+        let mut free_rooms = rooms.clone().filter(|r| !r.ongoing_lawsuit);
+        let free_room = chosen_room
+            .and_then(|chosen| free_rooms.clone().find(|r| r.channel_id == chosen))
+            .or_else(|| free_rooms.next())
+            .cloned();
+
+        match (free_room, category) {
+            (Some(room), _) => Ok(Ok(room)),
+            (None, Some(category)) => self
+                .create_room(state, level, rooms.count(), category)
+                .await
+                .wrap_err("create new room"),
+            (None, None) => Ok(Err(Response(strings.set_category_first.to_string()))),
+        }
+    }
+
+    /// Numbers the lawsuit and announces it in the claimed room.
+    async fn open_room(
+        &mut self,
+        state: &State,
+        room: &CourtRoom,
+        opened_at: DateTime,
+    ) -> Result<Result<(), Response>> {
+        let year = duration::year(opened_at);
+        let number = self
+            .storage
+            .next_case_number(self.guild_id.into(), year)
+            .await?;
+        self.lawsuit.case_number = Some(format!("{year}-{number:02}"));
+
+        set_room_locked(&self.http, self.guild_id, room.channel_id, false)
+            .await
+            .wrap_err("unlock court room")?;
+
+        self.send_process_open_message(state, &self.http, self.guild_id, room)
+            .await
+            .wrap_err("send process open message")
     }
 
     async fn setup(&self, room: CourtRoom) -> Result<()> {
         let Self {
-            mongo_client,
+            storage,
             http,
             guild_id,
             lawsuit,
+            ..
         } = self;
         let guild_id = *guild_id;
 
-        mongo_client.add_lawsuit(guild_id.into(), lawsuit).await?;
-        mongo_client
-            .set_court_room(
-                guild_id.into(),
-                room.channel_id,
-                doc! { "court_rooms.$.ongoing_lawsuit": true },
-            )
-            .await?;
+        storage.add_lawsuit(lawsuit).await?;
 
         async fn assign_role(
             user: SnowflakeId,
@@ -116,9 +1186,7 @@ impl LawsuitCtx {
             guild_id: GuildId,
             role_id: SnowflakeId,
         ) -> Result<()> {
-            let mut member = guild_id.member(http, user).await.wrap_err("fetch member")?;
-            member
-                .add_role(http, role_id)
+            retry(|| http.add_member_role(guild_id.0, user.0, role_id.0, None))
                 .await
                 .wrap_err("add role to member")?;
 
@@ -135,6 +1203,14 @@ impl LawsuitCtx {
         assign_role(lawsuit.judge, http, guild_id, room.role_id).await?;
 
         info!(?lawsuit, "Created lawsuit");
+        metrics::lawsuit_created();
+
+        let state = storage.find_or_insert_state(guild_id.into()).await?;
+        if let Some(slowmode) = state.court_slowmode {
+            set_slowmode(http, room.channel_id, slowmode).await?;
+        }
+        audit::log(http, &state, AuditEvent::LawsuitCreated(lawsuit)).await;
+        self.announce_opened(&state, http).await;
 
         Ok(())
     }
@@ -144,14 +1220,21 @@ impl LawsuitCtx {
         permission_override: bool,
         user_id: UserId,
         verdict: String,
+        fine: Option<i64>,
         room: CourtRoom,
     ) -> Result<Result<(), Response>> {
+        let state = self
+            .storage
+            .find_or_insert_state(self.guild_id.into())
+            .await?;
+        let strings = state.locale.strings();
+
         if self.lawsuit.judge != user_id.into() && !permission_override {
-            return Ok(Err(Response("du häsch kei recht für da!".to_string())));
+            return Ok(Err(Response(strings.no_permission.to_string())));
         }
 
         self.lawsuit.verdict = Some(verdict);
-        let lawsuit = &self.lawsuit;
+        self.lawsuit.closed_at = Some(DateTime::now());
 
         async fn remove_role(
             user: SnowflakeId,
@@ -159,9 +1242,7 @@ impl LawsuitCtx {
             guild_id: GuildId,
             role_id: SnowflakeId,
         ) -> Result<()> {
-            let mut member = guild_id.member(http, user).await.wrap_err("fetch member")?;
-            member
-                .remove_role(http, role_id)
+            retry(|| http.remove_member_role(guild_id.0, user.0, role_id.0, None))
                 .await
                 .wrap_err("remove role from member")?;
 
@@ -172,33 +1253,66 @@ impl LawsuitCtx {
         let guild_id = self.guild_id;
 
         tokio::try_join!(
-            self.mongo_client.set_court_room(
-                self.guild_id.into(),
-                lawsuit.court_room,
-                doc! { "court_rooms.$.ongoing_lawsuit": false },
-            ),
-            self.mongo_client.set_lawsuit(
-                self.guild_id.into(),
-                lawsuit.id,
-                doc! { "lawsuits.$.verdict": &lawsuit.verdict },
-            ),
-            remove_role(lawsuit.accused, http, guild_id, room.role_id),
-            remove_role(lawsuit.plaintiff, http, guild_id, room.role_id),
-            remove_role(lawsuit.judge, http, guild_id, room.role_id),
+            self.storage
+                .close_lawsuit(self.guild_id.into(), &self.lawsuit),
+            remove_role(self.lawsuit.accused, http, guild_id, room.role_id),
+            remove_role(self.lawsuit.plaintiff, http, guild_id, room.role_id),
+            remove_role(self.lawsuit.judge, http, guild_id, room.role_id),
         )?;
 
+        // only once the verdict is stored, a retry after a failed close would find the fine
+        // already imposed otherwise. Before the messages so that it's shown with the verdict.
+        if let Some(amount) = fine {
+            impose_fine(http, &self.storage, &state, &mut self.lawsuit, amount).await?;
+        }
+        let lawsuit = &self.lawsuit;
+
         if let Some(accused_lawyer) = lawsuit.accused_lawyer {
             remove_role(accused_lawyer, http, guild_id, room.role_id).await?;
         }
         if let Some(plaintiff_lawyer) = lawsuit.plaintiff_lawyer {
             remove_role(plaintiff_lawyer, http, guild_id, room.role_id).await?;
         }
+        for &juror in &lawsuit.jurors {
+            remove_role(juror, http, guild_id, room.role_id).await?;
+        }
+        for &ejected in &lawsuit.ejected {
+            set_ejected(http, room.channel_id, ejected, false).await?;
+        }
+        if state
+            .gag_orders
+            .iter()
+            .any(|order| order.lawsuit_id == lawsuit.id)
+        {
+            self.storage
+                .remove_gag_orders(guild_id.into(), lawsuit.id)
+                .await?;
+        }
+        // rooms of guilds without the setting keep the slowmode their admins gave them
+        if state.court_slowmode.is_some() {
+            set_slowmode(http, room.channel_id, 0).await?;
+        }
 
         let response = self
-            .send_process_close_message(http, guild_id, &room)
+            .send_process_close_message(&state, http, guild_id, &room)
             .await?;
+        // after the verdict was posted, the bot can't always write in locked rooms
+        set_room_locked(http, guild_id, room.channel_id, true)
+            .await
+            .wrap_err("lock court room")?;
+        // the next trial in the room is public again
+        if room.closed_to_public {
+            set_room_closed_to_public(http, guild_id, &room, false).await?;
+            self.storage
+                .set_room_closed_to_public(guild_id.into(), room.channel_id, false)
+                .await?;
+        }
 
         info!(?lawsuit, "Closed lawsuit");
+        metrics::lawsuit_closed();
+
+        audit::log(http, &state, AuditEvent::LawsuitClosed(lawsuit)).await;
+        self.publish_record(&state, http).await;
 
         if let Err(response) = response {
             return Ok(Err(response));
@@ -209,76 +1323,231 @@ impl LawsuitCtx {
 
     async fn send_process_open_message(
         &self,
+        state: &State,
         http: &Http,
         guild_id: GuildId,
         room: &CourtRoom,
     ) -> Result<Result<(), Response>> {
-        self.send_court_message(http, guild_id, room, |msg| {
+        let strings = state.locale.strings();
+        let title = templates::render(
+            state,
+            Template::LawsuitTitle,
+            &templates::lawsuit_values(&self.lawsuit),
+        );
+        self.send_court_message(strings, http, guild_id, room, |msg| {
             msg.embed(|embed| {
                 let lawsuit = &self.lawsuit;
                 embed
-                    .title("Prozess")
-                    .field("Grund", &lawsuit.reason, false)
-                    .field("Kläger", format!("<@{}>", lawsuit.plaintiff), true)
+                    .title(title)
+                    .field(strings.field_reason, &lawsuit.reason, false)
                     .field(
-                        "Anwalt des Klägers",
+                        strings.field_plaintiff,
+                        format!("<@{}>", lawsuit.plaintiff),
+                        true,
+                    )
+                    .field(
+                        strings.field_plaintiff_lawyer,
                         match &lawsuit.plaintiff_lawyer {
                             Some(lawyer) => format!("<@{}>", lawyer),
-                            None => "Keinen".to_string(),
+                            None => strings.no_lawyer.to_string(),
                         },
                         true,
                     )
-                    .field("Angeklagter", format!("<@{}>", lawsuit.accused), true)
                     .field(
-                        "Anwalt des Angeklagten",
+                        strings.field_accused,
+                        format!("<@{}>", lawsuit.accused),
+                        true,
+                    )
+                    .field(
+                        strings.field_accused_lawyer,
                         match &lawsuit.accused_lawyer {
                             Some(lawyer) => format!("<@{}>", lawyer),
-                            None => "Keinen".to_string(),
+                            None => strings.no_lawyer.to_string(),
                         },
                         true,
                     )
-                    .field("Richter", format!("<@{}>", lawsuit.judge), true)
+                    .field(strings.field_judge, format!("<@{}>", lawsuit.judge), true);
+                if let Some(case_number) = &lawsuit.case_number {
+                    embed.field(strings.field_case_number, case_number, true);
+                }
+                embed
             })
+            .components(|c| evidence::submit_button(c, strings, &self.lawsuit))
         })
         .await
     }
 
+    /// Announces the new lawsuit in the records channel for the members with the news role. Only
+    /// the verdicts are posted there without the role.
+    async fn announce_opened(&self, state: &State, http: &Http) {
+        let (channel_id, role_id) = match (state.records_channel, state.news_role) {
+            (Some(channel_id), Some(role_id)) => (ChannelId::from(channel_id), role_id),
+            _ => return,
+        };
+        let strings = state.locale.strings();
+        let lawsuit = &self.lawsuit;
+        let case_number = lawsuit
+            .case_number
+            .clone()
+            .unwrap_or_else(|| lawsuit.id.to_string());
+
+        let result = channel_id
+            .send_message(http, |msg| {
+                msg.content(format!("<@&{role_id}>")).embed(|embed| {
+                    embed
+                        .title((strings.record_opened_title)(&case_number))
+                        .field(strings.field_reason, &lawsuit.reason, false)
+                        .field(
+                            strings.field_plaintiff,
+                            format!("<@{}>", lawsuit.plaintiff),
+                            true,
+                        )
+                        .field(
+                            strings.field_accused,
+                            format!("<@{}>", lawsuit.accused),
+                            true,
+                        )
+                        .field(strings.field_judge, format!("<@{}>", lawsuit.judge), true)
+                        .field(
+                            strings.field_court_room,
+                            format!("<#{}>", lawsuit.court_room),
+                            true,
+                        )
+                })
+            })
+            .await;
+
+        if let Err(err) = result {
+            error!(?err, lawsuit_id = %lawsuit.id, "Failed to announce the lawsuit");
+        }
+    }
+
+    /// Posts the verdict as a permanent public record in the records channel, unless the lawsuit
+    /// is sealed. Failing to post it is only logged, the verdict is in the court room as well.
+    async fn publish_record(&self, state: &State, http: &Http) {
+        let channel_id = match state.records_channel {
+            Some(channel_id) if !self.lawsuit.sealed => ChannelId::from(channel_id),
+            _ => return,
+        };
+        let strings = state.locale.strings();
+        let lawsuit = &self.lawsuit;
+        let case_number = lawsuit
+            .case_number
+            .clone()
+            .unwrap_or_else(|| lawsuit.id.to_string());
+
+        let result = channel_id
+            .send_message(http, |msg| {
+                if let Some(role_id) = state.news_role {
+                    msg.content(format!("<@&{role_id}>"));
+                }
+                msg.embed(|embed| {
+                    embed
+                        .title((strings.record_title)(&case_number))
+                        .field(strings.field_reason, &lawsuit.reason, false)
+                        .field(
+                            strings.field_plaintiff,
+                            format!("<@{}>", lawsuit.plaintiff),
+                            true,
+                        )
+                        .field(
+                            strings.field_accused,
+                            format!("<@{}>", lawsuit.accused),
+                            true,
+                        )
+                        .field(strings.field_judge, format!("<@{}>", lawsuit.judge), true)
+                        .field(
+                            strings.field_verdict,
+                            lawsuit.verdict.as_deref().unwrap_or_default(),
+                            false,
+                        );
+                    verdict_details_fields(embed, strings, lawsuit);
+                    if let Some(fine) = &lawsuit.fine {
+                        embed.field(strings.field_fine, fine.describe(strings), true);
+                    }
+                    if let Some(closed_at) = lawsuit.closed_at {
+                        embed.field(
+                            strings.field_closed_at,
+                            duration::date_timestamp(closed_at),
+                            true,
+                        );
+                    }
+                    embed
+                })
+            })
+            .await;
+
+        if let Err(err) = result {
+            error!(?err, lawsuit_id = %lawsuit.id, "Failed to publish the record of the lawsuit");
+        }
+    }
+
     async fn send_process_close_message(
         &self,
+        state: &State,
         http: &Http,
         guild_id: GuildId,
         room: &CourtRoom,
     ) -> Result<Result<(), Response>> {
-        self.send_court_message(http, guild_id, room, |msg| {
+        let strings = state.locale.strings();
+        let title = templates::render(
+            state,
+            Template::LawsuitClosedTitle,
+            &templates::lawsuit_values(&self.lawsuit),
+        );
+        self.send_court_message(strings, http, guild_id, room, |msg| {
             msg.embed(|embed| {
                 let lawsuit = &self.lawsuit;
                 embed
-                    .title("Prozess abgeschlossen")
-                    .field("Grund", &lawsuit.reason, false)
-                    .field("Kläger", format!("<@{}>", lawsuit.plaintiff), true)
+                    .title(title)
+                    .field(strings.field_reason, &lawsuit.reason, false)
                     .field(
-                        "Anwalt des Klägers",
+                        strings.field_plaintiff,
+                        format!("<@{}>", lawsuit.plaintiff),
+                        true,
+                    )
+                    .field(
+                        strings.field_plaintiff_lawyer,
                         match &lawsuit.plaintiff_lawyer {
                             Some(lawyer) => format!("<@{}>", lawyer),
-                            None => "Keinen".to_string(),
+                            None => strings.no_lawyer.to_string(),
                         },
                         true,
                     )
-                    .field("Angeklagter", format!("<@{}>", lawsuit.accused), true)
                     .field(
-                        "Anwalt des Angeklagten",
+                        strings.field_accused,
+                        format!("<@{}>", lawsuit.accused),
+                        true,
+                    )
+                    .field(
+                        strings.field_accused_lawyer,
                         match &lawsuit.accused_lawyer {
                             Some(lawyer) => format!("<@{}>", lawyer),
-                            None => "Keinen".to_string(),
+                            None => strings.no_lawyer.to_string(),
                         },
                         true,
                     )
-                    .field("Richter", format!("<@{}>", lawsuit.judge), true)
+                    .field(strings.field_judge, format!("<@{}>", lawsuit.judge), true)
                     .field(
-                        "Urteil",
+                        strings.field_verdict,
                         lawsuit.verdict.clone().expect("no verdict found!"),
                         true,
-                    )
+                    );
+                verdict_details_fields(embed, strings, lawsuit);
+                if let Some(fine) = &lawsuit.fine {
+                    embed.field(strings.field_fine, fine.describe(strings), true);
+                }
+                let billed = lawsuit.billed_by_lawyer();
+                if !billed.is_empty() {
+                    let billed = billed
+                        .iter()
+                        .map(|(lawyer, amount)| {
+                            format!("<@{lawyer}>: {}", (strings.currency)(*amount))
+                        })
+                        .collect::<Vec<_>>();
+                    embed.field(strings.field_lawyer_fees, billed.join("\n"), false);
+                }
+                embed
             })
         })
         .await
@@ -286,6 +1555,7 @@ impl LawsuitCtx {
 
     async fn send_court_message<'a, F>(
         &self,
+        strings: &Strings,
         http: &Http,
         guild_id: GuildId,
         room: &CourtRoom,
@@ -313,23 +1583,39 @@ impl LawsuitCtx {
             }
             None => {
                 // todo: remove the court room from the db
-                return Ok(Err(Response(
-                    "i ha de channel für de prozess nöd gfunde".to_string(),
-                )));
+                return Ok(Err(Response(strings.court_channel_not_found.to_string())));
             }
         }
 
         Ok(Ok(()))
     }
 
+    /// Creates the next room of the level, `room_len` is the number of rooms it already has.
     async fn create_room(
         &self,
+        state: &State,
+        level: CourtLevel,
         room_len: usize,
         category_id: SnowflakeId,
     ) -> Result<Result<CourtRoom, Response>> {
+        let strings = state.locale.strings();
         let room_number = room_len + 1;
-        let room_name = format!("gerichtsraum-{room_number}");
-        let role_name = format!("Gerichtsprozess {room_number}");
+        let (room_name, role_name) = match level {
+            CourtLevel::FirstInstance => (
+                format!("gerichtsraum-{room_number}"),
+                format!("Gerichtsprozess {room_number}"),
+            ),
+            CourtLevel::Appeal => {
+                let name = state
+                    .appeal_room_name
+                    .as_deref()
+                    .unwrap_or(DEFAULT_APPEAL_ROOM_NAME);
+                (
+                    format!("{name}-{room_number}"),
+                    format!("Berufungsprozess {room_number}"),
+                )
+            }
+        };
 
         let guild = self
             .guild_id
@@ -340,13 +1626,14 @@ impl LawsuitCtx {
         let role_id = match guild.role_by_name(&role_name) {
             Some(role) => role.id,
             None => {
-                guild
-                    .create_role(&self.http, |role| {
-                        role.name(role_name).permissions(Permissions::empty())
+                retry(|| {
+                    guild.create_role(&self.http, |role| {
+                        role.name(&role_name).permissions(Permissions::empty())
                     })
-                    .await
-                    .wrap_err("create role")?
-                    .id
+                })
+                .await
+                .wrap_err("create role")?
+                .id
             }
         };
 
@@ -358,27 +1645,26 @@ impl LawsuitCtx {
         let channel_id = match channels.values().find(|c| c.name() == room_name) {
             Some(channel) => {
                 if channel.parent_id != Some(category_id.into()) {
-                    return Ok(Err(Response(format!(
-                        "de channel {room_name} isch i de falsche kategorie, man eh"
-                    ))));
+                    return Ok(Err(Response((strings.room_in_wrong_category)(&room_name))));
                 }
                 channel.id
             }
             None => {
-                guild
-                    .create_channel(&self.http, |channel| {
+                retry(|| {
+                    guild.create_channel(&self.http, |channel| {
                         channel
-                            .name(room_name)
+                            .name(&room_name)
                             .category(category_id)
                             .permissions(vec![PermissionOverwrite {
-                                allow: Permissions::SEND_MESSAGES,
+                                allow: Permissions::VIEW_CHANNEL | Permissions::SEND_MESSAGES,
                                 deny: Permissions::empty(),
                                 kind: PermissionOverwriteType::Role(role_id),
                             }])
                     })
-                    .await
-                    .wrap_err("create channel")?
-                    .id
+                })
+                .await
+                .wrap_err("create channel")?
+                .id
             }
         };
 
@@ -386,14 +1672,26 @@ impl LawsuitCtx {
             channel_id: channel_id.into(),
             ongoing_lawsuit: false,
             role_id: role_id.into(),
+            last_used: None,
+            last_activity: None,
+            nudges: 0,
+            closed_to_public: false,
+            level,
         };
 
-        self.mongo_client
+        self.storage
             .add_court_room(self.guild_id.into(), &room)
             .await
             .wrap_err("add court room to database")?;
 
-        info!(guild_id = %self.guild_id, channel_id = %channel_id, "Created new court room");
+        info!(guild_id = %self.guild_id, channel_id = %channel_id, ?level, "Created new court room");
+
+        audit::log(
+            &self.http,
+            state,
+            AuditEvent::CourtRoomCreated { channel_id },
+        )
+        .await;
 
         Ok(Ok(room))
     }
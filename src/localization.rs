@@ -0,0 +1,1015 @@
+use poise::serenity_prelude::CreateApplicationCommands;
+use serde_json::{json, Value};
+
+/// German names and English descriptions of the commands and their options, by the path of the
+/// command like `prison arrest duration`. The German descriptions are the doc comments of the
+/// commands, discord shows them to everyone whose client isn't set to English.
+const COMMANDS: &[(&str, &str, &str)] = &[
+    ("hello", "hallo", "Say hello to Karin."),
+    ("lawsuit", "klage", "Manage lawsuits"),
+    ("lawsuit create", "erstellen", "Create a new lawsuit"),
+    ("lawsuit create plaintiff", "kläger", "The plaintiff"),
+    ("lawsuit create accused", "angeklagter", "The accused"),
+    (
+        "lawsuit create judge",
+        "richter",
+        "The judge, otherwise the next registered judge",
+    ),
+    (
+        "lawsuit create reason",
+        "grund",
+        "The reason for the lawsuit",
+    ),
+    (
+        "lawsuit create plaintiff_lawyer",
+        "anwalt_kläger",
+        "The lawyer of the plaintiff",
+    ),
+    (
+        "lawsuit create accused_lawyer",
+        "anwalt_angeklagter",
+        "The lawyer of the accused",
+    ),
+    (
+        "lawsuit create allow_duplicate",
+        "duplikat_erlauben",
+        "Also create it if a lawsuit between the two is already running (admins only)",
+    ),
+    ("lawsuit file", "einreichen", "File a lawsuit step by step"),
+    (
+        "lawsuit close",
+        "abschliessen",
+        "Close the lawsuit and pass a verdict",
+    ),
+    (
+        "lawsuit close case",
+        "prozess",
+        "The lawsuit, otherwise the one in this channel",
+    ),
+    (
+        "lawsuit close overturn",
+        "aufheben",
+        "For an appeal: whether the original verdict is overturned",
+    ),
+    (
+        "lawsuit close sealed",
+        "versiegelt",
+        "Don't publish the verdict in the public records",
+    ),
+    (
+        "lawsuit close precedent",
+        "präjudiz",
+        "Earlier cases the verdict is based on, e.g. 2023-04",
+    ),
+    (
+        "lawsuit appeal",
+        "berufung",
+        "Appeal the verdict of a closed lawsuit",
+    ),
+    ("lawsuit appeal case", "prozess", "The closed lawsuit"),
+    ("lawsuit appeal reason", "grund", "Why the verdict is wrong"),
+    (
+        "lawsuit jury",
+        "geschworene",
+        "Let the jury vote secretly on the lawsuit in this channel",
+    ),
+    (
+        "lawsuit jury jurors",
+        "geschworene",
+        "The jurors, e.g. @person1 @person2, otherwise drawn from the pool",
+    ),
+    (
+        "lawsuit jury count",
+        "anzahl",
+        "How many jurors are drawn from the pool (default: 5)",
+    ),
+    (
+        "lawsuit jury deadline",
+        "frist",
+        "How long the jury can vote, e.g. 30m or 1d (default: 1h)",
+    ),
+    (
+        "lawsuit witness",
+        "zeuge",
+        "Summon a person as a witness to the lawsuit in this channel",
+    ),
+    ("lawsuit witness user", "person", "The witness"),
+    (
+        "lawsuit witness deadline",
+        "frist",
+        "Until when the witness has to answer, e.g. 12h (default: 1d)",
+    ),
+    (
+        "lawsuit hearing",
+        "verhandlung",
+        "Set the date of the hearing of the lawsuit in this channel",
+    ),
+    (
+        "lawsuit hearing when",
+        "wann",
+        "When, e.g. 2d or 2023-04-01 18:00 in UTC, removes the date otherwise",
+    ),
+    (
+        "lawsuit public",
+        "öffentlich",
+        "Open or close the lawsuit in this channel to the public",
+    ),
+    (
+        "lawsuit public public",
+        "öffentlich",
+        "Whether everyone can see the court room or only the participants",
+    ),
+    (
+        "lawsuit contempt",
+        "missachtung",
+        "Mute a person for contempt of court",
+    ),
+    ("lawsuit contempt user", "person", "The person"),
+    (
+        "lawsuit eject",
+        "hinauswerfen",
+        "Eject a person who doesn't take part in the lawsuit from the court room",
+    ),
+    ("lawsuit eject user", "person", "The person"),
+    (
+        "lawsuit gag",
+        "schweigepflicht",
+        "Forbid a person to write about the lawsuit outside the court room until the verdict",
+    ),
+    ("lawsuit gag user", "person", "The person"),
+    (
+        "lawsuit contempt duration",
+        "dauer",
+        "How long, e.g. 10m or 1h",
+    ),
+    (
+        "lawsuit summary",
+        "zusammenfassung",
+        "Create a summary of a lawsuit with the reason, evidence and verdict",
+    ),
+    (
+        "lawsuit summary case",
+        "fall",
+        "The case number, otherwise the ongoing lawsuit in this channel",
+    ),
+    (
+        "lawsuit transcript",
+        "protokoll",
+        "The transcripts of the lawsuits",
+    ),
+    (
+        "lawsuit transcript export",
+        "exportieren",
+        "Download the course of a lawsuit as an HTML file",
+    ),
+    (
+        "lawsuit transcript export case",
+        "fall",
+        "The case number, otherwise the ongoing lawsuit in this channel",
+    ),
+    (
+        "lawsuit fine",
+        "busse",
+        "Impose a fine on the accused of the lawsuit in this channel",
+    ),
+    (
+        "lawsuit fine amount",
+        "betrag",
+        "The amount, it's taken from the balance immediately",
+    ),
+    ("lawsuit list", "liste", "List all lawsuits"),
+    (
+        "lawsuit clear",
+        "leeren",
+        "Delete data of the server, each after a confirmation",
+    ),
+    (
+        "lawsuit clear lawsuits",
+        "prozesse",
+        "Delete all lawsuits, which frees the court rooms",
+    ),
+    (
+        "lawsuit clear lawsuits dry_run",
+        "vorschau",
+        "Only show what would be deleted without changing anything (default: no)",
+    ),
+    (
+        "lawsuit clear rooms",
+        "gerichtsräume",
+        "Remove all court rooms, the channels are kept",
+    ),
+    (
+        "lawsuit clear rooms dry_run",
+        "vorschau",
+        "Only show what would be deleted without changing anything (default: no)",
+    ),
+    ("lawsuit clear prison", "gefängnis", "Release all prisoners"),
+    (
+        "lawsuit clear prison dry_run",
+        "vorschau",
+        "Only show what would be deleted without changing anything (default: no)",
+    ),
+    (
+        "lawsuit clear all",
+        "alles",
+        "Delete everything including the settings, can be undone for a week",
+    ),
+    (
+        "lawsuit clear all dry_run",
+        "vorschau",
+        "Only show what would be deleted without changing anything (default: no)",
+    ),
+    (
+        "lawsuit restore",
+        "wiederherstellen",
+        "Restore the data deleted with /lawsuit clear all",
+    ),
+    ("lawsuit ban", "sperren", "Forbid a person to file lawsuits"),
+    ("lawsuit ban user", "person", "The person"),
+    (
+        "lawsuit unban",
+        "entsperren",
+        "Allow a person to file lawsuits again",
+    ),
+    ("lawsuit unban user", "person", "The person"),
+    (
+        "lawsuit sync_permissions",
+        "berechtigungen_wiederherstellen",
+        "Restore the permissions of the court rooms and the prison",
+    ),
+    ("prison", "gefängnis", "Manage the prison"),
+    (
+        "prison set_tier",
+        "haftstufe_setzen",
+        "Create or change a prison tier with its own role",
+    ),
+    (
+        "prison set_tier name",
+        "name",
+        "The name of the tier, e.g. solitary confinement",
+    ),
+    (
+        "prison set_tier role",
+        "rolle",
+        "The role for prisoners in this tier",
+    ),
+    (
+        "prison remove_tier",
+        "haftstufe_entfernen",
+        "Remove a prison tier",
+    ),
+    ("prison remove_tier name", "name", "The name of the tier"),
+    ("prison arrest", "einsperren", "Arrest someone"),
+    ("prison arrest user", "person", "The person to arrest"),
+    ("prison arrest tier", "haftstufe", "The prison tier"),
+    (
+        "prison arrest duration",
+        "dauer",
+        "How long, e.g. 7d or 12h",
+    ),
+    ("prison arrest reason", "grund", "The reason for the arrest"),
+    (
+        "prison arrest_many",
+        "mehrere_einsperren",
+        "Arrest multiple people at once, e.g. after a raid",
+    ),
+    (
+        "prison arrest_many role",
+        "rolle",
+        "Arrest everyone with this role, otherwise people can be entered",
+    ),
+    ("prison arrest_many tier", "haftstufe", "The prison tier"),
+    (
+        "prison arrest_many duration",
+        "dauer",
+        "How long, e.g. 7d or 12h",
+    ),
+    (
+        "prison arrest_many reason",
+        "grund",
+        "The reason for the arrest",
+    ),
+    ("prison release", "freilassen", "Release a prisoner"),
+    ("prison release user", "person", "The person to release"),
+    (
+        "prison release_all",
+        "alle_freilassen",
+        "Release all prisoners",
+    ),
+    (
+        "prison release_all dry_run",
+        "vorschau",
+        "Only show who would be released without changing anything (default: no)",
+    ),
+    (
+        "prison extend",
+        "verlängern",
+        "Extend the sentence of a prisoner",
+    ),
+    ("prison extend user", "person", "The prisoner"),
+    (
+        "prison extend duration",
+        "dauer",
+        "By how much, e.g. 7d or 12h",
+    ),
+    (
+        "prison reduce",
+        "verkürzen",
+        "Reduce the sentence of a prisoner",
+    ),
+    ("prison reduce user", "person", "The prisoner"),
+    (
+        "prison reduce duration",
+        "dauer",
+        "By how much, e.g. 7d or 12h",
+    ),
+    ("prison parole", "bewährung", "Release a prisoner on parole"),
+    ("prison parole user", "person", "The prisoner"),
+    (
+        "prison parole duration",
+        "dauer",
+        "How long the parole lasts, e.g. 7d or 12h",
+    ),
+    (
+        "prison revoke_parole",
+        "bewährung_widerrufen",
+        "Revoke the parole and arrest the person again",
+    ),
+    (
+        "prison revoke_parole user",
+        "person",
+        "The person on parole",
+    ),
+    ("prison appeal", "berufung", "Appeal your own arrest"),
+    ("prison appeal reason", "grund", "Why the arrest is unjust"),
+    ("prison list", "liste", "List all prisoners"),
+    (
+        "prison check",
+        "nachschauen",
+        "Check whether someone is in prison and when they are released",
+    ),
+    ("prison check user", "person", "The person to check"),
+    (
+        "prison stats",
+        "statistik",
+        "Show statistics about the prison",
+    ),
+    ("config", "einstellungen", "The settings of the server"),
+    ("config show", "anzeigen", "Show all settings"),
+    (
+        "config messages",
+        "antworten",
+        "Set custom responses of the bot",
+    ),
+    (
+        "config messages set",
+        "setzen",
+        "Replace a response with your own text",
+    ),
+    ("config messages set key", "antwort", "The response"),
+    (
+        "config messages set text",
+        "text",
+        "The text, placeholders like {user} are replaced",
+    ),
+    (
+        "config messages reset",
+        "zurücksetzen",
+        "Use the normal response again",
+    ),
+    ("config messages reset key", "antwort", "The response"),
+    (
+        "config messages list",
+        "liste",
+        "Show all responses with their placeholders",
+    ),
+    (
+        "config export",
+        "exportieren",
+        "Export all data of the server as a JSON file",
+    ),
+    ("config import", "importieren", "Import exported data again"),
+    ("config import file", "datei", "The exported JSON file"),
+    (
+        "config import mode",
+        "modus",
+        "Whether the data is replaced or merged",
+    ),
+    (
+        "config import dry_run",
+        "vorschau",
+        "Only show a preview without changing anything (default: yes)",
+    ),
+    (
+        "config api_token",
+        "api_token",
+        "Create a new token for the API, the old token stops working",
+    ),
+    (
+        "config api_token revoke",
+        "entfernen",
+        "Only remove the old token",
+    ),
+    (
+        "config calendar_token",
+        "kalender",
+        "Create a new link to the calendar of the hearings, the old link stops working",
+    ),
+    (
+        "config calendar_token revoke",
+        "entfernen",
+        "Only remove the old link",
+    ),
+    ("config set", "setzen", "Change a setting"),
+    (
+        "config set court_category",
+        "gerichtskategorie",
+        "Set the category for the court rooms",
+    ),
+    (
+        "config set court_category category",
+        "kategorie",
+        "The category",
+    ),
+    (
+        "config set appeal_category",
+        "berufungskategorie",
+        "Set a separate category for the court rooms of appeals",
+    ),
+    (
+        "config set appeal_category category",
+        "kategorie",
+        "The category",
+    ),
+    (
+        "config set appeal_category room_name",
+        "raumname",
+        "The name of the rooms before their number (default: berufungsraum)",
+    ),
+    (
+        "config set prison_role",
+        "gefängnisrolle",
+        "Set the role for prisoners",
+    ),
+    ("config set prison_role role", "rolle", "The role"),
+    (
+        "config set probation_role",
+        "bewährungsrolle",
+        "Set the role for prisoners on parole",
+    ),
+    ("config set probation_role role", "rolle", "The role"),
+    (
+        "config set prison_channel",
+        "gefängniskanal",
+        "Set the channel for prisoners",
+    ),
+    ("config set prison_channel channel", "kanal", "The channel"),
+    (
+        "config set prison_channel lockdown",
+        "abriegeln",
+        "Forbid prisoners to write in all other channels",
+    ),
+    (
+        "config set strip_roles",
+        "rollen_entfernen",
+        "Set whether prisoners lose all their other roles",
+    ),
+    (
+        "config set strip_roles enabled",
+        "aktiviert",
+        "Remove roles on arrest and give them back on release",
+    ),
+    (
+        "config set moderator_channel",
+        "moderatorkanal",
+        "Set the channel where moderators are told about escapes",
+    ),
+    (
+        "config set moderator_channel channel",
+        "kanal",
+        "The channel",
+    ),
+    (
+        "config set notifications",
+        "benachrichtigungen",
+        "Set whether prisoners get a direct message on arrest and release",
+    ),
+    (
+        "config set notifications enabled",
+        "aktiviert",
+        "Send prisoners a direct message",
+    ),
+    (
+        "config set strict_lawyers",
+        "nur_zugelassene_anwälte",
+        "Set whether only admitted lawyers can represent the parties",
+    ),
+    (
+        "config set strict_lawyers enabled",
+        "aktiviert",
+        "Only allow lawyers who passed the bar exam",
+    ),
+    (
+        "config set conflicts",
+        "interessenkonflikte",
+        "Set what happens with conflicts of interest in new lawsuits",
+    ),
+    (
+        "config set conflicts policy",
+        "vorgehen",
+        "Reject the lawsuit, only warn or allow it",
+    ),
+    (
+        "config set appeal_judge",
+        "berufungsrichter",
+        "Set the judge for appeals and lawsuits filed from the context menu",
+    ),
+    ("config set appeal_judge judge", "richter", "The judge"),
+    (
+        "config set audit_channel",
+        "protokollkanal",
+        "Set the channel where all actions of the bot are logged",
+    ),
+    ("config set audit_channel channel", "kanal", "The channel"),
+    (
+        "config set error_channel",
+        "fehlerkanal",
+        "Set the channel where errors of commands are reported",
+    ),
+    ("config set error_channel channel", "kanal", "The channel"),
+    (
+        "config set records_channel",
+        "aktenkanal",
+        "Set the channel where verdicts are published as public records",
+    ),
+    ("config set records_channel channel", "kanal", "The channel"),
+    (
+        "config set news_role",
+        "news_rolle",
+        "Set the role that is mentioned for new lawsuits and verdicts in the records channel",
+    ),
+    (
+        "config set news_role role",
+        "rolle",
+        "The role, leave it empty to mention nobody",
+    ),
+    (
+        "config set weekly_report",
+        "wochenbericht",
+        "Set the channel where a summary is posted every week",
+    ),
+    (
+        "config set weekly_report channel",
+        "kanal",
+        "The channel, leave it empty to turn the summary off",
+    ),
+    (
+        "config set statute_of_limitations",
+        "verjährung",
+        "Set after how long open lawsuits are dismissed",
+    ),
+    (
+        "config set statute_of_limitations max_age",
+        "dauer",
+        "The time like 30d, leave it empty to never dismiss lawsuits",
+    ),
+    (
+        "config set inactivity_nudge",
+        "inaktivität",
+        "Set after how long without a message in the court room the judge is reminded",
+    ),
+    (
+        "config set inactivity_nudge after",
+        "nach",
+        "The time like 1h, the admins are reminded after twice the time",
+    ),
+    (
+        "config set lawsuit_limit",
+        "prozesslimit",
+        "Set how many running lawsuits a person can be the plaintiff of at the same time",
+    ),
+    (
+        "config set lawsuit_limit count",
+        "anzahl",
+        "The number, empty or 0 for no limit",
+    ),
+    (
+        "config set filing_cooldown",
+        "klage_wartezeit",
+        "Set how long a person has to wait between two lawsuits, admins are exempt",
+    ),
+    (
+        "config set filing_cooldown cooldown",
+        "wartezeit",
+        "The time like 1h, leave empty so nobody has to wait between lawsuits",
+    ),
+    (
+        "config set slowmode",
+        "slowmode",
+        "Set the slowmode of the court rooms while a lawsuit is running",
+    ),
+    (
+        "config set slowmode interval",
+        "intervall",
+        "The time like 30s, at most 6h, leave empty for no slowmode",
+    ),
+    (
+        "config set role",
+        "rolle",
+        "Set the role that gives access to some of the commands",
+    ),
+    (
+        "config set role kind",
+        "art",
+        "What the role gives access to",
+    ),
+    ("config set role role", "rolle", "The role"),
+    (
+        "config set locale",
+        "sprache",
+        "Set the language of the answers of the bot",
+    ),
+    ("config set locale locale", "sprache", "The language"),
+    (
+        "config set webhook",
+        "webhook",
+        "Set a URL that lawsuits, arrests and releases are sent to as JSON",
+    ),
+    (
+        "config set webhook url",
+        "url",
+        "The URL, leave it empty to remove the webhook",
+    ),
+    ("fine", "busse", "Pay fines"),
+    (
+        "fine pay",
+        "bezahlen",
+        "Settle the open fines once there is no debt left on the balance",
+    ),
+    ("economy", "wirtschaft", "Manage the money of the members"),
+    ("economy balance", "kontostand", "Show the balance"),
+    (
+        "economy balance user",
+        "person",
+        "The person, otherwise yourself",
+    ),
+    (
+        "economy give",
+        "geben",
+        "Give money to a person or take it away with a negative amount",
+    ),
+    ("economy give user", "person", "The person"),
+    (
+        "economy give amount",
+        "betrag",
+        "The amount, negative to take money away",
+    ),
+    (
+        "lawyer",
+        "anwalt",
+        "The bar exam and the fees of the lawyers",
+    ),
+    (
+        "lawyer exam",
+        "prüfung",
+        "Take the bar exam to be admitted as lawyer",
+    ),
+    (
+        "lawyer questions",
+        "fragen",
+        "Manage the questions of the bar exam",
+    ),
+    (
+        "lawyer questions add",
+        "hinzufügen",
+        "Add a question to the bar exam",
+    ),
+    ("lawyer questions add question", "frage", "The question"),
+    (
+        "lawyer questions add answers",
+        "antworten",
+        "The answers, separated by ;",
+    ),
+    (
+        "lawyer questions add correct",
+        "richtig",
+        "The number of the right answer",
+    ),
+    (
+        "lawyer questions remove",
+        "löschen",
+        "Remove a question of the bar exam",
+    ),
+    (
+        "lawyer questions remove number",
+        "nummer",
+        "The number of the question",
+    ),
+    (
+        "lawyer questions list",
+        "liste",
+        "Show all questions of the bar exam with the right answers",
+    ),
+    ("lawyer fee", "honorar", "Set your own fee as lawyer"),
+    ("lawyer fee kind", "art", "Per hour or flat per lawsuit"),
+    (
+        "lawyer fee amount",
+        "betrag",
+        "The amount, 0 to no longer charge a fee",
+    ),
+    ("lawyer fees", "honorare", "List the fees of all lawyers"),
+    (
+        "lawyer retainer",
+        "vorschuss",
+        "Pay your lawyer in the lawsuit in this channel a retainer",
+    ),
+    ("lawyer retainer amount", "betrag", "The amount"),
+    (
+        "lawyer bill",
+        "verrechnen",
+        "Bill your client in the lawsuit in this channel for your fee",
+    ),
+    (
+        "lawyer bill hours",
+        "stunden",
+        "The hours, only for hourly fees (default: 1)",
+    ),
+    (
+        "lawyer record",
+        "bilanz",
+        "Show the cases of a lawyer and how often they won",
+    ),
+    ("lawyer record user", "anwalt", "The lawyer"),
+    (
+        "judge",
+        "richter",
+        "Judges who get lawsuits assigned automatically",
+    ),
+    (
+        "judge register",
+        "registrieren",
+        "Get lawsuits without a judge assigned in turn",
+    ),
+    (
+        "judge unregister",
+        "abmelden",
+        "No longer get lawsuits assigned",
+    ),
+    (
+        "judge list",
+        "liste",
+        "List the registered judges in the order they get lawsuits",
+    ),
+    (
+        "judge stats",
+        "statistik",
+        "Show statistics about the lawsuits of a judge",
+    ),
+    ("judge stats user", "richter", "The judge"),
+    ("court", "gericht", "The court of the server"),
+    (
+        "court stats",
+        "statistik",
+        "Show statistics about the lawsuits of the server",
+    ),
+    (
+        "court leaderboard",
+        "bestenliste",
+        "Show the members with the most lawsuits filed, won and convicted",
+    ),
+    (
+        "jury",
+        "geschworene",
+        "The pool of members who can be drawn as jurors",
+    ),
+    (
+        "jury join",
+        "beitreten",
+        "Join the jury pool and get drawn for trials",
+    ),
+    ("jury leave", "austreten", "Leave the jury pool"),
+    ("election", "wahl", "Elections of members for a role"),
+    (
+        "election start",
+        "starten",
+        "Start an election for a role, e.g. the judge role",
+    ),
+    (
+        "election start role",
+        "rolle",
+        "The role that the elected members get",
+    ),
+    (
+        "election start duration",
+        "dauer",
+        "How long the candidacy and then the voting take each, e.g. 1d",
+    ),
+    (
+        "election start seats",
+        "sitze",
+        "How many members are elected (default: 1)",
+    ),
+    ("election list", "liste", "List the past elections"),
+    ("records", "akten", "The records of the closed lawsuits"),
+    (
+        "records search",
+        "suchen",
+        "Search the reasons and verdicts of the closed lawsuits",
+    ),
+    ("records search query", "suche", "The words to search for"),
+    (
+        "records show",
+        "anzeigen",
+        "Show a case with the precedents it cites and that cite it",
+    ),
+    ("records show case", "fall", "The case number, e.g. 2023-04"),
+    (
+        "records import",
+        "importieren",
+        "Add old cases from a CSV or JSON file to the records, without court rooms",
+    ),
+    (
+        "records import file",
+        "datei",
+        "The file with plaintiff, accused, judge, reason, verdict and date",
+    ),
+    (
+        "docket",
+        "terminliste",
+        "Show the open lawsuits by court room",
+    ),
+    ("privacy", "datenschutz", "Privacy"),
+    (
+        "privacy erase",
+        "löschen",
+        "Delete or anonymize all data about a person",
+    ),
+    ("privacy erase user", "person", "The person"),
+    ("stats", "statistik", "Show statistics about the bot"),
+    (
+        "debug",
+        "debug",
+        "Show diagnostics for debugging, only for the owner of the bot",
+    ),
+    (
+        "analytics",
+        "nutzung",
+        "Show how the commands are used, only for the owner of the bot",
+    ),
+    (
+        "analytics window",
+        "zeitraum",
+        "How far back, like 24h, the last 7 days otherwise",
+    ),
+    (
+        "maintenance",
+        "wartung",
+        "Make the bot read-only for migrations and backups, only for the owner of the bot",
+    ),
+    (
+        "maintenance enabled",
+        "aktiv",
+        "Whether only the commands that don't change anything work",
+    ),
+    (
+        "guilds",
+        "server",
+        "Choose which servers the bot may be on, only for the owner of the bot",
+    ),
+    (
+        "guilds allow",
+        "erlauben",
+        "Approve a server, even if it's not on the allowlist",
+    ),
+    ("guilds allow guild_id", "server_id", "The id of the server"),
+    (
+        "guilds block",
+        "sperren",
+        "Block a server, the bot leaves it right away",
+    ),
+    ("guilds block guild_id", "server_id", "The id of the server"),
+    (
+        "guilds forget",
+        "vergessen",
+        "Remove a server from the lists, the environment variables apply again",
+    ),
+    (
+        "guilds forget guild_id",
+        "server_id",
+        "The id of the server",
+    ),
+    (
+        "guilds list",
+        "liste",
+        "List the approved and blocked servers",
+    ),
+];
+
+/// English names of the choices, the German names are the ones the choice enums are defined with.
+/// Languages keep their own name.
+const CHOICES: &[(&str, &str)] = &[
+    ("Ersetzen", "Replace"),
+    ("Zusammenführen", "Merge"),
+    ("Gerichtsverwaltung", "Court administration"),
+    ("Richter", "Judge"),
+    ("Gerichtsdiener", "Bailiff"),
+    ("Einstellung gesetzt", "Setting changed"),
+    ("Eingesperrt", "Arrested"),
+    ("Eingesperrt bis", "Arrested until"),
+    ("Freigelassen", "Released"),
+    ("Prozess eröffnet", "Lawsuit opened"),
+    ("Prozess abgeschlossen", "Lawsuit closed"),
+    ("Titel des Prozesses", "Lawsuit title"),
+    (
+        "Titel des abgeschlossenen Prozesses",
+        "Closed lawsuit title",
+    ),
+    ("Zusammenfassung des Prozesses", "Case summary"),
+    ("Pro Stunde", "Per hour"),
+    ("Pauschal", "Flat"),
+    ("Ablehnen", "Reject"),
+    ("Warnen", "Warn"),
+    ("Erlauben", "Allow"),
+];
+
+/// English names of the context menu commands. They don't have descriptions and their names are
+/// shown as they are, so they are in German already.
+const CONTEXT_MENUS: &[(&str, &str)] = &[
+    ("Verklagen", "Sue"),
+    ("Einsperren", "Arrest"),
+    ("Als Beweis markieren", "Mark as evidence"),
+];
+
+/// Adds the localizations to the commands. serenity doesn't have builder methods for them yet, so
+/// they are added to the JSON directly.
+pub fn localize_commands(commands: &mut CreateApplicationCommands) {
+    for command in &mut commands.0 {
+        localize_context_menu(command);
+        localize(command, None);
+    }
+}
+
+fn localize_context_menu(value: &mut Value) {
+    let object = match value.as_object_mut() {
+        Some(object) => object,
+        None => return,
+    };
+    let english = object
+        .get("name")
+        .and_then(Value::as_str)
+        .and_then(|name| CONTEXT_MENUS.iter().find(|(de, _)| *de == name))
+        .map(|(_, en)| *en);
+    if let Some(english) = english {
+        object.insert(
+            "name_localizations".into(),
+            json!({ "en-US": english, "en-GB": english }),
+        );
+    }
+}
+
+/// Localizes a command or option and everything nested in it. `parent` is the path of the command
+/// it belongs to.
+fn localize(value: &mut Value, parent: Option<&str>) {
+    let object = match value.as_object_mut() {
+        Some(object) => object,
+        None => return,
+    };
+    let name = match object.get("name").and_then(Value::as_str) {
+        Some(name) => name,
+        None => return,
+    };
+    let path = match parent {
+        Some(parent) => format!("{parent} {name}"),
+        None => name.to_owned(),
+    };
+
+    if let Some((_, name_de, description_en)) = COMMANDS.iter().find(|(p, ..)| *p == path) {
+        object.insert("name_localizations".into(), json!({ "de": name_de }));
+        object.insert(
+            "description_localizations".into(),
+            json!({ "en-US": description_en, "en-GB": description_en }),
+        );
+    }
+
+    if let Some(choices) = object.get_mut("choices").and_then(Value::as_array_mut) {
+        for choice in choices.iter_mut().filter_map(Value::as_object_mut) {
+            let english = choice
+                .get("name")
+                .and_then(Value::as_str)
+                .and_then(|name| CHOICES.iter().find(|(de, _)| *de == name))
+                .map(|(_, en)| *en);
+            if let Some(english) = english {
+                choice.insert(
+                    "name_localizations".into(),
+                    json!({ "en-US": english, "en-GB": english }),
+                );
+            }
+        }
+    }
+
+    if let Some(options) = object.get_mut("options").and_then(Value::as_array_mut) {
+        for option in options {
+            localize(option, Some(&path));
+        }
+    }
+}
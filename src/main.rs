@@ -1,20 +1,70 @@
 extern crate core;
 
+mod analytics;
+mod api;
+mod archive;
+mod audit;
+mod backup;
+mod bar;
+mod cache;
+mod calendar;
+mod confirmation;
+mod cooldown;
+mod dashboard;
+mod duration;
+mod election;
+mod error_report;
+mod evidence;
+mod gag;
+mod guild_access;
 mod handler;
+mod http;
+mod i18n;
+mod inactivity;
+mod jury;
 mod lawsuit;
+mod localization;
+mod maintenance;
+mod metrics;
 mod model;
+mod outage;
+mod pagination;
+mod permissions;
+mod postgres;
+mod presence;
+mod prison;
+mod report;
+mod retry;
+mod scheduler;
+mod sharding;
+mod shutdown;
+mod storage;
+mod subpoena;
+mod templates;
+mod transcript;
+mod webhook;
+mod wizard;
 
-use std::env;
+use std::{env, net::SocketAddr, sync::Arc, time::Instant};
 
-use color_eyre::{eyre::WrapErr, Report, Result};
+use color_eyre::{
+    eyre::{bail, WrapErr},
+    Report, Result,
+};
 use poise::{
+    serenity::http::Http,
     serenity_prelude as serenity,
-    serenity_prelude::{Activity, GatewayIntents, GuildId},
+    serenity_prelude::{GatewayIntents, GuildId},
 };
 use tracing::{error, info};
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt, EnvFilter, Registry};
 
-use crate::{handler::Handler, model::Mongo};
+use crate::{
+    cache::CachedStorage, cooldown::Cooldowns, dashboard::Dashboard, guild_access::GuildAccess,
+    handler::Handler, inactivity::ActivityTracker, maintenance::Maintenance, model::Mongo,
+    outage::Outage, postgres::Postgres, presence::Presence, sharding::Sharding,
+    shutdown::BackgroundTasks, storage::Storage,
+};
 
 type Context<'a> = poise::Context<'a, Handler, Report>;
 
@@ -29,18 +79,13 @@ async fn main() -> Result<()> {
     setup_tracing(pretty);
 
     info!("Starting up...");
+    let started_at = Instant::now();
 
-    let mongo_uri = env::var("MONGO_URI").wrap_err("MONGO_URI not found in the environment")?;
-    let db_name = env::var("DB_NAME").unwrap_or_else(|_| "court-bot".to_string());
-
-    let username = env::var("MONGO_INITDB_ROOT_USERNAME")
-        .wrap_err("MONGO_INITDB_ROOT_USERNAME not found in the environment")?;
-    let password = env::var("MONGO_INITDB_ROOT_PASSWORD")
-        .wrap_err("MONGO_INITDB_ROOT_PASSWORD not found in the environment")?;
+    let sharding = Sharding::init()?;
+    info!(?sharding, "Configured gateway shards");
 
-    let mongo = Mongo::connect(&mongo_uri, &db_name, username, password).await?;
-
-    info!("Connected to mongodb");
+    let tasks = BackgroundTasks::default();
+    let storage = connect_storage(&tasks).await?;
 
     let token = env::var("DISCORD_TOKEN").wrap_err("DISCORD_TOKEN not found in environment")?;
     let dev_guild_id = if env::var("DEV").is_ok() {
@@ -55,19 +100,64 @@ async fn main() -> Result<()> {
     };
 
     let set_global_commands = env::var("SET_GLOBAL").is_ok();
+    let dry_run = env::var("DRY_RUN").is_ok();
+    if dry_run {
+        info!("Dry run, destructive commands only report what they would change");
+    }
+
+    // privileged, it has to be enabled for the bot in the developer portal first
+    let message_content = env::var("MESSAGE_CONTENT").is_ok();
+    if !message_content {
+        info!("No message content, gag orders only check the mentions of messages");
+    }
 
-    poise::Framework::build()
+    let http_addr = match env::var("HTTP_ADDR") {
+        Ok(addr) => Some(
+            addr.parse::<SocketAddr>()
+                .wrap_err("HTTP_ADDR must be a socket address like 0.0.0.0:8080")?,
+        ),
+        Err(_) => None,
+    };
+    let dashboard = Dashboard::from_env(Arc::new(Http::new(&token)))?.map(Arc::new);
+    let http_storage = storage.clone();
+    let shutdown_storage = storage.clone();
+    let cooldowns = Cooldowns::from_env()?;
+    let error_channel = error_report::owner_channel_from_env()?;
+    let presence = Arc::new(Presence::from_env()?);
+    let outage = Arc::new(Outage::default());
+    let maintenance = Arc::new(Maintenance::from_env());
+    let http_maintenance = maintenance.clone();
+    let guild_access = GuildAccess::from_env(dev_guild_id)?;
+    if maintenance.is_enabled() {
+        info!("Maintenance mode, only commands that don't change anything work");
+    }
+    tasks.spawn(outage.clone().recover(storage.clone(), tasks.clone()));
+    let shutdown_tasks = tasks.clone();
+
+    let framework = poise::Framework::build()
         .token(token)
         .user_data_setup(move |ctx, ready, framework| {
             Box::pin(async move {
                 let data = Handler {
                     dev_guild_id,
                     set_global_commands,
-                    mongo,
+                    dry_run,
+                    message_content,
+                    storage,
+                    tasks,
+                    cooldowns,
+                    error_channel,
+                    presence,
+                    outage,
+                    maintenance,
+                    guild_access,
+                    activity: ActivityTracker::default(),
+                    started_at,
                 };
 
                 let commands = &framework.options().commands;
-                let create_commands = poise::builtins::create_application_commands(commands);
+                let mut create_commands = poise::builtins::create_application_commands(commands);
+                localization::localize_commands(&mut create_commands);
 
                 if data.set_global_commands {
                     info!("Installing global slash commands...");
@@ -97,10 +187,16 @@ async fn main() -> Result<()> {
                     }
                 }
 
-                ctx.set_activity(Activity::playing("für Recht und Ordnung sorgen"))
-                    .await;
+                if sharding.runs_first_shard() {
+                    data.tasks.spawn(scheduler::run(
+                        ctx.http.clone(),
+                        data.storage.clone(),
+                        data.tasks.clone(),
+                        data.maintenance.clone(),
+                    ));
+                }
 
-                info!(name = %ready.user.name, "Bot is connected!");
+                info!(name = %ready.user.name, shard = ?ready.shard, "Bot is connected!");
 
                 Ok(data)
             })
@@ -108,9 +204,35 @@ async fn main() -> Result<()> {
         .options(poise::FrameworkOptions {
             commands: vec![
                 handler::lawsuit::lawsuit(),
+                handler::lawsuit::sue(),
+                handler::lawsuit::mark_evidence(),
                 handler::prison::prison(),
+                handler::prison::arrest_user(),
+                handler::config::config(),
+                handler::fine::fine(),
+                handler::economy::economy(),
+                handler::lawyer::lawyer(),
+                handler::judge::judge(),
+                handler::court::court(),
+                handler::jury_pool::jury(),
+                handler::election::election(),
+                handler::records::records(),
+                handler::docket::docket(),
+                handler::privacy::privacy(),
+                handler::stats::stats(),
+                handler::debug::debug(),
+                handler::maintenance::maintenance(),
+                handler::analytics::analytics(),
+                handler::guilds::guilds(),
                 hello(),
             ],
+            command_check: Some(|ctx| {
+                Box::pin(async move {
+                    outage::check(ctx)?;
+                    maintenance::check(ctx)?;
+                    cooldown::check(ctx).await
+                })
+            }),
             on_error: |err| Box::pin(async { handler::error_handler(err).await }),
             listener: |ctx, event, ctx2, data| {
                 Box::pin(async move { handler::listener(ctx, event, ctx2, data).await })
@@ -123,12 +245,19 @@ async fn main() -> Result<()> {
                         .await
                         .unwrap_or_else(|| "<unknown>".to_owned());
                     let author = ctx.author().tag();
+                    let shard_id = ctx.discord().shard_id;
 
                     match ctx {
                         Context::Application(ctx) => {
                             let command_name = &ctx.interaction.data().name;
 
-                            info!(?author, ?channel_name, ?command_name, "Command called");
+                            info!(
+                                ?author,
+                                ?channel_name,
+                                ?command_name,
+                                shard_id,
+                                "Command called"
+                            );
                         }
                         Context::Prefix(_) => {
                             tracing::warn!("Prefix command called!");
@@ -137,22 +266,119 @@ async fn main() -> Result<()> {
                     }
                 })
             },
+            post_command: |ctx| {
+                Box::pin(async move {
+                    ctx.data().outage.record_success();
+                    metrics::command_finished(ctx, "ok");
+                })
+            },
             ..Default::default()
         })
-        .intents(GatewayIntents::non_privileged() | GatewayIntents::GUILD_MEMBERS)
-        .run()
+        .intents(intents(message_content))
+        .build()
         .await
         .wrap_err("failed to create discord client")?;
+
+    if let Some(addr) = http_addr {
+        let shard_manager = framework.shard_manager();
+        tokio::spawn(async move {
+            if let Err(err) = http::serve(
+                addr,
+                shard_manager,
+                http_storage,
+                dashboard,
+                http_maintenance,
+            )
+            .await
+            {
+                error!(?err, "HTTP server stopped");
+            }
+        });
+    }
+
+    let shard_manager = framework.shard_manager();
+    tokio::spawn(async move {
+        if let Err(err) = shutdown::signal().await {
+            error!(?err, "Failed to listen for shutdown signals");
+            return;
+        }
+        info!("Shutting down, disconnecting from discord");
+        shard_manager.lock().await.shutdown_all().await;
+    });
+
+    framework
+        .start_with(sharding::start)
+        .await
+        .wrap_err("failed to run discord client")?;
+
+    info!("Waiting for background tasks");
+    shutdown_tasks.shutdown().await;
+    shutdown_storage.close().await;
+
+    info!("Shut down");
     Ok(())
 }
 
 /// Sag Karin hallo.
 #[poise::command(slash_command)]
 async fn hello(ctx: Context<'_>) -> Result<()> {
-    ctx.say("hoi!").await?;
+    let strings = i18n::strings(ctx).await?;
+    ctx.say(strings.hello).await?;
     Ok(())
 }
 
+/// Connects to the database selected with `STORAGE`, mongodb if it's not set.
+async fn connect_storage(tasks: &BackgroundTasks) -> Result<Arc<dyn Storage>> {
+    match env::var("STORAGE").as_deref() {
+        Ok("mongodb") | Err(_) => {
+            let mongo_uri =
+                env::var("MONGO_URI").wrap_err("MONGO_URI not found in the environment")?;
+            let db_name = env::var("DB_NAME").unwrap_or_else(|_| "court-bot".to_string());
+
+            let username = env::var("MONGO_INITDB_ROOT_USERNAME")
+                .wrap_err("MONGO_INITDB_ROOT_USERNAME not found in the environment")?;
+            let password = env::var("MONGO_INITDB_ROOT_PASSWORD")
+                .wrap_err("MONGO_INITDB_ROOT_PASSWORD not found in the environment")?;
+
+            let mongo = Mongo::connect(&mongo_uri, &db_name, username, password).await?;
+
+            info!("Connected to mongodb");
+
+            Ok(cached(mongo, tasks))
+        }
+        Ok("postgres") => {
+            let url =
+                env::var("POSTGRES_URL").wrap_err("POSTGRES_URL not found in the environment")?;
+
+            let postgres = Postgres::connect(&url).await?;
+
+            info!("Connected to postgres");
+
+            Ok(cached(postgres, tasks))
+        }
+        Ok(other) => bail!("unknown STORAGE {other}, must be mongodb or postgres"),
+    }
+}
+
+/// Puts the cache in front of the database and keeps it up to date with other processes.
+fn cached<S: Storage + 'static>(storage: S, tasks: &BackgroundTasks) -> Arc<dyn Storage> {
+    let cached = Arc::new(CachedStorage::new(storage));
+    tasks.spawn(cached.clone().watch_changes(tasks.clone()));
+    cached
+}
+
+/// The members are needed for the prison. The content of messages is only needed for the case
+/// numbers in gag orders and the texts in transcripts, it's privileged and only requested with
+/// `MESSAGE_CONTENT`.
+fn intents(message_content: bool) -> GatewayIntents {
+    let intents = GatewayIntents::non_privileged() | GatewayIntents::GUILD_MEMBERS;
+    if message_content {
+        intents | GatewayIntents::MESSAGE_CONTENT
+    } else {
+        intents
+    }
+}
+
 fn setup_tracing(pretty: bool) {
     let registry = Registry::default()
         .with(EnvFilter::from_default_env())
@@ -0,0 +1,91 @@
+use std::{
+    env,
+    fmt::{Display, Formatter},
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+use color_eyre::Result;
+
+use crate::Context;
+
+/// The commands that only read, they keep working during maintenance. All other commands can
+/// change discord or the database.
+const READ_ONLY: &[&str] = &[
+    "hello",
+    "lawsuit transcript export",
+    "lawsuit summary",
+    "lawsuit list",
+    "prison list",
+    "prison check",
+    "prison stats",
+    "config show",
+    "config messages list",
+    "config export",
+    "economy balance",
+    "lawyer fees",
+    "lawyer record",
+    "lawyer questions list",
+    "judge list",
+    "judge stats",
+    "court stats",
+    "court leaderboard",
+    "election list",
+    "records search",
+    "records show",
+    "docket",
+    "stats",
+    "debug",
+    "analytics",
+    "guilds list",
+    // otherwise it could never be switched off again
+    "maintenance",
+];
+
+/// Returned from the command check during maintenance, the error handler answers with the
+/// maintenance notice.
+#[derive(Debug)]
+pub struct UnderMaintenance;
+
+impl Display for UnderMaintenance {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str("the bot is under maintenance")
+    }
+}
+
+impl std::error::Error for UnderMaintenance {}
+
+/// Switches the bot to read-only, for migrations and backups of the database. `MAINTENANCE`
+/// starts the bot in maintenance mode, the owner switches it with `/maintenance`. While it's on,
+/// only the informational commands work and the scheduled jobs wait.
+#[derive(Debug)]
+pub struct Maintenance {
+    enabled: AtomicBool,
+}
+
+impl Maintenance {
+    pub fn from_env() -> Self {
+        Self {
+            enabled: AtomicBool::new(env::var("MAINTENANCE").is_ok()),
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled.load(Ordering::SeqCst)
+    }
+
+    /// Returns whether it was enabled before.
+    pub fn set(&self, enabled: bool) -> bool {
+        self.enabled.swap(enabled, Ordering::SeqCst)
+    }
+}
+
+/// Part of the command check of the framework, fails for the commands that change something
+/// during maintenance.
+pub fn check(ctx: Context<'_>) -> Result<()> {
+    if ctx.data().maintenance.is_enabled()
+        && !READ_ONLY.contains(&ctx.command().qualified_name.as_str())
+    {
+        return Err(UnderMaintenance.into());
+    }
+    Ok(())
+}
@@ -0,0 +1,138 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use color_eyre::Result;
+use mongodb::event::command::{CommandEventHandler, CommandFailedEvent, CommandSucceededEvent};
+use once_cell::sync::Lazy;
+use prometheus::{
+    register_histogram_vec, register_int_counter_vec, register_int_gauge_vec, Encoder,
+    HistogramVec, IntCounterVec, IntGaugeVec, TextEncoder,
+};
+
+use crate::{analytics, storage::Storage, Context, WrapErr};
+
+/// Discord ids contain the milliseconds since the start of 2015 when they were created.
+const DISCORD_EPOCH_MILLIS: u64 = 1_420_070_400_000;
+
+static COMMANDS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "court_bot_commands_total",
+        "Executed commands",
+        &["command", "result"]
+    )
+    .expect("register commands metric")
+});
+
+static COMMAND_DURATION: Lazy<HistogramVec> = Lazy::new(|| {
+    register_histogram_vec!(
+        "court_bot_command_duration_seconds",
+        "Time from the invocation of a command until it's done",
+        &["command"]
+    )
+    .expect("register command duration metric")
+});
+
+static MONGO_DURATION: Lazy<HistogramVec> = Lazy::new(|| {
+    register_histogram_vec!(
+        "court_bot_mongo_command_duration_seconds",
+        "Time until mongodb answered a command",
+        &["command", "result"]
+    )
+    .expect("register mongo duration metric")
+});
+
+static LAWSUITS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "court_bot_lawsuits_total",
+        "Lawsuits that were created or closed",
+        &["event"]
+    )
+    .expect("register lawsuits metric")
+});
+
+static OPEN_LAWSUITS: Lazy<IntGaugeVec> = Lazy::new(|| {
+    register_int_gauge_vec!(
+        "court_bot_open_lawsuits",
+        "Lawsuits without a verdict",
+        &["guild_id"]
+    )
+    .expect("register open lawsuits metric")
+});
+
+static PRISONERS: Lazy<IntGaugeVec> = Lazy::new(|| {
+    register_int_gauge_vec!(
+        "court_bot_prisoners",
+        "Users that are in prison",
+        &["guild_id"]
+    )
+    .expect("register prisoners metric")
+});
+
+/// Counts the command and how long it took since the user invoked it, which is read from the id of
+/// the interaction. `result` is `ok`, `error` or why the command didn't run, like `denied`. The
+/// invocation is also stored for [`crate::analytics`].
+pub fn command_finished(ctx: Context<'_>, result: &str) {
+    let command = &ctx.command().qualified_name;
+
+    COMMANDS.with_label_values(&[command, result]).inc();
+
+    let created_at = (ctx.id() >> 22) + DISCORD_EPOCH_MILLIS;
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|now| now.as_millis() as u64)
+        .unwrap_or_default();
+    let latency = Duration::from_millis(now.saturating_sub(created_at));
+    COMMAND_DURATION
+        .with_label_values(&[command])
+        .observe(latency.as_secs_f64());
+
+    analytics::record(ctx, result, latency);
+}
+
+pub fn lawsuit_created() {
+    LAWSUITS.with_label_values(&["created"]).inc();
+}
+
+pub fn lawsuit_closed() {
+    LAWSUITS.with_label_values(&["closed"]).inc();
+}
+
+/// Renders all metrics in the prometheus text format. The per guild numbers are counted right
+/// before, so they are always up to date.
+pub async fn gather(storage: &dyn Storage) -> Result<String> {
+    // guilds that don't have anything anymore are dropped instead of staying at their old value
+    OPEN_LAWSUITS.reset();
+    for (guild_id, count) in storage.count_open_lawsuits().await? {
+        OPEN_LAWSUITS
+            .with_label_values(&[&guild_id.to_string()])
+            .set(count as i64);
+    }
+    PRISONERS.reset();
+    for (guild_id, count) in storage.count_prisoners().await? {
+        PRISONERS
+            .with_label_values(&[&guild_id.to_string()])
+            .set(count as i64);
+    }
+
+    let mut buffer = Vec::new();
+    TextEncoder::new()
+        .encode(&prometheus::gather(), &mut buffer)
+        .wrap_err("encode metrics")?;
+    String::from_utf8(buffer).wrap_err("metrics are not utf-8")
+}
+
+/// Measures every command sent to mongodb, set on the client in [`crate::model::Mongo::connect`].
+pub struct MongoMetrics;
+
+impl CommandEventHandler for MongoMetrics {
+    fn handle_command_succeeded_event(&self, event: CommandSucceededEvent) {
+        MONGO_DURATION
+            .with_label_values(&[&event.command_name, "ok"])
+            .observe(event.duration.as_secs_f64());
+    }
+
+    fn handle_command_failed_event(&self, event: CommandFailedEvent) {
+        MONGO_DURATION
+            .with_label_values(&[&event.command_name, "error"])
+            .observe(event.duration.as_secs_f64());
+    }
+}
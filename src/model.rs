@@ -1,23 +1,51 @@
 use std::{
+    collections::BTreeMap,
     fmt::{Display, Formatter},
     num::ParseIntError,
     str::FromStr,
+    sync::Arc,
+    time::Duration,
 };
 
-use color_eyre::Result;
+use async_trait::async_trait;
+use color_eyre::{
+    eyre::{bail, ContextCompat},
+    Result,
+};
+use futures::{future::BoxFuture, stream::BoxStream, StreamExt, TryStreamExt};
 use mongodb::{
     bson,
-    bson::{doc, Bson, Uuid},
-    options::{ClientOptions, Credential, IndexOptions, UpdateOptions},
+    bson::{doc, Bson, DateTime, Document, Uuid},
+    options::{
+        ChangeStreamOptions, ClientOptions, CreateCollectionOptions, Credential,
+        FindOneAndUpdateOptions, FindOptions, FullDocumentType, IndexOptions, ReplaceOptions,
+        ReturnDocument, UpdateOptions,
+    },
     Client, Collection, Database, IndexModel,
 };
-use poise::serenity::model::id::{ChannelId, GuildId, RoleId, UserId};
+use poise::serenity::model::{
+    guild::Role,
+    id::{ChannelId, GuildId, RoleId, UserId},
+};
 use serde::{Deserialize, Serialize};
-use tracing::info;
+use tracing::{info, warn};
 
-use crate::{lawsuit::Lawsuit, WrapErr};
+use crate::{
+    bar::BarQuestion,
+    election::Election,
+    i18n::Locale,
+    lawsuit::{
+        Billing, ConflictPolicy, Contempt, Evidence, Fine, JuryTally, Lawsuit, LawyerFee,
+        SubpoenaResponse, Witness, GUILTY_VERDICT, NOT_GUILTY_VERDICT,
+    },
+    metrics::MongoMetrics,
+    permissions::CourtRole,
+    storage::Storage,
+    templates::Template,
+    WrapErr,
+};
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 #[serde(transparent)]
 pub struct SnowflakeId(#[serde(with = "serde_string")] pub u64);
 
@@ -89,10 +117,231 @@ from_snowflake!(GuildId, RoleId, ChannelId, UserId);
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct State {
     pub guild_id: SnowflakeId,
-    pub lawsuits: Vec<Lawsuit>,
     pub court_category: Option<SnowflakeId>,
     pub court_rooms: Vec<CourtRoom>,
+    /// Appeals get their own rooms in this category, without it they use the normal court rooms
+    #[serde(default)]
+    pub appeal_category: Option<SnowflakeId>,
+    /// The name of the appeal rooms before their number, [`DEFAULT_APPEAL_ROOM_NAME`] if unset
+    #[serde(default)]
+    pub appeal_room_name: Option<String>,
     pub prison_role: Option<SnowflakeId>,
+    /// How the prison role looked when it was set, it's recreated like this if it's deleted
+    #[serde(default)]
+    pub prison_role_template: Option<RoleTemplate>,
+    #[serde(default)]
+    pub probation_role: Option<SnowflakeId>,
+    #[serde(default)]
+    pub prison_channel: Option<SnowflakeId>,
+    /// Whether prisoners are denied sending messages in all channels except the prison channel
+    #[serde(default)]
+    pub prison_lockdown: bool,
+    #[serde(default)]
+    pub prison_tiers: Vec<PrisonTier>,
+    /// Whether all other roles are removed from prisoners and restored on release
+    #[serde(default)]
+    pub strip_roles_on_arrest: bool,
+    /// The channel where moderators are notified, for example when a prisoner escaped
+    #[serde(default)]
+    pub moderator_channel: Option<SnowflakeId>,
+    /// Whether prisoners get a direct message when they are arrested or released
+    #[serde(default)]
+    pub notify_prisoners: bool,
+    /// The judge for appeals of prisoners against their arrest and lawsuits filed with "Verklagen"
+    #[serde(default)]
+    pub appeal_judge: Option<SnowflakeId>,
+    /// The channel where every action of the bot is logged
+    #[serde(default)]
+    pub audit_channel: Option<SnowflakeId>,
+    /// The channel where errors of commands are reported, see [`crate::error_report`]
+    #[serde(default)]
+    pub error_channel: Option<SnowflakeId>,
+    /// The channel where the verdicts are published as public records
+    #[serde(default)]
+    pub records_channel: Option<SnowflakeId>,
+    /// The role that is pinged when a lawsuit is opened or closed, in the records channel
+    #[serde(default)]
+    pub news_role: Option<SnowflakeId>,
+    /// The channel where the weekly report is posted, `None` if it's turned off
+    #[serde(default)]
+    pub report_channel: Option<SnowflakeId>,
+    /// After how many seconds open lawsuits are dismissed, `None` if they never are
+    #[serde(default)]
+    pub statute_of_limitations: Option<u64>,
+    /// After how many seconds without a message in an ongoing court room the judge is pinged,
+    /// the admins are pinged after twice the time
+    #[serde(default)]
+    pub inactivity_nudge: Option<u64>,
+    /// How many open lawsuits a member can be the plaintiff of, `None` if there's no limit
+    #[serde(default)]
+    pub max_open_lawsuits: Option<u32>,
+    /// How many seconds a member has to wait between filing lawsuits, `None` if they don't
+    #[serde(default)]
+    pub filing_cooldown: Option<u64>,
+    /// Vexatious litigants who can't file lawsuits anymore, see `/lawsuit ban`
+    #[serde(default)]
+    pub banned_litigants: Vec<SnowflakeId>,
+    /// The slowmode of the court rooms in seconds while a lawsuit is running, `None` if there's none
+    #[serde(default)]
+    pub court_slowmode: Option<u64>,
+    /// How many cases were opened in a year, for the case numbers
+    #[serde(default)]
+    pub case_counters: BTreeMap<String, u32>,
+    /// The roles for the permission checks, see [`CourtRole`]
+    #[serde(default)]
+    pub admin_role: Option<SnowflakeId>,
+    #[serde(default)]
+    pub judge_role: Option<SnowflakeId>,
+    #[serde(default)]
+    pub bailiff_role: Option<SnowflakeId>,
+    /// The language of the responses
+    #[serde(default)]
+    pub locale: Locale,
+    /// Where court events are sent as JSON, see [`crate::webhook`]
+    #[serde(default)]
+    pub webhook_url: Option<String>,
+    /// The SHA-256 hash of the token for the API, see [`crate::api`]
+    #[serde(default)]
+    pub api_token_hash: Option<String>,
+    /// The SHA-256 hash of the token in the URL of the calendar feed, see [`crate::calendar`]
+    #[serde(default)]
+    pub calendar_token_hash: Option<String>,
+    /// The texts that replace some of the responses, by [`Template::key`]
+    #[serde(default)]
+    pub message_templates: BTreeMap<String, String>,
+    /// The questions of the bar exam, see [`crate::bar`]
+    #[serde(default)]
+    pub bar_questions: Vec<BarQuestion>,
+    /// The members who passed the bar exam
+    #[serde(default)]
+    pub admitted_lawyers: Vec<SnowflakeId>,
+    /// Whether only admitted lawyers can represent the parties of a lawsuit
+    #[serde(default)]
+    pub strict_lawyers: bool,
+    /// What happens when `/lawsuit create` finds a conflict of interest
+    #[serde(default)]
+    pub conflict_policy: ConflictPolicy,
+    /// The members who want to be drawn as jurors, see [`crate::jury::draw`]
+    #[serde(default)]
+    pub jury_pool: Vec<SnowflakeId>,
+    /// Lifted at the verdict of their lawsuit, see [`crate::gag`]
+    #[serde(default)]
+    pub gag_orders: Vec<GagOrder>,
+    /// Incremented by every change, so that a command can change the state only if nobody else
+    /// did since it was read
+    #[serde(default)]
+    pub version: u64,
+}
+
+impl State {
+    pub fn new(guild_id: SnowflakeId) -> Self {
+        Self {
+            guild_id,
+            court_category: None,
+            court_rooms: vec![],
+            appeal_category: None,
+            appeal_room_name: None,
+            prison_role: None,
+            prison_role_template: None,
+            probation_role: None,
+            prison_channel: None,
+            prison_lockdown: false,
+            prison_tiers: vec![],
+            strip_roles_on_arrest: false,
+            moderator_channel: None,
+            notify_prisoners: false,
+            appeal_judge: None,
+            audit_channel: None,
+            error_channel: None,
+            records_channel: None,
+            news_role: None,
+            report_channel: None,
+            statute_of_limitations: None,
+            inactivity_nudge: None,
+            max_open_lawsuits: None,
+            filing_cooldown: None,
+            banned_litigants: Vec::new(),
+            court_slowmode: None,
+            case_counters: BTreeMap::new(),
+            admin_role: None,
+            judge_role: None,
+            bailiff_role: None,
+            locale: Locale::default(),
+            webhook_url: None,
+            api_token_hash: None,
+            calendar_token_hash: None,
+            message_templates: BTreeMap::new(),
+            bar_questions: Vec::new(),
+            admitted_lawyers: Vec::new(),
+            strict_lawyers: false,
+            conflict_policy: ConflictPolicy::default(),
+            jury_pool: Vec::new(),
+            gag_orders: Vec::new(),
+            version: 0,
+        }
+    }
+
+    /// The role for prisoners in the tier. Prisoners without a tier or in a tier that has been
+    /// removed get the normal prison role.
+    pub fn prison_role_for(&self, tier: Option<&str>) -> Option<SnowflakeId> {
+        tier.and_then(|tier| self.prison_tiers.iter().find(|t| t.name == tier))
+            .map(|tier| tier.role_id)
+            .or(self.prison_role)
+    }
+
+    /// All roles that prisoners can have, including the roles of the tiers.
+    pub fn prisoner_roles(&self) -> impl Iterator<Item = SnowflakeId> + '_ {
+        self.prison_role
+            .into_iter()
+            .chain(self.prison_tiers.iter().map(|tier| tier.role_id))
+    }
+
+    /// Removes the user as appeal judge, their admission as lawyer and from the jury pool.
+    pub fn anonymize_user(&mut self, user_id: SnowflakeId, report: &mut ErasureReport) {
+        if self.appeal_judge == Some(user_id) {
+            self.appeal_judge = None;
+            report.appeal_judge = true;
+        }
+        self.banned_litigants.retain(|banned| *banned != user_id);
+        if self.admitted_lawyers.contains(&user_id) {
+            self.admitted_lawyers.retain(|lawyer| *lawyer != user_id);
+            report.admitted_lawyer = true;
+        }
+        if self.jury_pool.contains(&user_id) {
+            self.jury_pool.retain(|juror| *juror != user_id);
+            report.jury_pool = true;
+        }
+        // only until the verdict, not worth reporting
+        self.gag_orders.retain(|order| order.user_id != user_id);
+    }
+}
+
+/// The settings of a role that the bot recreates if the role is deleted.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RoleTemplate {
+    pub name: String,
+    pub permissions: u64,
+    pub colour: u32,
+    pub hoist: bool,
+    pub mentionable: bool,
+}
+
+impl From<&Role> for RoleTemplate {
+    fn from(role: &Role) -> Self {
+        Self {
+            name: role.name.clone(),
+            permissions: role.permissions.bits(),
+            colour: role.colour.0,
+            hoist: role.hoist,
+            mentionable: role.mentionable,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PrisonTier {
+    pub name: String,
+    pub role_id: SnowflakeId,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -100,17 +349,585 @@ pub struct CourtRoom {
     pub channel_id: SnowflakeId,
     pub ongoing_lawsuit: bool,
     pub role_id: SnowflakeId,
+    /// When the last lawsuit in the room started, `None` if it was never used
+    #[serde(default)]
+    pub last_used: Option<DateTime>,
+    /// When the last message was posted while a lawsuit was ongoing, see [`crate::inactivity`]
+    #[serde(default)]
+    pub last_activity: Option<DateTime>,
+    /// How often the room was nudged since the last message, 1 for the judge and 2 for the admins
+    #[serde(default)]
+    pub nudges: u32,
+    /// Whether the judge closed the trial to the public with `/lawsuit public`
+    #[serde(default)]
+    pub closed_to_public: bool,
+    #[serde(default)]
+    pub level: CourtLevel,
+}
+
+pub const DEFAULT_APPEAL_ROOM_NAME: &str = "berufungsraum";
+
+/// A member who may not talk about the lawsuit outside its court room, see `/lawsuit gag`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GagOrder {
+    pub lawsuit_id: Uuid,
+    pub user_id: SnowflakeId,
+}
+
+/// Whether a court room is for first instance lawsuits or for appeals, see
+/// [`State::appeal_category`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CourtLevel {
+    FirstInstance,
+    Appeal,
+}
+
+impl Default for CourtLevel {
+    fn default() -> Self {
+        Self::FirstInstance
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PrisonEntry {
     pub guild_id: SnowflakeId,
     pub user_id: SnowflakeId,
+    /// Set while the prisoner is out on parole, the parole ends at this time
+    #[serde(default)]
+    pub parole_until: Option<DateTime>,
+    /// The name of the prison tier, `None` for the normal prison role
+    #[serde(default)]
+    pub tier: Option<String>,
+    /// The roles that were removed on arrest and are given back on release
+    #[serde(default)]
+    pub saved_roles: Vec<SnowflakeId>,
+    /// When the prisoner is released automatically, `None` for an indefinite sentence
+    #[serde(default)]
+    pub release_at: Option<DateTime>,
+    /// The moderator who put them into prison in the first place
+    #[serde(default)]
+    pub arrested_by: Option<SnowflakeId>,
+}
+
+/// An arrest that is kept after the release for the statistics.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PrisonRecord {
+    pub guild_id: SnowflakeId,
+    pub user_id: SnowflakeId,
+    pub reason: Option<String>,
+    pub arrested_at: DateTime,
+    /// `None` while the prisoner is still in prison or on parole
+    pub released_at: Option<DateTime>,
+}
+
+#[derive(Debug, Clone)]
+pub struct PrisonStats {
+    pub current_prisoners: u64,
+    pub total_arrests: u64,
+    pub average_sentence: Option<Duration>,
+    /// The most frequent reasons with their count, most frequent first
+    pub top_reasons: Vec<(String, u64)>,
+}
+
+/// The numbers about the lawsuits of a guild for `/court stats`.
+#[derive(Debug, Clone, Default)]
+pub struct CourtStats {
+    /// The lawsuits opened in the last months like `2023-04`, the latest first
+    pub per_month: Vec<(String, u64)>,
+    /// See [`Lawsuit::is_guilty`]
+    pub guilty: u64,
+    pub not_guilty: u64,
+    /// The closed lawsuits whose verdict says neither
+    pub other_verdicts: u64,
+    /// Only of the closed lawsuits that know when they were opened
+    pub average_time_to_verdict: Option<Duration>,
+    /// The judges with the most lawsuits, the busiest first
+    pub busiest_judges: Vec<(SnowflakeId, u64)>,
+    /// The members that were sued the most, the most sued first
+    pub most_sued: Vec<(SnowflakeId, u64)>,
+}
+
+/// How many months and people `/court stats` shows
+pub const COURT_STATS_LEN: usize = 6;
+
+/// What happened in a guild in a period, see [`crate::report`].
+#[derive(Debug, Clone)]
+pub struct CourtReport {
+    pub opened_lawsuits: u64,
+    /// The lawsuits that got their verdict, in the order they got it
+    pub closed_lawsuits: Vec<Lawsuit>,
+    pub arrests: u64,
+    pub releases: u64,
+}
+
+/// A job that is run by the scheduler once `run_at` has passed. There's at most one job of every
+/// kind per guild, scheduling it again moves the existing one.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Job {
+    pub guild_id: SnowflakeId,
+    pub kind: JobKind,
+    pub run_at: DateTime,
+    /// How many times the job already failed
+    #[serde(default)]
+    pub attempts: u32,
+}
+
+/// One invocation of a command, see [`crate::analytics`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommandUsage {
+    /// `None` for direct messages
+    pub guild_id: Option<SnowflakeId>,
+    /// Like `lawsuit create`
+    pub command: String,
+    /// From the invocation until the command was done
+    pub latency_ms: u64,
+    /// `ok`, `error` or why the command didn't run, like `denied` or `cooldown`
+    pub outcome: String,
+    pub used_at: DateTime,
+}
+
+/// Only the newest invocations are kept, the older ones are dropped.
+pub const MAX_COMMAND_USAGES: u64 = 100_000;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum JobKind {
+    /// The sentence of the prisoner is over
+    Release { user_id: SnowflakeId },
+    /// The parole of the prisoner is over
+    EndParole { user_id: SnowflakeId },
+    /// The grace period of the cleared state is over
+    PurgeTrash,
+    /// The weekly report is due, see [`crate::report`]
+    WeeklyReport,
+    /// The open lawsuits are checked against the statute of limitations, see
+    /// [`crate::lawsuit::dismiss_expired`]
+    DismissExpiredLawsuits,
+    /// The ongoing court rooms are checked for inactivity, see [`crate::inactivity`]
+    NudgeInactiveRooms,
+    /// The member who was held in contempt may write in the court room again, see
+    /// [`crate::lawsuit::hold_in_contempt`]
+    LiftContempt {
+        channel_id: SnowflakeId,
+        user_id: SnowflakeId,
+    },
+}
+
+/// A guild that the owner allowed or blocked with `/guilds`, see [`crate::guild_access`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GuildListing {
+    pub guild_id: SnowflakeId,
+    /// `false` for blocked guilds
+    pub allowed: bool,
+}
+
+/// The judges that get the lawsuits in turn when no judge is chosen, registered with
+/// `/judge register`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JudgeRegistry {
+    pub guild_id: SnowflakeId,
+    /// In the order they registered, which is the order they get the lawsuits in
+    #[serde(default)]
+    pub judges: Vec<SnowflakeId>,
+    /// The judge who got the last lawsuit, the next lawsuit goes to the judge after them
+    #[serde(default)]
+    pub last_assigned: Option<SnowflakeId>,
+}
+
+impl JudgeRegistry {
+    pub fn new(guild_id: SnowflakeId) -> Self {
+        Self {
+            guild_id,
+            judges: Vec::new(),
+            last_assigned: None,
+        }
+    }
+
+    /// The next judge after the last assigned one, skipping the judges who are a party of the
+    /// lawsuit.
+    pub fn next_judge(&self, parties: &[SnowflakeId]) -> Option<SnowflakeId> {
+        let start = self
+            .last_assigned
+            .and_then(|last| self.judges.iter().position(|judge| *judge == last))
+            .map_or(0, |position| position + 1);
+        self.judges
+            .iter()
+            .cycle()
+            .skip(start)
+            .take(self.judges.len())
+            .find(|judge| !parties.contains(judge))
+            .copied()
+    }
+}
+
+/// What is stored about a single member, in its own collection so that the state doesn't grow
+/// with everyone who ever used the bot. Members without anything stored don't have one.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MemberData {
+    pub guild_id: SnowflakeId,
+    pub user_id: SnowflakeId,
+    /// The money of the member, negative for debts
+    #[serde(default)]
+    pub balance: i64,
+    /// What the member charges as lawyer, see `/lawyer fee`
+    #[serde(default)]
+    pub lawyer_fee: Option<LawyerFee>,
+    /// When the member last filed a lawsuit, for the filing cooldown
+    #[serde(default)]
+    pub last_filed: Option<DateTime>,
+}
+
+impl MemberData {
+    pub fn new(guild_id: SnowflakeId, user_id: SnowflakeId) -> Self {
+        Self {
+            guild_id,
+            user_id,
+            balance: 0,
+            lawyer_fee: None,
+            last_filed: None,
+        }
+    }
+}
+
+/// How long the state is kept after `/lawsuit clear all` so that it can be restored.
+pub const TRASH_GRACE_PERIOD: Duration = Duration::from_secs(60 * 60 * 24 * 7);
+
+/// A cleared state, waiting to be restored or purged.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrashedState {
+    pub guild_id: SnowflakeId,
+    pub state: State,
+    #[serde(default)]
+    pub lawsuits: Vec<Lawsuit>,
+    #[serde(default)]
+    pub members: Vec<MemberData>,
+    pub deleted_at: DateTime,
+}
+
+/// What was removed or anonymized when erasing the data of a user.
+#[derive(Debug, Clone, Default)]
+pub struct ErasureReport {
+    /// Lawsuits where the user was replaced with an anonymous id
+    pub lawsuits: u64,
+    pub prison_entries: u64,
+    pub prison_records: u64,
+    pub jobs: u64,
+    /// Prison entries of other users where the user was the arresting moderator
+    pub arrests: u64,
+    pub appeal_judge: bool,
+    pub balance: bool,
+    pub lawyer_fee: bool,
+    pub registered_judge: bool,
+    pub admitted_lawyer: bool,
+    pub jury_pool: bool,
+    /// Elections where the user ran or that they started
+    pub elections: u64,
+}
+
+/// A part of a list that is too long to load at once. Pass `next` as the cursor to get the page
+/// after it, it's `None` on the last page.
+#[derive(Debug, Clone)]
+pub struct Page<T, C> {
+    pub items: Vec<T>,
+    pub next: Option<C>,
+}
+
+impl<T, C> Page<T, C> {
+    /// The backends fetch one item more than the limit to know whether there's a next page
+    /// without a second query.
+    pub fn new(mut items: Vec<T>, limit: usize, cursor: impl FnOnce(&T) -> C) -> Self {
+        let next = if items.len() > limit {
+            items.truncate(limit);
+            items.last().map(cursor)
+        } else {
+            None
+        };
+        Self { items, next }
+    }
+}
+
+/// Everything that is stored about a guild, for backups and moving it to another instance.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GuildData {
+    pub state: State,
+    #[serde(default)]
+    pub lawsuits: Vec<Lawsuit>,
+    pub prison_entries: Vec<PrisonEntry>,
+    pub prison_history: Vec<PrisonRecord>,
+    pub jobs: Vec<Job>,
+    #[serde(default)]
+    pub members: Vec<MemberData>,
+}
+
+/// Numbers across all guilds for `/stats`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct BotStats {
+    pub guilds: u64,
+    /// Every lawsuit that was ever created, including the closed and deleted ones
+    pub lawsuits: u64,
+    pub open_lawsuits: u64,
+    pub prisoners: u64,
+}
+
+/// The counters for `/stats` are kept up to date whenever a lawsuit is created, closed or removed,
+/// so that the statistics don't have to go through all lawsuits.
+pub const LAWSUITS_COUNTER: &str = "lawsuits";
+pub const OPEN_LAWSUITS_COUNTER: &str = "open_lawsuits";
+
+/// A document in the `counters` collection, the `_id` is the name of the counter.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Counter {
+    count: i64,
+}
+
+/// The prefix for the autocomplete is typed by users, so it can't contain any regex syntax.
+fn escape_regex(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for char in text.chars() {
+        if "\\^$.|?*+()[]{}".contains(char) {
+            escaped.push('\\');
+        }
+        escaped.push(char);
+    }
+    escaped
+}
+
+pub fn count_open(lawsuits: &[Lawsuit]) -> i64 {
+    lawsuits
+        .iter()
+        .filter(|lawsuit| lawsuit.verdict.is_none())
+        .count() as i64
+}
+
+/// The document in the `meta` collection that stores how many migrations have been run.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct SchemaVersion {
+    version: i64,
+}
+
+const SCHEMA_VERSION_ID: &str = "schema_version";
+
+type Migration = for<'a> fn(&'a Mongo) -> BoxFuture<'a, Result<()>>;
+
+/// All migrations in the order they have to run. The schema version of the database is the amount
+/// of migrations that have been run on it, so migrations must never be removed or reordered, only
+/// appended.
+const MIGRATIONS: &[(&str, Migration)] = &[
+    ("store default locale", migrate_default_locale),
+    (
+        "move lawsuits into their own collection",
+        migrate_lawsuits_collection,
+    ),
+    (
+        "count lawsuits for the statistics",
+        migrate_lawsuit_counters,
+    ),
+    ("store the state version", migrate_state_version),
+    (
+        "move the balances into the members collection",
+        migrate_member_balances,
+    ),
+    (
+        "move the lawyer fees into the members collection",
+        migrate_member_lawyer_fees,
+    ),
+    (
+        "move the filing times into the members collection",
+        migrate_member_last_filed,
+    ),
+];
+
+/// States created before there were locales don't have one stored.
+fn migrate_default_locale(mongo: &Mongo) -> BoxFuture<'_, Result<()>> {
+    Box::pin(async move {
+        let locale = bson::to_bson(&Locale::default()).wrap_err("serialize locale")?;
+        mongo
+            .state_coll()
+            .update_many(
+                doc! { "locale": { "$exists": false } },
+                doc! { "$set": { "locale": locale } },
+                None,
+            )
+            .await
+            .wrap_err("set default locale")?;
+        Ok(())
+    })
+}
+
+/// The lawsuits used to be an array in the state, which doesn't scale because of the maximum
+/// document size. Replacing them by id makes it safe to run this again if it failed halfway.
+fn migrate_lawsuits_collection(mongo: &Mongo) -> BoxFuture<'_, Result<()>> {
+    Box::pin(async move {
+        let states = mongo.db.collection::<Document>("state");
+        let lawsuits = mongo.db.collection::<Document>("lawsuits");
+
+        let mut cursor = states
+            .find(doc! { "lawsuits": { "$exists": true } }, None)
+            .await
+            .wrap_err("find states with lawsuits")?;
+
+        while let Some(state) = cursor.try_next().await.wrap_err("fetch state")? {
+            let guild_id = state
+                .get("guild_id")
+                .cloned()
+                .wrap_err("state without guild_id")?;
+
+            for lawsuit in state.get_array("lawsuits").wrap_err("invalid lawsuits")? {
+                let mut lawsuit = lawsuit.as_document().cloned().wrap_err("invalid lawsuit")?;
+                lawsuit.insert("guild_id", guild_id.clone());
+                let id = lawsuit.get("id").cloned().wrap_err("lawsuit without id")?;
+
+                lawsuits
+                    .replace_one(
+                        doc! { "guild_id": &guild_id, "id": id },
+                        lawsuit,
+                        ReplaceOptions::builder().upsert(true).build(),
+                    )
+                    .await
+                    .wrap_err("move lawsuit")?;
+            }
+
+            states
+                .update_one(
+                    doc! { "guild_id": &guild_id },
+                    doc! { "$unset": { "lawsuits": "" } },
+                    None,
+                )
+                .await
+                .wrap_err("remove lawsuits from state")?;
+        }
+
+        Ok(())
+    })
+}
+
+/// The counters start at the lawsuits that already exist. The lawsuits that were deleted before
+/// can't be counted anymore.
+fn migrate_lawsuit_counters(mongo: &Mongo) -> BoxFuture<'_, Result<()>> {
+    Box::pin(async move {
+        let coll = mongo.lawsuit_coll();
+        let lawsuits = coll
+            .count_documents(doc! {}, None)
+            .await
+            .wrap_err("count lawsuits")?;
+        let open_lawsuits = coll
+            .count_documents(doc! { "verdict": null }, None)
+            .await
+            .wrap_err("count open lawsuits")?;
+
+        for (name, count) in [
+            (LAWSUITS_COUNTER, lawsuits),
+            (OPEN_LAWSUITS_COUNTER, open_lawsuits),
+        ] {
+            mongo
+                .counter_coll()
+                .update_one(
+                    doc! { "_id": name },
+                    doc! { "$set": { "count": count as i64 } },
+                    UpdateOptions::builder().upsert(true).build(),
+                )
+                .await
+                .wrap_err("set counter")?;
+        }
+
+        Ok(())
+    })
+}
+
+/// The version is compared by the updates that depend on it, it must be stored to match.
+fn migrate_state_version(mongo: &Mongo) -> BoxFuture<'_, Result<()>> {
+    Box::pin(async move {
+        mongo
+            .state_coll()
+            .update_many(
+                doc! { "version": { "$exists": false } },
+                doc! { "$set": { "version": 0_i64 } },
+                None,
+            )
+            .await
+            .wrap_err("set state version")?;
+        Ok(())
+    })
+}
+
+/// The balances grew the state with every member who ever had money.
+fn migrate_member_balances(mongo: &Mongo) -> BoxFuture<'_, Result<()>> {
+    Box::pin(move_to_members(mongo, "balances", "balance"))
+}
+
+fn migrate_member_lawyer_fees(mongo: &Mongo) -> BoxFuture<'_, Result<()>> {
+    Box::pin(move_to_members(mongo, "lawyer_fees", "lawyer_fee"))
+}
+
+fn migrate_member_last_filed(mongo: &Mongo) -> BoxFuture<'_, Result<()>> {
+    Box::pin(move_to_members(mongo, "last_filed", "last_filed"))
+}
+
+/// Moves a map by user id out of the states into the members collection. Setting the field by
+/// user makes it safe to run this again if it failed halfway.
+async fn move_to_members(mongo: &Mongo, map: &str, field: &str) -> Result<()> {
+    let states = mongo.db.collection::<Document>("state");
+    let members = mongo.db.collection::<Document>("members");
+
+    let mut cursor = states
+        .find(doc! { map: { "$exists": true } }, None)
+        .await
+        .wrap_err_with(|| format!("find states with {map}"))?;
+
+    while let Some(state) = cursor.try_next().await.wrap_err("fetch state")? {
+        let guild_id = state
+            .get("guild_id")
+            .cloned()
+            .wrap_err("state without guild_id")?;
+
+        for (user_id, value) in state.get_document(map).wrap_err("invalid map")? {
+            members
+                .update_one(
+                    doc! { "guild_id": &guild_id, "user_id": user_id.as_str() },
+                    doc! { "$set": { field: value.clone() } },
+                    UpdateOptions::builder().upsert(true).build(),
+                )
+                .await
+                .wrap_err_with(|| format!("move {field}"))?;
+        }
+
+        states
+            .update_one(
+                doc! { "guild_id": &guild_id },
+                doc! { "$unset": { map: "" } },
+                None,
+            )
+            .await
+            .wrap_err_with(|| format!("remove {map} from state"))?;
+    }
+
+    Ok(())
+}
+
+/// Adds incrementing the version of the state to the update, every change of the state must go
+/// through this.
+fn versioned(mut update: Document) -> Document {
+    match update.get_document_mut("$inc") {
+        Ok(inc) => {
+            inc.insert("version", 1_i64);
+        }
+        Err(_) => {
+            update.insert("$inc", doc! { "version": 1_i64 });
+        }
+    }
+    update
 }
 
+const COMMAND_USAGE_COLL: &str = "command_usage";
+/// The maximum size of the capped collection in bytes, an invocation takes around 150
+const COMMAND_USAGE_SIZE: u64 = 32 * 1024 * 1024;
+
 #[derive(Clone)]
 pub struct Mongo {
     db: Database,
+    /// Transactions need a replica set or a sharded cluster, standalone servers don't have them
+    transactions: bool,
 }
 
 impl Mongo {
@@ -130,11 +947,21 @@ impl Mongo {
         credentials.username = Some(username);
         credentials.password = Some(password);
         client_options.credential = Some(credentials);
+        client_options.command_event_handler = Some(Arc::new(MongoMetrics));
 
         let client = Client::with_options(client_options).wrap_err("failed to create client")?;
 
         let db = client.database(db_name);
-        let mongo = Self { db };
+        let hello = db
+            .run_command(doc! { "isMaster": 1 }, None)
+            .await
+            .wrap_err("find server type")?;
+        let transactions =
+            hello.contains_key("setName") || matches!(hello.get_str("msg"), Ok("isdbgrid"));
+        if !transactions {
+            warn!("MongoDB is a standalone server, writes that belong together are not atomic");
+        }
+        let mongo = Self { db, transactions };
 
         info!("Creating indexes");
 
@@ -162,74 +989,596 @@ impl Mongo {
             .await
             .wrap_err("create state index")?;
 
-        Ok(mongo)
-    }
+        mongo
+            .prison_history_coll()
+            .create_index(
+                IndexModel::builder()
+                    .keys(doc! { "guild_id": 1 })
+                    .options(IndexOptions::builder().name("prison_history.guild_id".to_string()).build())
+                    .build(),
+                None,
+            )
+            .await
+            .wrap_err("create prison history index")?;
 
-    #[tracing::instrument(skip(self))]
-    pub async fn find_or_insert_state(&self, guild_id: SnowflakeId) -> Result<State> {
-        let coll = self.state_coll();
-        let state = coll
-            .find_one(doc! {"guild_id": &guild_id  }, None)
+        mongo
+            .job_coll()
+            .create_index(
+                IndexModel::builder()
+                    .keys(doc! { "run_at": 1 })
+                    .options(IndexOptions::builder().name("jobs.run_at".to_string()).build())
+                    .build(),
+                None,
+            )
             .await
-            .wrap_err("find state")?;
+            .wrap_err("create jobs index")?;
 
-        let state = match state {
-            Some(state) => state,
-            None => {
-                info!(%guild_id, "No state found for guild, creating new state");
-                self.new_state(guild_id.to_owned()).await?
-            }
-        };
+        mongo
+            .lawsuit_coll()
+            .create_index(
+                IndexModel::builder()
+                    .keys(doc! { "guild_id": 1, "id": 1 })
+                    .options(IndexOptions::builder().name("lawsuits.guild_id_id".to_string()).build())
+                    .build(),
+                None,
+            )
+            .await
+            .wrap_err("create lawsuits index")?;
 
-        Ok(state)
-    }
+        // the guilds write in different languages, so the words are not stemmed
+        mongo
+            .lawsuit_coll()
+            .create_index(
+                IndexModel::builder()
+                    .keys(doc! { "reason": "text", "verdict": "text" })
+                    .options(
+                        IndexOptions::builder()
+                            .name("lawsuits.text".to_string())
+                            .default_language("none".to_string())
+                            .build(),
+                    )
+                    .build(),
+                None,
+            )
+            .await
+            .wrap_err("create lawsuits text index")?;
 
-    #[tracing::instrument(skip(self))]
-    pub async fn new_state(&self, guild_id: SnowflakeId) -> Result<State> {
-        let state = State {
-            guild_id,
-            lawsuits: vec![],
-            court_category: None,
-            court_rooms: vec![],
-            prison_role: None,
-        };
+        mongo
+            .judge_coll()
+            .create_index(
+                IndexModel::builder()
+                    .keys(doc! { "guild_id": 1 })
+                    .options(
+                        IndexOptions::builder()
+                            .name("judges.guild_id".to_string())
+                            .unique(true)
+                            .build(),
+                    )
+                    .build(),
+                None,
+            )
+            .await
+            .wrap_err("create judges index")?;
 
-        let coll = self.db.collection::<State>("state");
-        coll.insert_one(&state, None)
+        mongo
+            .election_coll()
+            .create_index(
+                IndexModel::builder()
+                    .keys(doc! { "guild_id": 1, "ended_at": -1 })
+                    .options(
+                        IndexOptions::builder()
+                            .name("elections.guild_id_ended_at".to_string())
+                            .build(),
+                    )
+                    .build(),
+                None,
+            )
             .await
-            .wrap_err("insert state")?;
-        Ok(state)
-    }
+            .wrap_err("create elections index")?;
 
-    #[tracing::instrument(skip(self))]
-    pub async fn set_court_category(
-        &self,
-        guild_id: SnowflakeId,
-        category: SnowflakeId,
-    ) -> Result<()> {
-        let _ = self.find_or_insert_state(guild_id).await?;
-        let coll = self.state_coll();
-        coll.update_one(
-            doc! { "guild_id": &guild_id  },
-            doc! { "$set": { "court_category": category } },
-            None,
-        )
-        .await
+        // the purge job deletes the trash after the grace period, but there is none if the bot
+        // stopped between clearing the guild and scheduling the job
+        mongo
+            .trash_coll()
+            .create_index(
+                IndexModel::builder()
+                    .keys(doc! { "deleted_at": 1 })
+                    .options(
+                        IndexOptions::builder()
+                            .name("trash.deleted_at".to_string())
+                            .expire_after(TRASH_GRACE_PERIOD)
+                            .build(),
+                    )
+                    .build(),
+                None,
+            )
+            .await
+            .wrap_err("create trash index")?;
+
+        mongo
+            .guild_access_coll()
+            .create_index(
+                IndexModel::builder()
+                    .keys(doc! { "guild_id": 1 })
+                    .options(
+                        IndexOptions::builder()
+                            .name("guild_access.guild_id".to_string())
+                            .unique(true)
+                            .build(),
+                    )
+                    .build(),
+                None,
+            )
+            .await
+            .wrap_err("create guild access index")?;
+
+        mongo
+            .member_coll()
+            .create_index(
+                IndexModel::builder()
+                    .keys(doc! { "guild_id": 1, "user_id": 1 })
+                    .options(
+                        IndexOptions::builder()
+                            .name("members.guild_id_user_id".to_string())
+                            .unique(true)
+                            .build(),
+                    )
+                    .build(),
+                None,
+            )
+            .await
+            .wrap_err("create members index")?;
+
+        // a capped collection drops the oldest invocations by itself
+        let collections = mongo
+            .db
+            .list_collection_names(None)
+            .await
+            .wrap_err("list collections")?;
+        if !collections.iter().any(|name| name == COMMAND_USAGE_COLL) {
+            mongo
+                .db
+                .create_collection(
+                    COMMAND_USAGE_COLL,
+                    CreateCollectionOptions::builder()
+                        .capped(true)
+                        .size(COMMAND_USAGE_SIZE)
+                        .max(MAX_COMMAND_USAGES)
+                        .build(),
+                )
+                .await
+                .wrap_err("create command usage collection")?;
+        }
+
+        mongo
+            .command_usage_coll()
+            .create_index(
+                IndexModel::builder()
+                    .keys(doc! { "used_at": 1 })
+                    .options(
+                        IndexOptions::builder()
+                            .name("command_usage.used_at".to_string())
+                            .build(),
+                    )
+                    .build(),
+                None,
+            )
+            .await
+            .wrap_err("create command usage index")?;
+
+        mongo.migrate().await.wrap_err("migrate database")?;
+
+        Ok(mongo)
+    }
+
+    /// Runs the migrations that haven't been run on the database yet. The version is stored after
+    /// every migration, so a failed migration is retried on the next start without running the
+    /// previous ones again.
+    #[tracing::instrument(skip(self))]
+    async fn migrate(&self) -> Result<()> {
+        let coll = self.meta_coll();
+        let current = coll
+            .find_one(doc! { "_id": SCHEMA_VERSION_ID }, None)
+            .await
+            .wrap_err("find schema version")?
+            .map(|meta| meta.version)
+            .unwrap_or(0);
+
+        let latest = MIGRATIONS.len() as i64;
+        if current > latest {
+            bail!(
+                "database has schema version {current}, but this bot only knows version {latest}"
+            );
+        }
+
+        for (version, (name, migration)) in (1_i64..).zip(MIGRATIONS).skip(current as usize) {
+            info!(version, name, "Running migration");
+
+            migration(self)
+                .await
+                .wrap_err_with(|| format!("migration {version} ({name})"))?;
+
+            coll.update_one(
+                doc! { "_id": SCHEMA_VERSION_ID },
+                doc! { "$set": { "version": version } },
+                UpdateOptions::builder().upsert(true).build(),
+            )
+            .await
+            .wrap_err("set schema version")?;
+        }
+
+        info!(version = latest, "Database schema is up to date");
+
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn new_state(&self, guild_id: SnowflakeId) -> Result<State> {
+        let state = State::new(guild_id);
+
+        let coll = self.db.collection::<State>("state");
+        coll.insert_one(&state, None)
+            .await
+            .wrap_err("insert state")?;
+        Ok(state)
+    }
+
+    fn state_coll(&self) -> Collection<State> {
+        self.db.collection("state")
+    }
+
+    fn prison_coll(&self) -> Collection<PrisonEntry> {
+        self.db.collection("prison")
+    }
+
+    fn prison_history_coll(&self) -> Collection<PrisonRecord> {
+        self.db.collection("prison_history")
+    }
+
+    fn job_coll(&self) -> Collection<Job> {
+        self.db.collection("jobs")
+    }
+
+    fn lawsuit_coll(&self) -> Collection<Lawsuit> {
+        self.db.collection("lawsuits")
+    }
+
+    fn trash_coll(&self) -> Collection<TrashedState> {
+        self.db.collection("trash")
+    }
+
+    fn meta_coll(&self) -> Collection<SchemaVersion> {
+        self.db.collection("meta")
+    }
+
+    fn counter_coll(&self) -> Collection<Counter> {
+        self.db.collection("counters")
+    }
+
+    fn judge_coll(&self) -> Collection<JudgeRegistry> {
+        self.db.collection("judges")
+    }
+
+    fn election_coll(&self) -> Collection<Election> {
+        self.db.collection("elections")
+    }
+
+    fn command_usage_coll(&self) -> Collection<CommandUsage> {
+        self.db.collection(COMMAND_USAGE_COLL)
+    }
+
+    fn guild_access_coll(&self) -> Collection<GuildListing> {
+        self.db.collection("guild_access")
+    }
+
+    fn member_coll(&self) -> Collection<MemberData> {
+        self.db.collection("members")
+    }
+
+    async fn add_to_counter(&self, name: &str, amount: i64) -> Result<()> {
+        if amount == 0 {
+            return Ok(());
+        }
+
+        self.counter_coll()
+            .update_one(
+                doc! { "_id": name },
+                doc! { "$inc": { "count": amount } },
+                UpdateOptions::builder().upsert(true).build(),
+            )
+            .await
+            .wrap_err_with(|| format!("update counter {name}"))?;
+        Ok(())
+    }
+
+    async fn counter(&self, name: &str) -> Result<u64> {
+        let counter = self
+            .counter_coll()
+            .find_one(doc! { "_id": name }, None)
+            .await
+            .wrap_err_with(|| format!("find counter {name}"))?;
+
+        Ok(counter.map_or(0, |counter| counter.count.max(0) as u64))
+    }
+
+    async fn count_open_lawsuits_of(&self, guild_id: SnowflakeId) -> Result<i64> {
+        let count = self
+            .lawsuit_coll()
+            .count_documents(doc! { "guild_id": guild_id, "verdict": null }, None)
+            .await
+            .wrap_err("count open lawsuits of guild")?;
+        Ok(count as i64)
+    }
+
+    /// Like `replace_guild`, but nothing is replaced if any part fails.
+    async fn replace_guild_in_transaction(&self, data: &GuildData) -> Result<()> {
+        let guild_id = data.state.guild_id;
+        let filter = doc! { "guild_id": &guild_id };
+
+        let mut session = self
+            .db
+            .client()
+            .start_session(None)
+            .await
+            .wrap_err("start session")?;
+        session
+            .start_transaction(None)
+            .await
+            .wrap_err("start transaction")?;
+
+        // the transaction is aborted when the session is dropped after an error
+        self.state_coll()
+            .replace_one_with_session(
+                filter.clone(),
+                &data.state,
+                ReplaceOptions::builder().upsert(true).build(),
+                &mut session,
+            )
+            .await
+            .wrap_err("replace state")?;
+
+        let replaced_open_lawsuits = self
+            .lawsuit_coll()
+            .count_documents_with_session(
+                doc! { "guild_id": &guild_id, "verdict": null },
+                None,
+                &mut session,
+            )
+            .await
+            .wrap_err("count open lawsuits of guild")?;
+        self.lawsuit_coll()
+            .delete_many_with_session(filter.clone(), None, &mut session)
+            .await
+            .wrap_err("delete lawsuits")?;
+        // mongo doesn't like empty inserts
+        if !data.lawsuits.is_empty() {
+            self.lawsuit_coll()
+                .insert_many_with_session(&data.lawsuits, None, &mut session)
+                .await
+                .wrap_err("insert lawsuits")?;
+        }
+        let open_lawsuits = count_open(&data.lawsuits) - replaced_open_lawsuits as i64;
+        if open_lawsuits != 0 {
+            self.counter_coll()
+                .update_one_with_session(
+                    doc! { "_id": OPEN_LAWSUITS_COUNTER },
+                    doc! { "$inc": { "count": open_lawsuits } },
+                    UpdateOptions::builder().upsert(true).build(),
+                    &mut session,
+                )
+                .await
+                .wrap_err("update open lawsuits counter")?;
+        }
+
+        self.prison_coll()
+            .delete_many_with_session(filter.clone(), None, &mut session)
+            .await
+            .wrap_err("delete prison entries")?;
+        if !data.prison_entries.is_empty() {
+            self.prison_coll()
+                .insert_many_with_session(&data.prison_entries, None, &mut session)
+                .await
+                .wrap_err("insert prison entries")?;
+        }
+
+        self.prison_history_coll()
+            .delete_many_with_session(filter.clone(), None, &mut session)
+            .await
+            .wrap_err("delete prison records")?;
+        if !data.prison_history.is_empty() {
+            self.prison_history_coll()
+                .insert_many_with_session(&data.prison_history, None, &mut session)
+                .await
+                .wrap_err("insert prison records")?;
+        }
+
+        self.job_coll()
+            .delete_many_with_session(filter.clone(), None, &mut session)
+            .await
+            .wrap_err("delete jobs")?;
+        if !data.jobs.is_empty() {
+            self.job_coll()
+                .insert_many_with_session(&data.jobs, None, &mut session)
+                .await
+                .wrap_err("insert jobs")?;
+        }
+
+        self.member_coll()
+            .delete_many_with_session(filter, None, &mut session)
+            .await
+            .wrap_err("delete members")?;
+        if !data.members.is_empty() {
+            self.member_coll()
+                .insert_many_with_session(&data.members, None, &mut session)
+                .await
+                .wrap_err("insert members")?;
+        }
+
+        session
+            .commit_transaction()
+            .await
+            .wrap_err("commit transaction")?;
+        Ok(())
+    }
+}
+
+/// Counts the documents that match the filter in every guild.
+async fn count_per_guild<T>(
+    coll: &Collection<T>,
+    filter: Document,
+) -> Result<Vec<(SnowflakeId, u64)>> {
+    let pipeline = [
+        doc! { "$match": filter },
+        doc! { "$group": { "_id": "$guild_id", "count": { "$sum": 1 } } },
+    ];
+
+    let groups: Vec<Document> = coll
+        .aggregate(pipeline, None)
+        .await
+        .wrap_err("count per guild")?
+        .try_collect()
+        .await
+        .wrap_err("collect counts")?;
+
+    groups
+        .iter()
+        .map(|group| {
+            let guild_id = group
+                .get_str("_id")
+                .wrap_err("count without guild_id")?
+                .parse()
+                .wrap_err("invalid guild_id")?;
+            let count = match group.get("count") {
+                Some(Bson::Int32(count)) => *count as u64,
+                Some(Bson::Int64(count)) => *count as u64,
+                _ => bail!("invalid count"),
+            };
+            Ok((guild_id, count))
+        })
+        .collect()
+}
+
+#[async_trait]
+impl Storage for Mongo {
+    async fn ping(&self) -> Result<()> {
+        self.db
+            .run_command(doc! { "ping": 1 }, None)
+            .await
+            .wrap_err("ping mongodb")?;
+
+        Ok(())
+    }
+
+    async fn close(&self) {
+        // this version of the driver can't shut down explicitly, the connections are closed when
+        // the client is dropped on exit
+    }
+
+    async fn state_changes(
+        &self,
+    ) -> Result<Option<BoxStream<'static, Result<Option<SnowflakeId>>>>> {
+        // change streams need a replica set, just like transactions
+        if !self.transactions {
+            return Ok(None);
+        }
+
+        let options = ChangeStreamOptions::builder()
+            .full_document(Some(FullDocumentType::UpdateLookup))
+            .build();
+        let changes = self
+            .state_coll()
+            .watch(None, options)
+            .await
+            .wrap_err("watch state changes")?;
+
+        // deleted states don't have a document anymore
+        Ok(Some(
+            changes
+                .map(|change| {
+                    let change = change.wrap_err("receive state change")?;
+                    Ok(change.full_document.map(|state| state.guild_id))
+                })
+                .boxed(),
+        ))
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn find_or_insert_state(&self, guild_id: SnowflakeId) -> Result<State> {
+        let coll = self.state_coll();
+        let state = coll
+            .find_one(doc! {"guild_id": &guild_id  }, None)
+            .await
+            .wrap_err("find state")?;
+
+        let state = match state {
+            Some(state) => state,
+            None => {
+                info!(%guild_id, "No state found for guild, creating new state");
+                self.new_state(guild_id.to_owned()).await?
+            }
+        };
+
+        Ok(state)
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn find_state(&self, guild_id: SnowflakeId) -> Result<Option<State>> {
+        self.state_coll()
+            .find_one(doc! { "guild_id": &guild_id }, None)
+            .await
+            .wrap_err("find state")
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn set_court_category(&self, guild_id: SnowflakeId, category: SnowflakeId) -> Result<()> {
+        let _ = self.find_or_insert_state(guild_id).await?;
+        let coll = self.state_coll();
+        coll.update_one(
+            doc! { "guild_id": &guild_id  },
+            versioned(doc! { "$set": { "court_category": category } }),
+            None,
+        )
+        .await
         .wrap_err("update court category")?;
         Ok(())
     }
 
     #[tracing::instrument(skip(self))]
-    pub async fn set_prison_role(
+    async fn set_appeal_category(
+        &self,
+        guild_id: SnowflakeId,
+        category: SnowflakeId,
+        room_name: Option<&str>,
+    ) -> Result<()> {
+        let _ = self.find_or_insert_state(guild_id).await?;
+        let coll = self.state_coll();
+        coll.update_one(
+            doc! { "guild_id": &guild_id  },
+            versioned(
+                doc! { "$set": { "appeal_category": category, "appeal_room_name": room_name } },
+            ),
+            None,
+        )
+        .await
+        .wrap_err("update appeal category")?;
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn set_prison_role(
         &self,
         guild_id: SnowflakeId,
         prison_role: SnowflakeId,
+        template: &RoleTemplate,
     ) -> Result<()> {
         let _ = self.find_or_insert_state(guild_id).await?;
+        let template = bson::to_bson(template).wrap_err("invalid bson for role template")?;
         let coll = self.state_coll();
         coll.update_one(
             doc! { "guild_id": &guild_id  },
-            doc! { "$set": { "prison_role": prison_role } },
+            versioned(doc! {
+                "$set": { "prison_role": prison_role, "prison_role_template": template }
+            }),
             None,
         )
         .await
@@ -238,137 +1587,2513 @@ impl Mongo {
     }
 
     #[tracing::instrument(skip(self))]
-    pub async fn add_court_room(&self, guild_id: SnowflakeId, room: &CourtRoom) -> Result<()> {
+    async fn set_probation_role(
+        &self,
+        guild_id: SnowflakeId,
+        probation_role: SnowflakeId,
+    ) -> Result<()> {
         let _ = self.find_or_insert_state(guild_id).await?;
         let coll = self.state_coll();
         coll.update_one(
             doc! { "guild_id": &guild_id  },
-            doc! { "$push": { "court_rooms": bson::to_bson(room).wrap_err("invalid bson for room")? }},
+            versioned(doc! { "$set": { "probation_role": probation_role } }),
             None,
         )
         .await
-        .wrap_err("push court room")?;
+        .wrap_err("update probation role")?;
         Ok(())
     }
 
     #[tracing::instrument(skip(self))]
-    pub async fn add_lawsuit(&self, guild_id: SnowflakeId, lawsuit: &Lawsuit) -> Result<()> {
+    async fn set_prison_channel(
+        &self,
+        guild_id: SnowflakeId,
+        prison_channel: SnowflakeId,
+        lockdown: bool,
+    ) -> Result<()> {
         let _ = self.find_or_insert_state(guild_id).await?;
         let coll = self.state_coll();
-
         coll.update_one(
             doc! { "guild_id": &guild_id  },
-            doc! { "$push": { "lawsuits": bson::to_bson(lawsuit).wrap_err("invalid bson for lawsuit")? } },
+            versioned(
+                doc! { "$set": { "prison_channel": prison_channel, "prison_lockdown": lockdown } },
+            ),
             None,
         )
         .await
-        .wrap_err("push lawsuit")?;
-
+        .wrap_err("update prison channel")?;
         Ok(())
     }
 
-    #[tracing::instrument(skip(self, value))]
-    pub async fn set_court_room(
+    #[tracing::instrument(skip(self))]
+    async fn set_strip_roles_on_arrest(
         &self,
         guild_id: SnowflakeId,
-        channel_id: SnowflakeId,
-        value: impl Into<Bson>,
+        strip_roles: bool,
     ) -> Result<()> {
         let _ = self.find_or_insert_state(guild_id).await?;
         let coll = self.state_coll();
-
         coll.update_one(
-            doc! { "guild_id": &guild_id, "court_rooms.channel_id": channel_id  },
-            doc! { "$set": value.into() },
+            doc! { "guild_id": &guild_id  },
+            versioned(doc! { "$set": { "strip_roles_on_arrest": strip_roles } }),
             None,
         )
         .await
-        .wrap_err("set courtroom")?;
+        .wrap_err("update strip roles on arrest")?;
         Ok(())
     }
 
-    #[tracing::instrument(skip(self, value))]
-    pub async fn set_lawsuit(
+    #[tracing::instrument(skip(self))]
+    async fn set_moderator_channel(
         &self,
         guild_id: SnowflakeId,
-        lawsuit_id: Uuid,
-        value: impl Into<Bson>,
+        moderator_channel: SnowflakeId,
     ) -> Result<()> {
         let _ = self.find_or_insert_state(guild_id).await?;
         let coll = self.state_coll();
-
         coll.update_one(
-            doc! { "guild_id": &guild_id, "lawsuit.id": lawsuit_id  },
-            doc! { "$set": value.into() },
+            doc! { "guild_id": &guild_id  },
+            versioned(doc! { "$set": { "moderator_channel": moderator_channel } }),
             None,
         )
         .await
-        .wrap_err("set courtroom")?;
+        .wrap_err("update moderator channel")?;
         Ok(())
     }
 
     #[tracing::instrument(skip(self))]
-    pub async fn delete_guild(&self, guild_id: SnowflakeId) -> Result<()> {
+    async fn set_notify_prisoners(
+        &self,
+        guild_id: SnowflakeId,
+        notify_prisoners: bool,
+    ) -> Result<()> {
+        let _ = self.find_or_insert_state(guild_id).await?;
         let coll = self.state_coll();
-
-        coll.delete_one(doc! { "guild_id": &guild_id }, None)
-            .await
-            .wrap_err("delete guild")?;
+        coll.update_one(
+            doc! { "guild_id": &guild_id  },
+            versioned(doc! { "$set": { "notify_prisoners": notify_prisoners } }),
+            None,
+        )
+        .await
+        .wrap_err("update notify prisoners")?;
         Ok(())
     }
 
     #[tracing::instrument(skip(self))]
-    pub async fn add_to_prison(&self, guild_id: SnowflakeId, user_id: SnowflakeId) -> Result<()> {
-        let coll = self.prison_coll();
-
+    async fn set_strict_lawyers(&self, guild_id: SnowflakeId, strict_lawyers: bool) -> Result<()> {
+        let _ = self.find_or_insert_state(guild_id).await?;
+        let coll = self.state_coll();
         coll.update_one(
-            doc! { "guild_id": guild_id, "user_id": user_id },
-            doc! {
-                "$setOnInsert": {
-                    "guild_id": guild_id, "user_id": user_id,
-                }
-            },
-            UpdateOptions::builder().upsert(true).build(),
+            doc! { "guild_id": &guild_id },
+            versioned(doc! { "$set": { "strict_lawyers": strict_lawyers } }),
+            None,
         )
         .await
-        .wrap_err("add to prison collection")?;
-
+        .wrap_err("update strict lawyers")?;
         Ok(())
     }
 
     #[tracing::instrument(skip(self))]
-    pub async fn remove_from_prison(
+    async fn set_conflict_policy(
         &self,
         guild_id: SnowflakeId,
-        user_id: SnowflakeId,
+        conflict_policy: ConflictPolicy,
     ) -> Result<()> {
-        let coll = self.prison_coll();
-
-        coll.delete_one(doc! { "guild_id": guild_id, "user_id": user_id }, None)
-            .await
-            .wrap_err("remove from prison")?;
-
+        let _ = self.find_or_insert_state(guild_id).await?;
+        let coll = self.state_coll();
+        coll.update_one(
+            doc! { "guild_id": &guild_id },
+            versioned(doc! { "$set": { "conflict_policy": bson::to_bson(&conflict_policy).wrap_err("invalid bson for conflict policy")? } }),
+            None,
+        )
+        .await
+        .wrap_err("update conflict policy")?;
         Ok(())
     }
 
     #[tracing::instrument(skip(self))]
-    pub async fn find_prison_entry(
+    async fn set_appeal_judge(
         &self,
         guild_id: SnowflakeId,
-        user_id: SnowflakeId,
-    ) -> Result<Option<PrisonEntry>> {
-        let coll = self.prison_coll();
+        appeal_judge: SnowflakeId,
+    ) -> Result<()> {
+        let _ = self.find_or_insert_state(guild_id).await?;
+        let coll = self.state_coll();
+        coll.update_one(
+            doc! { "guild_id": &guild_id  },
+            versioned(doc! { "$set": { "appeal_judge": appeal_judge } }),
+            None,
+        )
+        .await
+        .wrap_err("update appeal judge")?;
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn set_audit_channel(
+        &self,
+        guild_id: SnowflakeId,
+        audit_channel: SnowflakeId,
+    ) -> Result<()> {
+        let _ = self.find_or_insert_state(guild_id).await?;
+        let coll = self.state_coll();
+        coll.update_one(
+            doc! { "guild_id": &guild_id  },
+            versioned(doc! { "$set": { "audit_channel": audit_channel } }),
+            None,
+        )
+        .await
+        .wrap_err("update audit channel")?;
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn set_error_channel(
+        &self,
+        guild_id: SnowflakeId,
+        error_channel: SnowflakeId,
+    ) -> Result<()> {
+        let _ = self.find_or_insert_state(guild_id).await?;
+        let coll = self.state_coll();
+        coll.update_one(
+            doc! { "guild_id": &guild_id  },
+            versioned(doc! { "$set": { "error_channel": error_channel } }),
+            None,
+        )
+        .await
+        .wrap_err("update error channel")?;
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn set_records_channel(
+        &self,
+        guild_id: SnowflakeId,
+        records_channel: SnowflakeId,
+    ) -> Result<()> {
+        let _ = self.find_or_insert_state(guild_id).await?;
+        let coll = self.state_coll();
+        coll.update_one(
+            doc! { "guild_id": &guild_id },
+            versioned(doc! { "$set": { "records_channel": records_channel } }),
+            None,
+        )
+        .await
+        .wrap_err("update records channel")?;
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn set_news_role(
+        &self,
+        guild_id: SnowflakeId,
+        role_id: Option<SnowflakeId>,
+    ) -> Result<()> {
+        let _ = self.find_or_insert_state(guild_id).await?;
+        let coll = self.state_coll();
+        coll.update_one(
+            doc! { "guild_id": &guild_id },
+            versioned(doc! { "$set": { "news_role": role_id } }),
+            None,
+        )
+        .await
+        .wrap_err("update news role")?;
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn set_report_channel(
+        &self,
+        guild_id: SnowflakeId,
+        report_channel: Option<SnowflakeId>,
+    ) -> Result<()> {
+        let _ = self.find_or_insert_state(guild_id).await?;
+        let coll = self.state_coll();
+        coll.update_one(
+            doc! { "guild_id": &guild_id },
+            versioned(doc! { "$set": { "report_channel": report_channel } }),
+            None,
+        )
+        .await
+        .wrap_err("update report channel")?;
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn set_statute_of_limitations(
+        &self,
+        guild_id: SnowflakeId,
+        max_age: Option<Duration>,
+    ) -> Result<()> {
+        let _ = self.find_or_insert_state(guild_id).await?;
+        let coll = self.state_coll();
+        let max_age = max_age.map(|max_age| max_age.as_secs() as i64);
+        coll.update_one(
+            doc! { "guild_id": &guild_id },
+            versioned(doc! { "$set": { "statute_of_limitations": max_age } }),
+            None,
+        )
+        .await
+        .wrap_err("update statute of limitations")?;
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn set_inactivity_nudge(
+        &self,
+        guild_id: SnowflakeId,
+        after: Option<Duration>,
+    ) -> Result<()> {
+        let _ = self.find_or_insert_state(guild_id).await?;
+        let coll = self.state_coll();
+        let after = after.map(|after| after.as_secs() as i64);
+        coll.update_one(
+            doc! { "guild_id": &guild_id },
+            versioned(doc! { "$set": { "inactivity_nudge": after } }),
+            None,
+        )
+        .await
+        .wrap_err("update inactivity nudge")?;
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn set_max_open_lawsuits(&self, guild_id: SnowflakeId, max: Option<u32>) -> Result<()> {
+        let _ = self.find_or_insert_state(guild_id).await?;
+        let coll = self.state_coll();
+        coll.update_one(
+            doc! { "guild_id": &guild_id },
+            versioned(doc! { "$set": { "max_open_lawsuits": max } }),
+            None,
+        )
+        .await
+        .wrap_err("update max open lawsuits")?;
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn set_filing_cooldown(
+        &self,
+        guild_id: SnowflakeId,
+        cooldown: Option<Duration>,
+    ) -> Result<()> {
+        let _ = self.find_or_insert_state(guild_id).await?;
+        let coll = self.state_coll();
+        let cooldown = cooldown.map(|cooldown| cooldown.as_secs() as i64);
+        coll.update_one(
+            doc! { "guild_id": &guild_id },
+            versioned(doc! { "$set": { "filing_cooldown": cooldown } }),
+            None,
+        )
+        .await
+        .wrap_err("update filing cooldown")?;
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn set_court_slowmode(
+        &self,
+        guild_id: SnowflakeId,
+        slowmode: Option<Duration>,
+    ) -> Result<()> {
+        let _ = self.find_or_insert_state(guild_id).await?;
+        let coll = self.state_coll();
+        let slowmode = slowmode.map(|slowmode| slowmode.as_secs() as i64);
+        coll.update_one(
+            doc! { "guild_id": &guild_id },
+            versioned(doc! { "$set": { "court_slowmode": slowmode } }),
+            None,
+        )
+        .await
+        .wrap_err("update court slowmode")?;
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn set_last_filed(
+        &self,
+        guild_id: SnowflakeId,
+        user_id: SnowflakeId,
+        filed_at: DateTime,
+    ) -> Result<()> {
+        self.member_coll()
+            .update_one(
+                doc! { "guild_id": &guild_id, "user_id": &user_id },
+                doc! { "$set": { "last_filed": filed_at } },
+                UpdateOptions::builder().upsert(true).build(),
+            )
+            .await
+            .wrap_err("set last filed")?;
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn next_case_number(&self, guild_id: SnowflakeId, year: i64) -> Result<u32> {
+        let _ = self.find_or_insert_state(guild_id).await?;
+        let field = format!("case_counters.{year}");
+        let state = self
+            .state_coll()
+            .find_one_and_update(
+                doc! { "guild_id": &guild_id },
+                versioned(doc! { "$inc": { field: 1 } }),
+                FindOneAndUpdateOptions::builder()
+                    .return_document(ReturnDocument::After)
+                    .build(),
+            )
+            .await
+            .wrap_err("update case counter")?
+            .wrap_err("state not found")?;
+        Ok(state
+            .case_counters
+            .get(&year.to_string())
+            .copied()
+            .unwrap_or_default())
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn set_locale(&self, guild_id: SnowflakeId, locale: Locale) -> Result<()> {
+        let _ = self.find_or_insert_state(guild_id).await?;
+        let coll = self.state_coll();
+        coll.update_one(
+            doc! { "guild_id": &guild_id  },
+            versioned(doc! { "$set": { "locale": bson::to_bson(&locale).wrap_err("invalid bson for locale")? } }),
+            None,
+        )
+        .await
+        .wrap_err("update locale")?;
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self, webhook_url))]
+    async fn set_webhook_url(
+        &self,
+        guild_id: SnowflakeId,
+        webhook_url: Option<&str>,
+    ) -> Result<()> {
+        let _ = self.find_or_insert_state(guild_id).await?;
+        let coll = self.state_coll();
+        coll.update_one(
+            doc! { "guild_id": &guild_id  },
+            versioned(doc! { "$set": { "webhook_url": webhook_url } }),
+            None,
+        )
+        .await
+        .wrap_err("update webhook url")?;
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn find_member(&self, guild_id: SnowflakeId, user_id: SnowflakeId) -> Result<MemberData> {
+        let member = self
+            .member_coll()
+            .find_one(doc! { "guild_id": &guild_id, "user_id": &user_id }, None)
+            .await
+            .wrap_err("find member")?;
+        Ok(member.unwrap_or_else(|| MemberData::new(guild_id, user_id)))
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn find_members(&self, guild_id: SnowflakeId) -> Result<Vec<MemberData>> {
+        self.member_coll()
+            .find(doc! { "guild_id": &guild_id }, None)
+            .await
+            .wrap_err("find members")?
+            .try_collect()
+            .await
+            .wrap_err("collect members")
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn add_to_balance(
+        &self,
+        guild_id: SnowflakeId,
+        user_id: SnowflakeId,
+        amount: i64,
+    ) -> Result<i64> {
+        let member = self
+            .member_coll()
+            .find_one_and_update(
+                doc! { "guild_id": &guild_id, "user_id": &user_id },
+                doc! { "$inc": { "balance": amount } },
+                FindOneAndUpdateOptions::builder()
+                    .upsert(true)
+                    .return_document(ReturnDocument::After)
+                    .build(),
+            )
+            .await
+            .wrap_err("update balance")?
+            .wrap_err("member not found")?;
+        Ok(member.balance)
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn transfer(
+        &self,
+        guild_id: SnowflakeId,
+        from: SnowflakeId,
+        to: SnowflakeId,
+        amount: i64,
+        overdraw: bool,
+    ) -> Result<Option<i64>> {
+        let mut take_filter = doc! { "guild_id": &guild_id, "user_id": &from };
+        if !overdraw {
+            // checked by the same write that takes the money, so concurrent transfers can't overdraw
+            take_filter.insert("balance", doc! { "$gte": amount });
+        }
+        let take = doc! { "$inc": { "balance": -amount } };
+        // a member without a document has nothing, they can only be created when overdrawing
+        let take_options = FindOneAndUpdateOptions::builder()
+            .upsert(overdraw)
+            .return_document(ReturnDocument::After)
+            .build();
+        let give_filter = doc! { "guild_id": &guild_id, "user_id": &to };
+        let give = doc! { "$inc": { "balance": amount } };
+
+        if !self.transactions {
+            let member = self
+                .member_coll()
+                .find_one_and_update(take_filter, take, take_options)
+                .await
+                .wrap_err("take money")?;
+            let member = match member {
+                Some(member) => member,
+                None => return Ok(None),
+            };
+            self.member_coll()
+                .update_one(
+                    give_filter,
+                    give,
+                    UpdateOptions::builder().upsert(true).build(),
+                )
+                .await
+                .wrap_err("give money")?;
+            return Ok(Some(member.balance));
+        }
+
+        let mut session = self
+            .db
+            .client()
+            .start_session(None)
+            .await
+            .wrap_err("start session")?;
+        session
+            .start_transaction(None)
+            .await
+            .wrap_err("start transaction")?;
+
+        // the transaction is aborted when the session is dropped after an error
+        let member = self
+            .member_coll()
+            .find_one_and_update_with_session(take_filter, take, take_options, &mut session)
+            .await
+            .wrap_err("take money")?;
+        let member = match member {
+            Some(member) => member,
+            None => return Ok(None),
+        };
+        self.member_coll()
+            .update_one_with_session(
+                give_filter,
+                give,
+                UpdateOptions::builder().upsert(true).build(),
+                &mut session,
+            )
+            .await
+            .wrap_err("give money")?;
+
+        session
+            .commit_transaction()
+            .await
+            .wrap_err("commit transfer")?;
+        Ok(Some(member.balance))
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn set_lawyer_fee(
+        &self,
+        guild_id: SnowflakeId,
+        lawyer: SnowflakeId,
+        fee: Option<LawyerFee>,
+    ) -> Result<()> {
+        let update = match fee {
+            Some(fee) => {
+                doc! { "$set": { "lawyer_fee": bson::to_bson(&fee).wrap_err("invalid bson for fee")? } }
+            }
+            None => doc! { "$unset": { "lawyer_fee": "" } },
+        };
+        self.member_coll()
+            .update_one(
+                doc! { "guild_id": &guild_id, "user_id": &lawyer },
+                update,
+                UpdateOptions::builder().upsert(fee.is_some()).build(),
+            )
+            .await
+            .wrap_err("set lawyer fee")?;
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self, questions))]
+    async fn set_bar_questions(
+        &self,
+        guild_id: SnowflakeId,
+        questions: &[BarQuestion],
+    ) -> Result<()> {
+        let _ = self.find_or_insert_state(guild_id).await?;
+        let coll = self.state_coll();
+        coll.update_one(
+            doc! { "guild_id": &guild_id },
+            versioned(doc! { "$set": { "bar_questions": bson::to_bson(questions).wrap_err("invalid bson for questions")? } }),
+            None,
+        )
+        .await
+        .wrap_err("set bar questions")?;
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn admit_lawyer(&self, guild_id: SnowflakeId, lawyer: SnowflakeId) -> Result<()> {
+        let _ = self.find_or_insert_state(guild_id).await?;
+        let coll = self.state_coll();
+        coll.update_one(
+            doc! { "guild_id": &guild_id },
+            versioned(doc! { "$addToSet": { "admitted_lawyers": lawyer } }),
+            None,
+        )
+        .await
+        .wrap_err("admit lawyer")?;
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn set_jury_pool_member(
+        &self,
+        guild_id: SnowflakeId,
+        user_id: SnowflakeId,
+        member: bool,
+    ) -> Result<()> {
+        let _ = self.find_or_insert_state(guild_id).await?;
+        let update = if member {
+            doc! { "$addToSet": { "jury_pool": user_id } }
+        } else {
+            doc! { "$pull": { "jury_pool": user_id } }
+        };
+        self.state_coll()
+            .update_one(doc! { "guild_id": &guild_id }, versioned(update), None)
+            .await
+            .wrap_err("set jury pool member")?;
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn add_gag_order(&self, guild_id: SnowflakeId, order: &GagOrder) -> Result<()> {
+        let _ = self.find_or_insert_state(guild_id).await?;
+        self.state_coll()
+            .update_one(
+                doc! { "guild_id": &guild_id },
+                versioned(doc! {
+                    "$addToSet": { "gag_orders": bson::to_bson(order).wrap_err("invalid bson for gag order")? }
+                }),
+                None,
+            )
+            .await
+            .wrap_err("add gag order")?;
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn remove_gag_orders(&self, guild_id: SnowflakeId, lawsuit_id: Uuid) -> Result<()> {
+        let _ = self.find_or_insert_state(guild_id).await?;
+        self.state_coll()
+            .update_one(
+                doc! { "guild_id": &guild_id },
+                versioned(doc! { "$pull": { "gag_orders": { "lawsuit_id": lawsuit_id } } }),
+                None,
+            )
+            .await
+            .wrap_err("remove gag orders")?;
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn set_litigant_banned(
+        &self,
+        guild_id: SnowflakeId,
+        user_id: SnowflakeId,
+        banned: bool,
+    ) -> Result<()> {
+        let _ = self.find_or_insert_state(guild_id).await?;
+        let update = if banned {
+            doc! { "$addToSet": { "banned_litigants": user_id } }
+        } else {
+            doc! { "$pull": { "banned_litigants": user_id } }
+        };
+        self.state_coll()
+            .update_one(doc! { "guild_id": &guild_id }, versioned(update), None)
+            .await
+            .wrap_err("set litigant banned")?;
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self, text))]
+    async fn set_message_template(
+        &self,
+        guild_id: SnowflakeId,
+        template: Template,
+        text: Option<&str>,
+    ) -> Result<()> {
+        let _ = self.find_or_insert_state(guild_id).await?;
+        let coll = self.state_coll();
+        let field = format!("message_templates.{}", template.key());
+        let update = match text {
+            Some(text) => doc! { "$set": { field: text } },
+            None => doc! { "$unset": { field: "" } },
+        };
+        coll.update_one(doc! { "guild_id": &guild_id  }, versioned(update), None)
+            .await
+            .wrap_err("update message template")?;
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self, api_token_hash))]
+    async fn set_api_token_hash(
+        &self,
+        guild_id: SnowflakeId,
+        api_token_hash: Option<&str>,
+    ) -> Result<()> {
+        let _ = self.find_or_insert_state(guild_id).await?;
+        let coll = self.state_coll();
+        coll.update_one(
+            doc! { "guild_id": &guild_id  },
+            versioned(doc! { "$set": { "api_token_hash": api_token_hash } }),
+            None,
+        )
+        .await
+        .wrap_err("update api token")?;
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self, calendar_token_hash))]
+    async fn set_calendar_token_hash(
+        &self,
+        guild_id: SnowflakeId,
+        calendar_token_hash: Option<&str>,
+    ) -> Result<()> {
+        let _ = self.find_or_insert_state(guild_id).await?;
+        let coll = self.state_coll();
+        coll.update_one(
+            doc! { "guild_id": &guild_id  },
+            versioned(doc! { "$set": { "calendar_token_hash": calendar_token_hash } }),
+            None,
+        )
+        .await
+        .wrap_err("update calendar token")?;
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn set_court_role(
+        &self,
+        guild_id: SnowflakeId,
+        court_role: CourtRole,
+        role_id: SnowflakeId,
+    ) -> Result<()> {
+        let _ = self.find_or_insert_state(guild_id).await?;
+        let coll = self.state_coll();
+        let mut update = Document::new();
+        update.insert(court_role.state_field(), role_id);
+        coll.update_one(
+            doc! { "guild_id": &guild_id  },
+            versioned(doc! { "$set": update }),
+            None,
+        )
+        .await
+        .wrap_err("update court role")?;
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn set_prison_tier(&self, guild_id: SnowflakeId, tier: &PrisonTier) -> Result<()> {
+        self.remove_prison_tier(guild_id, &tier.name).await?;
+        let coll = self.state_coll();
+        coll.update_one(
+            doc! { "guild_id": &guild_id  },
+            versioned(doc! { "$push": { "prison_tiers": bson::to_bson(tier).wrap_err("invalid bson for prison tier")? } }),
+            None,
+        )
+        .await
+        .wrap_err("push prison tier")?;
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn remove_prison_tier(&self, guild_id: SnowflakeId, name: &str) -> Result<()> {
+        let _ = self.find_or_insert_state(guild_id).await?;
+        let coll = self.state_coll();
+        coll.update_one(
+            doc! { "guild_id": &guild_id  },
+            versioned(doc! { "$pull": { "prison_tiers": { "name": name } } }),
+            None,
+        )
+        .await
+        .wrap_err("pull prison tier")?;
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn add_court_room(&self, guild_id: SnowflakeId, room: &CourtRoom) -> Result<()> {
+        let _ = self.find_or_insert_state(guild_id).await?;
+        let coll = self.state_coll();
+        coll.update_one(
+            doc! { "guild_id": &guild_id  },
+            versioned(doc! { "$push": { "court_rooms": bson::to_bson(room).wrap_err("invalid bson for room")? }}),
+            None,
+        )
+        .await
+        .wrap_err("push court room")?;
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn remove_court_rooms(&self, guild_id: SnowflakeId) -> Result<()> {
+        let _ = self.find_or_insert_state(guild_id).await?;
+        let coll = self.state_coll();
+        coll.update_one(
+            doc! { "guild_id": &guild_id  },
+            versioned(doc! { "$set": { "court_rooms": [] } }),
+            None,
+        )
+        .await
+        .wrap_err("remove court rooms")?;
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn add_lawsuit(&self, lawsuit: &Lawsuit) -> Result<()> {
+        let coll = self.lawsuit_coll();
+
+        coll.insert_one(lawsuit, None)
+            .await
+            .wrap_err("insert lawsuit")?;
+
+        self.add_to_counter(LAWSUITS_COUNTER, 1).await?;
+        self.add_to_counter(OPEN_LAWSUITS_COUNTER, lawsuit.verdict.is_none().into())
+            .await?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn delete_lawsuits(&self, guild_id: SnowflakeId) -> Result<u64> {
+        let _ = self.find_or_insert_state(guild_id).await?;
+        let open_lawsuits = self.count_open_lawsuits_of(guild_id).await?;
+
+        let deleted = self
+            .lawsuit_coll()
+            .delete_many(doc! { "guild_id": &guild_id }, None)
+            .await
+            .wrap_err("delete lawsuits")?
+            .deleted_count;
+        self.add_to_counter(OPEN_LAWSUITS_COUNTER, -open_lawsuits)
+            .await?;
+
+        self.state_coll()
+            .update_one(
+                doc! { "guild_id": &guild_id },
+                versioned(doc! { "$set": { "court_rooms.$[].ongoing_lawsuit": false } }),
+                None,
+            )
+            .await
+            .wrap_err("free court rooms")?;
+        Ok(deleted)
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn find_lawsuits(&self, guild_id: SnowflakeId) -> Result<Vec<Lawsuit>> {
+        let coll = self.lawsuit_coll();
+
+        coll.find(doc! { "guild_id": guild_id }, None)
+            .await
+            .wrap_err("find lawsuits")?
+            .try_collect()
+            .await
+            .wrap_err("collect lawsuits")
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn find_open_lawsuits(&self, guild_id: SnowflakeId) -> Result<Vec<Lawsuit>> {
+        let coll = self.lawsuit_coll();
+
+        coll.find(doc! { "guild_id": guild_id, "verdict": null }, None)
+            .await
+            .wrap_err("find open lawsuits")?
+            .try_collect()
+            .await
+            .wrap_err("collect open lawsuits")
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn find_open_lawsuits_by_reason(
+        &self,
+        guild_id: SnowflakeId,
+        prefix: &str,
+        limit: usize,
+    ) -> Result<Vec<Lawsuit>> {
+        let coll = self.lawsuit_coll();
+
+        let filter = doc! {
+            "guild_id": guild_id,
+            "verdict": null,
+            "reason": { "$regex": format!("^{}", escape_regex(prefix)), "$options": "i" },
+        };
+        let options = FindOptions::builder().limit(limit as i64).build();
+
+        coll.find(filter, options)
+            .await
+            .wrap_err("find open lawsuits by reason")?
+            .try_collect()
+            .await
+            .wrap_err("collect open lawsuits by reason")
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn search_closed_lawsuits(
+        &self,
+        guild_id: SnowflakeId,
+        query: &str,
+        limit: usize,
+    ) -> Result<Vec<Lawsuit>> {
+        let coll = self.lawsuit_coll();
+
+        let filter = doc! {
+            "guild_id": guild_id,
+            "verdict": { "$ne": null },
+            "sealed": { "$ne": true },
+            "$text": { "$search": query },
+        };
+        let options = FindOptions::builder()
+            .projection(doc! { "score": { "$meta": "textScore" } })
+            .sort(doc! { "score": { "$meta": "textScore" } })
+            .limit(limit as i64)
+            .build();
+
+        coll.find(filter, options)
+            .await
+            .wrap_err("search lawsuits")?
+            .try_collect()
+            .await
+            .wrap_err("collect found lawsuits")
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn find_lawsuits_page(
+        &self,
+        guild_id: SnowflakeId,
+        after: Option<Uuid>,
+        limit: usize,
+    ) -> Result<Page<Lawsuit, Uuid>> {
+        let coll = self.lawsuit_coll();
+
+        let mut filter = doc! { "guild_id": guild_id };
+        if let Some(after) = after {
+            filter.insert("id", doc! { "$gt": after });
+        }
+        let options = FindOptions::builder()
+            .sort(doc! { "id": 1 })
+            .limit((limit + 1) as i64)
+            .build();
+
+        let lawsuits = coll
+            .find(filter, options)
+            .await
+            .wrap_err("find lawsuits page")?
+            .try_collect()
+            .await
+            .wrap_err("collect lawsuits page")?;
+
+        Ok(Page::new(lawsuits, limit, |lawsuit| lawsuit.id))
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn count_open_lawsuits(&self) -> Result<Vec<(SnowflakeId, u64)>> {
+        let coll = self.lawsuit_coll();
+
+        count_per_guild(&coll, doc! { "verdict": null }).await
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn set_court_room_ongoing(
+        &self,
+        guild_id: SnowflakeId,
+        channel_id: SnowflakeId,
+        ongoing_lawsuit: bool,
+    ) -> Result<()> {
+        let _ = self.find_or_insert_state(guild_id).await?;
+        let coll = self.state_coll();
+
+        let mut set = doc! { "court_rooms.$.ongoing_lawsuit": ongoing_lawsuit };
+        if ongoing_lawsuit {
+            set.insert("court_rooms.$.last_used", DateTime::now());
+            set.insert("court_rooms.$.nudges", 0);
+        }
+
+        coll.update_one(
+            doc! { "guild_id": &guild_id, "court_rooms.channel_id": channel_id  },
+            versioned(doc! { "$set": set }),
+            None,
+        )
+        .await
+        .wrap_err("set courtroom")?;
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn claim_court_room(
+        &self,
+        guild_id: SnowflakeId,
+        channel_id: SnowflakeId,
+        version: u64,
+    ) -> Result<bool> {
+        let result = self
+            .state_coll()
+            .update_one(
+                doc! {
+                    "guild_id": &guild_id,
+                    "version": version as i64,
+                    "court_rooms.channel_id": channel_id,
+                },
+                versioned(doc! {
+                    "$set": {
+                        "court_rooms.$.ongoing_lawsuit": true,
+                        "court_rooms.$.last_used": DateTime::now(),
+                        "court_rooms.$.nudges": 0,
+                    }
+                }),
+                None,
+            )
+            .await
+            .wrap_err("claim court room")?;
+        Ok(result.modified_count == 1)
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn set_room_activity(
+        &self,
+        guild_id: SnowflakeId,
+        channel_id: SnowflakeId,
+        last_activity: DateTime,
+    ) -> Result<()> {
+        let coll = self.state_coll();
+        coll.update_one(
+            doc! { "guild_id": &guild_id, "court_rooms.channel_id": channel_id  },
+            versioned(doc! {
+                "$set": { "court_rooms.$.last_activity": last_activity, "court_rooms.$.nudges": 0 }
+            }),
+            None,
+        )
+        .await
+        .wrap_err("set room activity")?;
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn set_room_nudges(
+        &self,
+        guild_id: SnowflakeId,
+        channel_id: SnowflakeId,
+        nudges: u32,
+    ) -> Result<()> {
+        let coll = self.state_coll();
+        coll.update_one(
+            doc! { "guild_id": &guild_id, "court_rooms.channel_id": channel_id  },
+            versioned(doc! { "$set": { "court_rooms.$.nudges": nudges } }),
+            None,
+        )
+        .await
+        .wrap_err("set room nudges")?;
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn set_room_closed_to_public(
+        &self,
+        guild_id: SnowflakeId,
+        channel_id: SnowflakeId,
+        closed: bool,
+    ) -> Result<()> {
+        let coll = self.state_coll();
+        coll.update_one(
+            doc! { "guild_id": &guild_id, "court_rooms.channel_id": channel_id  },
+            versioned(doc! { "$set": { "court_rooms.$.closed_to_public": closed } }),
+            None,
+        )
+        .await
+        .wrap_err("set room closed to public")?;
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn set_lawsuit_verdict(
+        &self,
+        guild_id: SnowflakeId,
+        lawsuit_id: Uuid,
+        verdict: Option<&str>,
+    ) -> Result<()> {
+        let coll = self.lawsuit_coll();
+        let closed_at = verdict.map(|_| DateTime::now());
+
+        let previous = coll
+            .find_one_and_update(
+                doc! { "guild_id": &guild_id, "id": lawsuit_id },
+                doc! { "$set": { "verdict": verdict, "closed_at": closed_at } },
+                None,
+            )
+            .await
+            .wrap_err("set lawsuit verdict")?;
+
+        if let Some(previous) = previous {
+            let change = match (previous.verdict.is_some(), verdict.is_some()) {
+                (false, true) => -1,
+                (true, false) => 1,
+                _ => 0,
+            };
+            self.add_to_counter(OPEN_LAWSUITS_COUNTER, change).await?;
+        }
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn close_lawsuit(&self, guild_id: SnowflakeId, lawsuit: &Lawsuit) -> Result<()> {
+        let filter = doc! { "guild_id": &guild_id, "id": lawsuit.id };
+        let details =
+            bson::to_bson(&lawsuit.verdict_details).wrap_err("invalid verdict details")?;
+        let update = doc! { "$set": {
+            "verdict": lawsuit.verdict.as_deref(),
+            "closed_at": DateTime::now(),
+            "verdict_details": details,
+            "sealed": lawsuit.sealed,
+            "overturned": lawsuit.overturned,
+        } };
+
+        if !self.transactions {
+            let previous = self
+                .lawsuit_coll()
+                .find_one_and_update(filter, update, None)
+                .await
+                .wrap_err("set lawsuit verdict")?;
+            if previous.map_or(false, |previous| previous.verdict.is_none()) {
+                self.add_to_counter(OPEN_LAWSUITS_COUNTER, -1).await?;
+            }
+            return self
+                .set_court_room_ongoing(guild_id, lawsuit.court_room, false)
+                .await;
+        }
+
+        let mut session = self
+            .db
+            .client()
+            .start_session(None)
+            .await
+            .wrap_err("start session")?;
+        session
+            .start_transaction(None)
+            .await
+            .wrap_err("start transaction")?;
+
+        // the transaction is aborted when the session is dropped after an error
+        let previous = self
+            .lawsuit_coll()
+            .find_one_and_update_with_session(filter, update, None, &mut session)
+            .await
+            .wrap_err("set lawsuit verdict")?;
+        self.state_coll()
+            .update_one_with_session(
+                doc! { "guild_id": &guild_id, "court_rooms.channel_id": lawsuit.court_room },
+                versioned(doc! { "$set": { "court_rooms.$.ongoing_lawsuit": false } }),
+                None,
+                &mut session,
+            )
+            .await
+            .wrap_err("set courtroom")?;
+        if previous.map_or(false, |previous| previous.verdict.is_none()) {
+            self.counter_coll()
+                .update_one_with_session(
+                    doc! { "_id": OPEN_LAWSUITS_COUNTER },
+                    doc! { "$inc": { "count": -1 } },
+                    UpdateOptions::builder().upsert(true).build(),
+                    &mut session,
+                )
+                .await
+                .wrap_err("update open lawsuits counter")?;
+        }
+
+        session
+            .commit_transaction()
+            .await
+            .wrap_err("commit verdict")?;
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self, evidence))]
+    async fn add_evidence(
+        &self,
+        guild_id: SnowflakeId,
+        lawsuit_id: Uuid,
+        evidence: &Evidence,
+    ) -> Result<()> {
+        let coll = self.lawsuit_coll();
+
+        coll.update_one(
+            doc! { "guild_id": &guild_id, "id": lawsuit_id },
+            doc! { "$push": { "evidence": bson::to_bson(evidence).wrap_err("invalid bson for evidence")? } },
+            None,
+        )
+        .await
+        .wrap_err("add evidence")?;
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn add_witness(
+        &self,
+        guild_id: SnowflakeId,
+        lawsuit_id: Uuid,
+        witness: &Witness,
+    ) -> Result<()> {
+        let coll = self.lawsuit_coll();
+
+        coll.update_one(
+            doc! { "guild_id": &guild_id, "id": lawsuit_id },
+            doc! { "$push": { "witnesses": bson::to_bson(witness).wrap_err("invalid bson for witness")? } },
+            None,
+        )
+        .await
+        .wrap_err("add witness")?;
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn answer_subpoena(
+        &self,
+        guild_id: SnowflakeId,
+        lawsuit_id: Uuid,
+        witness_id: SnowflakeId,
+        response: SubpoenaResponse,
+    ) -> Result<bool> {
+        let coll = self.lawsuit_coll();
+        let pending =
+            bson::to_bson(&SubpoenaResponse::Pending).wrap_err("invalid bson for response")?;
+        let response = bson::to_bson(&response).wrap_err("invalid bson for response")?;
+
+        let result = coll
+            .update_one(
+                doc! {
+                    "guild_id": &guild_id,
+                    "id": lawsuit_id,
+                    "witnesses": { "$elemMatch": { "user_id": witness_id, "response": pending } },
+                },
+                doc! { "$set": { "witnesses.$.response": response } },
+                None,
+            )
+            .await
+            .wrap_err("answer subpoena")?;
+        Ok(result.modified_count == 1)
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn set_jury_tally(
+        &self,
+        guild_id: SnowflakeId,
+        lawsuit_id: Uuid,
+        tally: &JuryTally,
+    ) -> Result<()> {
+        let coll = self.lawsuit_coll();
+
+        coll.update_one(
+            doc! { "guild_id": &guild_id, "id": lawsuit_id },
+            doc! { "$set": { "jury_tally": bson::to_bson(tally).wrap_err("invalid bson for jury tally")? } },
+            None,
+        )
+        .await
+        .wrap_err("set jury tally")?;
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn set_lawsuit_hearing(
+        &self,
+        guild_id: SnowflakeId,
+        lawsuit_id: Uuid,
+        hearing_at: Option<DateTime>,
+    ) -> Result<()> {
+        self.lawsuit_coll()
+            .update_one(
+                doc! { "guild_id": &guild_id, "id": lawsuit_id },
+                doc! { "$set": { "hearing_at": hearing_at } },
+                None,
+            )
+            .await
+            .wrap_err("set lawsuit hearing")?;
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn add_citation(
+        &self,
+        guild_id: SnowflakeId,
+        lawsuit_id: Uuid,
+        precedent_id: Uuid,
+    ) -> Result<()> {
+        let coll = self.lawsuit_coll();
+        coll.update_one(
+            doc! { "guild_id": &guild_id, "id": lawsuit_id },
+            doc! { "$addToSet": { "cites": precedent_id } },
+            None,
+        )
+        .await
+        .wrap_err("add citation")?;
+        coll.update_one(
+            doc! { "guild_id": &guild_id, "id": precedent_id },
+            doc! { "$addToSet": { "cited_by": lawsuit_id } },
+            None,
+        )
+        .await
+        .wrap_err("add citing lawsuit")?;
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn set_lawsuit_jurors(
+        &self,
+        guild_id: SnowflakeId,
+        lawsuit_id: Uuid,
+        jurors: &[SnowflakeId],
+    ) -> Result<()> {
+        self.lawsuit_coll()
+            .update_one(
+                doc! { "guild_id": &guild_id, "id": lawsuit_id },
+                doc! { "$set": { "jurors": jurors } },
+                None,
+            )
+            .await
+            .wrap_err("set lawsuit jurors")?;
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn add_billing(
+        &self,
+        guild_id: SnowflakeId,
+        lawsuit_id: Uuid,
+        billing: &Billing,
+    ) -> Result<()> {
+        let coll = self.lawsuit_coll();
+
+        coll.update_one(
+            doc! { "guild_id": &guild_id, "id": lawsuit_id },
+            doc! { "$push": { "billing": bson::to_bson(billing).wrap_err("invalid bson for billing")? } },
+            None,
+        )
+        .await
+        .wrap_err("add billing")?;
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn add_contempt(
+        &self,
+        guild_id: SnowflakeId,
+        lawsuit_id: Uuid,
+        contempt: &Contempt,
+    ) -> Result<()> {
+        let coll = self.lawsuit_coll();
+
+        coll.update_one(
+            doc! { "guild_id": &guild_id, "id": lawsuit_id },
+            doc! { "$push": { "contempts": bson::to_bson(contempt).wrap_err("invalid bson for contempt")? } },
+            None,
+        )
+        .await
+        .wrap_err("add contempt")?;
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn add_ejected(
+        &self,
+        guild_id: SnowflakeId,
+        lawsuit_id: Uuid,
+        user_id: SnowflakeId,
+    ) -> Result<()> {
+        let coll = self.lawsuit_coll();
+        coll.update_one(
+            doc! { "guild_id": &guild_id, "id": lawsuit_id },
+            doc! { "$addToSet": { "ejected": user_id } },
+            None,
+        )
+        .await
+        .wrap_err("add ejected")?;
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn set_lawsuit_fine(
+        &self,
+        guild_id: SnowflakeId,
+        lawsuit_id: Uuid,
+        fine: &Fine,
+    ) -> Result<()> {
+        let coll = self.lawsuit_coll();
+
+        coll.update_one(
+            doc! { "guild_id": &guild_id, "id": lawsuit_id },
+            doc! { "$set": { "fine": bson::to_bson(fine).wrap_err("invalid bson for fine")? } },
+            None,
+        )
+        .await
+        .wrap_err("set lawsuit fine")?;
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn trash_guild(&self, guild_id: SnowflakeId) -> Result<()> {
+        let state = self.find_or_insert_state(guild_id).await?;
+        let lawsuits = self.find_lawsuits(guild_id).await?;
+        let open_lawsuits = count_open(&lawsuits);
+        let trashed = TrashedState {
+            guild_id,
+            state,
+            lawsuits,
+            members: self.find_members(guild_id).await?,
+            deleted_at: DateTime::now(),
+        };
+
+        self.trash_coll()
+            .replace_one(
+                doc! { "guild_id": &guild_id },
+                &trashed,
+                ReplaceOptions::builder().upsert(true).build(),
+            )
+            .await
+            .wrap_err("move state to trash")?;
+
+        self.state_coll()
+            .delete_one(doc! { "guild_id": &guild_id }, None)
+            .await
+            .wrap_err("delete guild")?;
+        self.lawsuit_coll()
+            .delete_many(doc! { "guild_id": &guild_id }, None)
+            .await
+            .wrap_err("delete lawsuits")?;
+        self.member_coll()
+            .delete_many(doc! { "guild_id": &guild_id }, None)
+            .await
+            .wrap_err("delete members")?;
+        self.add_to_counter(OPEN_LAWSUITS_COUNTER, -open_lawsuits)
+            .await?;
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn restore_guild(&self, guild_id: SnowflakeId) -> Result<Option<State>> {
+        let oldest = DateTime::from_millis(
+            DateTime::now().timestamp_millis() - TRASH_GRACE_PERIOD.as_millis() as i64,
+        );
+
+        let trashed = self
+            .trash_coll()
+            .find_one(
+                doc! { "guild_id": &guild_id, "deleted_at": { "$gt": oldest } },
+                None,
+            )
+            .await
+            .wrap_err("find trashed state")?;
+
+        let trashed = match trashed {
+            Some(trashed) => trashed,
+            None => return Ok(None),
+        };
+
+        self.state_coll()
+            .replace_one(
+                doc! { "guild_id": &guild_id },
+                &trashed.state,
+                ReplaceOptions::builder().upsert(true).build(),
+            )
+            .await
+            .wrap_err("restore state")?;
+
+        let replaced_open_lawsuits = self.count_open_lawsuits_of(guild_id).await?;
+        self.lawsuit_coll()
+            .delete_many(doc! { "guild_id": &guild_id }, None)
+            .await
+            .wrap_err("delete lawsuits")?;
+        if !trashed.lawsuits.is_empty() {
+            self.lawsuit_coll()
+                .insert_many(&trashed.lawsuits, None)
+                .await
+                .wrap_err("restore lawsuits")?;
+        }
+        self.add_to_counter(
+            OPEN_LAWSUITS_COUNTER,
+            count_open(&trashed.lawsuits) - replaced_open_lawsuits,
+        )
+        .await?;
+
+        self.member_coll()
+            .delete_many(doc! { "guild_id": &guild_id }, None)
+            .await
+            .wrap_err("delete members")?;
+        if !trashed.members.is_empty() {
+            self.member_coll()
+                .insert_many(&trashed.members, None)
+                .await
+                .wrap_err("restore members")?;
+        }
+
+        self.purge_trash(guild_id).await?;
+
+        Ok(Some(trashed.state))
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn purge_trash(&self, guild_id: SnowflakeId) -> Result<()> {
+        self.trash_coll()
+            .delete_many(doc! { "guild_id": &guild_id }, None)
+            .await
+            .wrap_err("purge trash")?;
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn erase_user(
+        &self,
+        guild_id: SnowflakeId,
+        user_id: SnowflakeId,
+    ) -> Result<ErasureReport> {
+        let mut report = ErasureReport::default();
+        let mut state = self.find_or_insert_state(guild_id).await?;
+        state.anonymize_user(user_id, &mut report);
+
+        report.registered_judge = self.unregister_judge(guild_id, user_id).await?;
+
+        let member = self
+            .member_coll()
+            .find_one_and_delete(doc! { "guild_id": &guild_id, "user_id": &user_id }, None)
+            .await
+            .wrap_err("erase member")?;
+        if let Some(member) = member {
+            report.balance = member.balance != 0;
+            report.lawyer_fee = member.lawyer_fee.is_some();
+        }
+
+        self.state_coll()
+            .update_one(
+                doc! { "guild_id": &guild_id },
+                versioned(doc! {
+                    "$set": { "appeal_judge": state.appeal_judge },
+                    "$pull": {
+                        "admitted_lawyers": user_id,
+                        "jury_pool": user_id,
+                        "banned_litigants": user_id,
+                        "gag_orders": { "user_id": user_id },
+                    },
+                }),
+                None,
+            )
+            .await
+            .wrap_err("anonymize state")?;
+
+        // everything that `Lawsuit::anonymize_user` changes, evidence can also come from spectators
+        let participant = doc! { "guild_id": guild_id, "$or": [
+            { "plaintiff": user_id },
+            { "accused": user_id },
+            { "judge": user_id },
+            { "plaintiff_lawyer": user_id },
+            { "accused_lawyer": user_id },
+            { "jurors": user_id },
+            { "billing.lawyer": user_id },
+            { "billing.client": user_id },
+            { "evidence.author": user_id },
+            { "evidence.added_by": user_id },
+            { "witnesses.user_id": user_id },
+            { "witnesses.added_by": user_id },
+            { "contempts.user_id": user_id },
+            { "ejected": user_id },
+        ] };
+        let lawsuits: Vec<Lawsuit> = self
+            .lawsuit_coll()
+            .find(participant, None)
+            .await
+            .wrap_err("find lawsuits of user")?
+            .try_collect()
+            .await
+            .wrap_err("collect lawsuits of user")?;
+        for mut lawsuit in lawsuits {
+            lawsuit.anonymize_user(user_id);
+            self.lawsuit_coll()
+                .replace_one(
+                    doc! { "guild_id": guild_id, "id": lawsuit.id },
+                    &lawsuit,
+                    None,
+                )
+                .await
+                .wrap_err("anonymize lawsuit")?;
+            report.lawsuits += 1;
+        }
+
+        let participant = doc! { "guild_id": guild_id, "$or": [
+            { "started_by": user_id },
+            { "results.candidate": user_id },
+        ] };
+        let elections: Vec<Election> = self
+            .election_coll()
+            .find(participant, None)
+            .await
+            .wrap_err("find elections of user")?
+            .try_collect()
+            .await
+            .wrap_err("collect elections of user")?;
+        for mut election in elections {
+            election.anonymize_user(user_id);
+            self.election_coll()
+                .replace_one(
+                    doc! { "guild_id": guild_id, "id": election.id },
+                    &election,
+                    None,
+                )
+                .await
+                .wrap_err("anonymize election")?;
+            report.elections += 1;
+        }
+
+        report.prison_entries = self
+            .prison_coll()
+            .delete_many(doc! { "guild_id": guild_id, "user_id": user_id }, None)
+            .await
+            .wrap_err("erase prison entry")?
+            .deleted_count;
+        report.arrests = self
+            .prison_coll()
+            .update_many(
+                doc! { "guild_id": guild_id, "arrested_by": user_id },
+                doc! { "$set": { "arrested_by": null } },
+                None,
+            )
+            .await
+            .wrap_err("anonymize arrests")?
+            .modified_count;
+        report.prison_records = self
+            .prison_history_coll()
+            .delete_many(doc! { "guild_id": guild_id, "user_id": user_id }, None)
+            .await
+            .wrap_err("erase prison records")?
+            .deleted_count;
+        report.jobs = self
+            .job_coll()
+            .delete_many(doc! { "guild_id": guild_id, "kind.user_id": user_id }, None)
+            .await
+            .wrap_err("erase jobs")?
+            .deleted_count;
+
+        // the cleared state still contains the user, erasing has to win over being able to undo
+        self.purge_trash(guild_id).await?;
+
+        Ok(report)
+    }
+
+    #[tracing::instrument(skip(self, data))]
+    async fn replace_guild(&self, data: &GuildData) -> Result<()> {
+        let guild_id = data.state.guild_id;
+
+        if self.transactions {
+            return self.replace_guild_in_transaction(data).await;
+        }
+
+        self.state_coll()
+            .replace_one(
+                doc! { "guild_id": &guild_id },
+                &data.state,
+                ReplaceOptions::builder().upsert(true).build(),
+            )
+            .await
+            .wrap_err("replace state")?;
+
+        let replaced_open_lawsuits = self.count_open_lawsuits_of(guild_id).await?;
+        self.lawsuit_coll()
+            .delete_many(doc! { "guild_id": &guild_id }, None)
+            .await
+            .wrap_err("delete lawsuits")?;
+        if !data.lawsuits.is_empty() {
+            self.lawsuit_coll()
+                .insert_many(&data.lawsuits, None)
+                .await
+                .wrap_err("insert lawsuits")?;
+        }
+        self.add_to_counter(
+            OPEN_LAWSUITS_COUNTER,
+            count_open(&data.lawsuits) - replaced_open_lawsuits,
+        )
+        .await?;
+
+        self.prison_coll()
+            .delete_many(doc! { "guild_id": &guild_id }, None)
+            .await
+            .wrap_err("delete prison entries")?;
+        self.insert_prison_entries(&data.prison_entries).await?;
+
+        self.prison_history_coll()
+            .delete_many(doc! { "guild_id": &guild_id }, None)
+            .await
+            .wrap_err("delete prison records")?;
+        self.add_prison_records(&data.prison_history).await?;
+
+        self.job_coll()
+            .delete_many(doc! { "guild_id": &guild_id }, None)
+            .await
+            .wrap_err("delete jobs")?;
+        if !data.jobs.is_empty() {
+            self.job_coll()
+                .insert_many(&data.jobs, None)
+                .await
+                .wrap_err("insert jobs")?;
+        }
+
+        self.member_coll()
+            .delete_many(doc! { "guild_id": &guild_id }, None)
+            .await
+            .wrap_err("delete members")?;
+        if !data.members.is_empty() {
+            self.member_coll()
+                .insert_many(&data.members, None)
+                .await
+                .wrap_err("insert members")?;
+        }
+
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn add_to_prison(
+        &self,
+        guild_id: SnowflakeId,
+        user_id: SnowflakeId,
+        tier: Option<&str>,
+        release_at: Option<DateTime>,
+        arrested_by: Option<SnowflakeId>,
+    ) -> Result<()> {
+        let coll = self.prison_coll();
+
+        coll.update_one(
+            doc! { "guild_id": guild_id, "user_id": user_id },
+            doc! {
+                "$setOnInsert": {
+                    "guild_id": guild_id, "user_id": user_id, "arrested_by": arrested_by,
+                },
+                "$set": { "tier": tier, "release_at": release_at, "parole_until": null },
+            },
+            UpdateOptions::builder().upsert(true).build(),
+        )
+        .await
+        .wrap_err("add to prison collection")?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn remove_from_prison(&self, guild_id: SnowflakeId, user_id: SnowflakeId) -> Result<()> {
+        let coll = self.prison_coll();
+
+        coll.delete_one(doc! { "guild_id": guild_id, "user_id": user_id }, None)
+            .await
+            .wrap_err("remove from prison")?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn find_prison_entry(
+        &self,
+        guild_id: SnowflakeId,
+        user_id: SnowflakeId,
+    ) -> Result<Option<PrisonEntry>> {
+        let coll = self.prison_coll();
+
+        coll.find_one(doc! { "guild_id": guild_id, "user_id": user_id }, None)
+            .await
+            .wrap_err("remove from prison")
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn set_parole(
+        &self,
+        guild_id: SnowflakeId,
+        user_id: SnowflakeId,
+        parole_until: Option<DateTime>,
+    ) -> Result<()> {
+        let coll = self.prison_coll();
+
+        coll.update_one(
+            doc! { "guild_id": guild_id, "user_id": user_id },
+            doc! { "$set": { "parole_until": parole_until } },
+            None,
+        )
+        .await
+        .wrap_err("set parole")?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn insert_prison_entries(&self, entries: &[PrisonEntry]) -> Result<()> {
+        if entries.is_empty() {
+            // mongo doesn't like empty inserts
+            return Ok(());
+        }
+
+        let coll = self.prison_coll();
+
+        coll.insert_many(entries, None)
+            .await
+            .wrap_err("insert prison entries")?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn set_prison_entries_tier(
+        &self,
+        guild_id: SnowflakeId,
+        user_ids: &[SnowflakeId],
+        tier: Option<&str>,
+    ) -> Result<()> {
+        let coll = self.prison_coll();
+
+        coll.update_many(
+            doc! { "guild_id": guild_id, "user_id": { "$in": user_ids } },
+            doc! { "$set": { "tier": tier, "parole_until": null } },
+            None,
+        )
+        .await
+        .wrap_err("set prison entries tier")?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn remove_all_from_prison(&self, guild_id: SnowflakeId) -> Result<()> {
+        let coll = self.prison_coll();
+
+        coll.delete_many(doc! { "guild_id": guild_id }, None)
+            .await
+            .wrap_err("remove all from prison")?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn set_saved_roles(
+        &self,
+        guild_id: SnowflakeId,
+        user_id: SnowflakeId,
+        saved_roles: &[SnowflakeId],
+    ) -> Result<()> {
+        let coll = self.prison_coll();
 
-        coll.find_one(doc! { "guild_id": guild_id, "user_id": user_id }, None)
+        coll.update_one(
+            doc! { "guild_id": guild_id, "user_id": user_id },
+            doc! { "$set": { "saved_roles": saved_roles } },
+            None,
+        )
+        .await
+        .wrap_err("set saved roles")?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn set_release_at(
+        &self,
+        guild_id: SnowflakeId,
+        user_id: SnowflakeId,
+        release_at: Option<DateTime>,
+    ) -> Result<()> {
+        let coll = self.prison_coll();
+
+        coll.update_one(
+            doc! { "guild_id": guild_id, "user_id": user_id },
+            doc! { "$set": { "release_at": release_at } },
+            None,
+        )
+        .await
+        .wrap_err("set release time")?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn find_prison_entries(&self, guild_id: SnowflakeId) -> Result<Vec<PrisonEntry>> {
+        let coll = self.prison_coll();
+
+        coll.find(doc! { "guild_id": guild_id }, None)
             .await
-            .wrap_err("remove from prison")
+            .wrap_err("find prison entries")?
+            .try_collect()
+            .await
+            .wrap_err("collect prison entries")
     }
 
-    fn state_coll(&self) -> Collection<State> {
-        self.db.collection("state")
+    #[tracing::instrument(skip(self))]
+    async fn find_prisoners_page(
+        &self,
+        guild_id: SnowflakeId,
+        after: Option<SnowflakeId>,
+        limit: usize,
+    ) -> Result<Page<PrisonEntry, SnowflakeId>> {
+        let coll = self.prison_coll();
+
+        let mut filter = doc! { "guild_id": guild_id };
+        if let Some(after) = after {
+            filter.insert("user_id", doc! { "$gt": after });
+        }
+        let options = FindOptions::builder()
+            .sort(doc! { "user_id": 1 })
+            .limit((limit + 1) as i64)
+            .build();
+
+        let entries = coll
+            .find(filter, options)
+            .await
+            .wrap_err("find prisoners page")?
+            .try_collect()
+            .await
+            .wrap_err("collect prisoners page")?;
+
+        Ok(Page::new(entries, limit, |entry| entry.user_id))
     }
 
-    fn prison_coll(&self) -> Collection<PrisonEntry> {
-        self.db.collection("prison")
+    #[tracing::instrument(skip(self))]
+    async fn count_prisoners(&self) -> Result<Vec<(SnowflakeId, u64)>> {
+        let coll = self.prison_coll();
+
+        count_per_guild(&coll, doc! {}).await
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn bot_stats(&self) -> Result<BotStats> {
+        // the estimated counts come from the metadata of the collections, nothing is scanned
+        let guilds = self
+            .state_coll()
+            .estimated_document_count(None)
+            .await
+            .wrap_err("count guilds")?;
+        let prisoners = self
+            .prison_coll()
+            .estimated_document_count(None)
+            .await
+            .wrap_err("count prisoners")?;
+
+        Ok(BotStats {
+            guilds,
+            lawsuits: self.counter(LAWSUITS_COUNTER).await?,
+            open_lawsuits: self.counter(OPEN_LAWSUITS_COUNTER).await?,
+            prisoners,
+        })
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn add_prison_records(&self, records: &[PrisonRecord]) -> Result<()> {
+        if records.is_empty() {
+            // mongo doesn't like empty inserts
+            return Ok(());
+        }
+
+        let coll = self.prison_history_coll();
+
+        coll.insert_many(records, None)
+            .await
+            .wrap_err("insert prison records")?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn find_prison_records(&self, guild_id: SnowflakeId) -> Result<Vec<PrisonRecord>> {
+        let coll = self.prison_history_coll();
+
+        coll.find(doc! { "guild_id": guild_id }, None)
+            .await
+            .wrap_err("find prison records")?
+            .try_collect()
+            .await
+            .wrap_err("collect prison records")
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn close_prison_records(
+        &self,
+        guild_id: SnowflakeId,
+        user_ids: Option<&[SnowflakeId]>,
+        released_at: DateTime,
+    ) -> Result<()> {
+        let coll = self.prison_history_coll();
+
+        let mut filter = doc! { "guild_id": guild_id, "released_at": null };
+        if let Some(user_ids) = user_ids {
+            filter.insert("user_id", doc! { "$in": user_ids });
+        }
+
+        coll.update_many(
+            filter,
+            doc! { "$set": { "released_at": released_at } },
+            None,
+        )
+        .await
+        .wrap_err("close prison records")?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn prison_stats(&self, guild_id: SnowflakeId) -> Result<PrisonStats> {
+        const TOP_REASONS: i64 = 5;
+
+        #[derive(Deserialize)]
+        struct Facets {
+            total: Vec<Count>,
+            sentence: Vec<Average>,
+            reasons: Vec<ReasonCount>,
+        }
+
+        #[derive(Deserialize)]
+        struct Count {
+            count: u64,
+        }
+
+        #[derive(Deserialize)]
+        struct Average {
+            average: f64,
+        }
+
+        #[derive(Deserialize)]
+        struct ReasonCount {
+            #[serde(rename = "_id")]
+            reason: String,
+            count: u64,
+        }
+
+        let current_prisoners = self
+            .prison_coll()
+            .count_documents(doc! { "guild_id": guild_id }, None)
+            .await
+            .wrap_err("count prisoners")?;
+
+        let pipeline = [
+            doc! { "$match": { "guild_id": guild_id } },
+            doc! { "$facet": {
+                "total": [{ "$count": "count" }],
+                "sentence": [
+                    { "$match": { "released_at": { "$ne": null } } },
+                    // subtracting two dates results in the difference in milliseconds
+                    { "$group": {
+                        "_id": null,
+                        "average": { "$avg": { "$subtract": ["$released_at", "$arrested_at"] } },
+                    } },
+                ],
+                "reasons": [
+                    { "$match": { "reason": { "$ne": null } } },
+                    { "$group": { "_id": "$reason", "count": { "$sum": 1 } } },
+                    { "$sort": { "count": -1 } },
+                    { "$limit": TOP_REASONS },
+                ],
+            } },
+        ];
+
+        let facets = self
+            .prison_history_coll()
+            .aggregate(pipeline, None)
+            .await
+            .wrap_err("aggregate prison stats")?
+            .try_next()
+            .await
+            .wrap_err("fetch prison stats")?
+            .wrap_err("facet result is missing")?;
+        let facets = bson::from_document::<Facets>(facets).wrap_err("invalid prison stats")?;
+
+        Ok(PrisonStats {
+            current_prisoners,
+            total_arrests: facets.total.first().map_or(0, |total| total.count),
+            average_sentence: facets
+                .sentence
+                .first()
+                .map(|sentence| Duration::from_millis(sentence.average as u64)),
+            top_reasons: facets
+                .reasons
+                .into_iter()
+                .map(|reason| (reason.reason, reason.count))
+                .collect(),
+        })
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn court_stats(&self, guild_id: SnowflakeId) -> Result<CourtStats> {
+        #[derive(Deserialize)]
+        struct Facets {
+            months: Vec<Count<String>>,
+            verdicts: Vec<Verdicts>,
+            duration: Vec<Average>,
+            judges: Vec<Count<SnowflakeId>>,
+            accused: Vec<Count<SnowflakeId>>,
+        }
+
+        #[derive(Deserialize)]
+        struct Count<T> {
+            #[serde(rename = "_id")]
+            key: T,
+            count: u64,
+        }
+
+        #[derive(Deserialize)]
+        struct Verdicts {
+            guilty: u64,
+            not_guilty: u64,
+            total: u64,
+        }
+
+        #[derive(Deserialize)]
+        struct Average {
+            average: f64,
+        }
+
+        let limit = COURT_STATS_LEN as i64;
+        let says = |words: &str| {
+            doc! { "$regexMatch": { "input": "$verdict", "regex": words, "options": "i" } }
+        };
+        let top = |field: &str| {
+            [
+                doc! { "$group": { "_id": field, "count": { "$sum": 1 } } },
+                doc! { "$sort": { "count": -1, "_id": 1 } },
+                doc! { "$limit": limit },
+            ]
+        };
+
+        let pipeline = [
+            doc! { "$match": { "guild_id": guild_id } },
+            doc! { "$facet": {
+                "months": [
+                    { "$match": { "opened_at": { "$ne": null } } },
+                    { "$group": {
+                        "_id": { "$dateToString": { "format": "%Y-%m", "date": "$opened_at" } },
+                        "count": { "$sum": 1 },
+                    } },
+                    { "$sort": { "_id": -1 } },
+                    { "$limit": limit },
+                ],
+                "verdicts": [
+                    { "$match": { "verdict": { "$ne": null } } },
+                    { "$group": {
+                        "_id": null,
+                        "not_guilty": { "$sum": { "$cond": [says(NOT_GUILTY_VERDICT), 1, 0] } },
+                        "guilty": { "$sum": { "$cond": [
+                            says(NOT_GUILTY_VERDICT),
+                            0,
+                            { "$cond": [says(GUILTY_VERDICT), 1, 0] },
+                        ] } },
+                        "total": { "$sum": 1 },
+                    } },
+                ],
+                "duration": [
+                    { "$match": {
+                        "verdict": { "$ne": null },
+                        "opened_at": { "$ne": null },
+                        "closed_at": { "$ne": null },
+                    } },
+                    // subtracting two dates results in the difference in milliseconds
+                    { "$group": {
+                        "_id": null,
+                        "average": { "$avg": { "$subtract": ["$closed_at", "$opened_at"] } },
+                    } },
+                ],
+                "judges": top("$judge").to_vec(),
+                "accused": top("$accused").to_vec(),
+            } },
+        ];
+
+        let facets = self
+            .lawsuit_coll()
+            .aggregate(pipeline, None)
+            .await
+            .wrap_err("aggregate court stats")?
+            .try_next()
+            .await
+            .wrap_err("fetch court stats")?
+            .wrap_err("facet result is missing")?;
+        let facets = bson::from_document::<Facets>(facets).wrap_err("invalid court stats")?;
+
+        let counts = |counts: Vec<Count<SnowflakeId>>| -> Vec<(SnowflakeId, u64)> {
+            counts
+                .into_iter()
+                .map(|count| (count.key, count.count))
+                .collect()
+        };
+        let verdicts = facets.verdicts.first();
+        Ok(CourtStats {
+            per_month: facets
+                .months
+                .into_iter()
+                .map(|month| (month.key, month.count))
+                .collect(),
+            guilty: verdicts.map_or(0, |verdicts| verdicts.guilty),
+            not_guilty: verdicts.map_or(0, |verdicts| verdicts.not_guilty),
+            other_verdicts: verdicts.map_or(0, |verdicts| {
+                verdicts.total - verdicts.guilty - verdicts.not_guilty
+            }),
+            average_time_to_verdict: facets
+                .duration
+                .first()
+                .map(|duration| Duration::from_millis(duration.average.max(0.0) as u64)),
+            busiest_judges: counts(facets.judges),
+            most_sued: counts(facets.accused),
+        })
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn court_report(&self, guild_id: SnowflakeId, since: DateTime) -> Result<CourtReport> {
+        #[derive(Deserialize)]
+        struct Facets {
+            arrests: Vec<Count>,
+            releases: Vec<Count>,
+        }
+
+        #[derive(Deserialize)]
+        struct Count {
+            count: u64,
+        }
+
+        let lawsuits = self.lawsuit_coll();
+        let opened_lawsuits = lawsuits
+            .count_documents(
+                doc! { "guild_id": guild_id, "opened_at": { "$gte": since } },
+                None,
+            )
+            .await
+            .wrap_err("count opened lawsuits")?;
+        let closed_lawsuits = lawsuits
+            .find(
+                doc! { "guild_id": guild_id, "closed_at": { "$gte": since } },
+                FindOptions::builder().sort(doc! { "closed_at": 1 }).build(),
+            )
+            .await
+            .wrap_err("find closed lawsuits")?
+            .try_collect()
+            .await
+            .wrap_err("collect closed lawsuits")?;
+
+        let pipeline = [
+            doc! { "$match": { "guild_id": guild_id } },
+            doc! { "$facet": {
+                "arrests": [
+                    { "$match": { "arrested_at": { "$gte": since } } },
+                    { "$count": "count" },
+                ],
+                "releases": [
+                    { "$match": { "released_at": { "$gte": since } } },
+                    { "$count": "count" },
+                ],
+            } },
+        ];
+        let facets = self
+            .prison_history_coll()
+            .aggregate(pipeline, None)
+            .await
+            .wrap_err("aggregate prison history")?
+            .try_next()
+            .await
+            .wrap_err("fetch prison history counts")?
+            .wrap_err("facet result is missing")?;
+        let facets =
+            bson::from_document::<Facets>(facets).wrap_err("invalid prison history counts")?;
+
+        Ok(CourtReport {
+            opened_lawsuits,
+            closed_lawsuits,
+            arrests: facets.arrests.first().map_or(0, |count| count.count),
+            releases: facets.releases.first().map_or(0, |count| count.count),
+        })
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn schedule_job(
+        &self,
+        guild_id: SnowflakeId,
+        kind: &JobKind,
+        run_at: DateTime,
+    ) -> Result<()> {
+        let coll = self.job_coll();
+        let kind = bson::to_bson(kind).wrap_err("invalid job kind")?;
+
+        coll.update_one(
+            doc! { "guild_id": guild_id, "kind": kind },
+            doc! {
+                "$set": { "run_at": run_at, "attempts": 0 },
+                "$unset": { "leased_until": "" },
+            },
+            UpdateOptions::builder().upsert(true).build(),
+        )
+        .await
+        .wrap_err("schedule job")?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn cancel_job(&self, guild_id: SnowflakeId, kind: &JobKind) -> Result<()> {
+        let coll = self.job_coll();
+        let kind = bson::to_bson(kind).wrap_err("invalid job kind")?;
+
+        coll.delete_one(doc! { "guild_id": guild_id, "kind": kind }, None)
+            .await
+            .wrap_err("cancel job")?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn cancel_prison_jobs(&self, guild_id: SnowflakeId) -> Result<()> {
+        let coll = self.job_coll();
+
+        coll.delete_many(
+            doc! { "guild_id": guild_id, "kind.type": { "$in": ["release", "end_parole"] } },
+            None,
+        )
+        .await
+        .wrap_err("cancel prison jobs")?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn find_jobs(&self, guild_id: SnowflakeId) -> Result<Vec<Job>> {
+        let coll = self.job_coll();
+
+        coll.find(doc! { "guild_id": guild_id }, None)
+            .await
+            .wrap_err("find jobs")?
+            .try_collect()
+            .await
+            .wrap_err("collect jobs")
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn find_due_jobs(&self, now: DateTime) -> Result<Vec<Job>> {
+        let coll = self.job_coll();
+
+        coll.find(doc! { "run_at": { "$lte": now } }, None)
+            .await
+            .wrap_err("find due jobs")?
+            .try_collect()
+            .await
+            .wrap_err("collect due jobs")
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn claim_job(&self, job: &Job, until: DateTime) -> Result<bool> {
+        let coll = self.job_coll();
+        let kind = bson::to_bson(&job.kind).wrap_err("invalid job kind")?;
+
+        let result = coll
+            .update_one(
+                doc! {
+                    "guild_id": job.guild_id,
+                    "kind": kind,
+                    "run_at": job.run_at,
+                    "$or": [
+                        { "leased_until": null },
+                        { "leased_until": { "$lte": DateTime::now() } },
+                    ],
+                },
+                doc! { "$set": { "leased_until": until } },
+                None,
+            )
+            .await
+            .wrap_err("claim job")?;
+
+        Ok(result.modified_count == 1)
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn finish_job(&self, job: &Job) -> Result<()> {
+        let coll = self.job_coll();
+        let kind = bson::to_bson(&job.kind).wrap_err("invalid job kind")?;
+
+        coll.delete_one(
+            doc! { "guild_id": job.guild_id, "kind": kind, "run_at": job.run_at },
+            None,
+        )
+        .await
+        .wrap_err("finish job")?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn retry_job(&self, job: &Job, run_at: DateTime) -> Result<()> {
+        let coll = self.job_coll();
+        let kind = bson::to_bson(&job.kind).wrap_err("invalid job kind")?;
+
+        coll.update_one(
+            doc! { "guild_id": job.guild_id, "kind": kind, "run_at": job.run_at },
+            doc! {
+                "$set": { "run_at": run_at },
+                "$inc": { "attempts": 1 },
+                "$unset": { "leased_until": "" },
+            },
+            None,
+        )
+        .await
+        .wrap_err("retry job")?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn find_judge_registry(&self, guild_id: SnowflakeId) -> Result<JudgeRegistry> {
+        let registry = self
+            .judge_coll()
+            .find_one(doc! { "guild_id": guild_id }, None)
+            .await
+            .wrap_err("find judge registry")?;
+        Ok(registry.unwrap_or_else(|| JudgeRegistry::new(guild_id)))
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn register_judge(&self, guild_id: SnowflakeId, judge: SnowflakeId) -> Result<bool> {
+        let result = self
+            .judge_coll()
+            .update_one(
+                doc! { "guild_id": guild_id },
+                doc! { "$addToSet": { "judges": judge } },
+                UpdateOptions::builder().upsert(true).build(),
+            )
+            .await
+            .wrap_err("register judge")?;
+        Ok(result.modified_count > 0 || result.upserted_id.is_some())
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn unregister_judge(&self, guild_id: SnowflakeId, judge: SnowflakeId) -> Result<bool> {
+        let result = self
+            .judge_coll()
+            .update_one(
+                doc! { "guild_id": guild_id },
+                doc! { "$pull": { "judges": judge } },
+                None,
+            )
+            .await
+            .wrap_err("unregister judge")?;
+        Ok(result.modified_count > 0)
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn assign_judge(
+        &self,
+        guild_id: SnowflakeId,
+        parties: &[SnowflakeId],
+    ) -> Result<Option<SnowflakeId>> {
+        // two lawsuits created at the same time must not get the same judge, so the rotation only
+        // moves on if nobody else moved it in the meantime
+        loop {
+            let registry = self.find_judge_registry(guild_id).await?;
+            let judge = match registry.next_judge(parties) {
+                Some(judge) => judge,
+                None => return Ok(None),
+            };
+
+            let result = self
+                .judge_coll()
+                .update_one(
+                    doc! { "guild_id": guild_id, "last_assigned": registry.last_assigned },
+                    doc! { "$set": { "last_assigned": judge } },
+                    None,
+                )
+                .await
+                .wrap_err("assign judge")?;
+            if result.matched_count > 0 {
+                return Ok(Some(judge));
+            }
+        }
+    }
+
+    #[tracing::instrument(skip(self, election))]
+    async fn add_election(&self, election: &Election) -> Result<()> {
+        self.election_coll()
+            .insert_one(election, None)
+            .await
+            .wrap_err("add election")?;
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn find_elections(&self, guild_id: SnowflakeId) -> Result<Vec<Election>> {
+        let options = FindOptions::builder().sort(doc! { "ended_at": -1 }).build();
+        self.election_coll()
+            .find(doc! { "guild_id": guild_id }, options)
+            .await
+            .wrap_err("find elections")?
+            .try_collect()
+            .await
+            .wrap_err("collect elections")
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn record_command_usage(&self, usage: &CommandUsage) -> Result<()> {
+        self.command_usage_coll()
+            .insert_one(usage, None)
+            .await
+            .wrap_err("record command usage")?;
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn find_command_usage(&self, since: DateTime) -> Result<Vec<CommandUsage>> {
+        self.command_usage_coll()
+            .find(doc! { "used_at": { "$gte": since } }, None)
+            .await
+            .wrap_err("find command usage")?
+            .try_collect()
+            .await
+            .wrap_err("collect command usage")
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn find_guild_listings(&self) -> Result<Vec<GuildListing>> {
+        self.guild_access_coll()
+            .find(None, None)
+            .await
+            .wrap_err("find guild listings")?
+            .try_collect()
+            .await
+            .wrap_err("collect guild listings")
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn set_guild_listing(&self, guild_id: SnowflakeId, allowed: Option<bool>) -> Result<()> {
+        let coll = self.guild_access_coll();
+        match allowed {
+            Some(allowed) => {
+                coll.update_one(
+                    doc! { "guild_id": guild_id },
+                    doc! { "$set": { "allowed": allowed } },
+                    UpdateOptions::builder().upsert(true).build(),
+                )
+                .await
+                .wrap_err("set guild listing")?;
+            }
+            None => {
+                coll.delete_one(doc! { "guild_id": guild_id }, None)
+                    .await
+                    .wrap_err("remove guild listing")?;
+            }
+        }
+        Ok(())
     }
 }
@@ -0,0 +1,137 @@
+use std::{
+    fmt::{Display, Formatter},
+    sync::{
+        atomic::{AtomicU32, Ordering},
+        Arc, Mutex, MutexGuard, PoisonError,
+    },
+    time::{Duration, Instant},
+};
+
+use color_eyre::{Report, Result};
+use mongodb::error::ErrorKind;
+use tokio::sync::Notify;
+use tracing::{debug, info, warn};
+
+use crate::{shutdown::BackgroundTasks, storage::Storage, Context};
+
+/// After this many commands in a row failed because the database is unreachable, the bot is
+/// considered offline.
+const FAILURE_THRESHOLD: u32 = 3;
+const MIN_BACKOFF: Duration = Duration::from_secs(1);
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+/// A ping that takes longer than this counts as failed, the driver would wait much longer
+const PING_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Returned from the command check while the database is unreachable, the error handler answers
+/// with the offline notice.
+#[derive(Debug)]
+pub struct Offline;
+
+impl Display for Offline {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str("the database is unreachable")
+    }
+}
+
+impl std::error::Error for Offline {}
+
+/// A circuit breaker in front of the database. Once it's unreachable, the commands are answered
+/// with a notice right away instead of each waiting for the timeouts of the driver, until pinging
+/// the database works again. The driver reconnects by itself, the pings only check whether it did.
+#[derive(Debug, Default)]
+pub struct Outage {
+    failures: AtomicU32,
+    /// When the database became unreachable, `None` while it works
+    since: Mutex<Option<Instant>>,
+    tripped: Notify,
+}
+
+impl Outage {
+    pub fn is_offline(&self) -> bool {
+        self.since().is_some()
+    }
+
+    /// Counts the error of a command if it means that the database is unreachable.
+    pub fn record_error(&self, err: &Report) {
+        if !is_unreachable(err) {
+            return;
+        }
+
+        let failures = self.failures.fetch_add(1, Ordering::SeqCst) + 1;
+        if failures < FAILURE_THRESHOLD {
+            return;
+        }
+        let mut since = self.since();
+        if since.is_none() {
+            *since = Some(Instant::now());
+            warn!(
+                failures,
+                "Database unreachable, answering commands with the offline notice"
+            );
+            self.tripped.notify_one();
+        }
+    }
+
+    pub fn record_success(&self) {
+        self.failures.store(0, Ordering::SeqCst);
+    }
+
+    /// Pings the database with a growing delay whenever it's unreachable. Runs until the bot
+    /// shuts down.
+    pub async fn recover(self: Arc<Self>, storage: Arc<dyn Storage>, tasks: BackgroundTasks) {
+        loop {
+            tokio::select! {
+                _ = self.tripped.notified() => {}
+                _ = tasks.shutting_down() => return,
+            }
+
+            let mut backoff = MIN_BACKOFF;
+            loop {
+                tokio::select! {
+                    _ = tokio::time::sleep(backoff) => {}
+                    _ = tasks.shutting_down() => return,
+                }
+                match tokio::time::timeout(PING_TIMEOUT, storage.ping()).await {
+                    Ok(Ok(())) => break,
+                    Ok(Err(err)) => debug!(?err, ?backoff, "Database still unreachable"),
+                    Err(_) => debug!(?backoff, "Database still unreachable, ping timed out"),
+                }
+                backoff = (backoff * 2).min(MAX_BACKOFF);
+            }
+
+            self.failures.store(0, Ordering::SeqCst);
+            let outage = self.since().take().map(|since| since.elapsed());
+            info!(?outage, "Database reachable again");
+        }
+    }
+
+    fn since(&self) -> MutexGuard<'_, Option<Instant>> {
+        self.since.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// Whether the error comes from not reaching the database, other database errors don't count.
+pub fn is_unreachable(err: &Report) -> bool {
+    err.chain().any(|cause| {
+        if let Some(err) = cause.downcast_ref::<mongodb::error::Error>() {
+            return matches!(
+                *err.kind,
+                ErrorKind::ServerSelection { .. }
+                    | ErrorKind::Io(_)
+                    | ErrorKind::ConnectionPoolCleared { .. }
+            );
+        }
+        matches!(
+            cause.downcast_ref::<sqlx::Error>(),
+            Some(sqlx::Error::Io(_) | sqlx::Error::PoolTimedOut | sqlx::Error::PoolClosed)
+        )
+    })
+}
+
+/// Part of the command check of the framework, fails while the database is unreachable.
+pub fn check(ctx: Context<'_>) -> Result<()> {
+    if ctx.data().outage.is_offline() {
+        return Err(Offline.into());
+    }
+    Ok(())
+}
@@ -0,0 +1,173 @@
+use std::time::Duration;
+
+use color_eyre::Result;
+use mongodb::bson::Uuid;
+use poise::serenity::{
+    builder::{CreateComponents, CreateEmbed},
+    collector::CollectComponentInteraction,
+    model::interactions::{message_component::ButtonStyle, InteractionResponseType},
+};
+
+use crate::{
+    i18n::{self, Strings},
+    Context, WrapErr,
+};
+
+const ENTRIES_PER_PAGE: usize = 10;
+/// Discord only allows 25 options in a select menu
+const MAX_SELECT_OPTIONS: usize = 25;
+const TIMEOUT: Duration = Duration::from_secs(60 * 5);
+
+/// Sends the entries as an embed with buttons to go to the next and previous page and a select
+/// menu to jump directly to a page. Listens for button presses until nobody touched it for a while.
+pub async fn paginate(ctx: Context<'_>, title: &str, entries: &[String]) -> Result<()> {
+    let pages = entries
+        .chunks(ENTRIES_PER_PAGE)
+        .map(|chunk| chunk.join("\n"))
+        .collect::<Vec<_>>();
+
+    if pages.len() <= 1 {
+        let description = pages.first().cloned().unwrap_or_default();
+        ctx.send(|reply| reply.embed(|embed| embed.title(title).description(description)))
+            .await
+            .wrap_err("send single page")?;
+        return Ok(());
+    }
+
+    let strings = i18n::strings(ctx).await?;
+    let ids = PaginationIds::new();
+    let mut current = 0;
+
+    ctx.send(|reply| {
+        reply
+            .embed(|embed| page_embed(embed, strings, title, &pages, current))
+            .components(|c| page_components(c, strings, &ids, pages.len(), current))
+    })
+    .await
+    .wrap_err("send first page")?;
+
+    loop {
+        let prefix = ids.prefix.clone();
+        let interaction = CollectComponentInteraction::new(ctx.discord())
+            .channel_id(ctx.channel_id())
+            .filter(move |interaction| interaction.data.custom_id.starts_with(&prefix))
+            .timeout(TIMEOUT)
+            .await;
+
+        let interaction = match interaction {
+            Some(interaction) => interaction,
+            None => break,
+        };
+
+        let custom_id = &interaction.data.custom_id;
+        if *custom_id == ids.next {
+            current = (current + 1) % pages.len();
+        } else if *custom_id == ids.prev {
+            current = current.checked_sub(1).unwrap_or(pages.len() - 1);
+        } else if *custom_id == ids.select {
+            if let Some(page) = interaction
+                .data
+                .values
+                .first()
+                .and_then(|value| value.parse::<usize>().ok())
+                .filter(|page| *page < pages.len())
+            {
+                current = page;
+            }
+        }
+
+        interaction
+            .create_interaction_response(ctx.discord(), |response| {
+                response
+                    .kind(InteractionResponseType::UpdateMessage)
+                    .interaction_response_data(|data| {
+                        data.embed(|embed| page_embed(embed, strings, title, &pages, current))
+                            .components(|c| page_components(c, strings, &ids, pages.len(), current))
+                    })
+            })
+            .await
+            .wrap_err("update page")?;
+    }
+
+    Ok(())
+}
+
+struct PaginationIds {
+    prefix: String,
+    prev: String,
+    next: String,
+    select: String,
+}
+
+impl PaginationIds {
+    fn new() -> Self {
+        // the ids must be unique so that multiple paginations in the same channel don't interfere
+        let prefix = format!("page-{}", Uuid::new());
+        Self {
+            prev: format!("{prefix}-prev"),
+            next: format!("{prefix}-next"),
+            select: format!("{prefix}-select"),
+            prefix,
+        }
+    }
+}
+
+fn page_embed<'a>(
+    embed: &'a mut CreateEmbed,
+    strings: &Strings,
+    title: &str,
+    pages: &[String],
+    current: usize,
+) -> &'a mut CreateEmbed {
+    embed
+        .title(title)
+        .description(&pages[current])
+        .footer(|footer| footer.text((strings.page)(current + 1, pages.len())))
+}
+
+fn page_components<'a>(
+    components: &'a mut CreateComponents,
+    strings: &Strings,
+    ids: &PaginationIds,
+    page_count: usize,
+    current: usize,
+) -> &'a mut CreateComponents {
+    // only show the pages around the current one if there are too many for the select menu
+    let first_option = current
+        .saturating_sub(MAX_SELECT_OPTIONS / 2)
+        .min(page_count.saturating_sub(MAX_SELECT_OPTIONS));
+    let last_option = (first_option + MAX_SELECT_OPTIONS).min(page_count);
+
+    components
+        .create_action_row(|row| {
+            row.create_button(|button| {
+                button
+                    .custom_id(&ids.prev)
+                    .label(strings.previous_page)
+                    .style(ButtonStyle::Secondary)
+            })
+            .create_button(|button| {
+                button
+                    .custom_id(&ids.next)
+                    .label(strings.next_page)
+                    .style(ButtonStyle::Primary)
+            })
+        })
+        .create_action_row(|row| {
+            row.create_select_menu(|menu| {
+                menu.custom_id(&ids.select)
+                    .placeholder((strings.page)(current + 1, page_count))
+                    .options(|options| {
+                        for page in first_option..last_option {
+                            options.create_option(|option| {
+                                option
+                                    .label((strings.page_option)(page + 1))
+                                    .value(page)
+                                    .default_selection(page == current)
+                            });
+                        }
+                        options
+                    })
+            })
+        })
+}
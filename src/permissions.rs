@@ -0,0 +1,105 @@
+use color_eyre::Result;
+use poise::serenity::model::prelude::*;
+
+use crate::{
+    i18n::Strings,
+    model::{SnowflakeId, State},
+    Context,
+};
+
+/// The roles that give access to the commands of the bot. Members that can manage the server can
+/// always use every command.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, poise::ChoiceParameter)]
+pub enum CourtRole {
+    #[name = "Gerichtsverwaltung"]
+    Admin,
+    #[name = "Richter"]
+    Judge,
+    #[name = "Gerichtsdiener"]
+    Bailiff,
+}
+
+impl CourtRole {
+    pub fn label(self, strings: &Strings) -> &'static str {
+        match self {
+            Self::Admin => strings.setting_admin_role,
+            Self::Judge => strings.setting_judge_role,
+            Self::Bailiff => strings.setting_bailiff_role,
+        }
+    }
+
+    /// The name of the field in the state.
+    pub fn state_field(self) -> &'static str {
+        match self {
+            Self::Admin => "admin_role",
+            Self::Judge => "judge_role",
+            Self::Bailiff => "bailiff_role",
+        }
+    }
+
+    fn role_id(self, state: &State) -> Option<SnowflakeId> {
+        match self {
+            Self::Admin => state.admin_role,
+            Self::Judge => state.judge_role,
+            Self::Bailiff => state.bailiff_role,
+        }
+    }
+
+    pub fn role_id_mut(self, state: &mut State) -> &mut Option<SnowflakeId> {
+        match self {
+            Self::Admin => &mut state.admin_role,
+            Self::Judge => &mut state.judge_role,
+            Self::Bailiff => &mut state.bailiff_role,
+        }
+    }
+}
+
+/// Whether the author can manage the server or has one of the roles.
+pub async fn has_any_role(ctx: Context<'_>, roles: &[CourtRole]) -> Result<bool> {
+    let member = match ctx {
+        Context::Application(ctx) => ctx.interaction.member(),
+        Context::Prefix(_) => None,
+    };
+
+    let member = match member {
+        Some(member) => member,
+        None => return Ok(false),
+    };
+
+    if member
+        .permissions
+        .map_or(false, |p| p.contains(Permissions::MANAGE_GUILD))
+    {
+        return Ok(true);
+    }
+
+    let state = ctx
+        .data()
+        .storage
+        .find_or_insert_state(member.guild_id.into())
+        .await?;
+
+    Ok(roles
+        .iter()
+        .filter_map(|role| role.role_id(&state))
+        .any(|role| member.roles.contains(&role.into())))
+}
+
+/// For the settings of the bot.
+pub async fn is_admin(ctx: Context<'_>) -> Result<bool> {
+    has_any_role(ctx, &[CourtRole::Admin]).await
+}
+
+/// For running lawsuits.
+pub async fn is_judge(ctx: Context<'_>) -> Result<bool> {
+    has_any_role(ctx, &[CourtRole::Admin, CourtRole::Judge]).await
+}
+
+/// For the prison. Judges can also carry out their verdicts themselves.
+pub async fn is_bailiff(ctx: Context<'_>) -> Result<bool> {
+    has_any_role(
+        ctx,
+        &[CourtRole::Admin, CourtRole::Judge, CourtRole::Bailiff],
+    )
+    .await
+}
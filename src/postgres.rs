@@ -0,0 +1,2594 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    time::Duration,
+};
+
+use async_trait::async_trait;
+use color_eyre::Result;
+use mongodb::{
+    bson,
+    bson::{Bson, DateTime, Uuid},
+};
+use serde::{de::DeserializeOwned, Serialize};
+use sqlx::{
+    postgres::{PgExecutor, PgPoolOptions},
+    PgPool, Postgres, Transaction,
+};
+use tracing::info;
+
+use crate::{
+    bar::BarQuestion,
+    duration,
+    election::Election,
+    i18n::Locale,
+    lawsuit::{
+        Billing, ConflictPolicy, Contempt, Evidence, Fine, JuryTally, Lawsuit, LawyerFee,
+        SubpoenaResponse, Witness,
+    },
+    model::{
+        count_open, BotStats, CommandUsage, CourtReport, CourtRoom, CourtStats, ErasureReport,
+        GagOrder, GuildData, GuildListing, Job, JobKind, JudgeRegistry, MemberData, Page,
+        PrisonEntry, PrisonRecord, PrisonStats, PrisonTier, RoleTemplate, SnowflakeId, State,
+        TrashedState, COURT_STATS_LEN, LAWSUITS_COUNTER, MAX_COMMAND_USAGES, OPEN_LAWSUITS_COUNTER,
+        TRASH_GRACE_PERIOD,
+    },
+    permissions::CourtRole,
+    storage::Storage,
+    templates::Template,
+    WrapErr,
+};
+
+/// The documents are stored as a whole in `jsonb` columns, next to the columns that are needed to
+/// find them. Fields that were added later are filled in with their defaults when they are read,
+/// so unlike with mongodb only data that moves to another table has to be migrated here.
+const SCHEMA: &[&str] = &[
+    "CREATE TABLE IF NOT EXISTS state (
+        guild_id TEXT PRIMARY KEY,
+        data JSONB NOT NULL
+    )",
+    "CREATE TABLE IF NOT EXISTS lawsuits (
+        guild_id TEXT NOT NULL,
+        id TEXT NOT NULL,
+        data JSONB NOT NULL,
+        PRIMARY KEY (guild_id, id)
+    )",
+    "CREATE TABLE IF NOT EXISTS trash (
+        guild_id TEXT PRIMARY KEY,
+        data JSONB NOT NULL,
+        deleted_at BIGINT NOT NULL
+    )",
+    "CREATE TABLE IF NOT EXISTS prison (
+        guild_id TEXT NOT NULL,
+        user_id TEXT NOT NULL,
+        data JSONB NOT NULL,
+        PRIMARY KEY (guild_id, user_id)
+    )",
+    "CREATE TABLE IF NOT EXISTS prison_history (
+        id BIGSERIAL PRIMARY KEY,
+        guild_id TEXT NOT NULL,
+        data JSONB NOT NULL
+    )",
+    "CREATE INDEX IF NOT EXISTS prison_history_guild_id ON prison_history (guild_id)",
+    "CREATE TABLE IF NOT EXISTS jobs (
+        guild_id TEXT NOT NULL,
+        kind JSONB NOT NULL,
+        run_at BIGINT NOT NULL,
+        attempts INT NOT NULL DEFAULT 0,
+        PRIMARY KEY (guild_id, kind)
+    )",
+    "CREATE INDEX IF NOT EXISTS jobs_run_at ON jobs (run_at)",
+    "ALTER TABLE jobs ADD COLUMN IF NOT EXISTS leased_until BIGINT",
+    "CREATE TABLE IF NOT EXISTS judges (
+        guild_id TEXT PRIMARY KEY,
+        data JSONB NOT NULL
+    )",
+    "CREATE TABLE IF NOT EXISTS elections (
+        guild_id TEXT NOT NULL,
+        id TEXT NOT NULL,
+        ended_at BIGINT NOT NULL,
+        data JSONB NOT NULL,
+        PRIMARY KEY (guild_id, id)
+    )",
+    "CREATE TABLE IF NOT EXISTS command_usage (
+        id BIGSERIAL PRIMARY KEY,
+        used_at BIGINT NOT NULL,
+        data JSONB NOT NULL
+    )",
+    "CREATE INDEX IF NOT EXISTS command_usage_used_at ON command_usage (used_at)",
+    "CREATE TABLE IF NOT EXISTS guild_access (
+        guild_id TEXT PRIMARY KEY,
+        allowed BOOLEAN NOT NULL
+    )",
+    "CREATE TABLE IF NOT EXISTS counters (
+        name TEXT PRIMARY KEY,
+        count BIGINT NOT NULL
+    )",
+    // the counters start at the lawsuits that exist when they are created
+    "INSERT INTO counters (name, count) SELECT 'lawsuits', COUNT(*) FROM lawsuits
+    ON CONFLICT (name) DO NOTHING",
+    "INSERT INTO counters (name, count)
+    SELECT 'open_lawsuits', COUNT(*) FROM lawsuits WHERE data->'verdict' = 'null'
+    ON CONFLICT (name) DO NOTHING",
+    "CREATE TABLE IF NOT EXISTS members (
+        guild_id TEXT NOT NULL,
+        user_id TEXT NOT NULL,
+        data JSONB NOT NULL,
+        PRIMARY KEY (guild_id, user_id)
+    )",
+    // the balances, lawyer fees and filing times used to be stored in the state
+    "INSERT INTO members (guild_id, user_id, data)
+    SELECT state.guild_id, balance.key, jsonb_build_object(
+        'guild_id', state.guild_id, 'user_id', balance.key, 'balance', balance.value
+    )
+    FROM state, jsonb_each(state.data->'balances') AS balance
+    ON CONFLICT (guild_id, user_id) DO NOTHING",
+    "UPDATE state SET data = data - 'balances' WHERE data ? 'balances'",
+    "INSERT INTO members (guild_id, user_id, data)
+    SELECT state.guild_id, fee.key, jsonb_build_object(
+        'guild_id', state.guild_id, 'user_id', fee.key, 'lawyer_fee', fee.value
+    )
+    FROM state, jsonb_each(state.data->'lawyer_fees') AS fee
+    ON CONFLICT (guild_id, user_id) DO UPDATE SET data = members.data || EXCLUDED.data",
+    "UPDATE state SET data = data - 'lawyer_fees' WHERE data ? 'lawyer_fees'",
+    "INSERT INTO members (guild_id, user_id, data)
+    SELECT state.guild_id, filed.key, jsonb_build_object(
+        'guild_id', state.guild_id, 'user_id', filed.key, 'last_filed', filed.value
+    )
+    FROM state, jsonb_each(state.data->'last_filed') AS filed
+    ON CONFLICT (guild_id, user_id) DO UPDATE SET data = members.data || EXCLUDED.data",
+    "UPDATE state SET data = data - 'last_filed' WHERE data ? 'last_filed'",
+];
+
+/// Stores everything in postgres, for servers that would rather not run mongodb.
+#[derive(Clone)]
+pub struct Postgres {
+    pool: PgPool,
+}
+
+/// The documents are stored as extended json, so that the bson types like [`DateTime`] and
+/// [`Uuid`] survive the round trip.
+fn to_json<T: Serialize>(value: &T) -> Result<serde_json::Value> {
+    Ok(bson::to_bson(value)
+        .wrap_err("invalid bson")?
+        .into_relaxed_extjson())
+}
+
+fn from_json<T: DeserializeOwned>(value: serde_json::Value) -> Result<T> {
+    let value = Bson::try_from(value).wrap_err("invalid extended json")?;
+    bson::from_bson(value).wrap_err("invalid document")
+}
+
+fn to_job(
+    (guild_id, kind, run_at, attempts): (String, serde_json::Value, i64, i32),
+) -> Result<Job> {
+    Ok(Job {
+        guild_id: guild_id.parse().wrap_err("invalid guild id")?,
+        kind: from_json(kind)?,
+        run_at: DateTime::from_millis(run_at),
+        attempts: attempts as u32,
+    })
+}
+
+fn to_guild_count((guild_id, count): (String, i64)) -> Result<(SnowflakeId, u64)> {
+    Ok((guild_id.parse().wrap_err("invalid guild id")?, count as u64))
+}
+
+async fn add_to_counter<'e>(executor: impl PgExecutor<'e>, name: &str, amount: i64) -> Result<()> {
+    if amount == 0 {
+        return Ok(());
+    }
+
+    sqlx::query(
+        "INSERT INTO counters (name, count) VALUES ($1, $2)
+        ON CONFLICT (name) DO UPDATE SET count = counters.count + EXCLUDED.count",
+    )
+    .bind(name)
+    .bind(amount)
+    .execute(executor)
+    .await
+    .wrap_err_with(|| format!("update counter {name}"))?;
+    Ok(())
+}
+
+/// Locks the state of the guild for the rest of the transaction, it must exist.
+async fn update_state_in<R>(
+    tx: &mut Transaction<'_, Postgres>,
+    guild_id: SnowflakeId,
+    update: impl FnOnce(&mut State) -> R,
+) -> Result<R> {
+    let data = sqlx::query_scalar("SELECT data FROM state WHERE guild_id = $1 FOR UPDATE")
+        .bind(guild_id.to_string())
+        .fetch_one(&mut *tx)
+        .await
+        .wrap_err("find state")?;
+
+    let mut state = from_json::<State>(data)?;
+    let result = update(&mut state);
+    state.version += 1;
+
+    sqlx::query("UPDATE state SET data = $2 WHERE guild_id = $1")
+        .bind(guild_id.to_string())
+        .bind(to_json(&state)?)
+        .execute(&mut *tx)
+        .await
+        .wrap_err("update state")?;
+
+    Ok(result)
+}
+
+/// Locks the member for the rest of the transaction, it's created if it doesn't exist yet.
+async fn update_member_in<R>(
+    tx: &mut Transaction<'_, Postgres>,
+    guild_id: SnowflakeId,
+    user_id: SnowflakeId,
+    update: impl FnOnce(&mut MemberData) -> R,
+) -> Result<R> {
+    sqlx::query(
+        "INSERT INTO members (guild_id, user_id, data) VALUES ($1, $2, $3)
+        ON CONFLICT (guild_id, user_id) DO NOTHING",
+    )
+    .bind(guild_id.to_string())
+    .bind(user_id.to_string())
+    .bind(to_json(&MemberData::new(guild_id, user_id))?)
+    .execute(&mut *tx)
+    .await
+    .wrap_err("insert member")?;
+
+    let data = sqlx::query_scalar(
+        "SELECT data FROM members WHERE guild_id = $1 AND user_id = $2 FOR UPDATE",
+    )
+    .bind(guild_id.to_string())
+    .bind(user_id.to_string())
+    .fetch_one(&mut *tx)
+    .await
+    .wrap_err("find member")?;
+
+    let mut member = from_json::<MemberData>(data)?;
+    let result = update(&mut member);
+
+    sqlx::query("UPDATE members SET data = $3 WHERE guild_id = $1 AND user_id = $2")
+        .bind(guild_id.to_string())
+        .bind(user_id.to_string())
+        .bind(to_json(&member)?)
+        .execute(&mut *tx)
+        .await
+        .wrap_err("update member")?;
+
+    Ok(result)
+}
+
+async fn set_verdict_in(
+    tx: &mut Transaction<'_, Postgres>,
+    guild_id: SnowflakeId,
+    lawsuit_id: Uuid,
+    verdict: Option<&str>,
+) -> Result<()> {
+    let was_open: Option<bool> = sqlx::query_scalar(
+        "SELECT data->'verdict' = 'null' FROM lawsuits
+        WHERE guild_id = $1 AND id = $2 FOR UPDATE",
+    )
+    .bind(guild_id.to_string())
+    .bind(lawsuit_id.to_string())
+    .fetch_optional(&mut *tx)
+    .await
+    .wrap_err("find lawsuit")?;
+
+    sqlx::query(
+        "UPDATE lawsuits
+        SET data = jsonb_set(jsonb_set(data, '{verdict}', $3), '{closed_at}', $4)
+        WHERE guild_id = $1 AND id = $2",
+    )
+    .bind(guild_id.to_string())
+    .bind(lawsuit_id.to_string())
+    .bind(serde_json::Value::from(verdict))
+    .bind(to_json(&verdict.map(|_| DateTime::now()))?)
+    .execute(&mut *tx)
+    .await
+    .wrap_err("set lawsuit verdict")?;
+
+    let change = match (was_open, verdict.is_some()) {
+        (Some(true), true) => -1,
+        (Some(false), false) => 1,
+        _ => 0,
+    };
+    add_to_counter(&mut *tx, OPEN_LAWSUITS_COUNTER, change).await
+}
+
+async fn count_open_lawsuits_of<'e>(executor: impl PgExecutor<'e>, guild_id: &str) -> Result<i64> {
+    sqlx::query_scalar(
+        "SELECT COUNT(*) FROM lawsuits WHERE guild_id = $1 AND data->'verdict' = 'null'",
+    )
+    .bind(guild_id)
+    .fetch_one(executor)
+    .await
+    .wrap_err("count open lawsuits of guild")
+}
+
+impl Postgres {
+    #[tracing::instrument(skip(url))]
+    pub async fn connect(url: &str) -> Result<Self> {
+        let pool = PgPoolOptions::new()
+            .connect(url)
+            .await
+            .wrap_err("failed to connect to postgres")?;
+
+        info!("Creating tables");
+
+        for statement in SCHEMA {
+            sqlx::query(statement)
+                .execute(&pool)
+                .await
+                .wrap_err("create tables")?;
+        }
+
+        Ok(Self { pool })
+    }
+
+    async fn counter(&self, name: &str) -> Result<u64> {
+        let count: Option<i64> = sqlx::query_scalar("SELECT count FROM counters WHERE name = $1")
+            .bind(name)
+            .fetch_optional(&self.pool)
+            .await
+            .wrap_err_with(|| format!("find counter {name}"))?;
+
+        Ok(count.unwrap_or_default().max(0) as u64)
+    }
+
+    /// Loads the state, changes it and stores it again. The row is locked in the meantime, so
+    /// that concurrent changes don't get lost.
+    async fn update_state<R: Send>(
+        &self,
+        guild_id: SnowflakeId,
+        update: impl FnOnce(&mut State) -> R + Send,
+    ) -> Result<R> {
+        let _ = self.find_or_insert_state(guild_id).await?;
+
+        let mut tx = self.pool.begin().await.wrap_err("begin transaction")?;
+        let result = update_state_in(&mut tx, guild_id, update).await?;
+        tx.commit().await.wrap_err("commit state")?;
+
+        Ok(result)
+    }
+
+    /// Like [`Self::update_state`] for the judge registry of the guild.
+    async fn update_judges<R: Send>(
+        &self,
+        guild_id: SnowflakeId,
+        update: impl FnOnce(&mut JudgeRegistry) -> R + Send,
+    ) -> Result<R> {
+        sqlx::query(
+            "INSERT INTO judges (guild_id, data) VALUES ($1, $2)
+            ON CONFLICT (guild_id) DO NOTHING",
+        )
+        .bind(guild_id.to_string())
+        .bind(to_json(&JudgeRegistry::new(guild_id))?)
+        .execute(&self.pool)
+        .await
+        .wrap_err("insert judge registry")?;
+
+        let mut tx = self.pool.begin().await.wrap_err("begin transaction")?;
+        let data = sqlx::query_scalar("SELECT data FROM judges WHERE guild_id = $1 FOR UPDATE")
+            .bind(guild_id.to_string())
+            .fetch_one(&mut tx)
+            .await
+            .wrap_err("find judge registry")?;
+
+        let mut registry = from_json::<JudgeRegistry>(data)?;
+        let result = update(&mut registry);
+
+        sqlx::query("UPDATE judges SET data = $2 WHERE guild_id = $1")
+            .bind(guild_id.to_string())
+            .bind(to_json(&registry)?)
+            .execute(&mut tx)
+            .await
+            .wrap_err("update judge registry")?;
+        tx.commit().await.wrap_err("commit judge registry")?;
+
+        Ok(result)
+    }
+
+    /// Like [`Self::update_state`] for what is stored about the member.
+    async fn update_member<R: Send>(
+        &self,
+        guild_id: SnowflakeId,
+        user_id: SnowflakeId,
+        update: impl FnOnce(&mut MemberData) -> R + Send,
+    ) -> Result<R> {
+        let mut tx = self.pool.begin().await.wrap_err("begin transaction")?;
+        let result = update_member_in(&mut tx, guild_id, user_id, update).await?;
+        tx.commit().await.wrap_err("commit member")?;
+
+        Ok(result)
+    }
+
+    /// Like [`Self::update_state`], but nothing happens if the user isn't in prison.
+    async fn update_prison_entry(
+        &self,
+        guild_id: SnowflakeId,
+        user_id: SnowflakeId,
+        update: impl FnOnce(&mut PrisonEntry) + Send,
+    ) -> Result<()> {
+        let mut tx = self.pool.begin().await.wrap_err("begin transaction")?;
+        let data = sqlx::query_scalar(
+            "SELECT data FROM prison WHERE guild_id = $1 AND user_id = $2 FOR UPDATE",
+        )
+        .bind(guild_id.to_string())
+        .bind(user_id.to_string())
+        .fetch_optional(&mut tx)
+        .await
+        .wrap_err("find prison entry")?;
+
+        let mut entry = match data {
+            Some(data) => from_json::<PrisonEntry>(data)?,
+            None => return Ok(()),
+        };
+        update(&mut entry);
+
+        sqlx::query("UPDATE prison SET data = $3 WHERE guild_id = $1 AND user_id = $2")
+            .bind(guild_id.to_string())
+            .bind(user_id.to_string())
+            .bind(to_json(&entry)?)
+            .execute(&mut tx)
+            .await
+            .wrap_err("update prison entry")?;
+        tx.commit().await.wrap_err("commit prison entry")?;
+
+        Ok(())
+    }
+
+    /// Like [`Self::update_state`] for a lawsuit, returns `None` if the lawsuit doesn't exist.
+    async fn update_lawsuit<R: Send>(
+        &self,
+        guild_id: SnowflakeId,
+        lawsuit_id: Uuid,
+        update: impl FnOnce(&mut Lawsuit) -> R + Send,
+    ) -> Result<Option<R>> {
+        let mut tx = self.pool.begin().await.wrap_err("begin transaction")?;
+        let data = sqlx::query_scalar(
+            "SELECT data FROM lawsuits WHERE guild_id = $1 AND id = $2 FOR UPDATE",
+        )
+        .bind(guild_id.to_string())
+        .bind(lawsuit_id.to_string())
+        .fetch_optional(&mut tx)
+        .await
+        .wrap_err("find lawsuit")?;
+
+        let mut lawsuit = match data {
+            Some(data) => from_json::<Lawsuit>(data)?,
+            None => return Ok(None),
+        };
+        let result = update(&mut lawsuit);
+
+        sqlx::query("UPDATE lawsuits SET data = $3 WHERE guild_id = $1 AND id = $2")
+            .bind(guild_id.to_string())
+            .bind(lawsuit_id.to_string())
+            .bind(to_json(&lawsuit)?)
+            .execute(&mut tx)
+            .await
+            .wrap_err("update lawsuit")?;
+        tx.commit().await.wrap_err("commit lawsuit")?;
+
+        Ok(Some(result))
+    }
+}
+
+#[async_trait]
+impl Storage for Postgres {
+    async fn ping(&self) -> Result<()> {
+        sqlx::query("SELECT 1")
+            .execute(&self.pool)
+            .await
+            .wrap_err("ping postgres")?;
+
+        Ok(())
+    }
+
+    async fn close(&self) {
+        self.pool.close().await;
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn find_or_insert_state(&self, guild_id: SnowflakeId) -> Result<State> {
+        let data = sqlx::query_scalar("SELECT data FROM state WHERE guild_id = $1")
+            .bind(guild_id.to_string())
+            .fetch_optional(&self.pool)
+            .await
+            .wrap_err("find state")?;
+
+        match data {
+            Some(data) => from_json(data),
+            None => {
+                info!(%guild_id, "No state found for guild, creating new state");
+                let state = State::new(guild_id);
+                sqlx::query(
+                    "INSERT INTO state (guild_id, data) VALUES ($1, $2)
+                    ON CONFLICT (guild_id) DO NOTHING",
+                )
+                .bind(guild_id.to_string())
+                .bind(to_json(&state)?)
+                .execute(&self.pool)
+                .await
+                .wrap_err("insert state")?;
+                Ok(state)
+            }
+        }
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn find_state(&self, guild_id: SnowflakeId) -> Result<Option<State>> {
+        let data = sqlx::query_scalar("SELECT data FROM state WHERE guild_id = $1")
+            .bind(guild_id.to_string())
+            .fetch_optional(&self.pool)
+            .await
+            .wrap_err("find state")?;
+
+        data.map(from_json).transpose()
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn set_court_category(&self, guild_id: SnowflakeId, category: SnowflakeId) -> Result<()> {
+        self.update_state(guild_id, |state| state.court_category = Some(category))
+            .await
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn set_appeal_category(
+        &self,
+        guild_id: SnowflakeId,
+        category: SnowflakeId,
+        room_name: Option<&str>,
+    ) -> Result<()> {
+        self.update_state(guild_id, |state| {
+            state.appeal_category = Some(category);
+            state.appeal_room_name = room_name.map(ToOwned::to_owned);
+        })
+        .await
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn set_prison_role(
+        &self,
+        guild_id: SnowflakeId,
+        prison_role: SnowflakeId,
+        template: &RoleTemplate,
+    ) -> Result<()> {
+        self.update_state(guild_id, |state| {
+            state.prison_role = Some(prison_role);
+            state.prison_role_template = Some(template.clone());
+        })
+        .await
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn set_probation_role(
+        &self,
+        guild_id: SnowflakeId,
+        probation_role: SnowflakeId,
+    ) -> Result<()> {
+        self.update_state(guild_id, |state| {
+            state.probation_role = Some(probation_role)
+        })
+        .await
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn set_prison_channel(
+        &self,
+        guild_id: SnowflakeId,
+        prison_channel: SnowflakeId,
+        lockdown: bool,
+    ) -> Result<()> {
+        self.update_state(guild_id, |state| {
+            state.prison_channel = Some(prison_channel);
+            state.prison_lockdown = lockdown;
+        })
+        .await
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn set_strip_roles_on_arrest(
+        &self,
+        guild_id: SnowflakeId,
+        strip_roles: bool,
+    ) -> Result<()> {
+        self.update_state(guild_id, |state| state.strip_roles_on_arrest = strip_roles)
+            .await
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn set_moderator_channel(
+        &self,
+        guild_id: SnowflakeId,
+        moderator_channel: SnowflakeId,
+    ) -> Result<()> {
+        self.update_state(guild_id, |state| {
+            state.moderator_channel = Some(moderator_channel)
+        })
+        .await
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn set_notify_prisoners(
+        &self,
+        guild_id: SnowflakeId,
+        notify_prisoners: bool,
+    ) -> Result<()> {
+        self.update_state(guild_id, |state| state.notify_prisoners = notify_prisoners)
+            .await
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn set_strict_lawyers(&self, guild_id: SnowflakeId, strict_lawyers: bool) -> Result<()> {
+        self.update_state(guild_id, |state| state.strict_lawyers = strict_lawyers)
+            .await
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn set_conflict_policy(
+        &self,
+        guild_id: SnowflakeId,
+        conflict_policy: ConflictPolicy,
+    ) -> Result<()> {
+        self.update_state(guild_id, |state| state.conflict_policy = conflict_policy)
+            .await
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn set_appeal_judge(
+        &self,
+        guild_id: SnowflakeId,
+        appeal_judge: SnowflakeId,
+    ) -> Result<()> {
+        self.update_state(guild_id, |state| state.appeal_judge = Some(appeal_judge))
+            .await
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn set_audit_channel(
+        &self,
+        guild_id: SnowflakeId,
+        audit_channel: SnowflakeId,
+    ) -> Result<()> {
+        self.update_state(guild_id, |state| state.audit_channel = Some(audit_channel))
+            .await
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn set_error_channel(
+        &self,
+        guild_id: SnowflakeId,
+        error_channel: SnowflakeId,
+    ) -> Result<()> {
+        self.update_state(guild_id, |state| state.error_channel = Some(error_channel))
+            .await
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn set_records_channel(
+        &self,
+        guild_id: SnowflakeId,
+        records_channel: SnowflakeId,
+    ) -> Result<()> {
+        self.update_state(guild_id, |state| {
+            state.records_channel = Some(records_channel)
+        })
+        .await
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn set_news_role(
+        &self,
+        guild_id: SnowflakeId,
+        role_id: Option<SnowflakeId>,
+    ) -> Result<()> {
+        self.update_state(guild_id, |state| state.news_role = role_id)
+            .await
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn set_report_channel(
+        &self,
+        guild_id: SnowflakeId,
+        report_channel: Option<SnowflakeId>,
+    ) -> Result<()> {
+        self.update_state(guild_id, |state| state.report_channel = report_channel)
+            .await
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn set_statute_of_limitations(
+        &self,
+        guild_id: SnowflakeId,
+        max_age: Option<Duration>,
+    ) -> Result<()> {
+        self.update_state(guild_id, |state| {
+            state.statute_of_limitations = max_age.map(|max_age| max_age.as_secs())
+        })
+        .await
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn set_inactivity_nudge(
+        &self,
+        guild_id: SnowflakeId,
+        after: Option<Duration>,
+    ) -> Result<()> {
+        self.update_state(guild_id, |state| {
+            state.inactivity_nudge = after.map(|after| after.as_secs())
+        })
+        .await
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn set_max_open_lawsuits(&self, guild_id: SnowflakeId, max: Option<u32>) -> Result<()> {
+        self.update_state(guild_id, |state| state.max_open_lawsuits = max)
+            .await
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn set_filing_cooldown(
+        &self,
+        guild_id: SnowflakeId,
+        cooldown: Option<Duration>,
+    ) -> Result<()> {
+        self.update_state(guild_id, |state| {
+            state.filing_cooldown = cooldown.map(|cooldown| cooldown.as_secs())
+        })
+        .await
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn set_court_slowmode(
+        &self,
+        guild_id: SnowflakeId,
+        slowmode: Option<Duration>,
+    ) -> Result<()> {
+        self.update_state(guild_id, |state| {
+            state.court_slowmode = slowmode.map(|slowmode| slowmode.as_secs())
+        })
+        .await
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn set_last_filed(
+        &self,
+        guild_id: SnowflakeId,
+        user_id: SnowflakeId,
+        filed_at: DateTime,
+    ) -> Result<()> {
+        self.update_member(guild_id, user_id, |member| {
+            member.last_filed = Some(filed_at)
+        })
+        .await
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn next_case_number(&self, guild_id: SnowflakeId, year: i64) -> Result<u32> {
+        self.update_state(guild_id, |state| {
+            let counter = state.case_counters.entry(year.to_string()).or_default();
+            *counter += 1;
+            *counter
+        })
+        .await
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn set_locale(&self, guild_id: SnowflakeId, locale: Locale) -> Result<()> {
+        self.update_state(guild_id, |state| state.locale = locale)
+            .await
+    }
+
+    #[tracing::instrument(skip(self, webhook_url))]
+    async fn set_webhook_url(
+        &self,
+        guild_id: SnowflakeId,
+        webhook_url: Option<&str>,
+    ) -> Result<()> {
+        self.update_state(guild_id, |state| {
+            state.webhook_url = webhook_url.map(str::to_owned)
+        })
+        .await
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn find_member(&self, guild_id: SnowflakeId, user_id: SnowflakeId) -> Result<MemberData> {
+        let data =
+            sqlx::query_scalar("SELECT data FROM members WHERE guild_id = $1 AND user_id = $2")
+                .bind(guild_id.to_string())
+                .bind(user_id.to_string())
+                .fetch_optional(&self.pool)
+                .await
+                .wrap_err("find member")?;
+
+        match data {
+            Some(data) => from_json(data),
+            None => Ok(MemberData::new(guild_id, user_id)),
+        }
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn find_members(&self, guild_id: SnowflakeId) -> Result<Vec<MemberData>> {
+        sqlx::query_scalar("SELECT data FROM members WHERE guild_id = $1")
+            .bind(guild_id.to_string())
+            .fetch_all(&self.pool)
+            .await
+            .wrap_err("find members")?
+            .into_iter()
+            .map(from_json)
+            .collect()
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn add_to_balance(
+        &self,
+        guild_id: SnowflakeId,
+        user_id: SnowflakeId,
+        amount: i64,
+    ) -> Result<i64> {
+        self.update_member(guild_id, user_id, |member| {
+            member.balance = member.balance.saturating_add(amount);
+            member.balance
+        })
+        .await
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn transfer(
+        &self,
+        guild_id: SnowflakeId,
+        from: SnowflakeId,
+        to: SnowflakeId,
+        amount: i64,
+        overdraw: bool,
+    ) -> Result<Option<i64>> {
+        let mut tx = self.pool.begin().await.wrap_err("begin transaction")?;
+
+        // always locked in the same order, so that opposite transfers can't deadlock
+        let mut balance = None;
+        for user_id in [from.min(to), from.max(to)] {
+            update_member_in(&mut tx, guild_id, user_id, |member| {
+                if user_id == from {
+                    if !overdraw && member.balance < amount {
+                        return;
+                    }
+                    member.balance = member.balance.saturating_sub(amount);
+                    balance = Some(member.balance);
+                }
+                if user_id == to {
+                    member.balance = member.balance.saturating_add(amount);
+                }
+            })
+            .await?;
+        }
+
+        // dropping the transaction takes back the money that was already given
+        if balance.is_some() {
+            tx.commit().await.wrap_err("commit transfer")?;
+        }
+        Ok(balance)
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn set_lawyer_fee(
+        &self,
+        guild_id: SnowflakeId,
+        lawyer: SnowflakeId,
+        fee: Option<LawyerFee>,
+    ) -> Result<()> {
+        self.update_member(guild_id, lawyer, |member| member.lawyer_fee = fee)
+            .await
+    }
+
+    #[tracing::instrument(skip(self, questions))]
+    async fn set_bar_questions(
+        &self,
+        guild_id: SnowflakeId,
+        questions: &[BarQuestion],
+    ) -> Result<()> {
+        self.update_state(guild_id, |state| state.bar_questions = questions.to_vec())
+            .await
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn admit_lawyer(&self, guild_id: SnowflakeId, lawyer: SnowflakeId) -> Result<()> {
+        self.update_state(guild_id, |state| {
+            if !state.admitted_lawyers.contains(&lawyer) {
+                state.admitted_lawyers.push(lawyer);
+            }
+        })
+        .await
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn set_jury_pool_member(
+        &self,
+        guild_id: SnowflakeId,
+        user_id: SnowflakeId,
+        member: bool,
+    ) -> Result<()> {
+        self.update_state(guild_id, |state| {
+            state.jury_pool.retain(|juror| *juror != user_id);
+            if member {
+                state.jury_pool.push(user_id);
+            }
+        })
+        .await
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn add_gag_order(&self, guild_id: SnowflakeId, order: &GagOrder) -> Result<()> {
+        self.update_state(guild_id, |state| {
+            if !state.gag_orders.contains(order) {
+                state.gag_orders.push(order.clone());
+            }
+        })
+        .await
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn remove_gag_orders(&self, guild_id: SnowflakeId, lawsuit_id: Uuid) -> Result<()> {
+        self.update_state(guild_id, |state| {
+            state
+                .gag_orders
+                .retain(|order| order.lawsuit_id != lawsuit_id)
+        })
+        .await
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn set_litigant_banned(
+        &self,
+        guild_id: SnowflakeId,
+        user_id: SnowflakeId,
+        banned: bool,
+    ) -> Result<()> {
+        self.update_state(guild_id, |state| {
+            state
+                .banned_litigants
+                .retain(|litigant| *litigant != user_id);
+            if banned {
+                state.banned_litigants.push(user_id);
+            }
+        })
+        .await
+    }
+
+    #[tracing::instrument(skip(self, text))]
+    async fn set_message_template(
+        &self,
+        guild_id: SnowflakeId,
+        template: Template,
+        text: Option<&str>,
+    ) -> Result<()> {
+        self.update_state(guild_id, |state| match text {
+            Some(text) => {
+                state
+                    .message_templates
+                    .insert(template.key().to_owned(), text.to_owned());
+            }
+            None => {
+                state.message_templates.remove(template.key());
+            }
+        })
+        .await
+    }
+
+    #[tracing::instrument(skip(self, api_token_hash))]
+    async fn set_api_token_hash(
+        &self,
+        guild_id: SnowflakeId,
+        api_token_hash: Option<&str>,
+    ) -> Result<()> {
+        self.update_state(guild_id, |state| {
+            state.api_token_hash = api_token_hash.map(str::to_owned)
+        })
+        .await
+    }
+
+    #[tracing::instrument(skip(self, calendar_token_hash))]
+    async fn set_calendar_token_hash(
+        &self,
+        guild_id: SnowflakeId,
+        calendar_token_hash: Option<&str>,
+    ) -> Result<()> {
+        self.update_state(guild_id, |state| {
+            state.calendar_token_hash = calendar_token_hash.map(str::to_owned)
+        })
+        .await
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn set_court_role(
+        &self,
+        guild_id: SnowflakeId,
+        court_role: CourtRole,
+        role_id: SnowflakeId,
+    ) -> Result<()> {
+        self.update_state(guild_id, |state| {
+            *court_role.role_id_mut(state) = Some(role_id)
+        })
+        .await
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn set_prison_tier(&self, guild_id: SnowflakeId, tier: &PrisonTier) -> Result<()> {
+        self.update_state(guild_id, |state| {
+            state.prison_tiers.retain(|other| other.name != tier.name);
+            state.prison_tiers.push(tier.clone());
+        })
+        .await
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn remove_prison_tier(&self, guild_id: SnowflakeId, name: &str) -> Result<()> {
+        self.update_state(guild_id, |state| {
+            state.prison_tiers.retain(|tier| tier.name != name)
+        })
+        .await
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn add_court_room(&self, guild_id: SnowflakeId, room: &CourtRoom) -> Result<()> {
+        self.update_state(guild_id, |state| state.court_rooms.push(room.clone()))
+            .await
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn remove_court_rooms(&self, guild_id: SnowflakeId) -> Result<()> {
+        self.update_state(guild_id, |state| state.court_rooms.clear())
+            .await
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn add_lawsuit(&self, lawsuit: &Lawsuit) -> Result<()> {
+        let mut tx = self.pool.begin().await.wrap_err("begin transaction")?;
+        sqlx::query("INSERT INTO lawsuits (guild_id, id, data) VALUES ($1, $2, $3)")
+            .bind(lawsuit.guild_id.to_string())
+            .bind(lawsuit.id.to_string())
+            .bind(to_json(lawsuit)?)
+            .execute(&mut tx)
+            .await
+            .wrap_err("insert lawsuit")?;
+        add_to_counter(&mut tx, LAWSUITS_COUNTER, 1).await?;
+        add_to_counter(
+            &mut tx,
+            OPEN_LAWSUITS_COUNTER,
+            lawsuit.verdict.is_none().into(),
+        )
+        .await?;
+        tx.commit().await.wrap_err("commit lawsuit")?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn delete_lawsuits(&self, guild_id: SnowflakeId) -> Result<u64> {
+        let _ = self.find_or_insert_state(guild_id).await?;
+        let lawsuits = self.find_lawsuits(guild_id).await?;
+
+        let mut tx = self.pool.begin().await.wrap_err("begin transaction")?;
+        let deleted = sqlx::query("DELETE FROM lawsuits WHERE guild_id = $1")
+            .bind(guild_id.to_string())
+            .execute(&mut tx)
+            .await
+            .wrap_err("delete lawsuits")?
+            .rows_affected();
+        add_to_counter(&mut tx, OPEN_LAWSUITS_COUNTER, -count_open(&lawsuits)).await?;
+        update_state_in(&mut tx, guild_id, |state| {
+            for room in &mut state.court_rooms {
+                room.ongoing_lawsuit = false;
+            }
+        })
+        .await?;
+        tx.commit().await.wrap_err("commit delete lawsuits")?;
+
+        Ok(deleted)
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn find_lawsuits(&self, guild_id: SnowflakeId) -> Result<Vec<Lawsuit>> {
+        sqlx::query_scalar("SELECT data FROM lawsuits WHERE guild_id = $1")
+            .bind(guild_id.to_string())
+            .fetch_all(&self.pool)
+            .await
+            .wrap_err("find lawsuits")?
+            .into_iter()
+            .map(from_json)
+            .collect()
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn find_open_lawsuits(&self, guild_id: SnowflakeId) -> Result<Vec<Lawsuit>> {
+        sqlx::query_scalar(
+            "SELECT data FROM lawsuits WHERE guild_id = $1 AND data->'verdict' = 'null'",
+        )
+        .bind(guild_id.to_string())
+        .fetch_all(&self.pool)
+        .await
+        .wrap_err("find open lawsuits")?
+        .into_iter()
+        .map(from_json)
+        .collect()
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn find_open_lawsuits_by_reason(
+        &self,
+        guild_id: SnowflakeId,
+        prefix: &str,
+        limit: usize,
+    ) -> Result<Vec<Lawsuit>> {
+        // backslash is the default escape character of LIKE
+        let pattern = format!(
+            "{}%",
+            prefix
+                .replace('\\', "\\\\")
+                .replace('%', "\\%")
+                .replace('_', "\\_")
+        );
+        sqlx::query_scalar(
+            "SELECT data FROM lawsuits WHERE guild_id = $1 AND data->'verdict' = 'null'
+            AND data->>'reason' ILIKE $2 LIMIT $3",
+        )
+        .bind(guild_id.to_string())
+        .bind(pattern)
+        .bind(limit as i64)
+        .fetch_all(&self.pool)
+        .await
+        .wrap_err("find open lawsuits by reason")?
+        .into_iter()
+        .map(from_json)
+        .collect()
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn search_closed_lawsuits(
+        &self,
+        guild_id: SnowflakeId,
+        query: &str,
+        limit: usize,
+    ) -> Result<Vec<Lawsuit>> {
+        sqlx::query_scalar(
+            "SELECT data FROM lawsuits,
+                to_tsvector('simple', (data->>'reason') || ' ' || (data->>'verdict')) document,
+                plainto_tsquery('simple', $2) query
+            WHERE guild_id = $1 AND data->'verdict' <> 'null'
+                AND data->'sealed' IS DISTINCT FROM 'true' AND document @@ query
+            ORDER BY ts_rank(document, query) DESC LIMIT $3",
+        )
+        .bind(guild_id.to_string())
+        .bind(query)
+        .bind(limit as i64)
+        .fetch_all(&self.pool)
+        .await
+        .wrap_err("search lawsuits")?
+        .into_iter()
+        .map(from_json)
+        .collect()
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn find_lawsuits_page(
+        &self,
+        guild_id: SnowflakeId,
+        after: Option<Uuid>,
+        limit: usize,
+    ) -> Result<Page<Lawsuit, Uuid>> {
+        let lawsuits = sqlx::query_scalar(
+            "SELECT data FROM lawsuits WHERE guild_id = $1 AND ($2::TEXT IS NULL OR id > $2)
+            ORDER BY id LIMIT $3",
+        )
+        .bind(guild_id.to_string())
+        .bind(after.map(|id| id.to_string()))
+        .bind((limit + 1) as i64)
+        .fetch_all(&self.pool)
+        .await
+        .wrap_err("find lawsuits page")?
+        .into_iter()
+        .map(from_json)
+        .collect::<Result<Vec<Lawsuit>>>()?;
+
+        Ok(Page::new(lawsuits, limit, |lawsuit| lawsuit.id))
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn count_open_lawsuits(&self) -> Result<Vec<(SnowflakeId, u64)>> {
+        sqlx::query_as(
+            "SELECT guild_id, COUNT(*) FROM lawsuits WHERE data->'verdict' = 'null'
+            GROUP BY guild_id",
+        )
+        .fetch_all(&self.pool)
+        .await
+        .wrap_err("count open lawsuits")?
+        .into_iter()
+        .map(to_guild_count)
+        .collect()
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn set_court_room_ongoing(
+        &self,
+        guild_id: SnowflakeId,
+        channel_id: SnowflakeId,
+        ongoing_lawsuit: bool,
+    ) -> Result<()> {
+        self.update_state(guild_id, |state| {
+            if let Some(room) = state
+                .court_rooms
+                .iter_mut()
+                .find(|room| room.channel_id == channel_id)
+            {
+                room.ongoing_lawsuit = ongoing_lawsuit;
+                if ongoing_lawsuit {
+                    room.last_used = Some(DateTime::now());
+                    room.nudges = 0;
+                }
+            }
+        })
+        .await
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn claim_court_room(
+        &self,
+        guild_id: SnowflakeId,
+        channel_id: SnowflakeId,
+        version: u64,
+    ) -> Result<bool> {
+        // the state is locked while it's compared, so nobody can change it in between
+        self.update_state(guild_id, |state| {
+            if state.version != version {
+                return false;
+            }
+            match state
+                .court_rooms
+                .iter_mut()
+                .find(|room| room.channel_id == channel_id)
+            {
+                Some(room) => {
+                    room.ongoing_lawsuit = true;
+                    room.last_used = Some(DateTime::now());
+                    room.nudges = 0;
+                    true
+                }
+                None => false,
+            }
+        })
+        .await
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn set_room_activity(
+        &self,
+        guild_id: SnowflakeId,
+        channel_id: SnowflakeId,
+        last_activity: DateTime,
+    ) -> Result<()> {
+        self.update_state(guild_id, |state| {
+            if let Some(room) = state
+                .court_rooms
+                .iter_mut()
+                .find(|room| room.channel_id == channel_id)
+            {
+                room.last_activity = Some(last_activity);
+                room.nudges = 0;
+            }
+        })
+        .await
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn set_room_nudges(
+        &self,
+        guild_id: SnowflakeId,
+        channel_id: SnowflakeId,
+        nudges: u32,
+    ) -> Result<()> {
+        self.update_state(guild_id, |state| {
+            if let Some(room) = state
+                .court_rooms
+                .iter_mut()
+                .find(|room| room.channel_id == channel_id)
+            {
+                room.nudges = nudges;
+            }
+        })
+        .await
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn set_room_closed_to_public(
+        &self,
+        guild_id: SnowflakeId,
+        channel_id: SnowflakeId,
+        closed: bool,
+    ) -> Result<()> {
+        self.update_state(guild_id, |state| {
+            if let Some(room) = state
+                .court_rooms
+                .iter_mut()
+                .find(|room| room.channel_id == channel_id)
+            {
+                room.closed_to_public = closed;
+            }
+        })
+        .await
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn set_lawsuit_verdict(
+        &self,
+        guild_id: SnowflakeId,
+        lawsuit_id: Uuid,
+        verdict: Option<&str>,
+    ) -> Result<()> {
+        let mut tx = self.pool.begin().await.wrap_err("begin transaction")?;
+        set_verdict_in(&mut tx, guild_id, lawsuit_id, verdict).await?;
+        tx.commit().await.wrap_err("commit verdict")?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn close_lawsuit(&self, guild_id: SnowflakeId, lawsuit: &Lawsuit) -> Result<()> {
+        let _ = self.find_or_insert_state(guild_id).await?;
+
+        let mut tx = self.pool.begin().await.wrap_err("begin transaction")?;
+        set_verdict_in(&mut tx, guild_id, lawsuit.id, lawsuit.verdict.as_deref()).await?;
+        sqlx::query(
+            "UPDATE lawsuits
+            SET data = jsonb_set(jsonb_set(jsonb_set(data,
+                '{verdict_details}', $3), '{sealed}', $4), '{overturned}', $5)
+            WHERE guild_id = $1 AND id = $2",
+        )
+        .bind(guild_id.to_string())
+        .bind(lawsuit.id.to_string())
+        .bind(to_json(&lawsuit.verdict_details)?)
+        .bind(serde_json::Value::from(lawsuit.sealed))
+        .bind(serde_json::Value::from(lawsuit.overturned))
+        .execute(&mut tx)
+        .await
+        .wrap_err("set verdict details")?;
+        update_state_in(&mut tx, guild_id, |state| {
+            if let Some(room) = state
+                .court_rooms
+                .iter_mut()
+                .find(|room| room.channel_id == lawsuit.court_room)
+            {
+                room.ongoing_lawsuit = false;
+            }
+        })
+        .await?;
+        tx.commit().await.wrap_err("commit verdict")?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self, evidence))]
+    async fn add_evidence(
+        &self,
+        guild_id: SnowflakeId,
+        lawsuit_id: Uuid,
+        evidence: &Evidence,
+    ) -> Result<()> {
+        sqlx::query(
+            "UPDATE lawsuits
+            SET data = jsonb_set(data, '{evidence}', COALESCE(data->'evidence', '[]') || $3)
+            WHERE guild_id = $1 AND id = $2",
+        )
+        .bind(guild_id.to_string())
+        .bind(lawsuit_id.to_string())
+        .bind(serde_json::Value::Array(vec![to_json(evidence)?]))
+        .execute(&self.pool)
+        .await
+        .wrap_err("add evidence")?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn add_witness(
+        &self,
+        guild_id: SnowflakeId,
+        lawsuit_id: Uuid,
+        witness: &Witness,
+    ) -> Result<()> {
+        sqlx::query(
+            "UPDATE lawsuits
+            SET data = jsonb_set(data, '{witnesses}', COALESCE(data->'witnesses', '[]') || $3)
+            WHERE guild_id = $1 AND id = $2",
+        )
+        .bind(guild_id.to_string())
+        .bind(lawsuit_id.to_string())
+        .bind(serde_json::Value::Array(vec![to_json(witness)?]))
+        .execute(&self.pool)
+        .await
+        .wrap_err("add witness")?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn answer_subpoena(
+        &self,
+        guild_id: SnowflakeId,
+        lawsuit_id: Uuid,
+        witness_id: SnowflakeId,
+        response: SubpoenaResponse,
+    ) -> Result<bool> {
+        let answered = self
+            .update_lawsuit(guild_id, lawsuit_id, |lawsuit| {
+                match lawsuit.witnesses.iter_mut().find(|witness| {
+                    witness.user_id == witness_id && witness.response == SubpoenaResponse::Pending
+                }) {
+                    Some(witness) => {
+                        witness.response = response;
+                        true
+                    }
+                    None => false,
+                }
+            })
+            .await?;
+        Ok(answered.unwrap_or(false))
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn set_jury_tally(
+        &self,
+        guild_id: SnowflakeId,
+        lawsuit_id: Uuid,
+        tally: &JuryTally,
+    ) -> Result<()> {
+        sqlx::query(
+            "UPDATE lawsuits SET data = jsonb_set(data, '{jury_tally}', $3)
+            WHERE guild_id = $1 AND id = $2",
+        )
+        .bind(guild_id.to_string())
+        .bind(lawsuit_id.to_string())
+        .bind(to_json(tally)?)
+        .execute(&self.pool)
+        .await
+        .wrap_err("set jury tally")?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn add_billing(
+        &self,
+        guild_id: SnowflakeId,
+        lawsuit_id: Uuid,
+        billing: &Billing,
+    ) -> Result<()> {
+        sqlx::query(
+            "UPDATE lawsuits
+            SET data = jsonb_set(data, '{billing}', COALESCE(data->'billing', '[]') || $3)
+            WHERE guild_id = $1 AND id = $2",
+        )
+        .bind(guild_id.to_string())
+        .bind(lawsuit_id.to_string())
+        .bind(serde_json::Value::Array(vec![to_json(billing)?]))
+        .execute(&self.pool)
+        .await
+        .wrap_err("add billing")?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn add_contempt(
+        &self,
+        guild_id: SnowflakeId,
+        lawsuit_id: Uuid,
+        contempt: &Contempt,
+    ) -> Result<()> {
+        sqlx::query(
+            "UPDATE lawsuits
+            SET data = jsonb_set(data, '{contempts}', COALESCE(data->'contempts', '[]') || $3)
+            WHERE guild_id = $1 AND id = $2",
+        )
+        .bind(guild_id.to_string())
+        .bind(lawsuit_id.to_string())
+        .bind(serde_json::Value::Array(vec![to_json(contempt)?]))
+        .execute(&self.pool)
+        .await
+        .wrap_err("add contempt")?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn add_ejected(
+        &self,
+        guild_id: SnowflakeId,
+        lawsuit_id: Uuid,
+        user_id: SnowflakeId,
+    ) -> Result<()> {
+        let ejected = serde_json::Value::Array(vec![to_json(&user_id)?]);
+        sqlx::query(
+            "UPDATE lawsuits
+            SET data = jsonb_set(data, '{ejected}', COALESCE(data->'ejected', '[]') || $3)
+            WHERE guild_id = $1 AND id = $2 AND NOT COALESCE(data->'ejected', '[]') @> $3",
+        )
+        .bind(guild_id.to_string())
+        .bind(lawsuit_id.to_string())
+        .bind(ejected)
+        .execute(&self.pool)
+        .await
+        .wrap_err("add ejected")?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn set_lawsuit_hearing(
+        &self,
+        guild_id: SnowflakeId,
+        lawsuit_id: Uuid,
+        hearing_at: Option<DateTime>,
+    ) -> Result<()> {
+        sqlx::query(
+            "UPDATE lawsuits SET data = jsonb_set(data, '{hearing_at}', $3)
+            WHERE guild_id = $1 AND id = $2",
+        )
+        .bind(guild_id.to_string())
+        .bind(lawsuit_id.to_string())
+        .bind(to_json(&hearing_at)?)
+        .execute(&self.pool)
+        .await
+        .wrap_err("set lawsuit hearing")?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn add_citation(
+        &self,
+        guild_id: SnowflakeId,
+        lawsuit_id: Uuid,
+        precedent_id: Uuid,
+    ) -> Result<()> {
+        let links = [
+            ("cites", lawsuit_id, precedent_id),
+            ("cited_by", precedent_id, lawsuit_id),
+        ];
+        for (field, id, linked) in links {
+            let linked = serde_json::Value::Array(vec![to_json(&linked)?]);
+            sqlx::query(&format!(
+                "UPDATE lawsuits
+                SET data = jsonb_set(data, '{{{field}}}', COALESCE(data->'{field}', '[]') || $3)
+                WHERE guild_id = $1 AND id = $2 AND NOT COALESCE(data->'{field}', '[]') @> $3"
+            ))
+            .bind(guild_id.to_string())
+            .bind(id.to_string())
+            .bind(linked)
+            .execute(&self.pool)
+            .await
+            .wrap_err_with(|| format!("add {field}"))?;
+        }
+
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn set_lawsuit_jurors(
+        &self,
+        guild_id: SnowflakeId,
+        lawsuit_id: Uuid,
+        jurors: &[SnowflakeId],
+    ) -> Result<()> {
+        sqlx::query(
+            "UPDATE lawsuits SET data = jsonb_set(data, '{jurors}', $3)
+            WHERE guild_id = $1 AND id = $2",
+        )
+        .bind(guild_id.to_string())
+        .bind(lawsuit_id.to_string())
+        .bind(to_json(jurors)?)
+        .execute(&self.pool)
+        .await
+        .wrap_err("set lawsuit jurors")?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn set_lawsuit_fine(
+        &self,
+        guild_id: SnowflakeId,
+        lawsuit_id: Uuid,
+        fine: &Fine,
+    ) -> Result<()> {
+        sqlx::query(
+            "UPDATE lawsuits SET data = jsonb_set(data, '{fine}', $3)
+            WHERE guild_id = $1 AND id = $2",
+        )
+        .bind(guild_id.to_string())
+        .bind(lawsuit_id.to_string())
+        .bind(to_json(fine)?)
+        .execute(&self.pool)
+        .await
+        .wrap_err("set lawsuit fine")?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn trash_guild(&self, guild_id: SnowflakeId) -> Result<()> {
+        let trashed = TrashedState {
+            guild_id,
+            state: self.find_or_insert_state(guild_id).await?,
+            lawsuits: self.find_lawsuits(guild_id).await?,
+            members: self.find_members(guild_id).await?,
+            deleted_at: DateTime::now(),
+        };
+
+        let mut tx = self.pool.begin().await.wrap_err("begin transaction")?;
+        sqlx::query(
+            "INSERT INTO trash (guild_id, data, deleted_at) VALUES ($1, $2, $3)
+            ON CONFLICT (guild_id) DO UPDATE
+            SET data = EXCLUDED.data, deleted_at = EXCLUDED.deleted_at",
+        )
+        .bind(guild_id.to_string())
+        .bind(to_json(&trashed)?)
+        .bind(trashed.deleted_at.timestamp_millis())
+        .execute(&mut tx)
+        .await
+        .wrap_err("move state to trash")?;
+        for table in ["state", "lawsuits", "members"] {
+            sqlx::query(&format!("DELETE FROM {table} WHERE guild_id = $1"))
+                .bind(guild_id.to_string())
+                .execute(&mut tx)
+                .await
+                .wrap_err_with(|| format!("delete {table}"))?;
+        }
+        add_to_counter(
+            &mut tx,
+            OPEN_LAWSUITS_COUNTER,
+            -count_open(&trashed.lawsuits),
+        )
+        .await?;
+        tx.commit().await.wrap_err("commit trash")?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn restore_guild(&self, guild_id: SnowflakeId) -> Result<Option<State>> {
+        let oldest = DateTime::now().timestamp_millis() - TRASH_GRACE_PERIOD.as_millis() as i64;
+
+        let data =
+            sqlx::query_scalar("SELECT data FROM trash WHERE guild_id = $1 AND deleted_at > $2")
+                .bind(guild_id.to_string())
+                .bind(oldest)
+                .fetch_optional(&self.pool)
+                .await
+                .wrap_err("find trashed state")?;
+
+        let trashed = match data {
+            Some(data) => from_json::<TrashedState>(data)?,
+            None => return Ok(None),
+        };
+
+        let mut tx = self.pool.begin().await.wrap_err("begin transaction")?;
+        sqlx::query(
+            "INSERT INTO state (guild_id, data) VALUES ($1, $2)
+            ON CONFLICT (guild_id) DO UPDATE SET data = EXCLUDED.data",
+        )
+        .bind(guild_id.to_string())
+        .bind(to_json(&trashed.state)?)
+        .execute(&mut tx)
+        .await
+        .wrap_err("restore state")?;
+        let replaced_open_lawsuits = count_open_lawsuits_of(&mut tx, &guild_id.to_string()).await?;
+        sqlx::query("DELETE FROM lawsuits WHERE guild_id = $1")
+            .bind(guild_id.to_string())
+            .execute(&mut tx)
+            .await
+            .wrap_err("delete lawsuits")?;
+        for lawsuit in &trashed.lawsuits {
+            sqlx::query("INSERT INTO lawsuits (guild_id, id, data) VALUES ($1, $2, $3)")
+                .bind(guild_id.to_string())
+                .bind(lawsuit.id.to_string())
+                .bind(to_json(lawsuit)?)
+                .execute(&mut tx)
+                .await
+                .wrap_err("restore lawsuit")?;
+        }
+        add_to_counter(
+            &mut tx,
+            OPEN_LAWSUITS_COUNTER,
+            count_open(&trashed.lawsuits) - replaced_open_lawsuits,
+        )
+        .await?;
+        sqlx::query("DELETE FROM members WHERE guild_id = $1")
+            .bind(guild_id.to_string())
+            .execute(&mut tx)
+            .await
+            .wrap_err("delete members")?;
+        for member in &trashed.members {
+            sqlx::query("INSERT INTO members (guild_id, user_id, data) VALUES ($1, $2, $3)")
+                .bind(guild_id.to_string())
+                .bind(member.user_id.to_string())
+                .bind(to_json(member)?)
+                .execute(&mut tx)
+                .await
+                .wrap_err("restore member")?;
+        }
+        sqlx::query("DELETE FROM trash WHERE guild_id = $1")
+            .bind(guild_id.to_string())
+            .execute(&mut tx)
+            .await
+            .wrap_err("purge trash")?;
+        tx.commit().await.wrap_err("commit restore")?;
+
+        Ok(Some(trashed.state))
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn purge_trash(&self, guild_id: SnowflakeId) -> Result<()> {
+        sqlx::query("DELETE FROM trash WHERE guild_id = $1")
+            .bind(guild_id.to_string())
+            .execute(&self.pool)
+            .await
+            .wrap_err("purge trash")?;
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn erase_user(
+        &self,
+        guild_id: SnowflakeId,
+        user_id: SnowflakeId,
+    ) -> Result<ErasureReport> {
+        let mut report = ErasureReport::default();
+        self.update_state(guild_id, |state| state.anonymize_user(user_id, &mut report))
+            .await?;
+        report.registered_judge = self.unregister_judge(guild_id, user_id).await?;
+
+        let member = sqlx::query_scalar(
+            "DELETE FROM members WHERE guild_id = $1 AND user_id = $2 RETURNING data",
+        )
+        .bind(guild_id.to_string())
+        .bind(user_id.to_string())
+        .fetch_optional(&self.pool)
+        .await
+        .wrap_err("erase member")?
+        .map(from_json::<MemberData>)
+        .transpose()?;
+        if let Some(member) = member {
+            report.balance = member.balance != 0;
+            report.lawyer_fee = member.lawyer_fee.is_some();
+        }
+
+        for mut lawsuit in self.find_lawsuits(guild_id).await? {
+            if !lawsuit.anonymize_user(user_id) {
+                continue;
+            }
+            sqlx::query("UPDATE lawsuits SET data = $3 WHERE guild_id = $1 AND id = $2")
+                .bind(guild_id.to_string())
+                .bind(lawsuit.id.to_string())
+                .bind(to_json(&lawsuit)?)
+                .execute(&self.pool)
+                .await
+                .wrap_err("anonymize lawsuit")?;
+            report.lawsuits += 1;
+        }
+
+        for mut election in self.find_elections(guild_id).await? {
+            if !election.anonymize_user(user_id) {
+                continue;
+            }
+            sqlx::query("UPDATE elections SET data = $3 WHERE guild_id = $1 AND id = $2")
+                .bind(guild_id.to_string())
+                .bind(election.id.to_string())
+                .bind(to_json(&election)?)
+                .execute(&self.pool)
+                .await
+                .wrap_err("anonymize election")?;
+            report.elections += 1;
+        }
+
+        report.prison_entries =
+            sqlx::query("DELETE FROM prison WHERE guild_id = $1 AND user_id = $2")
+                .bind(guild_id.to_string())
+                .bind(user_id.to_string())
+                .execute(&self.pool)
+                .await
+                .wrap_err("erase prison entry")?
+                .rows_affected();
+        report.arrests = sqlx::query(
+            "UPDATE prison SET data = jsonb_set(data, '{arrested_by}', 'null')
+            WHERE guild_id = $1 AND data->>'arrested_by' = $2",
+        )
+        .bind(guild_id.to_string())
+        .bind(user_id.to_string())
+        .execute(&self.pool)
+        .await
+        .wrap_err("anonymize arrests")?
+        .rows_affected();
+        report.prison_records =
+            sqlx::query("DELETE FROM prison_history WHERE guild_id = $1 AND data->>'user_id' = $2")
+                .bind(guild_id.to_string())
+                .bind(user_id.to_string())
+                .execute(&self.pool)
+                .await
+                .wrap_err("erase prison records")?
+                .rows_affected();
+        report.jobs = sqlx::query("DELETE FROM jobs WHERE guild_id = $1 AND kind->>'user_id' = $2")
+            .bind(guild_id.to_string())
+            .bind(user_id.to_string())
+            .execute(&self.pool)
+            .await
+            .wrap_err("erase jobs")?
+            .rows_affected();
+
+        // the cleared state still contains the user, erasing has to win over being able to undo
+        self.purge_trash(guild_id).await?;
+
+        Ok(report)
+    }
+
+    #[tracing::instrument(skip(self, data))]
+    async fn replace_guild(&self, data: &GuildData) -> Result<()> {
+        let guild_id = data.state.guild_id.to_string();
+
+        let mut tx = self.pool.begin().await.wrap_err("begin transaction")?;
+
+        sqlx::query(
+            "INSERT INTO state (guild_id, data) VALUES ($1, $2)
+            ON CONFLICT (guild_id) DO UPDATE SET data = EXCLUDED.data",
+        )
+        .bind(&guild_id)
+        .bind(to_json(&data.state)?)
+        .execute(&mut tx)
+        .await
+        .wrap_err("replace state")?;
+
+        let replaced_open_lawsuits = count_open_lawsuits_of(&mut tx, &guild_id).await?;
+        for table in ["lawsuits", "prison", "prison_history", "jobs", "members"] {
+            sqlx::query(&format!("DELETE FROM {table} WHERE guild_id = $1"))
+                .bind(&guild_id)
+                .execute(&mut tx)
+                .await
+                .wrap_err_with(|| format!("delete {table}"))?;
+        }
+
+        for lawsuit in &data.lawsuits {
+            sqlx::query("INSERT INTO lawsuits (guild_id, id, data) VALUES ($1, $2, $3)")
+                .bind(&guild_id)
+                .bind(lawsuit.id.to_string())
+                .bind(to_json(lawsuit)?)
+                .execute(&mut tx)
+                .await
+                .wrap_err("insert lawsuit")?;
+        }
+        add_to_counter(
+            &mut tx,
+            OPEN_LAWSUITS_COUNTER,
+            count_open(&data.lawsuits) - replaced_open_lawsuits,
+        )
+        .await?;
+        for entry in &data.prison_entries {
+            sqlx::query("INSERT INTO prison (guild_id, user_id, data) VALUES ($1, $2, $3)")
+                .bind(&guild_id)
+                .bind(entry.user_id.to_string())
+                .bind(to_json(entry)?)
+                .execute(&mut tx)
+                .await
+                .wrap_err("insert prison entry")?;
+        }
+        for record in &data.prison_history {
+            sqlx::query("INSERT INTO prison_history (guild_id, data) VALUES ($1, $2)")
+                .bind(&guild_id)
+                .bind(to_json(record)?)
+                .execute(&mut tx)
+                .await
+                .wrap_err("insert prison record")?;
+        }
+        for job in &data.jobs {
+            sqlx::query(
+                "INSERT INTO jobs (guild_id, kind, run_at, attempts) VALUES ($1, $2, $3, $4)",
+            )
+            .bind(&guild_id)
+            .bind(to_json(&job.kind)?)
+            .bind(job.run_at.timestamp_millis())
+            .bind(job.attempts as i32)
+            .execute(&mut tx)
+            .await
+            .wrap_err("insert job")?;
+        }
+        for member in &data.members {
+            sqlx::query("INSERT INTO members (guild_id, user_id, data) VALUES ($1, $2, $3)")
+                .bind(&guild_id)
+                .bind(member.user_id.to_string())
+                .bind(to_json(member)?)
+                .execute(&mut tx)
+                .await
+                .wrap_err("insert member")?;
+        }
+
+        tx.commit().await.wrap_err("commit import")?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn add_to_prison(
+        &self,
+        guild_id: SnowflakeId,
+        user_id: SnowflakeId,
+        tier: Option<&str>,
+        release_at: Option<DateTime>,
+        arrested_by: Option<SnowflakeId>,
+    ) -> Result<()> {
+        let entry = PrisonEntry {
+            guild_id,
+            user_id,
+            parole_until: None,
+            tier: None,
+            saved_roles: vec![],
+            release_at: None,
+            arrested_by,
+        };
+
+        sqlx::query(
+            "INSERT INTO prison (guild_id, user_id, data) VALUES ($1, $2, $3)
+            ON CONFLICT (guild_id, user_id) DO NOTHING",
+        )
+        .bind(guild_id.to_string())
+        .bind(user_id.to_string())
+        .bind(to_json(&entry)?)
+        .execute(&self.pool)
+        .await
+        .wrap_err("add to prison table")?;
+
+        self.update_prison_entry(guild_id, user_id, |entry| {
+            entry.tier = tier.map(str::to_owned);
+            entry.release_at = release_at;
+            entry.parole_until = None;
+        })
+        .await
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn remove_from_prison(&self, guild_id: SnowflakeId, user_id: SnowflakeId) -> Result<()> {
+        sqlx::query("DELETE FROM prison WHERE guild_id = $1 AND user_id = $2")
+            .bind(guild_id.to_string())
+            .bind(user_id.to_string())
+            .execute(&self.pool)
+            .await
+            .wrap_err("remove from prison")?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn find_prison_entry(
+        &self,
+        guild_id: SnowflakeId,
+        user_id: SnowflakeId,
+    ) -> Result<Option<PrisonEntry>> {
+        sqlx::query_scalar("SELECT data FROM prison WHERE guild_id = $1 AND user_id = $2")
+            .bind(guild_id.to_string())
+            .bind(user_id.to_string())
+            .fetch_optional(&self.pool)
+            .await
+            .wrap_err("find prison entry")?
+            .map(from_json)
+            .transpose()
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn set_parole(
+        &self,
+        guild_id: SnowflakeId,
+        user_id: SnowflakeId,
+        parole_until: Option<DateTime>,
+    ) -> Result<()> {
+        self.update_prison_entry(guild_id, user_id, |entry| entry.parole_until = parole_until)
+            .await
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn insert_prison_entries(&self, entries: &[PrisonEntry]) -> Result<()> {
+        let mut tx = self.pool.begin().await.wrap_err("begin transaction")?;
+
+        for entry in entries {
+            sqlx::query("INSERT INTO prison (guild_id, user_id, data) VALUES ($1, $2, $3)")
+                .bind(entry.guild_id.to_string())
+                .bind(entry.user_id.to_string())
+                .bind(to_json(entry)?)
+                .execute(&mut tx)
+                .await
+                .wrap_err("insert prison entry")?;
+        }
+
+        tx.commit().await.wrap_err("commit prison entries")?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn set_prison_entries_tier(
+        &self,
+        guild_id: SnowflakeId,
+        user_ids: &[SnowflakeId],
+        tier: Option<&str>,
+    ) -> Result<()> {
+        for user_id in user_ids {
+            self.update_prison_entry(guild_id, *user_id, |entry| {
+                entry.tier = tier.map(str::to_owned);
+                entry.parole_until = None;
+            })
+            .await?;
+        }
+
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn remove_all_from_prison(&self, guild_id: SnowflakeId) -> Result<()> {
+        sqlx::query("DELETE FROM prison WHERE guild_id = $1")
+            .bind(guild_id.to_string())
+            .execute(&self.pool)
+            .await
+            .wrap_err("remove all from prison")?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn set_saved_roles(
+        &self,
+        guild_id: SnowflakeId,
+        user_id: SnowflakeId,
+        saved_roles: &[SnowflakeId],
+    ) -> Result<()> {
+        self.update_prison_entry(guild_id, user_id, |entry| {
+            entry.saved_roles = saved_roles.to_vec()
+        })
+        .await
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn set_release_at(
+        &self,
+        guild_id: SnowflakeId,
+        user_id: SnowflakeId,
+        release_at: Option<DateTime>,
+    ) -> Result<()> {
+        self.update_prison_entry(guild_id, user_id, |entry| entry.release_at = release_at)
+            .await
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn find_prison_entries(&self, guild_id: SnowflakeId) -> Result<Vec<PrisonEntry>> {
+        sqlx::query_scalar("SELECT data FROM prison WHERE guild_id = $1")
+            .bind(guild_id.to_string())
+            .fetch_all(&self.pool)
+            .await
+            .wrap_err("find prison entries")?
+            .into_iter()
+            .map(from_json)
+            .collect()
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn find_prisoners_page(
+        &self,
+        guild_id: SnowflakeId,
+        after: Option<SnowflakeId>,
+        limit: usize,
+    ) -> Result<Page<PrisonEntry, SnowflakeId>> {
+        let entries = sqlx::query_scalar(
+            "SELECT data FROM prison WHERE guild_id = $1 AND ($2::TEXT IS NULL OR user_id > $2)
+            ORDER BY user_id LIMIT $3",
+        )
+        .bind(guild_id.to_string())
+        .bind(after.map(|id| id.to_string()))
+        .bind((limit + 1) as i64)
+        .fetch_all(&self.pool)
+        .await
+        .wrap_err("find prisoners page")?
+        .into_iter()
+        .map(from_json)
+        .collect::<Result<Vec<PrisonEntry>>>()?;
+
+        Ok(Page::new(entries, limit, |entry| entry.user_id))
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn count_prisoners(&self) -> Result<Vec<(SnowflakeId, u64)>> {
+        sqlx::query_as("SELECT guild_id, COUNT(*) FROM prison GROUP BY guild_id")
+            .fetch_all(&self.pool)
+            .await
+            .wrap_err("count prisoners")?
+            .into_iter()
+            .map(to_guild_count)
+            .collect()
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn bot_stats(&self) -> Result<BotStats> {
+        // there's only a row per guild and prisoner, few enough to count them
+        let guilds: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM state")
+            .fetch_one(&self.pool)
+            .await
+            .wrap_err("count guilds")?;
+        let prisoners: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM prison")
+            .fetch_one(&self.pool)
+            .await
+            .wrap_err("count prisoners")?;
+
+        Ok(BotStats {
+            guilds: guilds as u64,
+            lawsuits: self.counter(LAWSUITS_COUNTER).await?,
+            open_lawsuits: self.counter(OPEN_LAWSUITS_COUNTER).await?,
+            prisoners: prisoners as u64,
+        })
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn add_prison_records(&self, records: &[PrisonRecord]) -> Result<()> {
+        let mut tx = self.pool.begin().await.wrap_err("begin transaction")?;
+
+        for record in records {
+            sqlx::query("INSERT INTO prison_history (guild_id, data) VALUES ($1, $2)")
+                .bind(record.guild_id.to_string())
+                .bind(to_json(record)?)
+                .execute(&mut tx)
+                .await
+                .wrap_err("insert prison record")?;
+        }
+
+        tx.commit().await.wrap_err("commit prison records")?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn find_prison_records(&self, guild_id: SnowflakeId) -> Result<Vec<PrisonRecord>> {
+        sqlx::query_scalar("SELECT data FROM prison_history WHERE guild_id = $1")
+            .bind(guild_id.to_string())
+            .fetch_all(&self.pool)
+            .await
+            .wrap_err("find prison records")?
+            .into_iter()
+            .map(from_json)
+            .collect()
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn close_prison_records(
+        &self,
+        guild_id: SnowflakeId,
+        user_ids: Option<&[SnowflakeId]>,
+        released_at: DateTime,
+    ) -> Result<()> {
+        let mut tx = self.pool.begin().await.wrap_err("begin transaction")?;
+
+        let rows: Vec<(i64, serde_json::Value)> = sqlx::query_as(
+            "SELECT id, data FROM prison_history
+            WHERE guild_id = $1 AND data->'released_at' = 'null'
+            FOR UPDATE",
+        )
+        .bind(guild_id.to_string())
+        .fetch_all(&mut tx)
+        .await
+        .wrap_err("find open prison records")?;
+
+        for (id, data) in rows {
+            let mut record = from_json::<PrisonRecord>(data)?;
+            if user_ids.map_or(false, |user_ids| !user_ids.contains(&record.user_id)) {
+                continue;
+            }
+            record.released_at = Some(released_at);
+
+            sqlx::query("UPDATE prison_history SET data = $2 WHERE id = $1")
+                .bind(id)
+                .bind(to_json(&record)?)
+                .execute(&mut tx)
+                .await
+                .wrap_err("close prison record")?;
+        }
+
+        tx.commit().await.wrap_err("commit prison records")?;
+
+        Ok(())
+    }
+
+    /// Unlike with mongodb, the statistics are calculated here and not in the database.
+    #[tracing::instrument(skip(self))]
+    async fn prison_stats(&self, guild_id: SnowflakeId) -> Result<PrisonStats> {
+        const TOP_REASONS: usize = 5;
+
+        let current_prisoners: i64 =
+            sqlx::query_scalar("SELECT COUNT(*) FROM prison WHERE guild_id = $1")
+                .bind(guild_id.to_string())
+                .fetch_one(&self.pool)
+                .await
+                .wrap_err("count prisoners")?;
+
+        let records = self.find_prison_records(guild_id).await?;
+
+        let sentences = records
+            .iter()
+            .filter_map(|record| {
+                let released_at = record.released_at?;
+                Some(released_at.timestamp_millis() - record.arrested_at.timestamp_millis())
+            })
+            .collect::<Vec<_>>();
+        let average_sentence = (!sentences.is_empty()).then(|| {
+            let average = sentences.iter().sum::<i64>() / sentences.len() as i64;
+            Duration::from_millis(average.max(0) as u64)
+        });
+
+        let mut reasons = HashMap::<&str, u64>::new();
+        for reason in records.iter().filter_map(|record| record.reason.as_deref()) {
+            *reasons.entry(reason).or_default() += 1;
+        }
+        let mut top_reasons = reasons
+            .into_iter()
+            .map(|(reason, count)| (reason.to_owned(), count))
+            .collect::<Vec<_>>();
+        top_reasons.sort_by(|(_, a), (_, b)| b.cmp(a));
+        top_reasons.truncate(TOP_REASONS);
+
+        Ok(PrisonStats {
+            current_prisoners: current_prisoners as u64,
+            total_arrests: records.len() as u64,
+            average_sentence,
+            top_reasons,
+        })
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn court_stats(&self, guild_id: SnowflakeId) -> Result<CourtStats> {
+        let lawsuits = self.find_lawsuits(guild_id).await?;
+
+        let top = |counts: HashMap<SnowflakeId, u64>| {
+            let mut counts = counts.into_iter().collect::<Vec<_>>();
+            counts.sort_by(|(a_id, a), (b_id, b)| b.cmp(a).then(a_id.cmp(b_id)));
+            counts.truncate(COURT_STATS_LEN);
+            counts
+        };
+
+        let mut stats = CourtStats::default();
+        let mut months = BTreeMap::<String, u64>::new();
+        let mut judges = HashMap::new();
+        let mut accused = HashMap::new();
+        let mut durations = Vec::new();
+        for lawsuit in &lawsuits {
+            if let Some(opened_at) = lawsuit.opened_at {
+                *months.entry(duration::month(opened_at)).or_default() += 1;
+            }
+            *judges.entry(lawsuit.judge).or_default() += 1;
+            *accused.entry(lawsuit.accused).or_default() += 1;
+
+            if lawsuit.verdict.is_none() {
+                continue;
+            }
+            match lawsuit.is_guilty() {
+                Some(true) => stats.guilty += 1,
+                Some(false) => stats.not_guilty += 1,
+                None => stats.other_verdicts += 1,
+            }
+            if let (Some(opened_at), Some(closed_at)) = (lawsuit.opened_at, lawsuit.closed_at) {
+                durations.push(closed_at.timestamp_millis() - opened_at.timestamp_millis());
+            }
+        }
+
+        stats.per_month = months.into_iter().rev().take(COURT_STATS_LEN).collect();
+        stats.average_time_to_verdict = (!durations.is_empty()).then(|| {
+            let average = durations.iter().sum::<i64>() / durations.len() as i64;
+            Duration::from_millis(average.max(0) as u64)
+        });
+        stats.busiest_judges = top(judges);
+        stats.most_sued = top(accused);
+        Ok(stats)
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn court_report(&self, guild_id: SnowflakeId, since: DateTime) -> Result<CourtReport> {
+        let since = since.timestamp_millis();
+        let is_since =
+            |time: Option<DateTime>| time.map_or(false, |time| time.timestamp_millis() >= since);
+
+        let lawsuits = self.find_lawsuits(guild_id).await?;
+        let opened_lawsuits = lawsuits
+            .iter()
+            .filter(|lawsuit| is_since(lawsuit.opened_at))
+            .count();
+        let mut closed_lawsuits = lawsuits
+            .into_iter()
+            .filter(|lawsuit| is_since(lawsuit.closed_at))
+            .collect::<Vec<_>>();
+        closed_lawsuits
+            .sort_by_key(|lawsuit| lawsuit.closed_at.map(|time| time.timestamp_millis()));
+
+        let records = self.find_prison_records(guild_id).await?;
+        let arrests = records
+            .iter()
+            .filter(|record| is_since(Some(record.arrested_at)))
+            .count();
+        let releases = records
+            .iter()
+            .filter(|record| is_since(record.released_at))
+            .count();
+
+        Ok(CourtReport {
+            opened_lawsuits: opened_lawsuits as u64,
+            closed_lawsuits,
+            arrests: arrests as u64,
+            releases: releases as u64,
+        })
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn schedule_job(
+        &self,
+        guild_id: SnowflakeId,
+        kind: &JobKind,
+        run_at: DateTime,
+    ) -> Result<()> {
+        sqlx::query(
+            "INSERT INTO jobs (guild_id, kind, run_at, attempts) VALUES ($1, $2, $3, 0)
+            ON CONFLICT (guild_id, kind) DO UPDATE
+            SET run_at = EXCLUDED.run_at, attempts = 0, leased_until = NULL",
+        )
+        .bind(guild_id.to_string())
+        .bind(to_json(kind)?)
+        .bind(run_at.timestamp_millis())
+        .execute(&self.pool)
+        .await
+        .wrap_err("schedule job")?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn cancel_job(&self, guild_id: SnowflakeId, kind: &JobKind) -> Result<()> {
+        sqlx::query("DELETE FROM jobs WHERE guild_id = $1 AND kind = $2")
+            .bind(guild_id.to_string())
+            .bind(to_json(kind)?)
+            .execute(&self.pool)
+            .await
+            .wrap_err("cancel job")?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn cancel_prison_jobs(&self, guild_id: SnowflakeId) -> Result<()> {
+        sqlx::query(
+            "DELETE FROM jobs WHERE guild_id = $1 AND kind->>'type' IN ('release', 'end_parole')",
+        )
+        .bind(guild_id.to_string())
+        .execute(&self.pool)
+        .await
+        .wrap_err("cancel prison jobs")?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn find_jobs(&self, guild_id: SnowflakeId) -> Result<Vec<Job>> {
+        sqlx::query_as("SELECT guild_id, kind, run_at, attempts FROM jobs WHERE guild_id = $1")
+            .bind(guild_id.to_string())
+            .fetch_all(&self.pool)
+            .await
+            .wrap_err("find jobs")?
+            .into_iter()
+            .map(to_job)
+            .collect()
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn find_due_jobs(&self, now: DateTime) -> Result<Vec<Job>> {
+        sqlx::query_as("SELECT guild_id, kind, run_at, attempts FROM jobs WHERE run_at <= $1")
+            .bind(now.timestamp_millis())
+            .fetch_all(&self.pool)
+            .await
+            .wrap_err("find due jobs")?
+            .into_iter()
+            .map(to_job)
+            .collect()
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn claim_job(&self, job: &Job, until: DateTime) -> Result<bool> {
+        let result = sqlx::query(
+            "UPDATE jobs SET leased_until = $4
+            WHERE guild_id = $1 AND kind = $2 AND run_at = $3
+            AND (leased_until IS NULL OR leased_until <= $5)",
+        )
+        .bind(job.guild_id.to_string())
+        .bind(to_json(&job.kind)?)
+        .bind(job.run_at.timestamp_millis())
+        .bind(until.timestamp_millis())
+        .bind(DateTime::now().timestamp_millis())
+        .execute(&self.pool)
+        .await
+        .wrap_err("claim job")?;
+
+        Ok(result.rows_affected() == 1)
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn finish_job(&self, job: &Job) -> Result<()> {
+        sqlx::query("DELETE FROM jobs WHERE guild_id = $1 AND kind = $2 AND run_at = $3")
+            .bind(job.guild_id.to_string())
+            .bind(to_json(&job.kind)?)
+            .bind(job.run_at.timestamp_millis())
+            .execute(&self.pool)
+            .await
+            .wrap_err("finish job")?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn retry_job(&self, job: &Job, run_at: DateTime) -> Result<()> {
+        sqlx::query(
+            "UPDATE jobs SET run_at = $4, attempts = attempts + 1, leased_until = NULL
+            WHERE guild_id = $1 AND kind = $2 AND run_at = $3",
+        )
+        .bind(job.guild_id.to_string())
+        .bind(to_json(&job.kind)?)
+        .bind(job.run_at.timestamp_millis())
+        .bind(run_at.timestamp_millis())
+        .execute(&self.pool)
+        .await
+        .wrap_err("retry job")?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn find_judge_registry(&self, guild_id: SnowflakeId) -> Result<JudgeRegistry> {
+        let data = sqlx::query_scalar("SELECT data FROM judges WHERE guild_id = $1")
+            .bind(guild_id.to_string())
+            .fetch_optional(&self.pool)
+            .await
+            .wrap_err("find judge registry")?;
+
+        match data {
+            Some(data) => from_json(data),
+            None => Ok(JudgeRegistry::new(guild_id)),
+        }
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn register_judge(&self, guild_id: SnowflakeId, judge: SnowflakeId) -> Result<bool> {
+        self.update_judges(guild_id, |registry| {
+            if registry.judges.contains(&judge) {
+                return false;
+            }
+            registry.judges.push(judge);
+            true
+        })
+        .await
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn unregister_judge(&self, guild_id: SnowflakeId, judge: SnowflakeId) -> Result<bool> {
+        self.update_judges(guild_id, |registry| {
+            let before = registry.judges.len();
+            registry.judges.retain(|registered| *registered != judge);
+            registry.judges.len() != before
+        })
+        .await
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn assign_judge(
+        &self,
+        guild_id: SnowflakeId,
+        parties: &[SnowflakeId],
+    ) -> Result<Option<SnowflakeId>> {
+        self.update_judges(guild_id, |registry| {
+            let judge = registry.next_judge(parties);
+            if judge.is_some() {
+                registry.last_assigned = judge;
+            }
+            judge
+        })
+        .await
+    }
+
+    #[tracing::instrument(skip(self, election))]
+    async fn add_election(&self, election: &Election) -> Result<()> {
+        sqlx::query("INSERT INTO elections (guild_id, id, ended_at, data) VALUES ($1, $2, $3, $4)")
+            .bind(election.guild_id.to_string())
+            .bind(election.id.to_string())
+            .bind(election.ended_at.timestamp_millis())
+            .bind(to_json(election)?)
+            .execute(&self.pool)
+            .await
+            .wrap_err("add election")?;
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn find_elections(&self, guild_id: SnowflakeId) -> Result<Vec<Election>> {
+        sqlx::query_scalar("SELECT data FROM elections WHERE guild_id = $1 ORDER BY ended_at DESC")
+            .bind(guild_id.to_string())
+            .fetch_all(&self.pool)
+            .await
+            .wrap_err("find elections")?
+            .into_iter()
+            .map(from_json)
+            .collect()
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn record_command_usage(&self, usage: &CommandUsage) -> Result<()> {
+        sqlx::query("INSERT INTO command_usage (used_at, data) VALUES ($1, $2)")
+            .bind(usage.used_at.timestamp_millis())
+            .bind(to_json(usage)?)
+            .execute(&self.pool)
+            .await
+            .wrap_err("record command usage")?;
+
+        // like the capped collection in mongodb
+        sqlx::query(
+            "DELETE FROM command_usage WHERE id <= (SELECT MAX(id) FROM command_usage) - $1",
+        )
+        .bind(MAX_COMMAND_USAGES as i64)
+        .execute(&self.pool)
+        .await
+        .wrap_err("drop old command usage")?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn find_command_usage(&self, since: DateTime) -> Result<Vec<CommandUsage>> {
+        sqlx::query_scalar("SELECT data FROM command_usage WHERE used_at >= $1")
+            .bind(since.timestamp_millis())
+            .fetch_all(&self.pool)
+            .await
+            .wrap_err("find command usage")?
+            .into_iter()
+            .map(from_json)
+            .collect()
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn find_guild_listings(&self) -> Result<Vec<GuildListing>> {
+        let rows: Vec<(String, bool)> =
+            sqlx::query_as("SELECT guild_id, allowed FROM guild_access")
+                .fetch_all(&self.pool)
+                .await
+                .wrap_err("find guild listings")?;
+        rows.into_iter()
+            .map(|(guild_id, allowed)| {
+                Ok(GuildListing {
+                    guild_id: guild_id.parse().wrap_err("invalid guild id")?,
+                    allowed,
+                })
+            })
+            .collect()
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn set_guild_listing(&self, guild_id: SnowflakeId, allowed: Option<bool>) -> Result<()> {
+        match allowed {
+            Some(allowed) => sqlx::query(
+                "INSERT INTO guild_access (guild_id, allowed) VALUES ($1, $2)
+                ON CONFLICT (guild_id) DO UPDATE SET allowed = EXCLUDED.allowed",
+            )
+            .bind(guild_id.to_string())
+            .bind(allowed)
+            .execute(&self.pool)
+            .await
+            .wrap_err("set guild listing")?,
+            None => sqlx::query("DELETE FROM guild_access WHERE guild_id = $1")
+                .bind(guild_id.to_string())
+                .execute(&self.pool)
+                .await
+                .wrap_err("remove guild listing")?,
+        };
+        Ok(())
+    }
+}
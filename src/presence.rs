@@ -0,0 +1,107 @@
+use std::{env, sync::Arc, time::Duration};
+
+use color_eyre::{eyre::ContextCompat, Result};
+use dashmap::DashSet;
+use poise::serenity_prelude::{Activity, Context};
+use tracing::{error, info};
+
+use crate::{duration, i18n::Locale, shutdown::BackgroundTasks, storage::Storage};
+
+const DEFAULT_TEXT: &str = "für Recht und Ordnung sorgen";
+
+/// The activity of the bot. `ACTIVITY` replaces the text, and with `ACTIVITY_ROTATION` set to a
+/// duration like `5m` the activity switches between the text and statistics about the open
+/// lawsuits and the prisoners.
+pub struct Presence {
+    text: String,
+    rotation: Option<Duration>,
+    /// The shards that already rotate, `Ready` is sent again after every reconnect
+    rotating: DashSet<u64>,
+}
+
+impl Presence {
+    pub fn from_env() -> Result<Self> {
+        let text = env::var("ACTIVITY").unwrap_or_else(|_| DEFAULT_TEXT.to_owned());
+        let rotation = match env::var("ACTIVITY_ROTATION") {
+            Ok(rotation) => Some(
+                duration::parse(&rotation)
+                    .wrap_err("ACTIVITY_ROTATION must be a duration like 5m")?,
+            ),
+            Err(_) => None,
+        };
+
+        Ok(Self {
+            text,
+            rotation,
+            rotating: DashSet::new(),
+        })
+    }
+
+    /// Sets the activity of the shard once it's connected and starts the rotation if it's
+    /// enabled and not running for the shard yet.
+    pub async fn start(
+        self: &Arc<Self>,
+        ctx: &Context,
+        storage: &Arc<dyn Storage>,
+        tasks: &BackgroundTasks,
+    ) {
+        // every shard has its own activity
+        ctx.set_activity(Activity::playing(&self.text)).await;
+
+        let rotation = match self.rotation {
+            Some(rotation) => rotation,
+            None => return,
+        };
+        if !self.rotating.insert(ctx.shard_id) {
+            return;
+        }
+
+        tasks.spawn(rotate(
+            self.clone(),
+            ctx.clone(),
+            storage.clone(),
+            tasks.clone(),
+            rotation,
+        ));
+    }
+}
+
+async fn rotate(
+    presence: Arc<Presence>,
+    ctx: Context,
+    storage: Arc<dyn Storage>,
+    tasks: BackgroundTasks,
+    rotation: Duration,
+) {
+    let strings = Locale::default().strings();
+    let mut interval = tokio::time::interval(rotation);
+    // the text was just set when the shard connected
+    interval.tick().await;
+
+    let mut step = 0;
+    loop {
+        tokio::select! {
+            _ = interval.tick() => {}
+            _ = tasks.shutting_down() => {
+                info!(shard_id = ctx.shard_id, "Stopped activity rotation");
+                return;
+            }
+        }
+
+        step = (step + 1) % 3;
+        let activity = match step {
+            0 => Activity::playing(&presence.text),
+            kind => match storage.bot_stats().await {
+                Ok(stats) if kind == 1 => {
+                    Activity::watching((strings.presence_open_lawsuits)(stats.open_lawsuits))
+                }
+                Ok(stats) => Activity::watching((strings.presence_prisoners)(stats.prisoners)),
+                Err(err) => {
+                    error!(?err, "Failed to get the statistics for the activity");
+                    Activity::playing(&presence.text)
+                }
+            },
+        };
+        ctx.set_activity(activity).await;
+    }
+}
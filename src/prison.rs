@@ -0,0 +1,1087 @@
+use std::{collections::HashMap, time::Duration};
+
+use color_eyre::Result;
+use mongodb::bson::DateTime;
+use poise::serenity::{builder::CreateEmbed, http::Http, model::prelude::*};
+use tracing::{debug, info, warn};
+
+use crate::{
+    audit::{self, AuditEvent},
+    duration,
+    handler::Response,
+    i18n::Strings,
+    model::{JobKind, PrisonEntry, PrisonRecord, RoleTemplate, SnowflakeId, State},
+    retry::retry,
+    storage::Storage,
+    WrapErr,
+};
+
+/// Pause between the role changes of mass arrests and releases to stay clear of the rate limits
+const BATCH_ROLE_CHANGE_DELAY: Duration = Duration::from_millis(500);
+
+/// The outcome of a mass arrest or release. The roles of the members in `failed` couldn't be
+/// changed, the others are counted in `count`.
+#[derive(Debug, Clone, Default)]
+pub struct Batch {
+    pub count: usize,
+    pub failed: Vec<UserId>,
+}
+
+/// The details of an arrest.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Sentence<'a> {
+    pub tier: Option<&'a str>,
+    /// `None` for an indefinite sentence
+    pub release_at: Option<DateTime>,
+    pub reason: Option<&'a str>,
+    /// The moderator who ordered the arrest
+    pub ordered_by: Option<UserId>,
+}
+
+/// Puts the user into prison with the role of the tier. If they are already in prison, they are
+/// moved to the new tier.
+pub async fn arrest(
+    http: &Http,
+    storage: &dyn Storage,
+    state: &State,
+    guild_id: GuildId,
+    user_id: UserId,
+    sentence: Sentence<'_>,
+) -> Result<Result<(), Response>> {
+    let Sentence {
+        tier,
+        release_at,
+        reason,
+        ordered_by,
+    } = sentence;
+
+    let role = match prison_role(state, tier) {
+        Ok(role) => role,
+        Err(response) => return Ok(Err(response)),
+    };
+
+    let previous_entry = storage
+        .find_prison_entry(guild_id.into(), user_id.into())
+        .await?;
+
+    let member = retry(|| guild_id.member(http, user_id))
+        .await
+        .wrap_err("fetching guild member")?;
+
+    storage
+        .add_to_prison(
+            guild_id.into(),
+            user_id.into(),
+            tier,
+            release_at,
+            ordered_by.map(SnowflakeId::from),
+        )
+        .await?;
+
+    let release_job = JobKind::Release {
+        user_id: user_id.into(),
+    };
+    match release_at {
+        Some(release_at) => {
+            storage
+                .schedule_job(guild_id.into(), &release_job, release_at)
+                .await?
+        }
+        None => storage.cancel_job(guild_id.into(), &release_job).await?,
+    }
+    // the parole ends with the new arrest, otherwise its end would release them
+    if previous_entry
+        .as_ref()
+        .map_or(false, |entry| entry.parole_until.is_some())
+    {
+        storage
+            .cancel_job(
+                guild_id.into(),
+                &JobKind::EndParole {
+                    user_id: user_id.into(),
+                },
+            )
+            .await?;
+    }
+
+    if previous_entry.is_none() {
+        storage
+            .add_prison_records(&[PrisonRecord {
+                guild_id: guild_id.into(),
+                user_id: user_id.into(),
+                reason: reason.map(ToOwned::to_owned),
+                arrested_at: DateTime::now(),
+                released_at: None,
+            }])
+            .await?;
+    }
+
+    // only strip the roles on the first arrest, they would be overwritten otherwise
+    let stripped_roles = if state.strip_roles_on_arrest && previous_entry.is_none() {
+        let guild_roles = guild_id.roles(http).await.wrap_err("fetch guild roles")?;
+        let stripped_roles = strippable_roles(&member, state, &guild_roles);
+        let saved_roles = stripped_roles
+            .iter()
+            .copied()
+            .map(SnowflakeId::from)
+            .collect::<Vec<_>>();
+        storage
+            .set_saved_roles(guild_id.into(), user_id.into(), &saved_roles)
+            .await?;
+        stripped_roles
+    } else {
+        vec![]
+    };
+
+    if let Err(err) = apply_arrest_roles(
+        http,
+        state,
+        &member,
+        previous_entry.as_ref(),
+        role,
+        &stripped_roles,
+    )
+    .await
+    {
+        // the role could have been deleted while the bot was offline
+        let role = match restore_prison_role(http, storage, guild_id, role).await? {
+            Some(role) => role,
+            None => return Err(err),
+        };
+        // the deleted role can't be removed anymore, so there is no previous entry to move from
+        apply_arrest_roles(http, state, &member, None, role, &stripped_roles).await?;
+    }
+
+    info!(%guild_id, %user_id, ?tier, "Arrested user");
+
+    audit::log(
+        http,
+        state,
+        AuditEvent::Arrested {
+            user_id,
+            ordered_by,
+            reason,
+            release_at,
+        },
+    )
+    .await;
+
+    if state.notify_prisoners {
+        notify_arrest(http, state.locale.strings(), guild_id, user_id, &sentence).await;
+    }
+
+    Ok(Ok(()))
+}
+
+/// Arrests all the users at once. The role changes are spaced out to not run into the rate limit,
+/// everyone is stored once their roles are changed. Users that are already in prison are only
+/// moved to the tier and keep their release time.
+pub async fn arrest_many(
+    http: &Http,
+    storage: &dyn Storage,
+    state: &State,
+    guild_id: GuildId,
+    user_ids: &[UserId],
+    sentence: Sentence<'_>,
+) -> Result<Result<Batch, Response>> {
+    let Sentence {
+        tier,
+        release_at,
+        reason,
+        ordered_by,
+    } = sentence;
+
+    let role = match prison_role(state, tier) {
+        Ok(role) => role,
+        Err(response) => return Ok(Err(response)),
+    };
+
+    let previous_entries = storage.find_prison_entries(guild_id.into()).await?;
+    let guild_roles = guild_id.roles(http).await.wrap_err("fetch guild roles")?;
+
+    let mut batch = Batch::default();
+    for &user_id in user_ids {
+        let member = match retry(|| guild_id.member(http, user_id)).await {
+            Ok(member) => member,
+            Err(err) => {
+                debug!(?err, %user_id, "Could not fetch member for mass arrest");
+                continue;
+            }
+        };
+        let previous_entry = previous_entries
+            .iter()
+            .find(|entry| entry.user_id == user_id.into());
+
+        let result: Result<()> = async {
+            let stripped_roles = match previous_entry {
+                // moved prisoners are stored first, otherwise losing the role of their old tier
+                // would look like an escape
+                Some(previous_entry) => {
+                    storage
+                        .set_prison_entries_tier(guild_id.into(), &[previous_entry.user_id], tier)
+                        .await?;
+                    // the parole ends with the new arrest, otherwise its end would release them
+                    if previous_entry.parole_until.is_some() {
+                        storage
+                            .cancel_job(
+                                guild_id.into(),
+                                &JobKind::EndParole {
+                                    user_id: previous_entry.user_id,
+                                },
+                            )
+                            .await?;
+                    }
+                    vec![]
+                }
+                None if state.strip_roles_on_arrest => {
+                    strippable_roles(&member, state, &guild_roles)
+                }
+                None => vec![],
+            };
+
+            apply_arrest_roles(http, state, &member, previous_entry, role, &stripped_roles).await?;
+
+            if previous_entry.is_none() {
+                storage
+                    .insert_prison_entries(&[PrisonEntry {
+                        guild_id: guild_id.into(),
+                        user_id: user_id.into(),
+                        parole_until: None,
+                        tier: tier.map(ToOwned::to_owned),
+                        release_at,
+                        arrested_by: ordered_by.map(SnowflakeId::from),
+                        saved_roles: stripped_roles
+                            .iter()
+                            .copied()
+                            .map(SnowflakeId::from)
+                            .collect(),
+                    }])
+                    .await?;
+                storage
+                    .add_prison_records(&[PrisonRecord {
+                        guild_id: guild_id.into(),
+                        user_id: user_id.into(),
+                        reason: reason.map(ToOwned::to_owned),
+                        arrested_at: DateTime::now(),
+                        released_at: None,
+                    }])
+                    .await?;
+                if let Some(release_at) = release_at {
+                    storage
+                        .schedule_job(
+                            guild_id.into(),
+                            &JobKind::Release {
+                                user_id: user_id.into(),
+                            },
+                            release_at,
+                        )
+                        .await?;
+                }
+            }
+
+            Ok(())
+        }
+        .await;
+
+        match result {
+            Ok(()) => {
+                batch.count += 1;
+                if state.notify_prisoners {
+                    notify_arrest(http, state.locale.strings(), guild_id, user_id, &sentence).await;
+                }
+            }
+            Err(err) => {
+                warn!(?err, %user_id, "Could not arrest member in mass arrest");
+                batch.failed.push(user_id);
+            }
+        }
+
+        tokio::time::sleep(BATCH_ROLE_CHANGE_DELAY).await;
+    }
+
+    info!(%guild_id, count = %batch.count, failed = %batch.failed.len(), ?tier, "Arrested many users");
+
+    audit::log(
+        http,
+        state,
+        AuditEvent::ArrestedMany {
+            count: batch.count,
+            ordered_by,
+        },
+    )
+    .await;
+
+    Ok(Ok(batch))
+}
+
+/// All members that have the role, for arresting everyone with a raid role at once.
+pub async fn members_with_role(
+    http: &Http,
+    guild_id: GuildId,
+    role_id: RoleId,
+) -> Result<Vec<UserId>> {
+    // discord only returns up to 1000 members at once
+    const PAGE_SIZE: u64 = 1000;
+
+    let mut user_ids = Vec::new();
+    let mut after = None;
+
+    loop {
+        let members = guild_id
+            .members(http, Some(PAGE_SIZE), after)
+            .await
+            .wrap_err("fetch guild members")?;
+
+        after = members.last().map(|member| member.user.id);
+        user_ids.extend(
+            members
+                .iter()
+                .filter(|member| member.roles.contains(&role_id))
+                .map(|member| member.user.id),
+        );
+
+        if members.len() < PAGE_SIZE as usize {
+            break;
+        }
+    }
+
+    Ok(user_ids)
+}
+
+/// Lets the user out of prison and gives them their roles back. `released_by` is `None` if the
+/// sentence is simply over.
+pub async fn release(
+    http: &Http,
+    storage: &dyn Storage,
+    state: &State,
+    guild_id: GuildId,
+    user_id: UserId,
+    released_by: Option<UserId>,
+) -> Result<Result<(), Response>> {
+    let entry = storage
+        .find_prison_entry(guild_id.into(), user_id.into())
+        .await?;
+
+    let role = match &entry {
+        Some(entry) => entry_role(state, entry),
+        None => state.prison_role,
+    };
+
+    let role = match role {
+        Some(role) => role,
+        None => {
+            return Ok(Err(Response(
+                state.locale.strings().set_prison_role_first.to_string(),
+            )))
+        }
+    };
+
+    storage
+        .remove_from_prison(guild_id.into(), user_id.into())
+        .await?;
+    storage
+        .close_prison_records(guild_id.into(), Some(&[user_id.into()]), DateTime::now())
+        .await?;
+    for job in [
+        JobKind::Release {
+            user_id: user_id.into(),
+        },
+        JobKind::EndParole {
+            user_id: user_id.into(),
+        },
+    ] {
+        storage.cancel_job(guild_id.into(), &job).await?;
+    }
+
+    let member = match retry(|| guild_id.member(http, user_id)).await {
+        Ok(member) => member,
+        Err(err) => {
+            // they left the guild, so there's no role to remove
+            debug!(?err, %user_id, "Could not fetch member to release");
+            return Ok(Ok(()));
+        }
+    };
+
+    retry(|| http.remove_member_role(guild_id.0, user_id.0, role.0, None))
+        .await
+        .wrap_err("remove guild member role")?;
+
+    if let Some(entry) = entry {
+        restore_roles(http, &member, Some(role), &entry.saved_roles).await?;
+    }
+
+    info!(%guild_id, %user_id, "Released user");
+
+    audit::log(
+        http,
+        state,
+        AuditEvent::Released {
+            user_id,
+            released_by,
+        },
+    )
+    .await;
+
+    if state.notify_prisoners {
+        notify_release(http, state.locale.strings(), guild_id, user_id, released_by).await;
+    }
+
+    Ok(Ok(()))
+}
+
+/// Releases everyone in the prison of the guild, one after the other. Whoever's roles can't be
+/// changed stays in prison and keeps their saved roles.
+pub async fn release_all(
+    http: &Http,
+    storage: &dyn Storage,
+    state: &State,
+    guild_id: GuildId,
+    released_by: UserId,
+) -> Result<Batch> {
+    let entries = storage.find_prison_entries(guild_id.into()).await?;
+
+    let mut batch = Batch::default();
+    for entry in &entries {
+        let user_id = UserId::from(entry.user_id);
+
+        let result: Result<bool> = async {
+            // remove the entry first, otherwise removing the role would look like an escape
+            storage
+                .remove_from_prison(guild_id.into(), entry.user_id)
+                .await?;
+
+            let member = match retry(|| guild_id.member(http, user_id)).await {
+                Ok(member) => Some(member),
+                Err(err) => {
+                    // they left the guild, so there's no role to remove
+                    debug!(?err, %user_id, "Could not fetch member to release");
+                    None
+                }
+            };
+            if let Some(member) = &member {
+                if let Err(err) = remove_prison_roles(http, state, member, entry).await {
+                    // they stay in prison, so their saved roles are kept
+                    storage.insert_prison_entries(&[entry.clone()]).await?;
+                    return Err(err);
+                }
+            }
+
+            storage
+                .close_prison_records(guild_id.into(), Some(&[entry.user_id]), DateTime::now())
+                .await?;
+            for job in [
+                JobKind::Release {
+                    user_id: entry.user_id,
+                },
+                JobKind::EndParole {
+                    user_id: entry.user_id,
+                },
+            ] {
+                storage.cancel_job(guild_id.into(), &job).await?;
+            }
+
+            Ok(member.is_some())
+        }
+        .await;
+
+        match result {
+            Ok(in_guild) => {
+                batch.count += 1;
+                if in_guild && state.notify_prisoners {
+                    notify_release(
+                        http,
+                        state.locale.strings(),
+                        guild_id,
+                        user_id,
+                        Some(released_by),
+                    )
+                    .await;
+                }
+            }
+            Err(err) => {
+                warn!(?err, %user_id, "Could not release member in mass release");
+                batch.failed.push(user_id);
+            }
+        }
+
+        tokio::time::sleep(BATCH_ROLE_CHANGE_DELAY).await;
+    }
+
+    info!(%guild_id, count = %batch.count, failed = %batch.failed.len(), "Released everyone");
+
+    audit::log(
+        http,
+        state,
+        AuditEvent::ReleasedAll {
+            count: batch.count,
+            released_by,
+        },
+    )
+    .await;
+
+    Ok(batch)
+}
+
+/// Takes the prison or probation role away and gives the saved roles back.
+async fn remove_prison_roles(
+    http: &Http,
+    state: &State,
+    member: &Member,
+    entry: &PrisonEntry,
+) -> Result<()> {
+    let role = entry_role(state, entry);
+    if let Some(role) = role {
+        retry(|| http.remove_member_role(member.guild_id.0, member.user.id.0, role.0, None))
+            .await
+            .wrap_err("remove guild member role")?;
+    }
+    restore_roles(http, member, role, &entry.saved_roles).await
+}
+
+fn prison_role(state: &State, tier: Option<&str>) -> Result<SnowflakeId, Response> {
+    let strings = state.locale.strings();
+
+    if let Some(tier) = tier {
+        if !state.prison_tiers.iter().any(|t| t.name == tier) {
+            return Err(Response(strings.tier_not_found.to_string()));
+        }
+    }
+
+    state
+        .prison_role_for(tier)
+        .ok_or_else(|| Response(strings.set_prison_role_first.to_string()))
+}
+
+/// The role the prisoner currently has, people on parole have the probation role instead of the
+/// prison role.
+pub fn entry_role(state: &State, entry: &PrisonEntry) -> Option<SnowflakeId> {
+    match entry.parole_until {
+        Some(_) => state.probation_role,
+        None => state.prison_role_for(entry.tier.as_deref()),
+    }
+}
+
+async fn apply_arrest_roles(
+    http: &Http,
+    state: &State,
+    member: &Member,
+    previous_entry: Option<&PrisonEntry>,
+    role: SnowflakeId,
+    stripped_roles: &[RoleId],
+) -> Result<()> {
+    let (guild_id, user_id) = (member.guild_id, member.user.id);
+
+    if !stripped_roles.is_empty() {
+        let kept_roles = member
+            .roles
+            .iter()
+            .filter(|role| !stripped_roles.contains(*role))
+            .collect::<Vec<_>>();
+        retry(|| guild_id.edit_member(http, user_id, |edit| edit.roles(&kept_roles)))
+            .await
+            .wrap_err("remove roles from prisoner, is the bot role high enough?")?;
+    }
+
+    // they are moved to another tier, so the role of the old one has to go
+    if let Some(previous_role) = previous_entry
+        .and_then(|entry| entry_role(state, entry))
+        .filter(|previous_role| *previous_role != role)
+    {
+        retry(|| http.remove_member_role(guild_id.0, user_id.0, previous_role.0, None))
+            .await
+            .wrap_err("remove previous tier role")?;
+    }
+
+    retry(|| http.add_member_role(guild_id.0, user_id.0, role.0, None))
+        .await
+        .wrap_err("add guild member role")?;
+
+    Ok(())
+}
+
+/// Called for every deleted role, the prison role is recreated right away.
+pub async fn handle_role_delete(
+    http: &Http,
+    storage: &dyn Storage,
+    guild_id: GuildId,
+    role_id: RoleId,
+) -> Result<()> {
+    restore_prison_role(http, storage, guild_id, role_id.into()).await?;
+    Ok(())
+}
+
+/// Recreates the prison role if `deleted` is the prison role and it doesn't exist anymore. The new
+/// role looks like the old one did when it was set, it gets the permissions in the prison channel
+/// and everyone in prison with the normal prison role gets it again. Returns the new role.
+async fn restore_prison_role(
+    http: &Http,
+    storage: &dyn Storage,
+    guild_id: GuildId,
+    deleted: SnowflakeId,
+) -> Result<Option<SnowflakeId>> {
+    let state = storage.find_or_insert_state(guild_id.into()).await?;
+    if state.prison_role != Some(deleted) {
+        return Ok(None);
+    }
+    let guild_roles = guild_id.roles(http).await.wrap_err("fetch guild roles")?;
+    if guild_roles.contains_key(&RoleId::from(deleted)) {
+        return Ok(None);
+    }
+
+    let strings = state.locale.strings();
+    // roles that were set before the templates were stored are recreated without permissions
+    let template = state
+        .prison_role_template
+        .clone()
+        .unwrap_or_else(|| RoleTemplate {
+            name: strings.prisoners_title.to_owned(),
+            permissions: 0,
+            colour: 0,
+            hoist: false,
+            mentionable: false,
+        });
+    let role = guild_id
+        .create_role(http, |role| {
+            role.name(&template.name)
+                .permissions(Permissions::from_bits_truncate(template.permissions))
+                .colour(template.colour.into())
+                .hoist(template.hoist)
+                .mentionable(template.mentionable)
+        })
+        .await
+        .wrap_err("recreate prison role, can the bot manage roles?")?;
+    storage
+        .set_prison_role(guild_id.into(), role.id.into(), &template)
+        .await?;
+    info!(%guild_id, old_role = %deleted, new_role = %role.id, "Recreated deleted prison role");
+
+    let state = storage.find_or_insert_state(guild_id.into()).await?;
+    apply_channel_permissions(http, guild_id, &state).await?;
+
+    let entries = storage.find_prison_entries(guild_id.into()).await?;
+    for entry in entries
+        .iter()
+        .filter(|entry| entry_role(&state, entry) == Some(role.id.into()))
+    {
+        // prisoners that left the server get the role when they join again
+        if let Err(err) =
+            retry(|| http.add_member_role(guild_id.0, entry.user_id.0, role.id.0, None)).await
+        {
+            debug!(?err, user_id = %entry.user_id, "Could not give prisoner the recreated role");
+        }
+        tokio::time::sleep(BATCH_ROLE_CHANGE_DELAY).await;
+    }
+
+    if let Some(channel) = state.moderator_channel {
+        ChannelId::from(channel)
+            .say(
+                http,
+                (strings.prison_role_recreated)(&format!("<@&{}>", role.id)),
+            )
+            .await
+            .wrap_err("notify moderators about recreated prison role")?;
+    }
+
+    Ok(Some(role.id.into()))
+}
+
+/// Run by the scheduler once the sentence is over. People on parole are released when the
+/// parole ends instead.
+pub async fn release_sentence_over(
+    http: &Http,
+    storage: &dyn Storage,
+    guild_id: GuildId,
+    user_id: UserId,
+) -> Result<()> {
+    let entry = storage
+        .find_prison_entry(guild_id.into(), user_id.into())
+        .await?;
+
+    if !matches!(
+        entry,
+        Some(PrisonEntry {
+            parole_until: None,
+            ..
+        })
+    ) {
+        return Ok(());
+    }
+
+    let state = storage.find_or_insert_state(guild_id.into()).await?;
+    let response = release(http, storage, &state, guild_id, user_id, None)
+        .await
+        .wrap_err("release prisoner")?;
+
+    if let Err(response) = response {
+        debug!(%response, %user_id, "Could not release prisoner");
+    }
+
+    Ok(())
+}
+
+/// Run by the scheduler once the parole is over, the prisoner is free after that.
+pub async fn end_parole(
+    http: &Http,
+    storage: &dyn Storage,
+    guild_id: GuildId,
+    user_id: UserId,
+) -> Result<()> {
+    let entry = match storage
+        .find_prison_entry(guild_id.into(), user_id.into())
+        .await?
+    {
+        Some(entry) if entry.parole_until.is_some() => entry,
+        _ => return Ok(()),
+    };
+
+    let state = storage.find_or_insert_state(entry.guild_id).await?;
+
+    // remove the entry first, otherwise removing the role would look like an escape
+    storage
+        .remove_from_prison(entry.guild_id, entry.user_id)
+        .await?;
+    storage
+        .close_prison_records(entry.guild_id, Some(&[entry.user_id]), DateTime::now())
+        .await?;
+    storage
+        .cancel_job(
+            entry.guild_id,
+            &JobKind::Release {
+                user_id: entry.user_id,
+            },
+        )
+        .await?;
+
+    match retry(|| GuildId::from(entry.guild_id).member(http, entry.user_id)).await {
+        Ok(member) => {
+            if let Some(role) = state.probation_role {
+                retry(|| http.remove_member_role(entry.guild_id.0, entry.user_id.0, role.0, None))
+                    .await
+                    .wrap_err("remove probation role")?;
+            }
+            restore_roles(http, &member, state.probation_role, &entry.saved_roles).await?;
+
+            if state.notify_prisoners {
+                notify_release(http, state.locale.strings(), guild_id, user_id, None).await;
+            }
+        }
+        Err(err) => {
+            // they left the guild, so there's no role to remove
+            debug!(?err, user_id = %entry.user_id, "Could not fetch member on parole");
+        }
+    }
+
+    info!(guild_id = %entry.guild_id, user_id = %entry.user_id, "Parole ended");
+
+    audit::log(
+        http,
+        &state,
+        AuditEvent::Released {
+            user_id,
+            released_by: None,
+        },
+    )
+    .await;
+
+    Ok(())
+}
+
+/// Tells the prisoner why they were arrested. Failing to send the message is not an error since
+/// many people don't accept direct messages.
+async fn notify_arrest(
+    http: &Http,
+    strings: &Strings,
+    guild_id: GuildId,
+    user_id: UserId,
+    sentence: &Sentence<'_>,
+) {
+    let duration = match sentence.release_at {
+        Some(release_at) => (strings.until)(&duration::relative_timestamp(release_at)),
+        None => strings.indefinite.to_string(),
+    };
+    let ordered_by = match sentence.ordered_by {
+        Some(ordered_by) => format!("<@{ordered_by}>"),
+        None => strings.none.to_string(),
+    };
+
+    let result = send_notification(http, guild_id, user_id, |embed| {
+        embed
+            .title(strings.arrested_dm_title)
+            .field(
+                strings.field_reason,
+                sentence.reason.unwrap_or(strings.no_reason),
+                false,
+            )
+            .field(strings.field_duration, duration, true)
+            .field(strings.dm_ordered_by, ordered_by, true)
+    })
+    .await;
+
+    if let Err(err) = result {
+        debug!(?err, %user_id, "Could not notify prisoner about arrest");
+    }
+}
+
+/// Tells the prisoner that they are free again. Failing to send the message is not an error since
+/// many people don't accept direct messages.
+async fn notify_release(
+    http: &Http,
+    strings: &Strings,
+    guild_id: GuildId,
+    user_id: UserId,
+    released_by: Option<UserId>,
+) {
+    let released_by = match released_by {
+        Some(released_by) => format!("<@{released_by}>"),
+        None => strings.sentence_served.to_string(),
+    };
+
+    let result = send_notification(http, guild_id, user_id, |embed| {
+        embed
+            .title(strings.released_dm_title)
+            .field(strings.dm_released_by, released_by, true)
+    })
+    .await;
+
+    if let Err(err) = result {
+        debug!(?err, %user_id, "Could not notify prisoner about release");
+    }
+}
+
+async fn send_notification(
+    http: &Http,
+    guild_id: GuildId,
+    user_id: UserId,
+    embed: impl FnOnce(&mut CreateEmbed) -> &mut CreateEmbed,
+) -> Result<()> {
+    let guild = guild_id
+        .to_partial_guild(http)
+        .await
+        .wrap_err("fetch guild")?;
+    let channel = user_id
+        .create_dm_channel(http)
+        .await
+        .wrap_err("create dm channel")?;
+
+    channel
+        .send_message(http, |message| {
+            message.embed(|e| embed(e).footer(|footer| footer.text(&guild.name)))
+        })
+        .await
+        .wrap_err("send dm")?;
+
+    Ok(())
+}
+
+/// Gives prisoners their role back if someone removed it without releasing them and tells the
+/// moderators about it. Only members whose old roles are cached can be checked, since otherwise
+/// it's not known whether they just lost the role or never had it yet while being arrested.
+pub async fn handle_member_update(
+    http: &Http,
+    storage: &dyn Storage,
+    old: Option<&Member>,
+    new: &Member,
+) -> Result<()> {
+    let old = match old {
+        Some(old) => old,
+        None => return Ok(()),
+    };
+
+    let entry = storage
+        .find_prison_entry(new.guild_id.into(), new.user.id.into())
+        .await?;
+    let entry = match entry {
+        Some(entry) => entry,
+        None => return Ok(()),
+    };
+
+    let state = storage.find_or_insert_state(new.guild_id.into()).await?;
+    let role = match entry_role(&state, &entry) {
+        Some(role) => RoleId::from(role),
+        None => return Ok(()),
+    };
+
+    if !old.roles.contains(&role) || new.roles.contains(&role) {
+        return Ok(());
+    }
+
+    info!(guild_id = %new.guild_id, user_id = %new.user.id, "Prisoner escaped, arresting them again");
+
+    retry(|| http.add_member_role(new.guild_id.0, new.user.id.0, role.0, None))
+        .await
+        .wrap_err("add role to escaped prisoner")?;
+
+    if let Some(channel) = state.moderator_channel {
+        ChannelId::from(channel)
+            .say(
+                http,
+                (state.locale.strings().escaped)(&format!("<@{}>", new.user.id)),
+            )
+            .await
+            .wrap_err("notify moderators about escape")?;
+    }
+
+    audit::log(
+        http,
+        &state,
+        AuditEvent::Escaped {
+            user_id: new.user.id,
+        },
+    )
+    .await;
+
+    Ok(())
+}
+
+/// Makes the prison channel only visible to prisoners. If the lockdown is enabled, prisoners are
+/// also denied sending messages in every other channel.
+pub async fn apply_channel_permissions(
+    http: &Http,
+    guild_id: GuildId,
+    state: &State,
+) -> Result<()> {
+    let prison_channel = match state.prison_channel {
+        Some(channel) => ChannelId::from(channel),
+        None => return Ok(()),
+    };
+
+    // the @everyone role has the same id as the guild
+    prison_channel
+        .create_permission(
+            http,
+            &PermissionOverwrite {
+                allow: Permissions::empty(),
+                deny: Permissions::VIEW_CHANNEL,
+                kind: PermissionOverwriteType::Role(RoleId(guild_id.0)),
+            },
+        )
+        .await
+        .wrap_err("deny everyone in prison channel")?;
+
+    for role in state.prisoner_roles() {
+        prison_channel
+            .create_permission(
+                http,
+                &PermissionOverwrite {
+                    allow: Permissions::VIEW_CHANNEL | Permissions::SEND_MESSAGES,
+                    deny: Permissions::empty(),
+                    kind: PermissionOverwriteType::Role(role.into()),
+                },
+            )
+            .await
+            .wrap_err("allow prisoners in prison channel")?;
+    }
+
+    if state.prison_lockdown {
+        let channels = guild_id.channels(http).await.wrap_err("fetch channels")?;
+
+        for channel_id in channels.keys().filter(|id| **id != prison_channel) {
+            for role in state.prisoner_roles() {
+                deny_prisoner_messages(http, *channel_id, role.into()).await?;
+            }
+        }
+    }
+
+    info!(%guild_id, lockdown = %state.prison_lockdown, "Applied prison channel permissions");
+
+    Ok(())
+}
+
+/// Called for new channels so that the lockdown also covers them.
+pub async fn handle_channel_create(
+    http: &Http,
+    storage: &dyn Storage,
+    channel: &GuildChannel,
+) -> Result<()> {
+    let state = storage
+        .find_or_insert_state(channel.guild_id.into())
+        .await?;
+
+    if !state.prison_lockdown || state.prison_channel == Some(SnowflakeId::from(channel.id)) {
+        return Ok(());
+    }
+
+    for role in state.prisoner_roles() {
+        deny_prisoner_messages(http, channel.id, role.into()).await?;
+    }
+
+    Ok(())
+}
+
+async fn deny_prisoner_messages(http: &Http, channel_id: ChannelId, role: RoleId) -> Result<()> {
+    channel_id
+        .create_permission(
+            http,
+            &PermissionOverwrite {
+                allow: Permissions::empty(),
+                deny: Permissions::SEND_MESSAGES,
+                kind: PermissionOverwriteType::Role(role),
+            },
+        )
+        .await
+        .wrap_err("deny prisoners sending messages")
+}
+
+/// The roles that are removed on arrest if role stripping is enabled. That's every role except the
+/// prison roles and roles managed by integrations, since those can't be removed.
+fn strippable_roles(
+    member: &Member,
+    state: &State,
+    guild_roles: &HashMap<RoleId, Role>,
+) -> Vec<RoleId> {
+    let prisoner_roles = state
+        .prisoner_roles()
+        .chain(state.probation_role)
+        .map(RoleId::from)
+        .collect::<Vec<_>>();
+
+    member
+        .roles
+        .iter()
+        .filter(|role| !prisoner_roles.contains(*role))
+        .filter(|role| guild_roles.get(*role).map_or(false, |role| !role.managed))
+        .copied()
+        .collect()
+}
+
+/// Gives the roles that were removed on arrest back, skipping roles that were deleted since.
+/// `member` was fetched before the prison or probation role `removed` was taken away, so it's left
+/// out of the roles of the member, otherwise they would get it back.
+pub async fn restore_roles(
+    http: &Http,
+    member: &Member,
+    removed: Option<SnowflakeId>,
+    roles: &[SnowflakeId],
+) -> Result<()> {
+    if roles.is_empty() {
+        return Ok(());
+    }
+
+    let guild_roles = member
+        .guild_id
+        .roles(http)
+        .await
+        .wrap_err("fetch guild roles")?;
+
+    let roles = roles
+        .iter()
+        .map(|role| RoleId::from(*role))
+        .filter(|role| guild_roles.contains_key(role))
+        .collect::<Vec<_>>();
+
+    let roles = member
+        .roles
+        .iter()
+        .filter(|role| removed.map_or(true, |removed| **role != RoleId::from(removed)))
+        .chain(&roles)
+        .collect::<Vec<_>>();
+    retry(|| {
+        member
+            .guild_id
+            .edit_member(http, member.user.id, |edit| edit.roles(&roles))
+    })
+    .await
+    .wrap_err("restore roles of prisoner")?;
+
+    Ok(())
+}
@@ -0,0 +1,94 @@
+use std::time::Duration;
+
+use color_eyre::Result;
+use mongodb::bson::DateTime;
+use poise::serenity::{http::Http, model::id::ChannelId};
+
+use crate::{
+    duration,
+    model::{CourtReport, JobKind, SnowflakeId},
+    storage::Storage,
+    WrapErr,
+};
+
+/// How often the report is posted and how far back it looks.
+pub const INTERVAL: Duration = Duration::from_secs(60 * 60 * 24 * 7);
+/// The report only lists the latest verdicts, the embed fields can't be longer than 1024
+/// characters
+const MAX_VERDICTS: usize = 10;
+const MAX_VERDICT_LEN: usize = 80;
+
+/// Posts the report of the last week in the report channel of the guild. The next report is
+/// scheduled first, so that a report that fails is skipped instead of ending the reports.
+pub async fn send_weekly(http: &Http, storage: &dyn Storage, guild_id: SnowflakeId) -> Result<()> {
+    let state = storage.find_or_insert_state(guild_id).await?;
+    let channel_id = match state.report_channel {
+        Some(channel_id) => ChannelId::from(channel_id),
+        // turned off after the job was scheduled
+        None => return Ok(()),
+    };
+
+    storage
+        .schedule_job(
+            guild_id,
+            &JobKind::WeeklyReport,
+            duration::from_now(INTERVAL),
+        )
+        .await?;
+
+    let since =
+        DateTime::from_millis(DateTime::now().timestamp_millis() - INTERVAL.as_millis() as i64);
+    let report = storage.court_report(guild_id, since).await?;
+    let strings = state.locale.strings();
+    let verdicts = verdicts(&report, strings.none);
+
+    channel_id
+        .send_message(http, |msg| {
+            msg.embed(|embed| {
+                embed
+                    .title(strings.weekly_report_title)
+                    .field(strings.weekly_report_opened, report.opened_lawsuits, true)
+                    .field(
+                        strings.weekly_report_closed,
+                        report.closed_lawsuits.len(),
+                        true,
+                    )
+                    .field(strings.weekly_report_arrests, report.arrests, true)
+                    .field(strings.weekly_report_releases, report.releases, true)
+                    .field(strings.weekly_report_verdicts, verdicts, false)
+            })
+        })
+        .await
+        .wrap_err("send weekly report")?;
+
+    Ok(())
+}
+
+/// The latest verdicts with their case numbers, one per line.
+fn verdicts(report: &CourtReport, none: &str) -> String {
+    let skip = report.closed_lawsuits.len().saturating_sub(MAX_VERDICTS);
+    let verdicts = report
+        .closed_lawsuits
+        .iter()
+        .skip(skip)
+        .filter(|lawsuit| !lawsuit.sealed)
+        .map(|lawsuit| {
+            let case_number = lawsuit
+                .case_number
+                .clone()
+                .unwrap_or_else(|| lawsuit.id.to_string());
+            let verdict = lawsuit.verdict.as_deref().unwrap_or_default();
+            let mut shortened = verdict.chars().take(MAX_VERDICT_LEN).collect::<String>();
+            if shortened.len() < verdict.len() {
+                shortened.push('…');
+            }
+            format!("`{case_number}` {shortened}")
+        })
+        .collect::<Vec<_>>();
+
+    if verdicts.is_empty() {
+        none.to_owned()
+    } else {
+        verdicts.join("\n")
+    }
+}
@@ -0,0 +1,47 @@
+use std::{future::Future, time::Duration};
+
+use poise::serenity::{
+    http::{HttpError, StatusCode},
+    Error,
+};
+use tracing::warn;
+
+const MAX_ATTEMPTS: u32 = 4;
+const FIRST_DELAY: Duration = Duration::from_millis(500);
+
+/// Sends the request again with a doubling delay while discord is rate limiting the bot or has a
+/// temporary problem. Other errors like missing permissions are returned right away.
+pub async fn retry<T, F, Fut>(mut request: F) -> Result<T, Error>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, Error>>,
+{
+    let mut delay = FIRST_DELAY;
+    let mut attempt = 1;
+
+    loop {
+        match request().await {
+            Err(err) if attempt < MAX_ATTEMPTS && is_transient(&err) => {
+                warn!(?err, attempt, ?delay, "Discord request failed, retrying");
+                tokio::time::sleep(delay).await;
+                delay *= 2;
+                attempt += 1;
+            }
+            result => return result,
+        }
+    }
+}
+
+fn is_transient(err: &Error) -> bool {
+    match err {
+        Error::Http(err) => match &**err {
+            HttpError::UnsuccessfulRequest(response) => {
+                response.status_code == StatusCode::TOO_MANY_REQUESTS
+                    || response.status_code.is_server_error()
+            }
+            HttpError::Request(err) => err.is_timeout() || err.is_connect(),
+            _ => false,
+        },
+        _ => false,
+    }
+}
@@ -0,0 +1,118 @@
+use std::{sync::Arc, time::Duration};
+
+use color_eyre::Result;
+use mongodb::bson::DateTime;
+use poise::serenity::http::Http;
+use tracing::{debug, error, info, warn};
+
+use crate::{
+    duration, inactivity, lawsuit,
+    maintenance::Maintenance,
+    model::{Job, JobKind},
+    prison, report,
+    shutdown::BackgroundTasks,
+    storage::Storage,
+};
+
+const CHECK_INTERVAL: Duration = Duration::from_secs(60);
+const MAX_ATTEMPTS: u32 = 5;
+const RETRY_DELAY: Duration = Duration::from_secs(60 * 5);
+/// Long enough for any job, if the process dies while running it, another one takes over after
+const LEASE: Duration = Duration::from_secs(60 * 10);
+
+/// Runs the jobs that are due. The jobs are stored in the database, so everything that became due
+/// while the bot was offline is run on the first check after startup. On shutdown, the jobs that
+/// are currently running are finished first. More than one process can run the scheduler, every
+/// job is leased by the process that runs it.
+pub async fn run(
+    http: Arc<Http>,
+    storage: Arc<dyn Storage>,
+    tasks: BackgroundTasks,
+    maintenance: Arc<Maintenance>,
+) {
+    let mut interval = tokio::time::interval(CHECK_INTERVAL);
+
+    loop {
+        tokio::select! {
+            _ = interval.tick() => {}
+            _ = tasks.shutting_down() => {
+                info!("Stopped scheduler");
+                return;
+            }
+        }
+
+        // the jobs change discord and the database, they run after the maintenance
+        if maintenance.is_enabled() {
+            debug!("Not running jobs during maintenance");
+            continue;
+        }
+
+        if let Err(err) = run_due_jobs(&http, &storage, &tasks).await {
+            error!(?err, "Error running due jobs");
+        }
+    }
+}
+
+async fn run_due_jobs(
+    http: &Arc<Http>,
+    storage: &Arc<dyn Storage>,
+    tasks: &BackgroundTasks,
+) -> Result<()> {
+    let jobs = storage.find_due_jobs(DateTime::now()).await?;
+
+    for job in jobs {
+        if !storage.claim_job(&job, duration::from_now(LEASE)).await? {
+            debug!(guild_id = %job.guild_id, kind = ?job.kind, "Job is run by another process");
+            continue;
+        }
+
+        match execute(http, storage, tasks, &job).await {
+            Ok(()) => {
+                info!(guild_id = %job.guild_id, kind = ?job.kind, "Ran job");
+                storage.finish_job(&job).await?;
+            }
+            Err(err) if job.attempts + 1 >= MAX_ATTEMPTS => {
+                error!(?err, guild_id = %job.guild_id, kind = ?job.kind, "Job failed, giving up");
+                storage.finish_job(&job).await?;
+            }
+            Err(err) => {
+                warn!(?err, guild_id = %job.guild_id, kind = ?job.kind, "Job failed, retrying");
+                storage
+                    .retry_job(&job, duration::from_now(RETRY_DELAY))
+                    .await?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+async fn execute(
+    http: &Arc<Http>,
+    storage: &Arc<dyn Storage>,
+    tasks: &BackgroundTasks,
+    job: &Job,
+) -> Result<()> {
+    let guild_id = job.guild_id.into();
+
+    match job.kind {
+        JobKind::Release { user_id } => {
+            prison::release_sentence_over(http, storage, guild_id, user_id.into()).await
+        }
+        JobKind::EndParole { user_id } => {
+            prison::end_parole(http, storage, guild_id, user_id.into()).await
+        }
+        JobKind::PurgeTrash => storage.purge_trash(job.guild_id).await,
+        JobKind::WeeklyReport => report::send_weekly(http, storage, job.guild_id).await,
+        JobKind::DismissExpiredLawsuits => {
+            lawsuit::dismiss_expired(http, storage, tasks, job.guild_id).await
+        }
+        JobKind::NudgeInactiveRooms => {
+            inactivity::nudge_inactive(http, storage, job.guild_id).await
+        }
+        JobKind::LiftContempt {
+            channel_id,
+            user_id,
+        } => lawsuit::lift_contempt(http, channel_id, user_id).await,
+    }
+}
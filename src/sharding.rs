@@ -0,0 +1,102 @@
+use std::env;
+
+use color_eyre::{
+    eyre::{bail, WrapErr},
+    Result,
+};
+use once_cell::sync::OnceCell;
+use poise::serenity_prelude as serenity;
+
+/// `Framework::start_with` only takes a function pointer, so the sharding is passed through here.
+static SHARDING: OnceCell<Sharding> = OnceCell::new();
+
+/// Which gateway shards this process connects. `SHARD_COUNT` is either the total number of
+/// shards or `auto` to ask discord. When the shards are split across multiple processes, `SHARD_ID`
+/// selects a single shard or a range like `0-3` for this process.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Sharding {
+    /// A single gateway connection, the default
+    Single,
+    /// As many shards as discord recommends, all in this process
+    Auto,
+    /// The shards `first` to `last` (inclusive) out of `total`
+    Range { first: u64, last: u64, total: u64 },
+}
+
+impl Sharding {
+    /// Reads the sharding from the environment and remembers it for [`start`].
+    pub fn init() -> Result<Self> {
+        let sharding = Self::from_env()?;
+        SHARDING
+            .set(sharding)
+            .ok()
+            .wrap_err("sharding initialized twice")?;
+        Ok(sharding)
+    }
+
+    fn from_env() -> Result<Self> {
+        let count = match env::var("SHARD_COUNT") {
+            Ok(count) => count,
+            Err(_) => {
+                if env::var("SHARD_ID").is_ok() {
+                    bail!("SHARD_ID is set without SHARD_COUNT");
+                }
+                return Ok(Self::Single);
+            }
+        };
+
+        if count == "auto" {
+            if env::var("SHARD_ID").is_ok() {
+                bail!("SHARD_ID can't be used with SHARD_COUNT=auto");
+            }
+            return Ok(Self::Auto);
+        }
+
+        let total = count
+            .parse::<u64>()
+            .wrap_err("SHARD_COUNT must be an integer or auto")?;
+        if total == 0 {
+            bail!("SHARD_COUNT must be at least 1");
+        }
+
+        let (first, last) = match env::var("SHARD_ID") {
+            Ok(id) => {
+                let (first, last) = id.split_once('-').unwrap_or((&id, &id));
+                (parse_shard_id(first)?, parse_shard_id(last)?)
+            }
+            Err(_) => (0, total - 1),
+        };
+
+        if first > last || last >= total {
+            bail!("SHARD_ID {first}-{last} is not within the {total} shards");
+        }
+
+        Ok(Self::Range { first, last, total })
+    }
+
+    /// Things that must only happen once across all processes, like running the scheduled jobs,
+    /// happen in the process with the first shard. They only use the http api, which works for
+    /// every guild.
+    pub fn runs_first_shard(self) -> bool {
+        match self {
+            Self::Single | Self::Auto => true,
+            Self::Range { first, .. } => first == 0,
+        }
+    }
+}
+
+fn parse_shard_id(id: &str) -> Result<u64> {
+    id.parse()
+        .wrap_err("SHARD_ID must be an integer or a range like 0-3")
+}
+
+/// Connects the shards of this process, for `Framework::start_with`.
+pub async fn start(mut client: serenity::Client) -> serenity::Result<()> {
+    match SHARDING.get().copied().unwrap_or(Sharding::Single) {
+        Sharding::Single => client.start().await,
+        Sharding::Auto => client.start_autosharded().await,
+        Sharding::Range { first, last, total } => {
+            client.start_shard_range([first, last], total).await
+        }
+    }
+}
@@ -0,0 +1,106 @@
+use std::{
+    future::Future,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+};
+
+use color_eyre::Result;
+use tokio::sync::{watch, Notify};
+
+use crate::WrapErr;
+
+/// Tasks that run in the background and have to finish before the bot exits, like setting up a
+/// lawsuit after the command was already answered or the scheduler.
+#[derive(Clone)]
+pub struct BackgroundTasks {
+    inner: Arc<Inner>,
+}
+
+struct Inner {
+    running: AtomicUsize,
+    all_done: Notify,
+    shutting_down: watch::Sender<bool>,
+    shutting_down_rx: watch::Receiver<bool>,
+}
+
+/// Marks the task as done when it's dropped, even if the task panicked.
+struct Running(Arc<Inner>);
+
+impl Drop for Running {
+    fn drop(&mut self) {
+        if self.0.running.fetch_sub(1, Ordering::SeqCst) == 1 {
+            self.0.all_done.notify_waiters();
+        }
+    }
+}
+
+impl Default for BackgroundTasks {
+    fn default() -> Self {
+        let (shutting_down, shutting_down_rx) = watch::channel(false);
+        Self {
+            inner: Arc::new(Inner {
+                running: AtomicUsize::new(0),
+                all_done: Notify::new(),
+                shutting_down,
+                shutting_down_rx,
+            }),
+        }
+    }
+}
+
+impl BackgroundTasks {
+    pub fn spawn(&self, task: impl Future<Output = ()> + Send + 'static) {
+        self.inner.running.fetch_add(1, Ordering::SeqCst);
+        let running = Running(self.inner.clone());
+        tokio::spawn(async move {
+            let _running = running;
+            task.await;
+        });
+    }
+
+    /// Completes once the shutdown started. Long running tasks stop at the next point where
+    /// nothing is left half done.
+    pub async fn shutting_down(&self) {
+        let mut shutting_down = self.inner.shutting_down_rx.clone();
+        while !*shutting_down.borrow() {
+            if shutting_down.changed().await.is_err() {
+                return;
+            }
+        }
+    }
+
+    /// Tells the tasks to stop and waits until all of them are done.
+    pub async fn shutdown(&self) {
+        let _ = self.inner.shutting_down.send(true);
+
+        loop {
+            // created before checking, so that a task finishing in between isn't missed
+            let all_done = self.inner.all_done.notified();
+            if self.inner.running.load(Ordering::SeqCst) == 0 {
+                return;
+            }
+            all_done.await;
+        }
+    }
+}
+
+/// Completes on ctrl-c or, on unix, when the process is asked to terminate like `docker stop`
+/// does.
+pub async fn signal() -> Result<()> {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{signal, SignalKind};
+
+        let mut terminate = signal(SignalKind::terminate()).wrap_err("listen for SIGTERM")?;
+        tokio::select! {
+            result = tokio::signal::ctrl_c() => result.wrap_err("listen for ctrl-c"),
+            _ = terminate.recv() => Ok(()),
+        }
+    }
+    #[cfg(not(unix))]
+    {
+        tokio::signal::ctrl_c().await.wrap_err("listen for ctrl-c")
+    }
+}
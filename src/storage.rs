@@ -0,0 +1,674 @@
+use std::time::Duration;
+
+use async_trait::async_trait;
+use color_eyre::Result;
+use futures::stream::BoxStream;
+use mongodb::bson::{DateTime, Uuid};
+
+use crate::{
+    bar::BarQuestion,
+    election::Election,
+    i18n::Locale,
+    lawsuit::{
+        Billing, ConflictPolicy, Contempt, Evidence, Fine, JuryTally, Lawsuit, LawyerFee,
+        SubpoenaResponse, Witness,
+    },
+    model::{
+        BotStats, CommandUsage, CourtReport, CourtRoom, CourtStats, ErasureReport, GagOrder,
+        GuildData, GuildListing, Job, JobKind, JudgeRegistry, MemberData, Page, PrisonEntry,
+        PrisonRecord, PrisonStats, PrisonTier, RoleTemplate, SnowflakeId, State,
+    },
+    permissions::CourtRole,
+    templates::Template,
+};
+
+/// Where the bot stores its data. The backend is chosen with the `STORAGE` environment variable,
+/// see [`crate::model::Mongo`] and [`crate::postgres::Postgres`].
+#[async_trait]
+pub trait Storage: Send + Sync {
+    /// Checks that the database is reachable, for the health check.
+    async fn ping(&self) -> Result<()>;
+
+    /// Closes the connections to the database before the bot exits.
+    async fn close(&self);
+
+    /// The cached state of the guild and how long ago it was cached, for `/debug`. Only
+    /// [`crate::cache::CachedStorage`] has a cache.
+    fn cached_state(&self, _guild_id: SnowflakeId) -> Option<(Duration, State)> {
+        None
+    }
+
+    /// The guilds whose state changes, also by other processes using the same database. `None`
+    /// in an item means that the guild is unknown. Without support from the database, this is
+    /// `None` and other processes can only be noticed when the cache expires.
+    async fn state_changes(
+        &self,
+    ) -> Result<Option<BoxStream<'static, Result<Option<SnowflakeId>>>>> {
+        Ok(None)
+    }
+
+    // state
+
+    async fn find_or_insert_state(&self, guild_id: SnowflakeId) -> Result<State>;
+
+    /// Unlike [`Storage::find_or_insert_state`], this doesn't create a state for guilds that don't
+    /// have one, for requests that don't come from discord.
+    async fn find_state(&self, guild_id: SnowflakeId) -> Result<Option<State>>;
+
+    async fn set_court_category(&self, guild_id: SnowflakeId, category: SnowflakeId) -> Result<()>;
+
+    /// `None` for the default name of the rooms, see [`crate::model::DEFAULT_APPEAL_ROOM_NAME`].
+    async fn set_appeal_category(
+        &self,
+        guild_id: SnowflakeId,
+        category: SnowflakeId,
+        room_name: Option<&str>,
+    ) -> Result<()>;
+
+    /// Also stores how the role looks, to recreate it if it's deleted.
+    async fn set_prison_role(
+        &self,
+        guild_id: SnowflakeId,
+        prison_role: SnowflakeId,
+        template: &RoleTemplate,
+    ) -> Result<()>;
+
+    async fn set_probation_role(
+        &self,
+        guild_id: SnowflakeId,
+        probation_role: SnowflakeId,
+    ) -> Result<()>;
+
+    async fn set_prison_channel(
+        &self,
+        guild_id: SnowflakeId,
+        prison_channel: SnowflakeId,
+        lockdown: bool,
+    ) -> Result<()>;
+
+    async fn set_strip_roles_on_arrest(
+        &self,
+        guild_id: SnowflakeId,
+        strip_roles: bool,
+    ) -> Result<()>;
+
+    async fn set_moderator_channel(
+        &self,
+        guild_id: SnowflakeId,
+        moderator_channel: SnowflakeId,
+    ) -> Result<()>;
+
+    async fn set_notify_prisoners(
+        &self,
+        guild_id: SnowflakeId,
+        notify_prisoners: bool,
+    ) -> Result<()>;
+
+    async fn set_strict_lawyers(&self, guild_id: SnowflakeId, strict_lawyers: bool) -> Result<()>;
+
+    async fn set_conflict_policy(
+        &self,
+        guild_id: SnowflakeId,
+        conflict_policy: ConflictPolicy,
+    ) -> Result<()>;
+
+    async fn set_appeal_judge(
+        &self,
+        guild_id: SnowflakeId,
+        appeal_judge: SnowflakeId,
+    ) -> Result<()>;
+
+    async fn set_audit_channel(
+        &self,
+        guild_id: SnowflakeId,
+        audit_channel: SnowflakeId,
+    ) -> Result<()>;
+
+    async fn set_error_channel(
+        &self,
+        guild_id: SnowflakeId,
+        error_channel: SnowflakeId,
+    ) -> Result<()>;
+
+    async fn set_records_channel(
+        &self,
+        guild_id: SnowflakeId,
+        records_channel: SnowflakeId,
+    ) -> Result<()>;
+
+    /// Nobody is pinged in the records channel if `role_id` is `None`.
+    async fn set_news_role(
+        &self,
+        guild_id: SnowflakeId,
+        role_id: Option<SnowflakeId>,
+    ) -> Result<()>;
+
+    /// Turns the weekly report off if `report_channel` is `None`.
+    async fn set_report_channel(
+        &self,
+        guild_id: SnowflakeId,
+        report_channel: Option<SnowflakeId>,
+    ) -> Result<()>;
+
+    /// Open lawsuits are never dismissed if `max_age` is `None`.
+    async fn set_statute_of_limitations(
+        &self,
+        guild_id: SnowflakeId,
+        max_age: Option<Duration>,
+    ) -> Result<()>;
+
+    /// Court rooms are never nudged if `after` is `None`.
+    async fn set_inactivity_nudge(
+        &self,
+        guild_id: SnowflakeId,
+        after: Option<Duration>,
+    ) -> Result<()>;
+
+    /// Members can be the plaintiff of any number of open lawsuits if `max` is `None`.
+    async fn set_max_open_lawsuits(&self, guild_id: SnowflakeId, max: Option<u32>) -> Result<()>;
+
+    /// Members can file lawsuits right after each other if `cooldown` is `None`.
+    async fn set_filing_cooldown(
+        &self,
+        guild_id: SnowflakeId,
+        cooldown: Option<Duration>,
+    ) -> Result<()>;
+
+    /// The court rooms don't get a slowmode during lawsuits if `slowmode` is `None`.
+    async fn set_court_slowmode(
+        &self,
+        guild_id: SnowflakeId,
+        slowmode: Option<Duration>,
+    ) -> Result<()>;
+
+    async fn set_last_filed(
+        &self,
+        guild_id: SnowflakeId,
+        user_id: SnowflakeId,
+        filed_at: DateTime,
+    ) -> Result<()>;
+
+    /// Counts the case up for the year and returns its number in the year, starting at 1.
+    async fn next_case_number(&self, guild_id: SnowflakeId, year: i64) -> Result<u32>;
+
+    async fn set_locale(&self, guild_id: SnowflakeId, locale: Locale) -> Result<()>;
+
+    /// Removes the webhook if `webhook_url` is `None`.
+    async fn set_webhook_url(&self, guild_id: SnowflakeId, webhook_url: Option<&str>)
+        -> Result<()>;
+
+    /// What is stored about the member, empty if there's nothing.
+    async fn find_member(&self, guild_id: SnowflakeId, user_id: SnowflakeId) -> Result<MemberData>;
+
+    async fn find_members(&self, guild_id: SnowflakeId) -> Result<Vec<MemberData>>;
+
+    /// Adds the amount to the balance of the user, the balance can become negative. Returns the
+    /// new balance.
+    async fn add_to_balance(
+        &self,
+        guild_id: SnowflakeId,
+        user_id: SnowflakeId,
+        amount: i64,
+    ) -> Result<i64>;
+
+    /// Moves the money in one step and returns the new balance of `from`. Unless `overdraw` is
+    /// set, nothing is moved and `None` is returned if `from` doesn't have enough money.
+    async fn transfer(
+        &self,
+        guild_id: SnowflakeId,
+        from: SnowflakeId,
+        to: SnowflakeId,
+        amount: i64,
+        overdraw: bool,
+    ) -> Result<Option<i64>>;
+
+    /// Removes the fee if `fee` is `None`.
+    async fn set_lawyer_fee(
+        &self,
+        guild_id: SnowflakeId,
+        lawyer: SnowflakeId,
+        fee: Option<LawyerFee>,
+    ) -> Result<()>;
+
+    async fn set_bar_questions(
+        &self,
+        guild_id: SnowflakeId,
+        questions: &[BarQuestion],
+    ) -> Result<()>;
+
+    async fn admit_lawyer(&self, guild_id: SnowflakeId, lawyer: SnowflakeId) -> Result<()>;
+
+    /// Adds the user to the jury pool or removes them from it.
+    async fn set_jury_pool_member(
+        &self,
+        guild_id: SnowflakeId,
+        user_id: SnowflakeId,
+        member: bool,
+    ) -> Result<()>;
+
+    async fn add_gag_order(&self, guild_id: SnowflakeId, order: &GagOrder) -> Result<()>;
+
+    /// Lifts the gag orders of the lawsuit, at its verdict.
+    async fn remove_gag_orders(&self, guild_id: SnowflakeId, lawsuit_id: Uuid) -> Result<()>;
+
+    async fn set_litigant_banned(
+        &self,
+        guild_id: SnowflakeId,
+        user_id: SnowflakeId,
+        banned: bool,
+    ) -> Result<()>;
+
+    /// Removes the template if `text` is `None`, so that the default text is used again.
+    async fn set_message_template(
+        &self,
+        guild_id: SnowflakeId,
+        template: Template,
+        text: Option<&str>,
+    ) -> Result<()>;
+
+    /// Removes the token if `api_token_hash` is `None`.
+    async fn set_api_token_hash(
+        &self,
+        guild_id: SnowflakeId,
+        api_token_hash: Option<&str>,
+    ) -> Result<()>;
+
+    /// Removes the token if `calendar_token_hash` is `None`.
+    async fn set_calendar_token_hash(
+        &self,
+        guild_id: SnowflakeId,
+        calendar_token_hash: Option<&str>,
+    ) -> Result<()>;
+
+    async fn set_court_role(
+        &self,
+        guild_id: SnowflakeId,
+        court_role: CourtRole,
+        role_id: SnowflakeId,
+    ) -> Result<()>;
+
+    /// Adds the tier, replacing the tier with the same name.
+    async fn set_prison_tier(&self, guild_id: SnowflakeId, tier: &PrisonTier) -> Result<()>;
+
+    async fn remove_prison_tier(&self, guild_id: SnowflakeId, name: &str) -> Result<()>;
+
+    // lawsuits
+
+    async fn add_court_room(&self, guild_id: SnowflakeId, room: &CourtRoom) -> Result<()>;
+
+    /// Forgets all court rooms, the channels are kept.
+    async fn remove_court_rooms(&self, guild_id: SnowflakeId) -> Result<()>;
+
+    /// Marks the free court room as used by a lawsuit, but only if the state still has the
+    /// version that the room was chosen from. Returns `false` if another command changed the
+    /// state in the meantime, then the room has to be chosen again.
+    async fn claim_court_room(
+        &self,
+        guild_id: SnowflakeId,
+        channel_id: SnowflakeId,
+        version: u64,
+    ) -> Result<bool>;
+
+    /// Stores the time of the last message in the court room and resets its nudges.
+    async fn set_room_activity(
+        &self,
+        guild_id: SnowflakeId,
+        channel_id: SnowflakeId,
+        last_activity: DateTime,
+    ) -> Result<()>;
+
+    async fn set_room_nudges(
+        &self,
+        guild_id: SnowflakeId,
+        channel_id: SnowflakeId,
+        nudges: u32,
+    ) -> Result<()>;
+
+    async fn set_room_closed_to_public(
+        &self,
+        guild_id: SnowflakeId,
+        channel_id: SnowflakeId,
+        closed: bool,
+    ) -> Result<()>;
+
+    async fn add_lawsuit(&self, lawsuit: &Lawsuit) -> Result<()>;
+
+    /// Deletes all lawsuits of the guild and frees the court rooms. Returns how many were deleted.
+    async fn delete_lawsuits(&self, guild_id: SnowflakeId) -> Result<u64>;
+
+    async fn find_lawsuits(&self, guild_id: SnowflakeId) -> Result<Vec<Lawsuit>>;
+
+    /// The lawsuits without a verdict.
+    async fn find_open_lawsuits(&self, guild_id: SnowflakeId) -> Result<Vec<Lawsuit>>;
+
+    /// Up to `limit` lawsuits without a verdict whose reason starts with `prefix`, ignoring the
+    /// case. For the autocomplete.
+    async fn find_open_lawsuits_by_reason(
+        &self,
+        guild_id: SnowflakeId,
+        prefix: &str,
+        limit: usize,
+    ) -> Result<Vec<Lawsuit>>;
+
+    /// Up to `limit` closed lawsuits that aren't sealed and whose reason or verdict contain the
+    /// words of the query, the best matches first.
+    async fn search_closed_lawsuits(
+        &self,
+        guild_id: SnowflakeId,
+        query: &str,
+        limit: usize,
+    ) -> Result<Vec<Lawsuit>>;
+
+    /// Up to `limit` lawsuits after the cursor, ordered by their id.
+    async fn find_lawsuits_page(
+        &self,
+        guild_id: SnowflakeId,
+        after: Option<Uuid>,
+        limit: usize,
+    ) -> Result<Page<Lawsuit, Uuid>>;
+
+    /// The number of open lawsuits in every guild that has any.
+    async fn count_open_lawsuits(&self) -> Result<Vec<(SnowflakeId, u64)>>;
+
+    async fn set_court_room_ongoing(
+        &self,
+        guild_id: SnowflakeId,
+        channel_id: SnowflakeId,
+        ongoing_lawsuit: bool,
+    ) -> Result<()>;
+
+    /// Also stores when the lawsuit was closed, or removes it if `verdict` is `None`.
+    async fn set_lawsuit_verdict(
+        &self,
+        guild_id: SnowflakeId,
+        lawsuit_id: Uuid,
+        verdict: Option<&str>,
+    ) -> Result<()>;
+
+    /// Stores the verdict and frees the court room of the lawsuit together, so that a crash can't
+    /// leave a closed lawsuit in a room that's still taken or the other way around. The details of
+    /// the verdict and whether the lawsuit is sealed or overturned are written with the verdict,
+    /// a sealed lawsuit must never be visible with its verdict.
+    async fn close_lawsuit(&self, guild_id: SnowflakeId, lawsuit: &Lawsuit) -> Result<()>;
+
+    async fn add_evidence(
+        &self,
+        guild_id: SnowflakeId,
+        lawsuit_id: Uuid,
+        evidence: &Evidence,
+    ) -> Result<()>;
+
+    async fn add_witness(
+        &self,
+        guild_id: SnowflakeId,
+        lawsuit_id: Uuid,
+        witness: &Witness,
+    ) -> Result<()>;
+
+    /// Stores the response if the subpoena of the witness is still pending, returns whether it
+    /// was.
+    async fn answer_subpoena(
+        &self,
+        guild_id: SnowflakeId,
+        lawsuit_id: Uuid,
+        witness_id: SnowflakeId,
+        response: SubpoenaResponse,
+    ) -> Result<bool>;
+
+    async fn set_jury_tally(
+        &self,
+        guild_id: SnowflakeId,
+        lawsuit_id: Uuid,
+        tally: &JuryTally,
+    ) -> Result<()>;
+
+    /// Removes the hearing if `hearing_at` is `None`.
+    async fn set_lawsuit_hearing(
+        &self,
+        guild_id: SnowflakeId,
+        lawsuit_id: Uuid,
+        hearing_at: Option<DateTime>,
+    ) -> Result<()>;
+
+    /// Stores that the lawsuit cites the precedent on both of them.
+    async fn add_citation(
+        &self,
+        guild_id: SnowflakeId,
+        lawsuit_id: Uuid,
+        precedent_id: Uuid,
+    ) -> Result<()>;
+
+    async fn set_lawsuit_jurors(
+        &self,
+        guild_id: SnowflakeId,
+        lawsuit_id: Uuid,
+        jurors: &[SnowflakeId],
+    ) -> Result<()>;
+
+    async fn set_lawsuit_fine(
+        &self,
+        guild_id: SnowflakeId,
+        lawsuit_id: Uuid,
+        fine: &Fine,
+    ) -> Result<()>;
+
+    async fn add_billing(
+        &self,
+        guild_id: SnowflakeId,
+        lawsuit_id: Uuid,
+        billing: &Billing,
+    ) -> Result<()>;
+
+    async fn add_contempt(
+        &self,
+        guild_id: SnowflakeId,
+        lawsuit_id: Uuid,
+        contempt: &Contempt,
+    ) -> Result<()>;
+
+    async fn add_ejected(
+        &self,
+        guild_id: SnowflakeId,
+        lawsuit_id: Uuid,
+        user_id: SnowflakeId,
+    ) -> Result<()>;
+
+    // whole guild
+
+    /// Moves the state into the trash, replacing the previously cleared state of the guild.
+    async fn trash_guild(&self, guild_id: SnowflakeId) -> Result<()>;
+
+    /// Puts the cleared state back if it's still within the grace period. Everything that was
+    /// created since the clear is replaced.
+    async fn restore_guild(&self, guild_id: SnowflakeId) -> Result<Option<State>>;
+
+    async fn purge_trash(&self, guild_id: SnowflakeId) -> Result<()>;
+
+    async fn export_guild(&self, guild_id: SnowflakeId) -> Result<GuildData> {
+        let state = self.find_or_insert_state(guild_id).await?;
+        let lawsuits = self.find_lawsuits(guild_id).await?;
+        let prison_entries = self.find_prison_entries(guild_id).await?;
+        let prison_history = self.find_prison_records(guild_id).await?;
+        let jobs = self.find_jobs(guild_id).await?;
+        let members = self.find_members(guild_id).await?;
+
+        Ok(GuildData {
+            state,
+            lawsuits,
+            prison_entries,
+            prison_history,
+            jobs,
+            members,
+        })
+    }
+
+    /// Removes everything about the user in the guild. Lawsuits are kept for the other
+    /// participants, the user is replaced with the id 0 there.
+    async fn erase_user(
+        &self,
+        guild_id: SnowflakeId,
+        user_id: SnowflakeId,
+    ) -> Result<ErasureReport>;
+
+    /// Replaces everything that is stored about the guild with the data.
+    async fn replace_guild(&self, data: &GuildData) -> Result<()>;
+
+    // prison
+
+    /// Puts the user into prison. If they are already in prison, only the tier and the release
+    /// time are changed and a parole ends.
+    async fn add_to_prison(
+        &self,
+        guild_id: SnowflakeId,
+        user_id: SnowflakeId,
+        tier: Option<&str>,
+        release_at: Option<DateTime>,
+        arrested_by: Option<SnowflakeId>,
+    ) -> Result<()>;
+
+    async fn remove_from_prison(&self, guild_id: SnowflakeId, user_id: SnowflakeId) -> Result<()>;
+
+    async fn find_prison_entry(
+        &self,
+        guild_id: SnowflakeId,
+        user_id: SnowflakeId,
+    ) -> Result<Option<PrisonEntry>>;
+
+    async fn set_parole(
+        &self,
+        guild_id: SnowflakeId,
+        user_id: SnowflakeId,
+        parole_until: Option<DateTime>,
+    ) -> Result<()>;
+
+    async fn insert_prison_entries(&self, entries: &[PrisonEntry]) -> Result<()>;
+
+    /// Moves the prisoners to the tier, people on parole are back in prison after that.
+    async fn set_prison_entries_tier(
+        &self,
+        guild_id: SnowflakeId,
+        user_ids: &[SnowflakeId],
+        tier: Option<&str>,
+    ) -> Result<()>;
+
+    async fn remove_all_from_prison(&self, guild_id: SnowflakeId) -> Result<()>;
+
+    async fn set_saved_roles(
+        &self,
+        guild_id: SnowflakeId,
+        user_id: SnowflakeId,
+        saved_roles: &[SnowflakeId],
+    ) -> Result<()>;
+
+    async fn set_release_at(
+        &self,
+        guild_id: SnowflakeId,
+        user_id: SnowflakeId,
+        release_at: Option<DateTime>,
+    ) -> Result<()>;
+
+    async fn find_prison_entries(&self, guild_id: SnowflakeId) -> Result<Vec<PrisonEntry>>;
+
+    /// Up to `limit` prisoners after the cursor, ordered by their user id.
+    async fn find_prisoners_page(
+        &self,
+        guild_id: SnowflakeId,
+        after: Option<SnowflakeId>,
+        limit: usize,
+    ) -> Result<Page<PrisonEntry, SnowflakeId>>;
+
+    /// The number of prisoners in every guild that has any.
+    async fn count_prisoners(&self) -> Result<Vec<(SnowflakeId, u64)>>;
+
+    async fn bot_stats(&self) -> Result<BotStats>;
+
+    // prison history
+
+    async fn add_prison_records(&self, records: &[PrisonRecord]) -> Result<()>;
+
+    async fn find_prison_records(&self, guild_id: SnowflakeId) -> Result<Vec<PrisonRecord>>;
+
+    /// Marks the open records of the users as released. Releases everyone in the guild if
+    /// `user_ids` is `None`.
+    async fn close_prison_records(
+        &self,
+        guild_id: SnowflakeId,
+        user_ids: Option<&[SnowflakeId]>,
+        released_at: DateTime,
+    ) -> Result<()>;
+
+    async fn prison_stats(&self, guild_id: SnowflakeId) -> Result<PrisonStats>;
+
+    /// The numbers about all lawsuits of the guild, see [`CourtStats`].
+    async fn court_stats(&self, guild_id: SnowflakeId) -> Result<CourtStats>;
+
+    /// What happened in the guild since the time, for the weekly report.
+    async fn court_report(&self, guild_id: SnowflakeId, since: DateTime) -> Result<CourtReport>;
+
+    // jobs
+
+    async fn schedule_job(
+        &self,
+        guild_id: SnowflakeId,
+        kind: &JobKind,
+        run_at: DateTime,
+    ) -> Result<()>;
+
+    async fn cancel_job(&self, guild_id: SnowflakeId, kind: &JobKind) -> Result<()>;
+
+    /// Cancels the release and parole jobs of everyone in the prison of the guild.
+    async fn cancel_prison_jobs(&self, guild_id: SnowflakeId) -> Result<()>;
+
+    async fn find_jobs(&self, guild_id: SnowflakeId) -> Result<Vec<Job>>;
+
+    async fn find_due_jobs(&self, now: DateTime) -> Result<Vec<Job>>;
+
+    /// Leases the due job to this process until the time, so that it's not run twice when more
+    /// than one process runs the scheduler. Returns `false` if another process holds the lease or
+    /// the job was rescheduled.
+    async fn claim_job(&self, job: &Job, until: DateTime) -> Result<bool>;
+
+    /// Removes the job after it has run. If it was rescheduled in the meantime, it's kept.
+    async fn finish_job(&self, job: &Job) -> Result<()>;
+
+    async fn retry_job(&self, job: &Job, run_at: DateTime) -> Result<()>;
+
+    // judges
+
+    async fn find_judge_registry(&self, guild_id: SnowflakeId) -> Result<JudgeRegistry>;
+
+    /// Returns `false` if the judge was already registered.
+    async fn register_judge(&self, guild_id: SnowflakeId, judge: SnowflakeId) -> Result<bool>;
+
+    /// Returns `false` if the judge wasn't registered.
+    async fn unregister_judge(&self, guild_id: SnowflakeId, judge: SnowflakeId) -> Result<bool>;
+
+    /// Picks the next registered judge who isn't one of the parties and moves the rotation on.
+    /// Returns `None` if there is no such judge.
+    async fn assign_judge(
+        &self,
+        guild_id: SnowflakeId,
+        parties: &[SnowflakeId],
+    ) -> Result<Option<SnowflakeId>>;
+
+    // elections
+
+    async fn add_election(&self, election: &Election) -> Result<()>;
+
+    /// The finished elections of the guild, the newest first.
+    async fn find_elections(&self, guild_id: SnowflakeId) -> Result<Vec<Election>>;
+
+    // analytics
+
+    /// Stores the invocation, only the newest [`crate::model::MAX_COMMAND_USAGES`] are kept.
+    async fn record_command_usage(&self, usage: &CommandUsage) -> Result<()>;
+
+    async fn find_command_usage(&self, since: DateTime) -> Result<Vec<CommandUsage>>;
+
+    // guild access
+
+    async fn find_guild_listings(&self) -> Result<Vec<GuildListing>>;
+
+    /// Allows or blocks the guild, `None` removes it from the lists.
+    async fn set_guild_listing(&self, guild_id: SnowflakeId, allowed: Option<bool>) -> Result<()>;
+}
@@ -0,0 +1,240 @@
+use std::{sync::Arc, time::Duration};
+
+use color_eyre::Result;
+use mongodb::bson::Uuid;
+use poise::serenity::{
+    builder::CreateComponents,
+    collector::CollectComponentInteraction,
+    http::Http,
+    model::{
+        channel::Message,
+        id::{ChannelId, GuildId, UserId},
+        interactions::{message_component::ButtonStyle, InteractionResponseType},
+    },
+    prelude::Context,
+};
+use tracing::{debug, info};
+
+use crate::{
+    duration,
+    i18n::Strings,
+    lawsuit::{Lawsuit, SubpoenaResponse},
+    shutdown::BackgroundTasks,
+    storage::Storage,
+    WrapErr,
+};
+
+/// How long the witness has to answer if the judge doesn't set a deadline.
+pub const DEFAULT_DEADLINE: Duration = Duration::from_secs(60 * 60 * 24);
+
+/// A subpoena that was sent to a witness as a direct message. Declining it or not answering
+/// before the deadline is flagged to the judge in the court room, who decides whether it's
+/// contempt of court.
+pub struct Subpoena {
+    ids: SubpoenaIds,
+    lawsuit: Lawsuit,
+    witness: UserId,
+    message: Message,
+    deadline: Duration,
+}
+
+impl Subpoena {
+    /// Sends the subpoena to the witness, `None` if they don't accept direct messages.
+    pub async fn send(
+        http: &Http,
+        guild_id: GuildId,
+        strings: &Strings,
+        lawsuit: Lawsuit,
+        witness: UserId,
+        deadline: Duration,
+    ) -> Result<Option<Self>> {
+        let guild = guild_id
+            .to_partial_guild(http)
+            .await
+            .wrap_err("fetch guild")?;
+        let ids = SubpoenaIds::new();
+        let until = duration::relative_timestamp(duration::from_now(deadline));
+
+        let result = async {
+            let channel = witness
+                .create_dm_channel(http)
+                .await
+                .wrap_err("create dm channel")?;
+            channel
+                .send_message(http, |message| {
+                    message
+                        .embed(|embed| {
+                            embed
+                                .title(strings.subpoena_title)
+                                .description((strings.subpoena_description)(
+                                    &format!("<#{}>", lawsuit.court_room),
+                                    &until,
+                                ))
+                                .field(strings.field_reason, &lawsuit.reason, false)
+                                .field(
+                                    strings.field_plaintiff,
+                                    format!("<@{}>", lawsuit.plaintiff),
+                                    true,
+                                )
+                                .field(
+                                    strings.field_accused,
+                                    format!("<@{}>", lawsuit.accused),
+                                    true,
+                                )
+                                .footer(|footer| footer.text(&guild.name))
+                        })
+                        .components(|c| subpoena_components(c, strings, &ids))
+                })
+                .await
+                .wrap_err("send subpoena")
+        }
+        .await;
+
+        match result {
+            Ok(message) => Ok(Some(Self {
+                ids,
+                lawsuit,
+                witness,
+                message,
+                deadline,
+            })),
+            Err(err) => {
+                debug!(?err, %witness, "Could not send subpoena");
+                Ok(None)
+            }
+        }
+    }
+
+    /// Waits for the answer of the witness and stores it on the lawsuit. If the bot shuts down
+    /// before, the subpoena stays pending.
+    pub async fn collect(
+        mut self,
+        ctx: Context,
+        storage: Arc<dyn Storage>,
+        tasks: BackgroundTasks,
+        strings: &'static Strings,
+    ) -> Result<()> {
+        let prefix = self.ids.prefix.clone();
+        let collector = CollectComponentInteraction::new(&ctx)
+            .message_id(self.message.id)
+            .author_id(self.witness)
+            .filter(move |interaction| interaction.data.custom_id.starts_with(&prefix))
+            .timeout(self.deadline);
+        let interaction = tokio::select! {
+            interaction = collector => interaction,
+            _ = tasks.shutting_down() => return Ok(()),
+        };
+
+        let response = match &interaction {
+            Some(interaction) if interaction.data.custom_id == self.ids.accept => {
+                SubpoenaResponse::Accepted
+            }
+            Some(_) => SubpoenaResponse::Declined,
+            None => SubpoenaResponse::Ignored,
+        };
+
+        match interaction {
+            Some(interaction) => {
+                let content = if response == SubpoenaResponse::Accepted {
+                    strings.subpoena_accepted
+                } else {
+                    strings.subpoena_declined
+                };
+                interaction
+                    .create_interaction_response(&ctx.http, |response| {
+                        response
+                            .kind(InteractionResponseType::UpdateMessage)
+                            .interaction_response_data(|data| {
+                                data.content(content).components(|c| c)
+                            })
+                    })
+                    .await
+                    .wrap_err("acknowledge answer")?;
+            }
+            // the buttons would do nothing after the deadline
+            None => {
+                self.message
+                    .edit(&ctx.http, |message| message.components(|c| c))
+                    .await
+                    .wrap_err("remove subpoena buttons")?;
+            }
+        }
+
+        let lawsuit = &self.lawsuit;
+        let witness_id = self.witness.into();
+        if !storage
+            .answer_subpoena(lawsuit.guild_id, lawsuit.id, witness_id, response)
+            .await?
+        {
+            return Ok(());
+        }
+        info!(lawsuit_id = %lawsuit.id, witness = %self.witness, ?response, "Subpoena answered");
+
+        let flag = match response {
+            SubpoenaResponse::Declined => strings.subpoena_declined_flag,
+            SubpoenaResponse::Ignored => strings.subpoena_ignored_flag,
+            _ => return Ok(()),
+        };
+        // the judge only has to act if the lawsuit is still running
+        let is_open = storage
+            .find_open_lawsuits(lawsuit.guild_id)
+            .await?
+            .iter()
+            .any(|open| open.id == lawsuit.id);
+        if !is_open {
+            return Ok(());
+        }
+
+        ChannelId::from(lawsuit.court_room)
+            .say(
+                &ctx.http,
+                flag(
+                    &format!("<@{}>", lawsuit.judge),
+                    &format!("<@{}>", self.witness),
+                ),
+            )
+            .await
+            .wrap_err("flag subpoena")?;
+
+        Ok(())
+    }
+}
+
+struct SubpoenaIds {
+    prefix: String,
+    accept: String,
+    decline: String,
+}
+
+impl SubpoenaIds {
+    fn new() -> Self {
+        // the ids must be unique so that two subpoenas of the same witness don't interfere
+        let prefix = format!("subpoena-{}", Uuid::new());
+        Self {
+            accept: format!("{prefix}-accept"),
+            decline: format!("{prefix}-decline"),
+            prefix,
+        }
+    }
+}
+
+fn subpoena_components<'a>(
+    components: &'a mut CreateComponents,
+    strings: &Strings,
+    ids: &SubpoenaIds,
+) -> &'a mut CreateComponents {
+    components.create_action_row(|row| {
+        row.create_button(|button| {
+            button
+                .custom_id(&ids.accept)
+                .label(strings.subpoena_accept)
+                .style(ButtonStyle::Success)
+        })
+        .create_button(|button| {
+            button
+                .custom_id(&ids.decline)
+                .label(strings.subpoena_decline)
+                .style(ButtonStyle::Danger)
+        })
+    })
+}
@@ -0,0 +1,187 @@
+use crate::{i18n::Strings, lawsuit::Lawsuit, model::State};
+
+/// The evidence in the summary is shortened, the whole summary should fit into a message
+const MAX_EVIDENCE_LEN: usize = 100;
+
+/// The responses that admins can replace with `/config messages set`, for servers where the
+/// default texts don't fit. Placeholders like `{user}` are replaced when the text is sent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, poise::ChoiceParameter)]
+pub enum Template {
+    #[name = "Einstellung gesetzt"]
+    IsSet,
+    #[name = "Eingesperrt"]
+    Arrested,
+    #[name = "Eingesperrt bis"]
+    ArrestedUntil,
+    #[name = "Freigelassen"]
+    Released,
+    #[name = "Prozess eröffnet"]
+    LawsuitOpened,
+    #[name = "Prozess abgeschlossen"]
+    LawsuitClosed,
+    #[name = "Titel des Prozesses"]
+    LawsuitTitle,
+    #[name = "Titel des abgeschlossenen Prozesses"]
+    LawsuitClosedTitle,
+    #[name = "Zusammenfassung des Prozesses"]
+    CaseSummary,
+}
+
+impl Template {
+    pub const ALL: [Self; 9] = [
+        Self::IsSet,
+        Self::Arrested,
+        Self::ArrestedUntil,
+        Self::Released,
+        Self::LawsuitOpened,
+        Self::LawsuitClosed,
+        Self::LawsuitTitle,
+        Self::LawsuitClosedTitle,
+        Self::CaseSummary,
+    ];
+
+    /// The key in [`State::message_templates`].
+    pub fn key(self) -> &'static str {
+        match self {
+            Self::IsSet => "is_set",
+            Self::Arrested => "arrested",
+            Self::ArrestedUntil => "arrested_until",
+            Self::Released => "released",
+            Self::LawsuitOpened => "lawsuit_opened",
+            Self::LawsuitClosed => "lawsuit_closed",
+            Self::LawsuitTitle => "lawsuit_title",
+            Self::LawsuitClosedTitle => "lawsuit_closed_title",
+            Self::CaseSummary => "case_summary",
+        }
+    }
+
+    pub fn placeholders(self) -> &'static [&'static str] {
+        match self {
+            Self::IsSet => &[],
+            Self::Arrested | Self::Released => &["user"],
+            Self::ArrestedUntil => &["user", "time"],
+            Self::LawsuitOpened => &["channel", "plaintiff", "accused"],
+            Self::LawsuitClosed | Self::LawsuitTitle | Self::LawsuitClosedTitle => {
+                &["plaintiff", "accused"]
+            }
+            Self::CaseSummary => &[
+                "case_number",
+                "plaintiff",
+                "accused",
+                "judge",
+                "reason",
+                "evidence",
+                "witnesses",
+                "verdict",
+            ],
+        }
+    }
+
+    /// The text that is used as long as the guild didn't set its own, with the placeholders
+    /// filled in where the default has them.
+    fn default_text(self, strings: &Strings, values: &[(&str, String)]) -> String {
+        let value = |name: &str| {
+            values
+                .iter()
+                .find(|(key, _)| *key == name)
+                .map_or("", |(_, value)| value.as_str())
+        };
+        match self {
+            Self::IsSet => strings.is_set.to_owned(),
+            Self::Arrested => strings.arrested.to_owned(),
+            Self::ArrestedUntil => (strings.arrested_until)(value("time")),
+            Self::Released => strings.released.to_owned(),
+            Self::LawsuitOpened => (strings.lawsuit_opened)(value("channel")),
+            Self::LawsuitClosed => strings.lawsuit_closed.to_owned(),
+            Self::LawsuitTitle => strings.lawsuit_title.to_owned(),
+            Self::LawsuitClosedTitle => strings.lawsuit_closed_title.to_owned(),
+            // too many values for a function, the default text has the placeholders itself
+            Self::CaseSummary => fill(strings.case_summary, values),
+        }
+    }
+}
+
+/// The text of the guild for the template, or the default text in its language.
+pub fn render(state: &State, template: Template, values: &[(&str, String)]) -> String {
+    match state.message_templates.get(template.key()) {
+        Some(text) => fill(text, values),
+        None => template.default_text(state.locale.strings(), values),
+    }
+}
+
+fn fill(text: &str, values: &[(&str, String)]) -> String {
+    values.iter().fold(text.to_owned(), |text, (name, value)| {
+        text.replace(&format!("{{{name}}}"), value)
+    })
+}
+
+/// The first placeholder in the text that the template doesn't have, most likely a typo.
+pub fn unknown_placeholder(template: Template, text: &str) -> Option<&str> {
+    text.split('{')
+        .skip(1)
+        .filter_map(|rest| rest.split_once('}'))
+        .map(|(name, _)| name)
+        .find(|name| !template.placeholders().contains(name))
+}
+
+/// The placeholders of the templates about a lawsuit.
+pub fn lawsuit_values(lawsuit: &Lawsuit) -> [(&'static str, String); 2] {
+    [
+        ("plaintiff", format!("<@{}>", lawsuit.plaintiff)),
+        ("accused", format!("<@{}>", lawsuit.accused)),
+    ]
+}
+
+/// The placeholders of [`Template::CaseSummary`].
+pub fn summary_values(strings: &Strings, lawsuit: &Lawsuit) -> Vec<(&'static str, String)> {
+    let list = |items: Vec<String>| {
+        if items.is_empty() {
+            strings.none.to_owned()
+        } else {
+            items.join("\n")
+        }
+    };
+    let evidence = lawsuit
+        .evidence
+        .iter()
+        .map(|evidence| {
+            let mut content = evidence
+                .content
+                .chars()
+                .take(MAX_EVIDENCE_LEN)
+                .collect::<String>();
+            if content.len() < evidence.content.len() {
+                content.push('…');
+            }
+            format!("- <@{}>: {content} ({})", evidence.author, evidence.link)
+        })
+        .collect();
+    let witnesses = lawsuit
+        .witnesses
+        .iter()
+        .map(|witness| format!("- <@{}>", witness.user_id))
+        .collect();
+
+    let mut values = lawsuit_values(lawsuit).to_vec();
+    values.extend([
+        (
+            "case_number",
+            lawsuit
+                .case_number
+                .clone()
+                .unwrap_or_else(|| lawsuit.id.to_string()),
+        ),
+        ("judge", format!("<@{}>", lawsuit.judge)),
+        ("reason", lawsuit.reason.clone()),
+        ("evidence", list(evidence)),
+        ("witnesses", list(witnesses)),
+        (
+            "verdict",
+            lawsuit
+                .verdict
+                .clone()
+                .unwrap_or_else(|| strings.case_summary_no_verdict.to_owned()),
+        ),
+    ]);
+    values
+}
@@ -0,0 +1,204 @@
+use std::fmt::Write;
+
+use color_eyre::Result;
+use mongodb::bson::DateTime;
+use poise::serenity::{
+    http::Http,
+    model::{
+        channel::Message,
+        id::{ChannelId, MessageId},
+    },
+};
+
+use crate::{duration, i18n::Strings, lawsuit::Lawsuit, model::SnowflakeId, WrapErr};
+
+/// Discord allows fetching 100 messages at once
+const PAGE_SIZE: u64 = 100;
+/// Long trials are cut off, the file would get too big to upload otherwise
+const MAX_MESSAGES: usize = 5000;
+/// The verdict is posted in the court room right after the lawsuit is closed
+const CLOSE_GRACE: i64 = 1000 * 60;
+/// The start of the discord snowflakes, 2015-01-01
+const DISCORD_EPOCH: i64 = 1_420_070_400_000;
+
+const STYLE: &str = "
+body { font-family: sans-serif; background: #36393f; color: #dcddde; margin: 2em; }
+h1 { color: #fff; }
+table.meta td { padding: 0.2em 1em 0.2em 0; vertical-align: top; }
+table.meta td:first-child { color: #b9bbbe; font-weight: bold; }
+.message { display: flex; margin: 1em 0; }
+.avatar { width: 40px; height: 40px; border-radius: 50%; margin-right: 1em; }
+.author { color: #fff; font-weight: bold; }
+.time { color: #72767d; font-size: 0.8em; margin-left: 0.5em; }
+.content { white-space: pre-wrap; }
+.embed { border-left: 4px solid #202225; background: #2f3136; padding: 0.5em; margin-top: 0.3em; }
+a { color: #00aff4; }
+";
+
+/// Fetches the messages that were posted in the court room while the lawsuit was running, the
+/// oldest first. The court rooms are reused, so the messages are only known for lawsuits that
+/// stored when they were opened.
+pub async fn fetch_messages(http: &Http, lawsuit: &Lawsuit) -> Result<Option<Vec<Message>>> {
+    let opened_at = match lawsuit.opened_at {
+        Some(opened_at) => opened_at,
+        None => return Ok(None),
+    };
+    let end = lawsuit.closed_at.map(|closed_at| {
+        message_id_at(DateTime::from_millis(
+            closed_at.timestamp_millis() + CLOSE_GRACE,
+        ))
+    });
+    let channel_id = ChannelId::from(lawsuit.court_room);
+
+    let mut messages = Vec::new();
+    let mut after = message_id_at(opened_at);
+    while messages.len() < MAX_MESSAGES {
+        let mut page = channel_id
+            .messages(http, |retriever| retriever.after(after).limit(PAGE_SIZE))
+            .await
+            .wrap_err("fetch court room messages")?;
+        page.sort_by_key(|message| message.id);
+
+        let last = match page.last() {
+            Some(last) => last.id,
+            None => break,
+        };
+        let is_full = page.len() as u64 == PAGE_SIZE;
+        messages.extend(
+            page.into_iter()
+                .filter(|message| end.map_or(true, |end| message.id <= end)),
+        );
+        if !is_full || end.map_or(false, |end| last > end) {
+            break;
+        }
+        after = last;
+    }
+    messages.truncate(MAX_MESSAGES);
+
+    Ok(Some(messages))
+}
+
+/// The smallest message id that discord could give a message at the time.
+fn message_id_at(time: DateTime) -> MessageId {
+    MessageId(((time.timestamp_millis() - DISCORD_EPOCH).max(0) as u64) << 22)
+}
+
+/// Renders the lawsuit and the messages into a HTML file that doesn't need anything else to be
+/// shown, only the avatars are loaded from discord.
+pub fn render(strings: &Strings, title: &str, lawsuit: &Lawsuit, messages: &[Message]) -> String {
+    let mut html = String::new();
+    let _ = write!(
+        html,
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{title}</title>\n\
+         <style>{STYLE}</style>\n</head>\n<body>\n<h1>{title}</h1>\n<table class=\"meta\">\n",
+        title = escape_html(title),
+    );
+
+    // the names of the participants are only known if they wrote something
+    let mention = |id: SnowflakeId| {
+        let name = messages
+            .iter()
+            .find(|message| SnowflakeId::from(message.author.id) == id)
+            .map_or_else(|| id.to_string(), |message| message.author.tag());
+        format!("@{}", escape_html(&name))
+    };
+    let mut meta = vec![
+        (strings.field_reason, escape_html(&lawsuit.reason)),
+        (strings.field_plaintiff, mention(lawsuit.plaintiff)),
+        (strings.field_accused, mention(lawsuit.accused)),
+        (strings.field_judge, mention(lawsuit.judge)),
+    ];
+    if let Some(lawyer) = lawsuit.plaintiff_lawyer {
+        meta.push((strings.field_plaintiff_lawyer, mention(lawyer)));
+    }
+    if let Some(lawyer) = lawsuit.accused_lawyer {
+        meta.push((strings.field_accused_lawyer, mention(lawyer)));
+    }
+    if let Some(opened_at) = lawsuit.opened_at {
+        meta.push((strings.field_opened_at, duration::utc(opened_at)));
+    }
+    if let Some(closed_at) = lawsuit.closed_at {
+        meta.push((strings.field_closed_at, duration::utc(closed_at)));
+    }
+    if let Some(verdict) = &lawsuit.verdict {
+        meta.push((strings.field_verdict, escape_html(verdict)));
+    }
+    for (name, value) in meta {
+        let _ = writeln!(
+            html,
+            "<tr><td>{}</td><td>{value}</td></tr>",
+            escape_html(name)
+        );
+    }
+    html.push_str("</table>\n<hr>\n");
+
+    for message in messages {
+        let time = DateTime::from_millis(message.timestamp.unix_timestamp() * 1000);
+        let _ = write!(
+            html,
+            "<div class=\"message\">\n<img class=\"avatar\" src=\"{}\" alt=\"\">\n<div>\n\
+             <span class=\"author\">{}</span><span class=\"time\">{}</span>\n",
+            escape_html(&message.author.face()),
+            escape_html(&message.author.tag()),
+            duration::utc(time),
+        );
+        if !message.content.is_empty() {
+            let _ = writeln!(
+                html,
+                "<div class=\"content\">{}</div>",
+                escape_html(&message.content)
+            );
+        }
+        for embed in &message.embeds {
+            html.push_str("<div class=\"embed\">");
+            if let Some(title) = &embed.title {
+                let _ = write!(html, "<b>{}</b>", escape_html(title));
+            }
+            if let Some(description) = &embed.description {
+                let _ = write!(
+                    html,
+                    "<div class=\"content\">{}</div>",
+                    escape_html(description)
+                );
+            }
+            for field in &embed.fields {
+                let _ = write!(
+                    html,
+                    "<div><b>{}</b>: {}</div>",
+                    escape_html(&field.name),
+                    escape_html(&field.value)
+                );
+            }
+            html.push_str("</div>\n");
+        }
+        for attachment in &message.attachments {
+            let _ = writeln!(
+                html,
+                "<div><a href=\"{}\">{}</a></div>",
+                escape_html(&attachment.url),
+                escape_html(&attachment.filename)
+            );
+        }
+        html.push_str("</div>\n</div>\n");
+    }
+
+    html.push_str("</body>\n</html>\n");
+    html
+}
+
+/// Escapes text for html, also in attribute values. Everything from users and discord, like
+/// reasons and guild names, goes through this, in the transcripts and the dashboard.
+pub(crate) fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for char in text.chars() {
+        match char {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            char => escaped.push(char),
+        }
+    }
+    escaped
+}
@@ -0,0 +1,211 @@
+use std::{
+    net::{IpAddr, Ipv4Addr},
+    time::Duration,
+};
+
+use mongodb::bson::DateTime;
+use once_cell::sync::Lazy;
+use reqwest::{redirect::Policy, StatusCode, Url};
+use serde_json::{json, Value};
+use tracing::{error, warn};
+
+use crate::{audit::AuditEvent, lawsuit::Lawsuit, model::State};
+
+const MAX_ATTEMPTS: u32 = 5;
+const FIRST_DELAY: Duration = Duration::from_secs(1);
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+static CLIENT: Lazy<reqwest::Client> = Lazy::new(|| {
+    reqwest::Client::builder()
+        .timeout(TIMEOUT)
+        // a redirect could lead to an address that `is_valid_url` rejects
+        .redirect(Policy::none())
+        .build()
+        .expect("create webhook client")
+});
+
+/// Posts the event to the webhook of the guild if one is set and the event is one that is sent
+/// there. The body looks like `{ "event": "arrested", "guild_id": "...", "timestamp": ..., "data":
+/// { ... } }`, ids are strings and times are unix timestamps in milliseconds.
+///
+/// The request is sent in the background and retried while the receiver is unreachable or has a
+/// temporary problem, so a slow webhook doesn't hold up the command.
+pub fn dispatch(state: &State, event: &AuditEvent<'_>) {
+    let url = match &state.webhook_url {
+        Some(url) => url.clone(),
+        None => return,
+    };
+    let (name, data) = match payload(event) {
+        Some(payload) => payload,
+        None => return,
+    };
+
+    let guild_id = state.guild_id;
+    let body = json!({
+        "event": name,
+        "guild_id": guild_id,
+        "timestamp": DateTime::now().timestamp_millis(),
+        "data": data,
+    });
+
+    tokio::spawn(async move {
+        // the addresses of the host can change after the url was set
+        if !is_valid_url(&url).await {
+            warn!(%guild_id, "The webhook doesn't point to a public address anymore");
+            return;
+        }
+        if let Err(err) = send(&url, &body).await {
+            error!(?err, %guild_id, event = name, "Failed to send webhook");
+        }
+    });
+}
+
+/// Only shows the host of the url, the rest often contains a secret token.
+pub fn display_url(url: &str) -> &str {
+    let without_scheme = url.split_once("://").map_or(url, |(_, rest)| rest);
+    without_scheme.split('/').next().unwrap_or(without_scheme)
+}
+
+/// Only https urls whose host resolves to public addresses are allowed, the webhook must not be
+/// used to reach the network the bot runs in.
+pub async fn is_valid_url(url: &str) -> bool {
+    let url = match Url::parse(url) {
+        Ok(url) if url.scheme() == "https" => url,
+        _ => return false,
+    };
+    let host = match url.host_str() {
+        Some(host) => host.trim_start_matches('[').trim_end_matches(']'),
+        None => return false,
+    };
+    let port = url.port_or_known_default().unwrap_or(443);
+
+    match tokio::net::lookup_host((host, port)).await {
+        Ok(addrs) => {
+            let addrs = addrs.collect::<Vec<_>>();
+            !addrs.is_empty() && addrs.iter().all(|addr| is_public(addr.ip()))
+        }
+        Err(_) => false,
+    }
+}
+
+fn is_public(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => {
+            !(ip.is_loopback()
+                || ip.is_private()
+                || ip.is_link_local()
+                || ip.is_unspecified()
+                || ip.is_broadcast())
+        }
+        IpAddr::V6(ip) => {
+            let segments = ip.segments();
+            // `::ffff:127.0.0.1` is the ipv4 address
+            if segments[..6] == [0, 0, 0, 0, 0, 0xffff] {
+                let [a, b] = segments[6].to_be_bytes();
+                let [c, d] = segments[7].to_be_bytes();
+                return is_public(Ipv4Addr::new(a, b, c, d).into());
+            }
+            // unique local addresses are in fc00::/7 and link local ones in fe80::/10
+            !(ip.is_loopback()
+                || ip.is_unspecified()
+                || segments[0] & 0xfe00 == 0xfc00
+                || segments[0] & 0xffc0 == 0xfe80)
+        }
+    }
+}
+
+fn payload(event: &AuditEvent<'_>) -> Option<(&'static str, Value)> {
+    let payload = match event {
+        AuditEvent::LawsuitCreated(lawsuit) => ("lawsuit_created", lawsuit_json(lawsuit)),
+        // sealed lawsuits are only for the court
+        AuditEvent::LawsuitClosed(lawsuit) if !lawsuit.sealed => {
+            ("lawsuit_closed", lawsuit_json(lawsuit))
+        }
+        AuditEvent::Arrested {
+            user_id,
+            ordered_by,
+            reason,
+            release_at,
+        } => (
+            "arrested",
+            json!({
+                "user_id": user_id.to_string(),
+                "ordered_by": ordered_by.map(|id| id.to_string()),
+                "reason": reason,
+                "release_at": release_at.map(|time| time.timestamp_millis()),
+            }),
+        ),
+        AuditEvent::ArrestedMany { count, ordered_by } => (
+            "arrested_many",
+            json!({
+                "count": count,
+                "ordered_by": ordered_by.map(|id| id.to_string()),
+            }),
+        ),
+        AuditEvent::Released {
+            user_id,
+            released_by,
+        } => (
+            "released",
+            json!({
+                "user_id": user_id.to_string(),
+                "released_by": released_by.map(|id| id.to_string()),
+            }),
+        ),
+        AuditEvent::ReleasedAll { count, released_by } => (
+            "released_all",
+            json!({
+                "count": count,
+                "released_by": released_by.to_string(),
+            }),
+        ),
+        _ => return None,
+    };
+    Some(payload)
+}
+
+pub fn lawsuit_json(lawsuit: &Lawsuit) -> Value {
+    json!({
+        "id": lawsuit.id.to_string(),
+        "plaintiff": lawsuit.plaintiff,
+        "accused": lawsuit.accused,
+        "plaintiff_lawyer": lawsuit.plaintiff_lawyer,
+        "accused_lawyer": lawsuit.accused_lawyer,
+        "judge": lawsuit.judge,
+        "reason": lawsuit.reason,
+        "verdict": lawsuit.verdict,
+        "court_room": lawsuit.court_room,
+    })
+}
+
+async fn send(url: &str, body: &Value) -> Result<(), reqwest::Error> {
+    let mut delay = FIRST_DELAY;
+    let mut attempt = 1;
+
+    loop {
+        let result = CLIENT
+            .post(url)
+            .json(body)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status());
+
+        match result {
+            Err(err) if attempt < MAX_ATTEMPTS && is_transient(&err) => {
+                warn!(?err, attempt, ?delay, "Webhook request failed, retrying");
+                tokio::time::sleep(delay).await;
+                delay *= 2;
+                attempt += 1;
+            }
+            result => return result.map(drop),
+        }
+    }
+}
+
+fn is_transient(err: &reqwest::Error) -> bool {
+    err.is_timeout()
+        || err.is_connect()
+        || err.status().map_or(false, |status| {
+            status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error()
+        })
+}
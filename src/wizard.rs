@@ -0,0 +1,257 @@
+use std::time::Duration;
+
+use color_eyre::Result;
+use mongodb::bson::Uuid;
+use poise::serenity::{
+    builder::CreateComponents,
+    collector::{CollectComponentInteraction, CollectModalInteraction},
+    model::{
+        id::UserId,
+        interactions::{
+            message_component::{ActionRowComponent, InputTextStyle},
+            InteractionResponseType,
+        },
+        user::User,
+    },
+};
+
+use crate::{i18n::Strings, Context, WrapErr};
+
+/// Discord only allows 25 options in a select menu
+const MAX_SELECT_OPTIONS: usize = 25;
+/// How many of the last messages in the channel are looked at to find the people to choose from
+const RECENT_MESSAGES: u64 = 100;
+const TIMEOUT: Duration = Duration::from_secs(60 * 5);
+
+/// What was entered in the wizard, the author is the plaintiff.
+#[derive(Debug)]
+pub struct LawsuitDraft {
+    pub accused: UserId,
+    pub judge: UserId,
+    pub reason: String,
+    /// What the plaintiff demands, it's optional
+    pub remedy: Option<String>,
+}
+
+/// Lets the author choose the accused and the judge from the people who wrote in the channel
+/// recently, and asks for the reason in a modal once both are chosen. Easier than the options of
+/// `/lawsuit create` on mobile.
+///
+/// Returns `None` if nobody wrote in the channel or the wizard wasn't finished in time.
+pub async fn file_lawsuit(ctx: Context<'_>, strings: &Strings) -> Result<Option<LawsuitDraft>> {
+    let members = recent_members(ctx).await?;
+    if members.is_empty() {
+        ctx.say(strings.no_recent_members).await?;
+        return Ok(None);
+    }
+
+    let ids = WizardIds::new();
+    ctx.send(|reply| {
+        reply
+            .content(strings.lawsuit_wizard_prompt)
+            .components(|c| wizard_components(c, strings, &ids, &members))
+    })
+    .await
+    .wrap_err("send wizard")?;
+
+    let author_id = ctx.author().id;
+    let mut accused = None;
+    let mut judge = None;
+
+    let (accused, judge) = loop {
+        let prefix = ids.prefix.clone();
+        let interaction = CollectComponentInteraction::new(ctx.discord())
+            .channel_id(ctx.channel_id())
+            .author_id(author_id)
+            .filter(move |interaction| interaction.data.custom_id.starts_with(&prefix))
+            .timeout(TIMEOUT)
+            .await;
+
+        let interaction = match interaction {
+            Some(interaction) => interaction,
+            None => return Ok(None),
+        };
+
+        let chosen = interaction
+            .data
+            .values
+            .first()
+            .and_then(|value| value.parse().ok())
+            .map(UserId);
+        if interaction.data.custom_id == ids.accused {
+            accused = chosen;
+        } else if interaction.data.custom_id == ids.judge {
+            judge = chosen;
+        }
+
+        // the modal can only be the direct response to an interaction, so it's shown as soon as
+        // the second person is chosen
+        if let (Some(accused), Some(judge)) = (accused, judge) {
+            interaction
+                .create_interaction_response(ctx.discord(), |response| {
+                    response
+                        .kind(InteractionResponseType::Modal)
+                        .interaction_response_data(|data| {
+                            data.custom_id(&ids.modal)
+                                .title(strings.lawsuit_wizard_title)
+                                .components(|c| modal_components(c, strings, &ids))
+                        })
+                })
+                .await
+                .wrap_err("show wizard modal")?;
+            break (accused, judge);
+        }
+
+        interaction
+            .create_interaction_response(ctx.discord(), |response| {
+                response.kind(InteractionResponseType::DeferredUpdateMessage)
+            })
+            .await
+            .wrap_err("acknowledge wizard selection")?;
+    };
+
+    let modal_id = ids.modal.clone();
+    let submission = CollectModalInteraction::new(ctx.discord())
+        .author_id(author_id)
+        .filter(move |interaction| interaction.data.custom_id == modal_id)
+        .timeout(TIMEOUT)
+        .await;
+
+    let submission = match submission {
+        Some(submission) => submission,
+        None => return Ok(None),
+    };
+
+    let mut reason = String::new();
+    let mut remedy = None;
+    for component in submission
+        .data
+        .components
+        .iter()
+        .flat_map(|row| &row.components)
+    {
+        if let ActionRowComponent::InputText(input) = component {
+            let value = input.value.trim().to_owned();
+            if input.custom_id == ids.reason {
+                reason = value;
+            } else if input.custom_id == ids.remedy && !value.is_empty() {
+                remedy = Some(value);
+            }
+        }
+    }
+
+    // the select menus are removed so that nobody files the same lawsuit twice
+    submission
+        .create_interaction_response(ctx.discord(), |response| {
+            response
+                .kind(InteractionResponseType::UpdateMessage)
+                .interaction_response_data(|data| data.components(|c| c))
+        })
+        .await
+        .wrap_err("close wizard")?;
+
+    Ok(Some(LawsuitDraft {
+        accused,
+        judge,
+        reason,
+        remedy,
+    }))
+}
+
+/// The people who wrote in the channel recently, most recent first, without bots.
+async fn recent_members(ctx: Context<'_>) -> Result<Vec<User>> {
+    let messages = ctx
+        .channel_id()
+        .messages(&ctx.discord().http, |get| get.limit(RECENT_MESSAGES))
+        .await
+        .wrap_err("fetch recent messages")?;
+
+    let mut members = Vec::<User>::new();
+    for message in messages {
+        if members.len() >= MAX_SELECT_OPTIONS {
+            break;
+        }
+        if !message.author.bot && !members.iter().any(|user| user.id == message.author.id) {
+            members.push(message.author);
+        }
+    }
+    Ok(members)
+}
+
+struct WizardIds {
+    prefix: String,
+    accused: String,
+    judge: String,
+    modal: String,
+    reason: String,
+    remedy: String,
+}
+
+impl WizardIds {
+    fn new() -> Self {
+        // the ids must be unique so that multiple wizards in the same channel don't interfere
+        let prefix = format!("wizard-{}", Uuid::new());
+        Self {
+            accused: format!("{prefix}-accused"),
+            judge: format!("{prefix}-judge"),
+            modal: format!("{prefix}-modal"),
+            reason: format!("{prefix}-reason"),
+            remedy: format!("{prefix}-remedy"),
+            prefix,
+        }
+    }
+}
+
+fn wizard_components<'a>(
+    components: &'a mut CreateComponents,
+    strings: &Strings,
+    ids: &WizardIds,
+    members: &[User],
+) -> &'a mut CreateComponents {
+    for (custom_id, placeholder) in [
+        (&ids.accused, strings.lawsuit_wizard_accused),
+        (&ids.judge, strings.lawsuit_wizard_judge),
+    ] {
+        components.create_action_row(|row| {
+            row.create_select_menu(|menu| {
+                menu.custom_id(custom_id)
+                    .placeholder(placeholder)
+                    .options(|options| {
+                        for member in members {
+                            options.create_option(|option| {
+                                option.label(&member.name).value(member.id)
+                            });
+                        }
+                        options
+                    })
+            })
+        });
+    }
+    components
+}
+
+fn modal_components<'a>(
+    components: &'a mut CreateComponents,
+    strings: &Strings,
+    ids: &WizardIds,
+) -> &'a mut CreateComponents {
+    components
+        .create_action_row(|row| {
+            row.create_input_text(|input| {
+                input
+                    .custom_id(&ids.reason)
+                    .label(strings.field_reason)
+                    .style(InputTextStyle::Paragraph)
+                    .required(true)
+            })
+        })
+        .create_action_row(|row| {
+            row.create_input_text(|input| {
+                input
+                    .custom_id(&ids.remedy)
+                    .label(strings.lawsuit_wizard_remedy)
+                    .style(InputTextStyle::Short)
+                    .required(false)
+            })
+        })
+}